@@ -0,0 +1,12 @@
+//! Arbitrary text through the strict and lossy decoders
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use weakauras_mass_import::decoder::WeakAuraDecoder;
+
+fuzz_target!(|input: &str| {
+    let _ = WeakAuraDecoder::decode(input);
+    let _ = WeakAuraDecoder::decode_lossy(input);
+    let _ = WeakAuraDecoder::decode_multiple(input);
+});
@@ -0,0 +1,17 @@
+//! Arbitrary bytes as the payload of a `!WA:2!` string, so every input gets
+//! past the character check and into decompression and deserialization
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use weakauras_mass_import::decoder::WeakAuraDecoder;
+
+const ALPHABET: &[u8; 64] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789()";
+
+fuzz_target!(|data: &[u8]| {
+    let payload: String = data
+        .iter()
+        .map(|b| ALPHABET[usize::from(*b) % ALPHABET.len()] as char)
+        .collect();
+    let _ = WeakAuraDecoder::decode(&format!("!WA:2!{}", payload));
+});
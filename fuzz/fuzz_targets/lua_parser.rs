@@ -0,0 +1,11 @@
+//! Arbitrary text as SavedVariables content and as a table literal
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use weakauras_mass_import::lua_parser::LuaParser;
+
+fuzz_target!(|input: &str| {
+    let _ = LuaParser::parse(input);
+    let _ = LuaParser::parse_value(input);
+});
@@ -0,0 +1,118 @@
+//! User tags and notes for auras, stored in a sidecar file.
+//!
+//! Annotations are keyed by aura `uid` so they survive renames, and live in
+//! `annotations.json` next to the app config. The game's SavedVariables file
+//! is never touched.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::error::Result;
+
+/// Sidecar file name
+const ANNOTATIONS_FILE_NAME: &str = "annotations.json";
+
+/// Tags and note attached to a single aura
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuraAnnotation {
+    /// User-defined tags, e.g. "raid", "pvp", "needs update"
+    pub tags: BTreeSet<String>,
+    /// Free-text note
+    pub note: String,
+}
+
+impl AuraAnnotation {
+    /// Whether there is nothing worth persisting
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.note.trim().is_empty()
+    }
+}
+
+/// Annotation database keyed by aura uid
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnnotationStore {
+    pub auras: BTreeMap<String, AuraAnnotation>,
+}
+
+impl AnnotationStore {
+    /// Load from the default location, falling back to an empty store on any error
+    pub fn load() -> Self {
+        config::load_json(ANNOTATIONS_FILE_NAME)
+    }
+
+    /// Save to the default location
+    pub fn save(&self) -> Result<()> {
+        config::save_json(ANNOTATIONS_FILE_NAME, self)
+    }
+
+    /// Annotation for an aura, if any
+    pub fn get(&self, uid: &str) -> Option<&AuraAnnotation> {
+        self.auras.get(uid)
+    }
+
+    /// Add a tag (trimmed). Returns `false` if empty or already present.
+    pub fn add_tag(&mut self, uid: &str, tag: &str) -> bool {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return false;
+        }
+        self.auras
+            .entry(uid.to_string())
+            .or_default()
+            .tags
+            .insert(tag.to_string())
+    }
+
+    /// Remove a tag. Returns `false` if it was not present.
+    pub fn remove_tag(&mut self, uid: &str, tag: &str) -> bool {
+        let removed = self
+            .auras
+            .get_mut(uid)
+            .map(|a| a.tags.remove(tag))
+            .unwrap_or(false);
+        self.prune(uid);
+        removed
+    }
+
+    /// Replace the note for an aura
+    pub fn set_note(&mut self, uid: &str, note: &str) {
+        self.auras.entry(uid.to_string()).or_default().note = note.to_string();
+        self.prune(uid);
+    }
+
+    /// All tags in use, sorted
+    pub fn all_tags(&self) -> BTreeSet<String> {
+        self.auras
+            .values()
+            .flat_map(|a| a.tags.iter().cloned())
+            .collect()
+    }
+
+    /// Whether an aura's tags or note contain `query` (case-insensitive).
+    /// An empty query matches everything.
+    pub fn matches(&self, uid: Option<&str>, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        let Some(annotation) = uid.and_then(|uid| self.auras.get(uid)) else {
+            return false;
+        };
+        annotation
+            .tags
+            .iter()
+            .any(|t| t.to_lowercase().contains(&query))
+            || annotation.note.to_lowercase().contains(&query)
+    }
+
+    /// Drop an entry that no longer carries any data
+    fn prune(&mut self, uid: &str) {
+        if self.auras.get(uid).is_some_and(AuraAnnotation::is_empty) {
+            self.auras.remove(uid);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Stable public API.
+//!
+//! Re-exports the types most callers need and provides [`Importer`], a builder
+//! for the whole decode → detect conflicts → resolve → save pipeline:
+//!
+//! ```no_run
+//! use weakauras_mass_import::api::{Importer, ResolutionPolicy};
+//!
+//! let result = Importer::new("WTF/Account/NAME/SavedVariables/WeakAuras.lua")
+//!     .add_strings(["!WA:2!..."])
+//!     .policy(ResolutionPolicy::NewerVersionOnly)
+//!     .run()?;
+//! println!("{}", result.summary());
+//! # Ok::<(), weakauras_mass_import::api::WeakAuraError>(())
+//! ```
+//!
+//! Items outside this module may change between releases.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::duplicates;
+
+pub use crate::archive_source::{read_archive, ArchiveEntry, ArchiveReader};
+pub use crate::categories::{CategoryKey, CategoryMapper, CategorySet, UpdateCategory};
+pub use crate::chunking::{ImportOptions, DEFAULT_CHUNK_SIZE};
+pub use crate::decoder::{LuaTable, LuaValue, WeakAura, WeakAuraDecoder};
+pub use crate::duplicates::DuplicatePolicy;
+pub use crate::error::{Result, WeakAuraError};
+pub use crate::field_sheet::{FieldUpdateResult, FIELD_COLUMNS};
+pub use crate::file_diff::{CategoryStat, FileDiff, ModifiedAura, MovedAura};
+pub use crate::fs_util::{
+    check_writable, is_permission_denied, is_protected_location, permission_hint,
+};
+pub use crate::policy::ResolutionPolicy;
+pub use crate::repair::{
+    damaged_path, find_fallbacks, game_backup_path, FallbackCandidate, FallbackSource, RepairReport,
+};
+pub use crate::saved_variables::{
+    ConflictAction, ConflictDetectionResult, ConflictResolution, ImportConflict, ImportOutcome,
+    ImportResult, SavedVariablesManager, VersionChange,
+};
+pub use crate::scan::{scan_folder, FolderScan, ScanOptions};
+pub use crate::scripting::{run_script, ScriptChange, ScriptReport};
+pub use crate::templates::{parse_params, AuraTemplate, TemplateParams, TemplateStore};
+pub use crate::transforms::{Transform, TransformPipeline, TransformSpec};
+
+/// Builder for a non-interactive import into one SavedVariables file
+#[derive(Debug, Clone)]
+pub struct Importer {
+    path: PathBuf,
+    strings: Vec<String>,
+    auras: Vec<WeakAura>,
+    policy: ResolutionPolicy,
+    duplicates: DuplicatePolicy,
+    dry_run: bool,
+    allow_newer_db_version: bool,
+    transforms: TransformPipeline,
+    options: ImportOptions,
+}
+
+impl Importer {
+    /// Import into the `WeakAuras.lua` SavedVariables file at `path`.
+    /// A missing file is created on save.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            strings: Vec::new(),
+            auras: Vec::new(),
+            policy: ResolutionPolicy::default(),
+            duplicates: DuplicatePolicy::default(),
+            dry_run: false,
+            allow_newer_db_version: false,
+            transforms: TransformPipeline::new(),
+            options: ImportOptions::default(),
+        }
+    }
+
+    /// Add one import string
+    pub fn add_string(mut self, import_string: impl Into<String>) -> Self {
+        self.strings.push(import_string.into());
+        self
+    }
+
+    /// Add several import strings
+    pub fn add_strings<I, S>(mut self, import_strings: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.strings
+            .extend(import_strings.into_iter().map(Into::into));
+        self
+    }
+
+    /// Add already decoded auras
+    pub fn add_auras(mut self, auras: impl IntoIterator<Item = WeakAura>) -> Self {
+        self.auras.extend(auras);
+        self
+    }
+
+    /// How conflicts with existing auras are resolved (default: update the
+    /// default categories)
+    pub fn policy(mut self, policy: ResolutionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Which copy is imported when the strings and auras hold the same aura
+    /// more than once (default: the first). [`DuplicatePolicy::Ask`] fails
+    /// the import if the copies differ.
+    pub fn duplicates(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicates = policy;
+        self
+    }
+
+    /// Compute the result without writing the SavedVariables file
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Write the file even if its `dbVersion` is newer than
+    /// [`MAX_SUPPORTED_DB_VERSION`](crate::saved_variables::MAX_SUPPORTED_DB_VERSION)
+    pub fn allow_newer_db_version(mut self, allow: bool) -> Self {
+        self.allow_newer_db_version = allow;
+        self
+    }
+
+    /// How the import is written, e.g. in chunks saved one at a time
+    pub fn options(mut self, options: ImportOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Run a transform on every aura before conflicts are detected.
+    /// Transforms run in the order they were added.
+    pub fn transform(mut self, transform: impl Transform + 'static) -> Self {
+        self.transforms.register(Arc::new(transform));
+        self
+    }
+
+    /// Decode all strings, resolve conflicts with the policy and save.
+    /// Fails before touching the file if any string does not decode.
+    pub fn run(self) -> Result<ImportResult> {
+        let mut auras = self
+            .strings
+            .iter()
+            .map(|s| WeakAuraDecoder::decode(s))
+            .collect::<Result<Vec<_>>>()?;
+        auras.extend(self.auras);
+        let (mut auras, duplicates) = duplicates::dedup_auras(auras, self.duplicates)?;
+        self.transforms.apply(&mut auras);
+
+        let mut manager = SavedVariablesManager::new(self.path);
+        manager.allow_newer_db_version = self.allow_newer_db_version;
+        if manager.path.exists() {
+            manager.load()?;
+        }
+        let mut result = if self.dry_run {
+            manager.import_with_policy(&auras, &self.policy)
+        } else {
+            let mut result =
+                manager.import_chunked(&auras, &self.policy, &self.options, |_, _| {})?;
+            result.comparison = Some(manager.comparison());
+            result
+        };
+        result.duplicates = duplicates
+            .iter()
+            .filter(|d| !d.dropped(self.duplicates).is_empty())
+            .map(|d| d.aura_id.clone())
+            .collect();
+        Ok(result)
+    }
+}
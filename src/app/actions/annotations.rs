@@ -0,0 +1,47 @@
+//! Aura tags and notes: editing, persistence, and list filtering.
+
+use crate::saved_variables::AuraTreeNode;
+
+use super::super::notifications::Notification;
+use super::super::state::ParsedAuraEntry;
+use super::super::WeakAuraImporter;
+
+impl WeakAuraImporter {
+    /// Add a tag to the aura currently open in the annotation editor
+    pub(crate) fn add_annotation_tag(&mut self, tag: &str) {
+        if let Some((uid, _)) = &self.annotations.editing {
+            if self.annotations.store.add_tag(uid, tag) {
+                self.save_annotations();
+            }
+        }
+    }
+
+    /// Persist the annotation sidecar, reporting failures as a toast
+    pub(crate) fn save_annotations(&mut self) {
+        if let Err(e) = self.annotations.store.save() {
+            self.notifications.push(
+                Notification::warning(format!("Failed to save tags and notes: {}", e))
+                    .title("Annotations"),
+            );
+        }
+    }
+
+    /// Whether a parsed aura passes the tag/note filter
+    pub(crate) fn parsed_entry_matches_filter(&self, entry: &ParsedAuraEntry) -> bool {
+        let uid = entry.aura.as_ref().and_then(|a| a.uid.as_deref());
+        self.annotations
+            .store
+            .matches(uid, &self.annotations.filter)
+    }
+
+    /// Whether a tree node, or any of its descendants, passes the tag/note filter
+    pub(crate) fn tree_node_matches_filter(&self, node: &AuraTreeNode) -> bool {
+        self.annotations
+            .store
+            .matches(node.uid.as_deref(), &self.annotations.filter)
+            || node
+                .children
+                .iter()
+                .any(|child| self.tree_node_matches_filter(child))
+    }
+}
@@ -0,0 +1,234 @@
+//! Right-click menus of the parsed auras list and the existing auras tree.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::error::WeakAuraError;
+use crate::saved_variables::{set_tree_checked, SavedVariablesManager};
+
+use super::super::notifications::Notification;
+use super::super::state::{ContextMenuState, ContextMenuTarget, StoredAuraEdit};
+use super::super::{Message, WeakAuraImporter};
+
+impl WeakAuraImporter {
+    /// Open the menu of an item, or close it when it is already open
+    pub(crate) fn toggle_context_menu(&mut self, target: ContextMenuTarget) {
+        self.context_menu = if self.context_menu.target.as_ref() == Some(&target) {
+            ContextMenuState::default()
+        } else {
+            ContextMenuState {
+                target: Some(target),
+                rename: None,
+            }
+        };
+    }
+
+    /// Show a parsed aura in the decoded panel, where its editors open
+    pub(crate) fn preview_parsed_aura(&mut self, idx: usize) {
+        if self
+            .parsed_auras
+            .get(idx)
+            .is_some_and(|e| e.validation.is_valid)
+        {
+            self.selected_aura_index = Some(idx);
+            self.ui.show_decoded_view = true;
+        }
+    }
+
+    /// Start typing a new ID for the tree node whose menu is open
+    pub(crate) fn start_rename_stored_aura(&mut self) {
+        if let Some(ContextMenuTarget::TreeNode(id)) = &self.context_menu.target {
+            self.context_menu.rename = Some(id.clone());
+        }
+    }
+
+    /// Rename the tree node whose menu is open to the typed ID
+    pub(crate) fn confirm_rename_stored_aura(&mut self) -> Task<Message> {
+        let menu = std::mem::take(&mut self.context_menu);
+        let (Some(ContextMenuTarget::TreeNode(old_id)), Some(new_id)) = (menu.target, menu.rename)
+        else {
+            return Task::none();
+        };
+        let new_id = new_id.trim().to_string();
+        if new_id == old_id {
+            return Task::none();
+        }
+        self.edit_stored_auras_async("Rename Aura", move |manager| {
+            manager
+                .rename_aura(&old_id, &new_id)
+                .map_err(|e| e.to_string())?;
+            Ok(format!("Renamed '{}' to '{}'", old_id, new_id))
+        })
+    }
+
+    /// Move an existing aura (with its descendants) into a group
+    pub(crate) fn move_stored_aura_async(&mut self, id: String, group_id: String) -> Task<Message> {
+        self.context_menu = ContextMenuState::default();
+        self.edit_stored_auras_async("Move Aura", move |manager| {
+            match manager.move_into_group(&group_id, std::slice::from_ref(&id)) {
+                Some(moved) if !moved.is_empty() => {
+                    Ok(format!("Moved '{}' into '{}'", id, group_id))
+                }
+                Some(_) => Err(format!("'{}' cannot be moved into '{}'", id, group_id)),
+                None => Err(format!("'{}' is not a group in the file", group_id)),
+            }
+        })
+    }
+
+    /// Ask to remove an existing aura and its descendants, leaving the
+    /// removal checkboxes of the tree as they are
+    pub(crate) fn remove_stored_aura(&mut self, id: &str) {
+        self.context_menu = ContextMenuState::default();
+        let mut ids = HashSet::new();
+        set_tree_checked(&self.saved_vars.auras_tree, &mut ids, id, true);
+        self.open_remove_confirm(ids.into_iter().collect());
+    }
+
+    /// Put an aura ID on the clipboard
+    pub(crate) fn copy_aura_id(&mut self, id: String) {
+        self.context_menu = ContextMenuState::default();
+        match self.clipboard.as_mut().map(|c| c.set_text(id)) {
+            Some(Ok(())) => {
+                self.notifications
+                    .push(Notification::info("Aura ID copied to clipboard"));
+            }
+            Some(Err(e)) => {
+                self.notifications.push(
+                    Notification::error(format!("Clipboard error: {}", e)).title("Clipboard Error"),
+                );
+            }
+            None => {
+                self.notifications
+                    .push(Notification::error("Clipboard unavailable").title("Clipboard Error"));
+            }
+        }
+    }
+
+    /// Save a parsed aura and its children to a Lua file chosen by the user
+    pub(crate) fn export_parsed_aura_async(&mut self, idx: usize) -> Task<Message> {
+        self.context_menu = ContextMenuState::default();
+        let Some(aura) = self.parsed_auras.get(idx).and_then(|e| e.aura_for_import()) else {
+            return Task::none();
+        };
+        let file_name = export_file_name(&aura.id);
+        let lua = aura.export_lua();
+        Task::perform(
+            async move {
+                let Some(path) = choose_export_path(file_name).await else {
+                    return Ok(None);
+                };
+                tokio::fs::write(&path, lua)
+                    .await
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                Ok(Some(path))
+            },
+            Message::AuraExported,
+        )
+    }
+
+    /// Save an existing aura and its descendants to a Lua file chosen by the user
+    pub(crate) fn export_stored_aura_async(&mut self, id: String) -> Task<Message> {
+        self.context_menu = ContextMenuState::default();
+        let Some(sv_path) = self.saved_vars.selected_path.clone() else {
+            return Task::none();
+        };
+        Task::perform(
+            async move {
+                let Some(path) = choose_export_path(export_file_name(&id)).await else {
+                    return Ok(None);
+                };
+                let lua = tokio::task::spawn_blocking(move || {
+                    let mut manager = SavedVariablesManager::new(sv_path);
+                    manager.load().map_err(|e| e.to_string())?;
+                    manager
+                        .extract_auras(std::slice::from_ref(&id))
+                        .first()
+                        .map(|aura| aura.export_lua())
+                        .ok_or_else(|| format!("'{}' is no longer in the file", id))
+                })
+                .await
+                .map_err(|e| format!("Task failed: {}", e))??;
+                tokio::fs::write(&path, lua)
+                    .await
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                Ok(Some(path))
+            },
+            Message::AuraExported,
+        )
+    }
+
+    /// Load the selected SavedVariables file in the background, change it
+    /// with `edit` and save it. `edit` returns the notification text.
+    pub(crate) fn edit_stored_auras_async<F>(
+        &mut self,
+        title: &'static str,
+        edit: F,
+    ) -> Task<Message>
+    where
+        F: FnOnce(&mut SavedVariablesManager) -> Result<String, String> + Send + 'static,
+    {
+        let Some(sv_path) = self.saved_vars.selected_path.clone() else {
+            self.notifications
+                .push(Notification::error("No SavedVariables file selected").title(title));
+            return Task::none();
+        };
+        if self.saved_vars.writes_blocked() {
+            self.notify_db_version_blocked(title);
+            return Task::none();
+        }
+        let allow_newer_db_version = self.saved_vars.allow_newer_db_version;
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut manager = SavedVariablesManager::new(sv_path);
+                    manager.allow_newer_db_version = allow_newer_db_version;
+                    match manager.load() {
+                        Ok(()) | Err(WeakAuraError::FileNotFound(_)) => {}
+                        Err(e) => return Err(format!("Failed to load SavedVariables: {}", e)),
+                    }
+                    let message = edit(&mut manager)?;
+                    manager
+                        .save()
+                        .map_err(|e| format!("Failed to save: {}", e))?;
+                    let tree = manager.get_aura_tree();
+                    let tree_count = tree.iter().map(|n| n.total_count()).sum();
+                    Ok(StoredAuraEdit {
+                        message,
+                        tree,
+                        tree_count,
+                    })
+                })
+                .await
+                .map_err(|e| format!("Task failed: {}", e))?
+            },
+            Message::StoredAuraEdited,
+        )
+    }
+}
+
+/// Suggested export file name for an aura ID
+fn export_file_name(id: &str) -> String {
+    let name: String = id
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}.lua", name)
+}
+
+async fn choose_export_path(file_name: String) -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .add_filter("Lua files", &["lua"])
+        .set_file_name(file_name)
+        .save_file()
+        .await
+        .map(|h| h.path().to_path_buf())
+}
@@ -0,0 +1,111 @@
+//! Duplicate policy for auras loaded more than once into the parsed list.
+
+use std::collections::HashSet;
+
+use crate::duplicates::{self, Duplicate, DuplicatePolicy};
+
+use super::super::state::ParsedAuraEntry;
+use super::super::WeakAuraImporter;
+
+/// Auras listed more than once among `entries`, with positions in `entries`
+fn listed_duplicates(entries: &[&ParsedAuraEntry]) -> Vec<Duplicate> {
+    let listed: Vec<usize> = (0..entries.len())
+        .filter(|&i| entries[i].aura.is_some())
+        .collect();
+    let mut found =
+        duplicates::find_duplicates(listed.iter().filter_map(|&i| entries[i].aura.as_ref()));
+    for duplicate in &mut found {
+        for position in &mut duplicate.positions {
+            *position = listed[*position];
+        }
+    }
+    found
+}
+
+impl WeakAuraImporter {
+    /// Add decoded entries to the parsed list and apply the duplicate policy
+    /// to auras now listed more than once. Returns how many of the entries
+    /// stay in the list, and how many copies were dropped or, with
+    /// [`DuplicatePolicy::Ask`], were added next to a differing copy.
+    pub(crate) fn add_parsed_entries(&mut self, entries: Vec<ParsedAuraEntry>) -> (usize, usize) {
+        let before = self.parsed_auras.len();
+        self.parsed_auras.extend(entries);
+        let policy = self.config.duplicate_policy;
+
+        let found = listed_duplicates(&self.parsed_auras.iter().collect::<Vec<_>>());
+        let dropped: HashSet<usize> = found.iter().flat_map(|d| d.dropped(policy)).collect();
+        let added = (before..self.parsed_auras.len())
+            .filter(|i| !dropped.contains(i))
+            .count();
+        let waiting = found
+            .iter()
+            .filter(|d| d.dropped(policy).is_empty())
+            .flat_map(|d| &d.positions)
+            .filter(|&&position| position >= before)
+            .count();
+        self.remove_parsed_entries(&dropped);
+        (added, dropped.len() + waiting)
+    }
+
+    /// Remove the parsed entries at `indices`. Later entries move up, so what
+    /// refers to entries by position is reset.
+    fn remove_parsed_entries(&mut self, indices: &HashSet<usize>) {
+        if indices.is_empty() {
+            return;
+        }
+        let mut idx = 0;
+        self.parsed_auras.retain(|_| {
+            let keep = !indices.contains(&idx);
+            idx += 1;
+            keep
+        });
+        self.selected_aura_index = None;
+        self.property_editor = Default::default();
+        self.raw_editor = Default::default();
+        self.context_menu = Default::default();
+        self.list_selection = Default::default();
+    }
+
+    /// Indices of parsed entries whose aura ID is listed more than once
+    /// with differing data
+    pub(crate) fn duplicate_indices(&self) -> HashSet<usize> {
+        listed_duplicates(&self.parsed_auras.iter().collect::<Vec<_>>())
+            .into_iter()
+            .filter(|d| !d.identical)
+            .flat_map(|d| d.positions)
+            .collect()
+    }
+
+    /// Change the duplicate policy, apply it to the auras already listed and
+    /// save the config
+    pub(crate) fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) {
+        self.config.duplicate_policy = policy;
+        self.save_config();
+        self.add_parsed_entries(Vec::new());
+    }
+
+    /// Keep one copy of an aura listed more than once, removing the others
+    pub(crate) fn keep_duplicate(&mut self, idx: usize) {
+        let Some(id) = self
+            .parsed_auras
+            .get(idx)
+            .and_then(|entry| entry.validation.aura_id.clone())
+        else {
+            return;
+        };
+        let others: HashSet<usize> = self
+            .parsed_auras
+            .iter()
+            .enumerate()
+            .filter(|(i, entry)| *i != idx && entry.validation.aura_id.as_ref() == Some(&id))
+            .map(|(i, _)| i)
+            .collect();
+        self.remove_parsed_entries(&others);
+    }
+
+    /// Auras about to be imported that are listed more than once, for the
+    /// import confirmation
+    pub(crate) fn check_import_duplicates(&mut self) {
+        self.status.import_duplicates = listed_duplicates(&self.entries_for_import());
+    }
+}
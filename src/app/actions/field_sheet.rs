@@ -0,0 +1,69 @@
+//! Exporting fields of existing auras to CSV and applying an edited sheet.
+
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::saved_variables::SavedVariablesManager;
+
+use super::super::notifications::Notification;
+use super::super::{Message, WeakAuraImporter};
+
+impl WeakAuraImporter {
+    /// Save the fields of the checked existing auras, in tree order, or of
+    /// every aura when none is checked, to a CSV file chosen by the user
+    pub(crate) fn export_fields_csv_async(&mut self) -> Task<Message> {
+        let Some(sv_path) = self.saved_vars.selected_path.clone() else {
+            self.notifications
+                .push(Notification::error("No SavedVariables file selected").title("Export"));
+            return Task::none();
+        };
+        let selected = &self.tree_selection.selected;
+        let ids: Vec<String> = self
+            .saved_vars
+            .auras_tree
+            .iter()
+            .flat_map(|node| node.ids())
+            .filter(|id| selected.contains(*id))
+            .map(str::to_string)
+            .collect();
+
+        Task::perform(
+            async move {
+                let Some(path) = rfd::AsyncFileDialog::new()
+                    .add_filter("CSV files", &["csv"])
+                    .set_file_name("auras.csv")
+                    .save_file()
+                    .await
+                    .map(|h| h.path().to_path_buf())
+                else {
+                    return Ok(None);
+                };
+                let csv = tokio::task::spawn_blocking(move || {
+                    let mut manager = SavedVariablesManager::new(sv_path);
+                    manager.load().map_err(|e| e.to_string())?;
+                    Ok::<_, String>(manager.export_fields_csv(&ids))
+                })
+                .await
+                .map_err(|e| format!("Task failed: {}", e))??;
+                tokio::fs::write(&path, csv)
+                    .await
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                Ok(Some(path))
+            },
+            Message::AuraExported,
+        )
+    }
+
+    /// Apply an edited CSV sheet to the selected SavedVariables file and save it
+    pub(crate) fn apply_fields_csv_async(&mut self, path: PathBuf) -> Task<Message> {
+        self.edit_stored_auras_async("Apply CSV", move |manager| {
+            let text = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let result = manager
+                .apply_fields_csv(&text)
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            Ok(result.summary())
+        })
+    }
+}
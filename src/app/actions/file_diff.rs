@@ -0,0 +1,63 @@
+//! "Compare files...": what changed between two whole SavedVariables files,
+//! e.g. after an addon manager or a guildmate edited one.
+
+use iced::Task;
+
+use crate::file_diff::FileDiff;
+use crate::saved_variables::SavedVariablesManager;
+
+use super::super::notifications::Notification;
+use super::super::{Message, WeakAuraImporter};
+
+impl WeakAuraImporter {
+    /// Pick the original and the changed file, then compare them in the background
+    pub(crate) fn compare_files_async(&mut self) -> Task<Message> {
+        let start_dir = self
+            .saved_vars
+            .selected_path
+            .as_deref()
+            .and_then(|path| path.parent())
+            .map(|dir| dir.to_path_buf());
+
+        Task::perform(
+            async move {
+                let pick = |title: &'static str| {
+                    let mut dialog = rfd::AsyncFileDialog::new()
+                        .set_title(title)
+                        .add_filter("Lua files", &["lua", "bak"]);
+                    if let Some(dir) = &start_dir {
+                        dialog = dialog.set_directory(dir);
+                    }
+                    dialog.pick_file()
+                };
+                let Some(old) = pick("Original file").await else {
+                    return Ok(None);
+                };
+                let Some(new) = pick("Changed file").await else {
+                    return Ok(None);
+                };
+                let (old, new) = (old.path().to_path_buf(), new.path().to_path_buf());
+                tokio::task::spawn_blocking(move || {
+                    SavedVariablesManager::diff_files(&old, &new)
+                        .map(Some)
+                        .map_err(|e| e.to_string())
+                })
+                .await
+                .map_err(|e| format!("Task failed: {}", e))?
+            },
+            Message::FilesCompared,
+        )
+    }
+
+    /// Show the comparison, or why it failed
+    pub(crate) fn finish_compare_files(&mut self, result: Result<Option<FileDiff>, String>) {
+        match result {
+            Ok(None) => {}
+            Ok(Some(diff)) => self.saved_vars.file_diff = Some(diff),
+            Err(e) => self.notifications.push(
+                Notification::error(format!("Could not compare the files: {}", e))
+                    .title("Compare Files"),
+            ),
+        }
+    }
+}
@@ -1,11 +1,13 @@
 //! Message update handlers for async task results.
 
-use iced_toasts::{toast, ToastLevel};
+use std::collections::BTreeSet;
 
 use crate::saved_variables::ConflictAction;
 
+use super::super::notifications::{Notification, NotificationLevel, Problem};
 use super::super::state::{
-    ConflictResolutionUI, ImportUpdate, LoadingUpdate, RemovalUpdate, ScanUpdate,
+    ConflictResolutionUI, ImportOutcome, ImportUpdate, LoadedAuras, LoadingUpdate, RemovalUpdate,
+    RemovedAuras, ScanOutcome, ScanUpdate, TaskEvent, TaskKind, TaskProgress,
 };
 use super::super::WeakAuraImporter;
 use super::notify_decode_results;
@@ -13,26 +15,20 @@ use super::notify_decode_results;
 impl WeakAuraImporter {
     /// Handle loading update from async task
     pub(crate) fn handle_loading_update(&mut self, update: LoadingUpdate) {
+        if update.is_final() {
+            self.tasks.handles.remove(&TaskKind::Loading);
+        }
         match update {
-            LoadingUpdate::Progress {
+            TaskEvent::Progress {
                 current,
                 total,
                 message,
             } => {
-                self.tasks.loading_progress = if total > 0 {
-                    current as f32 / total as f32
-                } else {
-                    0.0
-                };
+                self.tasks.loading_progress = TaskProgress::fraction(current, total);
                 self.tasks.loading_message = message;
             }
-            LoadingUpdate::Complete {
-                entries,
-                added,
-                duplicates,
-                errors,
-            } => {
-                self.parsed_auras.extend(entries);
+            TaskEvent::Complete(LoadedAuras { entries, errors }) => {
+                let (added, duplicates) = self.add_parsed_entries(entries);
                 self.tasks.is_loading = false;
                 self.tasks.loading_progress = 1.0;
                 self.tasks.loading_message.clear();
@@ -42,69 +38,105 @@ impl WeakAuraImporter {
                 self.status.message = format!("{} aura(s) loaded, ready to import.", total);
                 self.status.is_error = false;
 
-                notify_decode_results(&mut self.toasts, added, duplicates, &errors, "loaded");
+                notify_decode_results(
+                    &mut self.notifications,
+                    added,
+                    duplicates,
+                    self.config.duplicate_policy,
+                    &errors,
+                    "loaded",
+                );
             }
-            LoadingUpdate::Error(msg) => {
+            TaskEvent::Error(msg) => {
                 self.tasks.is_loading = false;
                 self.tasks.loading_progress = 0.0;
                 self.tasks.loading_message.clear();
                 self.status.message = format!("Load failed: {}", msg);
                 self.status.is_error = true;
-                self.toasts
-                    .push(toast(&msg).title("Load Error").level(ToastLevel::Error));
+                self.notifications
+                    .push(Notification::error(msg).title("Load Error"));
             }
         }
     }
 
     /// Handle import update from async task
     pub(crate) fn handle_import_update(&mut self, update: ImportUpdate) {
+        if update.is_final() {
+            self.tasks.handles.remove(&TaskKind::Importing);
+        }
         match update {
-            ImportUpdate::Progress {
+            TaskEvent::Progress {
                 current,
                 total,
                 message,
             } => {
-                self.tasks.import_progress = if total > 0 {
-                    current as f32 / total as f32
-                } else {
-                    0.0
-                };
+                self.tasks.import_progress = TaskProgress::fraction(current, total);
                 self.tasks.import_message = message;
             }
-            ImportUpdate::ConflictsDetected(conflict_result) => {
+            TaskEvent::Complete(ImportOutcome::ConflictsDetected(conflict_result)) => {
                 // Initialize resolutions with defaults
                 self.conflicts.resolutions = conflict_result
                     .conflicts
                     .iter()
                     .map(|c| ConflictResolutionUI {
                         aura_id: c.aura_id.clone(),
-                        action: ConflictAction::UpdateSelected,
+                        // Known previous import: merge like WeakAuras' own update flow
+                        action: if c.base.is_some() {
+                            ConflictAction::Merge
+                        } else {
+                            ConflictAction::UpdateSelected
+                        },
                         categories: self.conflicts.global_categories.clone(),
                         expanded: false,
+                        advanced: false,
+                        fields: BTreeSet::new(),
                     })
                     .collect();
                 self.conflicts.result = Some(conflict_result);
                 self.ui.show_conflict_dialog = true;
                 self.conflicts.selected_index = None;
+                self.conflicts.page = 0;
+                if let Some(name) = self.config.auto_preset.clone() {
+                    self.apply_conflict_preset(&name);
+                }
                 self.tasks.is_importing = false;
                 self.tasks.import_progress = 0.0;
                 self.tasks.import_message.clear();
             }
-            ImportUpdate::Complete {
+            TaskEvent::Complete(ImportOutcome::Imported {
                 result,
                 tree,
                 tree_count,
-            } => {
+            }) => {
                 let summary = result.summary();
                 self.status.message = format!("Import complete: {}", summary);
                 self.status.is_error = false;
-                self.toasts.push(
-                    toast(&format!("Import complete: {}", summary))
-                        .title("Success")
-                        .level(ToastLevel::Success),
-                );
+                if result.failed.is_empty() {
+                    self.notifications.push(
+                        Notification::success(format!("Import complete: {}", summary))
+                            .title("Success"),
+                    );
+                } else {
+                    self.notifications.push(
+                        Notification::warning(format!("Import complete: {}", summary))
+                            .title("Some Auras Failed")
+                            .details(
+                                result
+                                    .failed
+                                    .iter()
+                                    .map(|(id, error)| format!("{}: {}", id, error))
+                                    .collect(),
+                            ),
+                    );
+                }
+                if self.config.write_companion_data {
+                    self.write_companion_data(&result);
+                }
+                self.ui.show_import_report =
+                    !result.outcomes.is_empty() || !result.failed.is_empty();
                 self.status.last_import_result = Some(result);
                 self.saved_vars.auras_tree = tree;
+                self.refresh_has_backup();
                 self.saved_vars.auras_count = tree_count;
                 self.tasks.is_importing = false;
                 self.tasks.import_progress = 1.0;
@@ -112,11 +144,19 @@ impl WeakAuraImporter {
                 self.ui.show_conflict_dialog = false;
                 self.conflicts.resolutions.clear();
             }
-            ImportUpdate::Error(msg) => {
+            TaskEvent::Complete(ImportOutcome::SaveDenied(denied)) => {
+                self.show_denied_save(denied);
+                self.tasks.is_importing = false;
+                self.tasks.import_progress = 0.0;
+                self.tasks.import_message.clear();
+                self.ui.show_conflict_dialog = false;
+                self.conflicts.resolutions.clear();
+            }
+            TaskEvent::Error(msg) => {
                 self.status.message = format!("Import failed: {}", msg);
                 self.status.is_error = true;
-                self.toasts
-                    .push(toast(&msg).title("Import Error").level(ToastLevel::Error));
+                self.notifications
+                    .push(Notification::error(msg).title("Import Error"));
                 self.tasks.is_importing = false;
                 self.tasks.import_progress = 0.0;
                 self.tasks.import_message.clear();
@@ -127,8 +167,14 @@ impl WeakAuraImporter {
     /// Handle scan update from async task
     pub(crate) fn handle_scan_update(&mut self, update: ScanUpdate) {
         match update {
-            ScanUpdate::Complete { tree, count } => {
+            TaskEvent::Progress { message, .. } => {
+                self.tasks.scanning_message = message;
+            }
+            TaskEvent::Complete(ScanOutcome::Tree { tree, count }) => {
+                self.tasks.handles.remove(&TaskKind::Scanning);
+                self.saved_vars.load_failed = false;
                 self.saved_vars.auras_tree = tree;
+                self.refresh_has_backup();
                 self.saved_vars.auras_count = count;
                 self.sidebar.expanded_groups.clear();
                 self.tasks.is_scanning = false;
@@ -136,61 +182,102 @@ impl WeakAuraImporter {
                 if count > 0 {
                     self.status.message = format!("{} existing aura(s) in SavedVariables.", count);
                     self.status.is_error = false;
-                    self.toasts.push(
-                        toast(&format!("Loaded {} existing aura(s)", count))
-                            .level(ToastLevel::Info),
-                    );
+                    self.notifications.push(Notification::info(format!(
+                        "Loaded {} existing aura(s)",
+                        count
+                    )));
+                }
+            }
+            TaskEvent::Complete(ScanOutcome::Parsed(parsed)) => {
+                self.tasks.handles.remove(&TaskKind::Scanning);
+                let is_selected = self.saved_vars.selected_path.as_ref() == Some(&parsed.path);
+                let (tree, count) = (parsed.tree.clone(), parsed.count);
+                if is_selected {
+                    self.saved_vars.db_version = parsed.db_version;
+                }
+                self.describe_discovered(&parsed);
+                self.saved_vars.tree_cache.insert(parsed);
+                if is_selected {
+                    self.handle_scan_update(TaskEvent::Complete(ScanOutcome::Tree { tree, count }));
                 }
             }
-            ScanUpdate::Error(msg) => {
+            TaskEvent::Complete(ScanOutcome::Damaged { error, fallbacks }) => {
+                self.saved_vars.fallbacks = fallbacks;
+                self.handle_scan_update(TaskEvent::Error(error));
+            }
+            TaskEvent::Complete(ScanOutcome::Preparsed { path, parsed }) => {
+                self.saved_vars.preparsing.remove(&path);
+                if self.saved_vars.preparsing.is_empty() {
+                    self.tasks.handles.remove(&TaskKind::Preparsing);
+                }
+                match parsed {
+                    Ok(parsed) => {
+                        self.describe_discovered(&parsed);
+                        self.saved_vars.tree_cache.insert(parsed);
+                    }
+                    Err(e) => self.notifications.report(Problem::new(
+                        NotificationLevel::Warning,
+                        "Pre-parse",
+                        format!("{}: {}", path.display(), e),
+                    )),
+                }
+            }
+            TaskEvent::Error(msg) => {
+                self.tasks.handles.remove(&TaskKind::Scanning);
+                self.saved_vars.load_failed = true;
                 self.saved_vars.auras_tree = Vec::new();
                 self.saved_vars.auras_count = 0;
                 self.tasks.is_scanning = false;
                 self.tasks.scanning_message.clear();
                 self.status.message = format!("Scan failed: {}", msg);
                 self.status.is_error = true;
-                self.toasts
-                    .push(toast(&msg).title("Scan Error").level(ToastLevel::Error));
+                self.notifications
+                    .push(Notification::error(msg).title("Scan Error"));
             }
         }
     }
 
     /// Handle removal update from async task
     pub(crate) fn handle_removal_update(&mut self, update: RemovalUpdate) {
+        if update.is_final() {
+            self.tasks.handles.remove(&TaskKind::Removing);
+        }
         match update {
-            RemovalUpdate::Complete {
+            TaskEvent::Progress { message, .. } => {
+                self.tasks.removal_message = message;
+            }
+            TaskEvent::Complete(RemovedAuras {
                 removed_count,
                 tree,
                 tree_count,
-            } => {
+            }) => {
                 self.saved_vars.auras_tree = tree;
+                self.refresh_has_backup();
                 self.saved_vars.auras_count = tree_count;
-                self.removal.selected_ids.clear();
+                self.tree_selection.clear();
                 self.tasks.is_removing = false;
                 self.tasks.removal_message.clear();
                 if removed_count == 0 {
                     self.status.message = "No auras removed (already absent).".to_string();
                     self.status.is_error = false;
-                    self.toasts.push(
-                        toast("No auras were removed (already absent)").level(ToastLevel::Info),
-                    );
+                    self.notifications
+                        .push(Notification::info("No auras were removed (already absent)"));
                 } else {
                     self.status.message = format!("Removed {} aura(s).", removed_count);
                     self.status.is_error = false;
-                    self.toasts.push(
-                        toast(&format!("Removed {} aura(s)", removed_count))
-                            .title("Success")
-                            .level(ToastLevel::Success),
+                    self.notifications.push(
+                        Notification::success(format!("Removed {} aura(s)", removed_count))
+                            .title("Success"),
                     );
                 }
             }
-            RemovalUpdate::Error(msg) => {
+            TaskEvent::Error(msg) => {
                 self.tasks.is_removing = false;
                 self.tasks.removal_message.clear();
                 self.status.message = format!("Removal failed: {}", msg);
                 self.status.is_error = true;
-                self.toasts
-                    .push(toast(&msg).title("Removal Error").level(ToastLevel::Error));
+                self.notifications
+                    .push(Notification::error(msg).title("Removal Error"));
             }
         }
     }
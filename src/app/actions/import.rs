@@ -1,73 +1,372 @@
 //! Import auras to SavedVariables (with conflict detection and resolution).
 
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-use iced::futures::SinkExt;
-use iced::{stream, Task};
-use iced_toasts::{toast, ToastLevel};
+use iced::Task;
+use tracing::{info, warn};
 
+use crate::categories::CategoryMapper;
+use crate::chunking::ImportOptions;
+use crate::companion_data::CompanionData;
+use crate::config::ResolutionPreset;
+use crate::decoder::LuaValue;
 use crate::decoder::WeakAura;
+use crate::discovery;
+use crate::duplicates::{self, DuplicatePolicy};
 use crate::error::WeakAuraError;
-use crate::saved_variables::{ConflictResolution, SavedVariablesManager};
-
-use super::super::state::ImportUpdate;
+use crate::media;
+use crate::nesting;
+use crate::pack::MediaInstallResult;
+use crate::saved_variables::{
+    ConflictAction, ConflictResolution, ImportResult, SavedVariablesManager, VersionChange,
+};
+use crate::snapshots::SnapshotStore;
+use crate::transforms::TransformPipeline;
+
+use super::super::notifications::Notification;
+use super::super::state::{DeniedSave, ImportOutcome, ParsedAuraEntry, ReportFormat, TaskKind};
 use super::super::{Message, WeakAuraImporter};
+use super::{run_task, TaskSender};
 
 impl WeakAuraImporter {
     /// Import selected auras to SavedVariables (async with streaming progress)
     pub(crate) fn import_auras_async(&mut self) -> Task<Message> {
         let Some(sv_path) = self.saved_vars.selected_path.clone() else {
-            self.toasts.push(
-                toast("No SavedVariables file selected")
-                    .title("Import Error")
-                    .level(ToastLevel::Error),
-            );
+            self.notifications
+                .push(Notification::error("No SavedVariables file selected").title("Import Error"));
             return Task::none();
         };
 
-        // Collect selected valid auras
-        let auras: Vec<WeakAura> = self
-            .parsed_auras
-            .iter()
-            .filter(|e| e.selected && e.aura.is_some())
-            .filter_map(|e| e.aura.clone())
-            .collect();
-
+        let (auras, skipped) = self.selected_auras_for_import();
         if auras.is_empty() {
-            self.toasts.push(
-                toast("No valid auras selected for import")
-                    .title("Import Error")
-                    .level(ToastLevel::Error),
-            );
+            let message = if skipped > 0 {
+                "All selected auras were skipped by transforms"
+            } else {
+                "No valid auras selected for import"
+            };
+            self.notifications
+                .push(Notification::error(message).title("Import Error"));
             return Task::none();
         }
 
+        if self.saved_vars.writes_blocked() {
+            self.notify_db_version_blocked("Import Error");
+            return Task::none();
+        }
+
+        if skipped > 0 {
+            self.notifications.push(Notification::info(format!(
+                "{} aura(s) skipped by transforms",
+                skipped
+            )));
+        }
+
         self.tasks.is_importing = true;
         self.tasks.import_progress = 0.0;
         self.tasks.import_message = "Starting import...".to_string();
+        let allow_newer_db_version = self.saved_vars.allow_newer_db_version;
+        let target_group = self.saved_vars.target_group.clone();
+        let import_options = self.config.import_options.clone();
+
+        let import = run_task(Message::ImportUpdate, move |mut tasks| async move {
+            run_import_pipeline(
+                sv_path,
+                auras,
+                target_group,
+                allow_newer_db_version,
+                import_options,
+                &mut tasks,
+            )
+            .await;
+        });
+        self.track_task(TaskKind::Importing, import)
+    }
 
-        Task::run(
-            stream::channel(
-                100,
-                move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
-                    run_import_pipeline(sv_path, auras, &mut sender).await;
-                },
-            ),
-            |msg| msg,
+    /// Entries the pending import takes: every staged aura when importing the
+    /// staging area, otherwise the selected auras of the list
+    pub(crate) fn entries_for_import(&self) -> Vec<&ParsedAuraEntry> {
+        if self.staging.importing {
+            self.staging.entries.iter().collect()
+        } else {
+            self.parsed_auras.iter().filter(|e| e.selected).collect()
+        }
+    }
+
+    /// Valid auras to import, one copy of each under the duplicate policy,
+    /// run through the enabled transforms (conflicts are detected on the
+    /// transformed auras), and how many the transforms skipped
+    pub(crate) fn selected_auras_for_import(&self) -> (Vec<WeakAura>, usize) {
+        let auras: Vec<WeakAura> = self
+            .entries_for_import()
+            .into_iter()
+            .filter_map(ParsedAuraEntry::aura_for_import)
+            .collect();
+        // Differing copies under `Ask` keep the import from being confirmed;
+        // until one is picked, the first stands in for the others
+        let policy = match self.config.duplicate_policy {
+            DuplicatePolicy::Ask => DuplicatePolicy::KeepFirst,
+            policy => policy,
+        };
+        let (mut auras, _) = duplicates::dedup_auras(auras, policy).unwrap_or_default();
+        let skipped = TransformPipeline::from_steps(&self.config.transforms).apply(&mut auras);
+        (auras, skipped)
+    }
+
+    /// Look up the media files referenced by the auras to import, for the
+    /// warning in the import confirmation. Nothing is checked when the
+    /// SavedVariables file is not inside a WoW folder.
+    pub(crate) fn check_import_media(&mut self) {
+        let flavor_dir = self
+            .saved_vars
+            .selected_path
+            .as_deref()
+            .and_then(discovery::flavor_dir_of);
+        let mut missing = match flavor_dir {
+            Some(flavor_dir) => {
+                media::missing_media(&self.selected_auras_for_import().0, &flavor_dir)
+            }
+            None => Vec::new(),
+        };
+        // Files the packs bring along are installed with the auras
+        if self.media_packs.install {
+            let provided: HashSet<String> = self
+                .media_packs
+                .packs
+                .iter()
+                .flat_map(|pack| pack.game_paths(&self.config.media_folder))
+                .map(|path| path.to_ascii_lowercase())
+                .collect();
+            missing.retain(|reference| !provided.contains(&reference.path.to_ascii_lowercase()));
+        }
+        self.status.missing_media = missing;
+    }
+
+    /// Check the group nesting the import would create in its target group,
+    /// for the warning in the import confirmation
+    pub(crate) fn check_import_nesting(&mut self) {
+        self.status.nesting_warnings = nesting::check_nesting(
+            &self.saved_vars.auras_tree,
+            self.saved_vars.target_group.as_deref(),
+            &self.selected_auras_for_import().0,
+        );
+    }
+
+    /// Write the WeakAurasCompanion data addon for the Wago auras added or
+    /// replaced by an import, next to the selected SavedVariables file
+    pub(crate) fn write_companion_data(&mut self, result: &ImportResult) {
+        let Some(flavor_dir) = self
+            .saved_vars
+            .selected_path
+            .as_deref()
+            .and_then(discovery::flavor_dir_of)
+        else {
+            return;
+        };
+        let imported: HashSet<&String> = result.added.iter().chain(&result.replaced).collect();
+        let (mut auras, _) = self.selected_auras_for_import();
+        auras.retain(|aura| imported.contains(&aura.id));
+        let data = CompanionData::from_auras(&auras);
+        if data.is_empty() {
+            return;
+        }
+        if let Err(e) = data.write_addon(&flavor_dir) {
+            self.notifications.push(
+                Notification::error(format!("Failed to write update info: {}", e))
+                    .title("WeakAuras Companion"),
+            );
+        }
+    }
+
+    /// Copy the media of the loaded packs into the game folder of the
+    /// selected SavedVariables file (no task when there is nothing to install)
+    pub(crate) fn install_pack_media_async(&mut self) -> Task<Message> {
+        if !self.media_packs.install || self.media_packs.packs.is_empty() {
+            return Task::none();
+        }
+        let Some(flavor_dir) = self
+            .saved_vars
+            .selected_path
+            .as_deref()
+            .and_then(discovery::flavor_dir_of)
+        else {
+            self.notifications.push(
+                Notification::warning("SavedVariables file is not inside a WoW folder")
+                    .title("Media Not Installed"),
+            );
+            return Task::none();
+        };
+        let packs = self.media_packs.packs.clone();
+        let media_folder = self.config.media_folder.clone();
+        let replace_differing = self.media_packs.replace_differing;
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    packs
+                        .iter()
+                        .map(|pack| {
+                            pack.install(&flavor_dir, &media_folder, replace_differing)
+                                .map(|result| (pack.name(), result))
+                                .map_err(|e| format!("{}: {}", pack.name(), e))
+                        })
+                        .collect()
+                })
+                .await
+                .unwrap_or_else(|e| Err(e.to_string()))
+            },
+            Message::PackMediaInstalled,
         )
     }
 
+    /// Report the outcome of installing pack media
+    pub(crate) fn on_pack_media_installed(
+        &mut self,
+        result: Result<Vec<(String, MediaInstallResult)>, String>,
+    ) {
+        match result {
+            Ok(installed) => {
+                let copied: usize = installed.iter().map(|(_, r)| r.copied + r.replaced).sum();
+                let unchanged: usize = installed.iter().map(|(_, r)| r.unchanged).sum();
+                let names: Vec<&str> = installed.iter().map(|(name, _)| name.as_str()).collect();
+                let message = if unchanged > 0 {
+                    format!(
+                        "Installed {} media file(s) from {} ({} already up to date)",
+                        copied,
+                        names.join(", "),
+                        unchanged
+                    )
+                } else {
+                    format!(
+                        "Installed {} media file(s) from {}",
+                        copied,
+                        names.join(", ")
+                    )
+                };
+                let kept: usize = installed.iter().map(|(_, r)| r.kept).sum();
+                if kept > 0 {
+                    self.notifications.push(
+                        Notification::warning(format!(
+                            "{} ({} installed file(s) differ and were kept)",
+                            message, kept
+                        ))
+                        .title("Pack Media")
+                        .details(vec![
+                            "Tick \"Replace installed files that differ\" and import again to overwrite them".to_string(),
+                        ]),
+                    );
+                } else {
+                    self.notifications
+                        .push(Notification::success(message).title("Pack Media"));
+                }
+            }
+            Err(e) => {
+                self.notifications.push(
+                    Notification::error(format!("Failed to install media: {}", e))
+                        .title("Pack Media"),
+                );
+            }
+        }
+    }
+
+    /// Toggle field-level selection for a conflict. When first enabled, the field
+    /// list is pre-filled with the changed fields covered by the selected categories.
+    pub(crate) fn toggle_conflict_advanced(&mut self, idx: usize) {
+        let changed_fields = self
+            .conflicts
+            .result
+            .as_ref()
+            .and_then(|r| r.conflicts.get(idx))
+            .map(|c| c.changed_fields())
+            .unwrap_or_default();
+        let Some(res) = self.conflicts.resolutions.get_mut(idx) else {
+            return;
+        };
+
+        res.advanced = !res.advanced;
+        if res.advanced && res.fields.is_empty() {
+            let (categories, extra_fields) = self.conflicts.category_set.resolve(&res.categories);
+            res.fields = changed_fields
+                .into_iter()
+                .filter(|f| {
+                    categories.contains(&CategoryMapper::get_category(f))
+                        || extra_fields.contains(f)
+                })
+                .collect();
+        }
+    }
+
+    /// Update conflicts where the incoming version is newer and skip all others
+    /// (among the conflicts matching the current filters)
+    pub(crate) fn update_only_newer_conflicts(&mut self) {
+        let visible = self.conflicts.visible_indices();
+        let Some(result) = &self.conflicts.result else {
+            return;
+        };
+        for idx in visible {
+            let (Some(res), Some(conflict)) = (
+                self.conflicts.resolutions.get_mut(idx),
+                result.conflicts.get(idx),
+            ) else {
+                continue;
+            };
+            res.action = match conflict.version_change {
+                VersionChange::Newer if conflict.base.is_some() => ConflictAction::Merge,
+                VersionChange::Newer => ConflictAction::UpdateSelected,
+                _ => ConflictAction::Skip,
+            };
+            res.categories = self.conflicts.global_categories.clone();
+        }
+    }
+
+    /// Apply a saved resolution preset to the default categories and every conflict
+    pub(crate) fn apply_conflict_preset(&mut self, name: &str) {
+        let Some(preset) = self.config.preset(name) else {
+            return;
+        };
+        let categories = preset.category_keys(&self.conflicts.category_set);
+        let action = preset.action;
+        for res in &mut self.conflicts.resolutions {
+            res.action = action;
+            res.categories = categories.clone();
+            res.advanced = false;
+        }
+        self.conflicts.global_categories = categories;
+        self.conflicts.selected_preset = Some(name.to_string());
+    }
+
+    /// Save the current default categories as a named preset
+    pub(crate) fn save_conflict_preset(&mut self) {
+        let preset = ResolutionPreset::new(
+            &self.conflicts.preset_name,
+            self.conflicts.preset_action,
+            &self.conflicts.global_categories,
+            &self.conflicts.category_set,
+        );
+        let name = preset.name.clone();
+        if !self.config.save_preset(preset) {
+            return;
+        }
+        self.save_config();
+        self.notifications
+            .push(Notification::success(format!("Saved preset '{}'", name)).title("Preset"));
+        self.conflicts.selected_preset = Some(name);
+        self.conflicts.preset_name.clear();
+    }
+
     /// Complete import with conflict resolutions (async with streaming progress)
     pub(crate) fn complete_import_with_resolutions_async(&mut self) -> Task<Message> {
         let Some(sv_path) = self.saved_vars.selected_path.clone() else {
-            self.toasts.push(
-                toast("No SavedVariables file selected")
-                    .title("Import Error")
-                    .level(ToastLevel::Error),
-            );
+            self.notifications
+                .push(Notification::error("No SavedVariables file selected").title("Import Error"));
             return Task::none();
         };
 
+        if self.saved_vars.writes_blocked() {
+            self.notify_db_version_blocked("Import Error");
+            return Task::none();
+        }
+
         let Some(conflict_result) = self.conflicts.result.take() else {
             return Task::none();
         };
@@ -77,10 +376,18 @@ impl WeakAuraImporter {
             .conflicts
             .resolutions
             .iter()
-            .map(|r| ConflictResolution {
-                aura_id: r.aura_id.clone(),
-                action: r.action,
-                categories_to_update: r.categories.clone(),
+            .map(|r| {
+                let (categories_to_update, extra_fields) = if r.advanced {
+                    (HashSet::new(), r.fields.clone())
+                } else {
+                    self.conflicts.category_set.resolve(&r.categories)
+                };
+                ConflictResolution {
+                    aura_id: r.aura_id.clone(),
+                    action: r.action,
+                    categories_to_update,
+                    extra_fields,
+                }
             })
             .collect();
 
@@ -88,52 +395,79 @@ impl WeakAuraImporter {
         self.tasks.import_progress = 0.0;
         self.tasks.import_message = "Starting import...".to_string();
         self.ui.show_conflict_dialog = false;
+        let resolved = ResolvedImport {
+            sv_path,
+            conflict_result,
+            resolutions,
+            target_group: self.saved_vars.target_group.clone(),
+            preserve_position: self.config.preserve_position,
+            keep_user_config: self.config.keep_user_config,
+            allow_newer_db_version: self.saved_vars.allow_newer_db_version,
+        };
 
-        Task::run(
-            stream::channel(
-                100,
-                move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
-                    run_import_with_resolutions(sv_path, conflict_result, resolutions, &mut sender)
-                        .await;
-                },
-            ),
-            |msg| msg,
-        )
+        let import = run_task(Message::ImportUpdate, move |mut tasks| async move {
+            run_import_with_resolutions(resolved, &mut tasks).await;
+        });
+        self.track_task(TaskKind::Importing, import)
     }
-}
 
-/// Send a progress update message
-async fn send_progress(
-    sender: &mut iced::futures::channel::mpsc::Sender<Message>,
-    current: usize,
-    total: usize,
-    message: impl Into<String>,
-) {
-    let _ = sender
-        .send(Message::ImportUpdate(ImportUpdate::Progress {
-            current,
-            total,
-            message: message.into(),
-        }))
-        .await;
-}
+    /// Copy the last import report to the clipboard as plain text
+    pub(crate) fn copy_import_report(&mut self) {
+        let Some(result) = &self.status.last_import_result else {
+            return;
+        };
+        let report = result.to_report_text();
+        match self.clipboard.as_mut().map(|c| c.set_text(report)) {
+            Some(Ok(())) => {
+                self.notifications
+                    .push(Notification::info("Import report copied to clipboard"));
+            }
+            Some(Err(e)) => {
+                self.notifications.push(
+                    Notification::error(format!("Clipboard error: {}", e)).title("Clipboard Error"),
+                );
+            }
+            None => {
+                self.notifications
+                    .push(Notification::error("Clipboard unavailable").title("Clipboard Error"));
+            }
+        }
+    }
 
-/// Send an error message
-async fn send_error(sender: &mut iced::futures::channel::mpsc::Sender<Message>, msg: String) {
-    let _ = sender
-        .send(Message::ImportUpdate(ImportUpdate::Error(msg)))
-        .await;
+    /// Write the last import report to a file
+    pub(crate) fn export_import_report(&mut self, format: ReportFormat, path: &Path) {
+        let Some(result) = &self.status.last_import_result else {
+            return;
+        };
+        let content = match format {
+            ReportFormat::Csv => Ok(result.to_csv()),
+            ReportFormat::Json => result.to_json_string(),
+        };
+        match content.and_then(|c| std::fs::write(path, c).map_err(WeakAuraError::from)) {
+            Ok(()) => self.notifications.push(
+                Notification::success(format!("Report saved to {}", path.display()))
+                    .title("Export"),
+            ),
+            Err(e) => self.notifications.push(
+                Notification::error(format!("Failed to save report: {}", e)).title("Export Error"),
+            ),
+        }
+    }
 }
 
 /// Load SavedVariables manager, handling the common "file not found is OK" pattern
 async fn load_manager(
     sv_path: PathBuf,
-    sender: &mut iced::futures::channel::mpsc::Sender<Message>,
+    allow_newer_db_version: bool,
+    tasks: &mut TaskSender<ImportOutcome>,
 ) -> Option<SavedVariablesManager> {
     let mut manager = SavedVariablesManager::new(sv_path);
+    manager.allow_newer_db_version = allow_newer_db_version;
     if let Err(e) = manager.load() {
         if !matches!(e, WeakAuraError::FileNotFound(_)) {
-            send_error(sender, format!("Failed to load SavedVariables: {}", e)).await;
+            tasks
+                .error(format!("Failed to load SavedVariables: {}", e))
+                .await;
             return None;
         }
     }
@@ -143,103 +477,252 @@ async fn load_manager(
 /// Run the import pipeline (used by import_auras_async)
 async fn run_import_pipeline(
     sv_path: PathBuf,
-    auras: Vec<WeakAura>,
-    sender: &mut iced::futures::channel::mpsc::Sender<Message>,
+    mut auras: Vec<WeakAura>,
+    target_group: Option<String>,
+    allow_newer_db_version: bool,
+    import_options: ImportOptions,
+    tasks: &mut TaskSender<ImportOutcome>,
 ) {
     // Phase 1: Loading SavedVariables (0-25%)
-    send_progress(sender, 1, 4, "Loading SavedVariables...").await;
+    tasks.step(1, 4, "Loading SavedVariables...").await;
 
-    let Some(mut manager) = load_manager(sv_path, sender).await else {
+    let Some(mut manager) = load_manager(sv_path, allow_newer_db_version, tasks).await else {
         return;
     };
 
     // Phase 2: Detecting conflicts (25-50%)
-    send_progress(sender, 2, 4, "Detecting conflicts...").await;
-
-    let conflict_result = manager.detect_conflicts(&auras);
+    tasks.step(2, 4, "Detecting conflicts...").await;
+
+    let mut conflict_result = manager.detect_conflicts(&auras);
+    let fresh_uids = conflict_result.assign_fresh_uids();
+    if !fresh_uids.is_empty() {
+        info!(
+            count = fresh_uids.len(),
+            "Assigned fresh uids to auras sharing a uid with another aura"
+        );
+        for aura in &mut auras {
+            aura.replace_uids(&fresh_uids);
+        }
+    }
 
-    // If there are conflicts, send back to UI for resolution
+    // If there are conflicts, send back to UI for resolution (with merge bases)
     if !conflict_result.conflicts.is_empty() {
-        let _ = sender
-            .send(Message::ImportUpdate(ImportUpdate::ConflictsDetected(
-                conflict_result,
-            )))
+        conflict_result.attach_bases(&SnapshotStore::load());
+        tasks
+            .complete(ImportOutcome::ConflictsDetected(conflict_result))
             .await;
         return;
     }
 
-    // Phase 3: Importing auras (50-75%)
-    send_progress(
-        sender,
-        3,
-        4,
-        format!("Importing {} aura(s)...", auras.len()),
-    )
-    .await;
-
-    let result = match manager.add_auras(&auras) {
-        Ok(r) => r,
-        Err(e) => {
-            send_error(sender, format!("Import failed: {}", e)).await;
-            return;
+    // Phase 3: Importing auras (50-75%), saving after every chunk but the
+    // last when chunked saves are on
+    let chunks = import_options.chunks(&auras);
+    let mut result = ImportResult::default();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let message = if chunks.len() > 1 {
+            format!(
+                "Importing chunk {} of {} ({} aura(s))...",
+                index + 1,
+                chunks.len(),
+                chunk.len()
+            )
+        } else {
+            format!("Importing {} aura(s)...", auras.len())
+        };
+        tasks.step(3, 4, message).await;
+
+        match manager.add_auras(chunk) {
+            Ok(r) => result.merge(r),
+            Err(e) => {
+                tasks.error(format!("Import failed: {}", e)).await;
+                return;
+            }
         }
-    };
+        if index + 1 < chunks.len() {
+            // Only the first save writes the backup, which keeps the file
+            // from before the import
+            let saved = if index == 0 {
+                manager.save()
+            } else {
+                manager.save_without_backup()
+            };
+            if let Err(e) = saved {
+                match save_failure(&manager, e) {
+                    Ok(denied) => tasks.complete(denied).await,
+                    Err(message) => tasks.error(message).await,
+                }
+                return;
+            }
+        }
+    }
+    if let Some(group_id) = &target_group {
+        let ids: Vec<String> = auras
+            .iter()
+            .filter(|a| !result.failed.iter().any(|(id, _)| id == &a.id))
+            .map(|a| a.id.clone())
+            .collect();
+        move_into_target_group(&mut manager, group_id, &ids);
+    }
 
     // Phase 4: Saving (75-100%)
-    send_progress(sender, 4, 4, "Saving changes...").await;
+    tasks.step(4, 4, "Saving changes...").await;
 
-    if let Err(e) = manager.save() {
-        send_error(sender, format!("Failed to save: {}", e)).await;
+    let saved = if chunks.len() > 1 {
+        manager.save_without_backup()
+    } else {
+        manager.save()
+    };
+    if let Err(e) = saved {
+        match save_failure(&manager, e) {
+            Ok(denied) => tasks.complete(denied).await,
+            Err(message) => tasks.error(message).await,
+        }
         return;
     }
+    result.comparison = Some(manager.comparison());
+    record_snapshots(
+        auras
+            .iter()
+            .filter(|a| !result.failed.iter().any(|(id, _)| id == &a.id))
+            .flat_map(|a| std::iter::once(&a.data).chain(&a.child_data)),
+    );
 
     let tree = manager.get_aura_tree();
     let tree_count = tree.iter().map(|n| n.total_count()).sum();
 
-    let _ = sender
-        .send(Message::ImportUpdate(ImportUpdate::Complete {
+    tasks
+        .complete(ImportOutcome::Imported {
             result,
             tree,
             tree_count,
-        }))
+        })
         .await;
 }
 
-/// Run import with conflict resolutions (used by complete_import_with_resolutions_async)
-async fn run_import_with_resolutions(
+/// Outcome of a failed save: a denied one keeps the content for writing
+/// elsewhere, anything else is an error message
+fn save_failure(
+    manager: &SavedVariablesManager,
+    error: WeakAuraError,
+) -> Result<ImportOutcome, String> {
+    let message = error.to_string();
+    match error {
+        WeakAuraError::PermissionDenied(path) => Ok(ImportOutcome::SaveDenied(DeniedSave {
+            path,
+            message,
+            content: manager.generate_lua(),
+        })),
+        _ => Err(format!("Failed to save: {}", message)),
+    }
+}
+
+/// Move the imported top-level auras into the existing group chosen in the
+/// import confirmation
+fn move_into_target_group(manager: &mut SavedVariablesManager, group_id: &str, ids: &[String]) {
+    if manager.move_into_group(group_id, ids).is_none() {
+        warn!(group = %group_id, "Import target group not found, auras left at top level");
+    }
+}
+
+/// Record imported aura data as merge bases for future updates
+fn record_snapshots<'a>(values: impl Iterator<Item = &'a LuaValue>) {
+    let mut snapshots = SnapshotStore::load();
+    let recorded = values.filter(|v| snapshots.record(v)).count();
+    if recorded > 0 {
+        if let Err(e) = snapshots.save() {
+            warn!(error = %e, "Failed to save import snapshots");
+        }
+    }
+}
+
+/// A confirmed import with conflict resolutions, captured when the conflict
+/// dialog is accepted
+struct ResolvedImport {
     sv_path: PathBuf,
     conflict_result: crate::saved_variables::ConflictDetectionResult,
     resolutions: Vec<ConflictResolution>,
-    sender: &mut iced::futures::channel::mpsc::Sender<Message>,
+    target_group: Option<String>,
+    preserve_position: bool,
+    keep_user_config: bool,
+    allow_newer_db_version: bool,
+}
+
+/// Run import with conflict resolutions (used by complete_import_with_resolutions_async)
+async fn run_import_with_resolutions(
+    import: ResolvedImport,
+    tasks: &mut TaskSender<ImportOutcome>,
 ) {
+    let ResolvedImport {
+        sv_path,
+        conflict_result,
+        resolutions,
+        target_group,
+        preserve_position,
+        keep_user_config,
+        allow_newer_db_version,
+    } = import;
+
     // Phase 1: Loading SavedVariables (0-33%)
-    send_progress(sender, 1, 3, "Loading SavedVariables...").await;
+    tasks.step(1, 3, "Loading SavedVariables...").await;
 
-    let Some(mut manager) = load_manager(sv_path, sender).await else {
+    let Some(mut manager) = load_manager(sv_path, allow_newer_db_version, tasks).await else {
         return;
     };
+    manager.preserve_position = preserve_position;
+    manager.keep_user_config = keep_user_config;
 
     // Phase 2: Applying resolutions (33-66%)
-    send_progress(sender, 2, 3, "Applying conflict resolutions...").await;
-
-    let result = manager.apply_resolutions(&conflict_result, &resolutions);
+    tasks.step(2, 3, "Applying conflict resolutions...").await;
+
+    let mut result = manager.apply_resolutions(&conflict_result, &resolutions);
+    let skipped: HashSet<&str> = resolutions
+        .iter()
+        .filter(|r| r.action == ConflictAction::Skip)
+        .map(|r| r.aura_id.as_str())
+        .collect();
+    if let Some(group_id) = &target_group {
+        let ids: Vec<String> = conflict_result
+            .roots
+            .iter()
+            .filter(|id| !skipped.contains(id.as_str()))
+            .cloned()
+            .collect();
+        move_into_target_group(&mut manager, group_id, &ids);
+    }
 
     // Phase 3: Saving (66-100%)
-    send_progress(sender, 3, 3, "Saving changes...").await;
+    tasks.step(3, 3, "Saving changes...").await;
 
     if let Err(e) = manager.save() {
-        send_error(sender, format!("Failed to save: {}", e)).await;
+        match save_failure(&manager, e) {
+            Ok(denied) => tasks.complete(denied).await,
+            Err(message) => tasks.error(message).await,
+        }
         return;
     }
+    result.comparison = Some(manager.comparison());
+    record_snapshots(
+        conflict_result
+            .new_auras
+            .iter()
+            .map(|(_, data)| data)
+            .chain(
+                conflict_result
+                    .conflicts
+                    .iter()
+                    .filter(|c| !skipped.contains(c.aura_id.as_str()))
+                    .map(|c| &c.incoming),
+            ),
+    );
 
     let tree = manager.get_aura_tree();
     let tree_count = tree.iter().map(|n| n.total_count()).sum();
 
-    let _ = sender
-        .send(Message::ImportUpdate(ImportUpdate::Complete {
+    tasks
+        .complete(ImportOutcome::Imported {
             result,
             tree,
             tree_count,
-        }))
+        })
         .await;
 }
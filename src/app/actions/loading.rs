@@ -1,66 +1,446 @@
 //! Load auras from files, folders, clipboard, and text input.
 
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+use iced::futures::executor::block_on;
 use iced::futures::SinkExt;
+use iced::widget::text_editor;
 use iced::{stream, Task};
-use iced_toasts::{toast, ToastLevel};
+use tracing::warn;
+
+use crate::archive_source::{self, ArchiveEntry, ArchiveReader};
+use crate::companion::CompanionServer;
+use crate::decoder::WeakAuraDecoder;
+use crate::net::{self, HttpClient};
+use crate::pack::MediaPack;
+use crate::saved_variables::{format_duration, format_size, SavedVariablesManager};
+use crate::scan::{scan_folder, ScanOptions};
+use crate::tree_cache::ParsedTree;
+use crate::uri::InstanceListener;
+
+use super::super::notifications::Notification;
+use super::super::state::{LoadedAuras, LoadingUpdate, ScanOutcome, TaskKind};
+use super::super::{Message, WeakAuraImporter};
+use super::{
+    decode_auras, decode_auras_with_lines, line_region, notify_decode_results, run_task,
+    LineRegion, TaskSender,
+};
 
-use crate::saved_variables::SavedVariablesManager;
+/// Pause in typing after which the input is checked or auto-parsed
+const INPUT_IDLE_DELAY: Duration = Duration::from_millis(600);
 
-use super::super::state::LoadingUpdate;
-use super::super::{Message, WeakAuraImporter};
-use super::{collect_existing_ids, decode_auras_filtered, notify_decode_results};
+/// Bytes in a megabyte of the folder scan size limit
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// Time spent loading before an ETA is estimated from it
+const ETA_MIN_ELAPSED: Duration = Duration::from_secs(1);
 
 impl WeakAuraImporter {
-    /// Scan for SavedVariables files (synchronous, called during init)
+    /// Scan all registered WoW paths (plus the one being typed) for SavedVariables files
     pub(crate) fn scan_saved_variables_sync(&mut self) {
-        let path = PathBuf::from(&self.saved_vars.wow_path);
-        if path.exists() {
-            self.saved_vars.discovered_files = SavedVariablesManager::find_saved_variables(&path);
-            if !self.saved_vars.discovered_files.is_empty() {
-                self.toasts.push(
-                    toast(&format!(
-                        "Found {} SavedVariables file(s)",
-                        self.saved_vars.discovered_files.len()
-                    ))
-                    .level(ToastLevel::Info),
-                );
+        let mut roots = self.saved_vars.wow_paths.clone();
+        let typed = PathBuf::from(self.saved_vars.wow_path.trim());
+        if !self.saved_vars.wow_path.trim().is_empty() && typed.exists() && !roots.contains(&typed)
+        {
+            roots.push(typed);
+        }
+
+        self.saved_vars.discovered_files = SavedVariablesManager::find_saved_variables_in(&roots);
+        for info in &mut self.saved_vars.discovered_files {
+            if let Some(parsed) = self.saved_vars.tree_cache.get(&info.path) {
+                parsed.describe(info);
+            }
+        }
+        if !self.saved_vars.discovered_files.is_empty() {
+            self.notifications.push(Notification::info(format!(
+                "Found {} SavedVariables file(s)",
+                self.saved_vars.discovered_files.len()
+            )));
+        }
+    }
+
+    /// Show aura count and dbVersion of a parsed file in the discovery list
+    pub(crate) fn describe_discovered(&mut self, parsed: &ParsedTree) {
+        if let Some(info) = self
+            .saved_vars
+            .discovered_files
+            .iter_mut()
+            .find(|info| info.path == parsed.path)
+        {
+            parsed.describe(info);
+        }
+    }
+
+    /// Parse discovered SavedVariables files in the background, so selecting
+    /// one later does not have to wait for the parser
+    pub(crate) fn preparse_saved_variables(&mut self) -> Task<Message> {
+        let paths: Vec<PathBuf> = self
+            .saved_vars
+            .discovered_files
+            .iter()
+            .map(|info| info.path.clone())
+            .filter(|path| {
+                !self.saved_vars.preparsing.contains(path)
+                    && self.saved_vars.tree_cache.get(path).is_none()
+            })
+            .collect();
+        if paths.is_empty() {
+            return Task::none();
+        }
+        self.saved_vars.preparsing.extend(paths.iter().cloned());
+
+        let preparse = run_task(Message::ScanUpdate, |mut tasks| async move {
+            for path in paths {
+                let parse_path = path.clone();
+                let parsed = tokio::task::spawn_blocking(move || {
+                    ParsedTree::parse(&parse_path).map_err(|e| {
+                        warn!(path = %parse_path.display(), error = %e, "Pre-parse failed");
+                        e.to_string()
+                    })
+                })
+                .await
+                .unwrap_or_else(|e| Err(e.to_string()));
+                tasks
+                    .complete(ScanOutcome::Preparsed { path, parsed })
+                    .await;
             }
+        });
+        self.track_task(TaskKind::Preparsing, preparse)
+    }
+
+    /// Bookmark a WoW installation root, persist it and rescan
+    pub(crate) fn add_wow_path(&mut self, path: PathBuf) {
+        if path.as_os_str().is_empty() {
+            return;
         }
+        if !path.is_dir() {
+            self.notifications.push(
+                Notification::warning(format!("Not a directory: {}", path.display()))
+                    .title("Invalid Path"),
+            );
+            return;
+        }
+
+        if self.config.add_wow_path(path.clone()) {
+            self.save_config();
+        }
+        if !self.saved_vars.wow_paths.contains(&path) {
+            self.saved_vars.wow_paths.push(path);
+        }
+        self.scan_saved_variables_sync();
     }
 
-    /// Parse the input text for WeakAura strings (appends to existing list, skips duplicates)
-    pub(crate) fn parse_input(&mut self) {
-        let existing_ids = collect_existing_ids(&self.parsed_auras);
+    /// Remove a WoW installation root from the scanned set and bookmarks
+    pub(crate) fn remove_wow_path(&mut self, path: &Path) {
+        if self.config.remove_wow_path(path) {
+            self.save_config();
+        }
+        self.saved_vars.wow_paths.retain(|p| p != path);
+        if Path::new(self.saved_vars.wow_path.trim()) == path {
+            self.saved_vars.wow_path.clear();
+        }
+        self.scan_saved_variables_sync();
+    }
 
-        let (new_entries, added, duplicates, errors) =
-            decode_auras_filtered(&self.input_text, &existing_ids);
+    /// Persist the config, reporting failures as a toast
+    pub(crate) fn save_config(&mut self) {
+        if let Err(e) = self.config.save() {
+            self.notifications.push(
+                Notification::warning(format!("Failed to save settings: {}", e)).title("Settings"),
+            );
+        }
+    }
 
-        self.parsed_auras.extend(new_entries);
+    /// Parse the input text for WeakAura strings (appends to existing list,
+    /// applying the duplicate policy). Only text added after the last parse
+    /// is decoded, unless the parsed part was edited since. Links on their
+    /// own line are downloaded and decoded in the background.
+    pub(crate) fn parse_input(&mut self) -> Task<Message> {
+        let (unparsed, start) = self.unparsed_input();
+        let links = net::links_in(unparsed);
+        let (new_entries, errors, invalid_lines) = decode_auras_with_lines(unparsed, None);
+        self.parsed_input.clone_from(&self.input_text);
+        self.input_check.edited_at = None;
+        self.input_check.valid = 0;
+        self.input_check.invalid = 0;
+        self.mark_invalid_lines(start, invalid_lines);
+
+        let (added, duplicates) = self.add_parsed_entries(new_entries);
         self.selected_aura_index = None;
+        self.property_editor = Default::default();
+        self.raw_editor = Default::default();
+        self.context_menu = Default::default();
+        self.list_selection = Default::default();
+
+        // Input holding only links is reported once they are downloaded
+        if links.is_empty() || added > 0 || duplicates > 0 || !errors.is_empty() {
+            notify_decode_results(
+                &mut self.notifications,
+                added,
+                duplicates,
+                self.config.duplicate_policy,
+                &errors,
+                "input",
+            );
+        }
+        self.download_input_links(links)
+    }
+
+    /// Download the text behind links from the input (wago.io pages through
+    /// the Wago API) with the shared HTTP client
+    fn download_input_links(&mut self, links: Vec<String>) -> Task<Message> {
+        if links.is_empty() {
+            return Task::none();
+        }
+        self.status.message = format!("Downloading {} link(s)...", links.len());
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    links
+                        .into_iter()
+                        .map(|link| {
+                            let text = HttpClient::shared()
+                                .get_text(&net::download_url(&link))
+                                .map_err(|e| e.to_string());
+                            (link, text)
+                        })
+                        .collect()
+                })
+                .await
+                .unwrap_or_default()
+            },
+            Message::InputLinksDownloaded,
+        )
+    }
+
+    /// Decode the auras in downloaded link text
+    pub(crate) fn on_input_links_downloaded(
+        &mut self,
+        results: Vec<(String, Result<String, String>)>,
+    ) {
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+        for (link, text) in results {
+            match text {
+                Ok(text) => {
+                    let (link_entries, link_errors) = decode_auras(&text, None);
+                    if link_entries.is_empty() && link_errors.is_empty() {
+                        errors.push(format!("{}: no WeakAura strings found", link));
+                    }
+                    entries.extend(link_entries);
+                    errors.extend(link_errors);
+                }
+                Err(e) => errors.push(format!("{}: {}", link, e)),
+            }
+        }
+        self.status.message.clear();
+
+        let (added, duplicates) = self.add_parsed_entries(entries);
+        notify_decode_results(
+            &mut self.notifications,
+            added,
+            duplicates,
+            self.config.duplicate_policy,
+            &errors,
+            "downloaded",
+        );
+    }
+
+    /// Input text not parsed yet, and the line and column of the input it
+    /// starts at: what was added after the last parse, or all of it if the
+    /// parsed part was edited
+    fn unparsed_input(&self) -> (&str, (usize, usize)) {
+        match self.input_text.strip_prefix(self.parsed_input.as_str()) {
+            Some(rest) => {
+                let line = self.parsed_input.matches('\n').count();
+                let column =
+                    self.parsed_input.len() - self.parsed_input.rfind('\n').map_or(0, |i| i + 1);
+                (rest, (line, column))
+            }
+            None => (&self.input_text, (0, 0)),
+        }
+    }
+
+    /// Replace the highlighted strings from `start` (line and column of the
+    /// unparsed input) on with `regions`, which are relative to `start`
+    fn mark_invalid_lines(
+        &mut self,
+        (first_line, first_column): (usize, usize),
+        regions: Vec<LineRegion>,
+    ) {
+        let marked = &mut self.input_check.invalid_lines;
+        marked.retain(|&line, _| line < first_line);
+        for (line, range) in regions {
+            let shift = if line == 0 { first_column } else { 0 };
+            marked.insert(first_line + line, range.start + shift..range.end + shift);
+        }
+    }
+
+    /// Apply an edit of the paste box. Pasted text is parsed right away when
+    /// auto-parse is on; other edits are checked once typing pauses.
+    pub(crate) fn edit_input(&mut self, action: text_editor::Action) -> Task<Message> {
+        let pasted = matches!(
+            action,
+            text_editor::Action::Edit(text_editor::Edit::Paste(_))
+        );
+        let edited = action.is_edit();
+        self.input_editor.perform(action);
+        if !edited {
+            return Task::none();
+        }
+        self.input_text = self.input_editor.text();
+        if pasted && self.config.auto_parse {
+            return self.parse_input();
+        }
+        self.input_check.edited_at = Some(Instant::now());
+        Task::none()
+    }
+
+    /// Once typing has paused, count the valid and invalid strings not parsed
+    /// yet. With auto-parse on they are parsed instead, as long as they all
+    /// decode: a string still being typed is left alone.
+    pub(crate) fn check_input_when_idle(&mut self) -> Task<Message> {
+        if !self
+            .input_check
+            .edited_at
+            .is_some_and(|edited| edited.elapsed() >= INPUT_IDLE_DELAY)
+        {
+            return Task::none();
+        }
+        self.input_check.edited_at = None;
+
+        let (unparsed, start) = self.unparsed_input();
+        let decoded = WeakAuraDecoder::decode_multiple_with_spans(unparsed);
+        let invalid_lines: Vec<LineRegion> = decoded
+            .iter()
+            .filter(|d| d.result.is_err())
+            .map(|d| line_region(unparsed, d))
+            .collect();
+        let valid = decoded.len() - invalid_lines.len();
+        if self.config.auto_parse && valid > 0 && invalid_lines.is_empty() {
+            return self.parse_input();
+        }
+        self.input_check.valid = valid;
+        self.input_check.invalid = invalid_lines.len();
+        self.mark_invalid_lines(start, invalid_lines);
+        Task::none()
+    }
+
+    /// Start the browser companion endpoint and forward received strings to the app
+    pub(crate) fn start_companion(&mut self, addr: &str) -> Task<Message> {
+        let server = match CompanionServer::bind(addr) {
+            Ok(server) => server,
+            Err(e) => {
+                self.notifications.push(
+                    Notification::error(format!("Could not listen on {}: {}", addr, e))
+                        .title("Browser Companion"),
+                );
+                return Task::none();
+            }
+        };
+        if let Ok(local) = server.local_addr() {
+            self.status.message = format!("Receiving browser imports on http://{}", local);
+        }
+        receiver_task(move |on_received| server.run(on_received))
+    }
 
-        notify_decode_results(&mut self.toasts, added, duplicates, &errors, "input");
+    /// Accept `weakaura://` links forwarded by later launches of the app
+    pub(crate) fn listen_for_instances(&mut self) -> Task<Message> {
+        match InstanceListener::bind() {
+            Ok(listener) => receiver_task(move |on_received| listener.run(on_received)),
+            Err(e) => {
+                warn!(error = %e, "Another instance is already receiving links");
+                Task::none()
+            }
+        }
     }
 
-    /// Paste from clipboard
-    pub(crate) fn paste_from_clipboard(&mut self) {
+    /// Add strings received from the browser companion to the parsed list
+    pub(crate) fn receive_companion_strings(&mut self, text: &str) {
+        let (new_entries, errors) = decode_auras(text, None);
+
+        let (added, duplicates) = self.add_parsed_entries(new_entries);
+
+        notify_decode_results(
+            &mut self.notifications,
+            added,
+            duplicates,
+            self.config.duplicate_policy,
+            &errors,
+            "received",
+        );
+    }
+
+    /// Turn background clipboard collection on or off. Text already on the
+    /// clipboard is not collected.
+    pub(crate) fn set_collect_clipboard(&mut self, enabled: bool) {
+        self.config.collect_clipboard = enabled;
+        self.save_config();
+        self.clipboard_watch.last_text = if enabled {
+            self.clipboard.as_mut().and_then(|c| c.get_text().ok())
+        } else {
+            None
+        };
+        self.clipboard_watch.unseen = 0;
+    }
+
+    /// Add the auras of newly copied clipboard text to the parsed list.
+    /// Decode errors are ignored: most copied text is not an aura.
+    pub(crate) fn collect_from_clipboard(&mut self) {
+        let Some(text) = self.clipboard.as_mut().and_then(|c| c.get_text().ok()) else {
+            return;
+        };
+        if self.clipboard_watch.last_text.as_deref() == Some(text.as_str()) {
+            return;
+        }
+        // Only `!WA:` strings are decoded, not every copied text
+        if text.contains("!WA:") {
+            let (new_entries, _) = decode_auras(&text, None);
+            let (added, _) = self.add_parsed_entries(new_entries);
+            self.clipboard_watch.unseen += added;
+        }
+        self.clipboard_watch.last_text = Some(text);
+    }
+
+    /// Report the auras collected while the window was in the background
+    pub(crate) fn show_collected_from_clipboard(&mut self) {
+        let unseen = std::mem::take(&mut self.clipboard_watch.unseen);
+        if unseen > 0 {
+            self.notifications.push(
+                Notification::info(format!("{} aura(s) collected from the clipboard", unseen))
+                    .title("Clipboard"),
+            );
+        }
+    }
+
+    /// Paste from clipboard: appended to the input text on a new line, or
+    /// replacing it when `paste_replaces_input` is set
+    pub(crate) fn paste_from_clipboard(&mut self) -> Task<Message> {
         if let Some(clipboard) = &mut self.clipboard {
             match clipboard.get_text() {
                 Ok(text) => {
-                    self.input_text = text;
-                    self.parse_input();
+                    if self.config.paste_replaces_input {
+                        self.input_text = text;
+                        self.parsed_input.clear();
+                    } else {
+                        if !self.input_text.is_empty() && !self.input_text.ends_with('\n') {
+                            self.input_text.push('\n');
+                        }
+                        self.input_text.push_str(&text);
+                    }
+                    self.input_editor = text_editor::Content::with_text(&self.input_text);
+                    return self.parse_input();
                 }
                 Err(e) => {
-                    self.toasts.push(
-                        toast(&format!("Clipboard error: {}", e))
-                            .title("Clipboard Error")
-                            .level(ToastLevel::Error),
+                    self.notifications.push(
+                        Notification::error(format!("Clipboard error: {}", e))
+                            .title("Clipboard Error"),
                     );
                 }
             }
         }
+        Task::none()
     }
 
     /// Load from file dialog (async)
@@ -69,6 +449,7 @@ impl WeakAuraImporter {
             async {
                 rfd::AsyncFileDialog::new()
                     .add_filter("Text files", &["txt", "md"])
+                    .add_filter("ZIP archives", &["zip"])
                     .add_filter("All files", &["*"])
                     .pick_file()
                     .await
@@ -80,31 +461,70 @@ impl WeakAuraImporter {
 
     /// Load file content after selection (async)
     pub(crate) fn load_file_content_async(&mut self, path: PathBuf) -> Task<Message> {
-        let existing_ids = collect_existing_ids(&self.parsed_auras);
-
+        if archive_source::is_archive(&path) {
+            return self.load_archive_async(path);
+        }
         self.tasks.is_loading = true;
         self.tasks.loading_progress = 0.0;
         self.tasks.loading_message = format!("Loading {}...", path.display());
 
-        Task::perform(
+        let load = Task::perform(
             async move {
                 match tokio::fs::read_to_string(&path).await {
                     Ok(content) => {
-                        let (entries, added, duplicates, errors) =
-                            decode_auras_filtered(&content, &existing_ids);
-
-                        LoadingUpdate::Complete {
-                            entries,
-                            added,
-                            duplicates,
-                            errors,
-                        }
+                        let (entries, errors) = decode_auras(&content, Some(path.as_path()));
+
+                        LoadingUpdate::Complete(LoadedAuras { entries, errors })
                     }
                     Err(e) => LoadingUpdate::Error(format!("Failed to read file: {}", e)),
                 }
             },
             Message::LoadingUpdate,
-        )
+        );
+        self.track_task(TaskKind::Loading, load)
+    }
+
+    /// Load the text files inside a ZIP archive, reporting progress by
+    /// uncompressed bytes
+    fn load_archive_async(&mut self, path: PathBuf) -> Task<Message> {
+        self.tasks.is_loading = true;
+        self.tasks.loading_progress = 0.0;
+        self.tasks.loading_message = format!("Reading {}...", path.display());
+        let options = self.config.folder_scan.clone();
+
+        let load = run_task(Message::LoadingUpdate, move |mut tasks| async move {
+            let mut reader = match ArchiveReader::open(&path, &options) {
+                Ok(reader) => reader,
+                Err(e) => {
+                    tasks.error(format!("Failed to open archive: {}", e)).await;
+                    return;
+                }
+            };
+            let total = reader.total_bytes();
+            let (mut entries, mut errors) = (Vec::new(), Vec::new());
+            while let Some(entry) = reader.next() {
+                match entry {
+                    ArchiveEntry::Text { name, content } => {
+                        let source = reader.source_of(&name);
+                        let (decoded, e) = decode_auras(&content, Some(source.as_path()));
+                        entries.extend(decoded);
+                        errors.extend(e);
+                    }
+                    ArchiveEntry::Skipped { name, reason } => {
+                        errors.push(format!("{}: {}", name, reason));
+                    }
+                }
+                let done = reader.done_bytes();
+                let message = format!(
+                    "Reading archive ({} of {})",
+                    format_size(done),
+                    format_size(total)
+                );
+                tasks.progress(done, total, message).await;
+            }
+            tasks.complete(LoadedAuras { entries, errors }).await;
+        });
+        self.track_task(TaskKind::Loading, load)
     }
 
     /// Load from folder dialog (async)
@@ -120,28 +540,135 @@ impl WeakAuraImporter {
         )
     }
 
+    /// Show or hide the folder scan settings, filled from the config
+    pub(crate) fn toggle_scan_settings(&mut self) {
+        let settings = &mut self.scan_settings;
+        settings.show = !settings.show;
+        if !settings.show {
+            return;
+        }
+        let options = &self.config.folder_scan;
+        settings.extensions = options.extensions.join(", ");
+        settings.ignore = options.ignore.join(", ");
+        settings.max_size_mb = options
+            .max_file_size
+            .map(|bytes| format!("{}", bytes as f64 / BYTES_PER_MB))
+            .unwrap_or_default();
+        settings.max_depth = options
+            .max_depth
+            .map(|depth| depth.to_string())
+            .unwrap_or_default();
+        settings.error = None;
+    }
+
+    /// Save the folder scan settings as typed; left unsaved while they are
+    /// invalid
+    pub(crate) fn apply_scan_settings(&mut self) {
+        let settings = &mut self.scan_settings;
+        let split = |list: &str| -> Vec<String> {
+            list.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let max_file_size = match settings.max_size_mb.trim() {
+            "" => None,
+            size => match size.parse::<f64>() {
+                Ok(mb) if mb > 0.0 => Some((mb * BYTES_PER_MB) as u64),
+                _ => {
+                    settings.error = Some(format!("'{}' is not a size in MB", size));
+                    return;
+                }
+            },
+        };
+        let max_depth = match settings.max_depth.trim() {
+            "" => None,
+            depth => match depth.parse::<usize>() {
+                Ok(depth) => Some(depth),
+                Err(_) => {
+                    settings.error = Some(format!("'{}' is not a number of folders", depth));
+                    return;
+                }
+            },
+        };
+        let options = ScanOptions {
+            extensions: split(&settings.extensions),
+            ignore: split(&settings.ignore),
+            max_file_size,
+            follow_symlinks: self.config.folder_scan.follow_symlinks,
+            max_depth,
+        };
+        if let Err(e) = options.validate() {
+            settings.error = Some(e.to_string());
+            return;
+        }
+        settings.error = None;
+        self.config.folder_scan = options;
+        self.save_config();
+    }
+
     /// Load folder content after selection (async)
     pub(crate) fn load_folder_content_async(&mut self, folder_path: PathBuf) -> Task<Message> {
         // Scan folder synchronously (fast filesystem walk)
-        let file_paths = match scan_folder_recursive(&folder_path) {
-            Ok(paths) => paths,
+        let scan = match scan_folder(&folder_path, &self.config.folder_scan) {
+            Ok(scan) => scan,
             Err(e) => {
-                self.toasts.push(
-                    toast(&format!("Failed to scan folder: {}", e))
-                        .title("Folder Error")
-                        .level(ToastLevel::Error),
+                self.notifications.push(
+                    Notification::error(format!("Failed to scan folder: {}", e))
+                        .title("Folder Error"),
                 );
                 return Task::none();
             }
         };
 
+        if !scan.too_large.is_empty() {
+            self.notifications.push(
+                Notification::warning(format!(
+                    "{} file(s) over the size limit skipped",
+                    scan.too_large.len()
+                ))
+                .title("Folder Scan")
+                .details(
+                    scan.too_large
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect(),
+                ),
+            );
+        }
+        if !scan.errors.is_empty() {
+            self.notifications.push(
+                Notification::warning(format!(
+                    "{} path(s) in the folder could not be read",
+                    scan.errors.len()
+                ))
+                .title("Folder Scan")
+                .details(
+                    scan.errors
+                        .iter()
+                        .map(|(path, error)| format!("{}: {}", path.display(), error))
+                        .collect(),
+                ),
+            );
+        }
+        let file_paths = scan.files;
         if file_paths.is_empty() {
-            self.toasts
-                .push(toast("No supported files found in folder").level(ToastLevel::Warning));
+            self.notifications
+                .push(Notification::warning("No supported files found in folder"));
             return Task::none();
         }
 
-        let existing_ids = collect_existing_ids(&self.parsed_auras);
+        match MediaPack::open(&folder_path) {
+            Ok(Some(pack)) => {
+                self.media_packs.packs.retain(|p| p.root != pack.root);
+                self.media_packs.packs.push(pack);
+            }
+            Ok(None) => {}
+            Err(e) => self.notifications.push(
+                Notification::warning(format!("Pack media ignored: {}", e)).title("Folder Error"),
+            ),
+        }
 
         self.tasks.is_loading = true;
         self.tasks.loading_progress = 0.0;
@@ -149,97 +676,93 @@ impl WeakAuraImporter {
 
         let total_files = file_paths.len();
 
-        Task::run(
-            stream::channel(
-                100,
-                move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
-                    process_folder_files(file_paths, total_files, existing_ids, &mut sender).await;
-                },
-            ),
-            |msg| msg,
-        )
+        let load = run_task(Message::LoadingUpdate, move |mut tasks| async move {
+            process_folder_files(file_paths, total_files, &mut tasks).await;
+        });
+        self.track_task(TaskKind::Loading, load)
     }
 }
 
-/// Recursively scan a folder for supported files (.txt, .md, .lua)
-fn scan_folder_recursive(folder: &PathBuf) -> std::io::Result<Vec<PathBuf>> {
-    let mut files = Vec::new();
-    let supported_extensions = ["txt", "md", "lua"];
-
-    fn visit_dir(
-        dir: &PathBuf,
-        files: &mut Vec<PathBuf>,
-        extensions: &[&str],
-    ) -> std::io::Result<()> {
-        if dir.is_dir() {
-            for entry in std::fs::read_dir(dir)? {
-                let entry = entry?;
-                let path = entry.path();
-                if path.is_dir() {
-                    visit_dir(&path, files, extensions)?;
-                } else if let Some(ext) = path.extension() {
-                    if extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)) {
-                        files.push(path);
-                    }
-                }
-            }
-        }
-        Ok(())
-    }
+/// Run a blocking receiver (the companion endpoint or the instance
+/// listener), forwarding the text it hands over as messages. When the queue
+/// is full the receiver waits, so nothing it has accepted is dropped.
+fn receiver_task(run: impl FnOnce(&mut dyn FnMut(String)) + Send + 'static) -> Task<Message> {
+    Task::run(
+        stream::channel(
+            100,
+            move |mut sender: iced::futures::channel::mpsc::Sender<Message>| async move {
+                // The listener blocks, so keep it off the async runtime
+                let _ = tokio::task::spawn_blocking(move || {
+                    run(&mut |text| {
+                        let _ = block_on(sender.send(Message::CompanionReceived(text)));
+                    });
+                })
+                .await;
+            },
+        ),
+        |msg| msg,
+    )
+}
 
-    visit_dir(folder, &mut files, &supported_extensions)?;
-    Ok(files)
+/// Time left to process `total` bytes when `done` took `elapsed`; `None`
+/// until there is enough to go by
+fn estimate_remaining(elapsed: Duration, done: u64, total: u64) -> Option<Duration> {
+    if done == 0 || elapsed < ETA_MIN_ELAPSED {
+        return None;
+    }
+    let remaining = total.saturating_sub(done) as f64 / done as f64;
+    Some(elapsed.mul_f64(remaining))
 }
 
-/// Process multiple files from a folder with progress updates
+/// Process multiple files from a folder with progress updates. Progress and
+/// the ETA go by bytes, since a few big files can take most of the time.
 async fn process_folder_files(
     file_paths: Vec<PathBuf>,
     total_files: usize,
-    existing_ids: HashSet<String>,
-    sender: &mut iced::futures::channel::mpsc::Sender<Message>,
+    tasks: &mut TaskSender<LoadedAuras>,
 ) {
     let mut all_entries = Vec::new();
-    let mut total_added = 0;
-    let mut total_duplicates = 0;
     let mut all_errors = Vec::new();
-    let mut batch_ids = existing_ids;
 
-    for (idx, file_path) in file_paths.iter().enumerate() {
+    let mut sizes = Vec::with_capacity(file_paths.len());
+    for file_path in &file_paths {
+        let size = tokio::fs::metadata(file_path).await.map_or(0, |m| m.len());
+        sizes.push(size);
+    }
+    let total_bytes: u64 = sizes.iter().sum();
+    let started = Instant::now();
+    let mut done_bytes = 0;
+
+    for (idx, (file_path, size)) in file_paths.iter().zip(sizes).enumerate() {
         let current = idx + 1;
-        let _ = sender
-            .send(Message::LoadingUpdate(LoadingUpdate::Progress {
-                current,
-                total: total_files,
-                message: format!("Processing file {} of {}...", current, total_files),
-            }))
-            .await;
+        let mut message = format!(
+            "Processing file {} of {} ({} of {})",
+            current,
+            total_files,
+            format_size(done_bytes),
+            format_size(total_bytes)
+        );
+        if let Some(eta) = estimate_remaining(started.elapsed(), done_bytes, total_bytes) {
+            message.push_str(&format!(", about {} left", format_duration(eta)));
+        }
+        tasks.progress(done_bytes, total_bytes, message).await;
+        done_bytes += size;
 
         let content = match tokio::fs::read_to_string(&file_path).await {
             Ok(c) => c,
             Err(_) => continue,
         };
 
-        let (entries, added, duplicates, errors) = decode_auras_filtered(&content, &batch_ids);
-
-        // Add newly discovered IDs to batch set for cross-file dedup
-        for entry in &entries {
-            if let Some(ref id) = entry.validation.aura_id {
-                batch_ids.insert(id.clone());
-            }
-        }
-
+        // Auras in several files are left to the duplicate policy
+        let (entries, errors) = decode_auras(&content, Some(file_path.as_path()));
         all_entries.extend(entries);
-        total_added += added;
-        total_duplicates += duplicates;
         all_errors.extend(errors);
     }
 
-    let _ = sender
-        .send(Message::LoadingUpdate(LoadingUpdate::Complete {
+    tasks
+        .complete(LoadedAuras {
             entries: all_entries,
-            added: total_added,
-            duplicates: total_duplicates,
             errors: all_errors,
-        }))
+        })
         .await;
 }
@@ -1,35 +1,120 @@
 //! Business logic actions for WeakAuraImporter, organized by concern.
 //!
 //! This module handles all async operations and state updates for the GUI:
+//! - `annotations`: Aura tags and notes editing, persistence, and filtering
+//! - `context_menu`: Right-click menus of the parsed auras list and the existing auras tree
+//! - `duplicates`: Duplicate policy for auras loaded more than once
+//! - `field_sheet`: Exporting fields of existing auras to CSV and applying an edited sheet
+//! - `file_diff`: Comparing two whole SavedVariables files
 //! - `handlers`: Message update handlers for async task results
 //! - `import`: Import auras to SavedVariables (with conflict resolution)
 //! - `loading`: Load auras from files, folders, clipboard, and text input
+//! - `permissions`: Write probe of the selected file, and restarting elevated or saving elsewhere after a refused save
+//! - `problems`: Problems panel (session warnings and errors)
+//! - `properties`: Property editor for simple fields of a parsed aura
+//! - `raw_editor`: Lua/JSON editor for the decoded data of a parsed aura
+//! - `references`: Find-references search over the selected SavedVariables file
+//! - `recovery`: Crash report context, recovery after a crash, backup comparison, and repair of damaged files
 //! - `removal`: Remove auras and scan SavedVariables
+//! - `scripts`: Scripts dialog, running Rhai scripts against the selected SavedVariables file
+//! - `selection`: Bulk selection via selection rules and shift/ctrl-clicks in both lists
+//! - `session`: Auto-save of the loaded auras and restoring them on launch
+//! - `side_window`: Second window showing another SavedVariables file
+//! - `snippets`: Copying aura data to the clipboard as JSON or Lua snippets
+//! - `staging`: Staging area of auras set aside for import
+//! - `table`: Status filtering, column sorting and resizing of the parsed auras table
+//! - `tasks`: Running background jobs as streams of events with coalesced progress
+//! - `templates`: Saving aura templates and generating auras from parameter files
+//! - `transforms`: Ordering and settings of the transforms run before import
 
+mod annotations;
+mod context_menu;
+mod duplicates;
+mod field_sheet;
+mod file_diff;
 mod handlers;
 mod import;
 mod loading;
+mod permissions;
+mod problems;
+mod properties;
+mod raw_editor;
+mod recovery;
+mod references;
 mod removal;
+mod scripts;
+mod selection;
+mod session;
+mod side_window;
+mod snippets;
+mod staging;
+mod table;
+mod tasks;
+mod templates;
+mod transforms;
 
 use std::collections::HashSet;
+use std::ops::Range;
+use std::path::Path;
 
-use iced_toasts::{toast, ToastContainer, ToastLevel};
-
-use crate::decoder::{ValidationResult, WeakAuraDecoder};
+use crate::decoder::{DecodedString, WeakAuraDecoder};
+use crate::duplicates::DuplicatePolicy;
 
+use super::notifications::{Notification, Notifications};
 use super::state::ParsedAuraEntry;
-use super::Message;
 
 // Re-export all impl blocks from submodules for the parent module
 // The wildcard re-exports make all `impl WeakAuraImporter` methods available
 #[allow(unused_imports)]
+pub(crate) use annotations::*;
+#[allow(unused_imports)]
+pub(crate) use context_menu::*;
+#[allow(unused_imports)]
+pub(crate) use duplicates::*;
+#[allow(unused_imports)]
+pub(crate) use field_sheet::*;
+#[allow(unused_imports)]
+pub(crate) use file_diff::*;
+#[allow(unused_imports)]
 pub(crate) use handlers::*;
 #[allow(unused_imports)]
 pub(crate) use import::*;
 #[allow(unused_imports)]
 pub(crate) use loading::*;
 #[allow(unused_imports)]
+pub(crate) use permissions::*;
+#[allow(unused_imports)]
+pub(crate) use problems::*;
+#[allow(unused_imports)]
+pub(crate) use properties::*;
+#[allow(unused_imports)]
+pub(crate) use raw_editor::*;
+#[allow(unused_imports)]
+pub(crate) use recovery::*;
+#[allow(unused_imports)]
+pub(crate) use references::*;
+#[allow(unused_imports)]
 pub(crate) use removal::*;
+#[allow(unused_imports)]
+pub(crate) use scripts::*;
+#[allow(unused_imports)]
+pub(crate) use selection::*;
+#[allow(unused_imports)]
+pub(crate) use session::*;
+#[allow(unused_imports)]
+pub(crate) use side_window::*;
+#[allow(unused_imports)]
+pub(crate) use snippets::*;
+#[allow(unused_imports)]
+pub(crate) use staging::*;
+#[allow(unused_imports)]
+pub(crate) use table::*;
+#[allow(unused_imports)]
+pub(crate) use tasks::*;
+#[allow(unused_imports)]
+pub(crate) use templates::*;
+#[allow(unused_imports)]
+pub(crate) use transforms::*;
 
 /// Collect the set of aura IDs already present in the parsed auras list.
 pub(crate) fn collect_existing_ids(parsed_auras: &[ParsedAuraEntry]) -> HashSet<String> {
@@ -40,78 +125,97 @@ pub(crate) fn collect_existing_ids(parsed_auras: &[ParsedAuraEntry]) -> HashSet<
         .collect()
 }
 
-/// Decode auras from content, filtering out duplicates already in `existing_ids`.
-/// Returns `(entries, added, duplicates, errors)` where errors is a list of error messages.
-/// Invalid entries are NOT added to the entries list.
-pub(crate) fn decode_auras_filtered(
+/// Decode auras from content. Returns `(entries, errors)` where errors is a
+/// list of error messages; invalid entries are NOT added to the entries list.
+/// Duplicates are left to `add_parsed_entries`.
+/// `source` is the file the content was read from (`None` for pasted input).
+pub(crate) fn decode_auras(
     content: &str,
-    existing_ids: &HashSet<String>,
-) -> (Vec<ParsedAuraEntry>, usize, usize, Vec<String>) {
-    let results = WeakAuraDecoder::decode_multiple(content);
+    source: Option<&Path>,
+) -> (Vec<ParsedAuraEntry>, Vec<String>) {
+    let (entries, errors, _) = decode_auras_with_lines(content, source);
+    (entries, errors)
+}
+
+/// Zero-based line of a string and its byte range within that line
+pub(crate) type LineRegion = (usize, Range<usize>);
+
+/// Like [`decode_auras`], also returning where each string that failed to
+/// decode is: its zero-based line and its byte range within that line
+pub(crate) fn decode_auras_with_lines(
+    content: &str,
+    source: Option<&Path>,
+) -> (Vec<ParsedAuraEntry>, Vec<String>, Vec<LineRegion>) {
+    let results = WeakAuraDecoder::decode_multiple_with_spans(content);
     let mut entries = Vec::new();
-    let mut added = 0;
-    let mut duplicates = 0;
     let mut errors = Vec::new();
+    let mut invalid_lines = Vec::new();
 
-    for result in results {
-        match result {
-            Ok(aura) => {
-                if existing_ids.contains(&aura.id) {
-                    duplicates += 1;
-                    continue;
-                }
-                added += 1;
-                let validation = ValidationResult {
-                    is_valid: true,
-                    aura_id: Some(aura.id.clone()),
-                    is_group: aura.is_group,
-                    child_count: aura.children.len(),
-                    error: None,
-                };
-                entries.push(ParsedAuraEntry {
-                    validation,
-                    aura: Some(aura),
-                    selected: false,
-                });
-            }
+    for decoded in results {
+        let region = line_region(content, &decoded);
+        match decoded.result {
+            Ok(aura) => entries.push(ParsedAuraEntry::new(aura, source.map(Path::to_path_buf))),
             Err(e) => {
                 errors.push(e.to_string());
+                invalid_lines.push(region);
             }
         }
     }
 
-    (entries, added, duplicates, errors)
+    (entries, errors, invalid_lines)
 }
 
-/// Show toast notifications for decode results.
-/// Consolidates the repeated pattern of notifying users about added/duplicates/errors.
+/// Where in `content` a string was found, by line
+pub(crate) fn line_region(content: &str, decoded: &DecodedString) -> LineRegion {
+    let line_start = content[..decoded.span.start]
+        .rfind('\n')
+        .map_or(0, |i| i + 1);
+    (
+        decoded.line,
+        decoded.span.start - line_start..decoded.span.end - line_start,
+    )
+}
+
+/// Notify the user about decode results.
+/// Invalid strings are reported as one notification listing every error.
+/// `duplicates` counts the copies `policy` dropped or, with
+/// [`DuplicatePolicy::Ask`], left in the list to choose from.
 pub(crate) fn notify_decode_results(
-    toasts: &mut ToastContainer<'static, Message>,
+    notifications: &mut Notifications,
     added: usize,
     duplicates: usize,
+    policy: DuplicatePolicy,
     errors: &[String],
     context: &str, // e.g., "added", "loaded"
 ) {
-    // Show error toasts for invalid strings
-    for error in errors {
-        toasts.push(
-            toast(error)
-                .title("Invalid WeakAura")
-                .level(ToastLevel::Error),
-        );
+    let duplicates_text = match policy {
+        DuplicatePolicy::KeepFirst => format!("{} duplicate(s) skipped", duplicates),
+        DuplicatePolicy::KeepLast => format!("{} duplicate(s) replaced", duplicates),
+        DuplicatePolicy::Ask => format!("{} duplicate(s) to choose from", duplicates),
+    };
+
+    match errors {
+        [] => {}
+        [error] => notifications.push(Notification::error(error).title("Invalid WeakAura")),
+        _ => notifications.push(
+            Notification::error(format!("{} string(s) could not be decoded", errors.len()))
+                .title("Invalid WeakAuras")
+                .details(errors.to_vec()),
+        ),
     }
 
     if added == 0 && duplicates == 0 && errors.is_empty() {
-        toasts.push(
-            toast(&format!("No WeakAura strings found in {}", context)).level(ToastLevel::Warning),
-        );
+        notifications.push(Notification::warning(format!(
+            "No WeakAura strings found in {}",
+            context
+        )));
     } else if added > 0 {
         let mut msg = format!("{} aura(s) {}", added, context);
         if duplicates > 0 {
-            msg.push_str(&format!(", {} duplicate(s) skipped", duplicates));
+            msg.push_str(&format!(", {}", duplicates_text));
         }
-        toasts.push(toast(&msg).title("Success").level(ToastLevel::Success));
+        notifications.push(Notification::success(msg).title("Success"));
     } else if duplicates > 0 && errors.is_empty() {
-        toasts.push(toast(&format!("{} duplicate(s) skipped", duplicates)).level(ToastLevel::Info));
+        notifications.push(Notification::info(duplicates_text));
     }
 }
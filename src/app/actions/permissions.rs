@@ -0,0 +1,102 @@
+//! Files the operating system does not let us write: probing the selected
+//! file up front, and after a refused save, restarting as administrator or
+//! writing the result to another file.
+
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::fs_util;
+
+use super::super::notifications::Notification;
+use super::super::state::DeniedSave;
+use super::super::{Message, WeakAuraImporter};
+
+impl WeakAuraImporter {
+    /// Probe whether the selected file can be written, so the sidebar warns
+    /// before an import fails at its last step
+    pub(crate) fn refresh_writable(&mut self) {
+        self.saved_vars.read_only = self
+            .saved_vars
+            .selected_path
+            .as_deref()
+            .and_then(|path| fs_util::check_writable(path).err())
+            .map(|e| e.to_string());
+    }
+
+    /// Show the explanation of a save that was denied
+    pub(crate) fn show_denied_save(&mut self, denied: DeniedSave) {
+        self.status.message = denied.message.clone();
+        self.status.is_error = true;
+        self.saved_vars.read_only = Some(denied.message.clone());
+        self.status.denied_save = Some(denied);
+    }
+
+    /// Start this tool again as administrator and close this instance
+    pub(crate) fn restart_elevated(&mut self) -> Task<Message> {
+        match fs_util::relaunch_elevated() {
+            Ok(()) => iced::exit(),
+            Err(e) => {
+                self.notifications
+                    .push(Notification::error(e.to_string()).title("Permission Denied"));
+                Task::none()
+            }
+        }
+    }
+
+    /// Write what the denied save would have written to a file chosen by the user
+    pub(crate) fn save_denied_elsewhere_async(&mut self) -> Task<Message> {
+        let Some(denied) = &self.status.denied_save else {
+            return Task::none();
+        };
+        let file_name = denied
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "WeakAuras.lua".to_string());
+        let content = denied.content.clone();
+
+        Task::perform(
+            async move {
+                let Some(path) = rfd::AsyncFileDialog::new()
+                    .add_filter("Lua files", &["lua"])
+                    .set_file_name(file_name)
+                    .save_file()
+                    .await
+                    .map(|h| h.path().to_path_buf())
+                else {
+                    return Ok(None);
+                };
+                tokio::task::spawn_blocking(move || {
+                    fs_util::write(&path, content).map_err(|e| e.to_string())?;
+                    Ok(Some(path))
+                })
+                .await
+                .map_err(|e| format!("Task failed: {}", e))?
+            },
+            Message::DeniedSaveWritten,
+        )
+    }
+
+    /// Report where the denied save was written instead
+    pub(crate) fn finish_denied_save(&mut self, result: Result<Option<PathBuf>, String>) {
+        match result {
+            Ok(None) => {}
+            Ok(Some(path)) => {
+                self.status.denied_save = None;
+                self.status.message = format!("Saved to {}", path.display());
+                self.status.is_error = false;
+                self.notifications.push(
+                    Notification::success(format!(
+                        "Saved to {}. Copy it over the original once WoW is closed.",
+                        path.display()
+                    ))
+                    .title("Saved Elsewhere"),
+                );
+            }
+            Err(e) => self
+                .notifications
+                .push(Notification::error(e).title("Permission Denied")),
+        }
+    }
+}
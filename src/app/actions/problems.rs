@@ -0,0 +1,29 @@
+//! Problems panel: copying the session's warnings and errors.
+
+use super::super::notifications::Notification;
+use super::super::WeakAuraImporter;
+
+impl WeakAuraImporter {
+    /// Copy every problem of the session to the clipboard as plain text
+    pub(crate) fn copy_problems(&mut self) {
+        let report = self.notifications.problems_text();
+        if report.is_empty() {
+            return;
+        }
+        match self.clipboard.as_mut().map(|c| c.set_text(report)) {
+            Some(Ok(())) => {
+                self.notifications
+                    .push(Notification::info("Problems copied to clipboard"));
+            }
+            Some(Err(e)) => {
+                self.notifications.push(
+                    Notification::error(format!("Clipboard error: {}", e)).title("Clipboard Error"),
+                );
+            }
+            None => {
+                self.notifications
+                    .push(Notification::error("Clipboard unavailable").title("Clipboard Error"));
+            }
+        }
+    }
+}
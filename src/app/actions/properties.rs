@@ -0,0 +1,124 @@
+//! Property editor for simple fields of a parsed aura before import.
+
+use crate::model::AuraProperties;
+
+use super::super::notifications::Notification;
+use super::super::state::PropertyEditorState;
+use super::super::WeakAuraImporter;
+use super::collect_existing_ids;
+
+impl WeakAuraImporter {
+    /// Open the property editor with the current fields of a parsed aura
+    pub(crate) fn open_property_editor(&mut self, idx: usize) {
+        let Some(aura) = self.parsed_auras.get(idx).and_then(|e| e.aura.as_ref()) else {
+            return;
+        };
+        let properties = match AuraProperties::from_aura(aura) {
+            Ok(properties) => properties,
+            Err(e) => {
+                self.notifications.push(
+                    Notification::error(format!("Cannot edit '{}': {}", aura.id, e))
+                        .title("Edit Properties"),
+                );
+                return;
+            }
+        };
+        let join = |items: Vec<String>| items.join(", ");
+        self.raw_editor = Default::default();
+        self.property_editor = PropertyEditorState {
+            index: Some(idx),
+            id: properties.id,
+            x_offset: properties.x_offset.to_string(),
+            y_offset: properties.y_offset.to_string(),
+            classes: join(properties.classes),
+            specs: join(properties.specs.iter().map(u32::to_string).collect()),
+            desc: properties.desc,
+            error: None,
+        };
+    }
+
+    /// Parse and validate the editor fields and write them into the aura.
+    /// The editor stays open with the error when they are invalid.
+    pub(crate) fn apply_aura_properties(&mut self) {
+        let Some(idx) = self.property_editor.index else {
+            return;
+        };
+        let other_ids = {
+            let mut ids = collect_existing_ids(&self.parsed_auras);
+            if let Some(id) = self
+                .parsed_auras
+                .get(idx)
+                .and_then(|e| e.validation.aura_id.as_ref())
+            {
+                ids.remove(id);
+            }
+            ids
+        };
+        let result = self.parse_property_editor().and_then(|properties| {
+            if other_ids.contains(&properties.id) {
+                return Err(format!(
+                    "another parsed aura is already called '{}'",
+                    properties.id
+                ));
+            }
+            let aura = self
+                .parsed_auras
+                .get_mut(idx)
+                .and_then(|e| e.aura.as_mut())
+                .ok_or_else(|| "the aura is no longer loaded".to_string())?;
+            properties.apply(aura).map_err(|e| e.to_string())?;
+            Ok(properties.id)
+        });
+
+        match result {
+            Ok(id) => {
+                if let Some(entry) = self.parsed_auras.get_mut(idx) {
+                    entry.validation.aura_id = Some(id.clone());
+                }
+                self.property_editor = PropertyEditorState::default();
+                self.notifications.push(
+                    Notification::success(format!("Updated the properties of '{}'", id))
+                        .title("Edit Properties"),
+                );
+            }
+            Err(e) => self.property_editor.error = Some(e),
+        }
+    }
+
+    fn parse_property_editor(&self) -> Result<AuraProperties, String> {
+        let editor = &self.property_editor;
+        let offset = |name: &str, value: &str| {
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(0.0);
+            }
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("{} offset '{}' is not a number", name, value))
+        };
+        let list = |value: &str| -> Vec<String> {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let specs = list(&editor.specs)
+            .iter()
+            .map(|spec| {
+                spec.parse::<u32>()
+                    .map_err(|_| format!("specialization '{}' is not an ID", spec))
+            })
+            .collect::<Result<Vec<u32>, String>>()?;
+
+        Ok(AuraProperties {
+            id: editor.id.clone(),
+            x_offset: offset("X", &editor.x_offset)?,
+            y_offset: offset("Y", &editor.y_offset)?,
+            classes: list(&editor.classes.to_uppercase()),
+            specs,
+            desc: editor.desc.clone(),
+        })
+    }
+}
@@ -0,0 +1,99 @@
+//! Lua/JSON editor for the decoded data of a parsed aura.
+
+use iced::widget::text_editor;
+
+use crate::decoder::AuraTextFormat;
+
+use super::super::notifications::Notification;
+use super::super::state::RawEditorState;
+use super::super::WeakAuraImporter;
+use super::collect_existing_ids;
+
+impl WeakAuraImporter {
+    /// Open the raw editor with a parsed aura's data in the last used format
+    pub(crate) fn open_raw_editor(&mut self, idx: usize) {
+        let Some(aura) = self.parsed_auras.get(idx).and_then(|e| e.aura.as_ref()) else {
+            return;
+        };
+        let format = self.raw_editor.format;
+        self.property_editor = Default::default();
+        self.raw_editor = RawEditorState {
+            index: Some(idx),
+            format,
+            content: text_editor::Content::with_text(&aura.data_text(format)),
+            error: None,
+        };
+    }
+
+    /// Switch the editor to another format, converting the edited text. The
+    /// format stays when the text doesn't parse.
+    pub(crate) fn set_raw_editor_format(&mut self, format: AuraTextFormat) {
+        let editor = &mut self.raw_editor;
+        if editor.format == format {
+            return;
+        }
+        let Some(mut aura) = editor
+            .index
+            .and_then(|idx| self.parsed_auras.get(idx))
+            .and_then(|e| e.aura.clone())
+        else {
+            editor.format = format;
+            return;
+        };
+        match aura.set_data_text(&editor.content.text(), editor.format) {
+            Ok(()) => {
+                editor.content = text_editor::Content::with_text(&aura.data_text(format));
+                editor.format = format;
+                editor.error = None;
+            }
+            Err(e) => editor.error = Some(e.to_string()),
+        }
+    }
+
+    /// Parse and validate the edited text and make it the aura's data. The
+    /// editor stays open with the error when the text is invalid.
+    pub(crate) fn apply_raw_data(&mut self) {
+        let Some(idx) = self.raw_editor.index else {
+            return;
+        };
+        let Some(entry) = self.parsed_auras.get(idx) else {
+            self.raw_editor = RawEditorState::default();
+            return;
+        };
+        let Some(mut aura) = entry.aura.clone() else {
+            return;
+        };
+        if let Err(e) = aura.set_data_text(&self.raw_editor.content.text(), self.raw_editor.format)
+        {
+            self.raw_editor.error = Some(e.to_string());
+            return;
+        }
+
+        let mut other_ids = collect_existing_ids(&self.parsed_auras);
+        if let Some(id) = &entry.validation.aura_id {
+            other_ids.remove(id);
+        }
+        if other_ids.contains(&aura.id) {
+            self.raw_editor.error = Some(format!(
+                "another parsed aura is already called '{}'",
+                aura.id
+            ));
+            return;
+        }
+
+        let id = aura.id.clone();
+        if let Some(entry) = self.parsed_auras.get_mut(idx) {
+            entry.validation.aura_id = Some(id.clone());
+            entry.validation.is_group = aura.is_group;
+            entry.validation.child_count = aura.children.len();
+            entry.aura = Some(aura);
+        }
+        self.raw_editor = RawEditorState {
+            format: self.raw_editor.format,
+            ..RawEditorState::default()
+        };
+        self.notifications.push(
+            Notification::success(format!("Replaced the data of '{}'", id)).title("Edit Raw Data"),
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Crash reports and backups: app state summary, recovery after a crash,
+//! comparing auras with the backup of the last save, and repairing damaged
+//! files or restoring them from a parsable copy.
+
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::error::WeakAuraError;
+use crate::repair::{FallbackCandidate, RepairReport};
+use crate::saved_variables::{backup_path, SavedVariablesManager};
+
+use super::super::notifications::Notification;
+use super::super::{Message, WeakAuraImporter};
+
+impl WeakAuraImporter {
+    /// What the app is doing, for crash reports. No paths or aura names.
+    pub(crate) fn crash_summary(&self) -> String {
+        let flavor = self
+            .saved_vars
+            .selected_path
+            .as_ref()
+            .and_then(|path| {
+                self.saved_vars
+                    .discovered_files
+                    .iter()
+                    .find(|info| &info.path == path)
+            })
+            .map_or_else(|| "-".to_string(), |info| info.pretty_flavor());
+        format!(
+            "parsed auras: {} ({} selected)\n\
+             existing auras: {}\n\
+             SavedVariables flavor: {}\n\
+             loading: {}, importing: {}, removing: {}, scanning: {}\n\
+             conflict dialog open: {}",
+            self.parsed_auras.len(),
+            self.parsed_auras.iter().filter(|e| e.selected).count(),
+            self.saved_vars.auras_count,
+            flavor,
+            self.tasks.is_loading,
+            self.tasks.is_importing,
+            self.tasks.is_removing,
+            self.tasks.is_scanning,
+            self.ui.show_conflict_dialog,
+        )
+    }
+
+    /// Restore the SavedVariables file that was being saved during the last
+    /// crash from its backup, keeping the file it replaces
+    pub(crate) fn restore_crash_backup(&mut self) {
+        let Some(crash) = self.status.crash_recovery.take() else {
+            return;
+        };
+        let Some(path) = crash.saved_variables else {
+            return;
+        };
+        match SavedVariablesManager::restore_backup(&path) {
+            Ok(kept) => {
+                self.saved_vars.tree_cache = Default::default();
+                let mut notification =
+                    Notification::success(format!("Restored {} from its backup", path.display()))
+                        .title("Crash Recovery");
+                if let Some(kept) = kept {
+                    notification = notification.details(vec![format!(
+                        "The file it replaced was kept as {}",
+                        kept.display()
+                    )]);
+                }
+                self.notifications.push(notification);
+            }
+            Err(e) => self.notifications.push(
+                Notification::error(format!("Could not restore the backup: {}", e))
+                    .title("Crash Recovery"),
+            ),
+        }
+    }
+
+    /// Check whether the selected file has a backup to compare with
+    pub(crate) fn refresh_has_backup(&mut self) {
+        self.saved_vars.has_backup = self
+            .saved_vars
+            .selected_path
+            .as_deref()
+            .is_some_and(|path| backup_path(path).exists());
+    }
+
+    /// Load the aura as it is now and in the file's backup, for the
+    /// comparison dialog
+    pub(crate) fn compare_with_backup_async(&mut self, id: String) -> Task<Message> {
+        let Some(sv_path) = self.saved_vars.selected_path.clone() else {
+            return Task::none();
+        };
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut manager = SavedVariablesManager::new(sv_path);
+                    manager.load()?;
+                    manager.compare_with_backup(&id)
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result.map_err(|e| e.to_string()))
+            },
+            Message::BackupComparisonLoaded,
+        )
+    }
+
+    /// Salvage the auras of the selected file up to its damage and write
+    /// them back, keeping the damaged file beside it
+    pub(crate) fn repair_saved_variables_async(&mut self) -> Task<Message> {
+        let Some(sv_path) = self.saved_vars.selected_path.clone() else {
+            return Task::none();
+        };
+        let allow_newer_db_version = self.saved_vars.allow_newer_db_version;
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut manager = SavedVariablesManager::new(sv_path);
+                    manager.allow_newer_db_version = allow_newer_db_version;
+                    let Some(report) = manager.load_salvaging()? else {
+                        return Ok(None);
+                    };
+                    let damaged = manager.write_repaired()?;
+                    Ok::<_, WeakAuraError>(Some((report, damaged)))
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result.map_err(|e| e.to_string()))
+            },
+            Message::SavedVariablesRepaired,
+        )
+    }
+
+    /// Replace the selected file with the newest copy of it that parses
+    pub(crate) fn restore_fallback_async(&mut self) -> Task<Message> {
+        let (Some(sv_path), Some(candidate)) = (
+            self.saved_vars.selected_path.clone(),
+            self.saved_vars.fallbacks.first().cloned(),
+        ) else {
+            return Task::none();
+        };
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut manager = SavedVariablesManager::new(sv_path);
+                    manager.restore_fallback(&candidate)?;
+                    Ok::<_, WeakAuraError>(candidate)
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result.map_err(|e| e.to_string()))
+            },
+            Message::FallbackRestored,
+        )
+    }
+
+    /// Record which copy the selected file was restored from and load it
+    pub(crate) fn finish_restore_fallback(
+        &mut self,
+        result: Result<FallbackCandidate, String>,
+    ) -> Task<Message> {
+        match result {
+            Ok(candidate) => {
+                self.notifications.push(
+                    Notification::success(format!("Restored from {}", candidate.describe()))
+                        .title("Restore"),
+                );
+                self.saved_vars.restored_from = Some(candidate);
+                self.load_existing_auras_async()
+            }
+            Err(e) => {
+                self.notifications.push(
+                    Notification::error(format!("Could not restore the file: {}", e))
+                        .title("Restore"),
+                );
+                Task::none()
+            }
+        }
+    }
+
+    /// Report what the repair kept and lost, and load the repaired file
+    pub(crate) fn finish_repair(
+        &mut self,
+        result: Result<Option<(RepairReport, PathBuf)>, String>,
+    ) -> Task<Message> {
+        match result {
+            Ok(Some((report, damaged))) => {
+                let mut details = vec![
+                    format!("Damage at byte {}: {}", report.damaged_at, report.error),
+                    format!("Damaged original kept as {}", damaged.display()),
+                ];
+                details.extend(report.lost.iter().map(|id| format!("Lost: {}", id)));
+                details.extend(
+                    report
+                        .lost_keys
+                        .iter()
+                        .map(|key| format!("Lost setting: {}", key)),
+                );
+                self.notifications.push(
+                    Notification::warning(report.summary())
+                        .title("Repair")
+                        .details(details),
+                );
+                self.load_existing_auras_async()
+            }
+            Ok(None) => {
+                self.notifications.push(
+                    Notification::info("The file is not damaged; nothing to repair")
+                        .title("Repair"),
+                );
+                self.load_existing_auras_async()
+            }
+            Err(e) => {
+                self.notifications.push(
+                    Notification::error(format!("Could not repair the file: {}", e))
+                        .title("Repair"),
+                );
+                Task::none()
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Find-references search over the selected SavedVariables file.
+
+use iced::Task;
+
+use crate::references::{find_references, Reference};
+use crate::saved_variables::SavedVariablesManager;
+
+use super::super::notifications::Notification;
+use super::super::{Message, WeakAuraImporter};
+
+impl WeakAuraImporter {
+    /// Search every stored aura of the selected file for the query in the
+    /// background
+    pub(crate) fn search_references_async(&mut self) -> Task<Message> {
+        let query = self.references.query.trim().to_string();
+        let Some(path) = self.saved_vars.selected_path.clone() else {
+            return Task::none();
+        };
+        if query.is_empty() || self.references.is_searching {
+            return Task::none();
+        }
+
+        self.references.is_searching = true;
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut manager = SavedVariablesManager::new(path);
+                    manager.load().map_err(|e| e.to_string())?;
+                    Ok(find_references(&manager.displays, &query))
+                })
+                .await
+                .map_err(|e| format!("Task failed: {}", e))?
+            },
+            Message::ReferencesFound,
+        )
+    }
+
+    pub(crate) fn handle_references_found(&mut self, result: Result<Vec<Reference>, String>) {
+        self.references.is_searching = false;
+        match result {
+            Ok(references) => self.references.results = Some(references),
+            Err(e) => self.notifications.push(
+                Notification::error(format!("Search failed: {}", e)).title("Find References"),
+            ),
+        }
+    }
+}
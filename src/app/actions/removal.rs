@@ -1,12 +1,17 @@
 //! Remove auras from SavedVariables and scan existing auras.
 
-use iced::Task;
-use iced_toasts::{toast, ToastLevel};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
+use crate::discovery;
 use crate::error::WeakAuraError;
-use crate::saved_variables::SavedVariablesManager;
+use crate::repair::find_fallbacks;
+use crate::saved_variables::{topmost_selected, SavedVariablesManager, MAX_SUPPORTED_DB_VERSION};
+use crate::tree_cache::ParsedTree;
+use iced::Task;
 
-use super::super::state::{RemovalUpdate, ScanUpdate};
+use super::super::notifications::Notification;
+use super::super::state::{RemovalUpdate, RemovedAuras, ScanOutcome, ScanUpdate, TaskKind};
 use super::super::{Message, WeakAuraImporter};
 
 impl WeakAuraImporter {
@@ -16,55 +21,124 @@ impl WeakAuraImporter {
             return Task::none();
         };
 
+        self.saved_vars.db_version = None;
+        self.saved_vars.allow_newer_db_version = false;
+        self.saved_vars.load_failed = false;
+        self.saved_vars.fallbacks.clear();
+        self.refresh_writable();
+        self.warn_if_addon_missing(&sv_path);
+
+        // Parsed in the background already and unchanged since
+        if let Some(cached) = self.saved_vars.tree_cache.get(&sv_path) {
+            let (tree, count) = (cached.tree.clone(), cached.count);
+            self.saved_vars.db_version = cached.db_version;
+            self.handle_scan_update(ScanUpdate::Complete(ScanOutcome::Tree { tree, count }));
+            return Task::none();
+        }
+
         self.tasks.is_scanning = true;
         self.tasks.scanning_message = "Loading SavedVariables...".to_string();
 
-        Task::perform(
+        let scan = Task::perform(
             async move {
-                let mut manager = SavedVariablesManager::new(sv_path);
-
-                match manager.load() {
-                    Ok(()) => {
-                        let tree = manager.get_aura_tree();
-                        let count = tree.iter().map(|n| n.total_count()).sum();
-                        ScanUpdate::Complete { tree, count }
-                    }
+                match ParsedTree::parse(&sv_path) {
+                    Ok(parsed) => ScanUpdate::Complete(ScanOutcome::Parsed(parsed)),
                     Err(WeakAuraError::FileNotFound(_)) => {
                         // File doesn't exist yet — that's okay, just return empty
-                        ScanUpdate::Complete {
+                        ScanUpdate::Complete(ScanOutcome::Tree {
                             tree: Vec::new(),
                             count: 0,
-                        }
+                        })
+                    }
+                    Err(e @ WeakAuraError::LuaParseError(_)) => {
+                        ScanUpdate::Complete(ScanOutcome::Damaged {
+                            error: format!("Failed to load SavedVariables: {}", e),
+                            fallbacks: find_fallbacks(&sv_path),
+                        })
                     }
                     Err(e) => ScanUpdate::Error(format!("Failed to load SavedVariables: {}", e)),
                 }
             },
             Message::ScanUpdate,
+        );
+        self.track_task(TaskKind::Scanning, scan)
+    }
+
+    /// Show the removal confirmation for the given auras
+    pub(crate) fn open_remove_confirm(&mut self, ids: Vec<String>) {
+        self.removal.pending_ids = ids;
+        self.removal.reanchor_dependents = false;
+        self.ui.show_remove_confirm = true;
+    }
+
+    /// Start the confirmed removal, asking for the export file first if the
+    /// user opted to export the auras before removing them
+    pub(crate) fn confirm_removal(&mut self, keep_children: bool) -> Task<Message> {
+        if !self.removal.export_first {
+            return self.remove_auras_async(keep_children, None);
+        }
+        Task::perform(
+            async {
+                rfd::AsyncFileDialog::new()
+                    .add_filter("Lua files", &["lua"])
+                    .set_file_name("removed-auras.lua")
+                    .save_file()
+                    .await
+                    .map(|h| h.path().to_path_buf())
+            },
+            move |path| Message::RemovalExportPathSelected(path, keep_children),
         )
     }
 
-    /// Remove selected auras from SavedVariables (async)
-    pub(crate) fn remove_auras_async(&mut self) -> Task<Message> {
+    /// Remove selected auras from SavedVariables (async). With `keep_children`,
+    /// selected groups are dissolved and their children promoted to top level.
+    /// With `export_to`, the auras are dumped as Lua there first; nothing is
+    /// removed if that fails. Auras anchored to removed ones are anchored to
+    /// the screen if the user opted for it.
+    pub(crate) fn remove_auras_async(
+        &mut self,
+        keep_children: bool,
+        export_to: Option<PathBuf>,
+    ) -> Task<Message> {
         let Some(sv_path) = self.saved_vars.selected_path.clone() else {
-            self.toasts.push(
-                toast("No SavedVariables file selected")
-                    .title("Removal Error")
-                    .level(ToastLevel::Error),
+            self.notifications.push(
+                Notification::error("No SavedVariables file selected").title("Removal Error"),
             );
             return Task::none();
         };
 
+        if self.saved_vars.writes_blocked() {
+            self.notify_db_version_blocked("Removal Error");
+            return Task::none();
+        }
+
         let ids = std::mem::take(&mut self.removal.pending_ids);
         if ids.is_empty() {
             return Task::none();
         }
+        let (dissolve, ids): (Vec<String>, Vec<String>) = if keep_children {
+            let selected: HashSet<String> = ids.into_iter().collect();
+            let (groups, auras): (Vec<_>, Vec<_>) =
+                topmost_selected(&self.saved_vars.auras_tree, &selected)
+                    .into_iter()
+                    .partition(|node| node.is_group);
+            (
+                groups.into_iter().map(|node| node.id.clone()).collect(),
+                auras.into_iter().map(|node| node.id.clone()).collect(),
+            )
+        } else {
+            (Vec::new(), ids)
+        };
+        let allow_newer_db_version = self.saved_vars.allow_newer_db_version;
+        let reanchor_dependents = self.removal.reanchor_dependents;
 
         self.tasks.is_removing = true;
         self.tasks.removal_message = "Removing auras...".to_string();
 
-        Task::perform(
+        let removal = Task::perform(
             async move {
                 let mut manager = SavedVariablesManager::new(sv_path);
+                manager.allow_newer_db_version = allow_newer_db_version;
 
                 if let Err(e) = manager.load() {
                     if !matches!(e, WeakAuraError::FileNotFound(_)) {
@@ -75,16 +149,41 @@ impl WeakAuraImporter {
                     }
                 }
 
-                let removed = manager.remove_auras(&ids);
+                if let Some(path) = &export_to {
+                    let all_ids: Vec<String> = ids.iter().chain(&dissolve).cloned().collect();
+                    let lua = manager.export_displays_lua(&all_ids);
+                    if let Err(e) = tokio::fs::write(path, lua).await {
+                        return RemovalUpdate::Error(format!(
+                            "Failed to export auras to {}, nothing was removed: {}",
+                            path.display(),
+                            e
+                        ));
+                    }
+                }
+
+                let mut removed = manager.remove_auras(&ids);
+                for id in &dissolve {
+                    if manager.dissolve_group(id).is_some() {
+                        removed.push(id.clone());
+                    }
+                }
+                if reanchor_dependents {
+                    let dependents: Vec<String> = manager
+                        .anchor_dependents(&removed)
+                        .into_iter()
+                        .map(|(id, _)| id)
+                        .collect();
+                    manager.anchor_to_screen(&dependents);
+                }
 
                 if removed.is_empty() {
                     let tree = manager.get_aura_tree();
                     let tree_count = tree.iter().map(|n| n.total_count()).sum();
-                    return RemovalUpdate::Complete {
+                    return RemovalUpdate::Complete(RemovedAuras {
                         removed_count: 0,
                         tree,
                         tree_count,
-                    };
+                    });
                 }
 
                 if let Err(e) = manager.save() {
@@ -94,13 +193,44 @@ impl WeakAuraImporter {
                 let tree = manager.get_aura_tree();
                 let tree_count = tree.iter().map(|n| n.total_count()).sum();
 
-                RemovalUpdate::Complete {
+                RemovalUpdate::Complete(RemovedAuras {
                     removed_count: removed.len(),
                     tree,
                     tree_count,
-                }
+                })
             },
             Message::RemovalUpdate,
-        )
+        );
+        self.track_task(TaskKind::Removing, removal)
+    }
+
+    /// Warn when the WeakAuras addon is not installed for the file's flavor,
+    /// since imported auras would never show up in game
+    fn warn_if_addon_missing(&mut self, sv_path: &Path) {
+        if discovery::weakauras_installed(sv_path) != Some(false) {
+            return;
+        }
+        let message = if sv_path.exists() {
+            "WeakAuras is not installed for this game flavor (Interface/AddOns/WeakAuras is missing). \
+             Imported auras will not show up in game until it is installed."
+        } else {
+            "WeakAuras is not installed for this game flavor, and this SavedVariables file \
+             does not exist yet. Importing would create it for an addon you don't have."
+        };
+        self.notifications
+            .push(Notification::warning(message).title("WeakAuras Not Installed"));
+    }
+
+    /// Tell the user to confirm the `dbVersion` warning before writing
+    pub(crate) fn notify_db_version_blocked(&mut self, title: &str) {
+        let version = self.saved_vars.db_version.unwrap_or_default();
+        self.notifications.push(
+            Notification::warning(format!(
+                "This file has dbVersion {} (newer than the supported {}). \
+                 Confirm the warning in the sidebar to write it anyway.",
+                version, MAX_SUPPORTED_DB_VERSION
+            ))
+            .title(title),
+        );
     }
 }
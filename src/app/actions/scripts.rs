@@ -0,0 +1,115 @@
+//! Scripts dialog: loading scripts from the scripts folder and running them
+//! against the selected SavedVariables file.
+
+use std::path::PathBuf;
+
+use iced::widget::text_editor;
+use iced::Task;
+
+use crate::error::WeakAuraError;
+use crate::saved_variables::SavedVariablesManager;
+use crate::scripting::{list_scripts, run_script, scripts_dir, ScriptReport};
+
+use super::super::notifications::Notification;
+use super::super::state::StoredAuraEdit;
+use super::super::{Message, WeakAuraImporter};
+
+impl WeakAuraImporter {
+    /// Show or hide the scripts dialog, listing the scripts folder on open
+    pub(crate) fn toggle_scripts(&mut self) {
+        self.scripts.show = !self.scripts.show;
+        if self.scripts.show {
+            self.scripts.files = scripts_dir()
+                .map(|dir| list_scripts(&dir))
+                .unwrap_or_default();
+        }
+    }
+
+    /// Load a script file into the editor
+    pub(crate) fn load_script(&mut self, path: PathBuf) {
+        match std::fs::read_to_string(&path) {
+            Ok(source) => {
+                self.scripts.content = text_editor::Content::with_text(&source);
+                self.scripts.selected = Some(path);
+                self.scripts.result = None;
+            }
+            Err(e) => self.notifications.push(
+                Notification::error(format!("Failed to read {}: {}", path.display(), e))
+                    .title("Scripts"),
+            ),
+        }
+    }
+
+    /// Run the script in the editor in the background. A real run saves the
+    /// file when the script changed anything.
+    pub(crate) fn run_script_async(&mut self, dry_run: bool) -> Task<Message> {
+        let Some(sv_path) = self.saved_vars.selected_path.clone() else {
+            self.notifications
+                .push(Notification::error("No SavedVariables file selected").title("Scripts"));
+            return Task::none();
+        };
+        if !dry_run && self.saved_vars.writes_blocked() {
+            self.notify_db_version_blocked("Scripts");
+            return Task::none();
+        }
+        let allow_newer_db_version = self.saved_vars.allow_newer_db_version;
+        let source = self.scripts.content.text();
+        self.scripts.running = true;
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut manager = SavedVariablesManager::new(sv_path);
+                    manager.allow_newer_db_version = allow_newer_db_version;
+                    match manager.load() {
+                        Ok(()) | Err(WeakAuraError::FileNotFound(_)) => {}
+                        Err(e) => return Err(format!("Failed to load SavedVariables: {}", e)),
+                    }
+                    let report =
+                        run_script(&mut manager, &source, dry_run).map_err(|e| e.to_string())?;
+                    if !report.applied {
+                        return Ok((report, None));
+                    }
+                    manager
+                        .save()
+                        .map_err(|e| format!("Failed to save: {}", e))?;
+                    let tree = manager.get_aura_tree();
+                    let tree_count = tree.iter().map(|n| n.total_count()).sum();
+                    let edit = StoredAuraEdit {
+                        message: format!("Script changed {} aura(s)", report.changed_ids().len()),
+                        tree,
+                        tree_count,
+                    };
+                    Ok((report, Some(edit)))
+                })
+                .await
+                .map_err(|e| format!("Task failed: {}", e))?
+            },
+            Message::ScriptFinished,
+        )
+    }
+
+    /// Show the report of a finished run and refresh the tree after changes
+    pub(crate) fn finish_script(
+        &mut self,
+        result: Result<(ScriptReport, Option<StoredAuraEdit>), String>,
+    ) {
+        self.scripts.running = false;
+        let (report, edit) = match result {
+            Ok(done) => done,
+            Err(e) => {
+                self.scripts.result = Some(Err(e));
+                return;
+            }
+        };
+        if let Some(edit) = edit {
+            self.saved_vars.auras_tree = edit.tree;
+            self.saved_vars.auras_count = edit.tree_count;
+            self.refresh_has_backup();
+            self.tree_selection.clear();
+            self.notifications
+                .push(Notification::success(edit.message).title("Scripts"));
+        }
+        self.scripts.result = Some(Ok(report));
+    }
+}
@@ -0,0 +1,202 @@
+//! Bulk selection of parsed auras via selection rules, and shift/ctrl-click
+//! selection in the parsed auras list and the existing auras tree.
+
+use std::collections::BTreeMap;
+
+use crate::saved_variables::{set_tree_checked, AuraTreeNode};
+use crate::selection::{ClickMode, SelectionMode, SelectionRule};
+
+use super::super::notifications::Notification;
+use super::super::state::{ListGrouping, ParsedAuraEntry};
+use super::super::WeakAuraImporter;
+
+impl WeakAuraImporter {
+    /// Apply the current selection rule to every parsed aura
+    pub(crate) fn apply_selection_rule(&mut self, mode: SelectionMode) {
+        let inputs = &self.selection_rule;
+        let rule = SelectionRule {
+            valid_only: inputs.valid_only,
+            groups_only: inputs.groups_only,
+            region_type: inputs.region_type.clone(),
+            source_file: inputs.source_file.clone(),
+            ..SelectionRule::default()
+        }
+        .with_id_pattern(&inputs.id_pattern);
+
+        let rule = match rule {
+            Ok(rule) => rule,
+            Err(e) => {
+                self.notifications
+                    .push(Notification::error(e.to_string()).title("Selection Rule"));
+                return;
+            }
+        };
+
+        let skip_trivial = self.config.skip_trivial_auras;
+        for entry in &mut self.parsed_auras {
+            let matches = rule.matches(
+                &entry.validation,
+                entry.aura.as_ref(),
+                entry.source.as_deref(),
+            );
+            // Invalid auras can never be imported, so never select them
+            let selected = entry.validation.is_valid && mode.apply(entry.selected, matches);
+            entry.selected =
+                selected && (entry.selected || selectable_in_bulk(entry, skip_trivial));
+        }
+
+        let selected = self.parsed_auras.iter().filter(|e| e.selected).count();
+        self.notifications
+            .push(Notification::info(format!("{} aura(s) selected", selected)));
+    }
+
+    /// Check or uncheck every valid aura of a group of the list
+    pub(crate) fn set_group_selection(&mut self, key: &str, selected: bool) {
+        let grouping = self.list_view.grouping;
+        let skip_trivial = self.config.skip_trivial_auras;
+        for entry in &mut self.parsed_auras {
+            if entry.validation.is_valid && entry.group_key(grouping) == key {
+                entry.selected =
+                    selected && (entry.selected || selectable_in_bulk(entry, skip_trivial));
+            }
+        }
+    }
+
+    /// Turn skipping trivial auras in bulk selection on or off; turning it on
+    /// deselects the trivial auras listed
+    pub(crate) fn set_skip_trivial_auras(&mut self, skip: bool) {
+        self.config.skip_trivial_auras = skip;
+        self.save_config();
+        if !skip {
+            return;
+        }
+        let mut deselected = 0;
+        for entry in &mut self.parsed_auras {
+            if entry.selected && entry.trivial_reason().is_some() {
+                entry.selected = false;
+                deselected += 1;
+            }
+        }
+        if deselected > 0 {
+            self.notifications.push(Notification::info(format!(
+                "{} trivial aura(s) deselected",
+                deselected
+            )));
+        }
+    }
+
+    /// How a click changes a selection, from the modifier keys held
+    fn click_mode(&self) -> ClickMode {
+        ClickMode::from_modifiers(self.modifiers.shift(), self.modifiers.command())
+    }
+
+    /// Indices of the parsed auras in the order the list shows them
+    /// (filtered, sorted, grouped, without collapsed groups)
+    pub(crate) fn visible_list_order(&self) -> Vec<usize> {
+        let visible = self.visible_entries();
+        let grouping = self.list_view.grouping;
+        if grouping == ListGrouping::None {
+            return visible.into_iter().map(|(idx, _)| idx).collect();
+        }
+        let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (idx, entry) in visible {
+            groups
+                .entry(entry.group_key(grouping))
+                .or_default()
+                .push(idx);
+        }
+        groups
+            .into_iter()
+            .filter(|(key, _)| !self.list_view.collapsed_groups.contains(key))
+            .flat_map(|(_, indices)| indices)
+            .collect()
+    }
+
+    /// Check or uncheck a parsed aura. With shift held, every valid entry
+    /// from the range anchor to it is set the same way.
+    pub(crate) fn set_parsed_aura_selected(&mut self, idx: usize, selected: bool) {
+        let order = self.visible_list_order();
+        let mode = self.click_mode();
+        for idx in self.list_selection.range.click(&order, &idx, mode) {
+            if let Some(entry) = self.parsed_auras.get_mut(idx) {
+                entry.selected = selected && entry.validation.is_valid;
+            }
+        }
+    }
+
+    /// Click on a parsed aura's name: ctrl toggles it and shift selects the
+    /// range up to it; a plain click shows it in the decoded panel
+    pub(crate) fn click_parsed_aura(&mut self, idx: usize) {
+        let Some(entry) = self.parsed_auras.get(idx) else {
+            return;
+        };
+        match self.click_mode() {
+            ClickMode::Single => self.selected_aura_index = Some(idx),
+            ClickMode::Toggle => {
+                let selected = !entry.selected;
+                self.set_parsed_aura_selected(idx, selected);
+            }
+            ClickMode::Range => self.set_parsed_aura_selected(idx, true),
+        }
+    }
+
+    /// IDs of the existing auras in the order the tree shows them (sorted,
+    /// filtered, without the children of collapsed groups)
+    pub(crate) fn visible_tree_order(&self) -> Vec<String> {
+        fn visit(
+            importer: &WeakAuraImporter,
+            nodes: &[AuraTreeNode],
+            filtering: bool,
+            order: &mut Vec<String>,
+        ) {
+            for node in importer.sidebar.tree_sort.sorted(nodes) {
+                if !importer.tree_node_matches_filter(node) {
+                    continue;
+                }
+                order.push(node.id.clone());
+                if filtering || importer.sidebar.expanded_groups.contains(&node.id) {
+                    visit(importer, &node.children, filtering, order);
+                }
+            }
+        }
+        let filtering = !self.annotations.filter.trim().is_empty();
+        let mut order = Vec::new();
+        visit(self, &self.saved_vars.auras_tree, filtering, &mut order);
+        order
+    }
+
+    /// Check or uncheck an existing aura and its descendants. With shift
+    /// held, every aura from the range anchor to it is set the same way.
+    pub(crate) fn set_tree_node_checked(&mut self, id: &str, checked: bool) {
+        let order = self.visible_tree_order();
+        let mode = self.click_mode();
+        for id in self
+            .tree_selection
+            .range
+            .click(&order, &id.to_string(), mode)
+        {
+            set_tree_checked(
+                &self.saved_vars.auras_tree,
+                &mut self.tree_selection.selected,
+                &id,
+                checked,
+            );
+        }
+    }
+
+    /// Click on an existing aura's row: toggles it, or with shift checks the
+    /// range up to it
+    pub(crate) fn click_tree_node(&mut self, id: &str) {
+        let checked = match self.click_mode() {
+            ClickMode::Range => true,
+            ClickMode::Single | ClickMode::Toggle => !self.tree_selection.selected.contains(id),
+        };
+        self.set_tree_node_checked(id, checked);
+    }
+}
+
+/// Whether selecting in bulk may select `entry`: not when trivial auras are
+/// skipped and it is one
+pub(crate) fn selectable_in_bulk(entry: &ParsedAuraEntry, skip_trivial: bool) -> bool {
+    !skip_trivial || entry.trivial_reason().is_none()
+}
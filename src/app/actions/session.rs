@@ -0,0 +1,128 @@
+//! Auto-save of the loaded auras and restoring them on the next launch.
+
+use iced::Task;
+
+use crate::session::{Session, SessionEntry};
+
+use super::super::notifications::{Notification, NotificationLevel, Problem};
+use super::super::state::{LoadedAuras, LoadingUpdate, ParsedAuraEntry, TaskKind};
+use super::super::{Message, WeakAuraImporter};
+use super::decode_auras;
+
+impl WeakAuraImporter {
+    /// The loaded and staged auras with their selection state
+    pub(crate) fn current_session(&self) -> Session {
+        Session {
+            entries: session_entries(&self.parsed_auras),
+            staged: session_entries(&self.staging.entries),
+        }
+    }
+
+    /// Write the loaded auras to the session file if they changed since the
+    /// last save. Paused while an earlier session is offered for restore, so
+    /// it isn't overwritten before the user decides.
+    pub(crate) fn autosave_session(&mut self) {
+        if self.session.pending_restore.is_some() {
+            return;
+        }
+        let session = self.current_session();
+        if session == self.session.last_saved {
+            return;
+        }
+        if let Err(e) = session.save() {
+            self.notifications.report(Problem::new(
+                NotificationLevel::Warning,
+                "Session auto-save",
+                e.to_string(),
+            ));
+        }
+        // Not retried until the auras change again, to avoid a problem per tick
+        self.session.last_saved = session;
+    }
+
+    /// Decode the auras of the earlier session in the background and add
+    /// them to the list and the staging area with their saved selection
+    pub(crate) fn restore_session(&mut self) -> Task<Message> {
+        let Some(mut session) = self.session.pending_restore.take() else {
+            return Task::none();
+        };
+        let staged = std::mem::take(&mut session.staged);
+
+        self.tasks.is_loading = true;
+        self.tasks.loading_progress = 0.0;
+        self.tasks.loading_message = format!("Restoring {} aura(s)...", session.entries.len());
+
+        let restore = Task::perform(
+            async move {
+                let result = tokio::task::spawn_blocking(move || {
+                    let (mut entries, mut errors) = (Vec::new(), Vec::new());
+                    for saved in &session.entries {
+                        let (decoded, e) =
+                            decode_auras(&saved.import_string, saved.source.as_deref());
+                        entries.extend(decoded.into_iter().map(|mut entry| {
+                            entry.selected = saved.selected;
+                            entry
+                        }));
+                        errors.extend(e);
+                    }
+                    LoadingUpdate::Complete(LoadedAuras { entries, errors })
+                })
+                .await;
+                result.unwrap_or_else(|e| {
+                    LoadingUpdate::Error(format!("Session restore failed: {}", e))
+                })
+            },
+            Message::LoadingUpdate,
+        );
+        let restore = self.track_task(TaskKind::Loading, restore);
+        if staged.is_empty() {
+            return restore;
+        }
+        let restore_staged = Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    staged
+                        .iter()
+                        .flat_map(|saved| {
+                            decode_auras(&saved.import_string, saved.source.as_deref()).0
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await
+                .unwrap_or_default()
+            },
+            Message::StagingRestored,
+        );
+        Task::batch([restore, restore_staged])
+    }
+
+    /// Drop the earlier session and remove its file
+    pub(crate) fn discard_session(&mut self) {
+        self.session.pending_restore = None;
+        // Saving the current (possibly empty) list replaces the old session
+        let session = self.current_session();
+        if let Err(e) = session.save() {
+            self.notifications.push(
+                Notification::error(format!("Could not discard the session: {}", e))
+                    .title("Session"),
+            );
+        }
+        self.session.last_saved = session;
+    }
+}
+
+/// Session entries of decoded auras. Auras copied from another SavedVariables
+/// file have no import string to restore them from and are left out.
+fn session_entries(entries: &[ParsedAuraEntry]) -> Vec<SessionEntry> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let aura = entry.aura.as_ref()?;
+            (!aura.original_string.is_empty()).then(|| SessionEntry {
+                import_string: aura.original_string.clone(),
+                selected: entry.selected,
+                source: entry.source.clone(),
+            })
+        })
+        .collect()
+}
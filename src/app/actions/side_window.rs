@@ -0,0 +1,167 @@
+//! Second window showing another SavedVariables file side by side.
+
+use std::path::PathBuf;
+
+use iced::{window, Size, Task};
+
+use crate::decoder::WeakAura;
+use crate::saved_variables::{topmost_selected, SavedVariablesManager};
+use crate::tree_cache::ParsedTree;
+
+use super::super::notifications::Notification;
+use super::super::state::ParsedAuraEntry;
+use super::super::{Message, WeakAuraImporter};
+use super::{collect_existing_ids, selectable_in_bulk};
+
+impl WeakAuraImporter {
+    /// Open the second window, or bring it to the front when it is open
+    pub(crate) fn open_side_window(&mut self) -> Task<Message> {
+        if let Some(id) = self.side_window.id {
+            return window::gain_focus(id);
+        }
+        let (id, open) = window::open(window::Settings {
+            size: Size::new(520.0, 700.0),
+            ..window::Settings::default()
+        });
+        self.side_window.id = Some(id);
+        open.discard()
+    }
+
+    /// Pick a WeakAuras.lua anywhere, e.g. from a backup, to copy auras from
+    pub(crate) fn load_from_saved_variables_async(&mut self) -> Task<Message> {
+        Task::perform(
+            async {
+                rfd::AsyncFileDialog::new()
+                    .add_filter("Lua files", &["lua"])
+                    .pick_file()
+                    .await
+                    .map(|h| h.path().to_path_buf())
+            },
+            Message::SavedVariablesSourceSelected,
+        )
+    }
+
+    /// Browse a picked SavedVariables file in the second window, whether or
+    /// not it belongs to a discovered installation
+    pub(crate) fn open_saved_variables_source(&mut self, path: PathBuf) -> Task<Message> {
+        Task::batch([self.open_side_window(), self.select_side_file(path)])
+    }
+
+    /// Show a SavedVariables file in the second window, parsing it in the
+    /// background unless the cache has it
+    pub(crate) fn select_side_file(&mut self, path: PathBuf) -> Task<Message> {
+        self.side_window.selected.clear();
+        self.side_window.path = Some(path.clone());
+        if let Some(cached) = self.saved_vars.tree_cache.get(&path) {
+            self.side_window.tree = Some(cached.clone());
+            return Task::none();
+        }
+
+        self.side_window.tree = None;
+        self.side_window.is_loading = true;
+        Task::perform(
+            async move { ParsedTree::parse(&path).map_err(|e| e.to_string()) },
+            Message::SideWindowLoaded,
+        )
+    }
+
+    pub(crate) fn handle_side_window_loaded(&mut self, result: Result<ParsedTree, String>) {
+        self.side_window.is_loading = false;
+        match result {
+            // Ignore a file the user has switched away from meanwhile
+            Ok(parsed) if self.side_window.path.as_ref() == Some(&parsed.path) => {
+                self.saved_vars.tree_cache.insert(parsed.clone());
+                self.side_window.tree = Some(parsed);
+            }
+            Ok(_) => {}
+            Err(e) => self.notifications.push(
+                Notification::error(format!("Failed to load SavedVariables: {}", e))
+                    .title("Second Window"),
+            ),
+        }
+    }
+
+    /// Read the checked auras of the second window's file in the background
+    pub(crate) fn copy_side_auras_async(&mut self) -> Task<Message> {
+        let (Some(path), Some(tree)) = (&self.side_window.path, &self.side_window.tree) else {
+            return Task::none();
+        };
+        let ids: Vec<String> = topmost_selected(&tree.tree, &self.side_window.selected)
+            .into_iter()
+            .map(|node| node.id.clone())
+            .collect();
+        if ids.is_empty() {
+            return Task::none();
+        }
+
+        self.side_window.is_copying = true;
+        let path = path.clone();
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut manager = SavedVariablesManager::new(path);
+                    manager.load().map_err(|e| e.to_string())?;
+                    Ok(manager.extract_auras(&ids))
+                })
+                .await
+                .map_err(|e| format!("Task failed: {}", e))?
+            },
+            Message::SideAurasExtracted,
+        )
+    }
+
+    /// Add auras copied from the second window to the import list, selected
+    pub(crate) fn handle_side_auras_extracted(&mut self, result: Result<Vec<WeakAura>, String>) {
+        self.side_window.is_copying = false;
+        let auras = match result {
+            Ok(auras) => auras,
+            Err(e) => {
+                self.notifications.push(
+                    Notification::error(format!("Failed to read auras: {}", e))
+                        .title("Second Window"),
+                );
+                return;
+            }
+        };
+
+        let existing_ids = collect_existing_ids(&self.parsed_auras);
+        let skip_trivial = self.config.skip_trivial_auras;
+        let mut added = 0;
+        let mut duplicates = 0;
+        for aura in auras {
+            if existing_ids.contains(&aura.id) {
+                duplicates += 1;
+                continue;
+            }
+            added += 1;
+            let mut entry = ParsedAuraEntry::new(aura, self.side_window.path.clone());
+            entry.selected = selectable_in_bulk(&entry, skip_trivial);
+            self.parsed_auras.push(entry);
+        }
+        self.side_window.selected.clear();
+
+        let mut message = format!("{} aura(s) added to the import list", added);
+        if duplicates > 0 {
+            message.push_str(&format!(", {} already listed", duplicates));
+        }
+        self.notifications
+            .push(Notification::success(message).title("Copied from Second Window"));
+    }
+
+    /// Forget the second window once it is closed; closing the main window
+    /// quits the app
+    pub(crate) fn handle_window_closed(&mut self, id: window::Id) -> Task<Message> {
+        if self.side_window.id == Some(id) {
+            self.side_window = Default::default();
+            return Task::none();
+        }
+        if self.tasks.window == Some(id) {
+            self.tasks.window = None;
+            return Task::none();
+        }
+        if self.main_window == Some(id) {
+            return iced::exit();
+        }
+        Task::none()
+    }
+}
@@ -0,0 +1,71 @@
+//! Copying aura data to the clipboard as JSON or Lua snippets.
+
+use iced::Task;
+
+use crate::decoder::AuraTextFormat;
+use crate::saved_variables::SavedVariablesManager;
+
+use super::super::notifications::Notification;
+use super::super::{Message, WeakAuraImporter};
+
+impl WeakAuraImporter {
+    /// Copy the data of the aura shown in the decoded panel
+    pub(crate) fn copy_decoded_data(&mut self, format: AuraTextFormat) {
+        let Some(aura) = self
+            .selected_aura_index
+            .and_then(|idx| self.parsed_auras.get(idx))
+            .and_then(|e| e.aura.as_ref())
+        else {
+            return;
+        };
+        let text = aura.data_text(format);
+        self.copy_snippet(text);
+    }
+
+    /// Read an existing aura of the selected SavedVariables file in the
+    /// background and serialize its stored data
+    pub(crate) fn copy_stored_aura_async(
+        &mut self,
+        id: String,
+        format: AuraTextFormat,
+    ) -> Task<Message> {
+        let Some(path) = self.saved_vars.selected_path.clone() else {
+            return Task::none();
+        };
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut manager = SavedVariablesManager::new(path);
+                    manager.load().map_err(|e| e.to_string())?;
+                    manager
+                        .displays
+                        .get(&id)
+                        .map(|data| data.to_text(format))
+                        .ok_or_else(|| format!("'{}' is no longer in the file", id))
+                })
+                .await
+                .map_err(|e| format!("Task failed: {}", e))?
+            },
+            Message::StoredAuraTextLoaded,
+        )
+    }
+
+    /// Put a snippet on the clipboard
+    pub(crate) fn copy_snippet(&mut self, text: String) {
+        match self.clipboard.as_mut().map(|c| c.set_text(text)) {
+            Some(Ok(())) => {
+                self.notifications
+                    .push(Notification::info("Aura data copied to clipboard"));
+            }
+            Some(Err(e)) => {
+                self.notifications.push(
+                    Notification::error(format!("Clipboard error: {}", e)).title("Clipboard Error"),
+                );
+            }
+            None => {
+                self.notifications
+                    .push(Notification::error("Clipboard unavailable").title("Clipboard Error"));
+            }
+        }
+    }
+}
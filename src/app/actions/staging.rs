@@ -0,0 +1,75 @@
+//! Staging area: auras set aside for import apart from the parsed list.
+
+use super::super::notifications::Notification;
+use super::super::state::ParsedAuraEntry;
+use super::super::WeakAuraImporter;
+use super::collect_existing_ids;
+
+impl WeakAuraImporter {
+    /// Move the selected valid auras of the list to the staging area. Auras
+    /// already staged under the same ID stay in the list.
+    pub(crate) fn stage_selected(&mut self) {
+        let staged_ids = collect_existing_ids(&self.staging.entries);
+        let (moved, kept): (Vec<ParsedAuraEntry>, Vec<ParsedAuraEntry>) =
+            std::mem::take(&mut self.parsed_auras)
+                .into_iter()
+                .partition(|entry| {
+                    entry.selected
+                        && entry.validation.is_valid
+                        && entry
+                            .validation
+                            .aura_id
+                            .as_ref()
+                            .is_some_and(|id| !staged_ids.contains(id))
+                });
+        self.parsed_auras = kept;
+        self.selected_aura_index = None;
+        self.property_editor = Default::default();
+        self.raw_editor = Default::default();
+        self.context_menu = Default::default();
+        self.list_selection = Default::default();
+        if moved.is_empty() {
+            return;
+        }
+
+        self.notifications.push(Notification::info(format!(
+            "{} aura(s) staged for import",
+            moved.len()
+        )));
+        self.staging.entries.extend(moved);
+    }
+
+    /// Move a staged aura back to the list, selected
+    pub(crate) fn unstage_aura(&mut self, idx: usize) {
+        if idx >= self.staging.entries.len() {
+            return;
+        }
+        let mut entry = self.staging.entries.remove(idx);
+        let listed = collect_existing_ids(&self.parsed_auras);
+        if entry
+            .validation
+            .aura_id
+            .as_ref()
+            .is_some_and(|id| listed.contains(id))
+        {
+            // Loaded again since it was staged
+            return;
+        }
+        entry.selected = true;
+        self.parsed_auras.push(entry);
+    }
+
+    /// Add the staged auras of a restored session, skipping IDs already staged
+    pub(crate) fn restore_staging(&mut self, entries: Vec<ParsedAuraEntry>) {
+        let mut staged_ids = collect_existing_ids(&self.staging.entries);
+        for entry in entries {
+            let is_new = match &entry.validation.aura_id {
+                Some(id) => staged_ids.insert(id.clone()),
+                None => false,
+            };
+            if is_new {
+                self.staging.entries.push(entry);
+            }
+        }
+    }
+}
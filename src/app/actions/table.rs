@@ -0,0 +1,73 @@
+//! Status filtering, column sorting and resizing of the parsed auras table.
+
+use super::super::state::{ColumnResize, ListColumn, ParsedAuraEntry};
+use super::super::WeakAuraImporter;
+
+impl WeakAuraImporter {
+    /// Parsed auras passing the status chip and the annotation filter, in
+    /// table order. Entries that compare equal keep their load order.
+    pub(crate) fn visible_entries(&self) -> Vec<(usize, &ParsedAuraEntry)> {
+        let duplicates = self.duplicate_indices();
+        let mut entries: Vec<(usize, &ParsedAuraEntry)> = self
+            .parsed_auras
+            .iter()
+            .enumerate()
+            .filter(|(idx, entry)| {
+                self.list_view
+                    .status_filter
+                    .matches(entry, duplicates.contains(idx))
+                    && self.parsed_entry_matches_filter(entry)
+            })
+            .collect();
+        if let Some(column) = self.list_view.sort_column {
+            entries.sort_by(|(_, a), (_, b)| {
+                let ordering = a.compare_by(b, column);
+                if self.list_view.sort_descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+        entries
+    }
+
+    /// Sort the table by a column: ascending first, then descending, then
+    /// back to the load order
+    pub(crate) fn sort_list_by(&mut self, column: ListColumn) {
+        let view = &mut self.list_view;
+        match view.sort_column {
+            Some(current) if current == column && !view.sort_descending => {
+                view.sort_descending = true;
+            }
+            Some(current) if current == column => {
+                view.sort_column = None;
+                view.sort_descending = false;
+            }
+            _ => {
+                view.sort_column = Some(column);
+                view.sort_descending = false;
+            }
+        }
+    }
+
+    /// Start dragging the right edge of a column header
+    pub(crate) fn start_column_resize(&mut self, column: ListColumn) {
+        self.list_view.resizing = Some(ColumnResize {
+            column,
+            start_x: None,
+            start_width: self.list_view.column_width(column),
+        });
+    }
+
+    /// Resize the dragged column to follow the pointer
+    pub(crate) fn resize_column(&mut self, x: f32) {
+        let Some(resize) = &mut self.list_view.resizing else {
+            return;
+        };
+        let start_x = *resize.start_x.get_or_insert(x);
+        let width = (resize.start_width + x - start_x).max(ListColumn::MIN_WIDTH);
+        let column = resize.column;
+        self.list_view.column_widths.insert(column, width);
+    }
+}
@@ -0,0 +1,210 @@
+//! Plumbing shared by the background tasks: running a job as a stream of
+//! [`TaskEvent`]s, sending its events with coalesced progress, and the
+//! Tasks panel listing the running jobs with a way to cancel them.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use iced::futures::channel::mpsc;
+use iced::futures::SinkExt;
+use iced::{stream, window, Size, Task};
+
+use super::super::notifications::Notification;
+use super::super::state::{RunningTask, TaskEvent, TaskKind};
+use super::super::{Message, WeakAuraImporter};
+
+/// Minimum time between two progress events of a task; the UI redraws on
+/// every message, and a folder of small files reports far more often
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Sends the events of one background task to the UI
+pub(crate) struct TaskSender<T> {
+    sender: mpsc::Sender<Message>,
+    wrap: fn(TaskEvent<T>) -> Message,
+    last_progress: Option<Instant>,
+}
+
+impl<T> TaskSender<T> {
+    pub(crate) fn new(sender: mpsc::Sender<Message>, wrap: fn(TaskEvent<T>) -> Message) -> Self {
+        Self {
+            sender,
+            wrap,
+            last_progress: None,
+        }
+    }
+
+    /// Report progress of a loop. Dropped when the last progress event went
+    /// out less than `PROGRESS_INTERVAL` ago, unless the work is done.
+    pub(crate) async fn progress(&mut self, current: u64, total: u64, message: impl Into<String>) {
+        let recent = self
+            .last_progress
+            .is_some_and(|sent| sent.elapsed() < PROGRESS_INTERVAL);
+        if recent && current < total {
+            return;
+        }
+        self.step(current, total, message).await;
+    }
+
+    /// Report the start of a pipeline step; never dropped, since the step
+    /// may run for a while after it
+    pub(crate) async fn step(&mut self, current: u64, total: u64, message: impl Into<String>) {
+        self.last_progress = Some(Instant::now());
+        self.send(TaskEvent::Progress {
+            current,
+            total,
+            message: message.into(),
+        })
+        .await;
+    }
+
+    /// Send a result; tasks producing several results may send more than one
+    pub(crate) async fn complete(&mut self, result: T) {
+        self.send(TaskEvent::Complete(result)).await;
+    }
+
+    /// Report that the task failed
+    pub(crate) async fn error(&mut self, message: impl Into<String>) {
+        self.send(TaskEvent::Error(message.into())).await;
+    }
+
+    async fn send(&mut self, event: TaskEvent<T>) {
+        let _ = self.sender.send((self.wrap)(event)).await;
+    }
+}
+
+/// Run `job` in the background, turning the events it sends into messages
+/// through `wrap` (e.g. `Message::LoadingUpdate`)
+pub(crate) fn run_task<T, F, Fut>(wrap: fn(TaskEvent<T>) -> Message, job: F) -> Task<Message>
+where
+    T: Send + 'static,
+    F: FnOnce(TaskSender<T>) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    Task::run(
+        stream::channel(100, move |sender: mpsc::Sender<Message>| {
+            job(TaskSender::new(sender, wrap))
+        }),
+        |msg| msg,
+    )
+}
+
+impl WeakAuraImporter {
+    /// Make `task` cancellable from the Tasks panel as the job of `kind`
+    pub(crate) fn track_task(&mut self, kind: TaskKind, task: Task<Message>) -> Task<Message> {
+        let (task, handle) = task.abortable();
+        self.tasks.handles.entry(kind).or_default().push(handle);
+        task
+    }
+
+    /// Background jobs running now, in the order of [`TaskKind`]
+    pub(crate) fn running_tasks(&self) -> Vec<RunningTask> {
+        let tasks = &self.tasks;
+        let mut running = Vec::new();
+        if tasks.is_loading {
+            running.push(RunningTask {
+                kind: TaskKind::Loading,
+                progress: Some(tasks.loading_progress),
+                message: tasks.loading_message.clone(),
+            });
+        }
+        if tasks.is_scanning {
+            running.push(RunningTask {
+                kind: TaskKind::Scanning,
+                progress: None,
+                message: tasks.scanning_message.clone(),
+            });
+        }
+        let preparsing = self.saved_vars.preparsing.len();
+        if preparsing > 0 {
+            running.push(RunningTask {
+                kind: TaskKind::Preparsing,
+                progress: None,
+                message: format!("{} file(s) left", preparsing),
+            });
+        }
+        if tasks.is_importing {
+            running.push(RunningTask {
+                kind: TaskKind::Importing,
+                progress: Some(tasks.import_progress),
+                message: tasks.import_message.clone(),
+            });
+        }
+        if tasks.is_removing {
+            running.push(RunningTask {
+                kind: TaskKind::Removing,
+                progress: None,
+                message: tasks.removal_message.clone(),
+            });
+        }
+        running
+    }
+
+    /// Abort the running job of `kind`. Work already written to disk stays,
+    /// so the selected file is read again after a cancelled import or removal.
+    pub(crate) fn cancel_task(&mut self, kind: TaskKind) -> Task<Message> {
+        for handle in self.tasks.handles.remove(&kind).unwrap_or_default() {
+            handle.abort();
+        }
+        let tasks = &mut self.tasks;
+        let reload = match kind {
+            TaskKind::Loading => {
+                tasks.is_loading = false;
+                tasks.loading_progress = 0.0;
+                tasks.loading_message.clear();
+                false
+            }
+            TaskKind::Scanning => {
+                tasks.is_scanning = false;
+                tasks.scanning_message.clear();
+                false
+            }
+            TaskKind::Preparsing => {
+                self.saved_vars.preparsing.clear();
+                false
+            }
+            TaskKind::Importing => {
+                tasks.is_importing = false;
+                tasks.import_progress = 0.0;
+                tasks.import_message.clear();
+                true
+            }
+            TaskKind::Removing => {
+                tasks.is_removing = false;
+                tasks.removal_message.clear();
+                true
+            }
+        };
+        self.status.message = format!("{} cancelled.", kind.label());
+        self.status.is_error = false;
+        self.notifications
+            .push(Notification::info(format!("{} cancelled", kind.label())).title("Tasks"));
+        if reload {
+            self.load_existing_auras_async()
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Move the Tasks panel into its own window, or bring that to the front
+    pub(crate) fn detach_tasks_panel(&mut self) -> Task<Message> {
+        if let Some(id) = self.tasks.window {
+            return window::gain_focus(id);
+        }
+        let (id, open) = window::open(window::Settings {
+            size: Size::new(420.0, 300.0),
+            ..window::Settings::default()
+        });
+        self.tasks.window = Some(id);
+        self.ui.show_tasks = false;
+        open.discard()
+    }
+
+    /// Close the Tasks window and show the panel in the main window again
+    pub(crate) fn attach_tasks_panel(&mut self) -> Task<Message> {
+        let Some(id) = self.tasks.window.take() else {
+            return Task::none();
+        };
+        self.ui.show_tasks = true;
+        window::close(id)
+    }
+}
@@ -0,0 +1,134 @@
+//! Saving auras as templates and generating auras from parameter files.
+
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::decoder::WeakAura;
+use crate::templates::{parse_params, AuraTemplate};
+
+use super::super::notifications::Notification;
+use super::super::state::{ContextMenuState, ParsedAuraEntry};
+use super::super::{Message, WeakAuraImporter};
+use super::notify_decode_results;
+
+impl WeakAuraImporter {
+    /// Save a parsed aura as a template named after its ID, replacing a
+    /// template of that name
+    pub(crate) fn save_as_template(&mut self, idx: usize) {
+        self.context_menu = ContextMenuState::default();
+        let Some(aura) = self.parsed_auras.get(idx).and_then(|e| e.aura.clone()) else {
+            return;
+        };
+        let name = aura.id.clone();
+        let placeholders = AuraTemplate::new(aura.clone()).placeholders().len();
+        let replaced = self.templates.store.insert(&name, aura);
+        if !self.save_templates() {
+            return;
+        }
+
+        let verb = if replaced { "Replaced" } else { "Saved" };
+        let notification = if placeholders == 0 {
+            Notification::warning(format!(
+                "{} template '{}'; it has no {{{{name}}}} placeholders yet",
+                verb, name
+            ))
+        } else {
+            Notification::success(format!(
+                "{} template '{}' with {} placeholder(s)",
+                verb, name, placeholders
+            ))
+        };
+        self.notifications.push(notification.title("Templates"));
+        self.templates.show = true;
+    }
+
+    pub(crate) fn delete_template(&mut self, name: &str) {
+        if self.templates.store.remove(name) {
+            self.save_templates();
+        }
+    }
+
+    /// Persist the templates file, reporting failures as a toast. Returns
+    /// whether it was saved.
+    fn save_templates(&mut self) -> bool {
+        match self.templates.store.save() {
+            Ok(()) => true,
+            Err(e) => {
+                self.notifications.push(
+                    Notification::error(format!("Failed to save templates: {}", e))
+                        .title("Templates"),
+                );
+                false
+            }
+        }
+    }
+
+    /// Ask for a CSV or JSON parameter file and expand the template with it
+    /// in the background
+    pub(crate) fn generate_from_template_async(&mut self, name: String) -> Task<Message> {
+        let Some(template) = self.templates.store.get(&name).cloned() else {
+            return Task::none();
+        };
+        Task::perform(
+            async move {
+                let Some(handle) = rfd::AsyncFileDialog::new()
+                    .set_title(format!("Parameters for '{}'", name))
+                    .add_filter("CSV/JSON", &["csv", "json"])
+                    .add_filter("All files", &["*"])
+                    .pick_file()
+                    .await
+                else {
+                    return Ok(None);
+                };
+                let path = handle.path().to_path_buf();
+                let text = tokio::fs::read_to_string(&path)
+                    .await
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                let auras = tokio::task::spawn_blocking(move || {
+                    let rows = parse_params(&text).map_err(|e| e.to_string())?;
+                    template.expand(&rows).map_err(|e| e.to_string())
+                })
+                .await
+                .map_err(|e| format!("Task failed: {}", e))??;
+                Ok(Some((path, auras)))
+            },
+            Message::TemplateGenerated,
+        )
+    }
+
+    /// Add the auras generated from a template to the parsed list
+    pub(crate) fn add_generated_auras(
+        &mut self,
+        result: Result<Option<(PathBuf, Vec<WeakAura>)>, String>,
+    ) {
+        match result {
+            Ok(Some((path, auras))) => {
+                if auras.is_empty() {
+                    self.notifications.push(
+                        Notification::warning(format!("{} has no parameter rows", path.display()))
+                            .title("Templates"),
+                    );
+                    return;
+                }
+                let entries = auras
+                    .into_iter()
+                    .map(|aura| ParsedAuraEntry::new(aura, Some(path.clone())))
+                    .collect();
+                let (added, duplicates) = self.add_parsed_entries(entries);
+                notify_decode_results(
+                    &mut self.notifications,
+                    added,
+                    duplicates,
+                    self.config.duplicate_policy,
+                    &[],
+                    "generated",
+                );
+            }
+            Ok(None) => {}
+            Err(e) => self
+                .notifications
+                .push(Notification::error(e).title("Template Error")),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Transforms run on the selected auras before import.
+
+use crate::transforms::{TransformSpec, TransformStep};
+
+use super::super::state::OffsetAxis;
+use super::super::WeakAuraImporter;
+
+impl WeakAuraImporter {
+    /// Change one step of the transform pipeline and save the config
+    pub(crate) fn edit_transform(&mut self, idx: usize, edit: impl FnOnce(&mut TransformStep)) {
+        let Some(step) = self.config.transforms.get_mut(idx) else {
+            return;
+        };
+        edit(step);
+        self.save_config();
+    }
+
+    /// Move a step one position up or down and save the config
+    pub(crate) fn move_transform(&mut self, idx: usize, up: bool) {
+        let len = self.config.transforms.len();
+        let target = if up {
+            idx.checked_sub(1)
+        } else {
+            Some(idx + 1)
+        };
+        let Some(target) = target.filter(|&t| idx < len && t < len) else {
+            return;
+        };
+        self.config.transforms.swap(idx, target);
+        // Offset inputs are keyed by position
+        self.transforms.offset_inputs.clear();
+        self.save_config();
+    }
+
+    /// Keep the typed offset text and store it in the step once it parses
+    pub(crate) fn set_transform_offset(&mut self, idx: usize, axis: OffsetAxis, value: String) {
+        let parsed = match value.trim() {
+            "" | "-" => Some(0.0),
+            v => v.parse::<f64>().ok().filter(|n| n.is_finite()),
+        };
+        self.transforms.offset_inputs.insert((idx, axis), value);
+        let Some(offset) = parsed else {
+            return;
+        };
+        self.edit_transform(idx, |step| {
+            if let TransformSpec::Reposition(reposition) = &mut step.spec {
+                match axis {
+                    OffsetAxis::X => reposition.x_offset = offset,
+                    OffsetAxis::Y => reposition.y_offset = offset,
+                }
+            }
+        });
+    }
+}
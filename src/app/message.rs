@@ -4,24 +4,47 @@
 
 use std::path::PathBuf;
 
+use iced::widget::text_editor;
 use iced_toasts::ToastId;
 
-use crate::categories::UpdateCategory;
-use crate::saved_variables::ConflictAction;
+use crate::categories::CategoryKey;
+use crate::decoder::{AuraTextFormat, WeakAura};
+use crate::duplicates::DuplicatePolicy;
+use crate::file_diff::FileDiff;
+use crate::pack::MediaInstallResult;
+use crate::references::Reference;
+use crate::repair::{FallbackCandidate, RepairReport};
+use crate::saved_variables::{AuraTreeSort, BackupComparison, ConflictAction};
+use crate::scripting::ScriptReport;
+use crate::selection::SelectionMode;
+use crate::transforms::TransformSpec;
+use crate::tree_cache::ParsedTree;
 
-use super::state::{ImportUpdate, LoadingUpdate, RemovalUpdate, ScanUpdate};
+use super::state::{
+    AuraProperty, ConflictSort, ContextMenuTarget, ImportUpdate, ListColumn, ListGrouping,
+    LoadingUpdate, OffsetAxis, ParsedAuraEntry, RemovalUpdate, ReportFormat, ScanUpdate,
+    StatusFilter, StoredAuraEdit, TaskKind,
+};
 
 /// Messages for the iced application
 #[derive(Debug, Clone)]
 pub enum Message {
     // Input handling
-    InputTextChanged(String),
+    /// Typing, pasting or moving the cursor in the paste box
+    InputAction(text_editor::Action),
+    /// Check or auto-parse the input once typing pauses
+    InputIdleTick,
     WowPathChanged(String),
+    AnnotationFilterChanged(String),
 
     // File operations
     LoadFromFile,
     LoadFromFolder,
+    /// Pick any WeakAuras.lua to copy auras from in the second window
+    LoadFromSavedVariables,
     BrowseWowPath,
+    AddWowPath,
+    RemoveWowPath(PathBuf),
     SelectSavedVariablesFile(PathBuf),
     SelectSavedVariablesManually,
 
@@ -29,40 +52,167 @@ pub enum Message {
     TogglePasteInput,
     PasteFromClipboard,
     ParseInput,
+    /// Links from the input and the text downloaded from each, or why not
+    InputLinksDownloaded(Vec<(String, Result<String, String>)>),
+    /// Paste from clipboard replaces the input text instead of appending
+    SetPasteReplacesInput(bool),
+    SetAutoParse(bool),
+    // Folder scan settings
+    ToggleScanSettings,
+    ScanExtensionsChanged(String),
+    ScanIgnoreChanged(String),
+    ScanMaxSizeChanged(String),
+    ScanMaxDepthChanged(String),
+    SetScanFollowSymlinks(bool),
+
+    // Templates
+    ToggleTemplates,
+    /// Save a parsed aura as a template named after its ID
+    SaveAsTemplate(usize),
+    DeleteTemplate(String),
+    /// Pick a CSV or JSON parameter file and generate auras from a template
+    GenerateFromTemplate(String),
+    /// Generated auras and the parameter file (`None` when no file was chosen)
+    TemplateGenerated(Result<Option<(PathBuf, Vec<WeakAura>)>, String>),
     ClearInput,
+    /// Import strings posted to the browser companion endpoint
+    CompanionReceived(String),
 
     // View actions
     ToggleDecodedView,
-    SelectAuraForPreview(usize),
+    SetListGrouping(ListGrouping),
+    /// Show only the parsed auras matching a status chip
+    SetStatusFilter(StatusFilter),
+    /// Sort the parsed auras table by a column (ascending, descending, load order)
+    SortListBy(ListColumn),
+    StartColumnResize(ListColumn),
+    ColumnResize(f32),
+    EndColumnResize,
+    ToggleListGroupCollapsed(String),
 
     // Selection actions
     ToggleAuraSelection(usize),
-    SelectAllAuras,
-    DeselectAllAuras,
+    /// Click on a parsed aura's name (shift/ctrl select, plain click previews)
+    ParsedAuraClicked(usize),
+    /// Modifier keys changed, for shift/ctrl-clicks
+    ModifiersChanged(iced::keyboard::Modifiers),
+    /// Show or hide the child auras of a group in the list
+    ToggleEntryChildren(usize),
+    /// Include or exclude a child aura of a listed group (entry, child ID)
+    SetChildIncluded(usize, String, bool),
+    /// Import the only child of a wrapper group instead of the group
+    SetUnwrapGroup(usize, bool),
+    /// Select or deselect every valid aura in a list section
+    SetGroupSelection(String, bool),
+    ToggleSelectionRules,
+    SetRuleValidOnly(bool),
+    /// Leave trivial auras out of bulk selection, see `AppConfig::skip_trivial_auras`
+    SetSkipTrivialAuras(bool),
+    SetRuleGroupsOnly(bool),
+    SetRuleRegionType(String),
+    SetRuleSourceFile(String),
+    RuleIdPatternChanged(String),
+    ApplySelectionRule(SelectionMode),
     RemoveAuraFromList(usize),
     RemoveSelectedFromList,
+    /// Which copy is kept when an aura is loaded more than once
+    SetDuplicatePolicy(DuplicatePolicy),
+    /// Keep this copy of an aura listed more than once, removing the others
+    KeepDuplicate(usize),
+
+    // Staging area
+    /// Move the selected valid auras of the list to the staging area
+    StageSelected,
+    /// Move a staged aura back to the list
+    UnstageAura(usize),
+    ClearStaging,
+    /// Import every staged aura
+    ImportStaged,
+    /// Staged auras of a restored session
+    StagingRestored(Vec<ParsedAuraEntry>),
+
+    // Background clipboard collection
+    SetCollectClipboard(bool),
+    ClipboardTick,
+    MinimizeWindow,
+    WindowFocused,
+
+    // Transforms run before import
+    ToggleTransforms,
+    SetTransformEnabled(usize, bool),
+    /// Move a transform up (`true`) or down in the pipeline
+    MoveTransform(usize, bool),
+    /// Replace the parameters of a transform
+    SetTransformSpec(usize, TransformSpec),
+    /// Text typed into an offset input of a repositioning transform
+    TransformOffsetInput(usize, OffsetAxis, String),
 
     // Import actions
     ShowImportConfirm,
+    /// Existing group to import into (`None` for top level)
+    SetImportTargetGroup(Option<String>),
     HideImportConfirm,
     ConfirmImport,
+    /// Install the media of loaded packs together with the auras
+    SetInstallPackMedia(bool),
+    /// Overwrite installed media files that differ from the pack's
+    SetReplacePackMedia(bool),
+    /// Addon folder for pack media that does not name one
+    MediaFolderInput(String),
+    /// Write the WeakAurasCompanion data addon after import
+    SetWriteCompanionData(bool),
+    /// Save imports in chunks of [`DEFAULT_CHUNK_SIZE`](crate::chunking::DEFAULT_CHUNK_SIZE) auras
+    SetChunkedSaves(bool),
+    /// Pack names and install results, or the first error
+    PackMediaInstalled(Result<Vec<(String, MediaInstallResult)>, String>),
+    ShowImportReport,
+    HideImportReport,
+    CopyImportReport,
+    ExportImportReport(ReportFormat),
 
     // Conflict resolution
     HideConflictDialog,
     SetConflictAction(usize, ConflictAction),
     ToggleConflictExpanded(usize),
-    ToggleGlobalCategory(UpdateCategory),
-    ToggleConflictCategory(usize, UpdateCategory),
+    ToggleGlobalCategory(CategoryKey),
+    ToggleConflictCategory(usize, CategoryKey),
+    ToggleConflictAdvanced(usize),
+    ToggleConflictField(usize, String),
     SetAllConflictsAction(ConflictAction),
+    UpdateOnlyNewerConflicts,
+    SetConflictSort(ConflictSort),
+    SetConflictOnlyTriggers(bool),
+    SetConflictOnlyGroups(bool),
+    SetConflictPage(usize),
+    SetPreservePosition(bool),
+    SetKeepUserConfig(bool),
+    /// Confirm writing a SavedVariables file from a newer WeakAuras release
+    AllowNewerDbVersion,
+    ApplyConflictPreset(String),
+    SetConflictPresetName(String),
+    SetConflictPresetAction(ConflictAction),
+    SaveConflictPreset,
+    DeleteConflictPreset,
+    SetAutoPreset(bool),
     ConfirmConflictResolutions,
 
     // Removal actions
-    ToggleAuraForRemoval(String),
+    /// Check or uncheck an aura (and its descendants) for removal
+    SetAuraRemovalChecked(String, bool),
+    /// Click on an existing aura's row (toggles it, shift checks a range)
+    TreeNodeClicked(String),
     SelectAllForRemoval,
     DeselectAllForRemoval,
     ShowRemoveConfirm,
     HideRemoveConfirm,
     ConfirmRemoval,
+    /// Remove the selected groups only, promoting their children to top level
+    ConfirmGroupOnlyRemoval,
+    SetRemovalExportFirst(bool),
+    /// Anchor auras anchored to the removed ones to the screen
+    SetRemovalReanchor(bool),
+    /// Export file chosen before removing (path, keep children)
+    RemovalExportPathSelected(Option<PathBuf>, bool),
 
     // Setup wizard
     ShowSetupWizard,
@@ -72,6 +222,40 @@ pub enum Message {
     ToggleGroupExpanded(String),
     ExpandAllGroups,
     CollapseAllGroups,
+    SetTreeSort(AuraTreeSort),
+    /// Show how an aura differs from the file's backup
+    CompareWithBackup(String),
+    BackupComparisonLoaded(Result<BackupComparison, String>),
+    CloseBackupComparison,
+    /// Pick two SavedVariables files and show what changed between them
+    CompareFiles,
+    FilesCompared(Result<Option<FileDiff>, String>),
+    CloseFileDiff,
+    /// Salvage the auras of a selected file that failed to load and write them back
+    RepairSavedVariables,
+    SavedVariablesRepaired(Result<Option<(RepairReport, PathBuf)>, String>),
+    /// Replace a selected file that failed to parse with its newest parsable copy
+    RestoreFallback,
+    FallbackRestored(Result<FallbackCandidate, String>),
+
+    // Scripts
+    ToggleScripts,
+    /// Load a script of the scripts folder into the editor
+    ScriptSelected(PathBuf),
+    ScriptAction(text_editor::Action),
+    /// Run the script in the editor; `true` for a dry run
+    RunScript(bool),
+    ScriptFinished(Result<(ScriptReport, Option<StoredAuraEdit>), String>),
+
+    // Tags & notes
+    /// Open the annotation editor for (uid, display name)
+    EditAnnotations(String, String),
+    CloseAnnotationEditor,
+    AnnotationTagInputChanged(String),
+    AddAnnotationTag,
+    AddExistingAnnotationTag(String),
+    RemoveAnnotationTag(String),
+    AnnotationNoteChanged(String),
 
     // Async task results
     LoadingUpdate(LoadingUpdate),
@@ -79,11 +263,22 @@ pub enum Message {
     ScanUpdate(ScanUpdate),
     RemovalUpdate(RemovalUpdate),
 
+    // Tasks panel
+    ToggleTasks,
+    /// Abort the running job of a kind
+    CancelTask(TaskKind),
+    /// Move the Tasks panel into its own window
+    DetachTasks,
+    /// Close the Tasks window and show the panel in the main window again
+    AttachTasks,
+
     // File dialog results
     FileSelected(Option<PathBuf>),
     FolderSelected(Option<PathBuf>),
+    SavedVariablesSourceSelected(Option<PathBuf>),
     WowPathSelected(Option<PathBuf>),
     ManualSvSelected(Option<PathBuf>),
+    ImportReportPathSelected(ReportFormat, Option<PathBuf>),
 
     // Sidebar resize
     StartSidebarResize,
@@ -92,6 +287,103 @@ pub enum Message {
     HoverResizeEdge,
     UnhoverResizeEdge,
 
-    // Toast notifications
+    // Notifications
     DismissToast(ToastId),
+    ToggleNotifications,
+    /// Expand or collapse the details of a notification in the history panel
+    ToggleNotificationDetails(u64),
+    ClearNotifications,
+    ToggleProblems,
+    CopyProblems,
+    ClearProblems,
+
+    // Property editor
+    /// Open the property editor for a parsed aura
+    EditAuraProperties(usize),
+    AuraPropertyChanged(AuraProperty, String),
+    /// Validate the edited properties and write them into the aura
+    ApplyAuraProperties,
+    CloseAuraProperties,
+
+    // Copying aura data
+    /// Copy the data of the aura shown in the decoded panel
+    CopyDecodedData(AuraTextFormat),
+    /// Copy the stored data of an existing aura
+    CopyStoredAura(String, AuraTextFormat),
+    StoredAuraTextLoaded(Result<String, String>),
+
+    // Context menus
+    /// Open the right-click menu of a list entry or tree node (closes it when already open)
+    OpenContextMenu(ContextMenuTarget),
+    CloseContextMenu,
+    /// Show a parsed aura in the decoded panel
+    PreviewParsedAura(usize),
+    /// Save a parsed aura and its children to a Lua file
+    ExportParsedAura(usize),
+    StartRenameStoredAura,
+    RenameStoredAuraInputChanged(String),
+    ConfirmRenameStoredAura,
+    /// Move an existing aura into a group (aura ID, group ID)
+    MoveStoredAura(String, String),
+    /// Ask to remove an existing aura and its descendants
+    RemoveStoredAura(String),
+    /// Save an existing aura and its descendants to a Lua file
+    ExportStoredAura(String),
+    CopyAuraId(String),
+    StoredAuraEdited(Result<StoredAuraEdit, String>),
+    /// Export finished (`None` when no file was chosen)
+    AuraExported(Result<Option<PathBuf>, String>),
+    /// Save fields of the checked existing auras (all when none) to a CSV file
+    ExportFieldsCsv,
+    /// Pick an edited CSV sheet to apply to the existing auras
+    ApplyFieldsCsv,
+    FieldsCsvSelected(Option<PathBuf>),
+
+    // Raw data editor
+    /// Open the Lua/JSON editor for a parsed aura's data
+    EditRawData(usize),
+    RawDataFormatChanged(AuraTextFormat),
+    RawDataAction(text_editor::Action),
+    /// Parse and validate the edited text and replace the aura's data
+    ApplyRawData,
+    CloseRawData,
+
+    // Find references
+    ToggleReferences,
+    ReferenceQueryChanged(String),
+    /// Search the selected SavedVariables file for the query
+    SearchReferences,
+    ReferencesFound(Result<Vec<Reference>, String>),
+
+    // Crash recovery
+    /// Restore the SavedVariables file open during the crash from its backup
+    RestoreCrashBackup,
+    DismissCrashRecovery,
+
+    // Denied saves
+    /// Start the tool again as administrator (Windows)
+    RestartElevated,
+    /// Write the denied save to a file chosen by the user
+    SaveDeniedElsewhere,
+    DeniedSaveWritten(Result<Option<PathBuf>, String>),
+    DismissDeniedSave,
+
+    // Side-by-side window
+    /// Open the second window, or focus it when it is open
+    OpenSideWindow,
+    /// Show a SavedVariables file in the second window
+    SideWindowFileSelected(PathBuf),
+    SideWindowLoaded(Result<ParsedTree, String>),
+    /// Check or uncheck an aura (and its descendants) in the second window
+    SetSideAuraChecked(String, bool),
+    /// Add the checked auras of the second window to the import list
+    CopySideAuras,
+    SideAurasExtracted(Result<Vec<WeakAura>, String>),
+    WindowClosed(iced::window::Id),
+
+    // Session auto-save
+    /// Periodic tick to persist the loaded auras
+    AutosaveSession,
+    RestoreSession,
+    DiscardSession,
 }
@@ -2,114 +2,323 @@
 
 mod actions;
 mod message;
+mod notifications;
 mod state;
 mod ui;
 
 pub use message::Message;
 
 use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use arboard::Clipboard;
-use iced::widget::{column, container, row, text};
-use iced::{Element, Length, Task, Theme};
-use iced_toasts::{toast_container, ToastContainer};
+use iced::widget::{column, container, row, text, text_editor};
+use iced::{keyboard, window, Element, Length, Size, Subscription, Task, Theme};
 
-use crate::saved_variables::{AuraTreeNode, ConflictAction};
+use crate::annotations::AnnotationStore;
+use crate::chunking::DEFAULT_CHUNK_SIZE;
+use crate::config::AppConfig;
+use crate::crash;
+use crate::discovery;
+use crate::saved_variables::{
+    set_tree_checked, AuraTreeNode, ConflictAction, SavedVariablesManager,
+};
+use crate::session::Session;
+use crate::templates::TemplateStore;
 use crate::theme as app_theme;
 
-pub use state::{ConflictResolutionUI, ParsedAuraEntry};
+use notifications::{Notification, Notifications};
 use state::{
-    ConflictState, RemovalState, SavedVariablesState, SidebarState, StatusState, TaskProgress,
-    UiVisibility,
+    AnnotationState, ClipboardWatchState, ConflictState, ContextMenuState, InputCheckState,
+    ListSelectionState, ListViewState, MediaPackState, PropertyEditorState, RawEditorState,
+    ReferenceSearchState, RemovalState, SavedVariablesState, ScanSettingsState, ScriptsState,
+    SelectionRuleState, SessionState, SideWindowState, SidebarState, StagingState, StatusState,
+    TaskProgress, TemplateState, TransformsState, TreeSelectionState, UiVisibility,
 };
 
+/// How often the loaded auras are written to the session file
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How often the clipboard is checked while collecting in the background
+const CLIPBOARD_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often a pause in typing is looked for while the input is edited
+const INPUT_IDLE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Window title
+const APP_TITLE: &str = "WeakAuras Mass Importer";
+
+/// Pick-list label meaning "no filter" in the selection rules panel and "no
+/// choice" in the transforms panel
+pub(crate) const RULE_ANY: &str = "Any";
+
+/// Pick-list label meaning "leave the sound channel unchanged" in the transforms panel
+pub(crate) const SOUND_CHANNEL_KEEP: &str = "Keep channel";
+pub use state::{ConflictResolutionUI, ParsedAuraEntry};
+
+/// Command-line options for a GUI launch
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    /// Address of the browser companion endpoint (`--listen`)
+    pub listen: Option<String>,
+    /// Import strings to preload, e.g. from a `weakaura://` link
+    pub import_text: Option<String>,
+}
+
 /// Main application state
 pub struct WeakAuraImporter {
     /// Input text area content
     pub(crate) input_text: String,
+    /// Paste box editing `input_text`
+    pub(crate) input_editor: text_editor::Content,
+    /// Leading part of `input_text` that was already parsed; parsing again
+    /// only decodes what was added after it
+    pub(crate) parsed_input: String,
     /// Parsed auras from input
     pub(crate) parsed_auras: Vec<ParsedAuraEntry>,
     /// Selected aura index for preview
     pub(crate) selected_aura_index: Option<usize>,
     /// Clipboard handler
     pub(crate) clipboard: Option<Clipboard>,
-    /// Toast notification container
-    pub(crate) toasts: ToastContainer<'static, Message>,
+    /// Toasts and notification history
+    pub(crate) notifications: Notifications,
     /// UI visibility state
     pub(crate) ui: UiVisibility,
     /// Sidebar state
     pub(crate) sidebar: SidebarState,
+    /// Parsed aura list view state
+    pub(crate) list_view: ListViewState,
+    /// Shift/ctrl-click selection of the parsed aura list
+    pub(crate) list_selection: ListSelectionState,
+    /// Checked auras of the existing auras tree
+    pub(crate) tree_selection: TreeSelectionState,
+    /// Modifier keys held, for shift/ctrl-clicks
+    pub(crate) modifiers: keyboard::Modifiers,
+    /// Bulk selection rule inputs
+    pub(crate) selection_rule: SelectionRuleState,
     /// Task progress state
     pub(crate) tasks: TaskProgress,
     /// Conflict resolution state
     pub(crate) conflicts: ConflictState,
     /// Aura removal state
     pub(crate) removal: RemovalState,
+    /// Find-references search
+    pub(crate) references: ReferenceSearchState,
+    /// Property editor of the decoded panel
+    pub(crate) property_editor: PropertyEditorState,
+    /// Lua/JSON editor of the decoded panel
+    pub(crate) raw_editor: RawEditorState,
+    /// Right-click menu of list and tree items
+    pub(crate) context_menu: ContextMenuState,
     /// SavedVariables state
     pub(crate) saved_vars: SavedVariablesState,
     /// Status bar state
     pub(crate) status: StatusState,
+    /// Persistent user configuration
+    pub(crate) config: AppConfig,
+    /// Aura tags and notes
+    pub(crate) annotations: AnnotationState,
+    /// Saved aura templates
+    pub(crate) templates: TemplateState,
+    /// Scripts dialog
+    pub(crate) scripts: ScriptsState,
+    /// Auto-saved working session
+    pub(crate) session: SessionState,
+    /// Transforms panel inputs
+    pub(crate) transforms: TransformsState,
+    /// Auras staged for import
+    pub(crate) staging: StagingState,
+    /// Media bundled with loaded packs
+    pub(crate) media_packs: MediaPackState,
+    /// Background clipboard collection
+    pub(crate) clipboard_watch: ClipboardWatchState,
+    /// Validity of the input text while it is typed
+    pub(crate) input_check: InputCheckState,
+    /// Folder scan settings being edited
+    pub(crate) scan_settings: ScanSettingsState,
+    /// The main window, once opened
+    pub(crate) main_window: Option<window::Id>,
+    /// Second window with another SavedVariables file
+    pub(crate) side_window: SideWindowState,
 }
 
 impl Default for WeakAuraImporter {
     fn default() -> Self {
         Self {
             input_text: String::new(),
+            input_editor: text_editor::Content::new(),
+            parsed_input: String::new(),
             parsed_auras: Vec::new(),
             selected_aura_index: None,
             clipboard: Clipboard::new().ok(),
-            toasts: toast_container(Message::DismissToast),
+            notifications: Notifications::default(),
             ui: UiVisibility {
                 show_setup_wizard: true,
                 ..UiVisibility::default()
             },
             sidebar: SidebarState::default(),
+            list_view: ListViewState::default(),
+            list_selection: ListSelectionState::default(),
+            tree_selection: TreeSelectionState::default(),
+            modifiers: keyboard::Modifiers::default(),
+            selection_rule: SelectionRuleState::default(),
             tasks: TaskProgress::default(),
             conflicts: ConflictState::default(),
             removal: RemovalState::default(),
+            references: ReferenceSearchState::default(),
+            property_editor: PropertyEditorState::default(),
+            raw_editor: RawEditorState::default(),
+            context_menu: ContextMenuState::default(),
             saved_vars: SavedVariablesState::default(),
             status: StatusState::default(),
+            config: AppConfig::default(),
+            annotations: AnnotationState::default(),
+            templates: TemplateState::default(),
+            scripts: ScriptsState::default(),
+            session: SessionState::default(),
+            transforms: TransformsState::default(),
+            staging: StagingState::default(),
+            media_packs: MediaPackState::default(),
+            clipboard_watch: ClipboardWatchState::default(),
+            input_check: InputCheckState::default(),
+            scan_settings: ScanSettingsState::default(),
+            main_window: None,
+            side_window: SideWindowState::default(),
         }
     }
 }
 
 impl WeakAuraImporter {
     /// Create new application with initial state
-    pub fn new() -> (Self, Task<Message>) {
-        let mut app = Self::default();
+    pub fn new(launch: &LaunchOptions) -> (Self, Task<Message>) {
+        let mut app = Self {
+            config: AppConfig::load(),
+            ..Self::default()
+        };
 
-        // Auto-discover WoW installations
-        let wow_paths = crate::saved_variables::SavedVariablesManager::find_wow_paths();
-        if let Some(first_path) = wow_paths.first() {
+        crate::net::set_offline(app.config.offline);
+        if app.config.collect_clipboard {
+            // Text already on the clipboard is not collected
+            app.clipboard_watch.last_text = app.clipboard.as_mut().and_then(|c| c.get_text().ok());
+        }
+        app.annotations.store = AnnotationStore::load();
+        app.templates.store = TemplateStore::load();
+        app.conflicts.category_set = app.config.category_set();
+        // Bookmarked paths first, then auto-discovered WoW installations
+        app.saved_vars.wow_paths = discovery::dedup_paths(
+            app.config
+                .wow_paths
+                .iter()
+                .cloned()
+                .chain(SavedVariablesManager::find_wow_paths()),
+        );
+        if let Some(first_path) = app.saved_vars.wow_paths.first() {
             app.saved_vars.wow_path = first_path.to_string_lossy().to_string();
-            app.scan_saved_variables_sync();
         }
+        app.scan_saved_variables_sync();
+        app.status.crash_recovery = crash::take_pending();
+        let session = Session::load();
+        if !session.is_empty() {
+            app.session.pending_restore = Some(session);
+        }
+
+        let (main_window, open_main_window) = window::open(window::Settings {
+            size: Size::new(1000.0, 700.0),
+            ..window::Settings::default()
+        });
+        app.main_window = Some(main_window);
+
+        let mut tasks = vec![
+            open_main_window.discard(),
+            app.listen_for_instances(),
+            app.preparse_saved_variables(),
+        ];
+        if let Some(addr) = &launch.listen {
+            tasks.push(app.start_companion(addr));
+        }
+        if let Some(text) = &launch.import_text {
+            app.receive_companion_strings(text);
+        }
+        (app, Task::batch(tasks))
+    }
+
+    /// Periodic auto-save of the working session, closed windows and pauses
+    /// in typing
+    pub fn subscription(&self) -> Subscription<Message> {
+        let autosave = iced::time::every(AUTOSAVE_INTERVAL).map(|_| Message::AutosaveSession);
+        let closed = window::close_events().map(Message::WindowClosed);
+        let modifiers = iced::event::listen_with(|event, _, _| match event {
+            iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+            _ => None,
+        });
+        // Only polled while a pause in typing is awaited
+        let input_idle = if self.input_check.edited_at.is_some() {
+            iced::time::every(INPUT_IDLE_POLL_INTERVAL).map(|_| Message::InputIdleTick)
+        } else {
+            Subscription::none()
+        };
+        if !self.config.collect_clipboard {
+            return Subscription::batch([autosave, closed, modifiers, input_idle]);
+        }
+        Subscription::batch([
+            autosave,
+            closed,
+            modifiers,
+            input_idle,
+            iced::time::every(CLIPBOARD_POLL_INTERVAL).map(|_| Message::ClipboardTick),
+            iced::event::listen_with(|event, _, _| match event {
+                iced::Event::Window(window::Event::Focused) => Some(Message::WindowFocused),
+                _ => None,
+            }),
+        ])
+    }
 
-        (app, Task::none())
+    /// Window title, with the count of auras collected in the background
+    pub fn title(&self, window: window::Id) -> String {
+        if self.side_window.id == Some(window) {
+            return match &self.side_window.path {
+                Some(path) => format!("{} - {}", path.display(), APP_TITLE),
+                None => format!("Second Window - {}", APP_TITLE),
+            };
+        }
+        if self.tasks.window == Some(window) {
+            return format!("Tasks - {}", APP_TITLE);
+        }
+        match self.clipboard_watch.unseen {
+            0 => APP_TITLE.to_string(),
+            unseen => format!("({}) {}", unseen, APP_TITLE),
+        }
     }
 
-    /// Return the application theme
-    pub fn theme(&self) -> Theme {
+    /// Return the application theme (the same for every window)
+    pub fn theme(&self, _window: window::Id) -> Theme {
         app_theme::create_theme()
     }
 
     /// Update the application state based on messages
     pub fn update(&mut self, message: Message) -> Task<Message> {
+        crash::set_context(self.crash_summary());
         match message {
             // Input handling
-            Message::InputTextChanged(text) => {
-                self.input_text = text;
-                Task::none()
-            }
+            Message::InputAction(action) => self.edit_input(action),
+            Message::InputIdleTick => self.check_input_when_idle(),
             Message::WowPathChanged(path) => {
                 self.saved_vars.wow_path = path;
                 self.scan_saved_variables_sync();
+                self.preparse_saved_variables()
+            }
+            Message::AnnotationFilterChanged(filter) => {
+                self.annotations.filter = filter;
                 Task::none()
             }
 
             // File operations
             Message::LoadFromFile => self.load_from_file_async(),
             Message::LoadFromFolder => self.load_from_folder_async(),
+            Message::LoadFromSavedVariables => self.load_from_saved_variables_async(),
             Message::BrowseWowPath => Task::perform(
                 async {
                     rfd::AsyncFileDialog::new()
@@ -119,9 +328,20 @@ impl WeakAuraImporter {
                 },
                 Message::WowPathSelected,
             ),
+            Message::AddWowPath => {
+                let path = PathBuf::from(self.saved_vars.wow_path.trim());
+                self.add_wow_path(path);
+                self.preparse_saved_variables()
+            }
+            Message::RemoveWowPath(path) => {
+                self.remove_wow_path(&path);
+                self.preparse_saved_variables()
+            }
             Message::SelectSavedVariablesFile(path) => {
                 self.saved_vars.selected_path = Some(path);
-                self.removal.selected_ids.clear();
+                self.saved_vars.restored_from = None;
+                self.refresh_writable();
+                self.tree_selection.clear();
                 self.removal.pending_ids.clear();
                 // Don't load yet - wait for Continue button
                 Task::none()
@@ -152,17 +372,31 @@ impl WeakAuraImporter {
                     Task::none()
                 }
             }
+            Message::SavedVariablesSourceSelected(path) => {
+                if let Some(p) = path {
+                    self.open_saved_variables_source(p)
+                } else {
+                    Task::none()
+                }
+            }
             Message::WowPathSelected(path) => {
                 if let Some(p) = path {
                     self.saved_vars.wow_path = p.to_string_lossy().to_string();
-                    self.scan_saved_variables_sync();
+                    self.add_wow_path(p);
+                }
+                self.preparse_saved_variables()
+            }
+            Message::ImportReportPathSelected(format, path) => {
+                if let Some(p) = path {
+                    self.export_import_report(format, &p);
                 }
                 Task::none()
             }
             Message::ManualSvSelected(path) => {
                 if let Some(p) = path {
                     self.saved_vars.selected_path = Some(p);
-                    self.removal.selected_ids.clear();
+                    self.saved_vars.restored_from = None;
+                    self.tree_selection.clear();
                     self.removal.pending_ids.clear();
                     return self.load_existing_auras_async();
                 }
@@ -186,17 +420,81 @@ impl WeakAuraImporter {
                 self.ui.show_paste_input = !self.ui.show_paste_input;
                 Task::none()
             }
-            Message::PasteFromClipboard => {
-                self.paste_from_clipboard();
+            Message::PasteFromClipboard => self.paste_from_clipboard(),
+            Message::ParseInput => self.parse_input(),
+            Message::InputLinksDownloaded(results) => {
+                self.on_input_links_downloaded(results);
+                Task::none()
+            }
+            Message::SetPasteReplacesInput(replace) => {
+                self.config.paste_replaces_input = replace;
+                self.save_config();
+                Task::none()
+            }
+            Message::SetSkipTrivialAuras(skip) => {
+                self.set_skip_trivial_auras(skip);
                 Task::none()
             }
-            Message::ParseInput => {
-                self.parse_input();
+            Message::SetAutoParse(enabled) => {
+                self.config.auto_parse = enabled;
+                self.save_config();
+                Task::none()
+            }
+            Message::ToggleScanSettings => {
+                self.toggle_scan_settings();
+                Task::none()
+            }
+
+            // Templates
+            Message::ToggleTemplates => {
+                self.templates.show = !self.templates.show;
+                Task::none()
+            }
+            Message::SaveAsTemplate(idx) => {
+                self.save_as_template(idx);
+                Task::none()
+            }
+            Message::DeleteTemplate(name) => {
+                self.delete_template(&name);
+                Task::none()
+            }
+            Message::GenerateFromTemplate(name) => self.generate_from_template_async(name),
+            Message::TemplateGenerated(result) => {
+                self.add_generated_auras(result);
+                Task::none()
+            }
+            Message::ScanExtensionsChanged(extensions) => {
+                self.scan_settings.extensions = extensions;
+                self.apply_scan_settings();
+                Task::none()
+            }
+            Message::ScanIgnoreChanged(ignore) => {
+                self.scan_settings.ignore = ignore;
+                self.apply_scan_settings();
+                Task::none()
+            }
+            Message::ScanMaxSizeChanged(size) => {
+                self.scan_settings.max_size_mb = size;
+                self.apply_scan_settings();
+                Task::none()
+            }
+            Message::ScanMaxDepthChanged(depth) => {
+                self.scan_settings.max_depth = depth;
+                self.apply_scan_settings();
+                Task::none()
+            }
+            Message::SetScanFollowSymlinks(follow) => {
+                self.config.folder_scan.follow_symlinks = follow;
+                self.save_config();
                 Task::none()
             }
             Message::ClearInput => {
                 self.input_text.clear();
+                self.input_editor = text_editor::Content::new();
+                self.parsed_input.clear();
+                self.input_check = Default::default();
                 self.parsed_auras.clear();
+                self.media_packs.packs.clear();
                 self.ui.show_paste_input = false;
                 Task::none()
             }
@@ -206,32 +504,104 @@ impl WeakAuraImporter {
                 self.ui.show_decoded_view = !self.ui.show_decoded_view;
                 Task::none()
             }
-            Message::SelectAuraForPreview(idx) => {
-                self.selected_aura_index = Some(idx);
+            Message::SetListGrouping(grouping) => {
+                self.list_view.grouping = grouping;
+                self.list_view.collapsed_groups.clear();
+                Task::none()
+            }
+            Message::SetStatusFilter(filter) => {
+                self.list_view.status_filter = filter;
+                Task::none()
+            }
+            Message::SortListBy(column) => {
+                self.sort_list_by(column);
+                Task::none()
+            }
+            Message::StartColumnResize(column) => {
+                self.start_column_resize(column);
+                Task::none()
+            }
+            Message::ColumnResize(x) => {
+                self.resize_column(x);
+                Task::none()
+            }
+            Message::EndColumnResize => {
+                self.list_view.resizing = None;
+                Task::none()
+            }
+            Message::ToggleListGroupCollapsed(key) => {
+                if !self.list_view.collapsed_groups.remove(&key) {
+                    self.list_view.collapsed_groups.insert(key);
+                }
                 Task::none()
             }
 
             // Selection actions
             Message::ToggleAuraSelection(idx) => {
+                if let Some(entry) = self.parsed_auras.get(idx) {
+                    let selected = !entry.selected;
+                    self.set_parsed_aura_selected(idx, selected);
+                }
+                Task::none()
+            }
+            Message::ParsedAuraClicked(idx) => {
+                self.click_parsed_aura(idx);
+                Task::none()
+            }
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                Task::none()
+            }
+            Message::ToggleEntryChildren(idx) => {
                 if let Some(entry) = self.parsed_auras.get_mut(idx) {
-                    entry.selected = !entry.selected;
+                    entry.show_children = !entry.show_children;
                 }
                 Task::none()
             }
-            Message::SelectAllAuras => {
-                for entry in &mut self.parsed_auras {
-                    if entry.validation.is_valid {
-                        entry.selected = true;
-                    }
+            Message::SetChildIncluded(idx, id, included) => {
+                if let Some(entry) = self.parsed_auras.get_mut(idx) {
+                    entry.set_child_included(&id, included);
                 }
                 Task::none()
             }
-            Message::DeselectAllAuras => {
-                for entry in &mut self.parsed_auras {
-                    entry.selected = false;
+            Message::SetUnwrapGroup(idx, unwrap) => {
+                if let Some(entry) = self.parsed_auras.get_mut(idx) {
+                    entry.unwrap_group = unwrap;
                 }
                 Task::none()
             }
+            Message::SetGroupSelection(key, selected) => {
+                self.set_group_selection(&key, selected);
+                Task::none()
+            }
+            Message::ToggleSelectionRules => {
+                self.ui.show_selection_rules = !self.ui.show_selection_rules;
+                Task::none()
+            }
+            Message::SetRuleValidOnly(value) => {
+                self.selection_rule.valid_only = value;
+                Task::none()
+            }
+            Message::SetRuleGroupsOnly(value) => {
+                self.selection_rule.groups_only = value;
+                Task::none()
+            }
+            Message::SetRuleRegionType(region_type) => {
+                self.selection_rule.region_type = (region_type != RULE_ANY).then_some(region_type);
+                Task::none()
+            }
+            Message::SetRuleSourceFile(source_file) => {
+                self.selection_rule.source_file = (source_file != RULE_ANY).then_some(source_file);
+                Task::none()
+            }
+            Message::RuleIdPatternChanged(pattern) => {
+                self.selection_rule.id_pattern = pattern;
+                Task::none()
+            }
+            Message::ApplySelectionRule(mode) => {
+                self.apply_selection_rule(mode);
+                Task::none()
+            }
             Message::RemoveAuraFromList(idx) => {
                 if idx < self.parsed_auras.len() {
                     self.parsed_auras.remove(idx);
@@ -240,28 +610,160 @@ impl WeakAuraImporter {
                         Some(sel) if sel > idx => self.selected_aura_index = Some(sel - 1),
                         _ => {}
                     }
+                    self.property_editor = PropertyEditorState::default();
+                    self.raw_editor = RawEditorState::default();
+                    self.context_menu = ContextMenuState::default();
+                    self.list_selection = ListSelectionState::default();
                 }
                 Task::none()
             }
+            Message::SetDuplicatePolicy(policy) => {
+                self.set_duplicate_policy(policy);
+                Task::none()
+            }
+            Message::KeepDuplicate(idx) => {
+                self.keep_duplicate(idx);
+                Task::none()
+            }
             Message::RemoveSelectedFromList => {
                 self.parsed_auras.retain(|e| !e.selected);
                 self.selected_aura_index = None;
+                self.property_editor = PropertyEditorState::default();
+                self.raw_editor = RawEditorState::default();
+                self.context_menu = ContextMenuState::default();
+                self.list_selection = ListSelectionState::default();
+                Task::none()
+            }
+
+            // Staging area
+            Message::StageSelected => {
+                self.stage_selected();
+                Task::none()
+            }
+            Message::UnstageAura(idx) => {
+                self.unstage_aura(idx);
+                Task::none()
+            }
+            Message::ClearStaging => {
+                self.staging.entries.clear();
+                Task::none()
+            }
+            Message::ImportStaged => {
+                self.staging.importing = true;
+                self.saved_vars.target_group = None;
+                self.check_import_media();
+                self.check_import_nesting();
+                self.check_import_duplicates();
+                self.ui.show_import_confirm = true;
+                Task::none()
+            }
+            Message::StagingRestored(entries) => {
+                self.restore_staging(entries);
+                Task::none()
+            }
+
+            // Transforms run before import
+            Message::ToggleTransforms => {
+                self.ui.show_transforms = !self.ui.show_transforms;
+                Task::none()
+            }
+            Message::SetTransformEnabled(idx, enabled) => {
+                self.edit_transform(idx, |step| step.enabled = enabled);
+                Task::none()
+            }
+            Message::MoveTransform(idx, up) => {
+                self.move_transform(idx, up);
+                Task::none()
+            }
+            Message::SetTransformSpec(idx, spec) => {
+                self.edit_transform(idx, |step| step.spec = spec);
+                Task::none()
+            }
+            Message::TransformOffsetInput(idx, axis, value) => {
+                self.set_transform_offset(idx, axis, value);
                 Task::none()
             }
 
             // Import actions
             Message::ShowImportConfirm => {
+                self.staging.importing = false;
+                self.saved_vars.target_group = None;
+                self.check_import_media();
+                self.check_import_nesting();
+                self.check_import_duplicates();
                 self.ui.show_import_confirm = true;
                 Task::none()
             }
+            Message::SetImportTargetGroup(group) => {
+                self.saved_vars.target_group = group;
+                self.check_import_nesting();
+                Task::none()
+            }
             Message::HideImportConfirm => {
                 self.ui.show_import_confirm = false;
                 Task::none()
             }
             Message::ConfirmImport => {
                 self.ui.show_import_confirm = false;
-                self.import_auras_async()
+                let import = self.import_auras_async();
+                if self.tasks.is_importing {
+                    Task::batch([import, self.install_pack_media_async()])
+                } else {
+                    import
+                }
+            }
+            Message::SetInstallPackMedia(install) => {
+                self.media_packs.install = install;
+                self.check_import_media();
+                Task::none()
+            }
+            Message::SetReplacePackMedia(replace) => {
+                self.media_packs.replace_differing = replace;
+                Task::none()
+            }
+            Message::MediaFolderInput(folder) => {
+                self.config.media_folder = folder;
+                self.save_config();
+                self.check_import_media();
+                Task::none()
             }
+            Message::SetWriteCompanionData(enabled) => {
+                self.config.write_companion_data = enabled;
+                self.save_config();
+                Task::none()
+            }
+            Message::SetChunkedSaves(enabled) => {
+                self.config.import_options.chunk_size = enabled.then_some(DEFAULT_CHUNK_SIZE);
+                self.save_config();
+                Task::none()
+            }
+            Message::PackMediaInstalled(result) => {
+                self.on_pack_media_installed(result);
+                Task::none()
+            }
+            Message::ShowImportReport => {
+                self.ui.show_import_report = self.status.last_import_result.is_some();
+                Task::none()
+            }
+            Message::HideImportReport => {
+                self.ui.show_import_report = false;
+                Task::none()
+            }
+            Message::CopyImportReport => {
+                self.copy_import_report();
+                Task::none()
+            }
+            Message::ExportImportReport(format) => Task::perform(
+                async move {
+                    rfd::AsyncFileDialog::new()
+                        .add_filter(format.extension().to_uppercase(), &[format.extension()])
+                        .set_file_name(format!("import-report.{}", format.extension()))
+                        .save_file()
+                        .await
+                        .map(|h| h.path().to_path_buf())
+                },
+                move |path| Message::ImportReportPathSelected(format, path),
+            ),
 
             // Conflict resolution
             Message::HideConflictDialog => {
@@ -291,9 +793,12 @@ impl WeakAuraImporter {
                 } else {
                     self.conflicts.global_categories.insert(category);
                 }
-                // Update all resolutions that use UpdateSelected
+                // Update all resolutions that use categories (Merge falls back to them)
                 for res in &mut self.conflicts.resolutions {
-                    if res.action == ConflictAction::UpdateSelected {
+                    if matches!(
+                        res.action,
+                        ConflictAction::UpdateSelected | ConflictAction::Merge
+                    ) {
                         res.categories = self.conflicts.global_categories.clone();
                     }
                 }
@@ -309,8 +814,22 @@ impl WeakAuraImporter {
                 }
                 Task::none()
             }
+            Message::ToggleConflictAdvanced(idx) => {
+                self.toggle_conflict_advanced(idx);
+                Task::none()
+            }
+            Message::ToggleConflictField(idx, field) => {
+                if let Some(res) = self.conflicts.resolutions.get_mut(idx) {
+                    if !res.fields.remove(&field) {
+                        res.fields.insert(field);
+                    }
+                }
+                Task::none()
+            }
             Message::SetAllConflictsAction(action) => {
-                for res in &mut self.conflicts.resolutions {
+                // Bulk actions apply to the conflicts matching the current filters
+                for idx in self.conflicts.visible_indices() {
+                    let res = &mut self.conflicts.resolutions[idx];
                     res.action = action;
                     if action == ConflictAction::UpdateSelected {
                         res.categories = self.conflicts.global_categories.clone();
@@ -318,15 +837,84 @@ impl WeakAuraImporter {
                 }
                 Task::none()
             }
+            Message::SetConflictSort(sort) => {
+                self.conflicts.sort = sort;
+                self.conflicts.page = 0;
+                Task::none()
+            }
+            Message::SetConflictOnlyTriggers(only) => {
+                self.conflicts.only_trigger_changes = only;
+                self.conflicts.page = 0;
+                Task::none()
+            }
+            Message::SetConflictOnlyGroups(only) => {
+                self.conflicts.only_groups = only;
+                self.conflicts.page = 0;
+                Task::none()
+            }
+            Message::SetConflictPage(page) => {
+                self.conflicts.page = page;
+                Task::none()
+            }
+            Message::UpdateOnlyNewerConflicts => {
+                self.update_only_newer_conflicts();
+                Task::none()
+            }
+            Message::AllowNewerDbVersion => {
+                self.saved_vars.allow_newer_db_version = true;
+                Task::none()
+            }
+            Message::SetPreservePosition(preserve) => {
+                self.config.preserve_position = preserve;
+                self.save_config();
+                Task::none()
+            }
+            Message::SetKeepUserConfig(keep) => {
+                self.config.keep_user_config = keep;
+                self.save_config();
+                Task::none()
+            }
+            Message::ApplyConflictPreset(name) => {
+                self.apply_conflict_preset(&name);
+                Task::none()
+            }
+            Message::SetConflictPresetName(name) => {
+                self.conflicts.preset_name = name;
+                Task::none()
+            }
+            Message::SetConflictPresetAction(action) => {
+                self.conflicts.preset_action = action;
+                Task::none()
+            }
+            Message::SaveConflictPreset => {
+                self.save_conflict_preset();
+                Task::none()
+            }
+            Message::DeleteConflictPreset => {
+                if let Some(name) = self.conflicts.selected_preset.take() {
+                    self.config.remove_preset(&name);
+                    self.save_config();
+                }
+                Task::none()
+            }
+            Message::SetAutoPreset(enabled) => {
+                self.config.auto_preset = if enabled {
+                    self.conflicts.selected_preset.clone()
+                } else {
+                    None
+                };
+                self.save_config();
+                Task::none()
+            }
             Message::ConfirmConflictResolutions => self.complete_import_with_resolutions_async(),
 
             // Removal actions
-            Message::ToggleAuraForRemoval(id) => {
-                if self.removal.selected_ids.contains(&id) {
-                    self.removal.selected_ids.remove(&id);
-                } else {
-                    self.removal.selected_ids.insert(id);
-                }
+            Message::SetAuraRemovalChecked(id, checked) => {
+                self.set_tree_node_checked(&id, checked);
+                Task::none()
+            }
+            Message::TreeNodeClicked(id) => {
+                self.click_tree_node(&id);
                 Task::none()
             }
             Message::SelectAllForRemoval => {
@@ -337,17 +925,16 @@ impl WeakAuraImporter {
                     }
                 }
                 for node in &self.saved_vars.auras_tree {
-                    collect_ids(node, &mut self.removal.selected_ids);
+                    collect_ids(node, &mut self.tree_selection.selected);
                 }
                 Task::none()
             }
             Message::DeselectAllForRemoval => {
-                self.removal.selected_ids.clear();
+                self.tree_selection.clear();
                 Task::none()
             }
             Message::ShowRemoveConfirm => {
-                self.removal.pending_ids = self.removal.selected_ids.iter().cloned().collect();
-                self.ui.show_remove_confirm = true;
+                self.open_remove_confirm(self.tree_selection.selected.iter().cloned().collect());
                 Task::none()
             }
             Message::HideRemoveConfirm => {
@@ -357,10 +944,64 @@ impl WeakAuraImporter {
             }
             Message::ConfirmRemoval => {
                 self.ui.show_remove_confirm = false;
-                self.remove_auras_async()
+                self.confirm_removal(false)
             }
+            Message::ConfirmGroupOnlyRemoval => {
+                self.ui.show_remove_confirm = false;
+                self.confirm_removal(true)
+            }
+            Message::SetRemovalExportFirst(enabled) => {
+                self.removal.export_first = enabled;
+                Task::none()
+            }
+            Message::SetRemovalReanchor(enabled) => {
+                self.removal.reanchor_dependents = enabled;
+                Task::none()
+            }
+            Message::RemovalExportPathSelected(path, keep_children) => match path {
+                Some(path) => self.remove_auras_async(keep_children, Some(path)),
+                None => {
+                    self.removal.pending_ids.clear();
+                    self.notifications.push(Notification::info(
+                        "Removal cancelled: no export file chosen",
+                    ));
+                    Task::none()
+                }
+            },
 
             // Tree navigation
+            Message::SetTreeSort(sort) => {
+                self.sidebar.tree_sort = sort;
+                Task::none()
+            }
+            Message::CompareWithBackup(id) => self.compare_with_backup_async(id),
+            Message::BackupComparisonLoaded(result) => {
+                match result {
+                    Ok(comparison) => self.saved_vars.backup_comparison = Some(comparison),
+                    Err(e) => self.notifications.push(
+                        Notification::error(format!("Could not compare with the backup: {}", e))
+                            .title("Backup"),
+                    ),
+                }
+                Task::none()
+            }
+            Message::CloseBackupComparison => {
+                self.saved_vars.backup_comparison = None;
+                Task::none()
+            }
+            Message::CompareFiles => self.compare_files_async(),
+            Message::FilesCompared(result) => {
+                self.finish_compare_files(result);
+                Task::none()
+            }
+            Message::CloseFileDiff => {
+                self.saved_vars.file_diff = None;
+                Task::none()
+            }
+            Message::RepairSavedVariables => self.repair_saved_variables_async(),
+            Message::SavedVariablesRepaired(result) => self.finish_repair(result),
+            Message::RestoreFallback => self.restore_fallback_async(),
+            Message::FallbackRestored(result) => self.finish_restore_fallback(result),
             Message::ToggleGroupExpanded(id) => {
                 if self.sidebar.expanded_groups.contains(&id) {
                     self.sidebar.expanded_groups.remove(&id);
@@ -388,7 +1029,86 @@ impl WeakAuraImporter {
                 Task::none()
             }
 
+            // Scripts
+            Message::ToggleScripts => {
+                self.toggle_scripts();
+                Task::none()
+            }
+            Message::ScriptSelected(path) => {
+                self.load_script(path);
+                Task::none()
+            }
+            Message::ScriptAction(action) => {
+                self.scripts.content.perform(action);
+                Task::none()
+            }
+            Message::RunScript(dry_run) => self.run_script_async(dry_run),
+            Message::ScriptFinished(result) => {
+                self.finish_script(result);
+                Task::none()
+            }
+
+            // Tags & notes
+            Message::EditAnnotations(uid, name) => {
+                self.annotations.editing = Some((uid, name));
+                self.annotations.tag_input.clear();
+                Task::none()
+            }
+            Message::CloseAnnotationEditor => {
+                self.annotations.editing = None;
+                self.save_annotations();
+                Task::none()
+            }
+            Message::AnnotationTagInputChanged(tag) => {
+                self.annotations.tag_input = tag;
+                Task::none()
+            }
+            Message::AddAnnotationTag => {
+                let tag = std::mem::take(&mut self.annotations.tag_input);
+                self.add_annotation_tag(&tag);
+                Task::none()
+            }
+            Message::AddExistingAnnotationTag(tag) => {
+                self.add_annotation_tag(&tag);
+                Task::none()
+            }
+            Message::RemoveAnnotationTag(tag) => {
+                if let Some((uid, _)) = &self.annotations.editing {
+                    if self.annotations.store.remove_tag(uid, &tag) {
+                        self.save_annotations();
+                    }
+                }
+                Task::none()
+            }
+            Message::AnnotationNoteChanged(note) => {
+                // Saved when the editor closes
+                if let Some((uid, _)) = &self.annotations.editing {
+                    self.annotations.store.set_note(uid, &note);
+                }
+                Task::none()
+            }
+
             // Async task results
+            Message::SetCollectClipboard(enabled) => {
+                self.set_collect_clipboard(enabled);
+                Task::none()
+            }
+            Message::ClipboardTick => {
+                self.collect_from_clipboard();
+                Task::none()
+            }
+            Message::MinimizeWindow => match self.main_window {
+                Some(id) => window::minimize(id, true),
+                None => Task::none(),
+            },
+            Message::WindowFocused => {
+                self.show_collected_from_clipboard();
+                Task::none()
+            }
+            Message::CompanionReceived(text) => {
+                self.receive_companion_strings(&text);
+                Task::none()
+            }
             Message::LoadingUpdate(update) => {
                 self.handle_loading_update(update);
                 Task::none()
@@ -433,16 +1153,270 @@ impl WeakAuraImporter {
                 Task::none()
             }
 
-            // Toast notifications
+            // Notifications
             Message::DismissToast(id) => {
-                self.toasts.dismiss(id);
+                self.notifications.dismiss(id);
+                Task::none()
+            }
+            Message::ToggleNotifications => {
+                self.ui.show_notifications = !self.ui.show_notifications;
+                self.notifications.mark_read();
+                Task::none()
+            }
+            Message::ToggleNotificationDetails(id) => {
+                self.notifications.toggle_expanded(id);
+                Task::none()
+            }
+            Message::ClearNotifications => {
+                self.notifications.clear();
+                Task::none()
+            }
+            Message::ToggleProblems => {
+                self.ui.show_problems = !self.ui.show_problems;
+                Task::none()
+            }
+            Message::ToggleTasks => {
+                if let Some(id) = self.tasks.window {
+                    return window::gain_focus(id);
+                }
+                self.ui.show_tasks = !self.ui.show_tasks;
+                Task::none()
+            }
+            Message::CancelTask(kind) => self.cancel_task(kind),
+            Message::DetachTasks => self.detach_tasks_panel(),
+            Message::AttachTasks => self.attach_tasks_panel(),
+            Message::CopyProblems => {
+                self.copy_problems();
+                Task::none()
+            }
+            Message::ClearProblems => {
+                self.notifications.clear_problems();
+                Task::none()
+            }
+
+            // Property editor
+            Message::EditAuraProperties(idx) => {
+                self.context_menu = ContextMenuState::default();
+                self.preview_parsed_aura(idx);
+                self.open_property_editor(idx);
+                Task::none()
+            }
+            Message::AuraPropertyChanged(property, value) => {
+                *self.property_editor.field_mut(property) = value;
+                Task::none()
+            }
+            Message::ApplyAuraProperties => {
+                self.apply_aura_properties();
+                Task::none()
+            }
+            Message::CloseAuraProperties => {
+                self.property_editor = PropertyEditorState::default();
+                Task::none()
+            }
+
+            // Copying aura data
+            Message::CopyDecodedData(format) => {
+                self.copy_decoded_data(format);
+                Task::none()
+            }
+            Message::CopyStoredAura(id, format) => {
+                self.context_menu = ContextMenuState::default();
+                self.copy_stored_aura_async(id, format)
+            }
+            Message::StoredAuraTextLoaded(result) => {
+                match result {
+                    Ok(text) => self.copy_snippet(text),
+                    Err(e) => self.notifications.push(
+                        Notification::error(format!("Failed to read aura: {}", e))
+                            .title("Copy Aura"),
+                    ),
+                }
+                Task::none()
+            }
+
+            // Context menus
+            Message::OpenContextMenu(target) => {
+                self.toggle_context_menu(target);
+                Task::none()
+            }
+            Message::CloseContextMenu => {
+                self.context_menu = ContextMenuState::default();
+                Task::none()
+            }
+            Message::PreviewParsedAura(idx) => {
+                self.context_menu = ContextMenuState::default();
+                self.preview_parsed_aura(idx);
+                Task::none()
+            }
+            Message::ExportParsedAura(idx) => self.export_parsed_aura_async(idx),
+            Message::StartRenameStoredAura => {
+                self.start_rename_stored_aura();
+                Task::none()
+            }
+            Message::RenameStoredAuraInputChanged(value) => {
+                self.context_menu.rename = Some(value);
+                Task::none()
+            }
+            Message::ConfirmRenameStoredAura => self.confirm_rename_stored_aura(),
+            Message::MoveStoredAura(id, group_id) => self.move_stored_aura_async(id, group_id),
+            Message::RemoveStoredAura(id) => {
+                self.remove_stored_aura(&id);
+                Task::none()
+            }
+            Message::ExportStoredAura(id) => self.export_stored_aura_async(id),
+            Message::CopyAuraId(id) => {
+                self.copy_aura_id(id);
+                Task::none()
+            }
+            Message::StoredAuraEdited(result) => {
+                match result {
+                    Ok(edit) => {
+                        self.saved_vars.auras_tree = edit.tree;
+                        self.saved_vars.auras_count = edit.tree_count;
+                        self.refresh_has_backup();
+                        self.tree_selection.clear();
+                        self.notifications
+                            .push(Notification::success(edit.message).title("Existing Auras"));
+                    }
+                    Err(e) => self
+                        .notifications
+                        .push(Notification::error(e).title("Existing Auras")),
+                }
+                Task::none()
+            }
+            Message::ExportFieldsCsv => self.export_fields_csv_async(),
+            Message::ApplyFieldsCsv => Task::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .add_filter("CSV files", &["csv"])
+                        .pick_file()
+                        .await
+                        .map(|h| h.path().to_path_buf())
+                },
+                Message::FieldsCsvSelected,
+            ),
+            Message::FieldsCsvSelected(Some(path)) => self.apply_fields_csv_async(path),
+            Message::FieldsCsvSelected(None) => Task::none(),
+            Message::AuraExported(result) => {
+                match result {
+                    Ok(Some(path)) => self.notifications.push(
+                        Notification::success(format!("Exported to {}", path.display()))
+                            .title("Export"),
+                    ),
+                    Ok(None) => {}
+                    Err(e) => self
+                        .notifications
+                        .push(Notification::error(e).title("Export Error")),
+                }
+                Task::none()
+            }
+
+            // Raw data editor
+            Message::EditRawData(idx) => {
+                self.context_menu = ContextMenuState::default();
+                self.preview_parsed_aura(idx);
+                self.open_raw_editor(idx);
+                Task::none()
+            }
+            Message::RawDataFormatChanged(format) => {
+                self.set_raw_editor_format(format);
+                Task::none()
+            }
+            Message::RawDataAction(action) => {
+                self.raw_editor.content.perform(action);
+                Task::none()
+            }
+            Message::ApplyRawData => {
+                self.apply_raw_data();
+                Task::none()
+            }
+            Message::CloseRawData => {
+                self.raw_editor = RawEditorState::default();
+                Task::none()
+            }
+
+            // Find references
+            Message::ToggleReferences => {
+                self.ui.show_references = !self.ui.show_references;
+                Task::none()
+            }
+            Message::ReferenceQueryChanged(query) => {
+                self.references.query = query;
+                Task::none()
+            }
+            Message::SearchReferences => self.search_references_async(),
+            Message::ReferencesFound(result) => {
+                self.handle_references_found(result);
+                Task::none()
+            }
+
+            // Crash recovery
+            Message::RestoreCrashBackup => {
+                self.restore_crash_backup();
+                Task::none()
+            }
+            Message::DismissCrashRecovery => {
+                self.status.crash_recovery = None;
+                Task::none()
+            }
+
+            // Denied saves
+            Message::RestartElevated => self.restart_elevated(),
+            Message::SaveDeniedElsewhere => self.save_denied_elsewhere_async(),
+            Message::DeniedSaveWritten(result) => {
+                self.finish_denied_save(result);
+                Task::none()
+            }
+            Message::DismissDeniedSave => {
+                self.status.denied_save = None;
+                Task::none()
+            }
+
+            // Session auto-save
+            Message::AutosaveSession => {
+                self.autosave_session();
+                Task::none()
+            }
+            Message::OpenSideWindow => self.open_side_window(),
+            Message::SideWindowFileSelected(path) => self.select_side_file(path),
+            Message::SideWindowLoaded(result) => {
+                self.handle_side_window_loaded(result);
+                Task::none()
+            }
+            Message::SetSideAuraChecked(id, checked) => {
+                if let Some(parsed) = &self.side_window.tree {
+                    set_tree_checked(&parsed.tree, &mut self.side_window.selected, &id, checked);
+                }
                 Task::none()
             }
+            Message::CopySideAuras => self.copy_side_auras_async(),
+            Message::SideAurasExtracted(result) => {
+                self.handle_side_auras_extracted(result);
+                Task::none()
+            }
+            Message::WindowClosed(id) => self.handle_window_closed(id),
+
+            Message::RestoreSession => self.restore_session(),
+            Message::DiscardSession => {
+                self.discard_session();
+                Task::none()
+            }
+        }
+    }
+
+    /// Render the view of a window
+    pub fn view(&self, window: window::Id) -> Element<'_, Message> {
+        if self.side_window.id == Some(window) {
+            return self.render_side_window();
+        }
+        if self.tasks.window == Some(window) {
+            return self.render_tasks_window();
         }
+        self.render_main_window()
     }
 
-    /// Render the application view
-    pub fn view(&self) -> Element<'_, Message> {
+    /// Render the main window
+    fn render_main_window(&self) -> Element<'_, Message> {
         use crate::theme::{colors, spacing, typography};
         use iced::mouse::Interaction;
         use iced::widget::{mouse_area, space};
@@ -510,6 +1484,11 @@ impl WeakAuraImporter {
                 .on_move(|point| Message::SidebarResize(point.x))
                 .on_release(Message::EndSidebarResize)
                 .into()
+        } else if self.list_view.resizing.is_some() {
+            mouse_area(content_row)
+                .on_move(|point| Message::ColumnResize(point.x))
+                .on_release(Message::EndColumnResize)
+                .into()
         } else {
             content_row
         };
@@ -523,7 +1502,17 @@ impl WeakAuraImporter {
         let status_bar = self.render_status_bar();
 
         // Stack the dialogs on top using overlay pattern
-        let mut main_view: Element<Message> = column![header, content_container, status_bar].into();
+        let mut main_column = column![header, content_container];
+        if self.ui.show_references {
+            main_column = main_column.push(self.render_references_panel());
+        }
+        if self.ui.show_problems {
+            main_column = main_column.push(self.render_problems_panel());
+        }
+        if self.ui.show_tasks {
+            main_column = main_column.push(self.render_tasks_panel());
+        }
+        let mut main_view: Element<Message> = main_column.push(status_bar).into();
 
         // Modal overlays
         if self.ui.show_import_confirm {
@@ -535,6 +1524,33 @@ impl WeakAuraImporter {
         if self.ui.show_remove_confirm {
             main_view = self.overlay_remove_confirmation(main_view);
         }
+        if self.ui.show_import_report {
+            main_view = self.overlay_import_report(main_view);
+        }
+        if self.annotations.editing.is_some() {
+            main_view = self.overlay_annotation_editor(main_view);
+        }
+        if self.saved_vars.backup_comparison.is_some() {
+            main_view = self.overlay_backup_comparison(main_view);
+        }
+        if self.saved_vars.file_diff.is_some() {
+            main_view = self.overlay_file_diff(main_view);
+        }
+        if self.scripts.show {
+            main_view = self.overlay_scripts(main_view);
+        }
+        if self.ui.show_notifications {
+            main_view = self.overlay_notifications(main_view);
+        }
+        if self.status.crash_recovery.is_some() {
+            main_view = self.overlay_crash_recovery(main_view);
+        }
+        if self.status.denied_save.is_some() {
+            main_view = self.overlay_denied_save(main_view);
+        }
+        if self.session.pending_restore.is_some() {
+            main_view = self.overlay_session_restore(main_view);
+        }
         if self.ui.show_setup_wizard || self.saved_vars.selected_path.is_none() {
             main_view = self.overlay_setup_wizard(main_view);
         }
@@ -544,6 +1560,6 @@ impl WeakAuraImporter {
             .height(Length::Fill);
 
         // Wrap with toast notifications overlay
-        self.toasts.view(final_view)
+        self.notifications.view(final_view)
     }
 }
@@ -0,0 +1,308 @@
+//! Notifications: shown as toasts and kept in a history panel.
+//!
+//! Every user-facing message goes through [`Notifications::push`], so nothing
+//! is lost when a toast times out. Batch errors are grouped into one
+//! notification whose individual messages can be expanded in the panel.
+//!
+//! Warnings and errors are also collected as [`Problem`]s for the session's
+//! Problems panel, one per failure, along with problems from background work
+//! that never shows a toast.
+
+use std::collections::{HashSet, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use iced::{Color, Element};
+use iced_toasts::{toast, toast_container, ToastContainer, ToastId, ToastLevel};
+
+use crate::theme::colors;
+
+use super::Message;
+
+/// Oldest notifications are dropped beyond this many
+const MAX_HISTORY: usize = 200;
+
+/// Oldest problems are dropped beyond this many
+const MAX_PROBLEMS: usize = 1000;
+
+/// How important a notification is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    /// Display label
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Info => "Info",
+            Self::Success => "Success",
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+        }
+    }
+
+    /// Accent color in the notifications panel
+    pub fn color(self) -> Color {
+        match self {
+            Self::Info => colors::INFO,
+            Self::Success => colors::SUCCESS,
+            Self::Warning => colors::WARNING,
+            Self::Error => colors::ERROR,
+        }
+    }
+
+    fn toast_level(self) -> ToastLevel {
+        match self {
+            Self::Info => ToastLevel::Info,
+            Self::Success => ToastLevel::Success,
+            Self::Warning => ToastLevel::Warning,
+            Self::Error => ToastLevel::Error,
+        }
+    }
+}
+
+/// A message for the user
+#[derive(Debug, Clone)]
+pub struct Notification {
+    /// Assigned when pushed, used to expand details in the panel
+    pub id: u64,
+    pub level: NotificationLevel,
+    pub title: Option<String>,
+    pub message: String,
+    /// Individual messages behind a summary (e.g. one per failed string)
+    pub details: Vec<String>,
+    pub created: SystemTime,
+}
+
+impl Notification {
+    pub fn new(level: NotificationLevel, message: impl Into<String>) -> Self {
+        Self {
+            id: 0,
+            level,
+            title: None,
+            message: message.into(),
+            details: Vec::new(),
+            created: SystemTime::now(),
+        }
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(NotificationLevel::Info, message)
+    }
+
+    pub fn success(message: impl Into<String>) -> Self {
+        Self::new(NotificationLevel::Success, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(NotificationLevel::Warning, message)
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(NotificationLevel::Error, message)
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// A warning or error of the session, listed in the Problems panel
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub level: NotificationLevel,
+    /// What reported it, e.g. "Invalid WeakAura" or "Pre-parse"
+    pub source: String,
+    pub message: String,
+    pub created: SystemTime,
+}
+
+impl Problem {
+    pub fn new(
+        level: NotificationLevel,
+        source: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            level,
+            source: source.into(),
+            message: message.into(),
+            created: SystemTime::now(),
+        }
+    }
+
+    /// Time of day it was reported (UTC), e.g. "14:03:27"
+    pub fn timestamp(&self) -> String {
+        let secs = self
+            .created
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+            % 86_400;
+        format!(
+            "{:02}:{:02}:{:02}",
+            secs / 3_600,
+            secs % 3_600 / 60,
+            secs % 60
+        )
+    }
+}
+
+/// Toasts plus the history behind the notifications panel
+pub struct Notifications {
+    toasts: ToastContainer<'static, Message>,
+    /// Newest first
+    history: VecDeque<Notification>,
+    /// Notifications whose details are expanded in the panel
+    expanded: HashSet<u64>,
+    /// Notifications pushed since the panel was last opened
+    unread: usize,
+    next_id: u64,
+    /// Warnings and errors of the session, oldest first
+    problems: VecDeque<Problem>,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            toasts: toast_container(Message::DismissToast),
+            history: VecDeque::new(),
+            expanded: HashSet::new(),
+            unread: 0,
+            next_id: 1,
+            problems: VecDeque::new(),
+        }
+    }
+}
+
+impl Notifications {
+    /// Show a notification as a toast and record it in the history
+    pub fn push(&mut self, mut notification: Notification) {
+        notification.id = self.next_id;
+        self.next_id += 1;
+
+        let mut message = notification.message.clone();
+        if !notification.details.is_empty() {
+            message.push_str(" (see Notifications for details)");
+        }
+        let mut item = toast(&message).level(notification.level.toast_level());
+        if let Some(title) = &notification.title {
+            item = item.title(title);
+        }
+        self.toasts.push(item);
+
+        if matches!(
+            notification.level,
+            NotificationLevel::Warning | NotificationLevel::Error
+        ) {
+            let source = notification
+                .title
+                .clone()
+                .unwrap_or_else(|| notification.level.label().to_string());
+            if notification.details.is_empty() {
+                self.report(Problem::new(
+                    notification.level,
+                    source,
+                    &notification.message,
+                ));
+            } else {
+                for detail in &notification.details {
+                    self.report(Problem::new(notification.level, &source, detail));
+                }
+            }
+        }
+
+        self.history.push_front(notification);
+        if self.history.len() > MAX_HISTORY {
+            if let Some(dropped) = self.history.pop_back() {
+                self.expanded.remove(&dropped.id);
+            }
+        }
+        self.unread += 1;
+    }
+
+    pub fn dismiss(&mut self, id: ToastId) {
+        self.toasts.dismiss(id);
+    }
+
+    /// Recorded notifications, newest first
+    pub fn history(&self) -> impl Iterator<Item = &Notification> {
+        self.history.iter()
+    }
+
+    pub fn unread(&self) -> usize {
+        self.unread
+    }
+
+    pub fn mark_read(&mut self) {
+        self.unread = 0;
+    }
+
+    pub fn is_expanded(&self, id: u64) -> bool {
+        self.expanded.contains(&id)
+    }
+
+    pub fn toggle_expanded(&mut self, id: u64) {
+        if !self.expanded.remove(&id) {
+            self.expanded.insert(id);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.expanded.clear();
+        self.unread = 0;
+    }
+
+    /// Record a problem without showing a toast (e.g. from background work)
+    pub fn report(&mut self, problem: Problem) {
+        self.problems.push_back(problem);
+        if self.problems.len() > MAX_PROBLEMS {
+            self.problems.pop_front();
+        }
+    }
+
+    /// Warnings and errors of the session, oldest first
+    pub fn problems(&self) -> impl Iterator<Item = &Problem> {
+        self.problems.iter()
+    }
+
+    pub fn problem_count(&self) -> usize {
+        self.problems.len()
+    }
+
+    pub fn clear_problems(&mut self) {
+        self.problems.clear();
+    }
+
+    /// All problems as plain text, one per line, for the clipboard
+    pub fn problems_text(&self) -> String {
+        self.problems
+            .iter()
+            .map(|p| {
+                format!(
+                    "[{}] {} {}: {}",
+                    p.timestamp(),
+                    p.level.label(),
+                    p.source,
+                    p.message
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Draw the toasts over the given view
+    pub fn view<'a>(&'a self, content: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+        self.toasts.view(content.into())
+    }
+}
@@ -1,13 +1,33 @@
 //! Data types for GUI state that are shared across app submodules.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ops::Range;
 use std::path::PathBuf;
+use std::time::Instant;
 
-use crate::categories::UpdateCategory;
-use crate::decoder::{ValidationResult, WeakAura};
+use iced::widget::text_editor;
+use iced::{task, window};
+
+use crate::annotations::AnnotationStore;
+use crate::categories::{CategoryKey, CategorySet, UpdateCategory};
+use crate::crash::PendingCrash;
+use crate::decoder::{AuraTextFormat, LuaValue, ValidationResult, WeakAura};
+use crate::duplicates::Duplicate;
+use crate::file_diff::FileDiff;
+use crate::media::MediaReference;
+use crate::nesting::NestingWarning;
+use crate::pack::MediaPack;
+use crate::references::Reference;
+use crate::repair::FallbackCandidate;
 use crate::saved_variables::{
-    AuraTreeNode, ConflictAction, ConflictDetectionResult, ImportResult, SavedVariablesInfo,
+    AuraTreeNode, AuraTreeSort, BackupComparison, ConflictAction, ConflictDetectionResult,
+    ImportResult, SavedVariablesInfo, MAX_SUPPORTED_DB_VERSION,
 };
+use crate::scripting::ScriptReport;
+use crate::selection::{trivial_reason, RangeSelection};
+use crate::session::Session;
+use crate::templates::TemplateStore;
+use crate::tree_cache::{AuraTreeCache, ParsedTree};
 
 // =============================================================================
 // Nested State Structs for WeakAuraImporter
@@ -28,6 +48,20 @@ pub struct UiVisibility {
     pub show_remove_confirm: bool,
     /// Show setup wizard for selecting SavedVariables
     pub show_setup_wizard: bool,
+    /// Show selection rules panel above the parsed aura list
+    pub show_selection_rules: bool,
+    /// Show the transforms panel above the parsed aura list
+    pub show_transforms: bool,
+    /// Show per-aura report for the last import
+    pub show_import_report: bool,
+    /// Show the notifications history panel
+    pub show_notifications: bool,
+    /// Expand the Problems panel above the status bar
+    pub show_problems: bool,
+    /// Expand the find-references panel above the status bar
+    pub show_references: bool,
+    /// Expand the Tasks panel above the status bar
+    pub show_tasks: bool,
 }
 
 /// Sidebar-related state
@@ -41,6 +75,8 @@ pub struct SidebarState {
     pub is_hovering_resize: bool,
     /// Expanded groups in the existing auras tree
     pub expanded_groups: HashSet<String>,
+    /// Sort order of the existing auras tree
+    pub tree_sort: AuraTreeSort,
 }
 
 impl Default for SidebarState {
@@ -50,6 +86,7 @@ impl Default for SidebarState {
             is_resizing: false,
             is_hovering_resize: false,
             expanded_groups: HashSet::new(),
+            tree_sort: AuraTreeSort::default(),
         }
     }
 }
@@ -77,6 +114,152 @@ pub struct TaskProgress {
     pub is_removing: bool,
     /// Removal progress message
     pub removal_message: String,
+    /// Abort handles of the running jobs, by kind; dropped when they finish
+    pub handles: HashMap<TaskKind, Vec<task::Handle>>,
+    /// The Tasks panel's own window, when detached
+    pub window: Option<window::Id>,
+}
+
+impl TaskProgress {
+    /// Share of the work done, from 0.0 to 1.0
+    pub fn fraction(current: u64, total: u64) -> f32 {
+        if total > 0 {
+            current as f32 / total as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Kind of background job; one of each kind runs at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TaskKind {
+    Loading,
+    Scanning,
+    Preparsing,
+    Importing,
+    Removing,
+}
+
+impl TaskKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Loading => "Loading auras",
+            Self::Scanning => "Reading SavedVariables",
+            Self::Preparsing => "Pre-parsing SavedVariables",
+            Self::Importing => "Importing",
+            Self::Removing => "Removing auras",
+        }
+    }
+}
+
+/// A background job as listed in the Tasks panel
+#[derive(Debug, Clone)]
+pub struct RunningTask {
+    pub kind: TaskKind,
+    /// Share of the work done, when the job reports it
+    pub progress: Option<f32>,
+    pub message: String,
+}
+
+/// Parsed aura list view state
+#[derive(Debug, Default)]
+pub struct ListViewState {
+    /// Active grouping
+    pub grouping: ListGrouping,
+    /// Collapsed section labels
+    pub collapsed_groups: HashSet<String>,
+    /// Status chip filtering the list
+    pub status_filter: StatusFilter,
+    /// Column the table is sorted by (`None` keeps the load order)
+    pub sort_column: Option<ListColumn>,
+    pub sort_descending: bool,
+    /// Column widths changed by the user
+    pub column_widths: HashMap<ListColumn, f32>,
+    /// Column being resized by dragging its header edge
+    pub resizing: Option<ColumnResize>,
+}
+
+impl ListViewState {
+    pub fn column_width(&self, column: ListColumn) -> f32 {
+        self.column_widths
+            .get(&column)
+            .copied()
+            .unwrap_or_else(|| column.default_width())
+    }
+}
+
+/// Status chip above the parsed auras list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Valid,
+    Invalid,
+    Groups,
+    Selected,
+    /// Auras listed more than once with differing data
+    Duplicates,
+}
+
+impl StatusFilter {
+    pub const ALL: [StatusFilter; 6] = [
+        StatusFilter::All,
+        StatusFilter::Valid,
+        StatusFilter::Invalid,
+        StatusFilter::Groups,
+        StatusFilter::Selected,
+        StatusFilter::Duplicates,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusFilter::All => "All",
+            StatusFilter::Valid => "Valid",
+            StatusFilter::Invalid => "Invalid",
+            StatusFilter::Groups => "Groups",
+            StatusFilter::Selected => "Selected",
+            StatusFilter::Duplicates => "Duplicates",
+        }
+    }
+
+    /// Whether the chip shows `entry`; `is_duplicate` tells whether its aura
+    /// is listed more than once
+    pub fn matches(self, entry: &ParsedAuraEntry, is_duplicate: bool) -> bool {
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::Valid => entry.validation.is_valid,
+            StatusFilter::Invalid => !entry.validation.is_valid,
+            StatusFilter::Groups => entry.validation.is_group,
+            StatusFilter::Selected => entry.selected,
+            StatusFilter::Duplicates => is_duplicate,
+        }
+    }
+}
+
+/// Drag of a table column's header edge
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnResize {
+    pub column: ListColumn,
+    /// Pointer x of the first move of the drag
+    pub start_x: Option<f32>,
+    /// Column width when the drag started
+    pub start_width: f32,
+}
+
+/// Bulk selection rule inputs (see [`crate::selection::SelectionRule`])
+#[derive(Debug, Default)]
+pub struct SelectionRuleState {
+    /// Only match valid auras
+    pub valid_only: bool,
+    /// Only match groups
+    pub groups_only: bool,
+    /// Region type filter (`None` = any)
+    pub region_type: Option<String>,
+    /// Source file name filter (`None` = any)
+    pub source_file: Option<String>,
+    /// Aura ID regex
+    pub id_pattern: String,
 }
 
 /// Conflict resolution state
@@ -86,10 +269,26 @@ pub struct ConflictState {
     pub result: Option<ConflictDetectionResult>,
     /// Resolutions for each conflict (parallel to conflict_result.conflicts)
     pub resolutions: Vec<ConflictResolutionUI>,
+    /// Built-in and custom categories offered in the dialog
+    pub category_set: CategorySet,
     /// Global categories to update (used as default for all conflicts)
-    pub global_categories: HashSet<UpdateCategory>,
+    pub global_categories: HashSet<CategoryKey>,
     /// Selected conflict index in the dialog
     pub selected_index: Option<usize>,
+    /// Sort order of the conflict list
+    pub sort: ConflictSort,
+    /// Only show conflicts with trigger changes
+    pub only_trigger_changes: bool,
+    /// Only show group conflicts
+    pub only_groups: bool,
+    /// Current page of the conflict list (0-based)
+    pub page: usize,
+    /// Last applied or selected resolution preset
+    pub selected_preset: Option<String>,
+    /// Name input for saving a new preset
+    pub preset_name: String,
+    /// Action stored with a new preset
+    pub preset_action: ConflictAction,
 }
 
 impl Default for ConflictState {
@@ -97,26 +296,222 @@ impl Default for ConflictState {
         Self {
             result: None,
             resolutions: Vec::new(),
-            global_categories: UpdateCategory::defaults(),
+            category_set: CategorySet::default(),
+            global_categories: CategorySet::default().defaults(),
             selected_index: None,
+            sort: ConflictSort::default(),
+            only_trigger_changes: false,
+            only_groups: false,
+            page: 0,
+            selected_preset: None,
+            preset_name: String::new(),
+            preset_action: ConflictAction::UpdateSelected,
+        }
+    }
+}
+
+impl ConflictState {
+    /// Indices of conflicts passing the filters, in the selected sort order
+    pub fn visible_indices(&self) -> Vec<usize> {
+        let Some(result) = &self.result else {
+            return Vec::new();
+        };
+        let mut indices: Vec<usize> = result
+            .conflicts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !self.only_groups || c.is_group)
+            .filter(|(_, c)| {
+                !self.only_trigger_changes
+                    || c.changed_categories.contains(&UpdateCategory::Trigger)
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        let conflicts = &result.conflicts;
+        match self.sort {
+            ConflictSort::Version => indices.sort_by_key(|&i| conflicts[i].version_change),
+            ConflictSort::Name => {
+                indices.sort_by_key(|&i| conflicts[i].aura_id.to_lowercase());
+            }
+            ConflictSort::ChangedCategories => {
+                indices.sort_by_key(|&i| std::cmp::Reverse(conflicts[i].changed_categories.len()))
+            }
         }
+        indices
+    }
+
+    /// Number of pages for `count` visible conflicts
+    pub fn page_count(count: usize) -> usize {
+        ((count + CONFLICT_PAGE_SIZE - 1) / CONFLICT_PAGE_SIZE).max(1)
+    }
+}
+
+/// Conflicts shown per page in the conflict dialog
+pub const CONFLICT_PAGE_SIZE: usize = 50;
+
+/// Sort order of the conflict list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictSort {
+    /// Newer versions first
+    #[default]
+    Version,
+    /// Alphabetical by aura ID
+    Name,
+    /// Most changed categories first
+    ChangedCategories,
+}
+
+impl ConflictSort {
+    pub const ALL: [ConflictSort; 3] = [
+        ConflictSort::Version,
+        ConflictSort::Name,
+        ConflictSort::ChangedCategories,
+    ];
+}
+
+impl std::fmt::Display for ConflictSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConflictSort::Version => write!(f, "Version"),
+            ConflictSort::Name => write!(f, "Name"),
+            ConflictSort::ChangedCategories => write!(f, "Most changes"),
+        }
+    }
+}
+
+/// Find-references search over the selected SavedVariables file
+#[derive(Debug, Default)]
+pub struct ReferenceSearchState {
+    /// Text or spell ID to search for
+    pub query: String,
+    /// Matches of the last search (`None` before the first one)
+    pub results: Option<Vec<Reference>>,
+    /// Whether a search runs in the background
+    pub is_searching: bool,
+}
+
+/// Field of the property editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuraProperty {
+    Id,
+    XOffset,
+    YOffset,
+    Classes,
+    Specs,
+    Desc,
+}
+
+/// Property editor of the decoded panel. Fields are kept as typed text and
+/// only parsed when applied.
+#[derive(Debug, Default)]
+pub struct PropertyEditorState {
+    /// Parsed aura being edited (`None` when the editor is closed)
+    pub index: Option<usize>,
+    pub id: String,
+    pub x_offset: String,
+    pub y_offset: String,
+    /// Comma-separated class tokens
+    pub classes: String,
+    /// Comma-separated specialization IDs
+    pub specs: String,
+    pub desc: String,
+    /// Why the last apply failed
+    pub error: Option<String>,
+}
+
+impl PropertyEditorState {
+    pub fn field_mut(&mut self, property: AuraProperty) -> &mut String {
+        match property {
+            AuraProperty::Id => &mut self.id,
+            AuraProperty::XOffset => &mut self.x_offset,
+            AuraProperty::YOffset => &mut self.y_offset,
+            AuraProperty::Classes => &mut self.classes,
+            AuraProperty::Specs => &mut self.specs,
+            AuraProperty::Desc => &mut self.desc,
+        }
+    }
+}
+
+/// Lua/JSON editor of the decoded panel
+#[derive(Debug, Default)]
+pub struct RawEditorState {
+    /// Parsed aura being edited (`None` when the editor is closed)
+    pub index: Option<usize>,
+    pub format: AuraTextFormat,
+    pub content: text_editor::Content,
+    /// Why the last apply (or format switch) failed
+    pub error: Option<String>,
+}
+
+/// Item a context menu was opened on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContextMenuTarget {
+    /// Entry of the parsed auras list, by index
+    ParsedAura(usize),
+    /// Existing aura in the sidebar tree, by ID
+    TreeNode(String),
+}
+
+/// Right-click menu of the parsed auras list and the existing auras tree
+#[derive(Debug, Default)]
+pub struct ContextMenuState {
+    /// Item whose menu is open
+    pub target: Option<ContextMenuTarget>,
+    /// New ID typed for the tree node (`Some` while renaming it)
+    pub rename: Option<String>,
+}
+
+/// Existing auras tree after renaming or moving a stored aura
+#[derive(Clone, Debug)]
+pub struct StoredAuraEdit {
+    /// What was changed, for the notification
+    pub message: String,
+    pub tree: Vec<AuraTreeNode>,
+    pub tree_count: usize,
+}
+
+/// Shift/ctrl-click selection of the parsed auras list. Which entries are
+/// selected is kept on the entries themselves.
+#[derive(Debug, Default)]
+pub struct ListSelectionState {
+    /// Range anchor, by entry index
+    pub range: RangeSelection<usize>,
+}
+
+/// Checked auras of the existing auras tree, for removal and other bulk actions
+#[derive(Debug, Default)]
+pub struct TreeSelectionState {
+    pub selected: HashSet<String>,
+    /// Range anchor, by aura ID
+    pub range: RangeSelection<String>,
+}
+
+impl TreeSelectionState {
+    pub fn clear(&mut self) {
+        self.selected.clear();
+        self.range.clear();
     }
 }
 
 /// Aura removal state
 #[derive(Debug, Default)]
 pub struct RemovalState {
-    /// Auras selected for removal in the sidebar
-    pub selected_ids: HashSet<String>,
     /// IDs pending removal (populated when confirm dialog opens)
     pub pending_ids: Vec<String>,
+    /// Dump the auras to a Lua file before removing them
+    pub export_first: bool,
+    /// Anchor auras that are anchored to the removed ones to the screen
+    pub reanchor_dependents: bool,
 }
 
 /// SavedVariables file management state
 #[derive(Debug, Default)]
 pub struct SavedVariablesState {
-    /// WoW path for scanning
+    /// WoW path being entered in the setup wizard
     pub wow_path: String,
+    /// WoW installation roots scanned together (bookmarks and auto-detected)
+    pub wow_paths: Vec<PathBuf>,
     /// Selected SavedVariables file
     pub selected_path: Option<PathBuf>,
     /// Discovered SavedVariables files
@@ -125,6 +520,76 @@ pub struct SavedVariablesState {
     pub auras_tree: Vec<AuraTreeNode>,
     /// Total count of existing auras
     pub auras_count: usize,
+    /// Existing group the imported auras are moved into
+    pub target_group: Option<String>,
+    /// Aura trees of discovered files, parsed in the background
+    pub tree_cache: AuraTreeCache,
+    /// Discovered files currently being parsed in the background
+    pub preparsing: HashSet<PathBuf>,
+    /// `dbVersion` of the selected file
+    pub db_version: Option<u32>,
+    /// User confirmed writing a file whose `dbVersion` is newer than supported
+    pub allow_newer_db_version: bool,
+    /// Whether the selected file has a backup from its last save
+    pub has_backup: bool,
+    /// Why the selected file cannot be written, probed when it is selected
+    pub read_only: Option<String>,
+    /// Loading the selected file failed, e.g. because it is truncated
+    pub load_failed: bool,
+    /// Copies of a selected file that failed to parse which do parse, newest first
+    pub fallbacks: Vec<FallbackCandidate>,
+    /// Copy the selected file was restored from
+    pub restored_from: Option<FallbackCandidate>,
+    /// Aura shown in the backup comparison dialog
+    pub backup_comparison: Option<BackupComparison>,
+    /// Result of "Compare files...", shown in its dialog
+    pub file_diff: Option<FileDiff>,
+}
+
+impl SavedVariablesState {
+    /// Whether the selected file is from a newer WeakAuras release than
+    /// supported and writing it has not been confirmed
+    pub fn writes_blocked(&self) -> bool {
+        !self.allow_newer_db_version
+            && self
+                .db_version
+                .is_some_and(|version| version > MAX_SUPPORTED_DB_VERSION)
+    }
+}
+
+/// Aura tags and notes state
+#[derive(Debug, Default)]
+pub struct AnnotationState {
+    /// Sidecar annotation database
+    pub store: AnnotationStore,
+    /// Tag/note filter applied to both aura lists
+    pub filter: String,
+    /// UID and display name of the aura whose annotations are being edited
+    pub editing: Option<(String, String)>,
+    /// Tag text input in the editor
+    pub tag_input: String,
+}
+
+/// Saved aura templates and their panel
+#[derive(Debug, Default)]
+pub struct TemplateState {
+    pub store: TemplateStore,
+    /// Whether the templates panel is shown below the load buttons
+    pub show: bool,
+}
+
+/// Scripts dialog
+#[derive(Debug, Default)]
+pub struct ScriptsState {
+    pub show: bool,
+    /// Script files of the scripts folder
+    pub files: Vec<PathBuf>,
+    /// File loaded into the editor
+    pub selected: Option<PathBuf>,
+    pub content: text_editor::Content,
+    pub running: bool,
+    /// Report of the last run, or why it failed
+    pub result: Option<Result<ScriptReport, String>>,
 }
 
 /// Status bar state
@@ -136,6 +601,20 @@ pub struct StatusState {
     pub is_error: bool,
     /// Last import result
     pub last_import_result: Option<ImportResult>,
+    /// Crash of the previous session, offered for recovery on startup
+    pub crash_recovery: Option<PendingCrash>,
+    /// Save the operating system refused, offered to retry elevated or to
+    /// write elsewhere
+    pub denied_save: Option<DeniedSave>,
+    /// Media files referenced by the auras to import but not found in the
+    /// game folder, listed in the import confirmation
+    pub missing_media: Vec<MediaReference>,
+    /// Group nesting problems the import would create, listed in the import
+    /// confirmation
+    pub nesting_warnings: Vec<NestingWarning>,
+    /// Auras about to be imported that are listed more than once, listed in
+    /// the import confirmation
+    pub import_duplicates: Vec<Duplicate>,
 }
 
 impl Default for StatusState {
@@ -144,10 +623,126 @@ impl Default for StatusState {
             message: String::from("Ready. Load WeakAura strings from file or folder."),
             is_error: false,
             last_import_result: None,
+            crash_recovery: None,
+            denied_save: None,
+            missing_media: Vec::new(),
+            nesting_warnings: Vec::new(),
+            import_duplicates: Vec::new(),
+        }
+    }
+}
+
+/// Auto-saved working session
+#[derive(Debug, Default)]
+pub struct SessionState {
+    /// Session as last written to disk
+    pub last_saved: Session,
+    /// Session of an earlier launch, offered for restore on startup.
+    /// Auto-save is paused while the offer is open.
+    pub pending_restore: Option<Session>,
+}
+
+/// Offset of a repositioning transform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OffsetAxis {
+    X,
+    Y,
+}
+
+/// Transforms panel state
+#[derive(Debug, Default)]
+pub struct TransformsState {
+    /// Text of the offset inputs being edited, by step index. Kept so that
+    /// partial numbers like `-` or `1.` can be typed.
+    pub offset_inputs: HashMap<(usize, OffsetAxis), String>,
+}
+
+/// Background collection of strings copied to the clipboard
+#[derive(Debug, Default)]
+pub struct ClipboardWatchState {
+    /// Clipboard text seen last, so each copy is collected once
+    pub last_text: Option<String>,
+    /// Auras collected since the window last had focus, shown in the title
+    pub unseen: usize,
+}
+
+/// Checking the input text while it is typed
+#[derive(Debug, Default)]
+pub struct InputCheckState {
+    /// When the input text last changed, until it has been checked
+    pub edited_at: Option<Instant>,
+    /// Strings in the unparsed input that decode
+    pub valid: usize,
+    /// Strings in the unparsed input that do not decode
+    pub invalid: usize,
+    /// Strings of the input that failed to decode, highlighted in the paste
+    /// box: byte range within the line, by zero-based line
+    pub invalid_lines: BTreeMap<usize, Range<usize>>,
+}
+
+/// Folder scan settings being edited, as typed
+#[derive(Debug, Default)]
+pub struct ScanSettingsState {
+    /// Whether the settings are shown below the load buttons
+    pub show: bool,
+    /// Comma-separated extensions
+    pub extensions: String,
+    /// Comma-separated ignore patterns
+    pub ignore: String,
+    /// Size limit in megabytes, empty for none
+    pub max_size_mb: String,
+    /// Folder levels searched below the scanned folder, empty for all
+    pub max_depth: String,
+    /// Why the typed settings were not applied
+    pub error: Option<String>,
+}
+
+/// Media bundled with loaded packs
+#[derive(Debug)]
+pub struct MediaPackState {
+    /// Packs with media among the loaded folders
+    pub packs: Vec<MediaPack>,
+    /// Install the pack media together with the auras
+    pub install: bool,
+    /// Overwrite installed media files whose content differs
+    pub replace_differing: bool,
+}
+
+impl Default for MediaPackState {
+    fn default() -> Self {
+        Self {
+            packs: Vec::new(),
+            install: true,
+            replace_differing: false,
         }
     }
 }
 
+/// Auras set aside for import, kept apart from the parsed list so clearing
+/// the input does not drop them
+#[derive(Debug, Default)]
+pub struct StagingState {
+    pub entries: Vec<ParsedAuraEntry>,
+    /// Whether the pending import takes the staged auras instead of the
+    /// selected ones of the parsed list
+    pub importing: bool,
+}
+
+/// Second window showing another SavedVariables file side by side
+#[derive(Debug, Default)]
+pub struct SideWindowState {
+    /// The window, while it is open
+    pub id: Option<iced::window::Id>,
+    /// SavedVariables file shown in the window
+    pub path: Option<PathBuf>,
+    /// Aura tree of that file
+    pub tree: Option<ParsedTree>,
+    pub is_loading: bool,
+    /// Auras checked for copying to the import list
+    pub selected: HashSet<String>,
+    pub is_copying: bool,
+}
+
 // =============================================================================
 // Original Types (for async task results and parsed entries)
 // =============================================================================
@@ -158,6 +753,258 @@ pub struct ParsedAuraEntry {
     pub validation: ValidationResult,
     pub aura: Option<WeakAura>,
     pub selected: bool,
+    /// File the aura was loaded from (`None` for pasted input)
+    pub source: Option<PathBuf>,
+    /// Child auras of a group left out of the import (with their descendants)
+    pub excluded_children: HashSet<String>,
+    /// Whether the child auras are listed below the entry
+    pub show_children: bool,
+    /// Import the only child of a wrapper group in place of the group
+    pub unwrap_group: bool,
+}
+
+impl ParsedAuraEntry {
+    /// Entry for a decoded aura, unselected
+    pub fn new(aura: WeakAura, source: Option<PathBuf>) -> Self {
+        Self {
+            validation: ValidationResult {
+                is_valid: true,
+                aura_id: Some(aura.id.clone()),
+                is_group: aura.is_group,
+                child_count: aura.children.len(),
+                error: None,
+            },
+            aura: Some(aura),
+            selected: false,
+            source,
+            excluded_children: HashSet::new(),
+            show_children: false,
+            unwrap_group: false,
+        }
+    }
+
+    /// The aura as it will be imported, without the excluded children and
+    /// unwrapped when requested
+    pub fn aura_for_import(&self) -> Option<WeakAura> {
+        let mut aura = self.aura.clone()?;
+        aura.remove_children(&self.excluded_children);
+        if self.unwrap_group {
+            if let Some(child) = aura.unwrap_single_child() {
+                return Some(child);
+            }
+        }
+        Some(aura)
+    }
+
+    /// Include or exclude a child aura. Including a child also includes the
+    /// subgroups it sits in.
+    pub fn set_child_included(&mut self, id: &str, included: bool) {
+        if !included {
+            self.excluded_children.insert(id.to_string());
+            return;
+        }
+        self.excluded_children.remove(id);
+        let Some(aura) = &self.aura else {
+            return;
+        };
+        let parent_of = |id: &str| {
+            aura.child_data.iter().find_map(|child| {
+                let table = child.as_table()?;
+                match (table.get("id"), table.get("parent")) {
+                    (Some(LuaValue::String(child_id)), Some(LuaValue::String(parent)))
+                        if child_id == id =>
+                    {
+                        Some(parent.clone())
+                    }
+                    _ => None,
+                }
+            })
+        };
+        let mut current = parent_of(id);
+        // Bounded, as malformed data may contain parent cycles
+        for _ in 0..aura.child_data.len() {
+            let Some(parent) = current else {
+                break;
+            };
+            self.excluded_children.remove(&parent);
+            current = parent_of(&parent);
+        }
+    }
+
+    /// Child auras that will be imported, of all child auras
+    pub fn included_child_count(&self) -> (usize, usize) {
+        let Some(aura) = &self.aura else {
+            return (0, self.validation.child_count);
+        };
+        let total = aura.child_data.len();
+        if self.excluded_children.is_empty() {
+            return (total, total);
+        }
+        (
+            self.aura_for_import().map_or(0, |a| a.child_data.len()),
+            total,
+        )
+    }
+
+    /// Section label for this entry under the given grouping
+    pub fn group_key(&self, grouping: ListGrouping) -> String {
+        match grouping {
+            ListGrouping::None => String::new(),
+            ListGrouping::SourceFile => self.source_name(),
+            ListGrouping::RegionType => self.region_type().to_string(),
+            ListGrouping::Validity => {
+                if self.validation.is_valid {
+                    "Valid".to_string()
+                } else {
+                    "Invalid".to_string()
+                }
+            }
+        }
+    }
+
+    /// Name of the file the aura was loaded from
+    pub fn source_name(&self) -> String {
+        self.source
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Pasted input".to_string())
+    }
+
+    /// Why the aura looks like an empty placeholder, if it does
+    pub fn trivial_reason(&self) -> Option<&'static str> {
+        self.aura.as_ref().and_then(trivial_reason)
+    }
+
+    pub fn region_type(&self) -> &str {
+        self.aura
+            .as_ref()
+            .and_then(|a| a.region_type.as_deref())
+            .unwrap_or("unknown")
+    }
+
+    /// Length of the import string in bytes (0 when it did not decode)
+    pub fn size(&self) -> usize {
+        self.aura.as_ref().map_or(0, |a| a.original_string.len())
+    }
+
+    /// Order of two entries by a table column (ascending)
+    pub fn compare_by(&self, other: &Self, column: ListColumn) -> std::cmp::Ordering {
+        match column {
+            ListColumn::Id => self
+                .validation
+                .summary()
+                .to_lowercase()
+                .cmp(&other.validation.summary().to_lowercase()),
+            ListColumn::Type => self.region_type().cmp(other.region_type()),
+            ListColumn::Children => self
+                .validation
+                .child_count
+                .cmp(&other.validation.child_count),
+            ListColumn::Source => self.source_name().cmp(&other.source_name()),
+            ListColumn::Size => self.size().cmp(&other.size()),
+            // Invalid entries first
+            ListColumn::Status => self.validation.is_valid.cmp(&other.validation.is_valid),
+        }
+    }
+}
+
+/// Column of the parsed auras table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ListColumn {
+    Id,
+    Type,
+    Children,
+    Source,
+    Size,
+    Status,
+}
+
+impl ListColumn {
+    pub const ALL: [ListColumn; 6] = [
+        ListColumn::Id,
+        ListColumn::Type,
+        ListColumn::Children,
+        ListColumn::Source,
+        ListColumn::Size,
+        ListColumn::Status,
+    ];
+
+    /// Narrowest width a column can be resized to
+    pub const MIN_WIDTH: f32 = 40.0;
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ListColumn::Id => "ID",
+            ListColumn::Type => "Type",
+            ListColumn::Children => "Children",
+            ListColumn::Source => "Source",
+            ListColumn::Size => "Size",
+            ListColumn::Status => "Status",
+        }
+    }
+
+    pub fn default_width(self) -> f32 {
+        match self {
+            ListColumn::Id => 260.0,
+            ListColumn::Type => 100.0,
+            ListColumn::Children => 80.0,
+            ListColumn::Source => 140.0,
+            ListColumn::Size => 70.0,
+            ListColumn::Status => 70.0,
+        }
+    }
+}
+
+/// File format for exported import reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+impl ReportFormat {
+    /// File extension (without dot)
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Csv => "csv",
+            ReportFormat::Json => "json",
+        }
+    }
+}
+
+/// How the parsed aura list is grouped into sections
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListGrouping {
+    /// Flat list
+    #[default]
+    None,
+    /// One section per source file
+    SourceFile,
+    /// One section per region type (icon, aurabar, group, ...)
+    RegionType,
+    /// Valid and invalid entries
+    Validity,
+}
+
+impl ListGrouping {
+    pub const ALL: [ListGrouping; 4] = [
+        ListGrouping::None,
+        ListGrouping::SourceFile,
+        ListGrouping::RegionType,
+        ListGrouping::Validity,
+    ];
+}
+
+impl std::fmt::Display for ListGrouping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListGrouping::None => write!(f, "None"),
+            ListGrouping::SourceFile => write!(f, "Source file"),
+            ListGrouping::RegionType => write!(f, "Region type"),
+            ListGrouping::Validity => write!(f, "Validity"),
+        }
+    }
 }
 
 /// UI state for a single conflict resolution
@@ -168,74 +1015,101 @@ pub struct ConflictResolutionUI {
     /// Current action
     pub action: ConflictAction,
     /// Categories to update (when action is UpdateSelected)
-    pub categories: HashSet<UpdateCategory>,
+    pub categories: HashSet<CategoryKey>,
     /// Whether to show details
     pub expanded: bool,
+    /// Advanced mode: pick individual top-level fields instead of categories
+    pub advanced: bool,
+    /// Fields to update in advanced mode
+    pub fields: BTreeSet<String>,
 }
 
-/// Result from background loading task
+/// Event of a background task: progress while it runs, then its result.
+/// Progress events are coalesced by the sending side, see `TaskSender`.
 #[derive(Clone, Debug)]
-pub enum LoadingUpdate {
-    /// Progress update during loading (0.0 to 1.0)
+pub enum TaskEvent<T> {
+    /// Work done and to do so far, e.g. bytes read or pipeline phases
     Progress {
-        current: usize,
-        total: usize,
+        current: u64,
+        total: u64,
         message: String,
     },
-    /// Loading completed successfully
-    Complete {
-        entries: Vec<ParsedAuraEntry>,
-        added: usize,
-        duplicates: usize,
-        errors: Vec<String>,
-    },
-    /// Loading failed with an error
+    /// The task finished
+    Complete(T),
+    /// The task failed with an error
     Error(String),
 }
 
-/// Result from background import task
+impl<T> TaskEvent<T> {
+    /// Whether the event ends its task
+    pub fn is_final(&self) -> bool {
+        !matches!(self, Self::Progress { .. })
+    }
+}
+
+/// Auras decoded by a background loading task
 #[derive(Clone, Debug)]
-pub enum ImportUpdate {
-    /// Progress update during import (0.0 to 1.0)
-    #[allow(dead_code)]
-    Progress {
-        current: usize,
-        total: usize,
-        message: String,
-    },
+pub struct LoadedAuras {
+    pub entries: Vec<ParsedAuraEntry>,
+    pub errors: Vec<String>,
+}
+
+/// What a background import task ended with
+#[derive(Clone, Debug)]
+pub enum ImportOutcome {
     /// Conflicts detected — hand data back to UI for resolution
     ConflictsDetected(ConflictDetectionResult),
-    /// Import completed successfully
-    Complete {
+    /// Auras imported and saved
+    Imported {
         result: ImportResult,
         tree: Vec<AuraTreeNode>,
         tree_count: usize,
     },
-    /// Import failed with an error
-    Error(String),
+    /// Auras imported, but writing the file was denied
+    SaveDenied(DeniedSave),
 }
 
-/// Result from background SavedVariables scanning task
+/// A save of the SavedVariables file that was denied access
 #[derive(Clone, Debug)]
-pub enum ScanUpdate {
-    /// Scanning completed successfully
-    Complete {
+pub struct DeniedSave {
+    pub path: PathBuf,
+    /// The error, with a hint on what to do about it
+    pub message: String,
+    /// Lua the save would have written
+    pub content: String,
+}
+
+/// What a background SavedVariables scanning task found
+#[derive(Clone, Debug)]
+pub enum ScanOutcome {
+    /// Aura tree of the selected file
+    Tree {
         tree: Vec<AuraTreeNode>,
         count: usize,
     },
-    /// Scanning failed with an error
-    Error(String),
+    /// A SavedVariables file was parsed for display
+    Parsed(ParsedTree),
+    /// The selected file does not parse; copies it can be restored from
+    Damaged {
+        error: String,
+        fallbacks: Vec<FallbackCandidate>,
+    },
+    /// Background parsing of a discovered file finished
+    Preparsed {
+        path: PathBuf,
+        parsed: Result<ParsedTree, String>,
+    },
 }
 
-/// Result from background aura removal task
+/// Result of a background aura removal task
 #[derive(Clone, Debug)]
-pub enum RemovalUpdate {
-    /// Removal completed successfully
-    Complete {
-        removed_count: usize,
-        tree: Vec<AuraTreeNode>,
-        tree_count: usize,
-    },
-    /// Removal failed with an error
-    Error(String),
+pub struct RemovedAuras {
+    pub removed_count: usize,
+    pub tree: Vec<AuraTreeNode>,
+    pub tree_count: usize,
 }
+
+pub type LoadingUpdate = TaskEvent<LoadedAuras>;
+pub type ImportUpdate = TaskEvent<ImportOutcome>;
+pub type ScanUpdate = TaskEvent<ScanOutcome>;
+pub type RemovalUpdate = TaskEvent<RemovedAuras>;
@@ -0,0 +1,136 @@
+//! Right-click menus of the parsed auras list and the existing auras tree,
+//! shown inline below the clicked item.
+
+use iced::widget::{button, container, pick_list, row, text, text_input, Row};
+use iced::{Element, Length};
+
+use crate::decoder::AuraTextFormat;
+use crate::saved_variables::AuraTreeNode;
+use crate::theme::{self, colors, spacing, typography};
+
+use super::super::state::ParsedAuraEntry;
+use super::super::{Message, WeakAuraImporter};
+
+/// Menu entry button
+fn menu_item<'a>(label: &'a str, message: Message) -> Element<'a, Message> {
+    button(text(label).size(typography::CAPTION))
+        .style(theme::button_secondary)
+        .on_press(message)
+        .into()
+}
+
+/// Menu entries in a wrapping row, ending with a close button
+fn menu_row<'a>(items: Vec<Element<'a, Message>>, indent: f32) -> Element<'a, Message> {
+    let mut menu = Row::with_children(items)
+        .spacing(spacing::XS)
+        .align_y(iced::Alignment::Center);
+    menu = menu.push(
+        button(text("×").size(typography::CAPTION))
+            .style(theme::button_frameless)
+            .on_press(Message::CloseContextMenu),
+    );
+    container(
+        container(menu.wrap())
+            .padding(spacing::XS)
+            .style(theme::container_surface)
+            .width(Length::Fill),
+    )
+    .padding(iced::Padding::default().left(indent))
+    .into()
+}
+
+impl WeakAuraImporter {
+    /// Menu of a parsed aura: preview, edit, export or remove it
+    pub(crate) fn render_parsed_context_menu<'a>(
+        &self,
+        idx: usize,
+        entry: &ParsedAuraEntry,
+    ) -> Element<'a, Message> {
+        let mut items = Vec::new();
+        if entry.validation.is_valid {
+            items.push(menu_item("Preview", Message::PreviewParsedAura(idx)));
+            items.push(menu_item(
+                "Edit Properties",
+                Message::EditAuraProperties(idx),
+            ));
+            items.push(menu_item("Edit Raw", Message::EditRawData(idx)));
+            items.push(menu_item("Export...", Message::ExportParsedAura(idx)));
+            items.push(menu_item("Save as Template", Message::SaveAsTemplate(idx)));
+        }
+        items.push(menu_item("Remove", Message::RemoveAuraFromList(idx)));
+        menu_row(items, 24.0)
+    }
+
+    /// Menu of an existing aura: rename, move, remove, export or copy it
+    pub(crate) fn render_tree_context_menu<'a>(
+        &self,
+        node: &'a AuraTreeNode,
+        indent: f32,
+    ) -> Element<'a, Message> {
+        let id = node.id.clone();
+
+        if let Some(new_id) = &self.context_menu.rename {
+            let valid = !new_id.trim().is_empty();
+            return menu_row(
+                vec![
+                    text_input("New ID", new_id)
+                        .on_input(Message::RenameStoredAuraInputChanged)
+                        .on_submit_maybe(valid.then_some(Message::ConfirmRenameStoredAura))
+                        .size(typography::CAPTION)
+                        .style(theme::text_input_style)
+                        .width(Length::Fixed(180.0))
+                        .into(),
+                    button(text("Rename").size(typography::CAPTION))
+                        .style(theme::button_primary)
+                        .on_press_maybe(valid.then_some(Message::ConfirmRenameStoredAura))
+                        .into(),
+                ],
+                indent,
+            );
+        }
+
+        // Groups outside the aura's own subtree
+        let own_groups = node.group_ids();
+        let groups: Vec<String> = self
+            .saved_vars
+            .auras_tree
+            .iter()
+            .flat_map(AuraTreeNode::group_ids)
+            .filter(|group| !own_groups.contains(group))
+            .map(str::to_string)
+            .collect();
+
+        let mut items = vec![
+            menu_item("Rename", Message::StartRenameStoredAura),
+            menu_item("Remove", Message::RemoveStoredAura(id.clone())),
+            menu_item("Export...", Message::ExportStoredAura(id.clone())),
+            menu_item("Copy ID", Message::CopyAuraId(id.clone())),
+            menu_item(
+                "Copy as JSON",
+                Message::CopyStoredAura(id.clone(), AuraTextFormat::Json),
+            ),
+            menu_item(
+                "Copy as Lua table",
+                Message::CopyStoredAura(id.clone(), AuraTextFormat::Lua),
+            ),
+        ];
+        if !groups.is_empty() {
+            items.push(
+                row![
+                    text("Move to:")
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_SECONDARY),
+                    pick_list(groups, None::<String>, move |group| {
+                        Message::MoveStoredAura(id.clone(), group)
+                    })
+                    .placeholder("Group")
+                    .text_size(typography::CAPTION),
+                ]
+                .spacing(spacing::XS)
+                .align_y(iced::Alignment::Center)
+                .into(),
+            );
+        }
+        menu_row(items, indent)
+    }
+}
@@ -1,16 +1,40 @@
 //! Import confirmation and conflict resolution dialogs.
 
+use std::collections::HashSet;
+
 use iced::widget::{
-    button, checkbox, column, container, pick_list, row, scrollable, space, text, text_input,
-    Column,
+    button, checkbox, column, container, pick_list, row, scrollable, space, text, text_editor,
+    text_input, Column,
 };
 use iced::{Alignment, Element, Length, Padding};
 
-use crate::saved_variables::{ConflictAction, ImportConflict};
+use crate::chunking::DEFAULT_CHUNK_SIZE;
+use crate::decoder::LuaValue;
+use crate::duplicates::DuplicatePolicy;
+use crate::fs_util;
+use crate::lua_parser::LuaParser;
+use crate::pack::DEFAULT_MEDIA_ADDON;
+use crate::repair::damaged_path;
+use crate::saved_variables::{
+    backup_path, format_age, topmost_selected, tree_anchor_dependents, ConflictAction,
+    ImportConflict, ImportOutcome, VersionChange,
+};
+use crate::scripting::scripts_dir;
 use crate::theme::{self, colors, spacing, typography};
+use crate::transforms::TransformPipeline;
 
+use super::super::state::{ConflictSort, ConflictState, ReportFormat, CONFLICT_PAGE_SIZE};
 use super::super::{Message, WeakAuraImporter};
 
+/// Checkboxes per row in the category grids
+const CATEGORY_GRID_COLUMNS: usize = 4;
+
+/// Longest value shown in the backup comparison before it is cut off
+const VALUE_PREVIEW_CHARS: usize = 300;
+
+/// Missing media files listed in the import confirmation
+const MISSING_MEDIA_SHOWN: usize = 5;
+
 impl WeakAuraImporter {
     /// Overlay the setup wizard for selecting SavedVariables
     pub(crate) fn overlay_setup_wizard<'a>(
@@ -33,16 +57,49 @@ impl WeakAuraImporter {
             .style(theme::button_secondary)
             .on_press(Message::BrowseWowPath);
 
+        let add_btn = button(text("Add").size(typography::BODY))
+            .style(theme::button_secondary)
+            .on_press(Message::AddWowPath);
+
         content = content.push(
             column![
                 text("WoW Path:")
                     .size(typography::BODY)
                     .color(colors::TEXT_SECONDARY),
-                row![wow_path_input.width(Length::Fill), browse_btn].spacing(spacing::SM),
+                row![wow_path_input.width(Length::Fill), browse_btn, add_btn].spacing(spacing::SM),
             ]
             .spacing(spacing::XS),
         );
 
+        if !self.saved_vars.wow_paths.is_empty() {
+            let mut paths_col = Column::new().spacing(spacing::MICRO);
+            for path in &self.saved_vars.wow_paths {
+                paths_col = paths_col.push(
+                    row![
+                        text(path.display().to_string())
+                            .size(typography::CAPTION)
+                            .color(colors::TEXT_SECONDARY)
+                            .width(Length::Fill),
+                        button(text("×").size(typography::CAPTION).color(colors::ERROR))
+                            .style(theme::button_frameless)
+                            .on_press(Message::RemoveWowPath(path.clone())),
+                    ]
+                    .spacing(spacing::XS)
+                    .align_y(Alignment::Center),
+                );
+            }
+
+            content = content.push(
+                column![
+                    text("Scanned installations:")
+                        .size(typography::BODY)
+                        .color(colors::TEXT_SECONDARY),
+                    paths_col,
+                ]
+                .spacing(spacing::XS),
+            );
+        }
+
         content = content.push(
             text("Discovered SavedVariables:")
                 .size(typography::BODY)
@@ -57,16 +114,34 @@ impl WeakAuraImporter {
             let mut files_col = Column::new().spacing(spacing::XS);
             for sv_info in &self.saved_vars.discovered_files {
                 let is_selected = self.saved_vars.selected_path.as_ref() == Some(&sv_info.path);
-                let label_text = format!("{} ({})", sv_info.account, sv_info.pretty_flavor());
+                let label_text = if self.saved_vars.wow_paths.len() > 1 {
+                    format!(
+                        "{} ({}) - {}",
+                        sv_info.account,
+                        sv_info.pretty_flavor(),
+                        sv_info.install.display()
+                    )
+                } else {
+                    format!("{} ({})", sv_info.account, sv_info.pretty_flavor())
+                };
                 let path_clone = sv_info.path.clone();
 
-                let file_btn = button(row![text(label_text).size(typography::BODY).color(
-                    if is_selected {
-                        colors::BG_VOID
-                    } else {
-                        colors::TEXT_SECONDARY
-                    }
-                )])
+                let file_btn = button(column![
+                    text(label_text)
+                        .size(typography::BODY)
+                        .color(if is_selected {
+                            colors::BG_VOID
+                        } else {
+                            colors::TEXT_SECONDARY
+                        }),
+                    text(sv_info.details())
+                        .size(typography::CAPTION)
+                        .color(if is_selected {
+                            colors::BG_VOID
+                        } else {
+                            colors::TEXT_MUTED
+                        }),
+                ])
                 .width(Length::Fill)
                 .style(if is_selected {
                     theme::button_primary
@@ -166,9 +241,9 @@ impl WeakAuraImporter {
         underlay: Element<'a, Message>,
     ) -> Element<'a, Message> {
         let count = self
-            .parsed_auras
-            .iter()
-            .filter(|e| e.selected && e.validation.is_valid)
+            .entries_for_import()
+            .into_iter()
+            .filter(|e| e.validation.is_valid)
             .count();
 
         let target_text = if let Some(path) = &self.saved_vars.selected_path {
@@ -180,12 +255,74 @@ impl WeakAuraImporter {
             String::new()
         };
 
+        let groups: Vec<String> = self
+            .saved_vars
+            .auras_tree
+            .iter()
+            .flat_map(|node| node.group_ids())
+            .map(str::to_string)
+            .collect();
+        let target_group: Element<Message> = if groups.is_empty() {
+            Column::new().into()
+        } else {
+            row![
+                text("Target group:")
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_SECONDARY),
+                pick_list(groups, self.saved_vars.target_group.clone(), |id| {
+                    Message::SetImportTargetGroup(Some(id))
+                })
+                .placeholder("None (top level)")
+                .text_size(typography::CAPTION),
+                button(text("×").size(typography::CAPTION))
+                    .style(theme::button_frameless)
+                    .on_press_maybe(
+                        self.saved_vars
+                            .target_group
+                            .is_some()
+                            .then_some(Message::SetImportTargetGroup(None))
+                    ),
+            ]
+            .spacing(spacing::XS)
+            .align_y(Alignment::Center)
+            .into()
+        };
+
+        let transforms = TransformPipeline::from_steps(&self.config.transforms);
+        let transforms_text = if transforms.is_empty() {
+            String::new()
+        } else {
+            format!("Transforms: {}", transforms.names().join(", "))
+        };
+
         let dialog_content = column![
             text(format!("Import {} aura(s)?", count)).size(typography::HEADING),
             space::vertical().height(Length::Fixed(spacing::SM)),
             text(target_text)
                 .size(typography::BODY)
                 .color(colors::TEXT_MUTED),
+            text(transforms_text)
+                .size(typography::BODY)
+                .color(colors::TEXT_MUTED),
+            target_group,
+            self.render_import_duplicates(),
+            self.render_nesting_warnings(),
+            self.render_pack_media(),
+            checkbox(self.config.write_companion_data)
+                .label("Write update info for Wago auras (WeakAurasCompanion addon)")
+                .on_toggle(Message::SetWriteCompanionData)
+                .text_size(typography::CAPTION),
+            checkbox(self.config.import_options.chunk_size.is_some())
+                .label(format!(
+                    "Save in chunks of {} auras, with a backup after each",
+                    self.config
+                        .import_options
+                        .chunk_size
+                        .unwrap_or(DEFAULT_CHUNK_SIZE)
+                ))
+                .on_toggle(Message::SetChunkedSaves)
+                .text_size(typography::CAPTION),
+            self.render_missing_media(),
             space::vertical().height(Length::Fixed(spacing::LG)),
             row![
                 button(text("Cancel").size(typography::BODY))
@@ -198,7 +335,9 @@ impl WeakAuraImporter {
                         .color(colors::BG_VOID)
                 )
                 .style(theme::button_primary)
-                .on_press(Message::ConfirmImport),
+                .on_press_maybe(
+                    (!self.has_unresolved_duplicates()).then_some(Message::ConfirmImport)
+                ),
             ]
             .spacing(spacing::SM)
             .align_y(Alignment::Center),
@@ -227,6 +366,166 @@ impl WeakAuraImporter {
         iced::widget::stack![underlay, backdrop].into()
     }
 
+    /// Option to install the media bundled with the loaded packs (empty when
+    /// no pack has media)
+    fn render_pack_media(&self) -> Element<'_, Message> {
+        let packs = &self.media_packs.packs;
+        if packs.is_empty() {
+            return Column::new().into();
+        }
+
+        let files: usize = packs.iter().map(|pack| pack.files.len()).sum();
+        let mut content = Column::new().spacing(spacing::MICRO).push(
+            checkbox(self.media_packs.install)
+                .label(format!(
+                    "Install {} media file(s) from {} pack(s)",
+                    files,
+                    packs.len()
+                ))
+                .on_toggle(Message::SetInstallPackMedia)
+                .text_size(typography::CAPTION),
+        );
+        if self.media_packs.install {
+            content = content.push(
+                checkbox(self.media_packs.replace_differing)
+                    .label("Replace installed files that differ")
+                    .on_toggle(Message::SetReplacePackMedia)
+                    .text_size(typography::CAPTION),
+            );
+        }
+        // Packs naming their media addon are installed there
+        if self.media_packs.install && packs.iter().any(|p| p.manifest.media_addon.is_none()) {
+            content = content.push(
+                row![
+                    text("Media folder: Interface/AddOns/")
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_SECONDARY),
+                    text_input(DEFAULT_MEDIA_ADDON, &self.config.media_folder)
+                        .on_input(Message::MediaFolderInput)
+                        .size(typography::CAPTION)
+                        .style(theme::text_input_style)
+                        .width(Length::Fixed(160.0)),
+                ]
+                .spacing(spacing::XS)
+                .align_y(Alignment::Center),
+            );
+        }
+        content.into()
+    }
+
+    /// Whether the duplicate policy leaves differing copies of an aura for
+    /// the user to choose from before importing
+    fn has_unresolved_duplicates(&self) -> bool {
+        self.config.duplicate_policy == DuplicatePolicy::Ask
+            && self.status.import_duplicates.iter().any(|d| !d.identical)
+    }
+
+    /// Auras about to be imported more than once, and which copy is used
+    /// (empty when there is none)
+    fn render_import_duplicates(&self) -> Element<'_, Message> {
+        let duplicates = &self.status.import_duplicates;
+        if duplicates.is_empty() {
+            return Column::new().into();
+        }
+
+        let heading = match self.config.duplicate_policy {
+            DuplicatePolicy::KeepFirst => {
+                "Auras listed more than once - the first copy is imported:"
+            }
+            DuplicatePolicy::KeepLast => "Auras listed more than once - the last copy is imported:",
+            DuplicatePolicy::Ask => {
+                "⚠ Auras listed more than once - keep one copy of each in the list first:"
+            }
+        };
+        let mut list = Column::new().spacing(spacing::MICRO).push(
+            text(heading)
+                .size(typography::CAPTION)
+                .color(colors::WARNING),
+        );
+        for duplicate in duplicates {
+            let identical = if duplicate.identical {
+                " (identical)"
+            } else {
+                ""
+            };
+            list = list.push(
+                text(format!(
+                    "{} ×{}{}",
+                    duplicate.aura_id,
+                    duplicate.positions.len(),
+                    identical
+                ))
+                .size(typography::CAPTION)
+                .color(colors::TEXT_SECONDARY),
+            );
+        }
+        list.into()
+    }
+
+    /// Warning about group nesting WeakAuras handles badly (empty when there
+    /// is none)
+    fn render_nesting_warnings(&self) -> Element<'_, Message> {
+        let warnings = &self.status.nesting_warnings;
+        if warnings.is_empty() {
+            return Column::new().into();
+        }
+
+        let mut list = Column::new().spacing(spacing::MICRO).push(
+            text("⚠ Group nesting WeakAuras may not handle well:")
+                .size(typography::CAPTION)
+                .color(colors::WARNING),
+        );
+        for warning in warnings {
+            list = list.push(
+                text(warning.to_string())
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_SECONDARY),
+            );
+        }
+        list.into()
+    }
+
+    /// Warning about media files the auras to import reference but the game
+    /// folder lacks (empty when everything was found)
+    fn render_missing_media(&self) -> Element<'_, Message> {
+        let missing = &self.status.missing_media;
+        if missing.is_empty() {
+            return Column::new().into();
+        }
+
+        let mut list = Column::new().spacing(spacing::MICRO).push(
+            text(format!(
+                "⚠ {} media file(s) not found - these auras may show blank textures or play no sound:",
+                missing.len()
+            ))
+            .size(typography::CAPTION)
+            .color(colors::WARNING),
+        );
+        for reference in missing.iter().take(MISSING_MEDIA_SHOWN) {
+            list = list.push(
+                text(format!(
+                    "{}: {} ({})",
+                    reference.kind,
+                    reference.path,
+                    reference.aura_ids.join(", ")
+                ))
+                .size(typography::CAPTION)
+                .color(colors::TEXT_SECONDARY),
+            );
+        }
+        if missing.len() > MISSING_MEDIA_SHOWN {
+            list = list.push(
+                text(format!(
+                    "... and {} more",
+                    missing.len() - MISSING_MEDIA_SHOWN
+                ))
+                .size(typography::CAPTION)
+                .color(colors::TEXT_MUTED),
+            );
+        }
+        list.into()
+    }
+
     /// Overlay the conflict resolution dialog on top of the main view
     pub(crate) fn overlay_conflict_dialog<'a>(
         &'a self,
@@ -254,10 +553,63 @@ impl WeakAuraImporter {
         // Global category selection header
         let global_cat_header = text("Default categories to update:").size(typography::BODY);
 
-        // Category checkboxes (simplified grid - row of 4)
-        // Build rows without borrowing a local Vec
+        // Category checkboxes (built-in categories, then custom ones)
         let categories_grid = self.build_category_grid();
 
+        // Resolution presets: pick to apply, or save the current default categories
+        let preset_names: Vec<String> = self
+            .config
+            .resolution_presets
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
+        let selected_preset = self.conflicts.selected_preset.clone();
+        let is_auto = selected_preset.is_some() && self.config.auto_preset == selected_preset;
+        let presets_row = row![
+            text("Preset:")
+                .size(typography::CAPTION)
+                .color(colors::TEXT_SECONDARY),
+            pick_list(
+                preset_names,
+                selected_preset.clone(),
+                Message::ApplyConflictPreset
+            )
+            .placeholder("None")
+            .text_size(typography::CAPTION),
+            checkbox(is_auto)
+                .label("Apply automatically")
+                .on_toggle_maybe(selected_preset.is_some().then_some(Message::SetAutoPreset))
+                .text_size(typography::CAPTION),
+            button(text("Delete").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press_maybe(
+                    selected_preset
+                        .is_some()
+                        .then_some(Message::DeleteConflictPreset)
+                ),
+            space::horizontal(),
+            text_input("New preset name...", &self.conflicts.preset_name)
+                .on_input(Message::SetConflictPresetName)
+                .on_submit(Message::SaveConflictPreset)
+                .size(typography::CAPTION)
+                .style(theme::text_input_style)
+                .width(Length::Fixed(140.0)),
+            pick_list(
+                ConflictAction::ALL,
+                Some(self.conflicts.preset_action),
+                Message::SetConflictPresetAction
+            )
+            .text_size(typography::CAPTION),
+            button(text("Save").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press_maybe(
+                    (!self.conflicts.preset_name.trim().is_empty())
+                        .then_some(Message::SaveConflictPreset)
+                ),
+        ]
+        .spacing(spacing::SM)
+        .align_y(Alignment::Center);
+
         // Bulk action buttons
         let bulk_actions = row![
             button(text("Import All").size(typography::CAPTION))
@@ -271,11 +623,71 @@ impl WeakAuraImporter {
             button(text("Replace All").size(typography::CAPTION))
                 .style(theme::button_secondary)
                 .on_press(Message::SetAllConflictsAction(ConflictAction::ReplaceAll)),
+            button(text("Merge All").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press(Message::SetAllConflictsAction(ConflictAction::Merge)),
+            button(text("Only Newer").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press(Message::UpdateOnlyNewerConflicts),
         ]
         .spacing(spacing::SM);
 
-        // Conflict list
-        let conflict_list = self.render_conflict_list(&conflicts);
+        // Sorting and filters
+        let list_controls = row![
+            text("Sort:")
+                .size(typography::CAPTION)
+                .color(colors::TEXT_SECONDARY),
+            pick_list(
+                ConflictSort::ALL,
+                Some(self.conflicts.sort),
+                Message::SetConflictSort
+            )
+            .text_size(typography::CAPTION),
+            checkbox(self.conflicts.only_trigger_changes)
+                .label("Trigger changes only")
+                .on_toggle(Message::SetConflictOnlyTriggers)
+                .text_size(typography::CAPTION),
+            checkbox(self.conflicts.only_groups)
+                .label("Groups only")
+                .on_toggle(Message::SetConflictOnlyGroups)
+                .text_size(typography::CAPTION),
+        ]
+        .spacing(spacing::SM)
+        .align_y(Alignment::Center);
+
+        // Conflict list (filtered, sorted, one page at a time)
+        let visible = self.conflicts.visible_indices();
+        let page_count = ConflictState::page_count(visible.len());
+        let page = self.conflicts.page.min(page_count - 1);
+        let page_indices: Vec<usize> = visible
+            .iter()
+            .copied()
+            .skip(page * CONFLICT_PAGE_SIZE)
+            .take(CONFLICT_PAGE_SIZE)
+            .collect();
+        let conflict_list = self.render_conflict_list(&conflicts, &page_indices);
+
+        let pagination = row![
+            button(text("<").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press_maybe(page.checked_sub(1).map(Message::SetConflictPage)),
+            text(format!(
+                "Page {}/{} ({} of {} shown)",
+                page + 1,
+                page_count,
+                visible.len(),
+                conflict_count
+            ))
+            .size(typography::CAPTION)
+            .color(colors::TEXT_MUTED),
+            button(text(">").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press_maybe(
+                    (page + 1 < page_count).then_some(Message::SetConflictPage(page + 1))
+                ),
+        ]
+        .spacing(spacing::SM)
+        .align_y(Alignment::Center);
 
         let conflict_list_container = container(
             scrollable(conflict_list)
@@ -307,11 +719,25 @@ impl WeakAuraImporter {
             space::vertical().height(Length::Fixed(spacing::MD)),
             global_cat_header,
             categories_grid,
+            row![
+                checkbox(self.config.preserve_position)
+                    .label("Always keep position & size")
+                    .on_toggle(Message::SetPreservePosition)
+                    .text_size(typography::CAPTION),
+                checkbox(self.config.keep_user_config)
+                    .label("Keep my option values when replacing")
+                    .on_toggle(Message::SetKeepUserConfig)
+                    .text_size(typography::CAPTION),
+            ]
+            .spacing(spacing::MD),
+            presets_row,
             space::vertical().height(Length::Fixed(spacing::MD)),
             text("Conflicts:").size(typography::BODY),
+            list_controls,
             bulk_actions,
             space::vertical().height(Length::Fixed(spacing::SM)),
             conflict_list_container,
+            pagination,
             space::vertical().height(Length::Fixed(spacing::MD)),
             action_buttons,
         ]
@@ -338,151 +764,67 @@ impl WeakAuraImporter {
         iced::widget::stack![underlay, backdrop].into()
     }
 
-    /// Build the category checkbox grid without borrowing issues
+    /// Build the global category checkbox grid (built-in and custom categories)
     fn build_category_grid(&self) -> Column<'_, Message> {
-        use crate::categories::UpdateCategory;
-
-        // Row 1: Name, Display, Trigger, Load
-        let row1 = row![
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::Name)
-            )
-            .label("Name")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::Name))
-            .text_size(typography::CAPTION),
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::Display)
-            )
-            .label("Display")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::Display))
-            .text_size(typography::CAPTION),
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::Trigger)
-            )
-            .label("Trigger")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::Trigger))
-            .text_size(typography::CAPTION),
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::Load)
-            )
-            .label("Load")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::Load))
-            .text_size(typography::CAPTION),
-        ]
-        .spacing(spacing::MD);
-
-        // Row 2: Action, Animation, Conditions, Author Options
-        let row2 = row![
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::Action)
-            )
-            .label("Actions")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::Action))
-            .text_size(typography::CAPTION),
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::Animation)
-            )
-            .label("Animations")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::Animation))
-            .text_size(typography::CAPTION),
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::Conditions)
-            )
-            .label("Conditions")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::Conditions))
-            .text_size(typography::CAPTION),
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::AuthorOptions)
-            )
-            .label("Author Options")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::AuthorOptions))
-            .text_size(typography::CAPTION),
-        ]
-        .spacing(spacing::MD);
-
-        // Row 3: Arrangement, Anchor, User Config, Metadata
-        let row3 = row![
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::Arrangement)
-            )
-            .label("Arrangement")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::Arrangement))
-            .text_size(typography::CAPTION),
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::Anchor)
-            )
-            .label("Anchor")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::Anchor))
-            .text_size(typography::CAPTION),
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::UserConfig)
-            )
-            .label("User Config")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::UserConfig))
-            .text_size(typography::CAPTION),
-            checkbox(
-                self.conflicts
-                    .global_categories
-                    .contains(&UpdateCategory::Metadata)
-            )
-            .label("Metadata")
-            .on_toggle(|_| Message::ToggleGlobalCategory(UpdateCategory::Metadata))
-            .text_size(typography::CAPTION),
-        ]
-        .spacing(spacing::MD);
+        let set = &self.conflicts.category_set;
+        let mut grid = Column::new().spacing(spacing::XS);
+
+        for chunk in set.keys().chunks(CATEGORY_GRID_COLUMNS) {
+            let mut grid_row = row![].spacing(spacing::MD);
+            for key in chunk {
+                let label = set.display_name(key).to_string();
+                let checked = self.conflicts.global_categories.contains(key);
+                let key = key.clone();
+                grid_row = grid_row.push(
+                    checkbox(checked)
+                        .label(label)
+                        .on_toggle(move |_| Message::ToggleGlobalCategory(key.clone()))
+                        .text_size(typography::CAPTION),
+                );
+            }
+            grid = grid.push(grid_row);
+        }
 
-        Column::new()
-            .push(row1)
-            .push(row2)
-            .push(row3)
-            .spacing(spacing::XS)
+        grid
     }
 
-    fn render_conflict_list(&self, conflicts: &[ImportConflict]) -> Column<'_, Message> {
+    fn render_conflict_list(
+        &self,
+        conflicts: &[ImportConflict],
+        indices: &[usize],
+    ) -> Column<'_, Message> {
         let mut list_col = Column::new().spacing(spacing::SM);
 
-        for (idx, conflict) in conflicts.iter().enumerate() {
+        if indices.is_empty() {
+            return list_col.push(
+                text("No conflicts match the filters")
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_MUTED),
+            );
+        }
+
+        for &idx in indices {
+            let conflict = &conflicts[idx];
             let resolution = &self.conflicts.resolutions[idx];
 
             // Action dropdown using pick_list
-            let action_options = vec![
-                ConflictAction::Skip,
-                ConflictAction::ReplaceAll,
-                ConflictAction::UpdateSelected,
-            ];
-
-            let action_picker = pick_list(action_options, Some(resolution.action), move |action| {
-                Message::SetConflictAction(idx, action)
-            })
+            let action_picker = pick_list(
+                ConflictAction::ALL,
+                Some(resolution.action),
+                move |action| Message::SetConflictAction(idx, action),
+            )
             .text_size(typography::CAPTION)
             .width(Length::Fixed(90.0));
 
             // Aura name with color based on action
+            let version_color = match conflict.version_change {
+                VersionChange::Newer => colors::SUCCESS,
+                VersionChange::Older => colors::WARNING,
+                VersionChange::Same | VersionChange::Unknown => colors::TEXT_PRIMARY,
+            };
             let name_color = match resolution.action {
                 ConflictAction::Skip => colors::TEXT_MUTED,
-                _ => colors::TEXT_PRIMARY,
+                _ => version_color,
             };
 
             let aura_id = conflict.aura_id.clone();
@@ -493,6 +835,15 @@ impl WeakAuraImporter {
             .spacing(spacing::SM)
             .align_y(Alignment::Center);
 
+            // Version indicator
+            if let Some(version) = conflict.version_label() {
+                item_row = item_row.push(
+                    text(format!("{} ({})", version, conflict.version_change.label()))
+                        .color(version_color)
+                        .size(typography::CAPTION),
+                );
+            }
+
             // Group indicator
             if conflict.is_group {
                 item_row = item_row.push(
@@ -516,6 +867,15 @@ impl WeakAuraImporter {
                 );
             }
 
+            // Merge without a known previous import falls back to categories
+            if resolution.action == ConflictAction::Merge && conflict.base.is_none() {
+                item_row = item_row.push(
+                    text("(no previous import, using categories)")
+                        .color(colors::WARNING)
+                        .size(typography::CAPTION),
+                );
+            }
+
             // Expand button for per-aura category selection
             if resolution.action == ConflictAction::UpdateSelected {
                 let expand_text = if resolution.expanded { "v" } else { ">" };
@@ -528,10 +888,23 @@ impl WeakAuraImporter {
 
             list_col = list_col.push(item_row);
 
-            // Expanded category selection for this specific aura
+            // Expanded category (or field) selection for this specific aura
             if resolution.expanded && resolution.action == ConflictAction::UpdateSelected {
-                list_col =
-                    list_col.push(self.build_conflict_category_grid(idx, resolution, conflict));
+                list_col = list_col.push(
+                    row![
+                        space::horizontal().width(Length::Fixed(100.0)),
+                        checkbox(resolution.advanced)
+                            .label("Pick individual fields")
+                            .on_toggle(move |_| Message::ToggleConflictAdvanced(idx))
+                            .text_size(typography::MICRO),
+                    ]
+                    .spacing(spacing::SM),
+                );
+                list_col = if resolution.advanced {
+                    list_col.push(self.build_conflict_field_grid(idx, resolution, conflict))
+                } else {
+                    list_col.push(self.build_conflict_category_grid(idx, resolution, conflict))
+                };
             }
 
             // Separator
@@ -556,95 +929,72 @@ impl WeakAuraImporter {
         resolution: &'a crate::app::ConflictResolutionUI,
         _conflict: &ImportConflict,
     ) -> Column<'a, Message> {
-        use crate::categories::UpdateCategory;
-
-        let indent = space::horizontal().width(Length::Fixed(100.0));
-
-        // Row 1: Name, Display, Trigger, Load
-        let row1 = row![
-            indent,
-            checkbox(resolution.categories.contains(&UpdateCategory::Name))
-                .label("Name")
-                .on_toggle(move |_| Message::ToggleConflictCategory(idx, UpdateCategory::Name))
-                .text_size(typography::MICRO),
-            checkbox(resolution.categories.contains(&UpdateCategory::Display))
-                .label("Display")
-                .on_toggle(move |_| Message::ToggleConflictCategory(idx, UpdateCategory::Display))
-                .text_size(typography::MICRO),
-            checkbox(resolution.categories.contains(&UpdateCategory::Trigger))
-                .label("Trigger")
-                .on_toggle(move |_| Message::ToggleConflictCategory(idx, UpdateCategory::Trigger))
-                .text_size(typography::MICRO),
-            checkbox(resolution.categories.contains(&UpdateCategory::Load))
-                .label("Load")
-                .on_toggle(move |_| Message::ToggleConflictCategory(idx, UpdateCategory::Load))
-                .text_size(typography::MICRO),
-        ]
-        .spacing(spacing::SM);
-
-        // Row 2: Action, Animation, Conditions, Author Options
-        let row2 = row![
-            space::horizontal().width(Length::Fixed(100.0)),
-            checkbox(resolution.categories.contains(&UpdateCategory::Action))
-                .label("Actions")
-                .on_toggle(move |_| Message::ToggleConflictCategory(idx, UpdateCategory::Action))
-                .text_size(typography::MICRO),
-            checkbox(resolution.categories.contains(&UpdateCategory::Animation))
-                .label("Animations")
-                .on_toggle(move |_| Message::ToggleConflictCategory(idx, UpdateCategory::Animation))
-                .text_size(typography::MICRO),
-            checkbox(resolution.categories.contains(&UpdateCategory::Conditions))
-                .label("Conditions")
-                .on_toggle(move |_| Message::ToggleConflictCategory(
-                    idx,
-                    UpdateCategory::Conditions
-                ))
-                .text_size(typography::MICRO),
-            checkbox(
-                resolution
-                    .categories
-                    .contains(&UpdateCategory::AuthorOptions)
-            )
-            .label("Author Options")
-            .on_toggle(move |_| Message::ToggleConflictCategory(idx, UpdateCategory::AuthorOptions))
-            .text_size(typography::MICRO),
-        ]
-        .spacing(spacing::SM);
+        let set = &self.conflicts.category_set;
+        let mut grid = Column::new()
+            .spacing(2)
+            .padding(Padding::default().bottom(spacing::SM));
+
+        for chunk in set.keys().chunks(CATEGORY_GRID_COLUMNS) {
+            let mut grid_row =
+                row![space::horizontal().width(Length::Fixed(100.0))].spacing(spacing::SM);
+            for key in chunk {
+                let label = set.display_name(key).to_string();
+                let checked = resolution.categories.contains(key);
+                let key = key.clone();
+                grid_row = grid_row.push(
+                    checkbox(checked)
+                        .label(label)
+                        .on_toggle(move |_| Message::ToggleConflictCategory(idx, key.clone()))
+                        .text_size(typography::MICRO),
+                );
+            }
+            grid = grid.push(grid_row);
+        }
 
-        // Row 3: Arrangement, Anchor, User Config, Metadata
-        let row3 = row![
-            space::horizontal().width(Length::Fixed(100.0)),
-            checkbox(resolution.categories.contains(&UpdateCategory::Arrangement))
-                .label("Arrangement")
-                .on_toggle(move |_| Message::ToggleConflictCategory(
-                    idx,
-                    UpdateCategory::Arrangement
-                ))
-                .text_size(typography::MICRO),
-            checkbox(resolution.categories.contains(&UpdateCategory::Anchor))
-                .label("Anchor")
-                .on_toggle(move |_| Message::ToggleConflictCategory(idx, UpdateCategory::Anchor))
-                .text_size(typography::MICRO),
-            checkbox(resolution.categories.contains(&UpdateCategory::UserConfig))
-                .label("User Config")
-                .on_toggle(move |_| Message::ToggleConflictCategory(
-                    idx,
-                    UpdateCategory::UserConfig
-                ))
-                .text_size(typography::MICRO),
-            checkbox(resolution.categories.contains(&UpdateCategory::Metadata))
-                .label("Metadata")
-                .on_toggle(move |_| Message::ToggleConflictCategory(idx, UpdateCategory::Metadata))
-                .text_size(typography::MICRO),
-        ]
-        .spacing(spacing::SM);
+        grid
+    }
 
-        Column::new()
-            .push(row1)
-            .push(row2)
-            .push(row3)
+    /// Build the field picker for a specific conflict's advanced view
+    fn build_conflict_field_grid<'a>(
+        &'a self,
+        idx: usize,
+        resolution: &'a crate::app::ConflictResolutionUI,
+        conflict: &ImportConflict,
+    ) -> Column<'a, Message> {
+        let mut grid = Column::new()
             .spacing(2)
-            .padding(Padding::default().bottom(spacing::SM))
+            .padding(Padding::default().bottom(spacing::SM));
+
+        let fields = conflict.changed_fields();
+        if fields.is_empty() {
+            return grid.push(
+                row![
+                    space::horizontal().width(Length::Fixed(100.0)),
+                    text("No changed fields")
+                        .size(typography::MICRO)
+                        .color(colors::TEXT_MUTED),
+                ]
+                .spacing(spacing::SM),
+            );
+        }
+
+        for chunk in fields.chunks(CATEGORY_GRID_COLUMNS) {
+            let mut grid_row =
+                row![space::horizontal().width(Length::Fixed(100.0))].spacing(spacing::SM);
+            for field in chunk {
+                let checked = resolution.fields.contains(field);
+                let field = field.clone();
+                grid_row = grid_row.push(
+                    checkbox(checked)
+                        .label(field.clone())
+                        .on_toggle(move |_| Message::ToggleConflictField(idx, field.clone()))
+                        .text_size(typography::MICRO),
+                );
+            }
+            grid = grid.push(grid_row);
+        }
+
+        grid
     }
 
     /// Overlay the remove confirmation dialog on top of the main view
@@ -673,26 +1023,84 @@ impl WeakAuraImporter {
         .padding(spacing::SM)
         .width(Length::Fill);
 
+        // Groups can also be dissolved, keeping their children
+        let selected: HashSet<String> = self.removal.pending_ids.iter().cloned().collect();
+        let has_groups = topmost_selected(&self.saved_vars.auras_tree, &selected)
+            .iter()
+            .any(|node| node.is_group);
+
+        // Auras left anchored to a removed one block the removal until they
+        // are anchored to the screen instead
+        let dependents = tree_anchor_dependents(&self.saved_vars.auras_tree, &selected);
+        let can_remove = dependents.is_empty() || self.removal.reanchor_dependents;
+        let mut dependents_col = Column::new().spacing(spacing::MICRO);
+        if !dependents.is_empty() {
+            dependents_col = dependents_col.push(
+                text(format!(
+                    "⚠ {} aura(s) are anchored to auras being removed:",
+                    dependents.len()
+                ))
+                .size(typography::CAPTION)
+                .color(colors::WARNING),
+            );
+            for (dependent, target) in &dependents {
+                dependents_col = dependents_col.push(
+                    text(format!("{} → {}", dependent, target))
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_SECONDARY),
+                );
+            }
+            dependents_col = dependents_col.push(
+                checkbox(self.removal.reanchor_dependents)
+                    .label("Anchor them to the screen")
+                    .on_toggle(Message::SetRemovalReanchor)
+                    .text_size(typography::CAPTION),
+            );
+        }
+
+        let mut actions_row = row![
+            button(text("Cancel").size(typography::BODY))
+                .style(theme::button_secondary)
+                .on_press(Message::HideRemoveConfirm),
+            space::horizontal(),
+        ]
+        .spacing(spacing::SM)
+        .align_y(Alignment::Center);
+        if has_groups {
+            actions_row = actions_row.push(
+                button(text("Remove group only").size(typography::BODY))
+                    .style(theme::button_secondary)
+                    .on_press_maybe(can_remove.then_some(Message::ConfirmGroupOnlyRemoval)),
+            );
+        }
+        actions_row = actions_row.push(
+            button(text("Remove").size(typography::BODY).color(colors::BG_VOID))
+                .style(theme::button_danger)
+                .on_press_maybe(can_remove.then_some(Message::ConfirmRemoval)),
+        );
+
+        let caption = if has_groups {
+            "Groups will have all their children removed recursively. \
+             \"Remove group only\" keeps the children as top-level auras."
+        } else {
+            "Groups will have all their children removed recursively."
+        };
+
         let dialog_content = column![
             text(format!("Remove {} aura(s)?", count)).size(typography::HEADING),
             space::vertical().height(Length::Fixed(spacing::XS)),
-            text("Groups will have all their children removed recursively.")
+            text(caption)
                 .color(colors::TEXT_MUTED)
                 .size(typography::CAPTION),
             space::vertical().height(Length::Fixed(spacing::SM)),
             id_list_container,
+            dependents_col,
+            checkbox(self.removal.export_first)
+                .label("Export to a Lua file before removing")
+                .on_toggle(Message::SetRemovalExportFirst)
+                .text_size(typography::CAPTION),
             space::vertical().height(Length::Fixed(spacing::MD)),
-            row![
-                button(text("Cancel").size(typography::BODY))
-                    .style(theme::button_secondary)
-                    .on_press(Message::HideRemoveConfirm),
-                space::horizontal(),
-                button(text("Remove").size(typography::BODY).color(colors::BG_VOID))
-                    .style(theme::button_danger)
-                    .on_press(Message::ConfirmRemoval),
-            ]
-            .spacing(spacing::SM)
-            .align_y(Alignment::Center),
+            actions_row,
         ]
         .spacing(spacing::XS)
         .padding(spacing::XL)
@@ -716,4 +1124,1030 @@ impl WeakAuraImporter {
 
         iced::widget::stack![underlay, backdrop].into()
     }
+
+    /// Overlay the tags & notes editor for a single aura
+    pub(crate) fn overlay_annotation_editor<'a>(
+        &'a self,
+        underlay: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let Some((uid, name)) = &self.annotations.editing else {
+            return underlay;
+        };
+        let annotation = self.annotations.store.get(uid);
+
+        let mut content = Column::new().spacing(spacing::SM);
+
+        content = content.push(
+            text("Tags & Notes")
+                .size(typography::HEADING)
+                .color(colors::GOLD),
+        );
+        content = content.push(
+            text(name.as_str())
+                .size(typography::BODY)
+                .color(colors::TEXT_SECONDARY),
+        );
+
+        // Current tags with remove buttons
+        let mut tags_row = row![].spacing(spacing::XS).align_y(Alignment::Center);
+        match annotation.filter(|a| !a.tags.is_empty()) {
+            Some(a) => {
+                for tag in &a.tags {
+                    tags_row = tags_row.push(
+                        container(
+                            row![
+                                text(tag.as_str())
+                                    .size(typography::CAPTION)
+                                    .color(colors::GOLD),
+                                button(text("×").size(typography::CAPTION).color(colors::ERROR))
+                                    .style(theme::button_frameless)
+                                    .on_press(Message::RemoveAnnotationTag(tag.clone())),
+                            ]
+                            .spacing(spacing::MICRO)
+                            .align_y(Alignment::Center),
+                        )
+                        .padding(Padding::from([0, 6]))
+                        .style(theme::container_inset),
+                    );
+                }
+            }
+            None => {
+                tags_row = tags_row.push(
+                    text("No tags")
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_MUTED),
+                );
+            }
+        }
+        content = content.push(tags_row.wrap());
+
+        let tag_input = text_input("New tag...", &self.annotations.tag_input)
+            .on_input(Message::AnnotationTagInputChanged)
+            .on_submit(Message::AddAnnotationTag)
+            .style(theme::text_input_style);
+        content = content.push(
+            row![
+                tag_input.width(Length::Fill),
+                button(text("Add").size(typography::BODY))
+                    .style(theme::button_secondary)
+                    .on_press(Message::AddAnnotationTag),
+            ]
+            .spacing(spacing::SM),
+        );
+
+        // Tags used on other auras, for quick reuse
+        let suggestions: Vec<String> = self
+            .annotations
+            .store
+            .all_tags()
+            .into_iter()
+            .filter(|t| !annotation.is_some_and(|a| a.tags.contains(t)))
+            .collect();
+        if !suggestions.is_empty() {
+            let mut suggestions_row = row![].spacing(spacing::XS);
+            for tag in suggestions {
+                suggestions_row = suggestions_row.push(
+                    button(
+                        text(format!("+ {}", tag))
+                            .size(typography::CAPTION)
+                            .color(colors::TEXT_SECONDARY),
+                    )
+                    .style(theme::button_secondary)
+                    .on_press(Message::AddExistingAnnotationTag(tag)),
+                );
+            }
+            content = content.push(suggestions_row.wrap());
+        }
+
+        content = content.push(
+            text("Note:")
+                .size(typography::BODY)
+                .color(colors::TEXT_SECONDARY),
+        );
+        content = content.push(
+            text_input(
+                "Add a note...",
+                annotation.map(|a| a.note.as_str()).unwrap_or_default(),
+            )
+            .on_input(Message::AnnotationNoteChanged)
+            .style(theme::text_input_style),
+        );
+
+        let actions_row = row![
+            space::horizontal(),
+            button(text("Done").size(typography::BODY).color(colors::BG_VOID))
+                .style(theme::button_primary)
+                .on_press(Message::CloseAnnotationEditor),
+        ]
+        .align_y(Alignment::Center);
+
+        let dialog_content = column![content, space::vertical().height(spacing::MD), actions_row]
+            .spacing(spacing::SM)
+            .padding(spacing::XL)
+            .max_width(450);
+
+        let dialog_box = container(dialog_content)
+            .style(theme::container_modal)
+            .padding(spacing::SM)
+            .width(Length::Fixed(450.0));
+
+        let centered_dialog = container(dialog_box)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        let backdrop = container(centered_dialog)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::container_modal_backdrop);
+
+        iced::widget::stack![underlay, backdrop].into()
+    }
+
+    /// Overlay the scripts dialog: script files, an editor and the report of
+    /// the last run
+    pub(crate) fn overlay_scripts<'a>(
+        &'a self,
+        underlay: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let scripts = &self.scripts;
+        let mut content = Column::new().spacing(spacing::SM);
+        content = content.push(
+            text("Scripts")
+                .size(typography::HEADING)
+                .color(colors::GOLD),
+        );
+
+        let folder = scripts_dir()
+            .map(|dir| format!("Scripts (*.rhai) in {}", dir.display()))
+            .unwrap_or_else(|| "No scripts folder available".to_string());
+        content = content.push(
+            text(folder)
+                .size(typography::CAPTION)
+                .color(colors::TEXT_MUTED),
+        );
+        let mut files_row = row![].spacing(spacing::XS);
+        for path in &scripts.files {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let style = if scripts.selected.as_ref() == Some(path) {
+                theme::button_primary
+            } else {
+                theme::button_secondary
+            };
+            files_row = files_row.push(
+                button(text(name).size(typography::CAPTION))
+                    .style(style)
+                    .on_press(Message::ScriptSelected(path.clone())),
+            );
+        }
+        content = content.push(files_row.wrap());
+
+        content = content.push(
+            text_editor(&scripts.content)
+                .placeholder(
+                    "for id in descendants(\"My Group\") { set(id, \"actions.start.sound\", ()); }",
+                )
+                .on_action(Message::ScriptAction)
+                .height(Length::Fixed(220.0))
+                .size(typography::CAPTION),
+        );
+
+        if let Some(result) = &scripts.result {
+            let (output, color) = match result {
+                Ok(report) => (report.to_text(), colors::TEXT_SECONDARY),
+                Err(e) => (e.clone(), colors::ERROR),
+            };
+            content = content.push(
+                container(
+                    scrollable(text(output).size(typography::CAPTION).color(color))
+                        .height(Length::Fixed(160.0))
+                        .width(Length::Fill)
+                        .style(theme::scrollable_style),
+                )
+                .style(theme::container_inset)
+                .padding(spacing::SM)
+                .width(Length::Fill),
+            );
+        }
+
+        let can_run = !scripts.running && !scripts.content.text().trim().is_empty();
+        let actions_row = row![
+            button(text("Dry run").size(typography::BODY))
+                .style(theme::button_secondary)
+                .on_press_maybe(can_run.then_some(Message::RunScript(true))),
+            button(text("Run").size(typography::BODY))
+                .style(theme::button_danger)
+                .on_press_maybe(can_run.then_some(Message::RunScript(false))),
+            space::horizontal(),
+            button(text("Close").size(typography::BODY).color(colors::BG_VOID))
+                .style(theme::button_primary)
+                .on_press(Message::ToggleScripts),
+        ]
+        .spacing(spacing::SM)
+        .align_y(Alignment::Center);
+
+        let dialog_content = column![content, space::vertical().height(spacing::SM), actions_row]
+            .spacing(spacing::SM)
+            .padding(spacing::XL)
+            .max_width(700);
+
+        let dialog_box = container(dialog_content)
+            .style(theme::container_modal)
+            .padding(spacing::SM)
+            .width(Length::Fixed(700.0));
+
+        let centered_dialog = container(dialog_box)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        let backdrop = container(centered_dialog)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::container_modal_backdrop);
+
+        iced::widget::stack![underlay, backdrop].into()
+    }
+
+    /// Overlay the per-aura report for the last import
+    pub(crate) fn overlay_import_report<'a>(
+        &'a self,
+        underlay: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let Some(result) = &self.status.last_import_result else {
+            return underlay;
+        };
+
+        let mut rows = Column::new().spacing(spacing::MICRO);
+        for entry in &result.outcomes {
+            let outcome_color = match entry.outcome {
+                ImportOutcome::Added => colors::SUCCESS,
+                ImportOutcome::Replaced => colors::WARNING,
+                ImportOutcome::Merged(_) => colors::INFO,
+                ImportOutcome::Skipped => colors::TEXT_MUTED,
+            };
+            let categories = entry.outcome.category_names();
+
+            let mut entry_row = row![
+                text(entry.outcome.label())
+                    .size(typography::CAPTION)
+                    .color(outcome_color)
+                    .width(Length::Fixed(70.0)),
+                text(entry.aura_id.as_str())
+                    .size(typography::BODY)
+                    .color(colors::TEXT_PRIMARY),
+            ]
+            .spacing(spacing::SM)
+            .align_y(Alignment::Center);
+            if !categories.is_empty() {
+                entry_row = entry_row.push(
+                    text(categories.join(", "))
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_MUTED),
+                );
+            }
+            rows = rows.push(entry_row);
+        }
+        for (aura_id, error) in &result.failed {
+            rows = rows.push(
+                row![
+                    text("failed")
+                        .size(typography::CAPTION)
+                        .color(colors::ERROR)
+                        .width(Length::Fixed(70.0)),
+                    text(aura_id.as_str())
+                        .size(typography::BODY)
+                        .color(colors::TEXT_PRIMARY),
+                    text(error.as_str())
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_MUTED),
+                ]
+                .spacing(spacing::SM)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        let list = container(
+            scrollable(rows)
+                .height(Length::Fixed(300.0))
+                .style(theme::scrollable_style),
+        )
+        .style(theme::container_inset)
+        .padding(spacing::SM)
+        .width(Length::Fill);
+
+        let actions_row = row![
+            button(text("Copy").size(typography::BODY))
+                .style(theme::button_secondary)
+                .on_press(Message::CopyImportReport),
+            button(text("Export CSV").size(typography::BODY))
+                .style(theme::button_secondary)
+                .on_press(Message::ExportImportReport(ReportFormat::Csv)),
+            button(text("Export JSON").size(typography::BODY))
+                .style(theme::button_secondary)
+                .on_press(Message::ExportImportReport(ReportFormat::Json)),
+            space::horizontal(),
+            button(text("Close").size(typography::BODY).color(colors::BG_VOID))
+                .style(theme::button_primary)
+                .on_press(Message::HideImportReport),
+        ]
+        .spacing(spacing::SM)
+        .align_y(Alignment::Center);
+
+        let dialog_content = column![
+            text("Import Report")
+                .size(typography::HEADING)
+                .color(colors::GOLD),
+            text(result.summary())
+                .size(typography::BODY)
+                .color(colors::TEXT_SECONDARY),
+            text(
+                result
+                    .comparison
+                    .as_ref()
+                    .map(|c| format!("File: {}", c.summary()))
+                    .unwrap_or_default()
+            )
+            .size(typography::CAPTION)
+            .color(colors::TEXT_MUTED),
+            list,
+            space::vertical().height(spacing::SM),
+            actions_row,
+        ]
+        .spacing(spacing::SM)
+        .padding(spacing::XL)
+        .max_width(600);
+
+        let dialog_box = container(dialog_content)
+            .style(theme::container_modal)
+            .padding(spacing::SM)
+            .width(Length::Fixed(600.0));
+
+        let centered_dialog = container(dialog_box)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        let backdrop = container(centered_dialog)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::container_modal_backdrop);
+
+        iced::widget::stack![underlay, backdrop].into()
+    }
+
+    /// Overlay the notification history, with expandable details
+    pub(crate) fn overlay_notifications<'a>(
+        &'a self,
+        underlay: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let mut rows = Column::new().spacing(spacing::SM);
+        for notification in self.notifications.history() {
+            let mut header = row![text(notification.level.label())
+                .size(typography::CAPTION)
+                .color(notification.level.color())
+                .width(Length::Fixed(60.0)),]
+            .spacing(spacing::SM)
+            .align_y(Alignment::Center);
+            if let Some(title) = &notification.title {
+                header = header.push(
+                    text(title.as_str())
+                        .size(typography::BODY)
+                        .color(colors::TEXT_PRIMARY),
+                );
+            }
+            header = header.push(space::horizontal()).push(
+                text(format_age(notification.created))
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_MUTED),
+            );
+
+            let mut entry = column![
+                header,
+                text(notification.message.as_str())
+                    .size(typography::BODY)
+                    .color(colors::TEXT_SECONDARY),
+            ]
+            .spacing(spacing::XS);
+
+            if !notification.details.is_empty() {
+                let expanded = self.notifications.is_expanded(notification.id);
+                let toggle_label = if expanded {
+                    "Hide details".to_string()
+                } else {
+                    format!("Show details ({})", notification.details.len())
+                };
+                entry = entry.push(
+                    button(text(toggle_label).size(typography::CAPTION))
+                        .style(theme::button_frameless)
+                        .on_press(Message::ToggleNotificationDetails(notification.id)),
+                );
+                if expanded {
+                    let details = notification.details.iter().fold(
+                        Column::new().spacing(spacing::MICRO),
+                        |col, detail| {
+                            col.push(
+                                text(detail.as_str())
+                                    .size(typography::CAPTION)
+                                    .color(colors::TEXT_MUTED),
+                            )
+                        },
+                    );
+                    entry = entry.push(
+                        container(details)
+                            .style(theme::container_inset)
+                            .padding(spacing::SM)
+                            .width(Length::Fill),
+                    );
+                }
+            }
+
+            rows = rows.push(
+                container(entry)
+                    .style(theme::container_surface)
+                    .padding(spacing::SM)
+                    .width(Length::Fill),
+            );
+        }
+        if self.notifications.history().next().is_none() {
+            rows = rows.push(
+                text("No notifications yet")
+                    .size(typography::BODY)
+                    .color(colors::TEXT_MUTED),
+            );
+        }
+
+        let list = container(
+            scrollable(rows)
+                .height(Length::Fixed(400.0))
+                .style(theme::scrollable_style),
+        )
+        .style(theme::container_inset)
+        .padding(spacing::SM)
+        .width(Length::Fill);
+
+        let actions_row = row![
+            button(text("Clear").size(typography::BODY))
+                .style(theme::button_secondary)
+                .on_press(Message::ClearNotifications),
+            space::horizontal(),
+            button(text("Close").size(typography::BODY).color(colors::BG_VOID))
+                .style(theme::button_primary)
+                .on_press(Message::ToggleNotifications),
+        ]
+        .spacing(spacing::SM)
+        .align_y(Alignment::Center);
+
+        let dialog_content = column![
+            text("Notifications")
+                .size(typography::HEADING)
+                .color(colors::GOLD),
+            list,
+            space::vertical().height(spacing::SM),
+            actions_row,
+        ]
+        .spacing(spacing::SM)
+        .padding(spacing::XL)
+        .max_width(600);
+
+        let dialog_box = container(dialog_content)
+            .style(theme::container_modal)
+            .padding(spacing::SM)
+            .width(Length::Fixed(600.0));
+
+        let centered_dialog = container(dialog_box)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        let backdrop = container(centered_dialog)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::container_modal_backdrop);
+
+        iced::widget::stack![underlay, backdrop].into()
+    }
+
+    /// Overlay offering recovery after the previous session crashed
+    pub(crate) fn overlay_crash_recovery<'a>(
+        &'a self,
+        underlay: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let Some(crash) = &self.status.crash_recovery else {
+            return underlay;
+        };
+        let backup = crash
+            .saved_variables
+            .as_deref()
+            .map(backup_path)
+            .filter(|path| path.exists());
+
+        let mut content = column![
+            text("The app crashed last time")
+                .size(typography::HEADING)
+                .color(colors::GOLD),
+            text(format!(
+                "A crash report without personal paths was saved to {}",
+                crash.report.display()
+            ))
+            .size(typography::BODY)
+            .color(colors::TEXT_SECONDARY),
+        ]
+        .spacing(spacing::SM)
+        .padding(spacing::XL)
+        .max_width(600);
+
+        let mut actions_row = row![space::horizontal()]
+            .spacing(spacing::SM)
+            .align_y(Alignment::Center);
+        if let (Some(path), Some(_)) = (&crash.saved_variables, &backup) {
+            content = content.push(
+                text(format!(
+                    "The app crashed while saving {}, which may have been left half-written. You can restore it from the backup made before that save; the file it replaces is kept as {}.",
+                    path.display(),
+                    damaged_path(path).display()
+                ))
+                .size(typography::BODY)
+                .color(colors::TEXT_SECONDARY),
+            );
+            actions_row = actions_row.push(
+                button(text("Restore from backup").size(typography::BODY))
+                    .style(theme::button_secondary)
+                    .on_press(Message::RestoreCrashBackup),
+            );
+        }
+        actions_row = actions_row.push(
+            button(
+                text("Continue")
+                    .size(typography::BODY)
+                    .color(colors::BG_VOID),
+            )
+            .style(theme::button_primary)
+            .on_press(Message::DismissCrashRecovery),
+        );
+        content = content
+            .push(space::vertical().height(spacing::SM))
+            .push(actions_row);
+
+        let dialog_box = container(content)
+            .style(theme::container_modal)
+            .padding(spacing::SM)
+            .width(Length::Fixed(600.0));
+
+        let centered_dialog = container(dialog_box)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        let backdrop = container(centered_dialog)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::container_modal_backdrop);
+
+        iced::widget::stack![underlay, backdrop].into()
+    }
+
+    /// Overlay explaining a denied save, with ways around it
+    pub(crate) fn overlay_denied_save<'a>(
+        &'a self,
+        underlay: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let Some(denied) = &self.status.denied_save else {
+            return underlay;
+        };
+
+        let mut actions_row = row![space::horizontal()]
+            .spacing(spacing::SM)
+            .align_y(Alignment::Center);
+        actions_row = actions_row.push(
+            button(text("Close").size(typography::BODY))
+                .style(theme::button_secondary)
+                .on_press(Message::DismissDeniedSave),
+        );
+        actions_row = actions_row.push(
+            button(text("Save to another file...").size(typography::BODY))
+                .style(theme::button_secondary)
+                .on_press(Message::SaveDeniedElsewhere),
+        );
+        if fs_util::can_relaunch_elevated() {
+            actions_row = actions_row.push(
+                button(
+                    text("Restart as administrator")
+                        .size(typography::BODY)
+                        .color(colors::BG_VOID),
+                )
+                .style(theme::button_primary)
+                .on_press(Message::RestartElevated),
+            );
+        }
+
+        let content = column![
+            text("Could not save")
+                .size(typography::HEADING)
+                .color(colors::GOLD),
+            text(&denied.message)
+                .size(typography::BODY)
+                .color(colors::TEXT_SECONDARY),
+            text("Nothing was written to the file.")
+                .size(typography::BODY)
+                .color(colors::TEXT_SECONDARY),
+            space::vertical().height(spacing::SM),
+            actions_row,
+        ]
+        .spacing(spacing::SM)
+        .padding(spacing::XL)
+        .max_width(600);
+
+        let dialog_box = container(content)
+            .style(theme::container_modal)
+            .padding(spacing::SM)
+            .width(Length::Fixed(600.0));
+
+        let centered_dialog = container(dialog_box)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        let backdrop = container(centered_dialog)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::container_modal_backdrop);
+
+        iced::widget::stack![underlay, backdrop].into()
+    }
+
+    /// Overlay offering to restore the auras of an earlier session
+    pub(crate) fn overlay_session_restore<'a>(
+        &'a self,
+        underlay: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let Some(session) = &self.session.pending_restore else {
+            return underlay;
+        };
+        let selected = session.entries.iter().filter(|e| e.selected).count();
+        let mut summary = format!(
+            "{} aura(s) ({} selected) were loaded when the app last closed.",
+            session.entries.len(),
+            selected
+        );
+        if !session.staged.is_empty() {
+            summary.push_str(&format!(" {} more were staged.", session.staged.len()));
+        }
+
+        let content = column![
+            text("Restore previous session?")
+                .size(typography::HEADING)
+                .color(colors::GOLD),
+            text(summary)
+                .size(typography::BODY)
+                .color(colors::TEXT_SECONDARY),
+            space::vertical().height(spacing::SM),
+            row![
+                space::horizontal(),
+                button(text("Discard").size(typography::BODY))
+                    .style(theme::button_secondary)
+                    .on_press(Message::DiscardSession),
+                button(
+                    text("Restore")
+                        .size(typography::BODY)
+                        .color(colors::BG_VOID),
+                )
+                .style(theme::button_primary)
+                .on_press(Message::RestoreSession),
+            ]
+            .spacing(spacing::SM)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(spacing::SM)
+        .padding(spacing::XL)
+        .max_width(500);
+
+        let dialog_box = container(content)
+            .style(theme::container_modal)
+            .padding(spacing::SM)
+            .width(Length::Fixed(500.0));
+
+        let centered_dialog = container(dialog_box)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        let backdrop = container(centered_dialog)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::container_modal_backdrop);
+
+        iced::widget::stack![underlay, backdrop].into()
+    }
+
+    /// Overlay the field differences between an aura and its backup
+    pub(crate) fn overlay_backup_comparison<'a>(
+        &'a self,
+        underlay: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let Some(comparison) = &self.saved_vars.backup_comparison else {
+            return underlay;
+        };
+        let field = |data: &Option<LuaValue>, name: &str| {
+            data.as_ref()
+                .and_then(LuaValue::as_table)
+                .and_then(|t| t.get(name))
+                .map_or_else(|| "(not set)".to_string(), value_preview)
+        };
+
+        let summary = match (&comparison.backup, &comparison.current) {
+            (None, None) => "The aura is in neither the file nor its backup.".to_string(),
+            (None, Some(_)) => "The aura was added by the last save.".to_string(),
+            (Some(_), None) => "The aura was removed after the backup was made.".to_string(),
+            (Some(_), Some(_)) => match comparison.backup_modified {
+                Some(time) => format!("Backup made {}", format_age(time)),
+                None => "Backup of the last save".to_string(),
+            },
+        };
+
+        let mut changes_col = Column::new().spacing(spacing::SM);
+        let changes = comparison.changes();
+        if changes.is_empty() {
+            changes_col = changes_col.push(
+                text("No differences")
+                    .size(typography::BODY)
+                    .color(colors::TEXT_MUTED),
+            );
+        }
+        for (category, fields) in changes {
+            changes_col = changes_col.push(
+                text(category.display_name())
+                    .size(typography::BODY)
+                    .color(colors::GOLD),
+            );
+            for name in fields {
+                changes_col = changes_col.push(
+                    column![
+                        text(name.clone())
+                            .size(typography::CAPTION)
+                            .color(colors::TEXT_PRIMARY),
+                        text(format!("Backup: {}", field(&comparison.backup, &name)))
+                            .size(typography::MICRO)
+                            .color(colors::ERROR),
+                        text(format!("Now: {}", field(&comparison.current, &name)))
+                            .size(typography::MICRO)
+                            .color(colors::SUCCESS),
+                    ]
+                    .spacing(2)
+                    .padding(Padding::default().left(spacing::SM)),
+                );
+            }
+        }
+
+        let changes_container = container(
+            scrollable(changes_col)
+                .height(Length::Fixed(360.0))
+                .style(theme::scrollable_style),
+        )
+        .style(theme::container_inset)
+        .padding(spacing::SM)
+        .width(Length::Fill);
+
+        let content = column![
+            text(format!("Compare with backup: {}", comparison.aura_id))
+                .size(typography::HEADING)
+                .color(colors::GOLD),
+            text(summary)
+                .size(typography::CAPTION)
+                .color(colors::TEXT_SECONDARY),
+            changes_container,
+            row![
+                space::horizontal(),
+                button(text("Close").size(typography::BODY).color(colors::BG_VOID))
+                    .style(theme::button_primary)
+                    .on_press(Message::CloseBackupComparison),
+            ]
+            .align_y(Alignment::Center),
+        ]
+        .spacing(spacing::SM)
+        .padding(spacing::XL)
+        .max_width(700);
+
+        let dialog_box = container(content)
+            .style(theme::container_modal)
+            .padding(spacing::SM)
+            .width(Length::Fixed(700.0));
+
+        let centered_dialog = container(dialog_box)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        let backdrop = container(centered_dialog)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::container_modal_backdrop);
+
+        iced::widget::stack![underlay, backdrop].into()
+    }
+
+    /// Overlay what changed between two whole SavedVariables files
+    pub(crate) fn overlay_file_diff<'a>(
+        &'a self,
+        underlay: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let Some(diff) = &self.saved_vars.file_diff else {
+            return underlay;
+        };
+        let file_name = |path: &std::path::Path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string())
+        };
+
+        let mut changes_col = Column::new().spacing(spacing::SM);
+        if diff.is_empty() {
+            changes_col = changes_col.push(
+                text("Both files hold the same auras")
+                    .size(typography::BODY)
+                    .color(colors::TEXT_MUTED),
+            );
+        }
+
+        let stats = diff.category_stats();
+        if !stats.is_empty() {
+            changes_col = changes_col.push(
+                text("Changes by category")
+                    .size(typography::BODY)
+                    .color(colors::GOLD),
+            );
+            for stat in stats {
+                changes_col = changes_col.push(
+                    row![
+                        text(stat.category.display_name())
+                            .size(typography::CAPTION)
+                            .color(colors::TEXT_PRIMARY)
+                            .width(Length::Fixed(180.0)),
+                        text(format!("{} aura(s), {} field(s)", stat.auras, stat.fields))
+                            .size(typography::CAPTION)
+                            .color(colors::TEXT_SECONDARY),
+                    ]
+                    .padding(Padding::default().left(spacing::SM)),
+                );
+            }
+        }
+
+        for (heading, ids, color) in [
+            ("Added", &diff.added, colors::SUCCESS),
+            ("Removed", &diff.removed, colors::ERROR),
+        ] {
+            if ids.is_empty() {
+                continue;
+            }
+            changes_col = changes_col.push(
+                text(format!("{} ({})", heading, ids.len()))
+                    .size(typography::BODY)
+                    .color(colors::GOLD),
+            );
+            for id in ids {
+                changes_col = changes_col.push(
+                    text(id.as_str())
+                        .size(typography::CAPTION)
+                        .color(color)
+                        .width(Length::Fill),
+                );
+            }
+        }
+
+        if !diff.modified.is_empty() {
+            changes_col = changes_col.push(
+                text(format!("Modified ({})", diff.modified.len()))
+                    .size(typography::BODY)
+                    .color(colors::GOLD),
+            );
+            for aura in &diff.modified {
+                let mut aura_col = column![text(aura.id.as_str())
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_PRIMARY)]
+                .spacing(2);
+                for (category, fields) in &aura.changes {
+                    aura_col = aura_col.push(
+                        text(format!(
+                            "{}: {}",
+                            category.display_name(),
+                            fields.join(", ")
+                        ))
+                        .size(typography::MICRO)
+                        .color(colors::TEXT_SECONDARY)
+                        .width(Length::Fill),
+                    );
+                }
+                changes_col =
+                    changes_col.push(aura_col.padding(Padding::default().left(spacing::SM)));
+            }
+        }
+
+        if !diff.moved.is_empty() {
+            changes_col = changes_col.push(
+                text(format!("Moved ({})", diff.moved.len()))
+                    .size(typography::BODY)
+                    .color(colors::GOLD),
+            );
+            for aura in &diff.moved {
+                changes_col = changes_col.push(
+                    text(format!("{}: {}", aura.id, aura.move_text()))
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_PRIMARY)
+                        .width(Length::Fill),
+                );
+            }
+        }
+
+        if !diff.reordered.is_empty() {
+            changes_col = changes_col.push(
+                text(format!("Children reordered ({})", diff.reordered.len()))
+                    .size(typography::BODY)
+                    .color(colors::GOLD),
+            );
+            for id in &diff.reordered {
+                changes_col = changes_col.push(
+                    text(id.as_str())
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_PRIMARY)
+                        .width(Length::Fill),
+                );
+            }
+        }
+
+        let changes_container = container(
+            scrollable(changes_col)
+                .height(Length::Fixed(400.0))
+                .style(theme::scrollable_style),
+        )
+        .style(theme::container_inset)
+        .padding(spacing::SM)
+        .width(Length::Fill);
+
+        let content = column![
+            text(format!(
+                "Compare files: {} → {}",
+                file_name(&diff.old),
+                file_name(&diff.new)
+            ))
+            .size(typography::HEADING)
+            .color(colors::GOLD),
+            text(diff.summary())
+                .size(typography::CAPTION)
+                .color(colors::TEXT_SECONDARY),
+            changes_container,
+            row![
+                space::horizontal(),
+                button(text("Close").size(typography::BODY).color(colors::BG_VOID))
+                    .style(theme::button_primary)
+                    .on_press(Message::CloseFileDiff),
+            ]
+            .align_y(Alignment::Center),
+        ]
+        .spacing(spacing::SM)
+        .padding(spacing::XL)
+        .max_width(700);
+
+        let dialog_box = container(content)
+            .style(theme::container_modal)
+            .padding(spacing::SM)
+            .width(Length::Fixed(700.0));
+
+        let centered_dialog = container(dialog_box)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        let backdrop = container(centered_dialog)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme::container_modal_backdrop);
+
+        iced::widget::stack![underlay, backdrop].into()
+    }
+}
+
+/// Lua text of a value on one line, cut off when long
+fn value_preview(value: &LuaValue) -> String {
+    let text = LuaParser::serialize(value, 0)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.chars().count() > VALUE_PREVIEW_CHARS {
+        let cut: String = text.chars().take(VALUE_PREVIEW_CHARS).collect();
+        format!("{}…", cut)
+    } else {
+        text
+    }
 }
@@ -0,0 +1,63 @@
+//! Highlighting of the strings in the paste box that failed to decode.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use iced::advanced::text::highlighter::{self, Highlighter};
+use iced::{Font, Theme};
+
+use crate::theme::colors;
+
+/// Colors the strings of the paste box that failed to decode
+pub(crate) struct InvalidLines {
+    lines: BTreeMap<usize, Range<usize>>,
+    current: usize,
+}
+
+impl Highlighter for InvalidLines {
+    /// Byte range to highlight within the line, by zero-based line
+    type Settings = BTreeMap<usize, Range<usize>>;
+    type Highlight = ();
+    type Iterator<'a> = std::option::IntoIter<(Range<usize>, ())>;
+
+    fn new(settings: &Self::Settings) -> Self {
+        Self {
+            lines: settings.clone(),
+            current: 0,
+        }
+    }
+
+    fn update(&mut self, new_settings: &Self::Settings) {
+        self.lines.clone_from(new_settings);
+        self.current = 0;
+    }
+
+    fn change_line(&mut self, line: usize) {
+        self.current = self.current.min(line);
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        // A line edited since it was checked may no longer fit its range
+        let highlight = self
+            .lines
+            .get(&self.current)
+            .map(|range| (range.start.min(line.len())..range.end.min(line.len()), ()))
+            .filter(|(range, _)| {
+                line.is_char_boundary(range.start) && line.is_char_boundary(range.end)
+            });
+        self.current += 1;
+        highlight.into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.current
+    }
+}
+
+/// Format of a highlighted string
+pub(crate) fn invalid_line_format(_highlight: &(), _theme: &Theme) -> highlighter::Format<Font> {
+    highlighter::Format {
+        color: Some(colors::ERROR),
+        font: None,
+    }
+}
@@ -1,13 +1,32 @@
 //! Main content panel: input area, aura list, import controls.
 
+use std::collections::BTreeMap;
+
+use iced::mouse::Interaction;
+use iced::widget::text::Wrapping;
 use iced::widget::{
-    button, checkbox, container, progress_bar, row, scrollable, space, text, text_input, Column,
+    button, checkbox, column, container, mouse_area, pick_list, progress_bar, row, scrollable,
+    slider, space, text, text_editor, text_input, Column, Row,
 };
 use iced::{Element, Length};
 
+use crate::duplicates::DuplicatePolicy;
+use crate::saved_variables::format_size;
+use crate::selection::SelectionMode;
 use crate::theme::{self, colors, spacing, typography};
+use crate::transforms::{
+    class_info, RenameIds, Rescale, RestrictLoad, StripSounds, TransformSpec, CLASSES,
+    REGION_TYPES, SOUND_CHANNELS,
+};
 
-use super::super::{Message, WeakAuraImporter};
+use super::super::state::{
+    ContextMenuTarget, ListColumn, ListGrouping, OffsetAxis, ParsedAuraEntry, StatusFilter,
+};
+use super::super::{Message, WeakAuraImporter, RULE_ANY, SOUND_CHANNEL_KEEP};
+use super::input_highlight::{invalid_line_format, InvalidLines};
+
+/// Width of the selection checkbox column of the parsed auras table
+const CHECKBOX_WIDTH: f32 = 24.0;
 
 impl WeakAuraImporter {
     pub(crate) fn render_main_content(&self) -> Element<'_, Message> {
@@ -47,15 +66,56 @@ impl WeakAuraImporter {
                 .on_press(Message::LoadFromFolder)
         };
 
+        let load_sv_btn = button(text("Load from SavedVariables...").size(typography::BODY))
+            .style(theme::button_secondary)
+            .on_press(Message::LoadFromSavedVariables);
+
+        let scan_settings_btn = button(text("Folder options").size(typography::CAPTION))
+            .style(theme::button_frameless)
+            .on_press(Message::ToggleScanSettings);
+
+        let templates_btn = button(text("Templates...").size(typography::BODY))
+            .style(theme::button_secondary)
+            .on_press(Message::ToggleTemplates);
+
         let clear_btn = button(text("Clear").size(typography::BODY))
             .style(theme::button_secondary)
             .on_press(Message::ClearInput);
 
-        content = content.push(
-            row![paste_btn, load_file_btn, load_folder_btn, clear_btn]
-                .spacing(spacing::SM)
-                .align_y(iced::Alignment::Center),
-        );
+        let collect_toggle = checkbox(self.config.collect_clipboard)
+            .label("Collect from clipboard")
+            .on_toggle(Message::SetCollectClipboard)
+            .text_size(typography::CAPTION);
+
+        let mut actions = row![
+            paste_btn,
+            load_file_btn,
+            load_folder_btn,
+            scan_settings_btn,
+            load_sv_btn,
+            templates_btn,
+            clear_btn,
+            space::horizontal(),
+            collect_toggle
+        ]
+        .spacing(spacing::SM)
+        .align_y(iced::Alignment::Center);
+        // Keeps collecting while minimized, with the count in the title
+        if self.config.collect_clipboard {
+            actions = actions.push(
+                button(text("Minimize").size(typography::BODY))
+                    .style(theme::button_secondary)
+                    .on_press(Message::MinimizeWindow),
+            );
+        }
+        content = content.push(actions);
+
+        if self.scan_settings.show {
+            content = content.push(self.render_scan_settings());
+        }
+        if self.templates.show {
+            content = content.push(self.render_templates());
+        }
 
         // Loading progress bar (shown during async file/folder loading)
         if self.tasks.is_loading {
@@ -86,6 +146,11 @@ impl WeakAuraImporter {
             content = content.push(self.render_paste_input_area());
         }
 
+        // Staging area (kept when the input is cleared)
+        if !self.staging.entries.is_empty() {
+            content = content.push(self.render_staging_panel());
+        }
+
         // Review & Import section (only if auras parsed)
         if !self.parsed_auras.is_empty() {
             // Divider
@@ -110,14 +175,137 @@ impl WeakAuraImporter {
             .into()
     }
 
+    /// Which files loading a folder reads
+    fn render_scan_settings(&self) -> Element<'_, Message> {
+        let settings = &self.scan_settings;
+        let field = |label: &'static str, input: Element<'static, Message>| {
+            row![
+                text(label)
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_SECONDARY)
+                    .width(Length::Fixed(110.0)),
+                input,
+            ]
+            .spacing(spacing::SM)
+            .align_y(iced::Alignment::Center)
+        };
+
+        let mut panel = column![
+            field(
+                "Extensions",
+                text_input("txt, md, lua", &settings.extensions)
+                    .on_input(Message::ScanExtensionsChanged)
+                    .style(theme::text_input_style)
+                    .size(typography::CAPTION)
+                    .into(),
+            ),
+            field(
+                "Ignore",
+                text_input("Backups/, .git/, *.bak", &settings.ignore)
+                    .on_input(Message::ScanIgnoreChanged)
+                    .style(theme::text_input_style)
+                    .size(typography::CAPTION)
+                    .into(),
+            ),
+            field(
+                "Max size (MB)",
+                text_input("No limit", &settings.max_size_mb)
+                    .on_input(Message::ScanMaxSizeChanged)
+                    .style(theme::text_input_style)
+                    .size(typography::CAPTION)
+                    .width(Length::Fixed(100.0))
+                    .into(),
+            ),
+            field(
+                "Max depth",
+                text_input("No limit", &settings.max_depth)
+                    .on_input(Message::ScanMaxDepthChanged)
+                    .style(theme::text_input_style)
+                    .size(typography::CAPTION)
+                    .width(Length::Fixed(100.0))
+                    .into(),
+            ),
+            checkbox(self.config.folder_scan.follow_symlinks)
+                .label("Follow symlinks")
+                .on_toggle(Message::SetScanFollowSymlinks)
+                .text_size(typography::CAPTION),
+        ]
+        .spacing(spacing::XS);
+        if let Some(error) = &settings.error {
+            panel = panel.push(
+                text(error.as_str())
+                    .size(typography::CAPTION)
+                    .color(colors::ERROR),
+            );
+        }
+
+        container(panel)
+            .padding(spacing::SM)
+            .width(Length::Fill)
+            .style(theme::container_inset)
+            .into()
+    }
+
+    /// Saved templates with their placeholders
+    fn render_templates(&self) -> Element<'_, Message> {
+        let mut panel = Column::new().spacing(spacing::XS);
+        if self.templates.store.templates.is_empty() {
+            panel = panel.push(
+                text(
+                    "No templates yet. Use {{name}} in an aura's strings, then right-click \
+                     it and choose \"Save as Template\".",
+                )
+                .size(typography::CAPTION)
+                .color(colors::TEXT_SECONDARY),
+            );
+        }
+        for (name, template) in &self.templates.store.templates {
+            let placeholders: Vec<String> = template.placeholders().into_iter().collect();
+            let placeholders = if placeholders.is_empty() {
+                "No placeholders".to_string()
+            } else {
+                placeholders.join(", ")
+            };
+            panel = panel.push(
+                row![
+                    text(name.as_str()).size(typography::BODY),
+                    text(placeholders)
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_SECONDARY),
+                    space::horizontal(),
+                    button(text("Generate...").size(typography::CAPTION))
+                        .style(theme::button_secondary)
+                        .on_press(Message::GenerateFromTemplate(name.clone())),
+                    button(text("Delete").size(typography::CAPTION))
+                        .style(theme::button_danger)
+                        .on_press(Message::DeleteTemplate(name.clone())),
+                ]
+                .spacing(spacing::SM)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+
+        container(panel)
+            .padding(spacing::SM)
+            .width(Length::Fill)
+            .style(theme::container_inset)
+            .into()
+    }
+
     fn render_paste_input_area(&self) -> Element<'_, Message> {
         let mut paste_content = Column::new().spacing(spacing::XS);
 
         // Text input area
-        let input_area = text_input("Paste a WeakAura import string", &self.input_text)
-            .on_input(Message::InputTextChanged)
-            .style(theme::text_input_style)
-            .width(Length::Fill);
+        // Lines whose string failed to decode are highlighted
+        let input_area = text_editor(&self.input_editor)
+            .placeholder("Paste WeakAura import strings, one per line")
+            .on_action(Message::InputAction)
+            .highlight_with::<InvalidLines>(
+                self.input_check.invalid_lines.clone(),
+                invalid_line_format,
+            )
+            .size(typography::BODY)
+            .height(Length::Fixed(120.0));
 
         let input_container = container(input_area)
             .style(theme::container_elevated)
@@ -125,6 +313,36 @@ impl WeakAuraImporter {
 
         paste_content = paste_content.push(input_container);
 
+        // Live validity of the strings not parsed yet
+        let check = &self.input_check;
+        if check.valid + check.invalid > 0 {
+            let (message, color) = if check.invalid == 0 {
+                (
+                    format!("{} valid string(s), ready to parse", check.valid),
+                    colors::SUCCESS,
+                )
+            } else {
+                (
+                    format!("{} valid, {} invalid string(s)", check.valid, check.invalid),
+                    colors::ERROR,
+                )
+            };
+            paste_content =
+                paste_content.push(text(message).size(typography::CAPTION).color(color));
+        }
+        if !check.invalid_lines.is_empty() {
+            let lines: Vec<String> = check
+                .invalid_lines
+                .keys()
+                .map(|line| (line + 1).to_string())
+                .collect();
+            paste_content = paste_content.push(
+                text(format!("Failed to decode: line {}", lines.join(", ")))
+                    .size(typography::CAPTION)
+                    .color(colors::ERROR),
+            );
+        }
+
         // Paste from clipboard and Parse buttons
         let paste_clipboard_btn = button(text("Paste from clipboard").size(typography::BODY))
             .style(theme::button_secondary)
@@ -134,10 +352,26 @@ impl WeakAuraImporter {
             .style(theme::button_primary)
             .on_press(Message::ParseInput);
 
+        let auto_parse_toggle = checkbox(self.config.auto_parse)
+            .label("Auto-parse")
+            .on_toggle(Message::SetAutoParse)
+            .text_size(typography::CAPTION);
+
+        let replace_toggle = checkbox(self.config.paste_replaces_input)
+            .label("Replace on paste")
+            .on_toggle(Message::SetPasteReplacesInput)
+            .text_size(typography::CAPTION);
+
         paste_content = paste_content.push(
-            row![paste_clipboard_btn, space::horizontal(), parse_btn]
-                .spacing(spacing::SM)
-                .align_y(iced::Alignment::Center),
+            row![
+                paste_clipboard_btn,
+                replace_toggle,
+                auto_parse_toggle,
+                space::horizontal(),
+                parse_btn
+            ]
+            .spacing(spacing::SM)
+            .align_y(iced::Alignment::Center),
         );
 
         paste_content.into()
@@ -163,21 +397,36 @@ impl WeakAuraImporter {
             && !self.tasks.is_loading;
 
         // Selection Controls, Import Button & Stats
-        let select_all_btn = if self.tasks.is_importing {
-            button(text("Select All").size(typography::BODY)).style(theme::button_secondary)
-        } else {
-            button(text("Select All").size(typography::BODY))
-                .style(theme::button_secondary)
-                .on_press(Message::SelectAllAuras)
+        let selection_rules_btn = |enabled: bool| {
+            let btn = if self.ui.show_selection_rules {
+                button(
+                    text("Select...")
+                        .size(typography::BODY)
+                        .color(colors::BG_VOID),
+                )
+                .style(theme::button_primary)
+            } else {
+                button(text("Select...").size(typography::BODY)).style(theme::button_secondary)
+            };
+            btn.on_press_maybe(enabled.then_some(Message::ToggleSelectionRules))
         };
-
-        let deselect_all_btn = if self.tasks.is_importing {
-            button(text("Deselect All").size(typography::BODY)).style(theme::button_secondary)
+        let enabled_transforms = self.config.transforms.iter().filter(|s| s.enabled).count();
+        let transforms_label = if enabled_transforms > 0 {
+            format!("Transforms ({})", enabled_transforms)
         } else {
-            button(text("Deselect All").size(typography::BODY))
-                .style(theme::button_secondary)
-                .on_press(Message::DeselectAllAuras)
+            "Transforms...".to_string()
         };
+        let transforms_btn = if self.ui.show_transforms {
+            button(
+                text(transforms_label)
+                    .size(typography::BODY)
+                    .color(colors::BG_VOID),
+            )
+            .style(theme::button_primary)
+        } else {
+            button(text(transforms_label).size(typography::BODY)).style(theme::button_secondary)
+        }
+        .on_press(Message::ToggleTransforms);
 
         // Remove Selected button
         let has_selected = self.parsed_auras.iter().any(|e| e.selected);
@@ -191,6 +440,14 @@ impl WeakAuraImporter {
                     .style(theme::button_secondary)
             };
 
+        // Stage Selected button
+        let stage_selected_btn = button(text("Stage Selected").size(typography::BODY))
+            .style(theme::button_secondary)
+            .on_press_maybe(
+                (has_selected && !self.tasks.is_importing && !self.tasks.is_loading)
+                    .then_some(Message::StageSelected),
+            );
+
         // Import button
         let import_btn = if can_import {
             button(
@@ -209,32 +466,14 @@ impl WeakAuraImporter {
             .style(theme::button_secondary)
         };
 
-        // Stats
-        let selected_count = self.parsed_auras.iter().filter(|e| e.selected).count();
-        let valid_count = self
-            .parsed_auras
-            .iter()
-            .filter(|e| e.validation.is_valid)
-            .count();
-
-        let stats_format = format!(
-            "{} selected / {} valid / {} total",
-            selected_count,
-            valid_count,
-            self.parsed_auras.len()
-        );
-
         let controls_row =
             if !can_import && self.saved_vars.selected_path.is_none() && !self.tasks.is_importing {
                 row![
-                    button(text("Select All").size(typography::BODY))
-                        .style(theme::button_secondary)
-                        .on_press(Message::SelectAllAuras),
-                    button(text("Deselect All").size(typography::BODY))
-                        .style(theme::button_secondary)
-                        .on_press(Message::DeselectAllAuras),
+                    selection_rules_btn(true),
+                    transforms_btn,
                     button(text("Remove Selected").size(typography::BODY))
                         .style(theme::button_secondary),
+                    stage_selected_btn,
                     button(
                         text("Import Selected >>")
                             .size(typography::BODY)
@@ -244,29 +483,56 @@ impl WeakAuraImporter {
                     text("Select a SavedVariables file first")
                         .size(typography::BODY)
                         .color(colors::TEXT_MUTED),
-                    space::horizontal(),
-                    text(stats_format)
-                        .size(typography::CAPTION)
-                        .color(colors::TEXT_SECONDARY)
                 ]
                 .spacing(spacing::SM)
                 .align_y(iced::Alignment::Center)
             } else {
                 row![
-                    select_all_btn,
-                    deselect_all_btn,
+                    selection_rules_btn(!self.tasks.is_importing),
+                    transforms_btn,
                     remove_selected_btn,
+                    stage_selected_btn,
                     import_btn,
-                    space::horizontal(),
-                    text(stats_format)
-                        .size(typography::CAPTION)
-                        .color(colors::TEXT_SECONDARY)
                 ]
                 .spacing(spacing::SM)
                 .align_y(iced::Alignment::Center)
             };
 
         header = header.push(controls_row);
+        if self.ui.show_selection_rules {
+            header = header.push(self.render_selection_rules());
+        }
+        if self.ui.show_transforms {
+            header = header.push(self.render_transforms());
+        }
+        header = header.push(self.render_status_chips());
+        header = header.push(
+            row![
+                text("Group by:")
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_SECONDARY),
+                pick_list(
+                    ListGrouping::ALL,
+                    Some(self.list_view.grouping),
+                    Message::SetListGrouping
+                )
+                .text_size(typography::CAPTION)
+                .width(Length::Fixed(120.0)),
+                text("Duplicates:")
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_SECONDARY),
+                pick_list(
+                    DuplicatePolicy::ALL,
+                    Some(self.config.duplicate_policy),
+                    Message::SetDuplicatePolicy
+                )
+                .text_size(typography::CAPTION)
+                .width(Length::Fixed(110.0)),
+                self.render_annotation_filter(),
+            ]
+            .spacing(spacing::SM)
+            .align_y(iced::Alignment::Center),
+        );
 
         // Progress bar (shown during import)
         if self.tasks.is_importing {
@@ -302,90 +568,834 @@ impl WeakAuraImporter {
             .into()
     }
 
-    fn render_aura_list(&self) -> Element<'_, Message> {
+    /// Staged auras with their own import button
+    fn render_staging_panel(&self) -> Element<'_, Message> {
+        let can_import = self.saved_vars.selected_path.is_some()
+            && !self.tasks.is_importing
+            && !self.tasks.is_loading;
+        let import_label = text("Import Staged >>").size(typography::BODY);
+        let import_btn = if can_import {
+            button(import_label.color(colors::BG_VOID))
+                .style(theme::button_primary)
+                .on_press(Message::ImportStaged)
+        } else {
+            button(import_label.color(colors::TEXT_MUTED)).style(theme::button_secondary)
+        };
+
+        let groups = self
+            .staging
+            .entries
+            .iter()
+            .filter(|e| e.validation.is_group)
+            .count();
+        let header = row![
+            text(format!("Staging ({})", self.staging.entries.len()))
+                .size(typography::HEADING)
+                .color(colors::GOLD),
+            text(format!("{} group(s)", groups))
+                .size(typography::CAPTION)
+                .color(colors::TEXT_SECONDARY),
+            space::horizontal(),
+            button(text("Clear Staging").size(typography::BODY))
+                .style(theme::button_secondary)
+                .on_press(Message::ClearStaging),
+            import_btn,
+        ]
+        .spacing(spacing::SM)
+        .align_y(iced::Alignment::Center);
+
         let mut list_col = Column::new().spacing(spacing::MICRO);
+        for (idx, entry) in self.staging.entries.iter().enumerate() {
+            let name = entry.validation.aura_id.clone().unwrap_or_default();
+            let source = entry
+                .source
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "pasted".to_string());
+            list_col = list_col.push(
+                row![
+                    text(name)
+                        .size(typography::BODY)
+                        .color(colors::TEXT_PRIMARY),
+                    text(source)
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_MUTED),
+                    space::horizontal(),
+                    button(text("Unstage").size(typography::CAPTION))
+                        .style(theme::button_frameless)
+                        .on_press(Message::UnstageAura(idx)),
+                ]
+                .spacing(spacing::SM)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+
+        column![
+            header,
+            container(
+                scrollable(list_col)
+                    .height(Length::Fixed(120.0))
+                    .style(theme::scrollable_style),
+            )
+            .style(theme::container_inset)
+            .padding(spacing::SM),
+        ]
+        .spacing(spacing::XS)
+        .into()
+    }
 
-        for (idx, entry) in self.parsed_auras.iter().enumerate() {
-            let is_selected_for_view = self.selected_aura_index == Some(idx);
-            let is_valid = entry.validation.is_valid;
+    fn render_aura_list(&self) -> Element<'_, Message> {
+        let mut list_col = Column::new().spacing(spacing::MICRO);
 
-            let mut item_row = row![].spacing(spacing::XS).align_y(iced::Alignment::Center);
+        let visible = self.visible_entries();
+        let duplicates = self.duplicate_indices();
 
-            // Checkbox for selection (valid auras only)
-            if is_valid {
-                let checkbox_widget =
-                    checkbox(entry.selected).on_toggle(move |_| Message::ToggleAuraSelection(idx));
-                item_row = item_row.push(checkbox_widget);
-            } else {
-                // Placeholder to maintain alignment
-                item_row = item_row.push(space::horizontal().width(Length::Fixed(24.0)));
+        let grouping = self.list_view.grouping;
+        if grouping == ListGrouping::None {
+            for (idx, entry) in visible {
+                list_col = list_col.push(self.render_aura_list_item(
+                    idx,
+                    entry,
+                    duplicates.contains(&idx),
+                ));
+                if entry.show_children {
+                    list_col = list_col.push(self.render_entry_children(idx, entry));
+                }
+            }
+        } else {
+            let mut groups: BTreeMap<String, Vec<(usize, &ParsedAuraEntry)>> = BTreeMap::new();
+            for (idx, entry) in visible {
+                groups
+                    .entry(entry.group_key(grouping))
+                    .or_default()
+                    .push((idx, entry));
             }
 
-            // Aura name - always use button for consistent spacing
-            let name = entry.validation.summary();
-            // Dark text only when selected AND JSON view is visible (button has primary bg)
-            let name_color = if is_valid {
-                if is_selected_for_view && self.ui.show_decoded_view {
-                    colors::BG_VOID
-                } else {
-                    colors::TEXT_PRIMARY
+            for (key, entries) in groups {
+                let is_collapsed = self.list_view.collapsed_groups.contains(&key);
+                list_col =
+                    list_col.push(self.render_aura_group_header(&key, &entries, is_collapsed));
+                if !is_collapsed {
+                    for (idx, entry) in entries {
+                        list_col = list_col.push(
+                            container(self.render_aura_list_item(
+                                idx,
+                                entry,
+                                duplicates.contains(&idx),
+                            ))
+                            .padding(iced::Padding::default().left(spacing::MD)),
+                        );
+                        if entry.show_children {
+                            list_col = list_col.push(
+                                container(self.render_entry_children(idx, entry))
+                                    .padding(iced::Padding::default().left(spacing::MD)),
+                            );
+                        }
+                    }
                 }
+            }
+        }
+
+        let list_container = container(
+            column![
+                self.render_aura_table_header(),
+                scrollable(list_col)
+                    .height(Length::Fill)
+                    .style(theme::scrollable_style),
+            ]
+            .spacing(spacing::XS),
+        )
+        .style(theme::container_inset)
+        .padding(spacing::SM)
+        .height(Length::Fill);
+
+        list_container.into()
+    }
+
+    /// Status chips with live counts; clicking one shows only its entries
+    fn render_status_chips(&self) -> Element<'_, Message> {
+        let mut chips = row![].spacing(spacing::XS).align_y(iced::Alignment::Center);
+        let duplicates = self.duplicate_indices();
+        for filter in StatusFilter::ALL {
+            let count = self
+                .parsed_auras
+                .iter()
+                .enumerate()
+                .filter(|(idx, entry)| filter.matches(entry, duplicates.contains(idx)))
+                .count();
+            // Only offered while there is something to resolve
+            if filter == StatusFilter::Duplicates
+                && count == 0
+                && self.list_view.status_filter != filter
+            {
+                continue;
+            }
+            let label = text(format!("{} {}", filter.label(), count)).size(typography::CAPTION);
+            let chip = if self.list_view.status_filter == filter {
+                button(label.color(colors::BG_VOID)).style(theme::button_primary)
             } else {
-                colors::TEXT_MUTED
+                button(label).style(theme::button_secondary)
             };
-
-            // Always use a button wrapper for consistent padding/spacing
-            // regardless of whether JSON view is active
-            let label_btn = button(text(name).size(typography::BODY).color(name_color)).style(
-                if is_selected_for_view && self.ui.show_decoded_view {
-                    theme::button_primary
-                } else {
-                    theme::button_frameless
-                },
+            chips = chips.push(
+                chip.padding(iced::Padding::from([2, 8]))
+                    .on_press(Message::SetStatusFilter(filter)),
             );
+        }
+        chips.into()
+    }
+
+    /// Column headers of the parsed auras table: click one to sort by it,
+    /// drag its right edge to resize it
+    fn render_aura_table_header(&self) -> Element<'_, Message> {
+        let mut header = row![space::horizontal().width(Length::Fixed(CHECKBOX_WIDTH))]
+            .spacing(spacing::XS)
+            .align_y(iced::Alignment::Center);
+        if self.list_view.grouping != ListGrouping::None {
+            header = header.push(space::horizontal().width(Length::Fixed(spacing::MD)));
+        }
 
-            // Only make clickable when JSON panel is visible and aura is valid
-            let label_btn = if self.ui.show_decoded_view && is_valid {
-                label_btn.on_press(Message::SelectAuraForPreview(idx))
+        for column in ListColumn::ALL {
+            let arrow = match self.list_view.sort_column {
+                Some(sorted) if sorted == column && self.list_view.sort_descending => " ▼",
+                Some(sorted) if sorted == column => " ▲",
+                _ => "",
+            };
+            let edge_color = if self
+                .list_view
+                .resizing
+                .is_some_and(|resize| resize.column == column)
+            {
+                colors::GOLD
             } else {
-                label_btn
+                colors::BORDER
             };
+            let resize_edge = mouse_area(
+                container(space::horizontal())
+                    .width(Length::Fixed(3.0))
+                    .height(Length::Fixed(18.0))
+                    .style(move |_theme| container::Style {
+                        background: Some(edge_color.into()),
+                        ..Default::default()
+                    }),
+            )
+            .interaction(Interaction::ResizingHorizontally)
+            .on_press(Message::StartColumnResize(column));
 
-            item_row = item_row.push(label_btn);
-
-            // Group badge
-            if entry.validation.is_group {
-                item_row = item_row.push(
-                    container(
-                        text(format!("{}", entry.validation.child_count))
+            header = header.push(
+                row![
+                    button(
+                        text(format!("{}{}", column.label(), arrow))
                             .size(typography::CAPTION)
-                            .color(colors::TEXT_MUTED),
+                            .color(colors::TEXT_SECONDARY)
+                            .wrapping(Wrapping::None),
                     )
-                    .padding(iced::Padding::from([2, 6]))
-                    .style(theme::container_inset),
-                );
-            }
+                    .style(theme::button_frameless)
+                    .width(Length::Fill)
+                    .on_press(Message::SortListBy(column)),
+                    resize_edge,
+                ]
+                .align_y(iced::Alignment::Center)
+                .width(Length::Fixed(self.list_view.column_width(column))),
+            );
+        }
+        header.into()
+    }
 
-            // Remove button (at the end)
-            let remove_btn = button(text("×").color(colors::ERROR).size(typography::BODY))
-                .style(theme::button_frameless)
-                .on_press(Message::RemoveAuraFromList(idx));
-            item_row = item_row.push(space::horizontal().width(Length::Fill));
-            item_row = item_row.push(remove_btn);
-            item_row = item_row.push(space::horizontal().width(Length::Fixed(spacing::SM)));
+    /// Selection rules popover: predicates plus select/deselect/only actions
+    fn render_selection_rules(&self) -> Element<'_, Message> {
+        let rule = &self.selection_rule;
+
+        let mut region_types: Vec<String> = self
+            .parsed_auras
+            .iter()
+            .filter_map(|e| e.aura.as_ref().and_then(|a| a.region_type.clone()))
+            .collect();
+        region_types.sort();
+        region_types.dedup();
+        region_types.insert(0, RULE_ANY.to_string());
+
+        let mut source_files: Vec<String> = self
+            .parsed_auras
+            .iter()
+            .filter_map(|e| e.source.as_ref().and_then(|p| p.file_name()))
+            .map(|n| n.to_string_lossy().to_string())
+            .collect();
+        source_files.sort();
+        source_files.dedup();
+        source_files.insert(0, RULE_ANY.to_string());
+
+        let selected_region = rule
+            .region_type
+            .clone()
+            .unwrap_or_else(|| RULE_ANY.to_string());
+        let selected_source = rule
+            .source_file
+            .clone()
+            .unwrap_or_else(|| RULE_ANY.to_string());
+
+        let label = |s: &'static str| {
+            text(s)
+                .size(typography::CAPTION)
+                .color(colors::TEXT_SECONDARY)
+        };
+
+        let predicates = row![
+            checkbox(rule.valid_only)
+                .label("Valid only")
+                .on_toggle(Message::SetRuleValidOnly)
+                .text_size(typography::CAPTION),
+            checkbox(rule.groups_only)
+                .label("Groups only")
+                .on_toggle(Message::SetRuleGroupsOnly)
+                .text_size(typography::CAPTION),
+            checkbox(self.config.skip_trivial_auras)
+                .label("Skip trivial auras")
+                .on_toggle(Message::SetSkipTrivialAuras)
+                .text_size(typography::CAPTION),
+            label("Region:"),
+            pick_list(
+                region_types,
+                Some(selected_region),
+                Message::SetRuleRegionType
+            )
+            .text_size(typography::CAPTION),
+            label("File:"),
+            pick_list(
+                source_files,
+                Some(selected_source),
+                Message::SetRuleSourceFile
+            )
+            .text_size(typography::CAPTION),
+        ]
+        .spacing(spacing::SM)
+        .align_y(iced::Alignment::Center);
+
+        let pattern_input = text_input("ID regex, e.g. ^Boss.*Timer", &rule.id_pattern)
+            .on_input(Message::RuleIdPatternChanged)
+            .style(theme::text_input_style)
+            .size(typography::CAPTION)
+            .width(Length::Fill);
+
+        let actions = row![
+            pattern_input,
+            button(text("Select matching").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press(Message::ApplySelectionRule(SelectionMode::Select)),
+            button(text("Deselect matching").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press(Message::ApplySelectionRule(SelectionMode::Deselect)),
+            button(text("Only matching").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press(Message::ApplySelectionRule(SelectionMode::Only)),
+        ]
+        .spacing(spacing::SM)
+        .align_y(iced::Alignment::Center);
+
+        container(
+            Column::new()
+                .push(predicates)
+                .push(actions)
+                .spacing(spacing::SM),
+        )
+        .padding(spacing::SM)
+        .style(theme::container_surface)
+        .width(Length::Fill)
+        .into()
+    }
 
-            list_col = list_col.push(item_row);
+    /// Transforms run on the selected auras before import, in order
+    fn render_transforms(&self) -> Element<'_, Message> {
+        let count = self.config.transforms.len();
+        let mut steps = Column::new().spacing(spacing::XS);
+
+        for (idx, step) in self.config.transforms.iter().enumerate() {
+            steps = steps.push(
+                row![
+                    checkbox(step.enabled)
+                        .label(step.spec.to_string())
+                        .on_toggle(move |v| Message::SetTransformEnabled(idx, v))
+                        .text_size(typography::CAPTION)
+                        .width(Length::Fixed(220.0)),
+                    self.render_transform_parameters(idx, &step.spec),
+                    text(step.spec.description())
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_MUTED),
+                    space::horizontal(),
+                    button(text("↑").size(typography::CAPTION))
+                        .style(theme::button_secondary)
+                        .on_press_maybe((idx > 0).then_some(Message::MoveTransform(idx, true))),
+                    button(text("↓").size(typography::CAPTION))
+                        .style(theme::button_secondary)
+                        .on_press_maybe(
+                            (idx + 1 < count).then_some(Message::MoveTransform(idx, false)),
+                        ),
+                ]
+                .spacing(spacing::SM)
+                .align_y(iced::Alignment::Center),
+            );
         }
 
-        let list_container = container(
-            scrollable(list_col)
-                .height(Length::Fill)
-                .style(theme::scrollable_style),
+        container(
+            Column::new()
+                .push(
+                    text("Enabled transforms run from top to bottom on the selected auras before import")
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_SECONDARY),
+                )
+                .push(steps)
+                .spacing(spacing::SM),
         )
-        .style(theme::container_inset)
         .padding(spacing::SM)
-        .height(Length::Fill);
+        .style(theme::container_surface)
+        .width(Length::Fill)
+        .into()
+    }
 
-        list_container.into()
+    /// Inputs for the parameters of one transform
+    fn render_transform_parameters<'a>(
+        &'a self,
+        idx: usize,
+        spec: &'a TransformSpec,
+    ) -> Element<'a, Message> {
+        match spec {
+            TransformSpec::StripSounds(strip) => {
+                let toggle = |label: &'static str, value: bool, set: fn(&mut StripSounds, bool)| {
+                    let strip = strip.clone();
+                    checkbox(value)
+                        .label(label)
+                        .on_toggle(move |v| {
+                            let mut strip = strip.clone();
+                            set(&mut strip, v);
+                            Message::SetTransformSpec(idx, TransformSpec::StripSounds(strip))
+                        })
+                        .text_size(typography::CAPTION)
+                };
+                let mut channels = vec![SOUND_CHANNEL_KEEP.to_string()];
+                channels.extend(SOUND_CHANNELS.iter().map(|c| c.to_string()));
+                let selected_channel = strip
+                    .sound_channel
+                    .clone()
+                    .unwrap_or_else(|| SOUND_CHANNEL_KEEP.to_string());
+                let channel_strip = strip.clone();
+
+                let mut parameters = row![
+                    toggle("Sounds", strip.sounds, |s, v| s.sounds = v),
+                    toggle("Chat", strip.chat, |s, v| s.chat = v),
+                    toggle("TTS", strip.tts, |s, v| s.tts = v),
+                ]
+                .spacing(spacing::SM)
+                .align_y(iced::Alignment::Center);
+                if !strip.sounds {
+                    parameters = parameters.push(
+                        pick_list(channels, Some(selected_channel), move |channel: String| {
+                            let mut strip = channel_strip.clone();
+                            strip.sound_channel =
+                                (channel != SOUND_CHANNEL_KEEP).then_some(channel);
+                            Message::SetTransformSpec(idx, TransformSpec::StripSounds(strip))
+                        })
+                        .text_size(typography::CAPTION)
+                        .width(Length::Fixed(120.0)),
+                    );
+                }
+                parameters.into()
+            }
+            TransformSpec::Rescale(rescale) => slider(0.25..=2.0, rescale.factor, move |v| {
+                // Slider steps accumulate float error
+                let factor = (v * 100.0).round() / 100.0;
+                Message::SetTransformSpec(idx, TransformSpec::Rescale(Rescale { factor }))
+            })
+            .step(0.05)
+            .width(Length::Fixed(160.0))
+            .into(),
+            TransformSpec::Reposition(reposition) => {
+                let offset_input = |axis: OffsetAxis, value: f64| {
+                    let content = self
+                        .transforms
+                        .offset_inputs
+                        .get(&(idx, axis))
+                        .cloned()
+                        .unwrap_or_else(|| value.to_string());
+                    text_input("0", &content)
+                        .on_input(move |v| Message::TransformOffsetInput(idx, axis, v))
+                        .style(theme::text_input_style)
+                        .size(typography::CAPTION)
+                        .width(Length::Fixed(60.0))
+                };
+                let frame_reposition = reposition.clone();
+                let label = |s: &'static str| {
+                    text(s)
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_SECONDARY)
+                };
+
+                row![
+                    label("X"),
+                    offset_input(OffsetAxis::X, reposition.x_offset),
+                    label("Y"),
+                    offset_input(OffsetAxis::Y, reposition.y_offset),
+                    text_input(
+                        "Anchor frame (unchanged)",
+                        reposition.anchor_frame.as_deref().unwrap_or_default()
+                    )
+                    .on_input(move |frame| {
+                        let mut reposition = frame_reposition.clone();
+                        let frame = frame.trim().to_string();
+                        reposition.anchor_frame = (!frame.is_empty()).then_some(frame);
+                        Message::SetTransformSpec(idx, TransformSpec::Reposition(reposition))
+                    })
+                    .style(theme::text_input_style)
+                    .size(typography::CAPTION)
+                    .width(Length::Fixed(160.0)),
+                ]
+                .spacing(spacing::XS)
+                .align_y(iced::Alignment::Center)
+                .into()
+            }
+            TransformSpec::RestrictLoad(restrict) => {
+                let class = restrict.class.as_deref().and_then(class_info);
+                let mut class_names = vec![RULE_ANY.to_string()];
+                class_names.extend(CLASSES.iter().map(|c| c.name.to_string()));
+                let selected_class = class.map_or(RULE_ANY, |c| c.name).to_string();
+
+                let mut parameters =
+                    row![
+                        pick_list(class_names, Some(selected_class), move |name: String| {
+                            let class = CLASSES.iter().find(|c| c.name == name);
+                            Message::SetTransformSpec(
+                                idx,
+                                TransformSpec::RestrictLoad(RestrictLoad {
+                                    class: class.map(|c| c.token.to_string()),
+                                    spec: None,
+                                }),
+                            )
+                        })
+                        .text_size(typography::CAPTION)
+                        .width(Length::Fixed(120.0))
+                    ]
+                    .spacing(spacing::XS)
+                    .align_y(iced::Alignment::Center);
+
+                if let Some(class) = class {
+                    let mut spec_names = vec![RULE_ANY.to_string()];
+                    spec_names.extend(class.specs.iter().map(|(_, name)| name.to_string()));
+                    let selected_spec = class
+                        .specs
+                        .iter()
+                        .find(|(id, _)| restrict.spec == Some(*id))
+                        .map_or(RULE_ANY, |(_, name)| name)
+                        .to_string();
+                    parameters = parameters.push(
+                        pick_list(spec_names, Some(selected_spec), move |name: String| {
+                            let spec = class.specs.iter().find(|(_, n)| *n == name);
+                            Message::SetTransformSpec(
+                                idx,
+                                TransformSpec::RestrictLoad(RestrictLoad {
+                                    class: Some(class.token.to_string()),
+                                    spec: spec.map(|(id, _)| *id),
+                                }),
+                            )
+                        })
+                        .text_size(typography::CAPTION)
+                        .width(Length::Fixed(120.0)),
+                    );
+                }
+                parameters.into()
+            }
+            TransformSpec::ExcludeRegionTypes(exclude) => {
+                let mut parameters = Row::new()
+                    .spacing(spacing::SM)
+                    .align_y(iced::Alignment::Center);
+                for region_type in REGION_TYPES {
+                    let excluded = exclude.region_types.iter().any(|t| t == *region_type);
+                    let current = exclude.clone();
+                    parameters = parameters.push(
+                        checkbox(excluded)
+                            .label(*region_type)
+                            .on_toggle(move |skip| {
+                                let mut exclude = current.clone();
+                                exclude.region_types.retain(|t| t != *region_type);
+                                if skip {
+                                    exclude.region_types.push(region_type.to_string());
+                                }
+                                Message::SetTransformSpec(
+                                    idx,
+                                    TransformSpec::ExcludeRegionTypes(exclude),
+                                )
+                            })
+                            .text_size(typography::CAPTION),
+                    );
+                }
+                parameters.into()
+            }
+            TransformSpec::RenameIds(rename) => text_input("{name} [M+]", &rename.template)
+                .on_input(move |template| {
+                    Message::SetTransformSpec(idx, TransformSpec::RenameIds(RenameIds { template }))
+                })
+                .style(theme::text_input_style)
+                .size(typography::CAPTION)
+                .width(Length::Fixed(160.0))
+                .into(),
+        }
+    }
+
+    /// Collapsible section header with per-group select/deselect controls
+    fn render_aura_group_header<'a>(
+        &self,
+        key: &str,
+        entries: &[(usize, &ParsedAuraEntry)],
+        is_collapsed: bool,
+    ) -> Row<'a, Message> {
+        let selected = entries.iter().filter(|(_, e)| e.selected).count();
+        let expand_icon = if is_collapsed { "▶" } else { "▼" };
+        let controls_enabled = !self.tasks.is_importing;
+
+        let select_btn = button(text("Select").size(typography::CAPTION))
+            .style(theme::button_secondary)
+            .on_press_maybe(
+                controls_enabled.then(|| Message::SetGroupSelection(key.to_string(), true)),
+            );
+        let deselect_btn = button(text("Deselect").size(typography::CAPTION))
+            .style(theme::button_secondary)
+            .on_press_maybe(
+                controls_enabled.then(|| Message::SetGroupSelection(key.to_string(), false)),
+            );
+
+        row![
+            button(text(expand_icon).size(typography::CAPTION))
+                .style(theme::button_frameless)
+                .on_press(Message::ToggleListGroupCollapsed(key.to_string())),
+            text(key.to_string())
+                .size(typography::BODY)
+                .color(colors::GOLD),
+            text(format!("({}/{})", selected, entries.len()))
+                .size(typography::CAPTION)
+                .color(colors::TEXT_MUTED),
+            space::horizontal(),
+            select_btn,
+            deselect_btn,
+            space::horizontal().width(Length::Fixed(spacing::SM)),
+        ]
+        .spacing(spacing::XS)
+        .align_y(iced::Alignment::Center)
+    }
+
+    /// Table row of a parsed aura; right-clicking it opens its context menu
+    /// below it. A duplicate (an aura listed more than once with differing
+    /// data) offers to keep this copy.
+    fn render_aura_list_item<'a>(
+        &self,
+        idx: usize,
+        entry: &ParsedAuraEntry,
+        is_duplicate: bool,
+    ) -> Element<'a, Message> {
+        let is_selected_for_view = self.selected_aura_index == Some(idx);
+        let is_valid = entry.validation.is_valid;
+        let cell = |content: Element<'a, Message>, column: ListColumn| {
+            container(content)
+                .width(Length::Fixed(self.list_view.column_width(column)))
+                .clip(true)
+        };
+        let cell_text = |value: String, color: iced::Color| -> Element<'a, Message> {
+            text(value)
+                .size(typography::CAPTION)
+                .color(color)
+                .wrapping(Wrapping::None)
+                .into()
+        };
+
+        let mut item_row = row![].spacing(spacing::XS).align_y(iced::Alignment::Center);
+
+        // Checkbox for selection (valid auras only)
+        let selection: Element<'a, Message> = if is_valid {
+            checkbox(entry.selected)
+                .on_toggle(move |_| Message::ToggleAuraSelection(idx))
+                .into()
+        } else {
+            // Placeholder to maintain alignment
+            space::horizontal().into()
+        };
+        item_row = item_row.push(container(selection).width(Length::Fixed(CHECKBOX_WIDTH)));
+
+        // Aura name - always use button for consistent spacing
+        let name = entry.validation.summary();
+        let badges = self
+            .render_annotation_badges(entry.aura.as_ref().and_then(|a| a.uid.as_deref()), &name);
+        // Dark text only when selected AND JSON view is visible (button has primary bg)
+        let name_color = if is_valid {
+            if is_selected_for_view && self.ui.show_decoded_view {
+                colors::BG_VOID
+            } else {
+                colors::TEXT_PRIMARY
+            }
+        } else {
+            colors::TEXT_MUTED
+        };
+
+        // Always use a button wrapper for consistent padding/spacing
+        // regardless of whether JSON view is active
+        let label_btn = button(
+            text(name)
+                .size(typography::BODY)
+                .color(name_color)
+                .wrapping(Wrapping::None),
+        )
+        .style(if is_selected_for_view && self.ui.show_decoded_view {
+            theme::button_primary
+        } else {
+            theme::button_frameless
+        });
+
+        // Clicking previews the aura, ctrl/shift-clicking selects it
+        let label_btn = if is_valid {
+            label_btn.on_press(Message::ParsedAuraClicked(idx))
+        } else {
+            label_btn
+        };
+
+        item_row = item_row.push(cell(
+            row![label_btn, badges]
+                .spacing(spacing::XS)
+                .align_y(iced::Alignment::Center)
+                .into(),
+            ListColumn::Id,
+        ));
+
+        let type_label = if is_valid {
+            entry.region_type().to_string()
+        } else {
+            String::new()
+        };
+        item_row = item_row.push(cell(
+            cell_text(type_label, colors::TEXT_SECONDARY),
+            ListColumn::Type,
+        ));
+
+        // Child count of a group, expanding the child auras
+        let children: Element<'a, Message> = if entry.validation.is_group {
+            let (included, total) = entry.included_child_count();
+            let count = if included == total {
+                total.to_string()
+            } else {
+                format!("{}/{}", included, total)
+            };
+            let expand_icon = if entry.show_children { "▼" } else { "▶" };
+            button(
+                text(format!("{} {}", expand_icon, count))
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_MUTED),
+            )
+            .padding(iced::Padding::from([2, 6]))
+            .style(theme::button_secondary)
+            .on_press(Message::ToggleEntryChildren(idx))
+            .into()
+        } else {
+            space::horizontal().into()
+        };
+        item_row = item_row.push(cell(children, ListColumn::Children));
+
+        item_row = item_row.push(cell(
+            cell_text(entry.source_name(), colors::TEXT_SECONDARY),
+            ListColumn::Source,
+        ));
+        let size = if is_valid {
+            format_size(entry.size() as u64)
+        } else {
+            String::new()
+        };
+        item_row = item_row.push(cell(cell_text(size, colors::TEXT_MUTED), ListColumn::Size));
+        let status = if is_duplicate {
+            cell_text("Duplicate".to_string(), colors::WARNING)
+        } else if let Some(reason) = entry.trivial_reason() {
+            cell_text(format!("Trivial: {}", reason), colors::TEXT_MUTED)
+        } else if is_valid {
+            cell_text("Valid".to_string(), colors::SUCCESS)
+        } else {
+            cell_text("Invalid".to_string(), colors::ERROR)
+        };
+        item_row = item_row.push(cell(status, ListColumn::Status));
+
+        item_row = item_row.push(space::horizontal().width(Length::Fill));
+
+        // A group wrapping a single aura can be imported as that aura
+        if entry
+            .aura
+            .as_ref()
+            .is_some_and(|aura| aura.is_single_child_wrapper())
+        {
+            item_row = item_row.push(
+                checkbox(entry.unwrap_group)
+                    .label("Unwrap")
+                    .text_size(typography::CAPTION)
+                    .on_toggle(move |checked| Message::SetUnwrapGroup(idx, checked)),
+            );
+        }
+
+        if is_duplicate {
+            item_row = item_row.push(
+                button(text("Keep this").size(typography::CAPTION))
+                    .padding(iced::Padding::from([2, 6]))
+                    .style(theme::button_secondary)
+                    .on_press(Message::KeepDuplicate(idx)),
+            );
+        }
+
+        // Remove button (at the end)
+        let remove_btn = button(text("×").color(colors::ERROR).size(typography::BODY))
+            .style(theme::button_frameless)
+            .on_press(Message::RemoveAuraFromList(idx));
+        item_row = item_row.push(remove_btn);
+        item_row = item_row.push(space::horizontal().width(Length::Fixed(spacing::SM)));
+
+        let target = ContextMenuTarget::ParsedAura(idx);
+        let item = mouse_area(item_row).on_right_press(Message::OpenContextMenu(target.clone()));
+        if self.context_menu.target == Some(target) {
+            column![item, self.render_parsed_context_menu(idx, entry)]
+                .spacing(spacing::MICRO)
+                .into()
+        } else {
+            item.into()
+        }
+    }
+
+    /// Child auras of a listed group, each with a checkbox to leave it out
+    /// of the import. Children of an excluded subgroup show as unchecked.
+    fn render_entry_children<'a>(
+        &self,
+        idx: usize,
+        entry: &ParsedAuraEntry,
+    ) -> Column<'a, Message> {
+        let mut col = Column::new().spacing(spacing::MICRO);
+        let Some(aura) = &entry.aura else {
+            return col;
+        };
+        // Depth of the closest excluded ancestor while inside it
+        let mut excluded_depth: Option<usize> = None;
+        for (id, depth) in aura.descendants() {
+            if excluded_depth.is_some_and(|excluded| depth <= excluded) {
+                excluded_depth = None;
+            }
+            if excluded_depth.is_none() && entry.excluded_children.contains(&id) {
+                excluded_depth = Some(depth);
+            }
+            let included = excluded_depth.is_none();
+            let child_id = id.clone();
+            col = col.push(
+                row![
+                    checkbox(included).on_toggle(move |checked| {
+                        Message::SetChildIncluded(idx, child_id.clone(), checked)
+                    }),
+                    text(id).size(typography::CAPTION).color(if included {
+                        colors::TEXT_SECONDARY
+                    } else {
+                        colors::TEXT_MUTED
+                    }),
+                ]
+                .spacing(spacing::XS)
+                .align_y(iced::Alignment::Center)
+                .padding(iced::Padding::default().left(24.0 + depth as f32 * 12.0)),
+            );
+        }
+        col
     }
 }
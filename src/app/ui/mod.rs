@@ -1,14 +1,23 @@
 //! UI rendering components for iced.
 
+mod context_menu;
 mod dialogs;
+mod input_highlight;
 mod main_panel;
+mod references;
+mod side_window;
 mod sidebar;
+mod tasks;
 
-use iced::widget::{button, column, container, row, scrollable, space, text};
-use iced::{Element, Length};
+use iced::widget::{
+    button, column, container, row, scrollable, space, text, text_editor, text_input, Column, Row,
+};
+use iced::{Element, Length, Padding};
 
+use crate::decoder::AuraTextFormat;
 use crate::theme::{self, colors, spacing, typography};
 
+use super::state::AuraProperty;
 use super::{Message, WeakAuraImporter};
 
 impl WeakAuraImporter {
@@ -44,9 +53,84 @@ impl WeakAuraImporter {
             .style(theme::button_frameless)
             .on_press(Message::ShowSetupWizard);
 
-        row![file_menu, edit_menu, clear_btn, view_menu, setup_btn]
-            .spacing(spacing::SM)
-            .align_y(iced::Alignment::Center)
+        let side_window_btn = button(text("Second Window").size(typography::BODY))
+            .style(theme::button_frameless)
+            .on_press(Message::OpenSideWindow);
+
+        let references_btn = button(text("Find References").size(typography::BODY))
+            .style(theme::button_frameless)
+            .on_press(Message::ToggleReferences);
+
+        let unread = self.notifications.unread();
+        let notifications_label = if unread > 0 {
+            format!("Notifications ({})", unread)
+        } else {
+            "Notifications".to_string()
+        };
+        let notifications_btn = button(text(notifications_label).size(typography::BODY))
+            .style(theme::button_frameless)
+            .on_press(Message::ToggleNotifications);
+
+        row![
+            file_menu,
+            edit_menu,
+            clear_btn,
+            view_menu,
+            setup_btn,
+            side_window_btn,
+            references_btn,
+            notifications_btn
+        ]
+        .spacing(spacing::SM)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// Render tag chips and the edit button for an aura's annotations
+    pub(crate) fn render_annotation_badges<'a>(
+        &self,
+        uid: Option<&str>,
+        name: &str,
+    ) -> Row<'a, Message> {
+        let mut badges = row![].spacing(spacing::XS).align_y(iced::Alignment::Center);
+        let Some(uid) = uid else {
+            return badges;
+        };
+
+        let annotation = self.annotations.store.get(uid);
+        if let Some(annotation) = annotation {
+            for tag in &annotation.tags {
+                badges = badges.push(
+                    container(
+                        text(tag.clone())
+                            .size(typography::MICRO)
+                            .color(colors::GOLD),
+                    )
+                    .padding(Padding::from([1, 6]))
+                    .style(theme::container_inset),
+                );
+            }
+        }
+
+        let has_note = annotation.is_some_and(|a| !a.note.trim().is_empty());
+        badges.push(
+            button(text("✎").size(typography::CAPTION).color(if has_note {
+                colors::GOLD
+            } else {
+                colors::TEXT_MUTED
+            }))
+            .style(theme::button_frameless)
+            .on_press(Message::EditAnnotations(uid.to_string(), name.to_string())),
+        )
+    }
+
+    /// Render the tag/note filter input shared by both aura lists
+    pub(crate) fn render_annotation_filter(&self) -> Element<'_, Message> {
+        text_input("Filter by tag or note...", &self.annotations.filter)
+            .on_input(Message::AnnotationFilterChanged)
+            .style(theme::text_input_style)
+            .size(typography::CAPTION)
+            .width(Length::Fill)
             .into()
     }
 
@@ -66,27 +150,38 @@ impl WeakAuraImporter {
             .spacing(spacing::MD)
             .align_y(iced::Alignment::Center);
 
-        // Add progress bar only during import
-        if self.tasks.is_importing {
-            use iced::widget::progress_bar;
-            use iced::Border;
-
-            content = content.push(space::horizontal().width(Length::Fill));
-            content =
-                content.push(
-                    container(progress_bar(0.0..=1.0, self.tasks.import_progress).style(
-                        |_theme| progress_bar::Style {
-                            background: colors::BG_SURFACE.into(),
-                            bar: colors::GOLD.into(),
-                            border: Border::default().rounded(4.0),
-                        },
-                    ))
-                    .height(8.0)
-                    .width(Length::Fixed(200.0)),
-                );
-        } else {
-            content = content.push(space::horizontal().width(Length::Fill));
-        }
+        content = content.push(space::horizontal().width(Length::Fill));
+
+        // Running jobs are listed in the Tasks panel
+        let running = self.running_tasks().len();
+        content = content.push(
+            button(
+                text(format!("Tasks ({})", running))
+                    .size(typography::CAPTION)
+                    .color(if running > 0 {
+                        colors::GOLD
+                    } else {
+                        colors::TEXT_MUTED
+                    }),
+            )
+            .style(theme::button_frameless)
+            .on_press(Message::ToggleTasks),
+        );
+
+        let problem_count = self.notifications.problem_count();
+        content = content.push(
+            button(
+                text(format!("Problems ({})", problem_count))
+                    .size(typography::CAPTION)
+                    .color(if problem_count > 0 {
+                        colors::WARNING
+                    } else {
+                        colors::TEXT_MUTED
+                    }),
+            )
+            .style(theme::button_frameless)
+            .on_press(Message::ToggleProblems),
+        );
 
         container(content)
             .width(Length::Fill)
@@ -95,28 +190,134 @@ impl WeakAuraImporter {
             .into()
     }
 
+    /// Render the Problems panel: every warning and error of the session
+    pub(crate) fn render_problems_panel(&self) -> Element<'_, Message> {
+        let mut rows = Column::new().spacing(spacing::MICRO);
+        for problem in self.notifications.problems() {
+            rows = rows.push(
+                row![
+                    text(problem.timestamp())
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_MUTED)
+                        .width(Length::Fixed(60.0)),
+                    text(problem.level.label())
+                        .size(typography::CAPTION)
+                        .color(problem.level.color())
+                        .width(Length::Fixed(60.0)),
+                    text(problem.source.as_str())
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_SECONDARY)
+                        .width(Length::Fixed(140.0)),
+                    text(problem.message.as_str())
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_PRIMARY),
+                ]
+                .spacing(spacing::SM),
+            );
+        }
+        if self.notifications.problem_count() == 0 {
+            rows = rows.push(
+                text("No problems in this session")
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_MUTED),
+            );
+        }
+
+        let header = row![
+            text("Problems").size(typography::BODY).color(colors::GOLD),
+            space::horizontal(),
+            button(text("Copy").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press(Message::CopyProblems),
+            button(text("Clear").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press(Message::ClearProblems),
+            button(text("Hide").size(typography::CAPTION))
+                .style(theme::button_frameless)
+                .on_press(Message::ToggleProblems),
+        ]
+        .spacing(spacing::SM)
+        .align_y(iced::Alignment::Center);
+
+        container(
+            column![
+                header,
+                scrollable(rows)
+                    .height(Length::Fixed(160.0))
+                    .width(Length::Fill)
+                    .style(theme::scrollable_style),
+            ]
+            .spacing(spacing::XS),
+        )
+        .padding(spacing::SM)
+        .width(Length::Fill)
+        .style(theme::container_inset)
+        .into()
+    }
+
     /// Render the decoded JSON panel (right side)
     pub(crate) fn render_decoded_panel(&self) -> Element<'_, Message> {
-        let content = if let Some(idx) = self.selected_aura_index {
+        let content: Element<'_, Message> = if let Some(idx) = self.selected_aura_index {
             if let Some(entry) = self.parsed_auras.get(idx) {
                 if let Some(aura) = &entry.aura {
-                    let json = serde_json::to_string_pretty(&aura.data)
-                        .unwrap_or_else(|_| "Failed to serialize".to_string());
-                    text(json).size(typography::CAPTION)
+                    if self.raw_editor.index == Some(idx) {
+                        self.render_raw_editor()
+                    } else {
+                        let json = serde_json::to_string_pretty(&aura.data)
+                            .unwrap_or_else(|_| "Failed to serialize".to_string());
+                        let editor = if self.property_editor.index == Some(idx) {
+                            self.render_property_editor()
+                        } else {
+                            let small_button = |label: &'static str, message: Message| {
+                                button(text(label).size(typography::CAPTION))
+                                    .style(theme::button_secondary)
+                                    .on_press(message)
+                            };
+                            column![
+                                row![
+                                    small_button(
+                                        "Edit Properties",
+                                        Message::EditAuraProperties(idx)
+                                    ),
+                                    small_button("Edit Raw", Message::EditRawData(idx)),
+                                ]
+                                .spacing(spacing::SM),
+                                row![
+                                    small_button(
+                                        "Copy as JSON",
+                                        Message::CopyDecodedData(AuraTextFormat::Json)
+                                    ),
+                                    small_button(
+                                        "Copy as Lua table",
+                                        Message::CopyDecodedData(AuraTextFormat::Lua)
+                                    ),
+                                ]
+                                .spacing(spacing::SM),
+                            ]
+                            .spacing(spacing::XS)
+                            .into()
+                        };
+                        column![editor, text(json).size(typography::CAPTION)]
+                            .spacing(spacing::SM)
+                            .into()
+                    }
                 } else {
                     text("No aura data")
                         .size(typography::BODY)
                         .color(colors::TEXT_MUTED)
+                        .into()
                 }
             } else {
                 text("Invalid selection")
                     .size(typography::BODY)
                     .color(colors::TEXT_MUTED)
+                    .into()
             }
         } else {
             text("Select an aura to view decoded data")
                 .size(typography::BODY)
                 .color(colors::TEXT_MUTED)
+                .into()
         };
 
         let header = text("Decoded Data")
@@ -134,4 +335,120 @@ impl WeakAuraImporter {
             .style(theme::container_panel)
             .into()
     }
+
+    /// Render the property editor of the aura shown in the decoded panel
+    fn render_property_editor(&self) -> Element<'_, Message> {
+        let editor = &self.property_editor;
+        let field = |label: &'static str,
+                     placeholder: &'static str,
+                     value: &str,
+                     property: AuraProperty| {
+            column![
+                text(label)
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_SECONDARY),
+                text_input(placeholder, value)
+                    .on_input(move |value| Message::AuraPropertyChanged(property, value))
+                    .on_submit(Message::ApplyAuraProperties)
+                    .style(theme::text_input_style)
+                    .size(typography::CAPTION),
+            ]
+            .spacing(spacing::MICRO)
+        };
+
+        let mut form = column![
+            field("ID", "Aura ID", &editor.id, AuraProperty::Id),
+            row![
+                field("X offset", "0", &editor.x_offset, AuraProperty::XOffset),
+                field("Y offset", "0", &editor.y_offset, AuraProperty::YOffset),
+            ]
+            .spacing(spacing::SM),
+            field(
+                "Load for classes",
+                "Any, or e.g. WARRIOR, PALADIN",
+                &editor.classes,
+                AuraProperty::Classes,
+            ),
+            field(
+                "Load for specializations",
+                "Any, or spec IDs, e.g. 71, 73",
+                &editor.specs,
+                AuraProperty::Specs,
+            ),
+            field("Description", "", &editor.desc, AuraProperty::Desc),
+        ]
+        .spacing(spacing::XS);
+        if let Some(error) = &editor.error {
+            form = form.push(
+                text(error.as_str())
+                    .size(typography::CAPTION)
+                    .color(colors::ERROR),
+            );
+        }
+        form = form.push(
+            row![
+                button(text("Apply").size(typography::CAPTION))
+                    .style(theme::button_primary)
+                    .on_press(Message::ApplyAuraProperties),
+                button(text("Cancel").size(typography::CAPTION))
+                    .style(theme::button_frameless)
+                    .on_press(Message::CloseAuraProperties),
+            ]
+            .spacing(spacing::SM),
+        );
+
+        container(form)
+            .padding(spacing::SM)
+            .width(Length::Fill)
+            .style(theme::container_inset)
+            .into()
+    }
+
+    /// Render the Lua/JSON editor of the aura shown in the decoded panel
+    fn render_raw_editor(&self) -> Element<'_, Message> {
+        let editor = &self.raw_editor;
+        let format_button = |label: &'static str, format: AuraTextFormat| {
+            let style = if editor.format == format {
+                theme::button_primary
+            } else {
+                theme::button_secondary
+            };
+            button(text(label).size(typography::CAPTION))
+                .style(style)
+                .on_press(Message::RawDataFormatChanged(format))
+        };
+
+        let mut content = column![
+            row![
+                format_button("Lua", AuraTextFormat::Lua),
+                format_button("JSON", AuraTextFormat::Json),
+            ]
+            .spacing(spacing::XS),
+            text_editor(&editor.content)
+                .on_action(Message::RawDataAction)
+                .size(typography::CAPTION)
+                .height(Length::Fixed(420.0)),
+        ]
+        .spacing(spacing::XS);
+        if let Some(error) = &editor.error {
+            content = content.push(
+                text(error.as_str())
+                    .size(typography::CAPTION)
+                    .color(colors::ERROR),
+            );
+        }
+        content
+            .push(
+                row![
+                    button(text("Apply").size(typography::CAPTION))
+                        .style(theme::button_primary)
+                        .on_press(Message::ApplyRawData),
+                    button(text("Cancel").size(typography::CAPTION))
+                        .style(theme::button_frameless)
+                        .on_press(Message::CloseRawData),
+                ]
+                .spacing(spacing::SM),
+            )
+            .into()
+    }
 }
@@ -0,0 +1,98 @@
+//! Find-references panel: where a text or spell ID appears in stored auras.
+
+use iced::widget::{button, column, container, row, scrollable, space, text, text_input, Column};
+use iced::{Alignment, Element, Length};
+
+use crate::theme::{self, colors, spacing, typography};
+
+use super::super::{Message, WeakAuraImporter};
+
+impl WeakAuraImporter {
+    /// Render the find-references panel: a search field and the matching
+    /// fields of the selected SavedVariables file
+    pub(crate) fn render_references_panel(&self) -> Element<'_, Message> {
+        let can_search = !self.references.query.trim().is_empty()
+            && !self.references.is_searching
+            && self.saved_vars.selected_path.is_some();
+        let header = row![
+            text("Find References")
+                .size(typography::BODY)
+                .color(colors::GOLD),
+            text_input("Text or spell ID, e.g. 375087", &self.references.query)
+                .on_input(Message::ReferenceQueryChanged)
+                .on_submit(Message::SearchReferences)
+                .style(theme::text_input_style)
+                .size(typography::CAPTION)
+                .width(Length::Fixed(240.0)),
+            button(text("Search").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press_maybe(can_search.then_some(Message::SearchReferences)),
+            space::horizontal(),
+            button(text("Hide").size(typography::CAPTION))
+                .style(theme::button_frameless)
+                .on_press(Message::ToggleReferences),
+        ]
+        .spacing(spacing::SM)
+        .align_y(Alignment::Center);
+
+        let mut rows = Column::new().spacing(spacing::MICRO);
+        match &self.references.results {
+            _ if self.references.is_searching => {
+                rows = rows.push(
+                    text("Searching...")
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_MUTED),
+                );
+            }
+            None => {}
+            Some(results) if results.is_empty() => {
+                rows = rows.push(
+                    text("No references found")
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_MUTED),
+                );
+            }
+            Some(results) => {
+                rows = rows.push(
+                    text(format!("{} reference(s)", results.len()))
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_SECONDARY),
+                );
+                for reference in results {
+                    rows = rows.push(
+                        row![
+                            text(reference.aura_id.as_str())
+                                .size(typography::CAPTION)
+                                .color(colors::TEXT_PRIMARY)
+                                .width(Length::Fixed(200.0)),
+                            text(reference.path.as_str())
+                                .size(typography::CAPTION)
+                                .color(colors::TEXT_SECONDARY)
+                                .width(Length::FillPortion(2)),
+                            text(reference.value.as_str())
+                                .size(typography::CAPTION)
+                                .color(colors::TEXT_MUTED)
+                                .width(Length::FillPortion(1)),
+                        ]
+                        .spacing(spacing::SM),
+                    );
+                }
+            }
+        }
+
+        container(
+            column![
+                header,
+                scrollable(rows)
+                    .height(Length::Fixed(160.0))
+                    .width(Length::Fill)
+                    .style(theme::scrollable_style),
+            ]
+            .spacing(spacing::XS),
+        )
+        .padding(spacing::SM)
+        .width(Length::Fill)
+        .style(theme::container_inset)
+        .into()
+    }
+}
@@ -0,0 +1,173 @@
+//! Second window: another SavedVariables file next to the main one.
+
+use iced::widget::{button, checkbox, column, container, row, scrollable, space, text, Column};
+use iced::{Alignment, Element, Length};
+
+use crate::saved_variables::{AuraTreeNode, CheckState};
+use crate::theme::{self, colors, spacing, typography};
+
+use super::super::{Message, WeakAuraImporter};
+
+impl WeakAuraImporter {
+    /// Render the second window: the discovered files, and the aura tree of
+    /// the chosen one with checkboxes for copying
+    pub(crate) fn render_side_window(&self) -> Element<'_, Message> {
+        let header = container(
+            row![
+                text("Second Window")
+                    .size(typography::TITLE)
+                    .color(colors::GOLD),
+                space::horizontal(),
+                button(text("Open file...").size(typography::CAPTION))
+                    .style(theme::button_secondary)
+                    .on_press(Message::LoadFromSavedVariables),
+            ]
+            .padding(spacing::MD)
+            .align_y(Alignment::Center),
+        )
+        .width(Length::Fill)
+        .style(theme::container_toolbar);
+
+        let mut files_col = Column::new().spacing(spacing::XS);
+        for sv_info in &self.saved_vars.discovered_files {
+            let is_selected = self.side_window.path.as_ref() == Some(&sv_info.path);
+            let label_color = if is_selected {
+                colors::BG_VOID
+            } else {
+                colors::TEXT_SECONDARY
+            };
+            files_col = files_col.push(
+                button(
+                    text(format!("{} ({})", sv_info.account, sv_info.pretty_flavor()))
+                        .size(typography::BODY)
+                        .color(label_color),
+                )
+                .width(Length::Fill)
+                .style(if is_selected {
+                    theme::button_primary
+                } else {
+                    theme::button_frameless
+                })
+                .on_press(Message::SideWindowFileSelected(sv_info.path.clone())),
+            );
+        }
+        // A file picked with "Open file..." outside the discovered installations
+        if let Some(path) = self.side_window.path.as_ref().filter(|path| {
+            !self
+                .saved_vars
+                .discovered_files
+                .iter()
+                .any(|info| &info.path == *path)
+        }) {
+            files_col = files_col.push(
+                button(
+                    text(path.display().to_string())
+                        .size(typography::BODY)
+                        .color(colors::BG_VOID),
+                )
+                .width(Length::Fill)
+                .style(theme::button_primary)
+                .on_press(Message::SideWindowFileSelected(path.clone())),
+            );
+        } else if self.saved_vars.discovered_files.is_empty() {
+            files_col = files_col.push(
+                text("No SavedVariables found")
+                    .size(typography::BODY)
+                    .color(colors::TEXT_MUTED),
+            );
+        }
+        let files = container(
+            scrollable(files_col)
+                .height(Length::Fixed(140.0))
+                .style(theme::scrollable_style),
+        )
+        .style(theme::container_inset)
+        .padding(spacing::SM);
+
+        let tree: Element<Message> = if self.side_window.is_loading {
+            text("Loading...")
+                .size(typography::BODY)
+                .color(colors::TEXT_SECONDARY)
+                .into()
+        } else if let Some(parsed) = &self.side_window.tree {
+            let mut tree_col = Column::new().spacing(2).width(Length::Fill);
+            for node in self.sidebar.tree_sort.sorted(&parsed.tree) {
+                tree_col = self.render_side_tree_node(tree_col, node, 0);
+            }
+            scrollable(tree_col)
+                .height(Length::Fill)
+                .style(theme::scrollable_style)
+                .into()
+        } else {
+            text("Choose a SavedVariables file, or open any WeakAuras.lua, to copy auras from")
+                .size(typography::BODY)
+                .color(colors::TEXT_MUTED)
+                .into()
+        };
+
+        let can_copy = !self.side_window.selected.is_empty() && !self.side_window.is_copying;
+        let copy_btn = button(text("Send to import list").size(typography::BODY))
+            .style(theme::button_primary)
+            .on_press_maybe(can_copy.then_some(Message::CopySideAuras));
+
+        column![
+            header,
+            column![
+                files,
+                container(tree)
+                    .style(theme::container_inset)
+                    .padding(spacing::SM)
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+                row![
+                    text(format!("{} selected", self.side_window.selected.len()))
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_MUTED),
+                    space::horizontal(),
+                    copy_btn,
+                ]
+                .align_y(Alignment::Center),
+            ]
+            .spacing(spacing::SM)
+            .padding(spacing::MD)
+            .height(Length::Fill),
+        ]
+        .into()
+    }
+
+    /// Render a node of the second window's tree; groups are always expanded
+    fn render_side_tree_node<'a>(
+        &self,
+        mut col: Column<'a, Message>,
+        node: &'a AuraTreeNode,
+        depth: usize,
+    ) -> Column<'a, Message> {
+        let state = node.check_state(&self.side_window.selected);
+        let node_id = node.id.clone();
+        let mut node_checkbox = checkbox(state == CheckState::Checked)
+            .on_toggle(move |checked| Message::SetSideAuraChecked(node_id.clone(), checked));
+        if state == CheckState::Partial {
+            node_checkbox = node_checkbox.style(theme::checkbox_partial);
+        }
+
+        let label = if node.is_group {
+            text(format!("{} ({})", node.id, node.total_count() - 1))
+                .size(typography::BODY)
+                .color(colors::GOLD)
+        } else {
+            text(&node.id)
+                .size(typography::BODY)
+                .color(colors::TEXT_SECONDARY)
+        };
+        col = col.push(
+            row![node_checkbox, label]
+                .spacing(spacing::XS)
+                .padding(iced::Padding::default().left(depth as f32 * 12.0)),
+        );
+
+        for child in self.sidebar.tree_sort.sorted(&node.children) {
+            col = self.render_side_tree_node(col, child, depth + 1);
+        }
+        col
+    }
+}
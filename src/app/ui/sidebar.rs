@@ -1,11 +1,15 @@
 //! Sidebar rendering: existing aura tree.
 
-use iced::widget::{button, checkbox, column, container, row, scrollable, space, text, Column};
+use iced::widget::{
+    button, checkbox, column, container, mouse_area, pick_list, row, scrollable, space, text,
+    Column,
+};
 use iced::{Element, Length};
 
-use crate::saved_variables::AuraTreeNode;
+use crate::saved_variables::{AuraTreeNode, AuraTreeSort, CheckState, MAX_SUPPORTED_DB_VERSION};
 use crate::theme::{self, colors, spacing, typography};
 
+use super::super::state::ContextMenuTarget;
 use super::super::{Message, WeakAuraImporter};
 
 impl WeakAuraImporter {
@@ -29,6 +33,20 @@ impl WeakAuraImporter {
 
         content = content.push(header_row);
 
+        if self.saved_vars.writes_blocked() {
+            content = content.push(self.render_db_version_warning());
+        }
+        if let Some(candidate) = &self.saved_vars.restored_from {
+            content = content.push(
+                text(format!("Restored from {}", candidate.describe()))
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_MUTED),
+            );
+        }
+        if let Some(reason) = &self.saved_vars.read_only {
+            content = content.push(self.render_read_only_warning(reason));
+        }
+
         // Existing auras tree
         if !self.saved_vars.auras_tree.is_empty() && !self.tasks.is_scanning {
             // All controls in a single row
@@ -48,8 +66,8 @@ impl WeakAuraImporter {
             ]
             .spacing(spacing::XS);
 
-            if !self.removal.selected_ids.is_empty() {
-                let count = self.removal.selected_ids.len();
+            if !self.tree_selection.selected.is_empty() {
+                let count = self.tree_selection.selected.len();
                 controls_row = controls_row.push(
                     button(text(format!("Remove ({})", count)).size(typography::CAPTION))
                         .style(theme::button_danger)
@@ -58,6 +76,36 @@ impl WeakAuraImporter {
             }
 
             content = content.push(controls_row);
+            content = content.push(
+                row![
+                    text("Sort:")
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_SECONDARY),
+                    pick_list(
+                        AuraTreeSort::ALL,
+                        Some(self.sidebar.tree_sort),
+                        Message::SetTreeSort
+                    )
+                    .text_size(typography::CAPTION)
+                    .width(Length::Fixed(120.0)),
+                    space::horizontal(),
+                    button(text("Export CSV...").size(typography::CAPTION))
+                        .style(theme::button_frameless)
+                        .on_press(Message::ExportFieldsCsv),
+                    button(text("Apply CSV...").size(typography::CAPTION))
+                        .style(theme::button_frameless)
+                        .on_press(Message::ApplyFieldsCsv),
+                    button(text("Scripts...").size(typography::CAPTION))
+                        .style(theme::button_frameless)
+                        .on_press(Message::ToggleScripts),
+                    button(text("Compare files...").size(typography::CAPTION))
+                        .style(theme::button_frameless)
+                        .on_press(Message::CompareFiles),
+                ]
+                .spacing(spacing::SM)
+                .align_y(iced::Alignment::Center),
+            );
+            content = content.push(self.render_annotation_filter());
 
             // Scrollable aura tree
             let tree_content = self.render_aura_tree();
@@ -79,6 +127,34 @@ impl WeakAuraImporter {
                     .size(typography::BODY)
                     .color(colors::TEXT_MUTED),
             );
+        } else if self.saved_vars.load_failed {
+            let mut damaged = column![
+                text(
+                    "This file could not be read. WoW may have left it truncated \
+                     after a crash."
+                )
+                .size(typography::CAPTION)
+                .color(colors::WARNING),
+                button(text("Repair file").size(typography::CAPTION))
+                    .style(theme::button_secondary)
+                    .on_press(Message::RepairSavedVariables),
+            ]
+            .spacing(spacing::XS);
+            if let Some(candidate) = self.saved_vars.fallbacks.first() {
+                damaged = damaged.push(
+                    button(
+                        text(format!("Restore {}", candidate.describe())).size(typography::CAPTION),
+                    )
+                    .style(theme::button_secondary)
+                    .on_press(Message::RestoreFallback),
+                );
+            }
+            content = content.push(
+                container(damaged)
+                    .padding(spacing::SM)
+                    .style(theme::container_warning)
+                    .width(Length::Fill),
+            );
         } else {
             content = content.push(
                 text("No auras found")
@@ -89,21 +165,34 @@ impl WeakAuraImporter {
 
         // Import result
         if let Some(result) = &self.status.last_import_result {
+            let mut summary = column![
+                row![
+                    text("Last import:")
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_PRIMARY),
+                    space::horizontal(),
+                    button(text("Details").size(typography::CAPTION))
+                        .style(theme::button_frameless)
+                        .on_press(Message::ShowImportReport),
+                ]
+                .align_y(iced::Alignment::Center),
+                text(result.summary())
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_SECONDARY),
+            ]
+            .spacing(spacing::XS);
+            if let Some(comparison) = &result.comparison {
+                summary = summary.push(
+                    text(comparison.summary())
+                        .size(typography::CAPTION)
+                        .color(colors::TEXT_MUTED),
+                );
+            }
             content = content.push(
-                container(
-                    column![
-                        text("Last import:")
-                            .size(typography::CAPTION)
-                            .color(colors::TEXT_PRIMARY),
-                        text(result.summary())
-                            .size(typography::CAPTION)
-                            .color(colors::TEXT_SECONDARY),
-                    ]
-                    .spacing(spacing::XS),
-                )
-                .padding(spacing::SM)
-                .style(theme::container_surface)
-                .width(Length::Fill),
+                container(summary)
+                    .padding(spacing::SM)
+                    .style(theme::container_surface)
+                    .width(Length::Fill),
             );
         }
 
@@ -114,11 +203,56 @@ impl WeakAuraImporter {
             .into()
     }
 
+    /// Warning for files from a WeakAuras release newer than the writer supports
+    fn render_db_version_warning(&self) -> Element<'_, Message> {
+        let version = self.saved_vars.db_version.unwrap_or_default();
+        container(
+            column![
+                text(format!(
+                    "This file has dbVersion {}, newer than the supported {}. \
+                     Writing it may corrupt data added by newer WeakAuras releases.",
+                    version, MAX_SUPPORTED_DB_VERSION
+                ))
+                .size(typography::CAPTION)
+                .color(colors::WARNING),
+                button(text("Write anyway").size(typography::CAPTION))
+                    .style(theme::button_secondary)
+                    .on_press(Message::AllowNewerDbVersion),
+            ]
+            .spacing(spacing::XS),
+        )
+        .padding(spacing::SM)
+        .style(theme::container_warning)
+        .width(Length::Fill)
+        .into()
+    }
+
+    /// Badge for a selected file that cannot be written
+    fn render_read_only_warning<'a>(&self, reason: &'a str) -> Element<'a, Message> {
+        container(
+            column![
+                text("Read-only — imports will fail")
+                    .size(typography::BODY)
+                    .color(colors::WARNING),
+                text(reason)
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_SECONDARY),
+            ]
+            .spacing(spacing::XS),
+        )
+        .padding(spacing::SM)
+        .style(theme::container_warning)
+        .width(Length::Fill)
+        .into()
+    }
+
     fn render_aura_tree(&self) -> Column<'_, Message> {
         let mut tree_col = Column::new().spacing(2).width(Length::Fill);
 
-        for node in &self.saved_vars.auras_tree {
-            tree_col = self.render_aura_tree_node(tree_col, node, 0);
+        for node in self.sidebar.tree_sort.sorted(&self.saved_vars.auras_tree) {
+            if self.tree_node_matches_filter(node) {
+                tree_col = self.render_aura_tree_node(tree_col, node, 0);
+            }
         }
 
         tree_col
@@ -132,12 +266,14 @@ impl WeakAuraImporter {
     ) -> Column<'a, Message> {
         let indent = depth as u16 * 12;
 
-        let is_selected = self.removal.selected_ids.contains(&node.id);
-
-        // Checkbox for removal selection
+        // Tri-state checkbox for removal selection
+        let state = node.check_state(&self.tree_selection.selected);
         let node_id = node.id.clone();
-        let checkbox_btn = checkbox(is_selected)
-            .on_toggle(move |_| Message::ToggleAuraForRemoval(node_id.clone()));
+        let mut checkbox_btn = checkbox(state == CheckState::Checked)
+            .on_toggle(move |checked| Message::SetAuraRemovalChecked(node_id.clone(), checked));
+        if state == CheckState::Partial {
+            checkbox_btn = checkbox_btn.style(theme::checkbox_partial);
+        }
 
         let mut node_row = row![]
             .spacing(spacing::XS)
@@ -161,6 +297,8 @@ impl WeakAuraImporter {
                     .color(colors::TEXT_MUTED)
                     .size(typography::CAPTION),
             );
+            node_row = node_row.push(self.render_annotation_badges(node.uid.as_deref(), &node.id));
+            node_row = node_row.push(self.render_compare_button(&node.id));
         } else {
             node_row = node_row.push(space::horizontal().width(Length::Fixed(18.0)));
             node_row = node_row.push(
@@ -168,17 +306,47 @@ impl WeakAuraImporter {
                     .size(typography::BODY)
                     .color(colors::TEXT_SECONDARY),
             );
+            node_row = node_row.push(self.render_annotation_badges(node.uid.as_deref(), &node.id));
+            node_row = node_row.push(self.render_compare_button(&node.id));
         }
 
-        col = col.push(node_row);
+        // Clicking the row checks it (shift checks a range), right-clicking
+        // opens its context menu below it
+        let target = ContextMenuTarget::TreeNode(node.id.clone());
+        col = col.push(
+            mouse_area(node_row)
+                .on_press(Message::TreeNodeClicked(node.id.clone()))
+                .on_right_press(Message::OpenContextMenu(target.clone())),
+        );
+        if self.context_menu.target == Some(target) {
+            col = col.push(self.render_tree_context_menu(node, indent as f32 + 24.0));
+        }
 
-        // Render children if expanded
-        if node.is_group && self.sidebar.expanded_groups.contains(&node.id) {
-            for child in &node.children {
-                col = self.render_aura_tree_node(col, child, depth + 1);
+        // Render children if expanded (matches are always revealed while filtering)
+        let filtering = !self.annotations.filter.trim().is_empty();
+        if node.is_group && (filtering || self.sidebar.expanded_groups.contains(&node.id)) {
+            for child in self.sidebar.tree_sort.sorted(&node.children) {
+                if self.tree_node_matches_filter(child) {
+                    col = self.render_aura_tree_node(col, child, depth + 1);
+                }
             }
         }
 
         col
     }
+
+    /// Button opening the backup comparison for an aura (empty without a backup)
+    fn render_compare_button<'a>(&self, id: &str) -> Element<'a, Message> {
+        if !self.saved_vars.has_backup {
+            return space::horizontal().width(Length::Fixed(0.0)).into();
+        }
+        button(
+            text("⇄")
+                .size(typography::CAPTION)
+                .color(colors::TEXT_MUTED),
+        )
+        .style(theme::button_frameless)
+        .on_press(Message::CompareWithBackup(id.to_string()))
+        .into()
+    }
 }
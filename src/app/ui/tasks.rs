@@ -0,0 +1,121 @@
+//! Tasks panel: the running background jobs with their progress and a cancel
+//! button each, docked above the status bar or in its own window.
+
+use iced::widget::{button, column, container, progress_bar, row, scrollable, space, text, Column};
+use iced::{Alignment, Border, Element, Length};
+
+use crate::theme::{self, colors, spacing, typography};
+
+use super::super::state::RunningTask;
+use super::super::{Message, WeakAuraImporter};
+
+impl WeakAuraImporter {
+    /// Render the Tasks panel docked in the main window
+    pub(crate) fn render_tasks_panel(&self) -> Element<'_, Message> {
+        let header = row![
+            text("Tasks").size(typography::BODY).color(colors::GOLD),
+            space::horizontal(),
+            button(text("Detach").size(typography::CAPTION))
+                .style(theme::button_secondary)
+                .on_press(Message::DetachTasks),
+            button(text("Hide").size(typography::CAPTION))
+                .style(theme::button_frameless)
+                .on_press(Message::ToggleTasks),
+        ]
+        .spacing(spacing::SM)
+        .align_y(Alignment::Center);
+
+        container(
+            column![
+                header,
+                scrollable(self.render_task_rows())
+                    .height(Length::Fixed(120.0))
+                    .width(Length::Fill)
+                    .style(theme::scrollable_style),
+            ]
+            .spacing(spacing::XS),
+        )
+        .padding(spacing::SM)
+        .width(Length::Fill)
+        .style(theme::container_inset)
+        .into()
+    }
+
+    /// Render the Tasks panel's own window
+    pub(crate) fn render_tasks_window(&self) -> Element<'_, Message> {
+        let header = container(
+            row![
+                text("Tasks").size(typography::TITLE).color(colors::GOLD),
+                space::horizontal(),
+                button(text("Attach").size(typography::CAPTION))
+                    .style(theme::button_secondary)
+                    .on_press(Message::AttachTasks),
+            ]
+            .padding(spacing::MD)
+            .align_y(Alignment::Center),
+        )
+        .width(Length::Fill)
+        .style(theme::container_toolbar);
+
+        column![
+            header,
+            scrollable(container(self.render_task_rows()).padding(spacing::MD))
+                .height(Length::Fill)
+                .style(theme::scrollable_style),
+        ]
+        .into()
+    }
+
+    /// One row per running job, or a note that nothing runs
+    fn render_task_rows(&self) -> Column<'_, Message> {
+        let running = self.running_tasks();
+        let mut rows = Column::new().spacing(spacing::SM);
+        if running.is_empty() {
+            return rows.push(
+                text("No background tasks running")
+                    .size(typography::CAPTION)
+                    .color(colors::TEXT_MUTED),
+            );
+        }
+        for task in running {
+            rows = rows.push(render_task_row(task));
+        }
+        rows
+    }
+}
+
+fn render_task_row<'a>(task: RunningTask) -> Element<'a, Message> {
+    let title = row![
+        text(task.kind.label())
+            .size(typography::CAPTION)
+            .color(colors::TEXT_PRIMARY),
+        space::horizontal(),
+        button(text("Cancel").size(typography::CAPTION))
+            .style(theme::button_secondary)
+            .on_press(Message::CancelTask(task.kind)),
+    ]
+    .spacing(spacing::SM)
+    .align_y(Alignment::Center);
+
+    let mut rows = column![title].spacing(spacing::XS);
+    // Jobs without a measure of their progress get no bar
+    if let Some(progress) = task.progress {
+        rows = rows.push(
+            container(
+                progress_bar(0.0..=1.0, progress).style(|_theme| progress_bar::Style {
+                    background: colors::BG_SURFACE.into(),
+                    bar: colors::GOLD.into(),
+                    border: Border::default().rounded(4.0),
+                }),
+            )
+            .height(Length::Fixed(8.0))
+            .width(Length::Fill),
+        );
+    }
+    rows.push(
+        text(task.message)
+            .size(typography::CAPTION)
+            .color(colors::TEXT_SECONDARY),
+    )
+    .into()
+}
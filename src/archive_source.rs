@@ -0,0 +1,156 @@
+//! ZIP archives as a source of import strings.
+//!
+//! Aura packs are often shared as `.zip` files. [`ArchiveReader`] reads the
+//! text files inside one in memory, one entry at a time, so callers can
+//! report progress between entries. Which entries count follows the same
+//! [`ScanOptions`] as a folder scan: their extensions and the size limit,
+//! which applies to the uncompressed size.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use zip::ZipArchive;
+
+use crate::error::{Result, WeakAuraError};
+use crate::scan::ScanOptions;
+
+/// Whether `path` looks like a ZIP archive, by its extension
+pub fn is_archive(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// A text entry of an archive, or why an entry was skipped
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArchiveEntry {
+    Text {
+        /// Path inside the archive, with `/` separators
+        name: String,
+        content: String,
+    },
+    Skipped {
+        name: String,
+        reason: String,
+    },
+}
+
+/// Reads the text entries of a ZIP archive; iterate it for the entries
+pub struct ArchiveReader {
+    path: PathBuf,
+    archive: ZipArchive<BufReader<File>>,
+    options: ScanOptions,
+    next: usize,
+    total_bytes: u64,
+    done_bytes: u64,
+}
+
+impl ArchiveReader {
+    /// Open the archive at `path`, taking entries as `options` allows
+    pub fn open(path: &Path, options: &ScanOptions) -> Result<Self> {
+        let file = File::open(path)?;
+        let mut archive = ZipArchive::new(BufReader::new(file)).map_err(|e| {
+            WeakAuraError::DeserializationError(format!("{}: {}", path.display(), e))
+        })?;
+        let mut total_bytes = 0;
+        for index in 0..archive.len() {
+            if let Ok(entry) = archive.by_index_raw(index) {
+                total_bytes += entry.size();
+            }
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            archive,
+            options: options.clone(),
+            next: 0,
+            total_bytes,
+            done_bytes: 0,
+        })
+    }
+
+    /// Where the entry `name` came from, for display: the archive path with
+    /// the entry's path appended
+    pub fn source_of(&self, name: &str) -> PathBuf {
+        self.path.join(name)
+    }
+
+    /// Uncompressed bytes of all entries
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// Uncompressed bytes of the entries read or skipped so far
+    pub fn done_bytes(&self) -> u64 {
+        self.done_bytes
+    }
+
+    fn read_entry(&mut self, index: usize) -> Option<ArchiveEntry> {
+        let mut entry = match self.archive.by_index(index) {
+            Ok(entry) => entry,
+            Err(e) => {
+                return Some(ArchiveEntry::Skipped {
+                    name: format!("entry {}", index + 1),
+                    reason: e.to_string(),
+                })
+            }
+        };
+        self.done_bytes += entry.size();
+        let name = entry.name().to_string();
+        if entry.is_dir() || !self.options.has_extension(Path::new(&name)) {
+            return None;
+        }
+        // Paths escaping the archive root are not used even for display
+        if entry.enclosed_name().is_none() {
+            return Some(ArchiveEntry::Skipped {
+                name,
+                reason: "unsafe path".to_string(),
+            });
+        }
+        if let Some(max) = self.options.max_file_size {
+            if entry.size() > max {
+                return Some(ArchiveEntry::Skipped {
+                    name,
+                    reason: "over the size limit".to_string(),
+                });
+            }
+        }
+
+        // The size in the header may lie; never read more than it claims
+        let mut bytes = Vec::new();
+        let limit = entry.size();
+        if let Err(e) = (&mut entry).take(limit).read_to_end(&mut bytes) {
+            return Some(ArchiveEntry::Skipped {
+                name,
+                reason: e.to_string(),
+            });
+        }
+        match String::from_utf8(bytes) {
+            Ok(content) => Some(ArchiveEntry::Text { name, content }),
+            Err(_) => Some(ArchiveEntry::Skipped {
+                name,
+                reason: "not UTF-8 text".to_string(),
+            }),
+        }
+    }
+}
+
+impl Iterator for ArchiveReader {
+    type Item = ArchiveEntry;
+
+    fn next(&mut self) -> Option<ArchiveEntry> {
+        while self.next < self.archive.len() {
+            let index = self.next;
+            self.next += 1;
+            if let Some(entry) = self.read_entry(index) {
+                return Some(entry);
+            }
+        }
+        None
+    }
+}
+
+/// Read every text entry of the archive at `path` at once
+#[allow(dead_code)]
+pub fn read_archive(path: &Path, options: &ScanOptions) -> Result<Vec<ArchiveEntry>> {
+    Ok(ArchiveReader::open(path, options)?.collect())
+}
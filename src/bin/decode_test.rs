@@ -159,16 +159,17 @@ fn print_raw_map(value: &CodecLuaValue, indent: usize) {
 }
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Usage: decode_test <file_or_string>");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let lossy = args.iter().any(|arg| arg == "--lossy");
+    let Some(arg) = args.iter().find(|arg| *arg != "--lossy") else {
+        eprintln!("Usage: decode_test [--lossy] <file_or_string>");
         std::process::exit(1);
-    }
+    };
 
-    let input = if std::path::Path::new(&args[1]).exists() {
-        fs::read_to_string(&args[1]).expect("Failed to read file")
+    let input = if std::path::Path::new(arg).exists() {
+        fs::read_to_string(arg).expect("Failed to read file")
     } else {
-        args[1].clone()
+        arg.clone()
     };
 
     let input = input.trim();
@@ -179,7 +180,22 @@ fn main() {
         print_raw_triggers(&raw, "");
     }
 
-    match WeakAuraDecoder::decode(input) {
+    let decoded = if lossy {
+        WeakAuraDecoder::decode_lossy(input).map(|salvaged| {
+            println!("\n=== Repairs ===");
+            if salvaged.repairs.is_empty() {
+                println!("none");
+            }
+            for repair in &salvaged.repairs {
+                println!("- {}", repair);
+            }
+            salvaged.aura
+        })
+    } else {
+        WeakAuraDecoder::decode(input)
+    };
+
+    match decoded {
         Ok(aura) => {
             println!("\n=== Decoded WeakAura ===");
             println!("ID: {}", aura.id);
@@ -81,6 +81,7 @@ fn apply_replace_all(
             aura_id: conflict.aura_id.clone(),
             action: ConflictAction::ReplaceAll,
             categories_to_update: Default::default(),
+            ..Default::default()
         });
     }
 
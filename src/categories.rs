@@ -1,12 +1,18 @@
 //! WeakAuras update categories
 //!
 //! Based on WeakAuras2 source code, defines categories for selective updates
-//! when importing auras that already exist.
+//! when importing auras that already exist. Users can add their own categories
+//! (named field sets) on top of the built-in ones via [`CategorySet`].
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::error::{Result, WeakAuraError};
 
 /// Update categories matching WeakAuras' "Categories to Update" dialog
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum UpdateCategory {
     /// Aura name/ID
     Name,
@@ -152,6 +158,10 @@ impl CategoryMapper {
         "fontSize",
     ];
 
+    /// Position and size fields kept by the "preserve position" import preference
+    const POSITION_FIELDS: &'static [&'static str] =
+        &["xOffset", "yOffset", "anchorPoint", "width", "height"];
+
     /// Fields that belong to the UserConfig category
     const USER_CONFIG_FIELDS: &'static [&'static str] = &["config"];
 
@@ -180,6 +190,16 @@ impl CategoryMapper {
         Self::INTERNAL_FIELDS.contains(&field)
     }
 
+    /// Check if a field holds the aura's on-screen position or size
+    pub fn is_position_field(field: &str) -> bool {
+        Self::POSITION_FIELDS.contains(&field)
+    }
+
+    /// Position and size fields (see [`CategoryMapper::is_position_field`])
+    pub fn position_fields() -> &'static [&'static str] {
+        Self::POSITION_FIELDS
+    }
+
     /// Get the category for a field name
     pub fn get_category(field: &str) -> UpdateCategory {
         if Self::NAME_FIELDS.contains(&field) {
@@ -228,3 +248,157 @@ impl CategoryMapper {
         }
     }
 }
+
+/// User-defined category: a named set of top-level fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomCategory {
+    /// Name shown in the category grid
+    pub name: String,
+    /// Top-level aura fields covered by this category
+    pub fields: Vec<String>,
+}
+
+impl CustomCategory {
+    /// Parse a definition of the form `"Positioning = xOffset, yOffset, anchorPoint"`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (name, fields) = spec.split_once('=').ok_or_else(|| {
+            WeakAuraError::ConfigError(format!("Expected 'Name = field, ...', got '{}'", spec))
+        })?;
+        let category = Self {
+            name: name.trim().to_string(),
+            fields: fields
+                .split(',')
+                .map(str::trim)
+                .filter(|f| !f.is_empty())
+                .map(String::from)
+                .collect(),
+        };
+        category.validate()?;
+        Ok(category)
+    }
+
+    /// Check that the category has a name and at least one non-internal field
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(WeakAuraError::ConfigError(
+                "Custom category name is empty".to_string(),
+            ));
+        }
+        if UpdateCategory::all()
+            .iter()
+            .any(|c| c.display_name().eq_ignore_ascii_case(self.name.trim()))
+        {
+            return Err(WeakAuraError::ConfigError(format!(
+                "Custom category '{}' shadows a built-in category",
+                self.name
+            )));
+        }
+        if let Some(field) = self
+            .fields
+            .iter()
+            .find(|f| CategoryMapper::is_internal_field(f))
+        {
+            return Err(WeakAuraError::ConfigError(format!(
+                "Custom category '{}' contains internal field '{}'",
+                self.name, field
+            )));
+        }
+        if self.fields.is_empty() {
+            return Err(WeakAuraError::ConfigError(format!(
+                "Custom category '{}' has no fields",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Identifies a built-in or custom category in a selection
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CategoryKey {
+    /// Built-in category
+    Builtin(UpdateCategory),
+    /// Custom category, by name
+    Custom(String),
+}
+
+/// Built-in categories plus user-defined ones, in display order
+#[derive(Debug, Clone, Default)]
+pub struct CategorySet {
+    custom: Vec<CustomCategory>,
+}
+
+impl CategorySet {
+    /// Build a set from custom definitions. Invalid or duplicate definitions are skipped.
+    pub fn new(custom: impl IntoIterator<Item = CustomCategory>) -> Self {
+        let mut set = Self::default();
+        for category in custom {
+            if let Err(e) = category.validate() {
+                warn!(error = %e, "Skipping invalid custom category");
+                continue;
+            }
+            if set.find(&category.name).is_some() {
+                warn!(name = %category.name, "Skipping duplicate custom category");
+                continue;
+            }
+            set.custom.push(category);
+        }
+        set
+    }
+
+    fn find(&self, name: &str) -> Option<&CustomCategory> {
+        self.custom.iter().find(|c| c.name == name)
+    }
+
+    /// All category keys: built-ins first, then custom categories
+    pub fn keys(&self) -> Vec<CategoryKey> {
+        UpdateCategory::all()
+            .into_iter()
+            .map(CategoryKey::Builtin)
+            .chain(
+                self.custom
+                    .iter()
+                    .map(|c| CategoryKey::Custom(c.name.clone())),
+            )
+            .collect()
+    }
+
+    /// Display name for a key
+    pub fn display_name<'a>(&self, key: &'a CategoryKey) -> &'a str {
+        match key {
+            CategoryKey::Builtin(category) => category.display_name(),
+            CategoryKey::Custom(name) => name,
+        }
+    }
+
+    /// Keys enabled by default (built-in defaults; custom categories start off)
+    pub fn defaults(&self) -> HashSet<CategoryKey> {
+        UpdateCategory::defaults()
+            .into_iter()
+            .map(CategoryKey::Builtin)
+            .collect()
+    }
+
+    /// Split a selection into built-in categories and the extra fields of selected
+    /// custom categories. Unknown custom names are ignored.
+    pub fn resolve(
+        &self,
+        selection: &HashSet<CategoryKey>,
+    ) -> (HashSet<UpdateCategory>, BTreeSet<String>) {
+        let mut categories = HashSet::new();
+        let mut fields = BTreeSet::new();
+        for key in selection {
+            match key {
+                CategoryKey::Builtin(category) => {
+                    categories.insert(*category);
+                }
+                CategoryKey::Custom(name) => {
+                    if let Some(custom) = self.find(name) {
+                        fields.extend(custom.fields.iter().cloned());
+                    }
+                }
+            }
+        }
+        (categories, fields)
+    }
+}
@@ -0,0 +1,48 @@
+//! Imports of very large batches in chunks, saving after each.
+//!
+//! Importing thousands of auras at once ends in one enormous save; a crash
+//! during it loses everything. With [`ImportOptions::chunk_size`] set,
+//! [`import_chunked`](crate::saved_variables::SavedVariablesManager::import_chunked)
+//! imports a few hundred auras at a time and saves after each chunk, so
+//! every chunk leaves a backup and a failure keeps the chunks saved before it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::decoder::WeakAura;
+
+/// Chunk size used when chunked saves are turned on without a size
+pub const DEFAULT_CHUNK_SIZE: usize = 200;
+
+/// How an import is written
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportOptions {
+    /// Auras imported per save, counting group children; `None` (or 0)
+    /// imports everything in one save. A group larger than this is a chunk
+    /// of its own, since groups are never split.
+    pub chunk_size: Option<usize>,
+}
+
+impl ImportOptions {
+    /// The batches `auras` are imported in: whole top-level auras, in order,
+    /// with up to [`chunk_size`](Self::chunk_size) auras each
+    pub fn chunks<'a>(&self, auras: &'a [WeakAura]) -> Vec<&'a [WeakAura]> {
+        let Some(chunk_size) = self.chunk_size.filter(|&size| size > 0) else {
+            return vec![auras];
+        };
+        let mut chunks = Vec::new();
+        let (mut start, mut count) = (0, 0);
+        for (index, aura) in auras.iter().enumerate() {
+            let size = 1 + aura.child_data.len();
+            if count > 0 && count + size > chunk_size {
+                chunks.push(&auras[start..index]);
+                (start, count) = (index, 0);
+            }
+            count += size;
+        }
+        if start < auras.len() || chunks.is_empty() {
+            chunks.push(&auras[start..]);
+        }
+        chunks
+    }
+}
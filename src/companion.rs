@@ -0,0 +1,206 @@
+//! Local HTTP endpoint for receiving import strings from a browser extension.
+//!
+//! Enabled with `--listen 127.0.0.1:PORT`; only loopback addresses are
+//! accepted. A `POST /import` with either a plain-text body (one string per
+//! line) or JSON (`{"string": ".."}` or `{"strings": [..]}`) hands the strings
+//! to the GUI, similar to the WeakAuras Companion flow. `GET /status` reports
+//! that the app is running.
+//!
+//! Browsers attach an `Origin` to cross-site requests, so requests from web
+//! pages are refused: only browser extensions and local tools that send no
+//! `Origin` may call the endpoint, and CORS headers name the calling
+//! extension only. Requests are handled one at a time, each with a short read
+//! and write timeout so a stalled client cannot block the endpoint.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::json;
+use tracing::warn;
+
+use crate::error::Result;
+use crate::util::loopback_addrs;
+
+/// Largest accepted request body (import strings for big packs run to a few MB)
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Largest accepted request line and headers
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+/// Read and write timeout of a connection
+const IO_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// `Origin` schemes of browser extensions, the only origins allowed to call
+const EXTENSION_SCHEMES: [&str; 3] = [
+    "chrome-extension://",
+    "moz-extension://",
+    "safari-web-extension://",
+];
+
+/// JSON request body
+#[derive(Deserialize)]
+struct ImportBody {
+    #[serde(default)]
+    string: Option<String>,
+    #[serde(default)]
+    strings: Vec<String>,
+}
+
+/// Bound companion endpoint
+pub struct CompanionServer {
+    listener: TcpListener,
+}
+
+impl CompanionServer {
+    /// Bind the endpoint. Only loopback addresses are accepted, since the
+    /// endpoint has no authentication.
+    pub fn bind(addr: impl ToSocketAddrs) -> Result<Self> {
+        let addrs = loopback_addrs(addr)?;
+        Ok(Self {
+            listener: TcpListener::bind(&addrs[..])?,
+        })
+    }
+
+    /// Address the endpoint is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serve requests until the process exits, calling `on_received` with the
+    /// text of every accepted import (strings separated by newlines). The
+    /// import is answered once `on_received` returns, so it may block to hold
+    /// back clients while earlier imports are still being handled.
+    pub fn run(self, mut on_received: impl FnMut(String)) {
+        for stream in self.listener.incoming() {
+            let result = stream.and_then(|stream| handle_connection(stream, &mut on_received));
+            if let Err(e) = result {
+                warn!(error = %e, "Companion request failed");
+            }
+        }
+    }
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    on_received: &mut impl FnMut(String),
+) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream.take((MAX_HEAD_BYTES + MAX_BODY_BYTES) as u64));
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    let mut content_type = String::new();
+    let mut origin = None;
+    let mut head_bytes = request_line.len();
+    loop {
+        let mut header = String::new();
+        let read = reader.read_line(&mut header)?;
+        head_bytes += read;
+        if read == 0 || header.trim().is_empty() || head_bytes > MAX_HEAD_BYTES {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse().unwrap_or(0);
+            } else if name.eq_ignore_ascii_case("content-type") {
+                content_type = value.to_ascii_lowercase();
+            } else if name.eq_ignore_ascii_case("origin") {
+                origin = Some(value.to_string());
+            }
+        }
+    }
+
+    let allowed = match &origin {
+        Some(origin) => is_extension_origin(origin),
+        None => true,
+    };
+    let (status, body) = match (method.as_str(), path.as_str()) {
+        _ if head_bytes > MAX_HEAD_BYTES => (
+            "431 Request Header Fields Too Large",
+            json!({ "error": "Headers too large" }).to_string(),
+        ),
+        _ if !allowed => (
+            "403 Forbidden",
+            json!({ "error": "Only browser extensions may call this endpoint" }).to_string(),
+        ),
+        ("OPTIONS", _) => ("204 No Content", String::new()),
+        ("GET", "/status") => (
+            "200 OK",
+            json!({ "app": "weakauras-mass-import", "version": env!("CARGO_PKG_VERSION") })
+                .to_string(),
+        ),
+        ("POST", "/import") if content_length > MAX_BODY_BYTES => (
+            "413 Payload Too Large",
+            json!({ "error": "Body too large" }).to_string(),
+        ),
+        ("POST", "/import") => {
+            let mut raw = vec![0; content_length];
+            reader.read_exact(&mut raw)?;
+            match received_text(&content_type, &String::from_utf8_lossy(&raw)) {
+                Some(text) => {
+                    let count = text.lines().count();
+                    on_received(text);
+                    ("202 Accepted", json!({ "received": count }).to_string())
+                }
+                None => (
+                    "400 Bad Request",
+                    json!({ "error": "No import strings in body" }).to_string(),
+                ),
+            }
+        }
+        _ => ("404 Not Found", json!({ "error": "Not found" }).to_string()),
+    };
+
+    let cors = match origin.filter(|_| allowed) {
+        Some(origin) => format!(
+            "Access-Control-Allow-Origin: {}\r\nVary: Origin\r\n\
+             Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+             Access-Control-Allow-Headers: Content-Type\r\n",
+            origin
+        ),
+        None => String::new(),
+    };
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}\
+         Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        cors,
+        body
+    )?;
+    writer.flush()
+}
+
+/// Whether an `Origin` header names a browser extension rather than a web page
+fn is_extension_origin(origin: &str) -> bool {
+    EXTENSION_SCHEMES
+        .iter()
+        .any(|scheme| origin.len() > scheme.len() && origin.starts_with(scheme))
+}
+
+/// Import strings in a request body, one per line (`None` if there are none)
+fn received_text(content_type: &str, body: &str) -> Option<String> {
+    let strings: Vec<String> = if content_type.starts_with("application/json") {
+        let parsed: ImportBody = serde_json::from_str(body).ok()?;
+        parsed.string.into_iter().chain(parsed.strings).collect()
+    } else {
+        body.lines().map(String::from).collect()
+    };
+    let strings: Vec<&str> = strings
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+    (!strings.is_empty()).then(|| strings.join("\n"))
+}
@@ -0,0 +1,237 @@
+//! WeakAurasCompanion data addon.
+//!
+//! The WeakAuras Companion app writes an addon named `WeakAurasCompanion`
+//! whose `data.lua` lists the latest Wago version of auras by slug. In game,
+//! WeakAuras compares these versions with the `url` of installed auras
+//! (`https://wago.io/<slug>/<version>`) and offers updates from the bundled
+//! import strings. [`CompanionData`] builds the same tables from imported
+//! auras and [`CompanionData::write_addon`] writes the addon, keeping entries
+//! the Companion app or an earlier run wrote for other slugs.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::decoder::{LuaTable, LuaValue, WeakAura};
+use crate::error::{Result, WeakAuraError};
+use crate::fs_util;
+use crate::lua_parser::LuaParser;
+
+/// Folder and TOC name of the data addon, as WeakAuras expects it
+pub const COMPANION_ADDON_NAME: &str = "WeakAurasCompanion";
+
+/// Global the data is assigned to in `data.lua`
+const DATA_GLOBAL: &str = "WeakAurasCompanionData";
+
+/// Interface versions of the addon (retail, Mists Classic, Classic Era)
+const INTERFACE_VERSIONS: &str = "110205, 50500, 11507";
+
+/// `init.lua`: hand the data to WeakAuras once the addon is loaded
+const INIT_LUA: &str = r#"-- file generated automatically
+local loadedFrame = CreateFrame("FRAME")
+loadedFrame:RegisterEvent("ADDON_LOADED")
+loadedFrame:SetScript("OnEvent", function(_, _, addonName)
+  if addonName == "WeakAurasCompanion" then
+    if WeakAuras and WeakAuras.AddCompanionData and WeakAurasCompanionData then
+      local WeakAurasData = WeakAurasCompanionData.WeakAuras
+      if WeakAurasData then
+        WeakAuras.AddCompanionData(WeakAurasData)
+      end
+    end
+    loadedFrame:UnregisterEvent("ADDON_LOADED")
+  end
+end)
+"#;
+
+/// Update info of one Wago aura
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompanionEntry {
+    /// Aura name shown in the update list
+    pub name: String,
+    pub author: String,
+    /// Import string WeakAuras installs the update from
+    pub encoded: String,
+    /// Wago revision, the number at the end of the aura `url`
+    pub wago_version: u64,
+    pub wago_semver: Option<String>,
+    /// Changelog of the version
+    pub version_note: String,
+}
+
+impl CompanionEntry {
+    /// Update info of a root aura with a wago.io `url`, with its slug. Auras
+    /// without an import string to offer are left out.
+    pub fn from_aura(aura: &WeakAura) -> Option<(String, Self)> {
+        if aura.original_string.is_empty() {
+            return None;
+        }
+        let table = aura.data.as_table()?;
+        let (slug, wago_version) = match table.get("url") {
+            Some(LuaValue::String(url)) => wago_slug(url)?,
+            _ => return None,
+        };
+        let string = |key: &str| match table.get(key) {
+            Some(LuaValue::String(s)) => Some(s.clone()),
+            _ => None,
+        };
+        // Wago stores the changelog as `{ text = "...", format = "bbcode" }`
+        let version_note = match table.get("changelog") {
+            Some(LuaValue::String(s)) => s.clone(),
+            Some(value) => match value.as_table().and_then(|t| t.get("text")) {
+                Some(LuaValue::String(s)) => s.clone(),
+                _ => String::new(),
+            },
+            None => String::new(),
+        };
+        Some((
+            slug,
+            Self {
+                name: aura.id.clone(),
+                author: string("author").unwrap_or_default(),
+                encoded: aura.original_string.clone(),
+                wago_version,
+                wago_semver: string("semver"),
+                version_note,
+            },
+        ))
+    }
+
+    fn to_lua_value(&self) -> LuaValue {
+        let mut table = LuaTable::new();
+        let mut set = |key: &str, value: &str| {
+            table.insert(key.to_string(), LuaValue::String(value.to_string()))
+        };
+        set("name", &self.name);
+        set("author", &self.author);
+        set("encoded", &self.encoded);
+        set("wagoVersion", &self.wago_version.to_string());
+        if let Some(semver) = &self.wago_semver {
+            set("wagoSemver", semver);
+        }
+        set("versionNote", &self.version_note);
+        set("source", "Wago");
+        LuaValue::Table(table.into())
+    }
+}
+
+/// Slug and revision of a wago.io aura URL (`https://wago.io/<slug>/<version>`)
+pub fn wago_slug(url: &str) -> Option<(String, u64)> {
+    let (_, path) = url.split_once("wago.io/")?;
+    let mut parts = path.split(&['/', '?', '#'][..]);
+    let slug = parts.next().filter(|s| !s.is_empty())?;
+    let version = parts.next()?.parse().ok()?;
+    Some((slug.to_string(), version))
+}
+
+/// Companion data of a set of auras, by slug
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompanionData {
+    pub slugs: BTreeMap<String, CompanionEntry>,
+}
+
+impl CompanionData {
+    /// Entries of the auras with a wago.io `url`. Of several auras with the
+    /// same slug, the highest version is kept.
+    pub fn from_auras(auras: &[WeakAura]) -> Self {
+        let mut slugs: BTreeMap<String, CompanionEntry> = BTreeMap::new();
+        for (slug, entry) in auras.iter().filter_map(CompanionEntry::from_aura) {
+            let newer = match slugs.get(&slug) {
+                Some(existing) => existing.wago_version < entry.wago_version,
+                None => true,
+            };
+            if newer {
+                slugs.insert(slug, entry);
+            }
+        }
+        Self { slugs }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slugs.is_empty()
+    }
+
+    /// Content of `data.lua`, adding the entries to the data of an existing
+    /// file (`None` to start empty)
+    pub fn to_lua(&self, existing: Option<&str>) -> Result<String> {
+        let mut root = match existing.and_then(|content| content.split_once('=')) {
+            Some((_, table)) => LuaParser::parse_value(table)?,
+            None => empty_table(),
+        };
+        if matches!(&root, LuaValue::Array(array) if array.is_empty()) {
+            root = empty_table();
+        }
+        let weakauras = table_entry(as_table_mut(&mut root)?, "WeakAuras")?;
+        table_entry(weakauras, "stash")?;
+        let slugs = table_entry(weakauras, "slugs")?;
+        for (slug, entry) in &self.slugs {
+            slugs.insert(slug.clone(), entry.to_lua_value());
+        }
+
+        Ok(format!(
+            "-- file generated automatically\n{} = {}\n",
+            DATA_GLOBAL,
+            LuaParser::serialize_sorted(&root, 0)
+        ))
+    }
+
+    /// Write the addon to `<flavor_dir>/Interface/AddOns/WeakAurasCompanion`,
+    /// merging with its existing data. Returns the addon folder.
+    pub fn write_addon(&self, flavor_dir: &Path) -> Result<PathBuf> {
+        let addon_dir = flavor_dir
+            .join("Interface")
+            .join("AddOns")
+            .join(COMPANION_ADDON_NAME);
+        fs::create_dir_all(&addon_dir).map_err(|e| fs_util::map_io_error(&addon_dir, e))?;
+
+        let data_path = addon_dir.join("data.lua");
+        let existing = if data_path.is_file() {
+            Some(fs::read_to_string(&data_path)?)
+        } else {
+            None
+        };
+        let data = self.to_lua(existing.as_deref())?;
+
+        fs_util::write(
+            &addon_dir.join(format!("{}.toc", COMPANION_ADDON_NAME)),
+            toc(),
+        )?;
+        fs_util::write(&data_path, data)?;
+        fs_util::write(&addon_dir.join("init.lua"), INIT_LUA)?;
+        Ok(addon_dir)
+    }
+}
+
+fn toc() -> String {
+    format!(
+        "## Interface: {}\n\
+         ## Title: WeakAuras Companion\n\
+         ## Notes: Update information for WeakAuras\n\
+         ## DefaultState: Enabled\n\
+         ## LoadOnDemand: 0\n\
+         ## OptionalDeps: WeakAuras\n\
+         \n\
+         data.lua\n\
+         init.lua\n",
+        INTERFACE_VERSIONS
+    )
+}
+
+fn empty_table() -> LuaValue {
+    LuaValue::Table(LuaTable::new().into())
+}
+
+fn as_table_mut(value: &mut LuaValue) -> Result<&mut LuaTable> {
+    value
+        .as_table_mut()
+        .ok_or_else(|| WeakAuraError::LuaParseError("Companion data is not a table".to_string()))
+}
+
+/// Table under `key`, created if missing. An empty `{}` parses as an array
+/// and is replaced with a table.
+fn table_entry<'a>(table: &'a mut LuaTable, key: &str) -> Result<&'a mut LuaTable> {
+    let entry = table.entry(key.to_string()).or_insert_with(empty_table);
+    if matches!(entry, LuaValue::Array(array) if array.is_empty()) {
+        *entry = empty_table();
+    }
+    as_table_mut(entry)
+}
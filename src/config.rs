@@ -0,0 +1,351 @@
+//! Persistent user configuration
+//!
+//! Settings are stored as pretty-printed JSON in the platform config directory:
+//! - Windows: `%APPDATA%\weakauras-mass-import\config.json`
+//! - macOS: `~/Library/Application Support/weakauras-mass-import/config.json`
+//! - Linux: `$XDG_CONFIG_HOME/weakauras-mass-import/config.json` (or `~/.config/...`)
+//!
+//! In portable mode (`--portable`, or a `portable.txt` file next to the
+//! executable) the config and everything else kept in the config directory
+//! live in a `data` folder beside the executable instead.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::categories::{CategoryKey, CategorySet, CustomCategory};
+use crate::chunking::ImportOptions;
+use crate::duplicates::DuplicatePolicy;
+use crate::error::{Result, WeakAuraError};
+use crate::pack::DEFAULT_MEDIA_ADDON;
+use crate::saved_variables::ConflictAction;
+use crate::scan::ScanOptions;
+use crate::transforms::TransformStep;
+
+/// Directory name used under the platform config directory
+const APP_DIR_NAME: &str = "weakauras-mass-import";
+
+/// Config file name
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// Marker file next to the executable that turns on portable mode
+pub const PORTABLE_MARKER: &str = "portable.txt";
+
+/// Folder beside the executable holding the data in portable mode
+const PORTABLE_DIR_NAME: &str = "data";
+
+/// Portable mode requested with `--portable`
+static PORTABLE: AtomicBool = AtomicBool::new(false);
+
+/// User configuration persisted between sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// WoW installation roots registered by the user, scanned together
+    pub wow_paths: Vec<PathBuf>,
+    /// User-defined update categories, e.g. `"Positioning = xOffset, yOffset, anchorPoint"`
+    pub custom_categories: Vec<String>,
+    /// Never overwrite position and size of existing auras on import
+    pub preserve_position: bool,
+    /// Keep the user's author-option values (`config`) when replacing auras
+    pub keep_user_config: bool,
+    /// Saved conflict resolution presets
+    pub resolution_presets: Vec<ResolutionPreset>,
+    /// Preset applied automatically when the conflict dialog opens
+    pub auto_preset: Option<String>,
+    /// Built-in transforms run on auras before import, in order
+    pub transforms: Vec<TransformStep>,
+    /// Addon folder pack media is installed into when the pack names none
+    pub media_folder: String,
+    /// Write the WeakAurasCompanion data addon for imported Wago auras, so
+    /// WeakAuras shows their update info in game
+    pub write_companion_data: bool,
+    /// Offline mode: online features only use cached responses
+    pub offline: bool,
+    /// Keep collecting strings copied to the clipboard in the background
+    pub collect_clipboard: bool,
+    /// Which copy is kept when the same aura is loaded more than once
+    pub duplicate_policy: DuplicatePolicy,
+    /// Pasting from the clipboard replaces the input text instead of
+    /// appending to it
+    pub paste_replaces_input: bool,
+    /// Parse pasted text right away and typed text once typing pauses
+    pub auto_parse: bool,
+    /// Which files loading a folder reads
+    pub folder_scan: ScanOptions,
+    /// Leave auras that look like empty placeholders out of bulk selection;
+    /// they can still be checked by hand
+    pub skip_trivial_auras: bool,
+    /// How imports are written, e.g. in chunks saved one at a time
+    pub import_options: ImportOptions,
+}
+
+/// Named conflict resolution: an action plus the categories it updates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolutionPreset {
+    pub name: String,
+    pub action: ConflictAction,
+    /// Built-in category display names or custom category names
+    pub categories: Vec<String>,
+}
+
+impl ResolutionPreset {
+    /// Create a preset from a category selection, in the set's display order
+    pub fn new(
+        name: &str,
+        action: ConflictAction,
+        selection: &HashSet<CategoryKey>,
+        set: &CategorySet,
+    ) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            action,
+            categories: set
+                .keys()
+                .iter()
+                .filter(|key| selection.contains(key))
+                .map(|key| set.display_name(key).to_string())
+                .collect(),
+        }
+    }
+
+    /// Category selection of this preset. Names no longer known to `set` are ignored.
+    pub fn category_keys(&self, set: &CategorySet) -> HashSet<CategoryKey> {
+        set.keys()
+            .into_iter()
+            .filter(|key| self.categories.iter().any(|c| c == set.display_name(key)))
+            .collect()
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            wow_paths: Vec::new(),
+            custom_categories: Vec::new(),
+            preserve_position: false,
+            keep_user_config: true,
+            resolution_presets: Vec::new(),
+            auto_preset: None,
+            transforms: TransformStep::defaults(),
+            media_folder: DEFAULT_MEDIA_ADDON.to_string(),
+            write_companion_data: false,
+            offline: false,
+            collect_clipboard: false,
+            duplicate_policy: DuplicatePolicy::default(),
+            paste_replaces_input: false,
+            auto_parse: false,
+            folder_scan: ScanOptions::default(),
+            skip_trivial_auras: false,
+            import_options: ImportOptions::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Turn portable mode on or off (`--portable`). A `portable.txt` marker
+    /// next to the executable turns it on regardless.
+    pub fn set_portable(portable: bool) {
+        PORTABLE.store(portable, Ordering::Relaxed);
+    }
+
+    /// Data folder beside the executable when running in portable mode
+    pub fn portable_dir() -> Option<PathBuf> {
+        let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+        let portable = PORTABLE.load(Ordering::Relaxed) || exe_dir.join(PORTABLE_MARKER).is_file();
+        portable.then(|| exe_dir.join(PORTABLE_DIR_NAME))
+    }
+
+    /// Config directory for this application: the portable data folder, or
+    /// the platform config directory
+    pub fn config_dir() -> Option<PathBuf> {
+        if let Some(dir) = Self::portable_dir() {
+            return Some(dir);
+        }
+
+        #[cfg(target_os = "windows")]
+        let base = std::env::var_os("APPDATA").map(PathBuf::from);
+
+        #[cfg(target_os = "macos")]
+        let base = std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join("Library/Application Support"));
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+
+        base.map(|dir| dir.join(APP_DIR_NAME))
+    }
+
+    /// Default location of the config file
+    #[allow(dead_code)]
+    pub fn default_path() -> Option<PathBuf> {
+        Self::config_dir().map(|dir| dir.join(CONFIG_FILE_NAME))
+    }
+
+    /// Load the config from the default location, falling back to defaults on any error
+    pub fn load() -> Self {
+        let mut config: Self = load_json(CONFIG_FILE_NAME);
+        TransformStep::add_missing_builtins(&mut config.transforms);
+        config
+    }
+
+    /// Load the config from a specific file (a missing file yields defaults)
+    #[allow(dead_code)]
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let mut config: Self = load_json_from(path)?;
+        TransformStep::add_missing_builtins(&mut config.transforms);
+        Ok(config)
+    }
+
+    /// Save the config to the default location
+    pub fn save(&self) -> Result<()> {
+        save_json(CONFIG_FILE_NAME, self)
+    }
+
+    /// Save the config to a specific file, creating parent directories
+    #[allow(dead_code)]
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        save_json_to(path, self)
+    }
+
+    /// Write the access token of a local endpoint to the file `name` in the
+    /// config directory, where local clients read it from. On Unix only the
+    /// current user may read the file.
+    pub fn write_token(name: &str, token: &str) -> Result<PathBuf> {
+        let dir = Self::config_dir().ok_or_else(|| {
+            WeakAuraError::ConfigError("No config directory available".to_string())
+        })?;
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(name);
+        // Recreated so a file left by an older launch cannot keep wider permissions
+        let _ = fs::remove_file(&path);
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options.open(&path)?.write_all(token.as_bytes())?;
+        Ok(path)
+    }
+
+    /// Token written by [`AppConfig::write_token`], if there is one
+    pub fn read_token(name: &str) -> Option<String> {
+        let path = Self::config_dir()?.join(name);
+        let token = fs::read_to_string(path).ok()?;
+        let token = token.trim();
+        (!token.is_empty()).then(|| token.to_string())
+    }
+
+    /// Register a WoW installation root. Returns `false` if it was already registered.
+    pub fn add_wow_path(&mut self, path: PathBuf) -> bool {
+        if self.wow_paths.contains(&path) {
+            return false;
+        }
+        self.wow_paths.push(path);
+        true
+    }
+
+    /// Unregister a WoW installation root. Returns `false` if it was not registered.
+    pub fn remove_wow_path(&mut self, path: &Path) -> bool {
+        let before = self.wow_paths.len();
+        self.wow_paths.retain(|p| p != path);
+        self.wow_paths.len() != before
+    }
+
+    /// Built-in categories plus the custom categories from this config.
+    /// Definitions that fail to parse are skipped with a warning.
+    pub fn category_set(&self) -> CategorySet {
+        CategorySet::new(self.custom_categories.iter().filter_map(|spec| {
+            CustomCategory::parse(spec)
+                .map_err(|e| warn!(error = %e, "Skipping invalid custom category"))
+                .ok()
+        }))
+    }
+
+    /// Preset with the given name
+    pub fn preset(&self, name: &str) -> Option<&ResolutionPreset> {
+        self.resolution_presets.iter().find(|p| p.name == name)
+    }
+
+    /// Add a preset, replacing one with the same name.
+    /// Returns `false` (and stores nothing) if the name is empty.
+    pub fn save_preset(&mut self, preset: ResolutionPreset) -> bool {
+        if preset.name.is_empty() {
+            return false;
+        }
+        match self
+            .resolution_presets
+            .iter_mut()
+            .find(|p| p.name == preset.name)
+        {
+            Some(existing) => *existing = preset,
+            None => self.resolution_presets.push(preset),
+        }
+        true
+    }
+
+    /// Remove a preset (and clear it as auto preset). Returns `false` if it did not exist.
+    pub fn remove_preset(&mut self, name: &str) -> bool {
+        let before = self.resolution_presets.len();
+        self.resolution_presets.retain(|p| p.name != name);
+        if self.auto_preset.as_deref() == Some(name) {
+            self.auto_preset = None;
+        }
+        self.resolution_presets.len() != before
+    }
+}
+
+/// Load the JSON file `file_name` from the config directory, falling back to
+/// the default on any error. Used by the config and the sidecar stores kept
+/// next to it.
+pub fn load_json<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    let Some(path) = AppConfig::config_dir().map(|dir| dir.join(file_name)) else {
+        return T::default();
+    };
+    load_json_from(&path).unwrap_or_else(|e| {
+        warn!(path = %path.display(), error = %e, "Failed to load settings file, using defaults");
+        T::default()
+    })
+}
+
+/// Load a JSON file (a missing file yields the default)
+pub fn load_json_from<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|e| WeakAuraError::ConfigError(e.to_string()))
+}
+
+/// Save `value` to the JSON file `file_name` in the config directory
+pub fn save_json<T: Serialize>(file_name: &str, value: &T) -> Result<()> {
+    let path = AppConfig::config_dir()
+        .ok_or_else(|| WeakAuraError::ConfigError("No config directory available".to_string()))?
+        .join(file_name);
+    save_json_to(&path, value)
+}
+
+/// Save `value` as pretty-printed JSON, creating parent directories. The file
+/// is written under a temporary name and renamed into place, so a crash
+/// mid-write keeps the previous contents.
+pub fn save_json_to<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| WeakAuraError::ConfigError(e.to_string()))?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
@@ -0,0 +1,209 @@
+//! Crash reports.
+//!
+//! [`install_panic_hook`] writes a report for every panic, on the UI thread or
+//! in a background task, to `<config dir>/crashes/crash-<unix time>.txt`: the
+//! panic message and location, a backtrace, the app version, the OS, and a
+//! short summary of what the app was doing. The home directory is written as
+//! `~` and account folder names are masked, so reports can be shared as is.
+//!
+//! A SavedVariables file being written when the app crashes (see
+//! [`SaveInProgress`]) is recorded separately in `crashes/pending.json` (never
+//! in the report), so the next launch can offer to restore it from its backup.
+//! Crashes outside a save leave the files alone.
+
+use std::backtrace::Backtrace;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppConfig;
+use crate::error::{Result, WeakAuraError};
+
+/// Marker left for the next launch
+const PENDING_FILE_NAME: &str = "pending.json";
+
+/// What the app was doing, kept up to date by the GUI
+static CONTEXT: Mutex<Option<CrashContext>> = Mutex::new(None);
+
+/// SavedVariables files being written right now
+static SAVING: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Default)]
+struct CrashContext {
+    summary: String,
+}
+
+/// A crash of an earlier session that the user has not dealt with yet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingCrash {
+    /// The written crash report
+    pub report: PathBuf,
+    /// SavedVariables file that was being written when the app crashed
+    pub saved_variables: Option<PathBuf>,
+}
+
+/// Marks a SavedVariables file as being written until dropped; a crash in
+/// the meantime records it for recovery
+pub struct SaveInProgress(PathBuf);
+
+impl SaveInProgress {
+    pub fn start(path: &Path) -> Self {
+        if let Ok(mut saving) = SAVING.lock() {
+            saving.push(path.to_path_buf());
+        }
+        Self(path.to_path_buf())
+    }
+}
+
+impl Drop for SaveInProgress {
+    fn drop(&mut self) {
+        let mut saving = SAVING
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(index) = saving.iter().rposition(|path| *path == self.0) {
+            saving.remove(index);
+        }
+    }
+}
+
+/// SavedVariables files being written right now, most recent last
+pub fn saves_in_progress() -> Vec<PathBuf> {
+    SAVING
+        .try_lock()
+        .map(|saving| saving.clone())
+        .unwrap_or_default()
+}
+
+/// Directory crash reports are written to
+pub fn crash_dir() -> Option<PathBuf> {
+    AppConfig::config_dir().map(|dir| dir.join("crashes"))
+}
+
+/// Record what the app is doing, for the report of a later panic.
+/// `summary` must not contain personal data.
+pub fn set_context(summary: String) {
+    if let Ok(mut context) = CONTEXT.lock() {
+        *context = Some(CrashContext { summary });
+    }
+}
+
+/// Write a crash report for every panic, then run the default hook
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string());
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        let backtrace = Backtrace::force_capture().to_string();
+        // A panic while the context lock is held must not deadlock the hook
+        let context = CONTEXT
+            .try_lock()
+            .ok()
+            .and_then(|context| context.clone())
+            .unwrap_or_default();
+
+        let report = format_report(&message, location.as_deref(), &backtrace, &context.summary);
+        if let Some(dir) = crash_dir() {
+            let saving = saves_in_progress().pop();
+            if let Err(e) = write_report(&dir, &report, saving) {
+                eprintln!("Failed to write crash report: {}", e);
+            }
+        }
+        default_hook(info);
+    }));
+}
+
+/// Text of a crash report, anonymized
+pub fn format_report(
+    message: &str,
+    location: Option<&str>,
+    backtrace: &str,
+    summary: &str,
+) -> String {
+    let thread = std::thread::current();
+    let report = format!(
+        "WeakAuras Mass Importer crash report\n\
+         Version: {}\n\
+         OS: {} ({})\n\
+         Thread: {}\n\
+         \n\
+         Panic: {}\n\
+         Location: {}\n\
+         \n\
+         State:\n{}\n\
+         \n\
+         Backtrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        thread.name().unwrap_or("unnamed"),
+        message,
+        location.unwrap_or("unknown"),
+        if summary.is_empty() { "-" } else { summary },
+        backtrace
+    );
+    anonymize(&report, home_dir().as_deref())
+}
+
+/// Replace the home directory with `~` and mask WoW account folder names
+pub fn anonymize(text: &str, home: Option<&Path>) -> String {
+    let mut text = text.to_string();
+    if let Some(home) = home.map(|h| h.to_string_lossy().to_string()) {
+        let home = home.trim_end_matches(&['/', '\\'][..]);
+        if !home.is_empty() {
+            text = text.replace(home, "~");
+        }
+    }
+    let account = Regex::new(r"([/\\]Account[/\\])[^/\\\s]+").expect("valid account regex");
+    account.replace_all(&text, "${1}<account>").into_owned()
+}
+
+/// Write the report and the recovery marker into `dir`
+pub fn write_report(dir: &Path, report: &str, saved_variables: Option<PathBuf>) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let path = dir.join(format!("crash-{}.txt", secs));
+    fs::write(&path, report)?;
+
+    let pending = PendingCrash {
+        report: path.clone(),
+        saved_variables,
+    };
+    let json = serde_json::to_string_pretty(&pending)
+        .map_err(|e| WeakAuraError::SerializationError(e.to_string()))?;
+    fs::write(dir.join(PENDING_FILE_NAME), json)?;
+    Ok(path)
+}
+
+/// Crash of an earlier session recorded in `dir`, removing the marker so it
+/// is only offered once
+pub fn take_pending_in(dir: &Path) -> Option<PendingCrash> {
+    let marker = dir.join(PENDING_FILE_NAME);
+    let content = fs::read_to_string(&marker).ok()?;
+    let _ = fs::remove_file(&marker);
+    serde_json::from_str(&content).ok()
+}
+
+/// Crash of an earlier session, if the app crashed since the last launch
+pub fn take_pending() -> Option<PendingCrash> {
+    take_pending_in(&crash_dir()?)
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
@@ -8,8 +8,12 @@
 //! We use the `weakauras-codec` crate for the heavy lifting.
 
 use crate::error::{Result, WeakAuraError};
+use crate::format_sniffer;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::Arc;
 use tracing::{debug, warn};
 use weakauras_codec::LuaValue as CodecLuaValue;
 
@@ -36,8 +40,334 @@ pub struct WeakAura {
     pub encoding_version: u8,
 }
 
-/// Represents a Lua value (since WeakAura data is essentially a Lua table)
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+impl WeakAura {
+    /// Parent of each child aura, by ID
+    fn child_parents(&self) -> HashMap<String, String> {
+        self.child_data
+            .iter()
+            .filter_map(|child| {
+                let table = child.as_table()?;
+                match (table.get("id"), table.get("parent")) {
+                    (Some(LuaValue::String(id)), Some(LuaValue::String(parent))) => {
+                        Some((id.clone(), parent.clone()))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Child auras in display order, each with its depth below this aura
+    /// (1 for direct children). Subgroups list their `controlledChildren`,
+    /// falling back to the children naming them as `parent`.
+    pub fn descendants(&self) -> Vec<(String, usize)> {
+        let mut by_id: HashMap<&str, &LuaTable> = HashMap::new();
+        let mut by_parent: HashMap<&str, Vec<&str>> = HashMap::new();
+        for table in self.child_data.iter().filter_map(LuaValue::as_table) {
+            let Some(LuaValue::String(id)) = table.get("id") else {
+                continue;
+            };
+            by_id.insert(id, table);
+            if let Some(LuaValue::String(parent)) = table.get("parent") {
+                by_parent.entry(parent).or_default().push(id);
+            }
+        }
+
+        // `children` may be inferred from all child data, nested ones included
+        let roots: Vec<&str> = self
+            .children
+            .iter()
+            .map(String::as_str)
+            .filter(
+                |id| match by_id.get(id).and_then(|table| table.get("parent")) {
+                    Some(LuaValue::String(parent)) => *parent == self.id,
+                    _ => true,
+                },
+            )
+            .collect();
+
+        // Depth-first in display order, without recursion so long chains of
+        // subgroups cannot overflow the stack
+        let mut stack: Vec<(&str, usize)> = roots.into_iter().rev().map(|id| (id, 1)).collect();
+        let mut seen = HashSet::new();
+        let mut out = Vec::new();
+        while let Some((id, depth)) = stack.pop() {
+            // Malformed data may list a child twice or contain cycles
+            let Some(table) = by_id.get(id) else {
+                continue;
+            };
+            if !seen.insert(id) {
+                continue;
+            }
+            out.push((id.to_string(), depth));
+            let children: Vec<&str> =
+                match table.get("controlledChildren").and_then(LuaValue::as_array) {
+                    Some(children) => children
+                        .iter()
+                        .filter_map(|child| match child {
+                            LuaValue::String(child_id) => Some(child_id.as_str()),
+                            _ => None,
+                        })
+                        .collect(),
+                    None => by_parent.get(id).cloned().unwrap_or_default(),
+                };
+            stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+        }
+        out
+    }
+
+    /// Whether this is a group wrapping a single child aura, as authors often
+    /// export single auras
+    pub fn is_single_child_wrapper(&self) -> bool {
+        self.is_group
+            && self
+                .descendants()
+                .iter()
+                .filter(|(_, depth)| *depth == 1)
+                .count()
+                == 1
+    }
+
+    /// Set new uids on this aura and its children, keyed by aura ID. Returns
+    /// how many were changed.
+    pub fn replace_uids(&mut self, uids: &HashMap<String, String>) -> usize {
+        let mut replaced = 0;
+        for data in std::iter::once(&mut self.data).chain(self.child_data.iter_mut()) {
+            let Some(uid) = data
+                .get_path("id")
+                .and_then(LuaValue::as_str)
+                .and_then(|id| uids.get(id))
+            else {
+                continue;
+            };
+            if data.set_path("uid", LuaValue::String(uid.clone())) {
+                replaced += 1;
+            }
+        }
+        if let Some(uid) = uids.get(&self.id) {
+            self.uid = Some(uid.clone());
+        }
+        replaced
+    }
+
+    /// Change the aura's ID, renaming it in the `parent` field of its direct
+    /// children
+    pub fn rename(&mut self, new_id: &str) {
+        let old_id = std::mem::replace(&mut self.id, new_id.to_string());
+        self.data
+            .set_path("id", LuaValue::String(new_id.to_string()));
+        for child in &mut self.child_data {
+            if child.get_path("parent").and_then(LuaValue::as_str) == Some(old_id.as_str()) {
+                child.set_path("parent", LuaValue::String(new_id.to_string()));
+            }
+        }
+    }
+
+    /// The aura's data (without its children) as text for hand-editing
+    pub fn data_text(&self, format: AuraTextFormat) -> String {
+        self.data.to_text(format)
+    }
+
+    /// The aura and its children as a Lua file, in the same
+    /// `WeakAurasExportedDisplays` layout as the dump written before removing
+    /// stored auras
+    pub fn export_lua(&self) -> String {
+        let mut output = String::from(
+            "-- WeakAuras displays exported by WeakAuras Mass Import.\n\
+             -- Paste the entries into WeakAurasSaved.displays to add them.\n\
+             WeakAurasExportedDisplays = {\n",
+        );
+        for data in std::iter::once(&self.data).chain(&self.child_data) {
+            let Some(id) = data.get_path("id").and_then(LuaValue::as_str) else {
+                continue;
+            };
+            output.push_str(&format!(
+                "\t[\"{}\"] = {},\n",
+                crate::util::escape_lua_string(id),
+                crate::lua_parser::LuaParser::serialize(data, 1)
+            ));
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    /// Replace the aura's data with hand-edited text, re-reading its ID, uid,
+    /// region type and children. The data must be a table with a string
+    /// `id`; on any error the aura is left unchanged. A changed ID is renamed
+    /// in the children's `parent` field.
+    pub fn set_data_text(&mut self, text: &str, format: AuraTextFormat) -> Result<()> {
+        let data = match format {
+            AuraTextFormat::Lua => crate::lua_parser::LuaParser::parse_value(text)?,
+            AuraTextFormat::Json => serde_json::from_str::<serde_json::Value>(text)
+                .map(LuaValue::from_json)
+                .map_err(|e| WeakAuraError::DeserializationError(e.to_string()))?,
+        };
+        if data.as_table().is_none() {
+            return Err(WeakAuraError::DeserializationError(
+                "aura data must be a table".to_string(),
+            ));
+        }
+        let Some(new_id) = data.get_path("id").and_then(LuaValue::as_str) else {
+            return Err(WeakAuraError::DeserializationError(
+                "aura data has no string id".to_string(),
+            ));
+        };
+        let new_id = new_id.to_string();
+
+        self.data = data;
+        if new_id != self.id {
+            self.rename(&new_id);
+        }
+        let (id, uid, region_type, is_group, children) =
+            WeakAuraDecoder::extract_metadata(&self.data, &self.child_data);
+        self.id = id;
+        self.uid = uid;
+        self.region_type = region_type;
+        self.is_group = is_group;
+        self.children = children;
+        Ok(())
+    }
+
+    /// The child of a single-child wrapper group as a top-level aura, with
+    /// its own descendants. `None` for any other aura.
+    pub fn unwrap_single_child(&self) -> Option<WeakAura> {
+        if !self.is_single_child_wrapper() {
+            return None;
+        }
+        let (child_id, _) = self.descendants().into_iter().next()?;
+        let mut data = self
+            .child_data
+            .iter()
+            .find(|child| {
+                matches!(
+                    child.as_table().and_then(|t| t.get("id")),
+                    Some(LuaValue::String(id)) if *id == child_id
+                )
+            })?
+            .clone();
+        data.as_table_mut()?.shift_remove("parent");
+
+        let table = data.as_table()?;
+        let string = |key: &str| match table.get(key) {
+            Some(LuaValue::String(s)) => Some(s.clone()),
+            _ => None,
+        };
+        let region_type = string("regionType");
+        let uid = string("uid");
+        let children: Vec<String> = table
+            .get("controlledChildren")
+            .and_then(LuaValue::as_array)
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|v| match v {
+                        LuaValue::String(id) => Some(id.clone()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let child_data = self
+            .child_data
+            .iter()
+            .filter(|child| {
+                !matches!(
+                    child.as_table().and_then(|t| t.get("id")),
+                    Some(LuaValue::String(id)) if *id == child_id
+                )
+            })
+            .cloned()
+            .collect();
+
+        Some(WeakAura {
+            id: child_id,
+            uid,
+            is_group: !children.is_empty()
+                || matches!(region_type.as_deref(), Some("group" | "dynamicgroup")),
+            region_type,
+            children,
+            data,
+            child_data,
+            original_string: self.original_string.clone(),
+            encoding_version: self.encoding_version,
+        })
+    }
+
+    /// Remove child auras together with their descendants, dropping them
+    /// from the `controlledChildren` and `sortHybridTable` of their groups.
+    /// Returns how many child auras were removed.
+    pub fn remove_children(&mut self, ids: &HashSet<String>) -> usize {
+        if ids.is_empty() {
+            return 0;
+        }
+        let parents = self.child_parents();
+        let mut removed = ids.clone();
+        // Descendants of a removed subgroup go with it
+        for id in parents.keys() {
+            let mut current = id.as_str();
+            let mut depth = 0;
+            while let Some(parent) = parents.get(current) {
+                if ids.contains(parent) {
+                    removed.insert(id.clone());
+                    break;
+                }
+                current = parent;
+                // Malformed data may contain parent cycles
+                depth += 1;
+                if depth > parents.len() {
+                    break;
+                }
+            }
+        }
+
+        let before = self.child_data.len();
+        self.child_data.retain(|child| {
+            !matches!(
+                child.as_table().and_then(|t| t.get("id")),
+                Some(LuaValue::String(id)) if removed.contains(id)
+            )
+        });
+        self.children.retain(|id| !removed.contains(id));
+        for value in std::iter::once(&mut self.data).chain(self.child_data.iter_mut()) {
+            let Some(table) = value.as_table_mut() else {
+                continue;
+            };
+            if let Some(
+                LuaValue::Array(children)
+                | LuaValue::MixedTable {
+                    array: children, ..
+                },
+            ) = table.get_mut("controlledChildren")
+            {
+                children
+                    .retain(|child| !matches!(child, LuaValue::String(id) if removed.contains(id)));
+            }
+            if let Some(sort) = table
+                .get_mut("sortHybridTable")
+                .and_then(LuaValue::as_table_mut)
+            {
+                sort.retain(|id, _| !removed.contains(id));
+            }
+        }
+        before - self.child_data.len()
+    }
+}
+
+/// String-keyed part of a Lua table, in insertion (parse) order
+pub type LuaTable = IndexMap<String, LuaValue>;
+
+/// Deepest table nesting accepted from SavedVariables files, import strings
+/// and JSON (matching serde_json's own limit). Code that walks values
+/// recursively, like serialization and comparison, relies on it to stay
+/// well within the stack.
+pub const MAX_TABLE_DEPTH: usize = 128;
+
+/// Represents a Lua value (since WeakAura data is essentially a Lua table).
+///
+/// Tables are reference counted and copied on write, so cloning a value (e.g.
+/// into an [`ImportConflict`](crate::saved_variables::ImportConflict)) shares
+/// its subtables instead of duplicating them. Mutate through
+/// [`as_table_mut`](Self::as_table_mut) or [`Arc::make_mut`].
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(untagged)]
 pub enum LuaValue {
     #[default]
@@ -45,18 +375,40 @@ pub enum LuaValue {
     Bool(bool),
     Number(f64),
     String(String),
-    Table(HashMap<String, LuaValue>),
+    Table(Arc<LuaTable>),
     Array(Vec<LuaValue>),
     /// Mixed table: array part (1-indexed implicit) + hash part (string keys)
     /// This is common in Lua, e.g., triggers = { {trigger1}, {trigger2}, disjunctive = "all" }
     MixedTable {
         array: Vec<LuaValue>,
-        hash: HashMap<String, LuaValue>,
+        hash: Arc<LuaTable>,
     },
 }
 
+/// Structural equality; tables shared by both sides compare equal without a walk
+impl PartialEq for LuaValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (LuaValue::Nil, LuaValue::Nil) => true,
+            (LuaValue::Bool(a), LuaValue::Bool(b)) => a == b,
+            (LuaValue::Number(a), LuaValue::Number(b)) => a == b,
+            (LuaValue::String(a), LuaValue::String(b)) => a == b,
+            (LuaValue::Table(a), LuaValue::Table(b)) => Arc::ptr_eq(a, b) || a == b,
+            (LuaValue::Array(a), LuaValue::Array(b)) => a == b,
+            (
+                LuaValue::MixedTable { array, hash },
+                LuaValue::MixedTable {
+                    array: other_array,
+                    hash: other_hash,
+                },
+            ) => array == other_array && (Arc::ptr_eq(hash, other_hash) || hash == other_hash),
+            _ => false,
+        }
+    }
+}
+
 impl LuaValue {
-    pub fn as_table(&self) -> Option<&HashMap<String, LuaValue>> {
+    pub fn as_table(&self) -> Option<&LuaTable> {
         match self {
             LuaValue::Table(t) => Some(t),
             LuaValue::MixedTable { hash, .. } => Some(hash),
@@ -64,10 +416,10 @@ impl LuaValue {
         }
     }
 
-    pub fn as_table_mut(&mut self) -> Option<&mut HashMap<String, LuaValue>> {
+    pub fn as_table_mut(&mut self) -> Option<&mut LuaTable> {
         match self {
-            LuaValue::Table(t) => Some(t),
-            LuaValue::MixedTable { hash, .. } => Some(hash),
+            LuaValue::Table(t) => Some(Arc::make_mut(t)),
+            LuaValue::MixedTable { hash, .. } => Some(Arc::make_mut(hash)),
             _ => None,
         }
     }
@@ -79,61 +431,362 @@ impl LuaValue {
             _ => None,
         }
     }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            LuaValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Value at a dotted path such as `triggers.1.trigger.spellName`. Numeric
+    /// segments index the array part (1-based) when there is one, and are
+    /// table keys otherwise; the empty path is the value itself.
+    pub fn get_path(&self, path: &str) -> Option<&LuaValue> {
+        let mut value = self;
+        for segment in path_segments(path) {
+            value = value.child(segment)?;
+        }
+        Some(value)
+    }
+
+    /// Mutable value at a dotted path, see [`get_path`](Self::get_path)
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut LuaValue> {
+        let mut value = self;
+        for segment in path_segments(path) {
+            value = value.child_mut(segment, false)?;
+        }
+        Some(value)
+    }
+
+    /// Set the value at a dotted path, creating missing tables on the way.
+    /// An index one past the end of an array part appends. Returns `false`
+    /// (changing nothing) when the path runs into a value that is not a table.
+    pub fn set_path(&mut self, path: &str, new_value: LuaValue) -> bool {
+        let segments: Vec<&str> = path_segments(path).collect();
+        let Some((last, parents)) = segments.split_last() else {
+            *self = new_value;
+            return true;
+        };
+        // Check first so a failed set leaves no tables behind
+        let mut probe = Some(&*self);
+        for segment in parents {
+            match probe {
+                Some(value) if value.as_table().is_none() && value.as_array().is_none() => {
+                    return false;
+                }
+                Some(value) => probe = value.child(segment),
+                None => break,
+            }
+        }
+        if let Some(value) = probe {
+            if value.as_table().is_none() && value.as_array().is_none() {
+                return false;
+            }
+        }
+
+        let mut value = self;
+        for segment in parents {
+            match value.child_mut(segment, true) {
+                Some(child) => value = child,
+                None => return false,
+            }
+        }
+        match value.child_mut(last, true) {
+            Some(slot) => {
+                *slot = new_value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the value at a dotted path, returning it. Only table keys are
+    /// removed; entries of an array part are left alone, as removing one
+    /// would shift the entries after it.
+    pub fn remove_path(&mut self, path: &str) -> Option<LuaValue> {
+        let (parent, key) = match path.rsplit_once('.') {
+            Some((parent, key)) => (self.get_path_mut(parent)?, key),
+            None => (self, path),
+        };
+        parent.as_table_mut()?.shift_remove(key)
+    }
+
+    fn array_mut(&mut self) -> Option<&mut Vec<LuaValue>> {
+        match self {
+            LuaValue::Array(array) | LuaValue::MixedTable { array, .. } => Some(array),
+            _ => None,
+        }
+    }
+
+    fn child(&self, segment: &str) -> Option<&LuaValue> {
+        if let (Ok(index), Some(array)) = (segment.parse::<usize>(), self.as_array()) {
+            if let Some(item) = index.checked_sub(1).and_then(|i| array.get(i)) {
+                return Some(item);
+            }
+        }
+        self.as_table()?.get(segment)
+    }
+
+    /// Child at `segment`; with `create`, a missing child is inserted as an
+    /// empty table (or appended, for an index one past the array part)
+    fn child_mut(&mut self, segment: &str, create: bool) -> Option<&mut LuaValue> {
+        let array_len = self.as_array().map(Vec::len);
+        if let (Ok(index), Some(len)) = (segment.parse::<usize>(), array_len) {
+            if (1..=len).contains(&index) {
+                return self.array_mut()?.get_mut(index - 1);
+            }
+            if create && index == len + 1 {
+                let array = self.array_mut()?;
+                array.push(LuaValue::Table(Arc::default()));
+                return array.last_mut();
+            }
+        }
+        if create {
+            if let LuaValue::Array(array) = self {
+                // Give a plain array a hash part for the new key
+                *self = if array.is_empty() {
+                    LuaValue::Table(Arc::default())
+                } else {
+                    LuaValue::MixedTable {
+                        array: std::mem::take(array),
+                        hash: Arc::default(),
+                    }
+                };
+            }
+            let table = self.as_table_mut()?;
+            Some(
+                table
+                    .entry(segment.to_string())
+                    .or_insert_with(|| LuaValue::Table(Arc::default())),
+            )
+        } else {
+            self.as_table_mut()?.get_mut(segment)
+        }
+    }
+
+    /// Visit this value and every value nested in it, depth first in table
+    /// order, with the path leading there (empty for the value itself).
+    /// Array entries come before the string keys of a mixed table.
+    pub fn walk<F: FnMut(&[LuaPathSegment], &LuaValue)>(&self, visit: &mut F) {
+        fn walk_inner<F: FnMut(&[LuaPathSegment], &LuaValue)>(
+            value: &LuaValue,
+            path: &mut Vec<LuaPathSegment>,
+            visit: &mut F,
+        ) {
+            visit(path, value);
+            if let Some(array) = value.as_array() {
+                for (i, item) in array.iter().enumerate() {
+                    path.push(LuaPathSegment::Index(i + 1));
+                    walk_inner(item, path, visit);
+                    path.pop();
+                }
+            }
+            if let Some(table) = value.as_table() {
+                for (key, item) in table {
+                    path.push(LuaPathSegment::Key(key.clone()));
+                    walk_inner(item, path, visit);
+                    path.pop();
+                }
+            }
+        }
+        walk_inner(self, &mut Vec::new(), visit);
+    }
+
+    /// The value as a Lua table literal or pretty-printed JSON
+    pub fn to_text(&self, format: AuraTextFormat) -> String {
+        match format {
+            AuraTextFormat::Lua => crate::lua_parser::LuaParser::serialize(self, 0),
+            AuraTextFormat::Json => {
+                serde_json::to_string_pretty(&self.to_json()).unwrap_or_else(|_| "{}".to_string())
+            }
+        }
+    }
+
+    /// Plain JSON for the value. Unlike the serde representation, a mixed
+    /// table is one object with its array entries under `"1"`, `"2"`, ...
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value;
+        match self {
+            LuaValue::Nil => Value::Null,
+            LuaValue::Bool(b) => Value::Bool(*b),
+            // Whole numbers without a trailing `.0`
+            LuaValue::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => Value::from(*n as i64),
+            LuaValue::Number(n) => {
+                serde_json::Number::from_f64(*n).map_or(Value::Null, Value::Number)
+            }
+            LuaValue::String(s) => Value::String(s.clone()),
+            LuaValue::Array(array) => Value::Array(array.iter().map(LuaValue::to_json).collect()),
+            LuaValue::Table(_) | LuaValue::MixedTable { .. } => {
+                let mut object = serde_json::Map::new();
+                for (i, item) in self.as_array().into_iter().flatten().enumerate() {
+                    object.insert((i + 1).to_string(), item.to_json());
+                }
+                for (key, item) in self.as_table().into_iter().flatten() {
+                    object.insert(key.clone(), item.to_json());
+                }
+                Value::Object(object)
+            }
+        }
+    }
+
+    /// Value from JSON written by [`to_json`](Self::to_json): objects whose
+    /// keys start at `"1"`, `"2"`, ... get those entries as their array part
+    pub fn from_json(value: serde_json::Value) -> LuaValue {
+        use serde_json::Value;
+        match value {
+            Value::Null => LuaValue::Nil,
+            Value::Bool(b) => LuaValue::Bool(b),
+            Value::Number(n) => LuaValue::Number(n.as_f64().unwrap_or_default()),
+            Value::String(s) => LuaValue::String(s),
+            Value::Array(items) => {
+                LuaValue::Array(items.into_iter().map(LuaValue::from_json).collect())
+            }
+            Value::Object(mut object) => {
+                let mut array = Vec::new();
+                while let Some(item) = object.remove(&(array.len() + 1).to_string()) {
+                    array.push(LuaValue::from_json(item));
+                }
+                let hash: LuaTable = object
+                    .into_iter()
+                    .map(|(key, item)| (key, LuaValue::from_json(item)))
+                    .collect();
+                match (array.is_empty(), hash.is_empty()) {
+                    (false, true) => LuaValue::Array(array),
+                    (true, _) => LuaValue::Table(Arc::new(hash)),
+                    (false, false) => LuaValue::MixedTable {
+                        array,
+                        hash: Arc::new(hash),
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Text format for hand-editing aura data
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuraTextFormat {
+    /// A Lua table literal, as in SavedVariables files
+    #[default]
+    Lua,
+    /// JSON; the array part of a table becomes the keys `"1"`, `"2"`, ...
+    /// when the table also has string keys
+    Json,
+}
+
+/// Step from a Lua value into a nested one
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LuaPathSegment {
+    /// String key of a table
+    Key(String),
+    /// 1-based index into the array part of a table
+    Index(usize),
 }
 
-/// Convert from weakauras_codec::LuaValue to our LuaValue
-fn convert_lua_value(value: &CodecLuaValue) -> LuaValue {
-    match value {
+/// Segments of a dotted path, skipping empty ones
+fn path_segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('.').filter(|segment| !segment.is_empty())
+}
+
+/// Path as written in Lua, e.g. `triggers[1].trigger.spellIds[2]`
+pub fn format_lua_path(path: &[LuaPathSegment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            LuaPathSegment::Key(key) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(key);
+            }
+            LuaPathSegment::Index(index) => out.push_str(&format!("[{}]", index)),
+        }
+    }
+    out
+}
+
+/// Largest integer key taken as an array index; every integer up to it is
+/// exact as an `f64`
+const MAX_ARRAY_INDEX: f64 = 9_007_199_254_740_992.0;
+
+/// Table key for a number that is not an array index
+fn number_key(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() <= MAX_ARRAY_INDEX {
+        (n as i64).to_string()
+    } else {
+        n.to_string()
+    }
+}
+
+/// Convert from weakauras_codec::LuaValue to our LuaValue. Keys that are
+/// tables themselves have no counterpart and are counted in `dropped_keys`.
+///
+/// `depth` is the nesting level of `value`, counted so the aura data one
+/// level below the transmitted table is at 1, as it is when parsed on its own.
+fn convert_lua_value(
+    value: &CodecLuaValue,
+    depth: usize,
+    dropped_keys: &mut usize,
+) -> Result<LuaValue> {
+    if depth > MAX_TABLE_DEPTH && matches!(value, CodecLuaValue::Array(_) | CodecLuaValue::Map(_)) {
+        return Err(WeakAuraError::DeserializationError(format!(
+            "tables nested deeper than {} levels",
+            MAX_TABLE_DEPTH
+        )));
+    }
+    let convert =
+        |v: &CodecLuaValue, dropped_keys: &mut usize| convert_lua_value(v, depth + 1, dropped_keys);
+    Ok(match value {
         CodecLuaValue::Null => LuaValue::Nil,
         CodecLuaValue::Boolean(b) => LuaValue::Bool(*b),
         CodecLuaValue::Number(n) => LuaValue::Number(*n),
         CodecLuaValue::String(s) => LuaValue::String(s.clone()),
-        CodecLuaValue::Array(arr) => LuaValue::Array(arr.iter().map(convert_lua_value).collect()),
+        CodecLuaValue::Array(arr) => LuaValue::Array(
+            arr.iter()
+                .map(|v| convert(v, dropped_keys))
+                .collect::<Result<_>>()?,
+        ),
         CodecLuaValue::Map(map) => {
             // Separate numeric keys (array part) from string keys (hash part)
             let mut numeric_entries: Vec<(i64, LuaValue)> = Vec::new();
-            let mut string_entries: HashMap<String, LuaValue> = HashMap::new();
+            let mut string_entries = LuaTable::new();
             let mut has_string_keys = false;
             let mut has_numeric_keys = false;
 
             for (k, v) in map.iter() {
                 match k.as_value() {
-                    CodecLuaValue::Number(n) if *n > 0.0 && n.fract() == 0.0 => {
+                    CodecLuaValue::Number(n)
+                        if *n > 0.0 && n.fract() == 0.0 && *n <= MAX_ARRAY_INDEX =>
+                    {
                         has_numeric_keys = true;
-                        numeric_entries.push((*n as i64, convert_lua_value(v)));
+                        numeric_entries.push((*n as i64, convert(v, dropped_keys)?));
                     }
                     CodecLuaValue::String(s) => {
                         // Check if string represents a positive integer (array index)
-                        // The weakauras-codec may return numeric keys as strings for some encoding versions
-                        if let Ok(n) = s.parse::<i64>() {
-                            if n > 0 {
+                        // The weakauras-codec may return numeric keys as strings for some encoding versions.
+                        // Only the canonical form counts, so keys like "007" or "+1" stay strings.
+                        match s.parse::<i64>() {
+                            Ok(n) if n > 0 && n.to_string() == *s => {
                                 has_numeric_keys = true;
-                                numeric_entries.push((n, convert_lua_value(v)));
-                            } else {
-                                // Zero or negative - treat as string key
+                                numeric_entries.push((n, convert(v, dropped_keys)?));
+                            }
+                            _ => {
                                 has_string_keys = true;
-                                string_entries.insert(s.clone(), convert_lua_value(v));
+                                string_entries.insert(s.clone(), convert(v, dropped_keys)?);
                             }
-                        } else {
-                            has_string_keys = true;
-                            string_entries.insert(s.clone(), convert_lua_value(v));
                         }
                     }
                     CodecLuaValue::Number(n) => {
-                        // Non-positive or fractional number key - treat as string
+                        // Non-positive, fractional, huge or non-finite number key - treat as string
                         has_string_keys = true;
-                        let key = if n.fract() == 0.0 {
-                            (*n as i64).to_string()
-                        } else {
-                            n.to_string()
-                        };
-                        string_entries.insert(key, convert_lua_value(v));
+                        string_entries.insert(number_key(*n), convert(v, dropped_keys)?);
                     }
                     CodecLuaValue::Boolean(b) => {
                         has_string_keys = true;
-                        string_entries.insert(b.to_string(), convert_lua_value(v));
+                        string_entries.insert(b.to_string(), convert(v, dropped_keys)?);
                     }
-                    _ => continue,
+                    _ => *dropped_keys += 1,
                 }
             }
 
@@ -155,14 +808,14 @@ fn convert_lua_value(value: &CodecLuaValue) -> LuaValue {
                         numeric_entries.into_iter().map(|(_, v)| v).collect();
                     LuaValue::MixedTable {
                         array,
-                        hash: string_entries,
+                        hash: Arc::new(string_entries),
                     }
                 } else {
                     // Convert everything to string-keyed table
                     for (idx, val) in numeric_entries {
                         string_entries.insert(idx.to_string(), val);
                     }
-                    LuaValue::Table(string_entries)
+                    LuaValue::Table(Arc::new(string_entries))
                 }
             } else if has_numeric_keys {
                 // Pure array - verify contiguous from 1
@@ -185,76 +838,381 @@ fn convert_lua_value(value: &CodecLuaValue) -> LuaValue {
                     LuaValue::Array(arr)
                 } else {
                     // Non-contiguous numeric keys - treat as table
-                    let mut table = HashMap::new();
+                    let mut table = LuaTable::new();
                     for (idx, val) in numeric_entries {
                         table.insert(idx.to_string(), val);
                     }
-                    LuaValue::Table(table)
+                    LuaValue::Table(Arc::new(table))
                 }
             } else {
                 // Pure string-keyed table (or empty)
-                LuaValue::Table(string_entries)
+                LuaValue::Table(Arc::new(string_entries))
             }
         }
+    })
+}
+
+/// Largest decompressed size of an import string
+const MAX_DECODED_SIZE: usize = 10 * 1024 * 1024; // 10MB
+
+/// Whether `c` belongs to the printable encoding after the prefix (LibDeflate's
+/// `EncodeForPrint` and the legacy WeakAuras encoder share the alphabet)
+fn is_encoded_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '(' || c == ')'
+}
+
+/// Length of the version prefix of an import string (`!WA:N!`, `!` or none),
+/// or `None` if a `!WA:` prefix is not closed
+fn prefix_len(s: &str) -> Option<usize> {
+    if let Some(rest) = s.strip_prefix("!WA:") {
+        rest.find('!')
+            .map(|version_end| "!WA:".len() + version_end + 1)
+    } else if s.starts_with('!') {
+        Some(1)
+    } else {
+        Some(0)
+    }
+}
+
+/// Split an import string into its version prefix and the encoded payload
+fn split_prefix(s: &str) -> Result<(&str, &str)> {
+    let invalid = |message: &str| WeakAuraError::DeserializationError(message.to_string());
+    if s.is_empty() {
+        return Err(invalid("the import string is empty"));
+    }
+    let prefix_len =
+        prefix_len(s).ok_or_else(|| invalid("the '!WA:' prefix is not closed with '!'"))?;
+    let (prefix, payload) = s.split_at(prefix_len);
+    if payload.is_empty() {
+        return Err(invalid("the import string has no data after its prefix"));
+    }
+    Ok((prefix, payload))
+}
+
+/// Undo what copying a string out of chat, forums or documents tends to do
+/// to it: surrounding quotes, line breaks and spaces, stray characters.
+/// Returns the cleaned string and one line per kind of change.
+fn clean_import_string(input: &str) -> (String, Vec<String>) {
+    let mut repairs = Vec::new();
+    let trimmed = input.trim();
+    let unquoted = trimmed.trim_matches(|c: char| c.is_whitespace() || "\"'`".contains(c));
+    if unquoted.len() != trimmed.len() {
+        repairs.push("removed surrounding quotes".to_string());
+    }
+
+    let (prefix, payload) = unquoted.split_at(prefix_len(unquoted).unwrap_or(0));
+    let mut cleaned = prefix.to_string();
+    let (mut spaces, mut stray) = (0, 0);
+    for c in payload.chars() {
+        if is_encoded_char(c) {
+            cleaned.push(c);
+        } else if c.is_whitespace() {
+            spaces += 1;
+        } else {
+            stray += 1;
+        }
     }
+    if spaces > 0 {
+        repairs.push(format!("removed {} line break(s) or space(s)", spaces));
+    }
+    if stray > 0 {
+        repairs.push(format!(
+            "removed {} character(s) outside the encoding",
+            stray
+        ));
+    }
+    (cleaned, repairs)
+}
+
+/// Make a decoded child list importable under `root_id`: drop entries that
+/// are not auras or repeat an ID, and move children whose parent is invalid,
+/// unknown or part of a cycle to the top of the group. Returns the repairs.
+fn salvage_children(root_id: &str, child_data: &mut Vec<LuaValue>) -> Vec<String> {
+    let mut repairs = Vec::new();
+    let mut seen = HashSet::new();
+    let mut position = 0;
+    child_data.retain(|child| {
+        position += 1;
+        let id = match child.as_table().map(|table| table.get("id")) {
+            None => {
+                repairs.push(format!("dropped child #{}: not a table", position));
+                return false;
+            }
+            Some(Some(LuaValue::String(id))) if !id.is_empty() => id,
+            Some(_) => {
+                repairs.push(format!("dropped child #{}: no ID", position));
+                return false;
+            }
+        };
+        if id == root_id {
+            repairs.push(format!("dropped child '{}': same ID as its group", id));
+            false
+        } else if !seen.insert(id.clone()) {
+            repairs.push(format!("dropped a second child '{}'", id));
+            false
+        } else {
+            true
+        }
+    });
+
+    // Child ID -> parent ID, with invalid and unknown parents reset to the root
+    let mut parents: HashMap<String, String> = HashMap::new();
+    for child in child_data.iter() {
+        let Some(table) = child.as_table() else {
+            continue;
+        };
+        let Some(LuaValue::String(id)) = table.get("id") else {
+            continue;
+        };
+        let parent = match table.get("parent") {
+            None => root_id.to_string(),
+            Some(LuaValue::String(parent)) if parent == root_id || seen.contains(parent) => {
+                parent.clone()
+            }
+            Some(_) => {
+                repairs.push(format!(
+                    "moved '{}' to the top of the group: unknown parent",
+                    id
+                ));
+                root_id.to_string()
+            }
+        };
+        parents.insert(id.clone(), parent);
+    }
+    // Break parent cycles, one child per cycle
+    let mut ids: Vec<String> = parents.keys().cloned().collect();
+    ids.sort();
+    for id in ids {
+        let mut current = parents[&id].clone();
+        let mut steps = 0;
+        while current != root_id && steps <= parents.len() {
+            current = parents[&current].clone();
+            steps += 1;
+        }
+        if current != root_id {
+            repairs.push(format!(
+                "moved '{}' to the top of the group: its parents form a cycle",
+                id
+            ));
+            parents.insert(id, root_id.to_string());
+        }
+    }
+
+    for child in child_data.iter_mut() {
+        let Some(table) = child.as_table_mut() else {
+            continue;
+        };
+        let Some(id) = table.get("id").and_then(LuaValue::as_str) else {
+            continue;
+        };
+        let parent = parents[id].clone();
+        let needs_parent = match table.get("parent") {
+            None => false,
+            Some(current) => current.as_str() != Some(parent.as_str()),
+        };
+        if needs_parent {
+            table.insert("parent".to_string(), LuaValue::String(parent));
+        }
+    }
+    repairs
+}
+
+/// An aura decoded by [`WeakAuraDecoder::decode_lossy`] or
+/// [`WeakAuraDecoder::aura_from_data`], with what was repaired to get it
+#[derive(Debug, Clone)]
+pub struct SalvagedAura {
+    pub aura: WeakAura,
+    /// One line per repair; empty if nothing needed repairing
+    pub repairs: Vec<String>,
+}
+
+/// An import string found by [`WeakAuraDecoder::decode_multiple_with_spans`]
+#[derive(Debug)]
+pub struct DecodedString {
+    /// Byte range of the string in the input, without surrounding whitespace
+    pub span: Range<usize>,
+    /// Zero-based line of the input the string is on
+    pub line: usize,
+    pub result: Result<WeakAura>,
 }
 
 /// Decoder for WeakAura import strings
 pub struct WeakAuraDecoder;
 
 impl WeakAuraDecoder {
-    /// Decode a WeakAura import string
+    /// Decode a WeakAura import string. Malformed input of any kind is
+    /// returned as an error; children are checked when the aura is imported.
     pub fn decode(import_string: &str) -> Result<WeakAura> {
         let trimmed = import_string.trim();
+        if let Some(format) = format_sniffer::sniff(trimmed) {
+            return Err(WeakAuraError::ForeignString(format));
+        }
+        let (prefix, payload) = split_prefix(trimmed)?;
+        if let Some((pos, c)) = payload.char_indices().find(|(_, c)| !is_encoded_char(*c)) {
+            return Err(WeakAuraError::DeserializationError(format!(
+                "invalid character {:?} at position {}",
+                c,
+                prefix.chars().count() + payload[..pos].chars().count() + 1
+            )));
+        }
 
-        // Use weakauras-codec for decoding
-        // The crate takes bytes and an optional max decompressed size
-        let decoded = weakauras_codec::decode(trimmed.as_bytes(), Some(10 * 1024 * 1024)) // 10MB max
-            .map_err(|e| WeakAuraError::DeserializationError(e.to_string()))?
-            .ok_or_else(|| {
-                WeakAuraError::DeserializationError("Decode returned None".to_string())
-            })?;
+        let mut dropped_keys = 0;
+        let data = Self::decode_payload(trimmed, &mut dropped_keys)?;
+        if dropped_keys > 0 {
+            warn!(
+                dropped_keys,
+                "decode: dropped table keys of unsupported type"
+            );
+        }
+
+        let mut aura = Self::aura_from_data(data, false)?.aura;
+        aura.original_string = import_string.to_string();
+        aura.encoding_version = Self::detect_version(trimmed);
+        Ok(aura)
+    }
+
+    /// Decode a string that may have been damaged in transit, salvaging what
+    /// can be salvaged: stray quotes, line breaks and characters outside the
+    /// encoding are removed, and children that would make the import fail
+    /// are dropped or moved to the top of the group.
+    #[allow(dead_code)]
+    pub fn decode_lossy(import_string: &str) -> Result<SalvagedAura> {
+        let unquoted =
+            import_string.trim_matches(|c: char| c.is_whitespace() || "\"'`".contains(c));
+        if let Some(format) = format_sniffer::sniff(unquoted) {
+            return Err(WeakAuraError::ForeignString(format));
+        }
+        let (cleaned, mut repairs) = clean_import_string(import_string);
+        split_prefix(&cleaned)?;
+
+        let mut dropped_keys = 0;
+        let data = Self::decode_payload(&cleaned, &mut dropped_keys)?;
+        if dropped_keys > 0 {
+            repairs.push(format!(
+                "dropped {} table key(s) that were tables themselves",
+                dropped_keys
+            ));
+        }
 
-        // Determine encoding version from prefix
-        let encoding_version = Self::detect_version(trimmed);
+        let mut salvaged = Self::aura_from_data(data, true)?;
+        salvaged.aura.original_string = cleaned.clone();
+        salvaged.aura.encoding_version = Self::detect_version(&cleaned);
+        repairs.append(&mut salvaged.repairs);
+        salvaged.repairs = repairs;
+        Ok(salvaged)
+    }
 
-        // Convert the decoded data
-        let data = convert_lua_value(&decoded);
+    /// Build an aura from decoded transmission data (`{ d = <aura>, c =
+    /// {<children>} }` or a bare aura table). Data without an aura table or
+    /// ID is an error. With `lossy`, a damaged child list is repaired so the
+    /// aura imports, and each repair is listed; otherwise it is kept as-is.
+    /// `original_string` is left empty.
+    pub fn aura_from_data(data: LuaValue, lossy: bool) -> Result<SalvagedAura> {
+        let invalid = |message: &str| WeakAuraError::DeserializationError(message.to_string());
+        let mut repairs = Vec::new();
 
         // Extract metadata from the decoded structure
         // WeakAura format: { m = "d", d = <aura_data>, c = [<children>], v = version, s = wa_version }
-        let (aura_data, child_data) = Self::extract_aura_data(&data);
+        let (aura_data, child_list) = Self::extract_aura_data(&data);
+        let Some(aura_table) = aura_data.as_table() else {
+            return Err(invalid("the string does not contain aura data"));
+        };
+        let root_id = match aura_table.get("id") {
+            Some(LuaValue::String(id)) if !id.is_empty() => id.clone(),
+            _ => return Err(invalid("the aura has no ID")),
+        };
+
+        let mut child_data = match child_list {
+            None => Vec::new(),
+            Some(LuaValue::Array(children)) => children,
+            Some(LuaValue::Table(table)) if table.is_empty() => Vec::new(),
+            Some(LuaValue::MixedTable { array, .. }) if lossy => {
+                repairs.push("ignored named entries in the child list".to_string());
+                array
+            }
+            Some(LuaValue::Table(table)) if lossy => {
+                // Children with gaps between their indices, in index order
+                let mut indexed: Vec<(f64, LuaValue)> = table
+                    .iter()
+                    .filter_map(|(key, value)| Some((key.parse::<f64>().ok()?, value.clone())))
+                    .collect();
+                indexed.sort_by(|a, b| a.0.total_cmp(&b.0));
+                repairs.push("closed gaps in the child list".to_string());
+                indexed.into_iter().map(|(_, value)| value).collect()
+            }
+            Some(_) if lossy => {
+                repairs.push("dropped a child list that is not a list".to_string());
+                Vec::new()
+            }
+            Some(LuaValue::MixedTable { array, .. }) => array,
+            Some(_) => return Err(invalid("the child list is not a list")),
+        };
+        if lossy {
+            repairs.extend(salvage_children(&root_id, &mut child_data));
+        }
+
         let (id, uid, region_type, is_group, children) =
             Self::extract_metadata(&aura_data, &child_data);
 
-        Ok(WeakAura {
-            id,
-            uid,
-            region_type,
-            is_group,
-            children,
-            data: aura_data,
-            child_data,
-            original_string: import_string.to_string(),
-            encoding_version,
+        Ok(SalvagedAura {
+            aura: WeakAura {
+                id,
+                uid,
+                region_type,
+                is_group,
+                children,
+                data: aura_data,
+                child_data,
+                original_string: String::new(),
+                encoding_version: 2,
+            },
+            repairs,
         })
     }
 
+    /// Decompress and deserialize a trimmed import string
+    fn decode_payload(trimmed: &str, dropped_keys: &mut usize) -> Result<LuaValue> {
+        // Use weakauras-codec for decoding
+        // The crate takes bytes and an optional max decompressed size
+        let decoded = weakauras_codec::decode(trimmed.as_bytes(), Some(MAX_DECODED_SIZE))
+            .map_err(|e| WeakAuraError::DeserializationError(e.to_string()))?
+            .ok_or_else(|| {
+                WeakAuraError::DeserializationError("Decode returned None".to_string())
+            })?;
+        convert_lua_value(&decoded, 0, dropped_keys)
+    }
+
     /// Decode multiple import strings (one per line or separated by blank lines)
+    #[allow(dead_code)]
     pub fn decode_multiple(input: &str) -> Vec<Result<WeakAura>> {
-        let mut results = Vec::new();
+        Self::decode_multiple_with_spans(input)
+            .into_iter()
+            .map(|decoded| decoded.result)
+            .collect()
+    }
 
-        // Split by blank lines or detect individual strings
-        let strings: Vec<&str> = input
-            .split("\n\n")
-            .flat_map(|s| s.split('\n'))
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .filter(|s| s.starts_with('!') || Self::looks_like_weakaura(s))
-            .collect();
+    /// Decode multiple import strings like [`Self::decode_multiple`], keeping
+    /// where in `input` each string was found
+    pub fn decode_multiple_with_spans(input: &str) -> Vec<DecodedString> {
+        let mut results = Vec::new();
+        let mut line_start = 0;
 
-        for s in strings {
-            results.push(Self::decode(s));
+        for (line, text) in input.split('\n').enumerate() {
+            let start = line_start + (text.len() - text.trim_start().len());
+            line_start += text.len() + 1;
+            let s = text.trim();
+            // Other addons' strings are kept to report what they are
+            let candidate = s.starts_with('!')
+                || Self::looks_like_weakaura(s)
+                || format_sniffer::sniff(s).is_some();
+            if s.is_empty() || !candidate {
+                continue;
+            }
+            results.push(DecodedString {
+                span: start..start + s.len(),
+                line,
+                result: Self::decode(s),
+            });
         }
 
         results
@@ -285,32 +1243,27 @@ impl WeakAuraDecoder {
         }
     }
 
-    /// Extract the actual aura data and children from the transmission wrapper
-    fn extract_aura_data(data: &LuaValue) -> (LuaValue, Vec<LuaValue>) {
+    /// Extract the actual aura data and the child list from the transmission wrapper
+    fn extract_aura_data(data: &LuaValue) -> (LuaValue, Option<LuaValue>) {
         if let Some(table) = data.as_table() {
             // Check for transmission wrapper format
             let has_wrapper = table.contains_key("d");
             let aura_data = table.get("d").cloned().unwrap_or_else(|| data.clone());
-
-            let child_data = table
-                .get("c")
-                .and_then(|c| c.as_array())
-                .cloned()
-                .unwrap_or_default();
+            let child_list = table.get("c").cloned();
 
             debug!(
                 has_wrapper,
-                child_count = child_data.len(),
+                has_children = child_list.is_some(),
                 "Extracted aura data from transmission wrapper"
             );
 
-            (aura_data, child_data)
+            (aura_data, child_list)
         } else {
             warn!(
                 value_type = std::any::type_name::<LuaValue>(),
                 "extract_aura_data: data is not a table variant, returning as-is"
             );
-            (data.clone(), Vec::new())
+            (data.clone(), None)
         }
     }
 
@@ -0,0 +1,520 @@
+//! WoW installation discovery
+//!
+//! Locates World of Warcraft installations beyond the hard-coded common
+//! locations by reading what the Battle.net launcher knows about:
+//! - `product.db` (protobuf install database written by the Agent)
+//! - `Battle.net.config` (JSON launcher settings, default install path)
+//! - The Windows registry (`InstallPath` values under Blizzard Entertainment)
+//!
+//! On Linux, WoW runs inside Wine prefixes, so prefixes from Steam (Proton
+//! `compatdata`), Lutris and Bottles (native and Flatpak) are scanned too.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Game flavor folders that may exist under a WoW installation root
+pub const WOW_FLAVORS: &[&str] = &[
+    "_retail_",
+    "_classic_",
+    "_classic_era_",
+    "_anniversary_",
+    "_ptr_",
+    "_beta_",
+];
+
+/// Maximum nesting depth when walking protobuf messages
+const MAX_PROTOBUF_DEPTH: usize = 8;
+
+/// A Battle.net launcher data location
+struct LauncherLocation {
+    /// Battle.net data directory (contains `Agent/product.db`)
+    data_dir: PathBuf,
+    /// Directory containing `Battle.net.config`
+    config_dir: Option<PathBuf>,
+    /// Wine `drive_c` that Windows paths map onto (`None` for native installs)
+    drive_c: Option<PathBuf>,
+}
+
+/// Find WoW installations registered with the Battle.net launcher
+pub fn find_launcher_installs() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+
+    for location in launcher_locations() {
+        let mut found = Vec::new();
+
+        for db_name in ["Agent/product.db", "Launcher.db"] {
+            if let Ok(bytes) = fs::read(location.data_dir.join(db_name)) {
+                found.extend(extract_install_paths(&bytes));
+            }
+        }
+
+        if let Some(config_dir) = &location.config_dir {
+            if let Ok(content) = fs::read_to_string(config_dir.join("Battle.net.config")) {
+                found.extend(parse_launcher_config(&content));
+            }
+        }
+
+        candidates.extend(
+            found
+                .iter()
+                .map(|p| map_windows_path(p, location.drive_c.as_deref())),
+        );
+    }
+
+    #[cfg(target_os = "windows")]
+    candidates.extend(registry_install_paths());
+
+    dedup_paths(
+        candidates
+            .into_iter()
+            .map(|p| normalize_install_root(&p))
+            .filter(|p| is_wow_root(p)),
+    )
+}
+
+/// Launcher locations to probe on the current platform
+fn launcher_locations() -> Vec<LauncherLocation> {
+    #[allow(unused_mut)]
+    let mut locations = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        let program_data =
+            std::env::var("ProgramData").unwrap_or_else(|_| r"C:\ProgramData".to_string());
+        locations.push(LauncherLocation {
+            data_dir: PathBuf::from(program_data).join("Battle.net"),
+            config_dir: std::env::var("APPDATA")
+                .ok()
+                .map(|appdata| PathBuf::from(appdata).join("Battle.net")),
+            drive_c: None,
+        });
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let home = std::env::var("HOME").unwrap_or_default();
+        locations.push(LauncherLocation {
+            data_dir: PathBuf::from("/Users/Shared/Battle.net"),
+            config_dir: Some(PathBuf::from(home).join("Library/Application Support/Battle.net")),
+            drive_c: None,
+        });
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        for prefix in wine_prefixes() {
+            let drive_c = prefix.join("drive_c");
+            if !drive_c.is_dir() {
+                continue;
+            }
+            let config_dirs: Vec<PathBuf> = fs::read_dir(drive_c.join("users"))
+                .map(|users| {
+                    users
+                        .flatten()
+                        .map(|user| user.path().join("AppData/Roaming/Battle.net"))
+                        .filter(|dir| dir.is_dir())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            locations.push(LauncherLocation {
+                data_dir: drive_c.join("ProgramData/Battle.net"),
+                config_dir: config_dirs.into_iter().next(),
+                drive_c: Some(drive_c),
+            });
+        }
+    }
+
+    locations
+}
+
+/// Folders inside a Wine prefix's `drive_c` where WoW is usually installed
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const PREFIX_INSTALL_DIRS: &[&str] = &[
+    "Program Files (x86)/World of Warcraft",
+    "Program Files/World of Warcraft",
+];
+
+/// Find WoW installations inside known Wine prefixes (Linux only)
+pub fn find_prefix_installs() -> Vec<PathBuf> {
+    #[allow(unused_mut)]
+    let mut paths: Vec<PathBuf> = Vec::new();
+
+    #[cfg(target_os = "linux")]
+    for prefix in wine_prefixes() {
+        for dir in PREFIX_INSTALL_DIRS {
+            let candidate = prefix.join("drive_c").join(dir);
+            if is_wow_root(&candidate) {
+                paths.push(candidate);
+            }
+        }
+    }
+
+    dedup_paths(paths)
+}
+
+/// Wine prefixes that may host WoW or the Battle.net launcher.
+///
+/// Covers plain Wine, Lutris and Bottles (native and Flatpak) and every
+/// Proton `compatdata` prefix in each Steam library folder.
+#[cfg(target_os = "linux")]
+fn wine_prefixes() -> Vec<PathBuf> {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+    let mut prefixes = vec![
+        home.join(".wine"),
+        home.join("Games/battlenet"),
+        home.join("Games/battle-net"),
+        home.join("Games/world-of-warcraft"),
+    ];
+
+    // Lutris game configs record the prefix each game runs in
+    let lutris_config_dirs = [
+        home.join(".config/lutris/games"),
+        home.join(".local/share/lutris/games"),
+        home.join(".var/app/net.lutris.Lutris/config/lutris/games"),
+        home.join(".var/app/net.lutris.Lutris/data/lutris/games"),
+    ];
+    for dir in &lutris_config_dirs {
+        for entry in read_dir_paths(dir) {
+            if let Ok(content) = fs::read_to_string(&entry) {
+                prefixes.extend(parse_lutris_prefix(&content));
+            }
+        }
+    }
+    prefixes.push(home.join(".var/app/net.lutris.Lutris/data/games/battlenet"));
+
+    // Every bottle is its own prefix
+    let bottles_dirs = [
+        home.join(".local/share/bottles/bottles"),
+        home.join(".var/app/com.usebottles.bottles/data/bottles/bottles"),
+    ];
+    for dir in &bottles_dirs {
+        prefixes.extend(read_dir_paths(dir));
+    }
+
+    // Proton prefixes live in `<library>/steamapps/compatdata/<appid>/pfx`
+    let steam_roots = [
+        home.join(".steam/steam"),
+        home.join(".local/share/Steam"),
+        home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+    ];
+    let mut libraries: Vec<PathBuf> = steam_roots.to_vec();
+    for root in &steam_roots {
+        let vdf_path = root.join("steamapps/libraryfolders.vdf");
+        if let Ok(content) = fs::read_to_string(&vdf_path) {
+            libraries.extend(parse_steam_library_folders(&content));
+        }
+    }
+    for library in dedup_paths(libraries) {
+        for compat in read_dir_paths(&library.join("steamapps/compatdata")) {
+            prefixes.push(compat.join("pfx"));
+        }
+    }
+
+    dedup_paths(prefixes.into_iter().filter(|p| p.join("drive_c").is_dir()))
+}
+
+/// List the entries of a directory, ignoring errors
+fn read_dir_paths(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+/// Parse Steam's `libraryfolders.vdf` and return every library `path`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn parse_steam_library_folders(content: &str) -> Vec<PathBuf> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let tokens: Vec<&str> = line
+                .split('"')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .collect();
+            match tokens.as_slice() {
+                [key, value] if key.eq_ignore_ascii_case("path") => {
+                    Some(PathBuf::from(value.replace("\\\\", "\\")))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Extract the `prefix:` entry from a Lutris game YAML config
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn parse_lutris_prefix(content: &str) -> Option<PathBuf> {
+    content.lines().find_map(|line| {
+        let value = line.trim().strip_prefix("prefix:")?.trim();
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        (!value.is_empty()).then(|| PathBuf::from(value))
+    })
+}
+
+/// Extract absolute install paths from a launcher `product.db` / `Launcher.db`.
+///
+/// These files are protobuf messages. Rather than depending on the schema,
+/// every length-delimited field is walked and the ones that decode as
+/// absolute filesystem paths are kept.
+pub fn extract_install_paths(bytes: &[u8]) -> Vec<PathBuf> {
+    let mut strings = Vec::new();
+    collect_protobuf_strings(bytes, 0, &mut strings);
+
+    let paths = strings
+        .into_iter()
+        .filter(|s| looks_like_absolute_path(s))
+        .map(PathBuf::from);
+    dedup_paths(paths)
+}
+
+/// Extract install paths from the launcher's `Battle.net.config` JSON.
+///
+/// `DefaultInstallPath` points at the folder games are installed into, so
+/// `World of Warcraft` is appended to it; any other absolute path values are
+/// returned as-is for the caller to verify.
+pub fn parse_launcher_config(content: &str) -> Vec<PathBuf> {
+    fn visit(key: Option<&str>, value: &serde_json::Value, out: &mut Vec<PathBuf>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (k, v) in map {
+                    visit(Some(k), v, out);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for v in items {
+                    visit(None, v, out);
+                }
+            }
+            serde_json::Value::String(s) if looks_like_absolute_path(s) => {
+                let path = PathBuf::from(s);
+                if key == Some("DefaultInstallPath") {
+                    out.push(path.join("World of Warcraft"));
+                }
+                out.push(path);
+            }
+            _ => {}
+        }
+    }
+
+    let mut paths = Vec::new();
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(content) {
+        visit(None, &value, &mut paths);
+    }
+    paths
+}
+
+/// Parse the output of `reg query ... /s /v InstallPath`
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn parse_reg_query_output(output: &str) -> Vec<PathBuf> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("InstallPath"))
+        .filter_map(|line| line.split_once("REG_SZ"))
+        .map(|(_, value)| value.trim())
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Query the registry for Blizzard `InstallPath` values
+#[cfg(target_os = "windows")]
+fn registry_install_paths() -> Vec<PathBuf> {
+    let keys = [
+        r"HKLM\SOFTWARE\WOW6432Node\Blizzard Entertainment",
+        r"HKLM\SOFTWARE\Blizzard Entertainment",
+    ];
+
+    let mut paths = Vec::new();
+    for key in keys {
+        let output = std::process::Command::new("reg")
+            .args(["query", key, "/s", "/v", "InstallPath"])
+            .output();
+        if let Ok(output) = output {
+            paths.extend(parse_reg_query_output(&String::from_utf8_lossy(
+                &output.stdout,
+            )));
+        }
+    }
+    paths
+}
+
+/// Reduce a launcher-reported path to the WoW installation root.
+///
+/// The registry and launcher often point at a flavor folder
+/// (`...\World of Warcraft\_retail_\`); the root is its parent.
+pub fn normalize_install_root(path: &Path) -> PathBuf {
+    let trimmed = path
+        .to_string_lossy()
+        .trim_end_matches(&['/', '\\'][..])
+        .to_string();
+    let path = PathBuf::from(trimmed);
+
+    let is_flavor_dir = path
+        .file_name()
+        .map(|name| {
+            let name = name.to_string_lossy().to_lowercase();
+            WOW_FLAVORS.iter().any(|f| *f == name)
+        })
+        .unwrap_or(false);
+
+    match path.parent() {
+        Some(parent) if is_flavor_dir => parent.to_path_buf(),
+        _ => path,
+    }
+}
+
+/// Whether a directory looks like a WoW installation root
+pub fn is_wow_root(path: &Path) -> bool {
+    WOW_FLAVORS.iter().any(|flavor| path.join(flavor).is_dir())
+}
+
+/// Flavor folder (e.g. `.../_retail_`) of a SavedVariables file laid out as
+/// `<flavor>/WTF/Account/<account>/SavedVariables/<file>`
+pub fn flavor_dir_of(sv_path: &Path) -> Option<PathBuf> {
+    let sv_dir = sv_path.parent()?;
+    let accounts = sv_dir.parent()?.parent()?;
+    let wtf = accounts.parent()?;
+    let is_named = |path: &Path, name: &str| {
+        path.file_name()
+            .is_some_and(|n| n.to_string_lossy().eq_ignore_ascii_case(name))
+    };
+    if !(is_named(sv_dir, "SavedVariables")
+        && is_named(accounts, "Account")
+        && is_named(wtf, "WTF"))
+    {
+        return None;
+    }
+    wtf.parent().map(Path::to_path_buf)
+}
+
+/// Whether the WeakAuras addon is installed in a flavor folder
+/// (`Interface/AddOns/WeakAuras`, matched case-insensitively for Wine installs)
+pub fn weakauras_installed_in(flavor_dir: &Path) -> bool {
+    ["Interface", "AddOns", "WeakAuras"]
+        .iter()
+        .try_fold(flavor_dir.to_path_buf(), |dir, name| child_dir(&dir, name))
+        .is_some()
+}
+
+/// Whether the WeakAuras addon is installed for the flavor a SavedVariables
+/// file belongs to. `None` if the file is not inside a WoW folder layout.
+pub fn weakauras_installed(sv_path: &Path) -> Option<bool> {
+    flavor_dir_of(sv_path).map(|flavor_dir| weakauras_installed_in(&flavor_dir))
+}
+
+/// Subdirectory of `dir` whose name matches `name` ignoring ASCII case
+fn child_dir(dir: &Path, name: &str) -> Option<PathBuf> {
+    let exact = dir.join(name);
+    if exact.is_dir() {
+        return Some(exact);
+    }
+    read_dir_paths(dir).into_iter().find(|path| {
+        path.is_dir()
+            && path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().eq_ignore_ascii_case(name))
+    })
+}
+
+/// Map a Windows path (`C:/...`) into a Wine prefix's `drive_c`
+fn map_windows_path(path: &Path, drive_c: Option<&Path>) -> PathBuf {
+    let Some(drive_c) = drive_c else {
+        return path.to_path_buf();
+    };
+
+    let s = path.to_string_lossy().replace('\\', "/");
+    match s.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("c:/") => drive_c.join(&s[3..]),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Remove duplicate paths while preserving first-seen order
+pub(crate) fn dedup_paths(paths: impl IntoIterator<Item = PathBuf>) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    paths
+        .into_iter()
+        .filter(|p| seen.insert(p.to_string_lossy().to_lowercase()))
+        .collect()
+}
+
+/// Whether a string is an absolute Windows (`C:\`, `C:/`) or Unix path
+fn looks_like_absolute_path(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    let is_drive_path = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'/' || bytes[2] == b'\\');
+    (is_drive_path || s.starts_with('/')) && !s.chars().any(|c| c.is_control())
+}
+
+/// Walk a protobuf message, collecting every length-delimited field that is valid UTF-8.
+///
+/// Returns `false` if the bytes are not a well-formed message.
+fn collect_protobuf_strings(data: &[u8], depth: usize, out: &mut Vec<String>) -> bool {
+    let mut pos = 0;
+    while pos < data.len() {
+        let Some(key) = read_varint(data, &mut pos) else {
+            return false;
+        };
+
+        match key & 0x7 {
+            // Varint
+            0 => {
+                if read_varint(data, &mut pos).is_none() {
+                    return false;
+                }
+            }
+            // 64-bit
+            1 => pos += 8,
+            // Length-delimited: string, bytes, or nested message
+            2 => {
+                let Some(len) = read_varint(data, &mut pos) else {
+                    return false;
+                };
+                let Some(end) = pos
+                    .checked_add(len as usize)
+                    .filter(|end| *end <= data.len())
+                else {
+                    return false;
+                };
+                let field = &data[pos..end];
+
+                let mut nested = Vec::new();
+                if depth < MAX_PROTOBUF_DEPTH
+                    && !field.is_empty()
+                    && collect_protobuf_strings(field, depth + 1, &mut nested)
+                {
+                    out.extend(nested);
+                }
+                if let Ok(s) = std::str::from_utf8(field) {
+                    if !s.is_empty() {
+                        out.push(s.to_string());
+                    }
+                }
+                pos = end;
+            }
+            // 32-bit
+            5 => pos += 4,
+            _ => return false,
+        }
+    }
+    pos == data.len()
+}
+
+/// Read a base-128 varint, advancing `pos`
+fn read_varint(data: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut result = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        result |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(result);
+        }
+    }
+    None
+}
@@ -0,0 +1,134 @@
+//! Auras that appear more than once in one batch.
+//!
+//! Pasting the same string twice, or loading two files that share an aura,
+//! puts several copies of one aura ID into a batch, and on import the last
+//! copy would silently overwrite the others. A [`DuplicatePolicy`] decides
+//! which copy is kept before anything is written.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::decoder::WeakAura;
+use crate::error::{Result, WeakAuraError};
+
+/// Which copy of an aura is kept when a batch holds it more than once.
+/// Identical copies are always reduced to the first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// Keep the copy that came first and drop later ones
+    #[default]
+    KeepFirst,
+    /// Keep the copy that came last, replacing earlier ones
+    KeepLast,
+    /// Keep every differing copy for the user to choose from; headless
+    /// imports refuse the batch instead
+    Ask,
+}
+
+impl DuplicatePolicy {
+    pub const ALL: [DuplicatePolicy; 3] = [
+        DuplicatePolicy::KeepFirst,
+        DuplicatePolicy::KeepLast,
+        DuplicatePolicy::Ask,
+    ];
+}
+
+impl fmt::Display for DuplicatePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DuplicatePolicy::KeepFirst => write!(f, "Keep first"),
+            DuplicatePolicy::KeepLast => write!(f, "Keep last"),
+            DuplicatePolicy::Ask => write!(f, "Ask"),
+        }
+    }
+}
+
+/// An aura ID found more than once in a batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Duplicate {
+    pub aura_id: String,
+    /// Position of every copy in the batch, in batch order
+    pub positions: Vec<usize>,
+    /// Whether all copies hold the same data
+    pub identical: bool,
+}
+
+impl Duplicate {
+    /// Positions of the copies `policy` drops
+    pub fn dropped(&self, policy: DuplicatePolicy) -> Vec<usize> {
+        match policy {
+            _ if self.identical => self.positions[1..].to_vec(),
+            DuplicatePolicy::KeepFirst => self.positions[1..].to_vec(),
+            DuplicatePolicy::KeepLast => self.positions[..self.positions.len() - 1].to_vec(),
+            DuplicatePolicy::Ask => Vec::new(),
+        }
+    }
+}
+
+/// Every aura ID that appears more than once among `auras`, in order of
+/// first appearance
+pub fn find_duplicates<'a>(auras: impl IntoIterator<Item = &'a WeakAura>) -> Vec<Duplicate> {
+    let mut by_id: HashMap<&str, Vec<(usize, &WeakAura)>> = HashMap::new();
+    let mut order = Vec::new();
+    for (position, aura) in auras.into_iter().enumerate() {
+        let copies = by_id.entry(aura.id.as_str()).or_default();
+        if copies.is_empty() {
+            order.push(aura.id.as_str());
+        }
+        copies.push((position, aura));
+    }
+
+    order
+        .into_iter()
+        .filter_map(|id| {
+            let copies = &by_id[id];
+            if copies.len() < 2 {
+                return None;
+            }
+            let first = copies[0].1;
+            Some(Duplicate {
+                aura_id: id.to_string(),
+                positions: copies.iter().map(|(position, _)| *position).collect(),
+                identical: copies[1..].iter().all(|(_, aura)| {
+                    aura.data == first.data && aura.child_data == first.child_data
+                }),
+            })
+        })
+        .collect()
+}
+
+/// Reduce a batch to one copy of each aura under `policy`, returning the
+/// remaining auras in batch order and the duplicates found. With
+/// [`DuplicatePolicy::Ask`] a batch holding differing copies is an error,
+/// since there is nobody to ask.
+pub fn dedup_auras(
+    auras: Vec<WeakAura>,
+    policy: DuplicatePolicy,
+) -> Result<(Vec<WeakAura>, Vec<Duplicate>)> {
+    let duplicates = find_duplicates(&auras);
+    if policy == DuplicatePolicy::Ask {
+        let differing: Vec<&str> = duplicates
+            .iter()
+            .filter(|d| !d.identical)
+            .map(|d| d.aura_id.as_str())
+            .collect();
+        if !differing.is_empty() {
+            return Err(WeakAuraError::InvalidValue(format!(
+                "the batch holds differing copies of: {}",
+                differing.join(", ")
+            )));
+        }
+    }
+
+    let dropped: HashSet<usize> = duplicates.iter().flat_map(|d| d.dropped(policy)).collect();
+    let kept = auras
+        .into_iter()
+        .enumerate()
+        .filter(|(position, _)| !dropped.contains(position))
+        .map(|(_, aura)| aura)
+        .collect();
+    Ok((kept, duplicates))
+}
@@ -7,6 +7,9 @@ pub enum WeakAuraError {
     #[error("Deserialization error: {0}")]
     DeserializationError(String),
 
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+
     #[error("Lua parse error: {0}")]
     LuaParseError(String),
 
@@ -15,6 +18,47 @@ pub enum WeakAuraError {
 
     #[error("File not found: {0}")]
     FileNotFound(String),
+
+    #[error("Config error: {0}")]
+    ConfigError(String),
+
+    #[error("Invalid pattern: {0}")]
+    InvalidPattern(String),
+
+    #[error("Invalid value: {0}")]
+    InvalidValue(String),
+
+    #[error("Script error: {0}")]
+    ScriptError(String),
+
+    #[error(
+        "Permission denied writing {}: {}",
+        .0.display(),
+        crate::fs_util::permission_hint(.0)
+    )]
+    PermissionDenied(std::path::PathBuf),
+
+    #[error("{0} is locked by another program")]
+    FileLocked(String),
+
+    #[error("HTTP error: {0}")]
+    HttpError(String),
+
+    #[error("Offline mode: {0} is not cached")]
+    Offline(String),
+
+    #[error(
+        "SavedVariables dbVersion {0} is newer than supported ({max}); saving could corrupt it",
+        max = crate::saved_variables::MAX_SUPPORTED_DB_VERSION
+    )]
+    UnsupportedDbVersion(u32),
+
+    #[error(
+        "This is {}, not a WeakAura; import it in {} instead",
+        .0.description(),
+        .0.addon()
+    )]
+    ForeignString(crate::format_sniffer::ForeignFormat),
 }
 
 pub type Result<T> = std::result::Result<T, WeakAuraError>;
@@ -0,0 +1,160 @@
+//! C ABI over the decoding and import core (enabled with the `ffi` feature).
+//!
+//! Build a shared library with
+//! `cargo rustc --lib --release --features ffi --crate-type cdylib` and generate
+//! `weakauras_mass_import.h` with `cbindgen --config cbindgen.toml`.
+//!
+//! Structured data crosses the boundary as UTF-8 JSON:
+//! - import strings are passed as a JSON array of strings
+//! - policies use the serde form of [`ResolutionPolicy`], e.g. `"skip_all"`,
+//!   `"newer_version_only"` or `{"update_categories": ["Trigger", "Load"]}`
+//! - results are the serde form of [`WeakAura`], [`ConflictDetectionResult`]
+//!   and [`ImportResult`]
+//!
+//! Every returned string is owned by the library and must be released with
+//! [`wa_string_free`]. On failure functions return NULL and [`wa_last_error`]
+//! describes what went wrong.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::path::PathBuf;
+use std::ptr;
+
+use serde::Serialize;
+
+use crate::api::Importer;
+use crate::decoder::WeakAuraDecoder;
+use crate::error::{Result, WeakAuraError};
+use crate::policy::ResolutionPolicy;
+use crate::saved_variables::SavedVariablesManager;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Run `f`, converting its output into an owned JSON C string (NULL on error)
+fn json_result<T: Serialize>(f: impl FnOnce() -> Result<T>) -> *mut c_char {
+    let json = f().and_then(|value| {
+        serde_json::to_string(&value)
+            .ok()
+            .and_then(|json| CString::new(json).ok())
+            .ok_or_else(|| WeakAuraError::SerializationError("Result is not valid JSON".into()))
+    });
+    match json {
+        Ok(json) => json.into_raw(),
+        Err(e) => {
+            set_last_error(e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Borrow a NUL-terminated UTF-8 argument
+///
+/// # Safety
+/// `ptr` must be NULL or point to a valid NUL-terminated string.
+unsafe fn arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str> {
+    if ptr.is_null() {
+        return Err(WeakAuraError::ConfigError(format!("{} is NULL", name)));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| WeakAuraError::ConfigError(format!("{} is not valid UTF-8", name)))
+}
+
+/// Parse a JSON argument
+///
+/// # Safety
+/// Same as [`arg`].
+unsafe fn json_arg<T: serde::de::DeserializeOwned>(ptr: *const c_char, name: &str) -> Result<T> {
+    serde_json::from_str(arg(ptr, name)?)
+        .map_err(|e| WeakAuraError::DeserializationError(format!("{}: {}", name, e)))
+}
+
+/// Decode one import string. Returns the aura as JSON.
+///
+/// # Safety
+/// `import_string` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn wa_decode(import_string: *const c_char) -> *mut c_char {
+    json_result(|| WeakAuraDecoder::decode(arg(import_string, "import_string")?))
+}
+
+/// Detect conflicts of the given import strings (JSON array) against the
+/// SavedVariables file at `saved_variables_path`. Returns the detection result as JSON.
+///
+/// # Safety
+/// Both arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn wa_detect_conflicts(
+    saved_variables_path: *const c_char,
+    import_strings_json: *const c_char,
+) -> *mut c_char {
+    json_result(|| {
+        let path = PathBuf::from(arg(saved_variables_path, "saved_variables_path")?);
+        let strings: Vec<String> = json_arg(import_strings_json, "import_strings_json")?;
+        let auras = strings
+            .iter()
+            .map(|s| WeakAuraDecoder::decode(s))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut manager = SavedVariablesManager::new(path);
+        if manager.path.exists() {
+            manager.load()?;
+        }
+        Ok(manager.detect_conflicts(&auras))
+    })
+}
+
+/// Import the given import strings (JSON array) into the SavedVariables file,
+/// resolving conflicts with `policy_json` (NULL = default policy). With
+/// `dry_run` the file is not written. Returns the import result as JSON.
+///
+/// # Safety
+/// `saved_variables_path` and `import_strings_json` must be valid NUL-terminated
+/// strings; `policy_json` must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn wa_import_with_policy(
+    saved_variables_path: *const c_char,
+    import_strings_json: *const c_char,
+    policy_json: *const c_char,
+    dry_run: bool,
+) -> *mut c_char {
+    json_result(|| {
+        let path = arg(saved_variables_path, "saved_variables_path")?;
+        let strings: Vec<String> = json_arg(import_strings_json, "import_strings_json")?;
+        let policy: ResolutionPolicy = if policy_json.is_null() {
+            ResolutionPolicy::default()
+        } else {
+            json_arg(policy_json, "policy_json")?
+        };
+        Importer::new(path)
+            .add_strings(strings)
+            .policy(policy)
+            .dry_run(dry_run)
+            .run()
+    })
+}
+
+/// Message of the last error on this thread, or NULL. The pointer stays valid
+/// until the next failing call on the same thread; do not free it.
+#[no_mangle]
+pub extern "C" fn wa_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+/// Release a string returned by this library
+///
+/// # Safety
+/// `s` must be NULL or a pointer returned by this library that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn wa_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
@@ -0,0 +1,190 @@
+//! Spreadsheet round-trips of existing auras' fields.
+//!
+//! [`SavedVariablesManager::export_fields_csv`] writes the ID, offsets, load
+//! classes and specializations and description of stored auras as CSV. Once
+//! the sheet has been edited elsewhere,
+//! [`SavedVariablesManager::apply_fields_csv`] writes the changed cells back
+//! in one go. The `id` column names the aura of a row; the other columns may
+//! be left out, and the fields they hold are then kept.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::error::{Result, WeakAuraError};
+use crate::model::AuraProperties;
+use crate::saved_variables::SavedVariablesManager;
+use crate::util::{csv_field, parse_csv};
+
+/// Columns of an exported sheet, in order
+pub const FIELD_COLUMNS: [&str; 6] = ["id", "xOffset", "yOffset", "classes", "specs", "desc"];
+
+/// Separator of several classes or specializations in one cell
+const LIST_SEPARATOR: char = ';';
+
+/// What applying a sheet changed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FieldUpdateResult {
+    /// Auras whose fields changed, in sheet order
+    pub updated: Vec<String>,
+    /// Rows that matched their aura already
+    pub unchanged: usize,
+    /// IDs of rows without an aura in the file
+    pub missing: Vec<String>,
+}
+
+impl FieldUpdateResult {
+    /// One-line summary, e.g. "3 aura(s) updated, 2 unchanged, 1 not found"
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} aura(s) updated, {} unchanged",
+            self.updated.len(),
+            self.unchanged
+        );
+        if !self.missing.is_empty() {
+            summary.push_str(&format!(", {} not found", self.missing.len()));
+        }
+        summary
+    }
+}
+
+impl SavedVariablesManager {
+    /// CSV sheet with a header row and one row per aura of `ids`, in that
+    /// order; all auras of the file when `ids` is empty. IDs that are not in
+    /// the file are left out.
+    pub fn export_fields_csv(&self, ids: &[String]) -> String {
+        let mut out = FIELD_COLUMNS.join(",");
+        out.push('\n');
+        let ids: Vec<&String> = if ids.is_empty() {
+            self.displays.keys().collect()
+        } else {
+            ids.iter().collect()
+        };
+        for id in ids {
+            let Some(properties) = self
+                .displays
+                .get(id)
+                .and_then(|data| AuraProperties::from_data(data).ok())
+            else {
+                continue;
+            };
+            let row = [
+                id.clone(),
+                properties.x_offset.to_string(),
+                properties.y_offset.to_string(),
+                properties.classes.join(&LIST_SEPARATOR.to_string()),
+                properties
+                    .specs
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(&LIST_SEPARATOR.to_string()),
+                properties.desc,
+            ];
+            let row: Vec<String> = row.iter().map(|cell| csv_field(cell)).collect();
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Apply an edited sheet. Every row is checked before anything is
+    /// written, so a malformed cell or an unknown column changes nothing and
+    /// is reported with its row. Call [`SavedVariablesManager::save`] to
+    /// persist.
+    pub fn apply_fields_csv(&mut self, text: &str) -> Result<FieldUpdateResult> {
+        let invalid = |message: String| WeakAuraError::InvalidValue(message);
+        let mut rows = parse_csv(text)?.into_iter();
+        let Some(header) = rows.next() else {
+            return Ok(FieldUpdateResult::default());
+        };
+        let columns: Vec<String> = header.iter().map(|name| name.trim().to_string()).collect();
+        if let Some(unknown) = columns
+            .iter()
+            .find(|name| !FIELD_COLUMNS.contains(&name.as_str()))
+        {
+            return Err(invalid(format!(
+                "unknown column '{}'; expected {}",
+                unknown,
+                FIELD_COLUMNS.join(", ")
+            )));
+        }
+        let Some(id_column) = columns.iter().position(|name| name == "id") else {
+            return Err(invalid("the sheet has no 'id' column".to_string()));
+        };
+
+        let mut result = FieldUpdateResult::default();
+        let mut seen = HashSet::new();
+        let mut updates = Vec::new();
+        for (index, row) in rows.enumerate() {
+            let row_error = |message: String| invalid(format!("row {}: {}", index + 1, message));
+            let id = row.get(id_column).map(|id| id.trim()).unwrap_or_default();
+            if id.is_empty() {
+                return Err(row_error("the id is empty".to_string()));
+            }
+            if !seen.insert(id.to_string()) {
+                return Err(row_error(format!("'{}' is listed more than once", id)));
+            }
+            let Some(data) = self.displays.get(id) else {
+                result.missing.push(id.to_string());
+                continue;
+            };
+
+            let current = AuraProperties::from_data(data).map_err(|e| row_error(e.to_string()))?;
+            let mut properties = current.clone();
+            for (column, cell) in columns.iter().zip(&row) {
+                set_column(&mut properties, column, cell).map_err(row_error)?;
+            }
+            properties
+                .validate()
+                .map_err(|e| row_error(e.to_string()))?;
+
+            if properties == current {
+                result.unchanged += 1;
+            } else {
+                updates.push((id.to_string(), properties));
+            }
+        }
+
+        result.updated = self.set_properties(&updates);
+        result.unchanged += updates.len() - result.updated.len();
+        Ok(result)
+    }
+}
+
+/// Write one cell into the properties it belongs to
+fn set_column(
+    properties: &mut AuraProperties,
+    column: &str,
+    cell: &str,
+) -> std::result::Result<(), String> {
+    let cell = cell.trim();
+    let number = |cell: &str| -> std::result::Result<f64, String> {
+        if cell.is_empty() {
+            return Ok(0.0);
+        }
+        cell.parse()
+            .map_err(|_| format!("'{}' in {} is not a number", cell, column))
+    };
+    let list = || {
+        cell.split(LIST_SEPARATOR)
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+    };
+    match column {
+        "xOffset" => properties.x_offset = number(cell)?,
+        "yOffset" => properties.y_offset = number(cell)?,
+        "classes" => properties.classes = list().map(str::to_ascii_uppercase).collect(),
+        "specs" => {
+            properties.specs = list()
+                .map(|spec| {
+                    spec.parse()
+                        .map_err(|_| format!("'{}' in specs is not a specialization ID", spec))
+                })
+                .collect::<std::result::Result<_, _>>()?
+        }
+        "desc" => properties.desc = cell.to_string(),
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,280 @@
+//! "What changed" between two whole SavedVariables files.
+//!
+//! [`SavedVariablesManager::diff_files`] compares the displays of two files by
+//! ID, e.g. a copy from before an addon manager or a guildmate touched the
+//! file and the file afterwards, and reports which auras were added, removed
+//! or modified, with the changed fields of each aura grouped by category.
+//! The group hierarchy (`parent`, `controlledChildren`) is left out of the
+//! field comparison and reported on its own: auras moved to another group and
+//! groups whose children were reordered.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::categories::UpdateCategory;
+use crate::decoder::LuaValue;
+use crate::error::{Result, WeakAuraError};
+use crate::saved_variables::{changed_fields_by_category, SavedVariablesManager};
+
+/// One aura that is in both files but differs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModifiedAura {
+    pub id: String,
+    /// Changed top-level fields by category, categories and fields sorted
+    pub changes: Vec<(UpdateCategory, Vec<String>)>,
+}
+
+/// One aura that is in both files under a different group
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MovedAura {
+    pub id: String,
+    /// Group in `old`, `None` at the top level
+    pub from: Option<String>,
+    /// Group in `new`, `None` at the top level
+    pub to: Option<String>,
+}
+
+/// How many modified auras changed in one category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CategoryStat {
+    pub category: UpdateCategory,
+    /// Modified auras with a change in this category
+    pub auras: usize,
+    /// Changed fields of this category, summed over those auras
+    pub fields: usize,
+}
+
+/// Differences between two SavedVariables files, from `old` to `new`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileDiff {
+    pub old: PathBuf,
+    pub new: PathBuf,
+    /// IDs only in `new`, sorted
+    pub added: Vec<String>,
+    /// IDs only in `old`, sorted
+    pub removed: Vec<String>,
+    /// Auras in both files that differ, sorted by ID
+    pub modified: Vec<ModifiedAura>,
+    /// Auras in both files under a different group, sorted by ID
+    pub moved: Vec<MovedAura>,
+    /// Groups in both files with the same children in a different order,
+    /// sorted. Children joining or leaving a group show up in `moved`.
+    pub reordered: Vec<String>,
+    /// Auras in both files that are the same, including their place in the
+    /// hierarchy
+    pub unchanged: usize,
+}
+
+impl FileDiff {
+    /// Whether both files hold the same auras in the same hierarchy
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.moved.is_empty()
+            && self.reordered.is_empty()
+    }
+
+    /// One-line summary, e.g. "3 added, 1 removed, 12 modified, 140 unchanged".
+    /// Moved auras and reordered groups are counted when there are any.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![
+            format!("{} added", self.added.len()),
+            format!("{} removed", self.removed.len()),
+            format!("{} modified", self.modified.len()),
+        ];
+        if !self.moved.is_empty() {
+            parts.push(format!("{} moved", self.moved.len()));
+        }
+        if !self.reordered.is_empty() {
+            parts.push(format!("{} reordered", self.reordered.len()));
+        }
+        parts.push(format!("{} unchanged", self.unchanged));
+        parts.join(", ")
+    }
+
+    /// Per-category counts over the modified auras, most changed auras first
+    pub fn category_stats(&self) -> Vec<CategoryStat> {
+        let mut stats: Vec<CategoryStat> = UpdateCategory::all()
+            .into_iter()
+            .filter_map(|category| {
+                let fields: Vec<usize> = self
+                    .modified
+                    .iter()
+                    .filter_map(|aura| {
+                        aura.changes
+                            .iter()
+                            .find(|(c, _)| *c == category)
+                            .map(|(_, fields)| fields.len())
+                    })
+                    .collect();
+                (!fields.is_empty()).then(|| CategoryStat {
+                    category,
+                    auras: fields.len(),
+                    fields: fields.iter().sum(),
+                })
+            })
+            .collect();
+        stats.sort_by_key(|stat| std::cmp::Reverse(stat.auras));
+        stats
+    }
+
+    /// Plain-text report, as printed by the `diff` subcommand
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "Comparing {}\n       to {}\n{}\n",
+            self.old.display(),
+            self.new.display(),
+            self.summary()
+        );
+        let stats = self.category_stats();
+        if !stats.is_empty() {
+            out.push_str("\nChanges by category:\n");
+            for stat in stats {
+                out.push_str(&format!(
+                    "  {:<20} {} aura(s), {} field(s)\n",
+                    stat.category.display_name(),
+                    stat.auras,
+                    stat.fields
+                ));
+            }
+        }
+        for (heading, marker, ids) in [("Added", '+', &self.added), ("Removed", '-', &self.removed)]
+        {
+            if !ids.is_empty() {
+                out.push_str(&format!("\n{}:\n", heading));
+                for id in ids {
+                    out.push_str(&format!("  {} {}\n", marker, id));
+                }
+            }
+        }
+        if !self.modified.is_empty() {
+            out.push_str("\nModified:\n");
+            for aura in &self.modified {
+                out.push_str(&format!("  ~ {}\n", aura.id));
+                for (category, fields) in &aura.changes {
+                    out.push_str(&format!(
+                        "      {}: {}\n",
+                        category.display_name(),
+                        fields.join(", ")
+                    ));
+                }
+            }
+        }
+        if !self.moved.is_empty() {
+            out.push_str("\nMoved:\n");
+            for aura in &self.moved {
+                out.push_str(&format!("  > {}: {}\n", aura.id, aura.move_text()));
+            }
+        }
+        if !self.reordered.is_empty() {
+            out.push_str("\nChildren reordered:\n");
+            for id in &self.reordered {
+                out.push_str(&format!("  = {}\n", id));
+            }
+        }
+        out
+    }
+
+    /// Serialize to pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| WeakAuraError::SerializationError(e.to_string()))
+    }
+}
+
+impl MovedAura {
+    /// Where the aura moved, e.g. "Group A -> top level"
+    pub fn move_text(&self) -> String {
+        let place = |group: &Option<String>| group.as_deref().unwrap_or("top level").to_string();
+        format!("{} -> {}", place(&self.from), place(&self.to))
+    }
+}
+
+/// Group holding an aura, `None` at the top level
+fn parent_of(aura: &LuaValue) -> Option<String> {
+    aura.get_path("parent")
+        .and_then(LuaValue::as_str)
+        .map(str::to_string)
+}
+
+/// Child IDs of a group in order, empty for other auras
+fn children_of(aura: &LuaValue) -> Vec<&str> {
+    aura.get_path("controlledChildren")
+        .and_then(LuaValue::as_array)
+        .map(|children| children.iter().filter_map(LuaValue::as_str).collect())
+        .unwrap_or_default()
+}
+
+impl SavedVariablesManager {
+    /// Compare the auras of the SavedVariables files `old` and `new`
+    pub fn diff_files(old: &Path, new: &Path) -> Result<FileDiff> {
+        let load = |path: &Path| -> Result<SavedVariablesManager> {
+            let mut manager = SavedVariablesManager::new(path.to_path_buf());
+            manager.load()?;
+            Ok(manager)
+        };
+        let (old_file, new_file) = (load(old)?, load(new)?);
+
+        let mut diff = FileDiff {
+            old: old.to_path_buf(),
+            new: new.to_path_buf(),
+            added: Vec::new(),
+            removed: Vec::new(),
+            modified: Vec::new(),
+            moved: Vec::new(),
+            reordered: Vec::new(),
+            unchanged: 0,
+        };
+        for (id, before) in &old_file.displays {
+            match new_file.displays.get(id) {
+                None => diff.removed.push(id.clone()),
+                Some(after) => {
+                    let changes = changed_fields_by_category(before, after);
+                    let (from, to) = (parent_of(before), parent_of(after));
+                    let (old_children, new_children) = (children_of(before), children_of(after));
+                    let reordered = old_children != new_children && {
+                        let (mut old_sorted, mut new_sorted) =
+                            (old_children.clone(), new_children.clone());
+                        old_sorted.sort_unstable();
+                        new_sorted.sort_unstable();
+                        old_sorted == new_sorted
+                    };
+
+                    if changes.is_empty() && from == to && !reordered {
+                        diff.unchanged += 1;
+                    }
+                    if !changes.is_empty() {
+                        diff.modified.push(ModifiedAura {
+                            id: id.clone(),
+                            changes,
+                        });
+                    }
+                    if from != to {
+                        diff.moved.push(MovedAura {
+                            id: id.clone(),
+                            from,
+                            to,
+                        });
+                    }
+                    if reordered {
+                        diff.reordered.push(id.clone());
+                    }
+                }
+            }
+        }
+        diff.added = new_file
+            .displays
+            .keys()
+            .filter(|id| !old_file.displays.contains_key(*id))
+            .cloned()
+            .collect();
+        diff.added.sort();
+        diff.removed.sort();
+        diff.modified.sort_by(|a, b| a.id.cmp(&b.id));
+        diff.moved.sort_by(|a, b| a.id.cmp(&b.id));
+        diff.reordered.sort();
+        Ok(diff)
+    }
+}
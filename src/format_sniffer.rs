@@ -0,0 +1,61 @@
+//! Recognizing export strings of other addons.
+//!
+//! ElvUI and Plater profile strings look much like WeakAura strings and are
+//! often pasted here by mistake. [`sniff`] checks for their signatures before
+//! decoding, so the user learns what they pasted instead of getting a generic
+//! decode error.
+
+/// An addon whose export strings are not WeakAuras
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForeignFormat {
+    ElvUi,
+    Plater,
+}
+
+impl ForeignFormat {
+    /// Name of the addon that imports the string
+    pub fn addon(&self) -> &'static str {
+        match self {
+            Self::ElvUi => "ElvUI",
+            Self::Plater => "Plater",
+        }
+    }
+
+    /// What the string is, with its article, e.g. "an ElvUI profile string"
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::ElvUi => "an ElvUI profile string",
+            Self::Plater => "a Plater profile string",
+        }
+    }
+}
+
+/// Sections ElvUI appends to its plain-text (Lua table) exports
+const ELVUI_PLAIN_SECTIONS: &[&str] = &[
+    "::profile::",
+    "::private::",
+    "::global::",
+    "::filters::",
+    "::styleFilters::",
+];
+
+/// The addon a string was exported from, when it is a known non-WeakAura
+/// format. `s` is expected to be trimmed.
+pub fn sniff(s: &str) -> Option<ForeignFormat> {
+    if is_elvui_compressed(s) || ELVUI_PLAIN_SECTIONS.iter().any(|tag| s.contains(tag)) {
+        return Some(ForeignFormat::ElvUi);
+    }
+    if s.starts_with("!PLATER:") {
+        return Some(ForeignFormat::Plater);
+    }
+    None
+}
+
+/// ElvUI's compressed exports start with `!E<version>!`, e.g. `!E1!`
+fn is_elvui_compressed(s: &str) -> bool {
+    s.strip_prefix("!E")
+        .and_then(|rest| rest.split_once('!'))
+        .is_some_and(|(version, _)| {
+            !version.is_empty() && version.chars().all(|c| c.is_ascii_digit())
+        })
+}
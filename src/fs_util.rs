@@ -0,0 +1,138 @@
+//! File writes with errors that explain denied access.
+//!
+//! WoW is often installed below `Program Files`, where Windows (UAC) only
+//! lets elevated programs write. A plain IO error says little there, so the
+//! writes of [`SavedVariablesManager::save`](crate::saved_variables::SavedVariablesManager::save)
+//! go through this module, which turns denied access into
+//! [`WeakAuraError::PermissionDenied`] with a hint on what to do about it.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::{Result, WeakAuraError};
+
+/// Whether an IO error means the current user may not write the file. The
+/// standard library maps the platform codes (`EPERM`/`EACCES` on Unix,
+/// `ERROR_ACCESS_DENIED` on Windows) to this kind.
+pub fn is_permission_denied(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::PermissionDenied
+}
+
+/// Whether `path` lies below a `Program Files` folder, which only elevated
+/// programs may write to on Windows
+pub fn is_protected_location(path: &Path) -> bool {
+    path.components().any(|component| {
+        let name = component.as_os_str().to_string_lossy().to_ascii_lowercase();
+        name == "program files" || name == "program files (x86)"
+    })
+}
+
+/// What the user can do when writing `path` was denied
+pub fn permission_hint(path: &Path) -> &'static str {
+    if is_protected_location(path) {
+        "the folder is below Program Files, where Windows only lets programs run as \
+         administrator write; restart this tool as administrator, move World of Warcraft \
+         out of Program Files, or save to another file"
+    } else {
+        "check that the file and its folder are not read-only and that your account may \
+         write to them, or save to another file"
+    }
+}
+
+/// Turn an IO error on `path` into [`WeakAuraError::PermissionDenied`] when
+/// access was denied
+pub fn map_io_error(path: &Path, error: io::Error) -> WeakAuraError {
+    if is_permission_denied(&error) {
+        WeakAuraError::PermissionDenied(path.to_path_buf())
+    } else {
+        WeakAuraError::IoError(error)
+    }
+}
+
+/// [`fs::write`] with permission-aware errors
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    fs::write(path, contents).map_err(|e| map_io_error(path, e))
+}
+
+/// [`fs::copy`] with permission-aware errors, reported against `to`
+pub fn copy(from: &Path, to: &Path) -> Result<()> {
+    fs::copy(from, to)
+        .map(|_| ())
+        .map_err(|e| map_io_error(to, e))
+}
+
+/// Open (creating if needed) a file for reading and writing without
+/// truncating it, with permission-aware errors
+pub fn open_rw(path: &Path) -> Result<fs::File> {
+    fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|e| map_io_error(path, e))
+}
+
+/// Probe whether `path` can be saved without changing it: the file, when it
+/// exists, must open for writing, and its folder must take new files for the
+/// backup and lock file written alongside it
+pub fn check_writable(path: &Path) -> Result<()> {
+    if path.exists() {
+        fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|e| map_io_error(path, e))?;
+    }
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let probe = dir.join(format!(".write-probe-{}", std::process::id()));
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|e| map_io_error(dir, e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Whether [`relaunch_elevated`] is available on this platform
+pub fn can_relaunch_elevated() -> bool {
+    cfg!(target_os = "windows")
+}
+
+/// Start this executable again with the same arguments, asking Windows for
+/// administrator rights through the UAC prompt. The caller exits afterwards.
+#[cfg(target_os = "windows")]
+pub fn relaunch_elevated() -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let quote = |arg: &str| format!("'{}'", arg.replace('\'', "''"));
+    let mut command = format!(
+        "Start-Process -FilePath {} -Verb RunAs",
+        quote(&exe.to_string_lossy())
+    );
+    let args: Vec<String> = std::env::args().skip(1).map(|arg| quote(&arg)).collect();
+    if !args.is_empty() {
+        command.push_str(&format!(" -ArgumentList {}", args.join(",")));
+    }
+    let status = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &command])
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(WeakAuraError::ConfigError(
+            "Restarting as administrator was cancelled or failed".to_string(),
+        ))
+    }
+}
+
+/// Start this executable again with administrator rights (Windows only)
+#[cfg(not(target_os = "windows"))]
+pub fn relaunch_elevated() -> Result<()> {
+    Err(WeakAuraError::ConfigError(
+        "Restarting as administrator is only available on Windows".to_string(),
+    ))
+}
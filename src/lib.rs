@@ -3,9 +3,49 @@
 //! This library provides functionality for decoding WeakAura import strings
 //! and managing WeakAuras SavedVariables files.
 
+pub mod annotations;
+pub mod api;
+pub mod archive_source;
 pub mod categories;
+pub mod chunking;
+pub mod companion;
+pub mod companion_data;
+pub mod config;
+pub mod crash;
 pub mod decoder;
+pub mod discovery;
+pub mod duplicates;
 pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod field_sheet;
+pub mod file_diff;
+pub mod format_sniffer;
+pub mod fs_util;
 pub mod lua_parser;
+pub mod media;
+pub mod model;
+pub mod nesting;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod net;
+pub mod pack;
+pub mod policy;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod references;
+pub mod repair;
+pub mod report;
+pub mod rpc;
 pub mod saved_variables;
-pub mod util;
+pub mod scan;
+pub mod scripting;
+pub mod selection;
+pub mod session;
+pub mod snapshots;
+pub mod templates;
+pub mod transforms;
+pub mod tree_cache;
+pub mod uri;
+pub(crate) mod util;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -5,18 +5,36 @@
 //! - `WeakAurasSaved` - Contains all saved auras and settings
 //! - `WeakAurasDisplays` - Contains display metadata
 
-use crate::decoder::LuaValue;
+use crate::decoder::{LuaTable, LuaValue, MAX_TABLE_DEPTH};
 use crate::error::{Result, WeakAuraError};
 use crate::util;
 use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
 
 /// Represents the parsed WeakAuras SavedVariables
 #[derive(Debug, Clone, Default)]
 pub struct WeakAurasSaved {
     /// All saved displays/auras
-    pub displays: HashMap<String, LuaValue>,
+    pub displays: LuaTable,
     /// Other data
-    pub other: HashMap<String, LuaValue>,
+    pub other: LuaTable,
+    /// Byte range of each display's value in the parsed content
+    pub display_spans: HashMap<String, Range<usize>>,
+}
+
+/// Where a damaged SavedVariables file stopped parsing, found by
+/// [`LuaParser::parse_salvaging`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDamage {
+    /// Byte offset of the first entry that could not be read
+    pub offset: usize,
+    /// Why it could not be read
+    pub error: String,
+    /// Whether the damage is inside the `displays` table
+    pub in_displays: bool,
+    /// ID of the display that was cut off, when its key was still readable
+    pub display: Option<String>,
 }
 
 /// Parser for Lua SavedVariables files
@@ -25,7 +43,20 @@ pub struct LuaParser;
 impl LuaParser {
     /// Parse SavedVariables content
     pub fn parse(content: &str) -> Result<WeakAurasSaved> {
+        Self::parse_with(content, false).map(|(saved, _)| saved)
+    }
+
+    /// Parse SavedVariables content that may be truncated or damaged. Every
+    /// entry before the damage is kept, both in the `displays` table and in
+    /// the other fields; the entry the damage is in and everything after it
+    /// are dropped. Intact content parses as with [`LuaParser::parse`].
+    pub fn parse_salvaging(content: &str) -> Result<(WeakAurasSaved, Option<ParseDamage>)> {
+        Self::parse_with(content, true)
+    }
+
+    fn parse_with(content: &str, salvage: bool) -> Result<(WeakAurasSaved, Option<ParseDamage>)> {
         let mut saved = WeakAurasSaved::default();
+        let mut damage = None;
 
         // Find WeakAurasSaved assignment
         // Format: WeakAurasSaved = { ... }
@@ -34,28 +65,50 @@ impl LuaParser {
                 let table_start = start + eq_pos + 1;
                 if let Some(brace_pos) = content[table_start..].find('{') {
                     let table_content_start = table_start + brace_pos;
-                    if let Some((table_value, _)) =
-                        Self::parse_table(&content[table_content_start..])?
-                    {
-                        if let Some(table) = table_value.as_table() {
-                            // Extract displays
-                            if let Some(displays) = table.get("displays").and_then(|v| v.as_table())
-                            {
-                                saved.displays = displays.clone();
-                            }
-                            // Store other fields
-                            for (key, value) in table {
-                                if key != "displays" {
-                                    saved.other.insert(key.clone(), value.clone());
-                                }
+                    // Displays sit two levels down, and may nest as deep as
+                    // a table on its own
+                    let mut parser =
+                        LuaTableParser::new(&content[table_content_start..], MAX_TABLE_DEPTH + 2);
+                    parser.salvage = salvage;
+                    let table_value = parser.parse_table()?;
+                    if let Some(table) = table_value.as_table() {
+                        // Extract displays
+                        if let Some(displays) = table.get("displays").and_then(|v| v.as_table()) {
+                            saved.displays = displays.clone();
+                        }
+                        // Store other fields
+                        for (key, value) in table {
+                            if key != "displays" {
+                                saved.other.insert(key.clone(), value.clone());
                             }
                         }
                     }
+                    saved.display_spans = parser
+                        .display_spans
+                        .into_iter()
+                        .map(|(id, span)| {
+                            (
+                                id,
+                                span.start + table_content_start..span.end + table_content_start,
+                            )
+                        })
+                        .collect();
+                    damage = parser.damage.map(|damage| ParseDamage {
+                        offset: damage.offset + table_content_start,
+                        ..damage
+                    });
                 }
             }
         }
 
-        Ok(saved)
+        Ok((saved, damage))
+    }
+
+    /// Parse a single Lua table literal, e.g. the output of [`LuaParser::serialize`]
+    pub fn parse_value(input: &str) -> Result<LuaValue> {
+        Self::parse_table(input)?
+            .map(|(value, _)| value)
+            .ok_or_else(|| WeakAuraError::LuaParseError("Expected '{'".to_string()))
     }
 
     /// Parse a Lua table from string
@@ -65,13 +118,24 @@ impl LuaParser {
             return Ok(None);
         }
 
-        let mut parser = LuaTableParser::new(input);
+        let mut parser = LuaTableParser::new(input, MAX_TABLE_DEPTH);
         let value = parser.parse_table()?;
         Ok(Some((value, parser.pos)))
     }
 
-    /// Serialize a LuaValue back to Lua string format
+    /// Serialize a LuaValue back to Lua string format, keeping table keys in
+    /// insertion order (the order they were parsed or decoded in)
     pub fn serialize(value: &LuaValue, indent: usize) -> String {
+        Self::serialize_with(value, indent, false)
+    }
+
+    /// Serialize a LuaValue with table keys sorted, for output that does not
+    /// depend on where the data came from
+    pub fn serialize_sorted(value: &LuaValue, indent: usize) -> String {
+        Self::serialize_with(value, indent, true)
+    }
+
+    fn serialize_with(value: &LuaValue, indent: usize, sort_keys: bool) -> String {
         let indent_str = "\t".repeat(indent);
         match value {
             LuaValue::Nil => "nil".to_string(),
@@ -85,7 +149,7 @@ impl LuaParser {
                     } else {
                         "-math.huge".to_string()
                     }
-                } else if n.fract() == 0.0 {
+                } else if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
                     format!("{}", *n as i64)
                 } else {
                     format!("{}", n)
@@ -98,7 +162,7 @@ impl LuaParser {
                     result.push_str(&format!(
                         "{}\t{}, -- [{}]\n",
                         indent_str,
-                        Self::serialize(v, indent + 1),
+                        Self::serialize_with(v, indent + 1, sort_keys),
                         i + 1
                     ));
                 }
@@ -107,9 +171,7 @@ impl LuaParser {
             }
             LuaValue::Table(table) => {
                 let mut result = String::from("{\n");
-                let mut keys: Vec<_> = table.keys().collect();
-                keys.sort();
-                for key in keys {
+                for key in ordered_keys(table, sort_keys) {
                     let value = &table[key];
                     // All keys in WeakAuras SavedVariables use ["key"] format
                     let key_str = format!("[\"{}\"]", util::escape_lua_string(key));
@@ -117,7 +179,7 @@ impl LuaParser {
                         "{}\t{} = {},\n",
                         indent_str,
                         key_str,
-                        Self::serialize(value, indent + 1)
+                        Self::serialize_with(value, indent + 1, sort_keys)
                     ));
                 }
                 result.push_str(&format!("{}}}", indent_str));
@@ -132,22 +194,20 @@ impl LuaParser {
                     result.push_str(&format!(
                         "{}\t{}, -- [{}]\n",
                         indent_str,
-                        Self::serialize(v, indent + 1),
+                        Self::serialize_with(v, indent + 1, sort_keys),
                         i + 1
                     ));
                 }
 
                 // Hash part - use explicit string keys
-                let mut keys: Vec<_> = hash.keys().collect();
-                keys.sort();
-                for key in keys {
+                for key in ordered_keys(hash, sort_keys) {
                     let value = &hash[key];
                     let key_str = format!("[\"{}\"]", util::escape_lua_string(key));
                     result.push_str(&format!(
                         "{}\t{} = {},\n",
                         indent_str,
                         key_str,
-                        Self::serialize(value, indent + 1)
+                        Self::serialize_with(value, indent + 1, sort_keys)
                     ));
                 }
 
@@ -158,18 +218,70 @@ impl LuaParser {
     }
 }
 
+/// Keys of a table in insertion order, or sorted
+pub(crate) fn ordered_keys(table: &LuaTable, sort: bool) -> Vec<&String> {
+    let mut keys: Vec<&String> = table.keys().collect();
+    if sort {
+        keys.sort();
+    }
+    keys
+}
+
+/// Most `nil`s filled in to extend the array part of a mixed table up to an
+/// explicit index; keys further out stay sparse
+const MAX_ARRAY_GAP: usize = 1024;
+
 /// Internal parser for Lua tables
 struct LuaTableParser<'a> {
     input: &'a str,
     pos: usize,
+    /// Nesting level of the table being parsed (1 = outermost)
+    depth: usize,
+    /// Deepest nesting level accepted, so pathological input fails with an
+    /// error instead of overflowing the stack
+    max_depth: usize,
+    /// Whether the outermost table's `displays` entry is being parsed
+    in_displays: bool,
+    /// Where each entry of the `displays` table was found
+    display_spans: Vec<(String, Range<usize>)>,
+    /// Keep the entries of the outermost and `displays` tables read before
+    /// an error instead of failing
+    salvage: bool,
+    /// Display whose value is being parsed
+    current_display: Option<String>,
+    /// First error met while salvaging; nothing after it is read
+    damage: Option<ParseDamage>,
 }
 
 impl<'a> LuaTableParser<'a> {
-    fn new(input: &'a str) -> Self {
-        Self { input, pos: 0 }
+    fn new(input: &'a str, max_depth: usize) -> Self {
+        Self {
+            input,
+            pos: 0,
+            depth: 0,
+            max_depth,
+            in_displays: false,
+            display_spans: Vec::new(),
+            salvage: false,
+            current_display: None,
+            damage: None,
+        }
     }
 
     fn parse_table(&mut self) -> Result<LuaValue> {
+        if self.depth >= self.max_depth {
+            return Err(WeakAuraError::LuaParseError(format!(
+                "tables nested deeper than {} levels at byte {}",
+                MAX_TABLE_DEPTH, self.pos
+            )));
+        }
+        self.depth += 1;
+        let table = self.parse_table_entries();
+        self.depth -= 1;
+        table
+    }
+
+    fn parse_table_entries(&mut self) -> Result<LuaValue> {
         self.skip_whitespace();
 
         if !self.consume('{') {
@@ -179,7 +291,7 @@ impl<'a> LuaTableParser<'a> {
         // Track implicit array indices (elements without explicit keys)
         let mut implicit_array: Vec<LuaValue> = Vec::new();
         // Track explicit string keys
-        let mut explicit_hash: HashMap<String, LuaValue> = HashMap::new();
+        let mut explicit_hash = LuaTable::new();
         // Track explicit numeric keys (might be sparse or out of order)
         let mut explicit_numeric: Vec<(usize, LuaValue)> = Vec::new();
 
@@ -194,7 +306,21 @@ impl<'a> LuaTableParser<'a> {
             }
 
             // Parse key-value pair
-            let (key, value) = self.parse_key_value()?;
+            let entry_start = self.pos;
+            let (key, value) = match self.parse_key_value() {
+                Ok(entry) => entry,
+                Err(e) if self.salvages_here() => {
+                    self.damage = Some(ParseDamage {
+                        offset: entry_start,
+                        error: e.to_string(),
+                        in_displays: self.in_displays,
+                        display: self.current_display.take(),
+                    });
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            let damaged = self.damage.is_some();
 
             match key {
                 None => {
@@ -216,6 +342,9 @@ impl<'a> LuaTableParser<'a> {
                 }
             }
 
+            if damaged {
+                break;
+            }
             self.skip_whitespace();
             self.consume(','); // Optional comma
         }
@@ -247,11 +376,11 @@ impl<'a> LuaTableParser<'a> {
                 Ok(LuaValue::Array(arr))
             } else {
                 // Sparse numeric keys - convert to table
-                let mut table = HashMap::new();
+                let mut table = LuaTable::new();
                 for (idx, val) in explicit_numeric {
                     table.insert(idx.to_string(), val);
                 }
-                Ok(LuaValue::Table(table))
+                Ok(LuaValue::Table(Arc::new(table)))
             }
         } else if (has_implicit || has_explicit_numeric) && has_hash {
             // Mixed table: combine array part + hash part
@@ -271,6 +400,9 @@ impl<'a> LuaTableParser<'a> {
                     } else if idx == array.len() + 1 {
                         // Index is exactly next position - append
                         array.push(val);
+                    } else if idx - array.len() > MAX_ARRAY_GAP {
+                        // Far beyond the array - keep it as a sparse key
+                        explicit_hash.insert(idx.to_string(), val);
                     } else {
                         // Index beyond current array - fill gaps with Nil and add
                         while array.len() < idx - 1 {
@@ -282,22 +414,28 @@ impl<'a> LuaTableParser<'a> {
             }
 
             if array.is_empty() {
-                Ok(LuaValue::Table(explicit_hash))
+                Ok(LuaValue::Table(Arc::new(explicit_hash)))
             } else {
                 Ok(LuaValue::MixedTable {
                     array,
-                    hash: explicit_hash,
+                    hash: Arc::new(explicit_hash),
                 })
             }
         } else if has_hash {
             // Only string keys
-            Ok(LuaValue::Table(explicit_hash))
+            Ok(LuaValue::Table(Arc::new(explicit_hash)))
         } else {
             // Empty table
-            Ok(LuaValue::Table(HashMap::new()))
+            Ok(LuaValue::Table(Arc::default()))
         }
     }
 
+    /// Whether an error in an entry of the table being parsed is salvaged:
+    /// only in the outermost table and in `displays`
+    fn salvages_here(&self) -> bool {
+        self.salvage && (self.depth == 1 || (self.in_displays && self.depth == 2))
+    }
+
     fn parse_key_value(&mut self) -> Result<(Option<String>, LuaValue)> {
         self.skip_whitespace();
 
@@ -337,7 +475,21 @@ impl<'a> LuaTableParser<'a> {
         };
 
         self.skip_whitespace();
+        let start = self.pos;
+        let entering_displays = self.depth == 1 && key.as_deref() == Some("displays");
+        self.in_displays |= entering_displays;
+        if self.in_displays && self.depth == 2 {
+            self.current_display = key.clone();
+        }
         let value = self.parse_value()?;
+        if entering_displays {
+            self.in_displays = false;
+        } else if self.in_displays && self.depth == 2 {
+            self.current_display = None;
+            if let Some(id) = &key {
+                self.display_spans.push((id.clone(), start..self.pos));
+            }
+        }
 
         Ok((key, value))
     }
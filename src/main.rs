@@ -1,32 +1,171 @@
 //! WeakAura Mass Import Tool
 //!
 //! A GUI application to mass import WeakAura strings into WoW SavedVariables.
+//! Run with `serve` to expose the import engine over JSON-RPC instead, or with
+//! `--listen HOST:PORT` to receive strings from a browser extension.
+//! `diff OLD NEW [--json]` prints what changed between two SavedVariables files.
+//! `--register-uri` registers the app as handler of `weakaura://` links.
+//! `--portable` keeps config and data beside the executable.
 
 // Hide console window on Windows release builds
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod annotations;
 mod app;
+mod archive_source;
 mod categories;
+mod chunking;
+mod companion;
+mod companion_data;
+mod config;
+mod crash;
 mod decoder;
+mod discovery;
+mod duplicates;
 mod error;
+mod field_sheet;
+mod file_diff;
+mod format_sniffer;
+mod fs_util;
 mod lua_parser;
+mod media;
+mod model;
+mod nesting;
+mod net;
+mod pack;
+mod references;
+mod repair;
 mod saved_variables;
+mod scan;
+mod scripting;
+mod selection;
+mod session;
+mod snapshots;
+mod templates;
 mod theme;
+mod transforms;
+mod tree_cache;
+mod uri;
 mod util;
 
-use app::WeakAuraImporter;
+use app::{LaunchOptions, WeakAuraImporter};
 
 fn main() -> iced::Result {
     // Initialize logging
     tracing_subscriber::fmt::init();
+    crash::install_panic_hook();
 
-    iced::application(
-        WeakAuraImporter::new,
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--portable") {
+        config::AppConfig::set_portable(true);
+        // The library keeps its own copy of the setting
+        weakauras_mass_import::config::AppConfig::set_portable(true);
+    }
+
+    let subcommand = args.first().map(String::as_str);
+    if matches!(subcommand, Some("serve" | "diff")) || args.iter().any(|a| a == "--register-uri") {
+        attach_parent_console();
+    }
+
+    // Headless JSON-RPC server: `serve [--addr HOST:PORT]`
+    if subcommand == Some("serve") {
+        let addr = args
+            .windows(2)
+            .find(|pair| pair[0] == "--addr")
+            .map_or(weakauras_mass_import::rpc::DEFAULT_ADDR, |pair| {
+                pair[1].as_str()
+            });
+        if let Err(e) = weakauras_mass_import::rpc::RpcServer::new().serve(addr) {
+            eprintln!("Server error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // What changed between two SavedVariables files: `diff OLD NEW [--json]`
+    if subcommand == Some("diff") {
+        let paths: Vec<&String> = args[1..].iter().filter(|arg| *arg != "--json").collect();
+        let [old, new] = paths[..] else {
+            eprintln!("Usage: weakauras-mass-import diff OLD.lua NEW.lua [--json]");
+            std::process::exit(1);
+        };
+        let output = saved_variables::SavedVariablesManager::diff_files(old.as_ref(), new.as_ref())
+            .and_then(|diff| {
+                if args.iter().any(|arg| arg == "--json") {
+                    diff.to_json()
+                } else {
+                    Ok(diff.to_text())
+                }
+            });
+        match output {
+            Ok(output) => print!("{}", output),
+            Err(e) => {
+                eprintln!("Diff failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if args.iter().any(|arg| arg == "--register-uri") {
+        match uri::register() {
+            Ok(()) => println!("Registered {}:// links", uri::SCHEME),
+            Err(e) => {
+                eprintln!("Failed to register {}:// links: {}", uri::SCHEME, e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // `weakaura://` link: hand it to a running instance if there is one
+    let import_link = args.iter().find(|arg| uri::parse_import_uri(arg).is_some());
+    if let Some(link) = import_link {
+        if uri::forward_to_running_instance(link) {
+            return Ok(());
+        }
+    }
+
+    let launch = LaunchOptions {
+        // Browser companion endpoint: `--listen HOST:PORT`
+        listen: args
+            .windows(2)
+            .find(|pair| pair[0] == "--listen")
+            .map(|pair| pair[1].clone()),
+        import_text: import_link.and_then(|link| uri::parse_import_uri(link)),
+    };
+
+    // A daemon, so a second window can be opened next to the main one
+    iced::daemon(
+        move || WeakAuraImporter::new(&launch),
         WeakAuraImporter::update,
         WeakAuraImporter::view,
     )
-    .title("WeakAuras Mass Importer")
+    .title(WeakAuraImporter::title)
+    .subscription(WeakAuraImporter::subscription)
     .theme(WeakAuraImporter::theme)
-    .window_size((1000.0, 700.0))
     .run()
 }
+
+/// Release builds on Windows run without a console, so output of the
+/// command-line modes would go nowhere: write it to the console of the
+/// terminal the app was started from instead
+#[cfg(windows)]
+fn attach_parent_console() {
+    /// `ATTACH_PARENT_PROCESS`
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // Fails harmlessly when there is no parent console or one is attached
+    // already (debug builds)
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
@@ -0,0 +1,169 @@
+//! Media files referenced by auras.
+//!
+//! Auras point at custom textures, sounds and fonts by path, e.g.
+//! `Interface\AddOns\SharedMedia_Pack\sound\pull.ogg`. [`referenced_media`]
+//! lists these paths and [`missing_media`] looks them up under a flavor
+//! folder (e.g. `.../_retail_`), so missing files can be reported before
+//! import. Only paths under `Interface\AddOns` are checked: other paths are
+//! game files packed in the client's archives, and SharedMedia names are
+//! resolved by the addon at runtime.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::decoder::{LuaValue, WeakAura};
+
+/// Extensions WoW loads textures from when a path has none
+const TEXTURE_EXTENSIONS: &[&str] = &["blp", "tga", "png", "jpg", "jpeg"];
+
+/// Kind of a media file, from its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MediaKind {
+    Texture,
+    Sound,
+    Font,
+}
+
+impl MediaKind {
+    fn of(path: &str) -> Self {
+        let extension = path
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_ascii_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "ogg" | "mp3" | "wav" => MediaKind::Sound,
+            "ttf" | "otf" => MediaKind::Font,
+            _ => MediaKind::Texture,
+        }
+    }
+}
+
+impl fmt::Display for MediaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MediaKind::Texture => "Texture",
+            MediaKind::Sound => "Sound",
+            MediaKind::Font => "Font",
+        })
+    }
+}
+
+/// A media path and the auras using it
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaReference {
+    /// Path as written in the aura, with `\` separators
+    pub path: String,
+    pub kind: MediaKind,
+    /// IDs of the auras (or children) referencing the path, sorted
+    pub aura_ids: Vec<String>,
+}
+
+/// Paths under `Interface\AddOns` referenced by the auras and their
+/// children, sorted by path. Paths differing only in case or separators are
+/// one reference, as they are in game.
+pub fn referenced_media(auras: &[WeakAura]) -> Vec<MediaReference> {
+    let mut references: BTreeMap<String, MediaReference> = BTreeMap::new();
+    for aura in auras {
+        for value in std::iter::once(&aura.data).chain(&aura.child_data) {
+            let id = match value.as_table().and_then(|t| t.get("id")) {
+                Some(LuaValue::String(id)) => id.clone(),
+                _ => aura.id.clone(),
+            };
+            collect_paths(value, &mut |path| {
+                let path = path.replace('/', "\\");
+                let reference = references
+                    .entry(path.to_ascii_lowercase())
+                    .or_insert_with(|| MediaReference {
+                        kind: MediaKind::of(&path),
+                        path,
+                        aura_ids: Vec::new(),
+                    });
+                if !reference.aura_ids.contains(&id) {
+                    reference.aura_ids.push(id.clone());
+                }
+            });
+        }
+    }
+    references
+        .into_values()
+        .map(|mut reference| {
+            reference.aura_ids.sort();
+            reference
+        })
+        .collect()
+}
+
+/// References whose file does not exist under `flavor_dir`
+pub fn missing_media(auras: &[WeakAura], flavor_dir: &Path) -> Vec<MediaReference> {
+    referenced_media(auras)
+        .into_iter()
+        .filter(|reference| !media_exists(flavor_dir, &reference.path))
+        .collect()
+}
+
+/// Whether the file of a media path exists under `flavor_dir`. Names are
+/// matched ignoring ASCII case (for Wine installs), and a texture path
+/// without extension matches any texture format.
+pub fn media_exists(flavor_dir: &Path, path: &str) -> bool {
+    let components: Vec<&str> = path
+        .split(&['\\', '/'][..])
+        .filter(|c| !c.is_empty())
+        .collect();
+    let Some((file, dirs)) = components.split_last() else {
+        return false;
+    };
+    let Some(dir) = dirs
+        .iter()
+        .try_fold(flavor_dir.to_path_buf(), |dir, name| find_entry(&dir, name))
+    else {
+        return false;
+    };
+    if find_entry(&dir, file).is_some_and(|p| p.is_file()) {
+        return true;
+    }
+    !file.contains('.')
+        && TEXTURE_EXTENSIONS
+            .iter()
+            .any(|ext| find_entry(&dir, &format!("{}.{}", file, ext)).is_some_and(|p| p.is_file()))
+}
+
+/// Entry of `dir` whose name matches `name` ignoring ASCII case
+fn find_entry(dir: &Path, name: &str) -> Option<PathBuf> {
+    let exact = dir.join(name);
+    if exact.exists() {
+        return Some(exact);
+    }
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .is_some_and(|n| n.to_string_lossy().eq_ignore_ascii_case(name))
+        })
+}
+
+/// Call `f` with every string in `value` that is a path under `Interface\AddOns`
+fn collect_paths(value: &LuaValue, f: &mut impl FnMut(&str)) {
+    match value {
+        LuaValue::String(s) if is_addon_path(s) => f(s),
+        LuaValue::Table(table) => table.values().for_each(|v| collect_paths(v, f)),
+        LuaValue::Array(array) => array.iter().for_each(|v| collect_paths(v, f)),
+        LuaValue::MixedTable { array, hash } => array
+            .iter()
+            .chain(hash.values())
+            .for_each(|v| collect_paths(v, f)),
+        _ => {}
+    }
+}
+
+fn is_addon_path(s: &str) -> bool {
+    const PREFIX: &str = "interface/addons/";
+    let normalized = s
+        .trim_start_matches(&['\\', '/'][..])
+        .replace('\\', "/")
+        .to_ascii_lowercase();
+    normalized.len() > PREFIX.len() && normalized.starts_with(PREFIX)
+}
@@ -0,0 +1,447 @@
+//! Typed view of the aura fields most tools care about.
+//!
+//! [`AuraModel`] reads id, uid, region type, group membership, load
+//! conditions, triggers and anchoring out of an aura's raw [`LuaValue`]
+//! table, and writes changes back without touching the fields it doesn't
+//! model. It serializes with serde for consumers of the library API.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::decoder::{LuaTable, LuaValue, WeakAura};
+use crate::error::{Result, WeakAuraError};
+
+/// Common fields of an aura, typed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuraModel {
+    pub id: String,
+    pub uid: Option<String>,
+    pub region_type: Option<String>,
+    /// Group the aura belongs to
+    pub parent: Option<String>,
+    /// Direct children, for groups
+    pub controlled_children: Vec<String>,
+    pub load: LoadModel,
+    pub triggers: Vec<TriggerModel>,
+    /// How the triggers combine (`any`, `all` or `custom`)
+    pub disjunctive: Option<String>,
+    pub anchor: AnchorModel,
+}
+
+/// Class and specialization load conditions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoadModel {
+    /// `use_never`: the aura never loads
+    pub never: bool,
+    /// Class tokens the aura loads for, e.g. `WARRIOR` (empty: any class)
+    pub classes: Vec<String>,
+    /// Specialization IDs the aura loads for (empty: any specialization)
+    pub specs: Vec<u32>,
+}
+
+/// The `trigger` table of an entry in `triggers`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TriggerModel {
+    /// `type`, e.g. `aura2`, `event`, `custom`
+    pub trigger_type: Option<String>,
+    pub event: Option<String>,
+    pub unit: Option<String>,
+    /// `auranames`: aura names or spell IDs watched by an aura trigger
+    pub aura_names: Vec<String>,
+    /// `spellName` of a cooldown or cast trigger (a spell ID or a name)
+    pub spell_name: Option<String>,
+}
+
+/// Where the aura is anchored
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnchorModel {
+    /// `anchorFrameType`, e.g. `SCREEN`, `SELECTFRAME`, `MOUSE`
+    pub frame_type: Option<String>,
+    /// `anchorFrameFrame`, the frame for `SELECTFRAME`
+    pub frame: Option<String>,
+    /// `selfPoint`
+    pub point: Option<String>,
+    /// `anchorPoint`, the point of the frame anchored to
+    pub relative_point: Option<String>,
+    pub x_offset: f64,
+    pub y_offset: f64,
+}
+
+impl AuraModel {
+    /// Read the modeled fields of an aura's data table
+    pub fn from_lua(data: &LuaValue) -> Result<Self> {
+        if data.as_table().is_none() {
+            return Err(WeakAuraError::DeserializationError(
+                "aura data is not a table".to_string(),
+            ));
+        }
+        let id = string(data, "id")
+            .ok_or_else(|| WeakAuraError::DeserializationError("aura has no id".to_string()))?;
+
+        let triggers = data
+            .get_path("triggers")
+            .and_then(LuaValue::as_array)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .map(|entry| {
+                        let trigger = entry.get_path("trigger").unwrap_or(&LuaValue::Nil);
+                        TriggerModel {
+                            trigger_type: string(trigger, "type"),
+                            event: string(trigger, "event"),
+                            unit: string(trigger, "unit"),
+                            aura_names: strings(trigger.get_path("auranames")),
+                            spell_name: string(trigger, "spellName"),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            id,
+            uid: string(data, "uid"),
+            region_type: string(data, "regionType"),
+            parent: string(data, "parent"),
+            controlled_children: strings(data.get_path("controlledChildren")),
+            load: LoadModel {
+                never: data.get_path("load.use_never") == Some(&LuaValue::Bool(true)),
+                classes: read_selection(data, "class"),
+                specs: read_selection(data, "class_and_spec")
+                    .iter()
+                    .filter_map(|spec| spec.parse().ok())
+                    .collect(),
+            },
+            triggers,
+            disjunctive: string(data, "triggers.disjunctive"),
+            anchor: AnchorModel {
+                frame_type: string(data, "anchorFrameType"),
+                frame: string(data, "anchorFrameFrame"),
+                point: string(data, "selfPoint"),
+                relative_point: string(data, "anchorPoint"),
+                x_offset: number(data, "xOffset"),
+                y_offset: number(data, "yOffset"),
+            },
+        })
+    }
+
+    /// Write the modeled fields back into an aura's data table. Fields the
+    /// model doesn't cover are kept; `None` and empty values remove their
+    /// field.
+    pub fn write_to(&self, data: &mut LuaValue) -> Result<()> {
+        if data.as_table().is_none() {
+            return Err(WeakAuraError::SerializationError(
+                "aura data is not a table".to_string(),
+            ));
+        }
+        set_string(data, "id", Some(&self.id));
+        set_string(data, "uid", self.uid.as_deref());
+        set_string(data, "regionType", self.region_type.as_deref());
+        set_string(data, "parent", self.parent.as_deref());
+        if self.controlled_children.is_empty() {
+            remove(data, "controlledChildren");
+        } else {
+            data.set_path(
+                "controlledChildren",
+                string_array(&self.controlled_children),
+            );
+        }
+
+        if self.load.never {
+            data.set_path("load.use_never", LuaValue::Bool(true));
+        } else {
+            remove(data, "load.use_never");
+        }
+        write_selection(data, "class", &self.load.classes);
+        let specs: Vec<String> = self.load.specs.iter().map(u32::to_string).collect();
+        write_selection(data, "class_and_spec", &specs);
+
+        if !self.triggers.is_empty() || data.get_path("triggers").is_some() {
+            // Entries past the model's triggers are dropped; the rest keep
+            // their unmodeled fields
+            if let Some(entries) = trigger_array_mut(data) {
+                entries.truncate(self.triggers.len());
+                entries.resize_with(self.triggers.len(), || LuaValue::Table(Arc::default()));
+            }
+            for (i, trigger) in self.triggers.iter().enumerate() {
+                let path = format!("triggers.{}.trigger", i + 1);
+                set_string(
+                    data,
+                    &format!("{path}.type"),
+                    trigger.trigger_type.as_deref(),
+                );
+                set_string(data, &format!("{path}.event"), trigger.event.as_deref());
+                set_string(data, &format!("{path}.unit"), trigger.unit.as_deref());
+                set_string(
+                    data,
+                    &format!("{path}.spellName"),
+                    trigger.spell_name.as_deref(),
+                );
+                if trigger.aura_names.is_empty() {
+                    remove(data, &format!("{path}.auranames"));
+                } else {
+                    data.set_path(
+                        &format!("{path}.auranames"),
+                        string_array(&trigger.aura_names),
+                    );
+                }
+            }
+            set_string(data, "triggers.disjunctive", self.disjunctive.as_deref());
+        }
+
+        set_string(data, "anchorFrameType", self.anchor.frame_type.as_deref());
+        set_string(data, "anchorFrameFrame", self.anchor.frame.as_deref());
+        set_string(data, "selfPoint", self.anchor.point.as_deref());
+        set_string(data, "anchorPoint", self.anchor.relative_point.as_deref());
+        set_offset(data, "xOffset", self.anchor.x_offset);
+        set_offset(data, "yOffset", self.anchor.y_offset);
+        Ok(())
+    }
+}
+
+/// Class tokens WeakAuras' class load option accepts
+pub const CLASS_TOKENS: [&str; 13] = [
+    "WARRIOR",
+    "PALADIN",
+    "HUNTER",
+    "ROGUE",
+    "PRIEST",
+    "DEATHKNIGHT",
+    "SHAMAN",
+    "MAGE",
+    "WARLOCK",
+    "MONK",
+    "DRUID",
+    "DEMONHUNTER",
+    "EVOKER",
+];
+
+/// Simple fields of a decoded aura that can be edited before import
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuraProperties {
+    pub id: String,
+    pub x_offset: f64,
+    pub y_offset: f64,
+    /// Class tokens the aura loads for (empty: any class)
+    pub classes: Vec<String>,
+    /// Specialization IDs the aura loads for (empty: any specialization)
+    pub specs: Vec<u32>,
+    /// The aura's description
+    pub desc: String,
+}
+
+impl AuraProperties {
+    /// Editable properties of a decoded aura (its top-level aura for groups)
+    pub fn from_aura(aura: &WeakAura) -> Result<Self> {
+        Self::from_data(&aura.data)
+    }
+
+    /// Editable properties of an aura's data table
+    pub fn from_data(data: &LuaValue) -> Result<Self> {
+        let model = AuraModel::from_lua(data)?;
+        Ok(Self {
+            id: model.id,
+            x_offset: model.anchor.x_offset,
+            y_offset: model.anchor.y_offset,
+            classes: model.load.classes,
+            specs: model.load.specs,
+            desc: string(data, "desc").unwrap_or_default(),
+        })
+    }
+
+    /// Check the properties are valid for WeakAuras
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| Err(WeakAuraError::InvalidValue(message));
+        if self.id.trim().is_empty() {
+            return invalid("the aura ID is empty".to_string());
+        }
+        if self.id.trim() != self.id {
+            return invalid("the aura ID starts or ends with whitespace".to_string());
+        }
+        if !self.x_offset.is_finite() || !self.y_offset.is_finite() {
+            return invalid("offsets must be finite numbers".to_string());
+        }
+        if let Some(class) = self
+            .classes
+            .iter()
+            .find(|class| !CLASS_TOKENS.contains(&class.as_str()))
+        {
+            return invalid(format!("unknown class '{}'", class));
+        }
+        Ok(())
+    }
+
+    /// Validate and write the properties into a decoded aura. Changing the ID
+    /// also renames the aura in its children's `parent` field.
+    pub fn apply(&self, aura: &mut WeakAura) -> Result<()> {
+        self.write_to(&mut aura.data)?;
+        if aura.id != self.id {
+            aura.rename(&self.id);
+        }
+        Ok(())
+    }
+
+    /// Validate and write the properties into an aura's data table. Only
+    /// the table itself changes: children and groups are not told about a
+    /// new ID.
+    pub fn write_to(&self, data: &mut LuaValue) -> Result<()> {
+        self.validate()?;
+        let mut model = AuraModel::from_lua(data)?;
+        model.id = self.id.clone();
+        model.anchor.x_offset = self.x_offset;
+        model.anchor.y_offset = self.y_offset;
+        model.load.classes = self.classes.clone();
+        model.load.specs = self.specs.clone();
+        model.write_to(data)?;
+        set_string(
+            data,
+            "desc",
+            Some(self.desc.as_str()).filter(|desc| !desc.is_empty()),
+        );
+        Ok(())
+    }
+}
+
+fn string(value: &LuaValue, path: &str) -> Option<String> {
+    value
+        .get_path(path)
+        .and_then(LuaValue::as_str)
+        .map(str::to_string)
+}
+
+fn number(value: &LuaValue, path: &str) -> f64 {
+    match value.get_path(path) {
+        Some(LuaValue::Number(n)) => *n,
+        _ => 0.0,
+    }
+}
+
+/// Strings and numbers of an array, as text
+fn strings(value: Option<&LuaValue>) -> Vec<String> {
+    value
+        .and_then(LuaValue::as_array)
+        .map(|items| items.iter().filter_map(scalar_text).collect())
+        .unwrap_or_default()
+}
+
+fn scalar_text(value: &LuaValue) -> Option<String> {
+    match value {
+        LuaValue::String(s) => Some(s.clone()),
+        LuaValue::Number(n) if n.fract() == 0.0 => Some((*n as i64).to_string()),
+        LuaValue::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn string_array(items: &[String]) -> LuaValue {
+    LuaValue::Array(items.iter().cloned().map(LuaValue::String).collect())
+}
+
+/// A load option: `use_<key>` is true for the `single` choice, false for the
+/// `multi` set and nil when the option is unused
+fn read_selection(data: &LuaValue, key: &str) -> Vec<String> {
+    match data.get_path(&format!("load.use_{key}")) {
+        Some(LuaValue::Bool(true)) => data
+            .get_path(&format!("load.{key}.single"))
+            .and_then(scalar_text)
+            .into_iter()
+            .collect(),
+        Some(LuaValue::Bool(false)) => data
+            .get_path(&format!("load.{key}.multi"))
+            .and_then(LuaValue::as_table)
+            .map(|multi| {
+                multi
+                    .iter()
+                    .filter(|(_, enabled)| **enabled == LuaValue::Bool(true))
+                    .map(|(choice, _)| choice.clone())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn write_selection(data: &mut LuaValue, key: &str, choices: &[String]) {
+    let use_key = format!("load.use_{key}");
+    match choices {
+        [] => remove(data, &use_key),
+        [choice] => {
+            data.set_path(&use_key, LuaValue::Bool(true));
+            data.set_path(&format!("load.{key}.single"), lua_choice(choice));
+        }
+        _ => {
+            data.set_path(&use_key, LuaValue::Bool(false));
+            let multi: LuaTable = choices
+                .iter()
+                .map(|choice| (choice.clone(), LuaValue::Bool(true)))
+                .collect();
+            data.set_path(
+                &format!("load.{key}.multi"),
+                LuaValue::Table(Arc::new(multi)),
+            );
+        }
+    }
+}
+
+/// Load choices are stored as numbers when they are IDs
+fn lua_choice(choice: &str) -> LuaValue {
+    match choice.parse::<f64>() {
+        Ok(n) => LuaValue::Number(n),
+        Err(_) => LuaValue::String(choice.to_string()),
+    }
+}
+
+fn set_string(data: &mut LuaValue, path: &str, value: Option<&str>) {
+    match value {
+        Some(value) => {
+            data.set_path(path, LuaValue::String(value.to_string()));
+        }
+        None => remove(data, path),
+    }
+}
+
+/// Remove the field at a dotted path, if its table exists
+fn remove(data: &mut LuaValue, path: &str) {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (data.get_path_mut(parent), key),
+        None => (Some(data), path),
+    };
+    if let Some(table) = parent.and_then(LuaValue::as_table_mut) {
+        table.shift_remove(key);
+    }
+}
+
+/// Offsets default to 0, so a missing offset is only written when it moves
+fn set_offset(data: &mut LuaValue, path: &str, offset: f64) {
+    if offset != 0.0 || data.get_path(path).is_some() {
+        data.set_path(path, LuaValue::Number(offset));
+    }
+}
+
+/// Array part of `triggers`, created (keeping any string keys) when missing
+fn trigger_array_mut(data: &mut LuaValue) -> Option<&mut Vec<LuaValue>> {
+    let triggers = data
+        .as_table_mut()?
+        .entry("triggers".to_string())
+        .or_default();
+    match triggers {
+        LuaValue::Nil => *triggers = LuaValue::Array(Vec::new()),
+        LuaValue::Table(hash) => {
+            *triggers = LuaValue::MixedTable {
+                array: Vec::new(),
+                hash: std::mem::take(hash),
+            }
+        }
+        _ => {}
+    }
+    match triggers {
+        LuaValue::Array(array) | LuaValue::MixedTable { array, .. } => Some(array),
+        _ => None,
+    }
+}
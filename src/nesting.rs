@@ -0,0 +1,139 @@
+//! Group nesting checks for auras about to be imported.
+//!
+//! WeakAuras copes badly with deep group hierarchies, and a dynamic group
+//! inside another dynamic group fights its parent over the positions of its
+//! children. [`check_nesting`] lists what an import (optionally into an
+//! existing group) would create, so it can be flagged before writing.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::decoder::{LuaValue, WeakAura};
+use crate::saved_variables::AuraTreeNode;
+
+/// Nested groups an aura can sit in before WeakAuras starts misbehaving
+pub const MAX_GROUP_DEPTH: usize = 3;
+
+/// A nesting problem an import would create
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NestingWarning {
+    /// An aura would sit in more than [`MAX_GROUP_DEPTH`] nested groups
+    TooDeep { aura_id: String, depth: usize },
+    /// A dynamic group would be a child of another dynamic group
+    DynamicInDynamic { group_id: String, parent_id: String },
+}
+
+impl fmt::Display for NestingWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooDeep { aura_id, depth } => write!(
+                f,
+                "'{}' would be nested {} groups deep (more than {} is unreliable)",
+                aura_id, depth, MAX_GROUP_DEPTH
+            ),
+            Self::DynamicInDynamic {
+                group_id,
+                parent_id,
+            } => write!(
+                f,
+                "Dynamic group '{}' would be inside dynamic group '{}'",
+                group_id, parent_id
+            ),
+        }
+    }
+}
+
+/// Nesting warnings for importing `auras` into the `target` group of the
+/// existing `tree`, or at the top level when `target` is `None` or not in the
+/// tree. Each aura reports at most one [`NestingWarning::TooDeep`], for its
+/// deepest descendant.
+pub fn check_nesting(
+    tree: &[AuraTreeNode],
+    target: Option<&str>,
+    auras: &[WeakAura],
+) -> Vec<NestingWarning> {
+    let path = target
+        .map(|id| {
+            let mut path = Vec::new();
+            find_path(tree, id, &mut path);
+            path
+        })
+        .unwrap_or_default();
+    let target = path.last();
+
+    let mut warnings = Vec::new();
+    for aura in auras {
+        if let Some(target) = target {
+            if is_dynamic(aura.region_type.as_deref()) && is_dynamic(target.region_type.as_deref())
+            {
+                warnings.push(NestingWarning::DynamicInDynamic {
+                    group_id: aura.id.clone(),
+                    parent_id: target.id.clone(),
+                });
+            }
+        }
+
+        // Parent and region type of each child aura
+        let children: HashMap<&str, (Option<&str>, Option<&str>)> = aura
+            .child_data
+            .iter()
+            .filter_map(|child| {
+                let table = child.as_table()?;
+                let field = |key: &str| match table.get(key) {
+                    Some(LuaValue::String(s)) => Some(s.as_str()),
+                    _ => None,
+                };
+                Some((field("id")?, (field("parent"), field("regionType"))))
+            })
+            .collect();
+        let region_of = |id: &str| {
+            if id == aura.id {
+                aura.region_type.as_deref()
+            } else {
+                children.get(id).and_then(|(_, region)| *region)
+            }
+        };
+
+        let descendants = aura.descendants();
+        let mut deepest = (aura.id.as_str(), path.len());
+        for (id, depth) in &descendants {
+            let Some((parent, region)) = children.get(id.as_str()) else {
+                continue;
+            };
+            if path.len() + *depth > deepest.1 {
+                deepest = (id.as_str(), path.len() + *depth);
+            }
+            if let Some(parent) = parent {
+                if is_dynamic(*region) && is_dynamic(region_of(parent)) {
+                    warnings.push(NestingWarning::DynamicInDynamic {
+                        group_id: id.clone(),
+                        parent_id: parent.to_string(),
+                    });
+                }
+            }
+        }
+        if deepest.1 > MAX_GROUP_DEPTH {
+            warnings.push(NestingWarning::TooDeep {
+                aura_id: deepest.0.to_string(),
+                depth: deepest.1,
+            });
+        }
+    }
+    warnings
+}
+
+fn is_dynamic(region_type: Option<&str>) -> bool {
+    region_type == Some("dynamicgroup")
+}
+
+/// Nodes from the top of the tree down to the node `id`
+fn find_path<'a>(nodes: &'a [AuraTreeNode], id: &str, path: &mut Vec<&'a AuraTreeNode>) -> bool {
+    for node in nodes {
+        path.push(node);
+        if node.id == id || find_path(&node.children, id, path) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
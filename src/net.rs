@@ -0,0 +1,380 @@
+//! Shared HTTP layer for online integrations.
+//!
+//! [`HttpClient`] wraps GET requests with what every online feature needs:
+//! - a disk cache, revalidated with `ETag`/`Last-Modified` so unchanged
+//!   resources cost a `304` instead of a download
+//! - a minimum interval between requests to the same host
+//! - retries with exponential backoff on network errors, `429` and `5xx`
+//!   (honoring a `Retry-After` of up to a minute), falling back to a stale
+//!   cached copy
+//! - the global offline switch ([`set_offline`]): while offline, cached
+//!   responses are served and anything else fails with
+//!   [`WeakAuraError::Offline`] without touching the network
+//!
+//! Online features share [`HttpClient::shared`], which caches under the
+//! config directory. The network itself sits behind [`Transport`], so
+//! callers and tests can swap it out. Links pasted into the input are
+//! downloaded through it ([`links_in`], [`download_url`]).
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::config::AppConfig;
+use crate::error::{Result, WeakAuraError};
+
+/// Largest accepted response body
+const MAX_BODY_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Cache folder under the config directory
+const CACHE_DIR_NAME: &str = "http_cache";
+
+/// Longest `Retry-After` waited for; a server asking for more is not retried
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Wago API endpoint returning the import string of an aura by slug
+const WAGO_ENCODED_URL: &str = "https://data.wago.io/api/raw/encoded?id=";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Turn offline mode on or off for every [`HttpClient`]
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether offline mode is on
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Response of a [`Transport`]
+#[derive(Debug, Clone, Default)]
+pub struct Response {
+    pub status: u16,
+    pub body: Vec<u8>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// Delay requested by a `Retry-After` header (in seconds)
+    pub retry_after: Option<Duration>,
+}
+
+/// Performs single GET requests. Errors are network failures; HTTP error
+/// statuses are responses.
+pub trait Transport: Send + Sync {
+    fn get(&self, url: &str, headers: &[(&str, String)]) -> std::result::Result<Response, String>;
+}
+
+/// [`Transport`] over ureq
+struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+impl UreqTransport {
+    fn new() -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .user_agent(concat!(
+                    env!("CARGO_PKG_NAME"),
+                    "/",
+                    env!("CARGO_PKG_VERSION")
+                ))
+                .build(),
+        }
+    }
+}
+
+impl Transport for UreqTransport {
+    fn get(&self, url: &str, headers: &[(&str, String)]) -> std::result::Result<Response, String> {
+        let mut request = self.agent.get(url);
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+        let response = match request.call() {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let header = |name: &str| response.header(name).map(str::to_string);
+        let etag = header("ETag");
+        let last_modified = header("Last-Modified");
+        let retry_after = response
+            .header("Retry-After")
+            .and_then(|s| s.trim().parse().ok())
+            .map(Duration::from_secs);
+        let status = response.status();
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_BODY_BYTES)
+            .read_to_end(&mut body)
+            .map_err(|e| e.to_string())?;
+        Ok(Response {
+            status,
+            body,
+            etag,
+            last_modified,
+            retry_after,
+        })
+    }
+}
+
+/// Validators stored next to a cached body
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// Cached, rate-limited HTTP client with retries
+pub struct HttpClient {
+    transport: Arc<dyn Transport>,
+    cache_dir: Option<PathBuf>,
+    min_interval: Duration,
+    retries: u32,
+    backoff: Duration,
+    /// Time of the next allowed request, by host
+    next_request: Mutex<HashMap<String, Instant>>,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpClient {
+    /// Client over the network without a disk cache: one request per host
+    /// every 500 ms, up to 3 retries starting at 1 s
+    pub fn new() -> Self {
+        Self::with_transport(Arc::new(UreqTransport::new()))
+    }
+
+    /// Client over a custom transport, with the defaults of [`HttpClient::new`]
+    pub fn with_transport(transport: Arc<dyn Transport>) -> Self {
+        Self {
+            transport,
+            cache_dir: None,
+            min_interval: Duration::from_millis(500),
+            retries: 3,
+            backoff: Duration::from_secs(1),
+            next_request: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Client shared by the online features, caching under the config directory
+    pub fn shared() -> &'static HttpClient {
+        static SHARED: OnceLock<HttpClient> = OnceLock::new();
+        SHARED.get_or_init(|| {
+            let client = HttpClient::new();
+            match AppConfig::config_dir() {
+                Some(dir) => client.cache_dir(dir.join(CACHE_DIR_NAME)),
+                None => client,
+            }
+        })
+    }
+
+    /// Cache responses in `dir`
+    pub fn cache_dir(mut self, dir: PathBuf) -> Self {
+        self.cache_dir = Some(dir);
+        self
+    }
+
+    /// Minimum time between two requests to the same host
+    #[allow(dead_code)]
+    pub fn min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// Retry failed requests `retries` times, waiting `backoff` before the
+    /// first retry and doubling it for each further one
+    #[allow(dead_code)]
+    pub fn retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// GET `url` as text
+    pub fn get_text(&self, url: &str) -> Result<String> {
+        String::from_utf8(self.get(url)?)
+            .map_err(|_| WeakAuraError::HttpError(format!("{} did not return text", url)))
+    }
+
+    /// GET `url`, from the cache when it is still current
+    pub fn get(&self, url: &str) -> Result<Vec<u8>> {
+        let cached = self.read_cache(url);
+        if is_offline() {
+            return cached
+                .map(|(_, body)| body)
+                .ok_or_else(|| WeakAuraError::Offline(url.to_string()));
+        }
+
+        let mut headers = Vec::new();
+        if let Some((entry, _)) = &cached {
+            if let Some(etag) = &entry.etag {
+                headers.push(("If-None-Match", etag.clone()));
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                headers.push(("If-Modified-Since", last_modified.clone()));
+            }
+        }
+
+        let mut delay = self.backoff;
+        let mut attempt = 0;
+        let error = loop {
+            self.wait_turn(url);
+            let (error, retry_after) = match self.transport.get(url, &headers) {
+                Ok(response) if (200..300).contains(&response.status) => {
+                    self.write_cache(url, &response);
+                    return Ok(response.body);
+                }
+                Ok(response) if response.status == 304 => match &cached {
+                    Some((_, body)) => return Ok(body.clone()),
+                    None => (format!("{} returned 304 without a cached copy", url), None),
+                },
+                Ok(response) if response.status == 429 || response.status >= 500 => (
+                    format!("{} returned {}", url, response.status),
+                    response.retry_after,
+                ),
+                Ok(response) => {
+                    return Err(WeakAuraError::HttpError(format!(
+                        "{} returned {}",
+                        url, response.status
+                    )))
+                }
+                Err(e) => (format!("{}: {}", url, e), None),
+            };
+            let too_long = retry_after.is_some_and(|wait| wait > MAX_RETRY_AFTER);
+            if attempt >= self.retries || too_long {
+                break error;
+            }
+            attempt += 1;
+            thread::sleep(retry_after.unwrap_or(delay));
+            delay *= 2;
+        };
+
+        match cached {
+            Some((_, body)) => {
+                warn!("Using cached copy: {}", error);
+                Ok(body)
+            }
+            None => Err(WeakAuraError::HttpError(error)),
+        }
+    }
+
+    /// Block until a request to the host of `url` is allowed
+    fn wait_turn(&self, url: &str) {
+        if self.min_interval.is_zero() {
+            return;
+        }
+        let now = Instant::now();
+        let start = {
+            let mut next_request = self
+                .next_request
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let next = next_request.entry(host_of(url).to_string()).or_insert(now);
+            let start = (*next).max(now);
+            *next = start + self.min_interval;
+            start
+        };
+        if start > now {
+            thread::sleep(start - now);
+        }
+    }
+
+    fn cache_paths(&self, url: &str) -> Option<(PathBuf, PathBuf)> {
+        let dir = self.cache_dir.as_ref()?;
+        let key = format!("{:016x}", fnv1a(url.as_bytes()));
+        Some((
+            dir.join(format!("{}.json", key)),
+            dir.join(format!("{}.body", key)),
+        ))
+    }
+
+    fn read_cache(&self, url: &str) -> Option<(CacheEntry, Vec<u8>)> {
+        let (meta_path, body_path) = self.cache_paths(url)?;
+        let entry: CacheEntry = serde_json::from_str(&fs::read_to_string(meta_path).ok()?).ok()?;
+        // Another URL with the same hash
+        if entry.url != url {
+            return None;
+        }
+        Some((entry, fs::read(body_path).ok()?))
+    }
+
+    fn write_cache(&self, url: &str, response: &Response) {
+        let Some((meta_path, body_path)) = self.cache_paths(url) else {
+            return;
+        };
+        let entry = CacheEntry {
+            url: url.to_string(),
+            etag: response.etag.clone(),
+            last_modified: response.last_modified.clone(),
+        };
+        let written = meta_path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&body_path, &response.body))
+            .and_then(|_| {
+                let json = serde_json::to_string(&entry).unwrap_or_default();
+                fs::write(&meta_path, json)
+            });
+        if let Err(e) = written {
+            warn!("Failed to cache {}: {}", url, e);
+        }
+    }
+}
+
+/// `http(s)` links standing alone on a line of `text`, in order
+pub fn links_in(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| {
+            (line.starts_with("https://") || line.starts_with("http://"))
+                && !line.contains(char::is_whitespace)
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Where to download the import strings behind `link`: wago.io aura pages
+/// through the Wago API, anything else (e.g. a raw paste) as it is
+pub fn download_url(link: &str) -> String {
+    let rest = link.split_once("://").map_or(link, |(_, rest)| rest);
+    let host = host_of(link);
+    if host == "wago.io" || host == "www.wago.io" {
+        let slug = rest[host.len()..]
+            .trim_start_matches('/')
+            .split(&['/', '?', '#'][..])
+            .next()
+            .unwrap_or_default();
+        if !slug.is_empty() {
+            return format!("{}{}", WAGO_ENCODED_URL, slug);
+        }
+    }
+    link.to_string()
+}
+
+/// Host (and port) of a URL
+fn host_of(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split(&['/', '?', '#'][..]).next().unwrap_or(rest)
+}
+
+/// 64-bit FNV-1a hash, stable across builds (unlike `DefaultHasher`)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    })
+}
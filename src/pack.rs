@@ -0,0 +1,233 @@
+//! Aura packs with bundled media.
+//!
+//! A pack is a folder of aura strings (`.txt`, `.md`, `.lua`). It can bundle
+//! the textures, sounds and fonts its auras use in a `media/` subfolder; the
+//! auras reference them as `Interface\AddOns\<media addon>\<path in media/>`.
+//! The media addon folder is named by the optional `pack.json` manifest,
+//! falling back to the folder configured by the user:
+//!
+//! ```text
+//! MyPack/
+//! ├── pack.json        { "name": "My Pack", "media_addon": "SharedMedia_MyPack" }
+//! ├── auras.txt
+//! └── media/
+//!     ├── sound/pull.ogg
+//!     └── statusbar/smooth.tga
+//! ```
+//!
+//! [`MediaPack::install`] copies the media into the `Interface/AddOns`
+//! folder of a game flavor. It never writes into a real addon (a folder with
+//! a `.toc` file), and replaces installed files that differ only when asked
+//! to. Symbolic links inside `media/` are not followed.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, WeakAuraError};
+use crate::fs_util;
+
+/// Subfolder of a pack holding its media files
+pub const MEDIA_DIR_NAME: &str = "media";
+
+/// Optional manifest in the pack folder
+pub const MANIFEST_FILE_NAME: &str = "pack.json";
+
+/// Media addon folder used when neither the pack nor the user names one
+pub const DEFAULT_MEDIA_ADDON: &str = "WeakAurasMedia";
+
+/// Contents of `pack.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PackManifest {
+    /// Display name of the pack
+    pub name: Option<String>,
+    /// Folder under `Interface/AddOns` the media is installed into
+    pub media_addon: Option<String>,
+}
+
+/// Media bundled with a pack
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaPack {
+    /// Pack folder
+    pub root: PathBuf,
+    pub manifest: PackManifest,
+    /// Media files, relative to the `media/` folder
+    pub files: Vec<PathBuf>,
+}
+
+/// Outcome of installing the media of a pack
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaInstallResult {
+    /// Files copied into a new location
+    pub copied: usize,
+    /// Existing files replaced with different content
+    pub replaced: usize,
+    /// Existing files with different content that were left alone
+    pub kept: usize,
+    /// Files already installed with the same content
+    pub unchanged: usize,
+}
+
+impl MediaPack {
+    /// Media of the pack in `folder`. `None` if the folder has no
+    /// non-empty `media/` subfolder.
+    pub fn open(folder: &Path) -> Result<Option<Self>> {
+        let media_dir = folder.join(MEDIA_DIR_NAME);
+        if !media_dir.is_dir() {
+            return Ok(None);
+        }
+        let manifest_path = folder.join(MANIFEST_FILE_NAME);
+        let manifest = if manifest_path.is_file() {
+            let content = fs::read_to_string(&manifest_path)?;
+            serde_json::from_str(&content).map_err(|e| {
+                WeakAuraError::ConfigError(format!("Invalid {}: {}", MANIFEST_FILE_NAME, e))
+            })?
+        } else {
+            PackManifest::default()
+        };
+
+        let mut files = Vec::new();
+        collect_files(&media_dir, Path::new(""), &mut files)?;
+        if files.is_empty() {
+            return Ok(None);
+        }
+        files.sort();
+        Ok(Some(Self {
+            root: folder.to_path_buf(),
+            manifest,
+            files,
+        }))
+    }
+
+    /// Display name: the manifest name or the folder name
+    pub fn name(&self) -> String {
+        self.manifest.name.clone().unwrap_or_else(|| {
+            self.root
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Media addon folder name, from the manifest or `default_addon`.
+    /// Fails for names that are not a single folder name.
+    pub fn media_addon<'a>(&'a self, default_addon: &'a str) -> Result<&'a str> {
+        let addon = self
+            .manifest
+            .media_addon
+            .as_deref()
+            .unwrap_or(default_addon)
+            .trim();
+        let valid = !addon.is_empty()
+            && addon != "."
+            && addon != ".."
+            && !addon.contains(&['/', '\\', ':'][..]);
+        if valid {
+            Ok(addon)
+        } else {
+            Err(WeakAuraError::ConfigError(format!(
+                "Invalid media folder name '{}'",
+                addon
+            )))
+        }
+    }
+
+    /// Game paths of the bundled files (`Interface\AddOns\<addon>\...`),
+    /// as auras reference them
+    pub fn game_paths(&self, default_addon: &str) -> Vec<String> {
+        let Ok(addon) = self.media_addon(default_addon) else {
+            return Vec::new();
+        };
+        self.files
+            .iter()
+            .map(|file| {
+                let relative: Vec<String> = file
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect();
+                format!("Interface\\AddOns\\{}\\{}", addon, relative.join("\\"))
+            })
+            .collect()
+    }
+
+    /// Copy the media into `<flavor_dir>/Interface/AddOns/<media addon>/`,
+    /// keeping the folder layout of `media/`. Installed files with different
+    /// content are only overwritten with `replace_differing`. Fails if the
+    /// media addon folder is an existing addon.
+    pub fn install(
+        &self,
+        flavor_dir: &Path,
+        default_addon: &str,
+        replace_differing: bool,
+    ) -> Result<MediaInstallResult> {
+        let addon = self.media_addon(default_addon)?;
+        let target_dir = flavor_dir.join("Interface").join("AddOns").join(addon);
+        if is_addon(&target_dir)? {
+            return Err(WeakAuraError::ConfigError(format!(
+                "'{}' is an installed addon, not a media folder; choose another media folder",
+                addon
+            )));
+        }
+        let source_dir = self.root.join(MEDIA_DIR_NAME);
+
+        let mut result = MediaInstallResult::default();
+        for file in &self.files {
+            let source = source_dir.join(file);
+            let target = target_dir.join(file);
+            let content = fs::read(&source)?;
+            if target.is_file() {
+                if fs::read(&target)? == content {
+                    result.unchanged += 1;
+                    continue;
+                }
+                if !replace_differing {
+                    result.kept += 1;
+                    continue;
+                }
+                result.replaced += 1;
+            } else {
+                result.copied += 1;
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs_util::write(&target, content)?;
+        }
+        Ok(result)
+    }
+}
+
+/// Whether `dir` holds an addon, i.e. a `.toc` file
+fn is_addon(dir: &Path) -> Result<bool> {
+    if !dir.is_dir() {
+        return Ok(false);
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toc"))
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Files below `dir`, relative to the media folder. Symbolic links are
+/// skipped, so a pack cannot pull in files from elsewhere or loop.
+fn collect_files(dir: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let relative = relative.join(entry.file_name());
+        if file_type.is_dir() {
+            collect_files(&entry.path(), &relative, files)?;
+        } else if file_type.is_file() {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! Non-interactive conflict resolution.
+//!
+//! A [`ResolutionPolicy`] decides every conflict up front, so headless callers
+//! (CLI, scripts, bindings) can import without the conflict dialog via
+//! [`SavedVariablesManager::import_with_policy`].
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::categories::UpdateCategory;
+use crate::chunking::ImportOptions;
+use crate::decoder::WeakAura;
+use crate::error::{Result, WeakAuraError};
+use crate::saved_variables::{
+    ConflictAction, ConflictDetectionResult, ConflictResolution, ImportConflict, ImportResult,
+    SavedVariablesManager, VersionChange,
+};
+
+/// How conflicts are resolved without user interaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionPolicy {
+    /// Keep every existing aura
+    SkipAll,
+    /// Replace every existing aura with the incoming one
+    ReplaceAll,
+    /// Update only the given categories of existing auras
+    UpdateCategories(HashSet<UpdateCategory>),
+    /// Update auras whose incoming version is newer (merging against the last
+    /// import when a base is known, default categories otherwise); skip the rest
+    NewerVersionOnly,
+}
+
+impl Default for ResolutionPolicy {
+    fn default() -> Self {
+        Self::UpdateCategories(UpdateCategory::defaults())
+    }
+}
+
+impl ResolutionPolicy {
+    /// Build a policy from a name as used on command lines and in scripts:
+    /// `skip_all`, `replace_all`, `update_categories` or `newer_version_only`
+    /// (short forms `skip`, `replace`, `update`, `newer`). `categories` are
+    /// category display names for `update_categories`; empty means the defaults.
+    pub fn from_name<S: AsRef<str>>(name: &str, categories: &[S]) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "skip" | "skip_all" => Ok(Self::SkipAll),
+            "replace" | "replace_all" => Ok(Self::ReplaceAll),
+            "newer" | "newer_version_only" => Ok(Self::NewerVersionOnly),
+            "update" | "update_categories" if categories.is_empty() => Ok(Self::default()),
+            "update" | "update_categories" => categories
+                .iter()
+                .map(|name| {
+                    let name = name.as_ref().trim();
+                    UpdateCategory::all()
+                        .into_iter()
+                        .find(|c| c.display_name().eq_ignore_ascii_case(name))
+                        .ok_or_else(|| {
+                            WeakAuraError::ConfigError(format!("Unknown category '{}'", name))
+                        })
+                })
+                .collect::<Result<HashSet<_>>>()
+                .map(Self::UpdateCategories),
+            other => Err(WeakAuraError::ConfigError(format!(
+                "Unknown resolution policy '{}'",
+                other
+            ))),
+        }
+    }
+
+    /// Resolution for a single conflict
+    pub fn resolve(&self, conflict: &ImportConflict) -> ConflictResolution {
+        let action = match self {
+            Self::SkipAll => ConflictAction::Skip,
+            Self::ReplaceAll => ConflictAction::ReplaceAll,
+            Self::UpdateCategories(_) => ConflictAction::UpdateSelected,
+            Self::NewerVersionOnly => match conflict.version_change {
+                VersionChange::Newer if conflict.base.is_some() => ConflictAction::Merge,
+                VersionChange::Newer => ConflictAction::UpdateSelected,
+                _ => ConflictAction::Skip,
+            },
+        };
+        let categories_to_update = match self {
+            Self::UpdateCategories(categories) => categories.clone(),
+            _ => UpdateCategory::defaults(),
+        };
+        ConflictResolution {
+            aura_id: conflict.aura_id.clone(),
+            action,
+            categories_to_update,
+            ..Default::default()
+        }
+    }
+
+    /// Resolutions for every conflict of a detection result
+    pub fn resolutions(&self, detection: &ConflictDetectionResult) -> Vec<ConflictResolution> {
+        detection
+            .conflicts
+            .iter()
+            .map(|conflict| self.resolve(conflict))
+            .collect()
+    }
+}
+
+impl SavedVariablesManager {
+    /// Detect conflicts and resolve them with `policy`, without any interaction.
+    /// New auras are always added, with fresh uids where theirs is taken by
+    /// another aura. Call [`SavedVariablesManager::save`] to persist.
+    pub fn import_with_policy(
+        &mut self,
+        auras: &[WeakAura],
+        policy: &ResolutionPolicy,
+    ) -> ImportResult {
+        let mut detection = self.detect_conflicts(auras);
+        detection.assign_fresh_uids();
+        let resolutions = policy.resolutions(&detection);
+        self.apply_resolutions(&detection, &resolutions)
+    }
+
+    /// Import `auras` with `policy` in the chunks of `options`, saving after
+    /// each. `on_chunk` is called with the number of the chunk about to be
+    /// imported (from 1) and the number of chunks. When a save fails, the
+    /// chunks before it stay saved. Only the first save writes the backup,
+    /// so it holds the file from before the import.
+    pub fn import_chunked(
+        &mut self,
+        auras: &[WeakAura],
+        policy: &ResolutionPolicy,
+        options: &ImportOptions,
+        mut on_chunk: impl FnMut(usize, usize),
+    ) -> Result<ImportResult> {
+        let chunks = options.chunks(auras);
+        let mut result = ImportResult::default();
+        for (index, chunk) in chunks.iter().enumerate() {
+            on_chunk(index + 1, chunks.len());
+            result.merge(self.import_with_policy(chunk, policy));
+            if index == 0 {
+                self.save()?;
+            } else {
+                self.save_without_backup()?;
+            }
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,351 @@
+//! Python bindings (enabled with the `python` feature).
+//!
+//! Build and install the `weakauras_mass_import` module with
+//! `maturin develop --release` (see `pyproject.toml`):
+//!
+//! ```python
+//! from weakauras_mass_import import SavedVariablesManager, WeakAuraDecoder
+//!
+//! auras, errors = WeakAuraDecoder.decode_multiple(open("auras.txt").read())
+//! manager = SavedVariablesManager("WTF/Account/NAME/SavedVariables/WeakAuras.lua")
+//! manager.load()
+//! for conflict in manager.detect_conflicts(auras):
+//!     print(conflict.aura_id, conflict.version_change, conflict.changed_categories)
+//! result = manager.import_with_policy(auras, "update", ["Trigger", "Load"])
+//! manager.save()
+//! print(result.summary())
+//! ```
+//!
+//! Aura data is exposed as plain Python values: tables become `dict`s (mixed
+//! tables use integer keys for their array part) and arrays become `list`s.
+
+// `#[pymethods]` expansion in pyo3 0.22 trips this lint on every `PyResult` return
+#![allow(clippy::useless_conversion)]
+
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyFileNotFoundError, PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::decoder::{LuaTable, LuaValue, WeakAura, WeakAuraDecoder};
+use crate::error::WeakAuraError;
+use crate::policy::ResolutionPolicy;
+use crate::saved_variables::{ImportConflict, ImportResult, SavedVariablesManager};
+
+impl From<WeakAuraError> for PyErr {
+    fn from(e: WeakAuraError) -> Self {
+        match e {
+            WeakAuraError::FileNotFound(_) => PyFileNotFoundError::new_err(e.to_string()),
+            WeakAuraError::IoError(_) | WeakAuraError::FileLocked(_) => {
+                PyIOError::new_err(e.to_string())
+            }
+            _ => PyValueError::new_err(e.to_string()),
+        }
+    }
+}
+
+fn lua_to_py(py: Python<'_>, value: &LuaValue) -> PyResult<PyObject> {
+    Ok(match value {
+        LuaValue::Nil => py.None(),
+        LuaValue::Bool(b) => b.to_object(py),
+        LuaValue::Number(n) => n.to_object(py),
+        LuaValue::String(s) => s.to_object(py),
+        LuaValue::Array(items) => {
+            let list = PyList::empty_bound(py);
+            for item in items {
+                list.append(lua_to_py(py, item)?)?;
+            }
+            list.into_py(py)
+        }
+        LuaValue::Table(hash) => hash_to_dict(py, hash)?.into_py(py),
+        LuaValue::MixedTable { array, hash } => {
+            let dict = hash_to_dict(py, hash)?;
+            for (i, item) in array.iter().enumerate() {
+                dict.set_item(i + 1, lua_to_py(py, item)?)?;
+            }
+            dict.into_py(py)
+        }
+    })
+}
+
+fn hash_to_dict<'py>(py: Python<'py>, hash: &LuaTable) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    for (key, value) in hash {
+        dict.set_item(key, lua_to_py(py, value)?)?;
+    }
+    Ok(dict)
+}
+
+fn unwrap_auras(auras: &[PyRef<'_, PyWeakAura>]) -> Vec<WeakAura> {
+    auras.iter().map(|aura| aura.0.clone()).collect()
+}
+
+/// A decoded WeakAura
+#[pyclass(name = "WeakAura")]
+#[derive(Clone)]
+pub struct PyWeakAura(WeakAura);
+
+#[pymethods]
+impl PyWeakAura {
+    #[getter]
+    fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    #[getter]
+    fn uid(&self) -> Option<&str> {
+        self.0.uid.as_deref()
+    }
+
+    #[getter]
+    fn region_type(&self) -> Option<&str> {
+        self.0.region_type.as_deref()
+    }
+
+    #[getter]
+    fn is_group(&self) -> bool {
+        self.0.is_group
+    }
+
+    #[getter]
+    fn children(&self) -> Vec<String> {
+        self.0.children.clone()
+    }
+
+    #[getter]
+    fn encoding_version(&self) -> u8 {
+        self.0.encoding_version
+    }
+
+    /// Aura data as nested dicts/lists
+    #[getter]
+    fn data(&self, py: Python<'_>) -> PyResult<PyObject> {
+        lua_to_py(py, &self.0.data)
+    }
+
+    /// Data of all descendants (for groups)
+    #[getter]
+    fn child_data(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.0
+            .child_data
+            .iter()
+            .map(|child| lua_to_py(py, child))
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("WeakAura(id={:?}, is_group={})", self.0.id, self.0.is_group)
+    }
+}
+
+/// Decoder for WeakAura import strings
+#[pyclass(name = "WeakAuraDecoder")]
+pub struct PyWeakAuraDecoder;
+
+#[pymethods]
+impl PyWeakAuraDecoder {
+    /// Decode one import string (raises `ValueError` if it is invalid)
+    #[staticmethod]
+    fn decode(import_string: &str) -> PyResult<PyWeakAura> {
+        Ok(PyWeakAura(WeakAuraDecoder::decode(import_string)?))
+    }
+
+    /// Decode every import string in `input` (one per line).
+    /// Returns `(auras, errors)`.
+    #[staticmethod]
+    fn decode_multiple(input: &str) -> (Vec<PyWeakAura>, Vec<String>) {
+        let mut auras = Vec::new();
+        let mut errors = Vec::new();
+        for result in WeakAuraDecoder::decode_multiple(input) {
+            match result {
+                Ok(aura) => auras.push(PyWeakAura(aura)),
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+        (auras, errors)
+    }
+}
+
+/// An incoming aura that already exists in the SavedVariables
+#[pyclass(name = "ImportConflict")]
+pub struct PyImportConflict(ImportConflict);
+
+#[pymethods]
+impl PyImportConflict {
+    #[getter]
+    fn aura_id(&self) -> &str {
+        &self.0.aura_id
+    }
+
+    #[getter]
+    fn is_group(&self) -> bool {
+        self.0.is_group
+    }
+
+    #[getter]
+    fn child_count(&self) -> usize {
+        self.0.child_count
+    }
+
+    /// Display names of the categories that differ
+    #[getter]
+    fn changed_categories(&self) -> Vec<&'static str> {
+        self.0
+            .changed_categories
+            .iter()
+            .map(|c| c.display_name())
+            .collect()
+    }
+
+    /// Top-level fields that differ
+    #[getter]
+    fn changed_fields(&self) -> Vec<String> {
+        self.0.changed_fields()
+    }
+
+    /// `"newer"`, `"same"`, `"older"` or `"unknown"`
+    #[getter]
+    fn version_change(&self) -> &'static str {
+        self.0.version_change.label()
+    }
+
+    #[getter]
+    fn existing(&self, py: Python<'_>) -> PyResult<PyObject> {
+        lua_to_py(py, &self.0.existing)
+    }
+
+    #[getter]
+    fn incoming(&self, py: Python<'_>) -> PyResult<PyObject> {
+        lua_to_py(py, &self.0.incoming)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ImportConflict(aura_id={:?})", self.0.aura_id)
+    }
+}
+
+/// Outcome of an import
+#[pyclass(name = "ImportResult")]
+pub struct PyImportResult(ImportResult);
+
+#[pymethods]
+impl PyImportResult {
+    #[getter]
+    fn added(&self) -> Vec<String> {
+        self.0.added.clone()
+    }
+
+    #[getter]
+    fn replaced(&self) -> Vec<String> {
+        self.0.replaced.clone()
+    }
+
+    #[getter]
+    fn skipped(&self) -> Vec<String> {
+        self.0.skipped.clone()
+    }
+
+    /// `(aura_id, error)` pairs of auras left out as malformed
+    #[getter]
+    fn failed(&self) -> Vec<(String, String)> {
+        self.0.failed.clone()
+    }
+
+    fn summary(&self) -> String {
+        self.0.summary()
+    }
+
+    /// Per-aura outcomes as a JSON document
+    fn to_json(&self) -> PyResult<String> {
+        Ok(self.0.to_json_string()?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ImportResult({})", self.0.summary())
+    }
+}
+
+/// A WeakAuras SavedVariables file
+#[pyclass(name = "SavedVariablesManager")]
+pub struct PySavedVariablesManager(SavedVariablesManager);
+
+#[pymethods]
+impl PySavedVariablesManager {
+    #[new]
+    fn new(path: PathBuf) -> Self {
+        Self(SavedVariablesManager::new(path))
+    }
+
+    #[getter]
+    fn path(&self) -> PathBuf {
+        self.0.path.clone()
+    }
+
+    /// Read the file (raises `FileNotFoundError` if it does not exist)
+    fn load(&mut self) -> PyResult<()> {
+        Ok(self.0.load()?)
+    }
+
+    /// Write the file, keeping a `.lua.backup` of the previous version
+    fn save(&mut self) -> PyResult<()> {
+        Ok(self.0.save()?)
+    }
+
+    /// IDs of all installed auras, sorted
+    fn aura_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.0.displays.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Data of an installed aura, or `None`
+    fn get_aura(&self, py: Python<'_>, id: &str) -> PyResult<Option<PyObject>> {
+        self.0
+            .displays
+            .get(id)
+            .map(|data| lua_to_py(py, data))
+            .transpose()
+    }
+
+    /// Conflicts between `auras` and the installed auras
+    fn detect_conflicts(&self, auras: Vec<PyRef<'_, PyWeakAura>>) -> Vec<PyImportConflict> {
+        self.0
+            .detect_conflicts(&unwrap_auras(&auras))
+            .conflicts
+            .into_iter()
+            .map(PyImportConflict)
+            .collect()
+    }
+
+    /// Import `auras`, resolving conflicts with `policy` (`"skip"`, `"replace"`,
+    /// `"update"` or `"newer"`). `categories` selects what `"update"` changes.
+    #[pyo3(signature = (auras, policy = "update", categories = Vec::new()))]
+    fn import_with_policy(
+        &mut self,
+        auras: Vec<PyRef<'_, PyWeakAura>>,
+        policy: &str,
+        categories: Vec<String>,
+    ) -> PyResult<PyImportResult> {
+        let policy = ResolutionPolicy::from_name(policy, &categories)?;
+        Ok(PyImportResult(
+            self.0.import_with_policy(&unwrap_auras(&auras), &policy),
+        ))
+    }
+
+    /// Remove auras (and their children). Returns the removed IDs.
+    fn remove_auras(&mut self, ids: Vec<String>) -> Vec<String> {
+        self.0.remove_auras(&ids)
+    }
+}
+
+/// Python module `weakauras_mass_import`
+#[pymodule]
+fn weakauras_mass_import(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyWeakAura>()?;
+    m.add_class::<PyWeakAuraDecoder>()?;
+    m.add_class::<PyImportConflict>()?;
+    m.add_class::<PyImportResult>()?;
+    m.add_class::<PySavedVariablesManager>()?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//! Find-references search across stored aura data.
+//!
+//! Answers questions like "where is spell 375087 referenced?" by walking
+//! every display with [`LuaValue::walk`] and listing the matching fields.
+
+use serde::Serialize;
+
+use crate::decoder::{format_lua_path, LuaPathSegment, LuaTable, LuaValue};
+
+/// A field of a stored aura matching a search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Reference {
+    pub aura_id: String,
+    /// Path of the field in the aura data, e.g. `triggers[1].trigger.spellIds[1]`
+    pub path: String,
+    /// The field's value as text (`{...}` for tables)
+    pub value: String,
+}
+
+/// Fields of `displays` referencing `query`: string values containing it
+/// (ignoring case), numbers equal to it, and table keys equal to it. Spell
+/// IDs show up as all three depending on the field. An empty query finds
+/// nothing.
+pub fn find_references(displays: &LuaTable, query: &str) -> Vec<Reference> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let needle = query.to_lowercase();
+    let number = query.parse::<f64>().ok();
+
+    let mut references = Vec::new();
+    for (aura_id, data) in displays {
+        data.walk(&mut |path, value| {
+            let key_matches =
+                matches!(path.last(), Some(LuaPathSegment::Key(key)) if key.to_lowercase() == needle);
+            let value_matches = match value {
+                LuaValue::String(s) => s.to_lowercase().contains(&needle),
+                LuaValue::Number(n) => number == Some(*n),
+                _ => false,
+            };
+            if key_matches || value_matches {
+                references.push(Reference {
+                    aura_id: aura_id.clone(),
+                    path: format_lua_path(path),
+                    value: display_value(value),
+                });
+            }
+        });
+    }
+    references
+}
+
+fn display_value(value: &LuaValue) -> String {
+    match value {
+        LuaValue::Nil => "nil".to_string(),
+        LuaValue::Bool(b) => b.to_string(),
+        LuaValue::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => format!("{}", *n as i64),
+        LuaValue::Number(n) => n.to_string(),
+        LuaValue::String(s) => s.clone(),
+        LuaValue::Table(_) | LuaValue::Array(_) | LuaValue::MixedTable { .. } => {
+            "{...}".to_string()
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Repair of SavedVariables files that WoW left truncated or damaged.
+//!
+//! When the game crashes while writing `WeakAuras.lua`, the file ends in the
+//! middle of a display and [`SavedVariablesManager::load`] fails on it.
+//! [`SavedVariablesManager::load_salvaging`] keeps every complete display up
+//! to the damage and reports what was lost;
+//! [`SavedVariablesManager::write_repaired`] then writes the salvaged auras
+//! back, keeping the damaged original beside it.
+//!
+//! Often a whole copy survives instead: WoW keeps the previous file as
+//! `WeakAuras.lua.bak`, and each save of this tool leaves a backup.
+//! [`find_fallbacks`] lists the copies that still parse, newest first, and
+//! [`SavedVariablesManager::restore_fallback`] puts one in place.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::error::{Result, WeakAuraError};
+use crate::fs_util;
+use crate::lua_parser::{LuaParser, ParseDamage};
+use crate::saved_variables::{backup_path, format_age, lock_file, SavedVariablesManager};
+
+/// What salvaging a damaged file kept and lost
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RepairReport {
+    /// Displays read in full
+    pub recovered: usize,
+    /// Byte offset where the damage starts
+    pub damaged_at: usize,
+    /// Why the file could not be read from there on
+    pub error: String,
+    /// Displays that were cut off or came after the damage, as far as their
+    /// IDs can still be read
+    pub lost: Vec<String>,
+    /// Top-level entries of `WeakAurasSaved` (settings, the `displays`
+    /// table itself) that start after the damage and are dropped
+    pub lost_keys: Vec<String>,
+    /// Bytes of the file from the damage to its end, which are dropped
+    pub dropped_bytes: usize,
+}
+
+impl RepairReport {
+    /// One-line summary, e.g. "Recovered 41 aura(s); 2 lost after byte 81234",
+    /// followed by the number of lost top-level entries when there are any
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Recovered {} aura(s); {} lost after byte {}",
+            self.recovered,
+            self.lost.len(),
+            self.damaged_at
+        );
+        if !self.lost_keys.is_empty() {
+            summary.push_str(&format!(
+                "; {} top-level setting(s) lost",
+                self.lost_keys.len()
+            ));
+        }
+        summary
+    }
+}
+
+/// Where [`SavedVariablesManager::write_repaired`] keeps the damaged original
+pub fn damaged_path(path: &Path) -> PathBuf {
+    path.with_extension("lua.damaged")
+}
+
+/// Where WoW keeps the previous version of a SavedVariables file
+pub fn game_backup_path(path: &Path) -> PathBuf {
+    path.with_extension("lua.bak")
+}
+
+/// Who wrote a fallback copy of a SavedVariables file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FallbackSource {
+    /// `WeakAuras.lua.bak`, written by the game
+    Game,
+    /// The backup written by the last save of this tool
+    Importer,
+}
+
+impl FallbackSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FallbackSource::Game => "WoW's backup",
+            FallbackSource::Importer => "backup of the last save",
+        }
+    }
+}
+
+/// A copy of a SavedVariables file that parses
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FallbackCandidate {
+    pub path: PathBuf,
+    pub source: FallbackSource,
+    pub modified: Option<SystemTime>,
+    /// Displays in the copy
+    pub aura_count: usize,
+}
+
+impl FallbackCandidate {
+    /// e.g. "WeakAuras.lua.bak (WoW's backup, 2 hours ago, 41 aura(s))"
+    pub fn describe(&self) -> String {
+        let name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let age = self
+            .modified
+            .map(|modified| format!(", {}", format_age(modified)))
+            .unwrap_or_default();
+        format!(
+            "{} ({}{}, {} aura(s))",
+            name,
+            self.source.label(),
+            age,
+            self.aura_count
+        )
+    }
+}
+
+/// Copies of the SavedVariables file at `path` that parse, newest first
+pub fn find_fallbacks(path: &Path) -> Vec<FallbackCandidate> {
+    let mut candidates: Vec<FallbackCandidate> = [
+        (game_backup_path(path), FallbackSource::Game),
+        (backup_path(path), FallbackSource::Importer),
+    ]
+    .into_iter()
+    .filter_map(|(path, source)| {
+        let content = std::fs::read_to_string(&path).ok()?;
+        let saved = LuaParser::parse(&content).ok()?;
+        Some(FallbackCandidate {
+            modified: std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok(),
+            aura_count: saved.displays.len(),
+            path,
+            source,
+        })
+    })
+    .collect();
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.modified));
+    candidates
+}
+
+impl SavedVariablesManager {
+    /// Load the file like [`load`](Self::load), but keep what precedes
+    /// damage instead of failing on it. Returns `None` for an intact file.
+    pub fn load_salvaging(&mut self) -> Result<Option<RepairReport>> {
+        if !self.path.exists() {
+            return Err(WeakAuraError::FileNotFound(
+                self.path.to_string_lossy().to_string(),
+            ));
+        }
+        let _lock = match lock_file(&self.path, false) {
+            Err(WeakAuraError::IoError(e)) => {
+                warn!(error = %e, "Could not lock SavedVariables for reading");
+                None
+            }
+            lock => Some(lock?),
+        };
+        let Some(damage) = self.read_from_disk_with(true)? else {
+            return Ok(None);
+        };
+        let content = std::fs::read_to_string(&self.path)?;
+        Ok(Some(report(&content, damage, self.displays.len())))
+    }
+
+    /// Write the salvaged auras back over the damaged file, which is kept as
+    /// [`damaged_path`]. Returns where the original was kept. Like a save,
+    /// refuses files of a newer WeakAuras than supported.
+    pub fn write_repaired(&mut self) -> Result<PathBuf> {
+        self.check_db_version()?;
+        let damaged = damaged_path(&self.path);
+        {
+            let _lock = lock_file(&self.path, true)?;
+            fs_util::copy(&self.path, &damaged)?;
+            fs_util::write(&self.path, self.generate_lua())?;
+        }
+        self.load()?;
+        Ok(damaged)
+    }
+
+    /// Replace the file with a fallback copy and load it. The file it
+    /// replaces, when there is one, is kept as [`damaged_path`].
+    pub fn restore_fallback(&mut self, candidate: &FallbackCandidate) -> Result<()> {
+        {
+            let _lock = lock_file(&self.path, true)?;
+            if self.path.exists() {
+                fs_util::copy(&self.path, &damaged_path(&self.path))?;
+            }
+            fs_util::copy(&candidate.path, &self.path)?;
+        }
+        info!(
+            path = %self.path.display(),
+            source = %candidate.path.display(),
+            "Restored SavedVariables from a fallback copy"
+        );
+        self.load()
+    }
+}
+
+fn report(content: &str, damage: ParseDamage, recovered: usize) -> RepairReport {
+    let rest = content.get(damage.offset..).unwrap_or_default();
+    let mut lost: Vec<String> = damage.display.into_iter().collect();
+    for id in display_ids(rest, damage.in_displays) {
+        if !lost.contains(&id) {
+            lost.push(id);
+        }
+    }
+    RepairReport {
+        recovered,
+        damaged_at: damage.offset,
+        error: damage.error,
+        lost,
+        lost_keys: top_level_keys(rest),
+        dropped_bytes: rest.len(),
+    }
+}
+
+/// Keys of the top-level entries (`\t["key"] = ...`) starting in a piece of
+/// a SavedVariables file
+fn top_level_keys(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| line.strip_prefix("\t[\""))
+        .filter_map(|rest| rest.split_once("\"] = "))
+        .map(|(key, _)| key.replace("\\\"", "\"").replace("\\\\", "\\"))
+        .collect()
+}
+
+/// IDs of the display entries in a piece of a SavedVariables file, found by
+/// their lines (`\t\t["id"] = {` inside `\t["displays"] = {`) since the
+/// piece no longer parses
+fn display_ids(text: &str, starts_in_displays: bool) -> Vec<String> {
+    let mut in_displays = starts_in_displays;
+    let mut ids = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line == "\t[\"displays\"] = {" {
+            in_displays = true;
+        } else if line.starts_with("\t}") {
+            in_displays = false;
+        } else if in_displays {
+            let id = line
+                .strip_prefix("\t\t[\"")
+                .and_then(|rest| rest.strip_suffix("\"] = {"));
+            if let Some(id) = id {
+                ids.push(id.replace("\\\"", "\"").replace("\\\\", "\\"));
+            }
+        }
+    }
+    ids
+}
@@ -0,0 +1,159 @@
+//! Machine-readable import reports.
+//!
+//! An [`ImportReport`] bundles the [`ImportResult`] with every detected conflict,
+//! the resolution that was applied, and per-category field diffs, and can be
+//! written as JSON for auditing and tooling.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::categories::UpdateCategory;
+use crate::decoder::{LuaTable, LuaValue};
+use crate::error::{Result, WeakAuraError};
+use crate::saved_variables::{
+    changed_fields_by_category, ConflictAction, ConflictDetectionResult, ConflictResolution,
+    ImportConflict, ImportResult, VersionChange,
+};
+
+/// A single top-level field that differs between existing and incoming data
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldDiff {
+    pub field: String,
+    /// Existing value (`None` if the field is being added)
+    pub existing: Option<LuaValue>,
+    /// Incoming value (`None` if the field would be removed)
+    pub incoming: Option<LuaValue>,
+}
+
+/// All differing fields of one category
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CategoryDiff {
+    pub category: UpdateCategory,
+    pub fields: Vec<FieldDiff>,
+}
+
+/// Report entry for one conflicting aura
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictReport {
+    pub aura_id: String,
+    pub is_group: bool,
+    pub child_count: usize,
+    /// How the incoming version compares to the existing one
+    pub version_change: VersionChange,
+    /// Applied action (`None` if no resolution was given)
+    pub action: Option<ConflictAction>,
+    /// Categories selected for update (only for `UpdateSelected`)
+    pub categories_to_update: Vec<UpdateCategory>,
+    pub diffs: Vec<CategoryDiff>,
+}
+
+impl ConflictReport {
+    /// Build a report entry for a conflict and its (optional) resolution
+    pub fn new(conflict: &ImportConflict, resolution: Option<&ConflictResolution>) -> Self {
+        let categories_to_update = match resolution {
+            Some(r) if r.action == ConflictAction::UpdateSelected => UpdateCategory::all()
+                .into_iter()
+                .filter(|c| r.categories_to_update.contains(c))
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            aura_id: conflict.aura_id.clone(),
+            is_group: conflict.is_group,
+            child_count: conflict.child_count,
+            version_change: conflict.version_change,
+            action: resolution.map(|r| r.action),
+            categories_to_update,
+            diffs: diff_by_category(&conflict.existing, &conflict.incoming),
+        }
+    }
+}
+
+/// Full import report
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    /// SavedVariables file the import targeted
+    pub saved_variables: PathBuf,
+    /// Seconds since the Unix epoch when the report was created
+    pub generated_at: u64,
+    pub result: ImportResult,
+    /// IDs of auras that did not exist before the import
+    pub new_auras: Vec<String>,
+    pub conflicts: Vec<ConflictReport>,
+}
+
+impl ImportReport {
+    /// Create a report for an import result
+    pub fn new(saved_variables: &Path, result: &ImportResult) -> Self {
+        Self {
+            saved_variables: saved_variables.to_path_buf(),
+            generated_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            result: result.clone(),
+            new_auras: Vec::new(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    /// Attach detected conflicts and the resolutions applied to them
+    pub fn with_conflicts(
+        mut self,
+        detection: &ConflictDetectionResult,
+        resolutions: &[ConflictResolution],
+    ) -> Self {
+        self.new_auras = detection
+            .new_auras
+            .iter()
+            .map(|(id, _)| id.clone())
+            .collect();
+        self.conflicts = detection
+            .conflicts
+            .iter()
+            .map(|conflict| {
+                let resolution = resolutions.iter().find(|r| r.aura_id == conflict.aura_id);
+                ConflictReport::new(conflict, resolution)
+            })
+            .collect();
+        self
+    }
+
+    /// Serialize to pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| WeakAuraError::SerializationError(e.to_string()))
+    }
+
+    /// Write the report as JSON
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+/// Group top-level field differences by update category.
+/// Internal fields are ignored; categories and fields are sorted for stable output.
+pub fn diff_by_category(existing: &LuaValue, incoming: &LuaValue) -> Vec<CategoryDiff> {
+    let empty = LuaTable::new();
+    let existing_table = existing.as_table().unwrap_or(&empty);
+    let incoming_table = incoming.as_table().unwrap_or(&empty);
+
+    changed_fields_by_category(existing, incoming)
+        .into_iter()
+        .map(|(category, fields)| CategoryDiff {
+            category,
+            fields: fields
+                .into_iter()
+                .map(|field| FieldDiff {
+                    existing: existing_table.get(&field).cloned(),
+                    incoming: incoming_table.get(&field).cloned(),
+                    field,
+                })
+                .collect(),
+        })
+        .collect()
+}
@@ -0,0 +1,381 @@
+//! JSON-RPC 2.0 server for driving imports from other local tools.
+//!
+//! Started with `weakauras-mass-import serve [--addr 127.0.0.1:PORT]`. Each
+//! connection sends one request per line and receives one response per line.
+//! Only loopback addresses can be served.
+//!
+//! The server writes a fresh token to `rpc.token` in the config directory at
+//! startup. The first request of a connection must be `authenticate {token}`;
+//! a connection is closed on a wrong token, a request before authenticating,
+//! or a line that is not a JSON-RPC 2.0 request (e.g. an HTTP request a web
+//! page sent to the port).
+//!
+//! Methods (`saved_variables` is the path of a `.lua` file in one of the
+//! SavedVariables folders of the bookmarked and discovered WoW installations):
+//! - `authenticate {token}` → `true`
+//! - `list_auras {saved_variables}` → aura tree
+//! - `decode {string}` → decoded aura
+//! - `import {saved_variables, strings, policy?, categories?, duplicates?, dry_run?}`
+//!   → import result
+//!   (`policy` as in [`ResolutionPolicy::from_name`], default `"update"`;
+//!   `duplicates` is `"keep_first"` (default), `"keep_last"` or `"ask"`)
+//! - `remove {saved_variables, ids}` → removed IDs
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::api::Importer;
+use crate::config::AppConfig;
+use crate::decoder::WeakAuraDecoder;
+use crate::discovery;
+use crate::duplicates::DuplicatePolicy;
+use crate::error::{Result, WeakAuraError};
+use crate::policy::ResolutionPolicy;
+use crate::saved_variables::SavedVariablesManager;
+use crate::util;
+
+/// Default listen address (loopback only)
+pub const DEFAULT_ADDR: &str = "127.0.0.1:47821";
+
+/// File in the config directory holding the running server's token
+pub const TOKEN_FILE: &str = "rpc.token";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+const UNAUTHORIZED: i64 = -32001;
+const FORBIDDEN_PATH: i64 = -32002;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct AuthenticateParams {
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct FileParams {
+    saved_variables: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct DecodeParams {
+    string: String,
+}
+
+#[derive(Deserialize)]
+struct ImportParams {
+    saved_variables: PathBuf,
+    strings: Vec<String>,
+    #[serde(default = "default_policy")]
+    policy: String,
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(default)]
+    duplicates: DuplicatePolicy,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Deserialize)]
+struct RemoveParams {
+    saved_variables: PathBuf,
+    ids: Vec<String>,
+}
+
+fn default_policy() -> String {
+    "update".to_string()
+}
+
+/// Error of a single call: JSON-RPC error code and message
+struct CallError(i64, String);
+
+impl From<WeakAuraError> for CallError {
+    fn from(e: WeakAuraError) -> Self {
+        Self(SERVER_ERROR, e.to_string())
+    }
+}
+
+fn params<T: DeserializeOwned>(value: Value) -> std::result::Result<T, CallError> {
+    serde_json::from_value(value).map_err(|e| CallError(INVALID_PARAMS, e.to_string()))
+}
+
+fn to_value<T: serde::Serialize>(value: T) -> std::result::Result<Value, CallError> {
+    serde_json::to_value(value).map_err(|e| CallError(SERVER_ERROR, e.to_string()))
+}
+
+fn load_manager(path: PathBuf) -> Result<SavedVariablesManager> {
+    let mut manager = SavedVariablesManager::new(path);
+    manager.load()?;
+    Ok(manager)
+}
+
+/// Access rules of a server: the token clients authenticate with and the
+/// SavedVariables folders requests may name files in
+pub struct RpcServer {
+    token: String,
+    roots: Vec<PathBuf>,
+}
+
+impl Default for RpcServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RpcServer {
+    /// Server for the SavedVariables folders of the bookmarked and
+    /// discovered WoW installations, with a fresh token
+    pub fn new() -> Self {
+        let wow_paths = discovery::dedup_paths(
+            AppConfig::load()
+                .wow_paths
+                .into_iter()
+                .chain(SavedVariablesManager::find_wow_paths()),
+        );
+        Self::with_roots(
+            SavedVariablesManager::find_saved_variables_in(&wow_paths)
+                .into_iter()
+                .filter_map(|info| info.path.parent().map(Path::to_path_buf))
+                .collect(),
+        )
+    }
+
+    /// Server for the files in `roots` (and their subfolders), with a fresh
+    /// token. Folders that do not exist are left out.
+    pub fn with_roots(roots: Vec<PathBuf>) -> Self {
+        let mut roots: Vec<PathBuf> = roots
+            .iter()
+            .filter_map(|root| root.canonicalize().ok())
+            .collect();
+        roots.sort();
+        roots.dedup();
+        Self {
+            token: util::random_token(),
+            roots,
+        }
+    }
+
+    /// Token clients must send with `authenticate`
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Folders requests may name files in
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// State of a new connection, which starts unauthenticated
+    pub fn session(&self) -> Session<'_> {
+        Session {
+            server: self,
+            authenticated: false,
+        }
+    }
+
+    /// Listen on `addr` and serve requests until the process exits. Only
+    /// loopback addresses are accepted. The token is written to
+    /// [`TOKEN_FILE`] in the config directory for local clients, and every
+    /// connection is handled on its own thread.
+    pub fn serve(self, addr: impl ToSocketAddrs) -> Result<()> {
+        let addrs = util::loopback_addrs(addr)?;
+        let listener = TcpListener::bind(&addrs[..])?;
+        let token_file = AppConfig::write_token(TOKEN_FILE, &self.token)?;
+        info!(
+            addr = %listener.local_addr()?,
+            token_file = %token_file.display(),
+            folders = self.roots.len(),
+            "JSON-RPC server listening"
+        );
+
+        let server = Arc::new(self);
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!(error = %e, "Failed to accept connection");
+                    continue;
+                }
+            };
+            let server = Arc::clone(&server);
+            thread::spawn(move || {
+                if let Err(e) = handle_connection(&server, stream) {
+                    warn!(error = %e, "JSON-RPC connection closed with error");
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// `path` if requests may read and write it: a `.lua` file in one of
+    /// the allowed folders
+    fn allowed_path(&self, path: &Path) -> std::result::Result<PathBuf, CallError> {
+        let forbidden = || {
+            CallError(
+                FORBIDDEN_PATH,
+                format!(
+                    "{} is not a .lua file in a SavedVariables folder of a known WoW installation",
+                    path.display()
+                ),
+            )
+        };
+        let is_lua = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("lua"));
+        let (Some(parent), Some(name), true) = (path.parent(), path.file_name(), is_lua) else {
+            return Err(forbidden());
+        };
+        let folder = parent.canonicalize().map_err(|_| forbidden())?;
+        if !self.roots.iter().any(|root| folder.starts_with(root)) {
+            return Err(forbidden());
+        }
+        Ok(folder.join(name))
+    }
+
+    fn call(&self, method: &str, raw_params: Value) -> std::result::Result<Value, CallError> {
+        match method {
+            "list_auras" => {
+                let p: FileParams = params(raw_params)?;
+                let path = self.allowed_path(&p.saved_variables)?;
+                to_value(load_manager(path)?.get_aura_tree())
+            }
+            "decode" => {
+                let p: DecodeParams = params(raw_params)?;
+                to_value(WeakAuraDecoder::decode(&p.string)?)
+            }
+            "import" => {
+                let p: ImportParams = params(raw_params)?;
+                let path = self.allowed_path(&p.saved_variables)?;
+                let policy = ResolutionPolicy::from_name(&p.policy, &p.categories)
+                    .map_err(|e| CallError(INVALID_PARAMS, e.to_string()))?;
+                let result = Importer::new(path)
+                    .add_strings(p.strings)
+                    .policy(policy)
+                    .duplicates(p.duplicates)
+                    .dry_run(p.dry_run)
+                    .run()?;
+                to_value(result)
+            }
+            "remove" => {
+                let p: RemoveParams = params(raw_params)?;
+                let mut manager = load_manager(self.allowed_path(&p.saved_variables)?)?;
+                let removed = manager.remove_auras(&p.ids);
+                if !removed.is_empty() {
+                    manager.save()?;
+                }
+                to_value(removed)
+            }
+            other => Err(CallError(
+                METHOD_NOT_FOUND,
+                format!("Unknown method '{}'", other),
+            )),
+        }
+    }
+}
+
+/// Response to one request line
+pub struct Reply {
+    pub response: String,
+    /// The connection must be closed after sending the response
+    pub close: bool,
+}
+
+/// One connection to an [`RpcServer`]
+pub struct Session<'a> {
+    server: &'a RpcServer,
+    authenticated: bool,
+}
+
+impl Session<'_> {
+    /// Handle one JSON-RPC request line
+    pub fn handle_request(&mut self, line: &str) -> Reply {
+        let closing = |code, message: String| Reply {
+            response: error_response(Value::Null, code, message).to_string(),
+            close: true,
+        };
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => return closing(PARSE_ERROR, e.to_string()),
+        };
+        let request = match serde_json::from_value::<Request>(value) {
+            Ok(request) if request.jsonrpc == "2.0" => request,
+            Ok(_) => return closing(INVALID_REQUEST, "Not a JSON-RPC 2.0 request".to_string()),
+            Err(e) => return closing(INVALID_REQUEST, e.to_string()),
+        };
+
+        if request.method == "authenticate" || !self.authenticated {
+            return self.authenticate(request);
+        }
+        let response = match self.server.call(&request.method, request.params) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request.id, "result": result }),
+            Err(CallError(code, message)) => error_response(request.id, code, message),
+        };
+        Reply {
+            response: response.to_string(),
+            close: false,
+        }
+    }
+
+    /// Handle `authenticate`, or a request sent before it
+    fn authenticate(&mut self, request: Request) -> Reply {
+        let accepted = request.method == "authenticate"
+            && params::<AuthenticateParams>(request.params)
+                .is_ok_and(|p| p.token == self.server.token);
+        if accepted {
+            self.authenticated = true;
+            return Reply {
+                response: json!({ "jsonrpc": "2.0", "id": request.id, "result": true }).to_string(),
+                close: false,
+            };
+        }
+        let message = if request.method == "authenticate" {
+            "Wrong token"
+        } else {
+            "Send `authenticate` with the server token first"
+        };
+        Reply {
+            response: error_response(request.id, UNAUTHORIZED, message.to_string()).to_string(),
+            close: true,
+        }
+    }
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn handle_connection(server: &RpcServer, stream: TcpStream) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut session = server.session();
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = session.handle_request(&line);
+        writeln!(writer, "{}", reply.response)?;
+        if reply.close {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -3,25 +3,71 @@
 //! Handles reading and writing WeakAuras SavedVariables files.
 
 use crate::categories::{CategoryMapper, UpdateCategory};
-use crate::decoder::{LuaValue, WeakAura};
+use crate::crash;
+use crate::decoder::{LuaTable, LuaValue, WeakAura};
+use crate::discovery::{self, WOW_FLAVORS};
 use crate::error::{Result, WeakAuraError};
-use crate::lua_parser::LuaParser;
-use crate::util;
-use std::collections::{HashMap, HashSet};
+use crate::fs_util;
+use crate::lua_parser::{ordered_keys, LuaParser, ParseDamage};
+use crate::model::AuraProperties;
+use crate::snapshots::SnapshotStore;
+use crate::util::{self, csv_field};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 use tracing::warn;
 
+/// Newest WeakAuras `dbVersion` the writer has been validated against.
+/// Files written by newer WeakAuras releases may use layouts this tool doesn't know.
+pub const MAX_SUPPORTED_DB_VERSION: u32 = 86;
+
+/// How long to wait for another program to release a SavedVariables lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Deepest group nesting shown in [`SavedVariablesManager::get_aura_tree`].
+/// WeakAuras itself stops at [`crate::nesting::MAX_GROUP_DEPTH`]; damaged
+/// files can chain far more, and the tree is walked recursively.
+const MAX_TREE_DEPTH: usize = 64;
+
 /// Manages WeakAuras SavedVariables
 pub struct SavedVariablesManager {
     /// Path to the SavedVariables file
     pub path: PathBuf,
-    /// Loaded displays
-    pub displays: HashMap<String, LuaValue>,
+    /// Loaded displays. Edits made directly here are not re-applied if the
+    /// file changes on disk before [`save`](Self::save); use the manager's methods.
+    pub displays: LuaTable,
     /// Other fields (metadata like dbVersion, minimap, registered, etc.)
-    other_fields: HashMap<String, LuaValue>,
-    /// Raw file content for backup
+    other_fields: LuaTable,
+    /// Raw file content, the source of unchanged displays when saving
     raw_content: Option<String>,
+    /// Serialized text of each display, reused by [`generate_lua`](Self::generate_lua)
+    /// while the display is unchanged
+    serialized: RefCell<HashMap<String, SerializedDisplay>>,
+    /// Modification time and size of the file as last loaded or saved, used
+    /// to detect writes by other programs in between
+    disk_stamp: Option<DiskStamp>,
+    /// Changes made since the file was loaded, replayed onto the fresh file
+    /// if another program wrote it in the meantime
+    pending: Vec<PendingOp>,
+    /// Metrics and group IDs of the file as loaded, for [`comparison`](Self::comparison)
+    baseline: Option<(FileMetrics, HashSet<String>)>,
+    /// Never overwrite position and size fields of existing auras
+    /// (see [`CategoryMapper::is_position_field`])
+    pub preserve_position: bool,
+    /// Carry the user's author-option values (`config`) over into replaced auras
+    pub keep_user_config: bool,
+    /// Write table keys sorted instead of in the order they were loaded or imported
+    pub sort_keys: bool,
+    /// Save files whose `dbVersion` is newer than [`MAX_SUPPORTED_DB_VERSION`]
+    pub allow_newer_db_version: bool,
 }
 
 impl SavedVariablesManager {
@@ -29,13 +75,21 @@ impl SavedVariablesManager {
     pub fn new(path: PathBuf) -> Self {
         Self {
             path,
-            displays: HashMap::new(),
-            other_fields: HashMap::new(),
+            displays: LuaTable::new(),
+            other_fields: LuaTable::new(),
             raw_content: None,
+            serialized: RefCell::default(),
+            disk_stamp: None,
+            pending: Vec::new(),
+            baseline: None,
+            preserve_position: false,
+            keep_user_config: true,
+            sort_keys: false,
+            allow_newer_db_version: false,
         }
     }
 
-    /// Find WoW installation paths (common locations)
+    /// Find WoW installation paths (common locations plus Battle.net launcher data)
     pub fn find_wow_paths() -> Vec<PathBuf> {
         let mut paths = Vec::new();
 
@@ -73,29 +127,13 @@ impl SavedVariablesManager {
             }
         }
 
-        #[cfg(target_os = "linux")]
-        {
-            let home = std::env::var("HOME").unwrap_or_default();
-            // Wine/Lutris common paths
-            let common_paths = [
-                format!(
-                    "{}/.wine/drive_c/Program Files (x86)/World of Warcraft",
-                    home
-                ),
-                format!(
-                    "{}/Games/world-of-warcraft/drive_c/Program Files (x86)/World of Warcraft",
-                    home
-                ),
-            ];
-            for path in common_paths {
-                let p = PathBuf::from(&path);
-                if p.exists() {
-                    paths.push(p);
-                }
-            }
-        }
+        // Wine prefixes (plain Wine, Lutris, Bottles, Steam Proton; native and Flatpak)
+        paths.extend(discovery::find_prefix_installs());
 
-        paths
+        // Installs registered with the Battle.net launcher (non-standard drives, multiple installs)
+        paths.extend(discovery::find_launcher_installs());
+
+        discovery::dedup_paths(paths)
     }
 
     /// Find SavedVariables files for all accounts
@@ -103,29 +141,28 @@ impl SavedVariablesManager {
         let mut results = Vec::new();
 
         // Check both retail and classic
-        let flavors = [
-            "_retail_",
-            "_classic_",
-            "_classic_era_",
-            "_anniversary_",
-            "_ptr_",
-            "_beta_",
-        ];
-
-        for flavor in &flavors {
-            let wtf_path = wow_path.join(flavor).join("WTF").join("Account");
+        for flavor in WOW_FLAVORS {
+            let flavor_dir = wow_path.join(flavor);
+            let wtf_path = flavor_dir.join("WTF").join("Account");
             if wtf_path.exists() {
+                let addon_installed = discovery::weakauras_installed_in(&flavor_dir);
                 if let Ok(accounts) = fs::read_dir(&wtf_path) {
                     for account in accounts.flatten() {
                         if account.file_type().map(|t| t.is_dir()).unwrap_or(false) {
                             let account_name = account.file_name().to_string_lossy().to_string();
                             let sv_path =
                                 account.path().join("SavedVariables").join("WeakAuras.lua");
-                            if sv_path.exists() {
+                            if let Ok(metadata) = fs::metadata(&sv_path) {
                                 results.push(SavedVariablesInfo {
                                     path: sv_path,
                                     account: account_name,
                                     flavor: flavor.trim_matches('_').to_string(),
+                                    install: wow_path.to_path_buf(),
+                                    size: metadata.len(),
+                                    modified: metadata.modified().ok(),
+                                    aura_count: None,
+                                    db_version: None,
+                                    addon_installed: Some(addon_installed),
                                 });
                             }
                         }
@@ -137,6 +174,42 @@ impl SavedVariablesManager {
         results
     }
 
+    /// Find SavedVariables files across several WoW installation roots
+    pub fn find_saved_variables_in(wow_paths: &[PathBuf]) -> Vec<SavedVariablesInfo> {
+        let mut seen = HashSet::new();
+        wow_paths
+            .iter()
+            .flat_map(|root| Self::find_saved_variables(root))
+            .filter(|info| seen.insert(info.path.clone()))
+            .collect()
+    }
+
+    /// `dbVersion` of the loaded file (the WeakAuras database schema version)
+    pub fn db_version(&self) -> Option<u32> {
+        match self.other_fields.get("dbVersion") {
+            Some(LuaValue::Number(n)) if *n >= 0.0 => Some(*n as u32),
+            _ => None,
+        }
+    }
+
+    /// Whether the loaded file comes from a WeakAuras release newer than
+    /// the writer has been validated against
+    #[allow(dead_code)]
+    pub fn has_unsupported_db_version(&self) -> bool {
+        self.db_version()
+            .is_some_and(|version| version > MAX_SUPPORTED_DB_VERSION)
+    }
+
+    /// Refuse to write a file with an unsupported `dbVersion` unless allowed
+    pub(crate) fn check_db_version(&self) -> Result<()> {
+        match self.db_version() {
+            Some(version) if version > MAX_SUPPORTED_DB_VERSION && !self.allow_newer_db_version => {
+                Err(WeakAuraError::UnsupportedDbVersion(version))
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Load the SavedVariables file
     pub fn load(&mut self) -> Result<()> {
         if !self.path.exists() {
@@ -145,33 +218,165 @@ impl SavedVariablesManager {
             ));
         }
 
+        // Reading without the lock is better than not reading at all
+        // (e.g. a read-only folder where the sidecar can't be created)
+        let _lock = match lock_file(&self.path, false) {
+            Err(WeakAuraError::IoError(e)) => {
+                warn!(error = %e, "Could not lock SavedVariables for reading");
+                None
+            }
+            lock => Some(lock?),
+        };
+        self.read_from_disk()?;
+        self.pending.clear();
+        self.baseline = Some((self.metrics(), self.group_ids()));
+
+        Ok(())
+    }
+
+    /// Aura count, group count and file size as of now. The size is the
+    /// file's on disk as last loaded or saved.
+    pub fn metrics(&self) -> FileMetrics {
+        FileMetrics {
+            aura_count: self.displays.len(),
+            group_count: self.group_ids().len(),
+            file_size: self.disk_stamp.as_ref().map_or(0, |stamp| stamp.len),
+        }
+    }
+
+    /// How the file changed since it was loaded (from nothing when it did
+    /// not exist), e.g. after saving an import
+    pub fn comparison(&self) -> FileComparison {
+        let (before, groups_before) = self.baseline.clone().unwrap_or_default();
+        let mut new_groups: Vec<String> = self
+            .group_ids()
+            .into_iter()
+            .filter(|id| !groups_before.contains(id))
+            .collect();
+        new_groups.sort();
+        FileComparison {
+            before,
+            after: self.metrics(),
+            new_groups,
+        }
+    }
+
+    fn group_ids(&self) -> HashSet<String> {
+        self.displays
+            .iter()
+            .filter(|(_, data)| {
+                matches!(
+                    data.get_path("regionType"),
+                    Some(LuaValue::String(rt)) if rt == "group" || rt == "dynamicgroup"
+                )
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Read and parse the file; the caller holds the lock
+    fn read_from_disk(&mut self) -> Result<()> {
+        self.read_from_disk_with(false).map(|_| ())
+    }
+
+    /// Read and parse the file, keeping what precedes any damage when
+    /// `salvage` is set; the caller holds the lock
+    pub(crate) fn read_from_disk_with(&mut self, salvage: bool) -> Result<Option<ParseDamage>> {
+        self.disk_stamp = DiskStamp::read(&self.path);
         let content = fs::read_to_string(&self.path)?;
-        self.raw_content = Some(content.clone());
+        let (saved, damage) = if salvage {
+            LuaParser::parse_salvaging(&content)?
+        } else {
+            (LuaParser::parse(&content)?, None)
+        };
 
-        let saved = LuaParser::parse(&content)?;
+        // Unchanged displays are written back exactly as they were read
+        let serialized = saved
+            .display_spans
+            .into_iter()
+            .filter_map(|(id, span)| {
+                let value = saved.displays.get(&id)?.clone();
+                let text = SerializedText::Source(span);
+                Some((id, SerializedDisplay { value, text }))
+            })
+            .collect();
+        self.serialized = RefCell::new(serialized);
+
+        self.raw_content = Some(content);
         self.displays = saved.displays;
         self.other_fields = saved.other;
 
+        Ok(damage)
+    }
+
+    /// Re-read a file that changed on disk and apply the pending changes to it again
+    fn reload_and_replay(&mut self) -> Result<()> {
+        let pending = std::mem::take(&mut self.pending);
+        warn!(
+            path = %self.path.display(),
+            changes = pending.len(),
+            "SavedVariables changed on disk since loading, re-applying changes"
+        );
+        self.read_from_disk()?;
+        for op in pending {
+            match op {
+                PendingOp::AddAuras(auras) => {
+                    self.add_auras(&auras)?;
+                }
+                PendingOp::ApplyResolutions(detection, resolutions) => {
+                    self.apply_resolutions(&detection, &resolutions);
+                }
+                PendingOp::RemoveAuras(ids) => {
+                    self.remove_auras(&ids);
+                }
+                PendingOp::DissolveGroup(id) => {
+                    self.dissolve_group(&id);
+                }
+                PendingOp::MoveIntoGroup(group_id, ids) => {
+                    self.move_into_group(&group_id, &ids);
+                }
+                PendingOp::AnchorToScreen(ids) => {
+                    self.anchor_to_screen(&ids);
+                }
+                PendingOp::RenameAura(old_id, new_id) => {
+                    if let Err(e) = self.rename_aura(&old_id, &new_id) {
+                        warn!(error = %e, "Could not re-apply rename");
+                    }
+                }
+                PendingOp::SetProperties(updates) => {
+                    self.set_properties(&updates);
+                }
+                PendingOp::SetFields(changes) => {
+                    self.set_fields(&changes);
+                }
+            }
+        }
         Ok(())
     }
 
-    /// Add multiple auras
+    /// Add multiple auras. Each aura goes in with its whole group hierarchy
+    /// or not at all: malformed auras are left out and listed in
+    /// [`ImportResult::failed`], the rest of the batch is still added.
     pub fn add_auras(&mut self, auras: &[WeakAura]) -> Result<ImportResult> {
-        let mut added = Vec::new();
-        let skipped = Vec::new();
-        let mut replaced = Vec::new();
+        let mut result = ImportResult::default();
+        let mut added = Vec::with_capacity(auras.len());
 
         for aura in auras {
+            if let Err(e) = check_hierarchy(aura) {
+                warn!(aura_id = %aura.id, error = %e, "add_auras: skipping malformed aura");
+                result.failed.push((aura.id.clone(), e));
+                continue;
+            }
             let hierarchy = util::build_children_hierarchy(aura);
 
             // Insert all prepared children into displays
-            for (child_id, child_value) in &hierarchy.prepared_children {
-                if self.displays.contains_key(child_id) {
-                    replaced.push(child_id.clone());
+            for (child_id, child_value) in hierarchy.prepared_children {
+                if self.displays.contains_key(&child_id) {
+                    result.record(&child_id, ImportOutcome::Replaced);
                 } else {
-                    added.push(child_id.clone());
+                    result.record(&child_id, ImportOutcome::Added);
                 }
-                self.displays.insert(child_id.clone(), child_value.clone());
+                self.displays.insert(child_id, child_value);
             }
 
             // Update subgroups' controlledChildren in the displays map
@@ -185,18 +390,18 @@ impl SavedVariablesManager {
             }
 
             if self.displays.contains_key(&aura.id) {
-                replaced.push(aura.id.clone());
+                result.record(&aura.id, ImportOutcome::Replaced);
             } else {
-                added.push(aura.id.clone());
+                result.record(&aura.id, ImportOutcome::Added);
             }
             self.displays.insert(aura.id.clone(), parent_data);
+            added.push(aura.clone());
         }
 
-        Ok(ImportResult {
-            added,
-            skipped,
-            replaced,
-        })
+        if !added.is_empty() {
+            self.pending.push(PendingOp::AddAuras(added));
+        }
+        Ok(result)
     }
 
     /// Detect conflicts between incoming auras and existing ones
@@ -204,6 +409,7 @@ impl SavedVariablesManager {
         let mut result = ConflictDetectionResult::default();
 
         for aura in auras {
+            result.roots.push(aura.id.clone());
             let hierarchy = util::build_children_hierarchy(aura);
             if !hierarchy.children_by_parent.is_empty() {
                 for (parent_id, child_ids) in &hierarchy.children_by_parent {
@@ -233,7 +439,7 @@ impl SavedVariablesManager {
             if let Some(existing) = self.displays.get(&aura.id) {
                 let conflict = ImportConflict::new(
                     aura.id.clone(),
-                    parent_data.clone(),
+                    parent_data,
                     existing.clone(),
                     aura.is_group,
                     total_child_count,
@@ -246,21 +452,21 @@ impl SavedVariablesManager {
             }
 
             // Check child auras
-            for (child_id, child_value) in &hierarchy.prepared_children {
-                let is_subgroup = hierarchy.children_by_parent.contains_key(child_id);
+            for (child_id, child_value) in hierarchy.prepared_children {
+                let is_subgroup = hierarchy.children_by_parent.contains_key(&child_id);
                 let subgroup_child_count = if is_subgroup {
                     hierarchy
                         .children_by_parent
-                        .get(child_id)
+                        .get(&child_id)
                         .map_or(0, |v| v.len())
                 } else {
                     0
                 };
 
-                if let Some(existing) = self.displays.get(child_id) {
+                if let Some(existing) = self.displays.get(&child_id) {
                     let conflict = ImportConflict::new(
-                        child_id.clone(),
-                        child_value.clone(),
+                        child_id,
+                        child_value,
                         existing.clone(),
                         is_subgroup,
                         subgroup_child_count,
@@ -269,22 +475,65 @@ impl SavedVariablesManager {
                         result.conflicts.push(conflict);
                     }
                 } else {
-                    result
-                        .new_auras
-                        .push((child_id.clone(), child_value.clone()));
+                    result.new_auras.push((child_id, child_value));
                 }
             }
         }
 
+        result.uid_collisions = self.uid_collisions(&result);
         result
     }
 
-    /// Perform selective merge based on category selection
+    /// Incoming auras whose uid already belongs to a different aura, either
+    /// stored or earlier in the same import
+    fn uid_collisions(&self, result: &ConflictDetectionResult) -> Vec<UidCollision> {
+        fn uid_of(data: &LuaValue) -> Option<&str> {
+            data.get_path("uid").and_then(LuaValue::as_str)
+        }
+        let mut owners: HashMap<&str, &str> = self
+            .displays
+            .iter()
+            .filter_map(|(id, data)| Some((uid_of(data)?, id.as_str())))
+            .collect();
+
+        let incoming = result
+            .new_auras
+            .iter()
+            .map(|(id, data)| (id.as_str(), data))
+            .chain(
+                result
+                    .conflicts
+                    .iter()
+                    .map(|c| (c.aura_id.as_str(), &c.incoming)),
+            );
+        let mut collisions = Vec::new();
+        for (aura_id, data) in incoming {
+            let Some(uid) = uid_of(data) else {
+                continue;
+            };
+            match owners.get(uid) {
+                Some(owner) if *owner != aura_id => collisions.push(UidCollision {
+                    aura_id: aura_id.to_string(),
+                    uid: uid.to_string(),
+                    existing_id: owner.to_string(),
+                }),
+                Some(_) => {}
+                None => {
+                    owners.insert(uid, aura_id);
+                }
+            }
+        }
+        collisions
+    }
+
+    /// Perform selective merge based on category selection.
+    /// Position and size fields are skipped when `preserve_position` is set.
     pub fn selective_merge(
         &mut self,
         conflict: &ImportConflict,
         categories: &HashSet<UpdateCategory>,
     ) {
+        let preserve_position = self.preserve_position;
         let Some(existing) = self.displays.get_mut(&conflict.aura_id) else {
             warn!(aura_id = %conflict.aura_id, "selective_merge: aura not found in displays");
             return;
@@ -315,27 +564,144 @@ impl SavedVariablesManager {
                 }
             } else {
                 // Copy specific fields for this category
-                for field in fields {
-                    if let Some(value) = incoming_table.get(*field) {
-                        existing_table.insert(field.to_string(), value.clone());
-                    } else {
-                        // Field exists in existing but not incoming - remove it
-                        existing_table.remove(*field);
-                    }
-                }
+                copy_fields(
+                    existing_table,
+                    incoming_table,
+                    fields
+                        .iter()
+                        .copied()
+                        .filter(|f| !(preserve_position && CategoryMapper::is_position_field(f))),
+                );
             }
         }
     }
 
+    /// Perform a field-level merge: copy only the given top-level fields from
+    /// incoming data. Fields missing from incoming are removed; internal fields (and
+    /// position fields when `preserve_position` is set) are ignored.
+    pub fn selective_merge_fields(&mut self, conflict: &ImportConflict, fields: &BTreeSet<String>) {
+        if fields.is_empty() {
+            return;
+        }
+        let preserve_position = self.preserve_position;
+        let Some(existing_table) = self
+            .displays
+            .get_mut(&conflict.aura_id)
+            .and_then(LuaValue::as_table_mut)
+        else {
+            warn!(aura_id = %conflict.aura_id, "selective_merge_fields: existing aura missing or not a table");
+            return;
+        };
+        let Some(incoming_table) = conflict.incoming.as_table() else {
+            warn!(aura_id = %conflict.aura_id, "selective_merge_fields: incoming data is not a table variant");
+            return;
+        };
+        copy_fields(
+            existing_table,
+            incoming_table,
+            fields
+                .iter()
+                .map(String::as_str)
+                .filter(|f| !CategoryMapper::is_internal_field(f))
+                .filter(|f| !(preserve_position && CategoryMapper::is_position_field(f))),
+        );
+    }
+
+    /// Three-way merge an existing aura with incoming data, using `base` (the
+    /// version last imported) to tell author changes from local customizations
+    pub fn merge_with_base(&mut self, conflict: &ImportConflict, base: &LuaValue) -> ThreeWayMerge {
+        let Some(existing) = self.displays.get_mut(&conflict.aura_id) else {
+            warn!(aura_id = %conflict.aura_id, "merge_with_base: aura not found in displays");
+            return ThreeWayMerge::default();
+        };
+
+        let merge = three_way_merge(base, existing, &conflict.incoming);
+        if !merge.conflicts.is_empty() {
+            warn!(
+                aura_id = %conflict.aura_id,
+                fields = ?merge.conflicts,
+                "merge_with_base: kept local values for fields changed on both sides"
+            );
+        }
+        *existing = merge.merged.clone();
+        self.restore_position(conflict);
+        merge
+    }
+
+    /// Replace an existing aura with the incoming data
+    fn replace_aura(&mut self, conflict: &ImportConflict) {
+        let mut data = conflict.incoming.clone();
+        if self.keep_user_config {
+            carry_over_user_config(&conflict.existing, &mut data);
+        }
+        self.displays.insert(conflict.aura_id.clone(), data);
+        self.restore_position(conflict);
+    }
+
+    /// Put back the position and size fields the aura had before the import,
+    /// if `preserve_position` is set
+    fn restore_position(&mut self, conflict: &ImportConflict) {
+        if !self.preserve_position {
+            return;
+        }
+        let (Some(existing_table), Some(original)) = (
+            self.displays
+                .get_mut(&conflict.aura_id)
+                .and_then(LuaValue::as_table_mut),
+            conflict.existing.as_table(),
+        ) else {
+            return;
+        };
+        for field in CategoryMapper::position_fields() {
+            match original.get(*field) {
+                Some(value) => existing_table.insert(field.to_string(), value.clone()),
+                None => existing_table.shift_remove(*field),
+            };
+        }
+    }
+
+    /// Apply an `UpdateSelected` resolution. Returns the changed categories that were updated.
+    fn apply_update_selected(
+        &mut self,
+        conflict: &ImportConflict,
+        resolution: &ConflictResolution,
+        conflict_result: &ConflictDetectionResult,
+    ) -> Vec<UpdateCategory> {
+        self.selective_merge(conflict, &resolution.categories_to_update);
+        self.selective_merge_fields(conflict, &resolution.extra_fields);
+        self.update_parent_field(conflict);
+        if conflict.is_group
+            && resolution
+                .categories_to_update
+                .contains(&UpdateCategory::Arrangement)
+        {
+            let incoming_descendants = conflict_result
+                .incoming_descendants_by_root
+                .get(&conflict.aura_id)
+                .map(|ids| ids.iter().cloned().collect::<HashSet<String>>())
+                .unwrap_or_default();
+            self.prune_missing_descendants(&conflict.aura_id, &incoming_descendants);
+        }
+        let extra_categories: HashSet<UpdateCategory> = resolution
+            .extra_fields
+            .iter()
+            .filter(|f| !CategoryMapper::is_internal_field(f))
+            .map(|f| CategoryMapper::get_category(f))
+            .collect();
+        UpdateCategory::all()
+            .into_iter()
+            .filter(|c| resolution.categories_to_update.contains(c) || extra_categories.contains(c))
+            .filter(|c| conflict.changed_categories.contains(c))
+            .collect()
+    }
+
     /// Apply all resolutions (convenience method)
     pub fn apply_resolutions(
         &mut self,
         conflict_result: &ConflictDetectionResult,
         resolutions: &[ConflictResolution],
     ) -> ImportResult {
-        let mut added = Vec::new();
-        let mut skipped = Vec::new();
-        let mut replaced = Vec::new();
+        let mut result = ImportResult::default();
 
         // Create a map for quick lookup
         let conflict_map: HashMap<&str, &ImportConflict> = conflict_result
@@ -413,16 +779,15 @@ impl SavedVariablesManager {
                 continue;
             }
             if let Some(conflict) = conflict_map.get(aura_id.as_str()) {
-                self.displays
-                    .insert(aura_id.clone(), conflict.incoming.clone());
-                replaced.push(aura_id.clone());
+                self.replace_aura(conflict);
+                result.record(aura_id, ImportOutcome::Replaced);
             }
         }
 
         // Add all new auras
         for (id, data) in &conflict_result.new_auras {
             self.displays.insert(id.clone(), data.clone());
-            added.push(id.clone());
+            result.record(id, ImportOutcome::Added);
         }
 
         // Apply resolutions
@@ -435,35 +800,35 @@ impl SavedVariablesManager {
 
             match action {
                 ConflictAction::Skip => {
-                    skipped.push(resolution.aura_id.clone());
+                    result.record(&resolution.aura_id, ImportOutcome::Skipped);
                 }
                 ConflictAction::ReplaceAll => {
                     if let Some(conflict) = conflict_map.get(resolution.aura_id.as_str()) {
-                        self.displays
-                            .insert(resolution.aura_id.clone(), conflict.incoming.clone());
-                        replaced.push(resolution.aura_id.clone());
+                        self.replace_aura(conflict);
+                        result.record(&resolution.aura_id, ImportOutcome::Replaced);
                     }
                 }
                 ConflictAction::UpdateSelected => {
                     if let Some(conflict) = conflict_map.get(resolution.aura_id.as_str()) {
-                        self.selective_merge(conflict, &resolution.categories_to_update);
-                        self.update_parent_field(conflict);
-                        if conflict.is_group
-                            && resolution
-                                .categories_to_update
-                                .contains(&UpdateCategory::Arrangement)
-                        {
-                            let incoming_descendants = conflict_result
-                                .incoming_descendants_by_root
-                                .get(&conflict.aura_id)
-                                .map(|ids| ids.iter().cloned().collect::<HashSet<String>>())
-                                .unwrap_or_default();
-                            self.prune_missing_descendants(
-                                &conflict.aura_id,
-                                &incoming_descendants,
-                            );
-                        }
-                        replaced.push(resolution.aura_id.clone());
+                        let categories =
+                            self.apply_update_selected(conflict, resolution, conflict_result);
+                        result.record(&resolution.aura_id, ImportOutcome::Merged(categories));
+                    }
+                }
+                ConflictAction::Merge => {
+                    if let Some(conflict) = conflict_map.get(resolution.aura_id.as_str()) {
+                        // Without a base snapshot, fall back to the selected categories
+                        let categories = match &conflict.base {
+                            Some(base) => {
+                                let merge = self.merge_with_base(conflict, base);
+                                self.update_parent_field(conflict);
+                                merge.categories()
+                            }
+                            None => {
+                                self.apply_update_selected(conflict, resolution, conflict_result)
+                            }
+                        };
+                        result.record(&resolution.aura_id, ImportOutcome::Merged(categories));
                     }
                 }
             }
@@ -492,11 +857,11 @@ impl SavedVariablesManager {
             util::set_controlled_children(parent_data, children);
         }
 
-        ImportResult {
-            added,
-            skipped,
-            replaced,
-        }
+        self.pending.push(PendingOp::ApplyResolutions(
+            conflict_result.clone(),
+            resolutions.to_vec(),
+        ));
+        result
     }
 
     /// Get auras organized in a tree structure (groups with children)
@@ -515,10 +880,11 @@ impl SavedVariablesManager {
             }
         }
 
-        // Recursive helper to build a tree node
+        // Recursive helper to build a tree node at nesting level `depth`
         fn build_node(
             id: &str,
-            displays: &HashMap<String, LuaValue>,
+            depth: usize,
+            displays: &LuaTable,
             children_map: &HashMap<String, Vec<String>>,
         ) -> AuraTreeNode {
             let is_group = displays
@@ -532,15 +898,60 @@ impl SavedVariablesManager {
                 })
                 .unwrap_or(false);
 
-            let children = if is_group {
+            let children = if is_group && depth >= MAX_TREE_DEPTH {
+                // Too deep to keep walking: list everything further down
+                // directly under this group, so no aura goes missing
+                let mut descendants = Vec::new();
+                let mut seen = HashSet::new();
+                let mut stack: Vec<&str> = children_map
+                    .get(id)
+                    .map(|ids| ids.iter().rev().map(String::as_str).collect())
+                    .unwrap_or_default();
+                while let Some(child_id) = stack.pop() {
+                    if !seen.insert(child_id) {
+                        continue;
+                    }
+                    let mut node = build_node(child_id, depth + 1, displays, &HashMap::new());
+                    node.order = Some(descendants.len());
+                    descendants.push(node);
+                    if let Some(ids) = children_map.get(child_id) {
+                        stack.extend(ids.iter().rev().map(String::as_str));
+                    }
+                }
+                if !descendants.is_empty() {
+                    warn!(group_id = %id, "get_aura_tree: groups nested too deeply, flattening");
+                }
+                descendants
+            } else if is_group {
+                // Game order: the group's controlledChildren, then any child
+                // missing from it
+                let controlled: Vec<&str> = displays
+                    .get(id)
+                    .and_then(|d| d.as_table())
+                    .and_then(|t| t.get("controlledChildren"))
+                    .and_then(LuaValue::as_array)
+                    .map(|ids| {
+                        ids.iter()
+                            .filter_map(|v| match v {
+                                LuaValue::String(s) => Some(s.as_str()),
+                                _ => None,
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
                 children_map
                     .get(id)
                     .map(|child_ids| {
                         let mut children: Vec<AuraTreeNode> = child_ids
                             .iter()
-                            .map(|child_id| build_node(child_id, displays, children_map))
+                            .map(|child_id| {
+                                let mut node =
+                                    build_node(child_id, depth + 1, displays, children_map);
+                                node.order = controlled.iter().position(|c| *c == child_id);
+                                node
+                            })
                             .collect();
-                        children.sort_by_key(|a| a.id.to_lowercase());
+                        children.sort_by(|a, b| AuraTreeSort::GameOrder.compare(a, b));
                         children
                     })
                     .unwrap_or_default()
@@ -548,9 +959,19 @@ impl SavedVariablesManager {
                 Vec::new()
             };
 
+            let table = displays.get(id).and_then(|d| d.as_table());
+            let string_field = |field: &str| match table.and_then(|t| t.get(field)) {
+                Some(LuaValue::String(value)) => Some(value.clone()),
+                _ => None,
+            };
+
             AuraTreeNode {
                 id: id.to_string(),
+                uid: string_field("uid"),
                 is_group,
+                region_type: string_field("regionType"),
+                anchored_to: displays.get(id).and_then(anchor_target).map(str::to_string),
+                order: None,
                 children,
             }
         }
@@ -564,15 +985,11 @@ impl SavedVariablesManager {
                     .map(|t| t.get("parent").is_none())
                     .unwrap_or(false)
             })
-            .map(|(id, _)| build_node(id, &self.displays, &children_map))
+            .map(|(id, _)| build_node(id, 1, &self.displays, &children_map))
             .collect();
 
         // Sort top-level nodes: groups first, then alphabetically
-        nodes.sort_by(|a, b| match (a.is_group, b.is_group) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.id.to_lowercase().cmp(&b.id.to_lowercase()),
-        });
+        nodes.sort_by(|a, b| AuraTreeSort::GameOrder.compare(a, b));
 
         nodes
     }
@@ -595,19 +1012,13 @@ impl SavedVariablesManager {
             let parent_id = self
                 .displays
                 .get(id)
-                .and_then(|d| d.as_table())
-                .and_then(|t| t.get("parent"))
-                .and_then(|v| {
-                    if let LuaValue::String(s) = v {
-                        Some(s.clone())
-                    } else {
-                        None
-                    }
-                });
+                .and_then(|d| d.get_path("parent"))
+                .and_then(LuaValue::as_str)
+                .map(str::to_string);
 
             // Remove all collected auras from displays
             for remove_id in &to_remove {
-                if self.displays.remove(remove_id).is_some() {
+                if self.displays.shift_remove(remove_id).is_some() {
                     removed.push(remove_id.clone());
                 }
             }
@@ -631,154 +1042,722 @@ impl SavedVariablesManager {
             }
         }
 
+        if !removed.is_empty() {
+            let dependents = self.anchor_dependents(&removed);
+            if !dependents.is_empty() {
+                warn!(
+                    dependents = ?dependents,
+                    "Removed auras that other auras are anchored to"
+                );
+            }
+            self.pending.push(PendingOp::RemoveAuras(ids.to_vec()));
+        }
         removed
     }
 
-    /// Collect an aura ID and all its descendant IDs (recursive).
-    fn collect_descendants(&self, id: &str) -> Vec<String> {
-        let mut result = vec![id.to_string()];
+    /// Auras outside `ids` that are anchored to one of `ids`, as
+    /// `(dependent, anchor target)` pairs. Removing the targets leaves the
+    /// dependents anchored to a frame that no longer exists.
+    pub fn anchor_dependents(&self, ids: &[String]) -> Vec<(String, String)> {
+        let ids: HashSet<&str> = ids.iter().map(String::as_str).collect();
+        self.displays
+            .iter()
+            .filter(|(id, _)| !ids.contains(id.as_str()))
+            .filter_map(|(id, data)| {
+                let target = anchor_target(data)?;
+                ids.contains(target)
+                    .then(|| (id.clone(), target.to_string()))
+            })
+            .collect()
+    }
 
-        // Check if this aura has controlledChildren
-        if let Some(data) = self.displays.get(id) {
-            if let Some(table) = data.as_table() {
-                if let Some(LuaValue::Array(children)) = table.get("controlledChildren") {
-                    for child in children {
-                        if let LuaValue::String(child_id) = child {
-                            result.extend(self.collect_descendants(child_id));
-                        }
-                    }
-                }
-            }
+    /// Anchor auras to the screen instead of the frame they were anchored
+    /// to. Returns how many auras were changed.
+    pub fn anchor_to_screen(&mut self, ids: &[String]) -> usize {
+        let mut changed = 0;
+        for id in ids {
+            let Some(table) = self.displays.get_mut(id).and_then(LuaValue::as_table_mut) else {
+                continue;
+            };
+            table.insert(
+                "anchorFrameType".to_string(),
+                LuaValue::String("SCREEN".to_string()),
+            );
+            table.shift_remove("anchorFrameFrame");
+            changed += 1;
         }
-
-        result
+        if changed > 0 {
+            self.pending.push(PendingOp::AnchorToScreen(ids.to_vec()));
+        }
+        changed
     }
 
-    /// Collect an aura ID and all its descendant IDs using parent relationships.
-    fn collect_descendants_by_parent(&self, root_id: &str) -> HashSet<String> {
-        let mut children_map: HashMap<String, Vec<String>> = HashMap::new();
-        for (id, data) in &self.displays {
-            if let Some(table) = data.as_table() {
-                if let Some(LuaValue::String(parent_id)) = table.get("parent") {
-                    children_map
-                        .entry(parent_id.clone())
-                        .or_default()
-                        .push(id.clone());
+    /// Lua dump of the given auras and their descendants, as a safety net
+    /// before removing them. The entries can be pasted back into
+    /// `WeakAurasSaved.displays` to restore the auras.
+    pub fn export_displays_lua(&self, ids: &[String]) -> String {
+        let mut exported: Vec<String> = Vec::new();
+        for id in ids {
+            for descendant in self.collect_descendants(id) {
+                if self.displays.contains_key(&descendant) && !exported.contains(&descendant) {
+                    exported.push(descendant);
                 }
             }
         }
 
-        let mut result: HashSet<String> = HashSet::new();
-        let mut stack: Vec<String> = vec![root_id.to_string()];
-        while let Some(current) = stack.pop() {
-            if result.insert(current.clone()) {
-                if let Some(children) = children_map.get(&current) {
-                    for child_id in children {
-                        stack.push(child_id.clone());
-                    }
-                }
-            }
+        let mut output = String::from(
+            "-- WeakAuras displays exported before removal.\n\
+             -- Paste the entries into WeakAurasSaved.displays to restore them.\n\
+             WeakAurasExportedDisplays = {\n",
+        );
+        for id in &exported {
+            output.push_str(&format!(
+                "\t[\"{}\"] = {},\n",
+                util::escape_lua_string(id),
+                self.serialize_value(&self.displays[id.as_str()], 1)
+            ));
         }
-
-        result
+        output.push_str("}\n");
+        output
     }
 
-    fn prune_missing_descendants(&mut self, root_id: &str, incoming_descendants: &HashSet<String>) {
-        let existing_descendants = self.collect_descendants_by_parent(root_id);
-        for existing_id in existing_descendants {
-            if existing_id == root_id {
+    /// Rebuild stored auras as importable [`WeakAura`]s, e.g. to copy them to
+    /// another SavedVariables file with [`SavedVariablesManager::add_auras`].
+    /// Groups carry their descendants as child data, and auras taken out of a
+    /// group lose their `parent`. Unknown IDs are skipped.
+    pub fn extract_auras(&self, ids: &[String]) -> Vec<WeakAura> {
+        let mut auras = Vec::new();
+        for id in ids {
+            let Some(data) = self.displays.get(id) else {
                 continue;
+            };
+            let string = |key: &str| {
+                data.get_path(key)
+                    .and_then(LuaValue::as_str)
+                    .map(str::to_string)
+            };
+            let children: Vec<String> = data
+                .get_path("controlledChildren")
+                .and_then(LuaValue::as_array)
+                .map(|ids| {
+                    ids.iter()
+                        .filter_map(|v| match v {
+                            LuaValue::String(child_id) => Some(child_id.clone()),
+                            _ => None,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let region_type = string("regionType");
+            let is_group = !children.is_empty()
+                || matches!(region_type.as_deref(), Some("group" | "dynamicgroup"));
+            let child_data = self
+                .collect_descendants(id)
+                .iter()
+                .skip(1)
+                .filter_map(|child_id| self.displays.get(child_id).cloned())
+                .collect();
+            let mut data = data.clone();
+            if let Some(table) = data.as_table_mut() {
+                table.shift_remove("parent");
             }
-            if !incoming_descendants.contains(&existing_id) {
-                self.displays.remove(&existing_id);
-            }
+
+            auras.push(WeakAura {
+                id: id.clone(),
+                uid: string("uid"),
+                region_type,
+                is_group,
+                children,
+                data,
+                child_data,
+                original_string: String::new(),
+                encoding_version: 2,
+            });
         }
+        auras
     }
 
-    fn update_parent_field(&mut self, conflict: &ImportConflict) {
-        let Some(existing) = self.displays.get_mut(&conflict.aura_id) else {
-            return;
-        };
-        let Some(incoming_table) = conflict.incoming.as_table() else {
-            return;
-        };
-        let Some(existing_table) = existing.as_table_mut() else {
-            return;
+    /// Remove a group but keep its children, promoting them to top-level
+    /// auras. Returns the promoted child IDs, or `None` if `id` is not a group.
+    pub fn dissolve_group(&mut self, id: &str) -> Option<Vec<String>> {
+        let table = self.displays.get(id)?.as_table()?;
+        if !matches!(
+            table.get("regionType"),
+            Some(LuaValue::String(rt)) if rt == "group" || rt == "dynamicgroup"
+        ) {
+            return None;
+        }
+        let parent_id = match table.get("parent") {
+            Some(LuaValue::String(parent)) => Some(parent.clone()),
+            _ => None,
         };
 
-        if let Some(parent_value) = incoming_table.get("parent") {
-            existing_table.insert("parent".to_string(), parent_value.clone());
-        } else {
-            existing_table.remove("parent");
+        // Direct children: controlledChildren plus any aura naming the group as parent
+        let mut children: Vec<String> = table
+            .get("controlledChildren")
+            .and_then(LuaValue::as_array)
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|v| match v {
+                        LuaValue::String(child_id) => Some(child_id.clone()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        for (child_id, data) in &self.displays {
+            let is_child = data.get_path("parent").and_then(LuaValue::as_str) == Some(id);
+            if is_child && !children.contains(child_id) {
+                children.push(child_id.clone());
+            }
         }
-    }
+        children.retain(|child_id| self.displays.contains_key(child_id));
 
-    /// Save the SavedVariables back to file
-    pub fn save(&self) -> Result<()> {
-        // Create backup first
-        if self.path.exists() {
-            let backup_path = self.path.with_extension("lua.backup");
-            fs::copy(&self.path, &backup_path)?;
+        self.displays.shift_remove(id);
+        for child_id in &children {
+            if let Some(child) = self
+                .displays
+                .get_mut(child_id)
+                .and_then(LuaValue::as_table_mut)
+            {
+                child.shift_remove("parent");
+            }
         }
 
-        // Generate new content
-        let content = self.generate_lua();
-        fs::write(&self.path, content)?;
+        // Drop the group from its own parent's controlledChildren
+        if let Some(parent_id) = parent_id {
+            if let Some(LuaValue::Array(siblings)) = self
+                .displays
+                .get_mut(&parent_id)
+                .and_then(LuaValue::as_table_mut)
+                .and_then(|t| t.get_mut("controlledChildren"))
+            {
+                siblings.retain(|v| !matches!(v, LuaValue::String(s) if s == id));
+            }
+        }
 
-        Ok(())
+        self.pending.push(PendingOp::DissolveGroup(id.to_string()));
+        Some(children)
     }
 
-    /// Save the SavedVariables to a specific file path.
-    #[allow(dead_code)]
-    pub fn save_as(&self, path: &Path) -> Result<()> {
-        if path.exists() {
-            let backup_path = path.with_extension("lua.backup");
-            fs::copy(path, &backup_path)?;
+    /// Move auras into an existing group: their `parent` is set to the group,
+    /// they leave the `controlledChildren` of a previous parent and are added
+    /// to the end of the group's. Unknown IDs, the group itself and its
+    /// ancestors are skipped. Returns the moved IDs, or `None` if `group_id`
+    /// is not a group.
+    pub fn move_into_group(&mut self, group_id: &str, ids: &[String]) -> Option<Vec<String>> {
+        let table = self.displays.get(group_id)?.as_table()?;
+        if !matches!(
+            table.get("regionType"),
+            Some(LuaValue::String(rt)) if rt == "group" || rt == "dynamicgroup"
+        ) {
+            return None;
         }
 
-        let content = self.generate_lua();
-        fs::write(path, content)?;
-
-        Ok(())
-    }
-
-    /// Generate Lua content for SavedVariables
-    pub fn generate_lua(&self) -> String {
-        let mut output = String::new();
-        output.push_str("\nWeakAurasSaved = {\n");
+        let parent_of = |displays: &LuaTable, id: &str| {
+            displays
+                .get(id)
+                .and_then(|data| data.get_path("parent"))
+                .and_then(LuaValue::as_str)
+                .map(str::to_string)
+        };
+        // The group and its ancestors can't go inside it
+        let mut ancestors = vec![group_id.to_string()];
+        while let Some(parent) = parent_of(&self.displays, ancestors.last().unwrap()) {
+            if ancestors.contains(&parent) {
+                break;
+            }
+            ancestors.push(parent);
+        }
 
-        // Write other fields first (metadata like dbVersion, minimap, registered, etc.)
-        let mut other_keys: Vec<_> = self.other_fields.keys().collect();
-        other_keys.sort();
-        for key in other_keys {
+        let mut moved = Vec::new();
+        for id in ids {
+            if ancestors.contains(id) || moved.contains(id) || !self.displays.contains_key(id) {
+                continue;
+            }
+            if let Some(old_parent) = parent_of(&self.displays, id) {
+                if let Some(LuaValue::Array(siblings)) = self
+                    .displays
+                    .get_mut(&old_parent)
+                    .and_then(LuaValue::as_table_mut)
+                    .and_then(|t| t.get_mut("controlledChildren"))
+                {
+                    siblings.retain(|v| !matches!(v, LuaValue::String(s) if s == id));
+                }
+            }
+            if let Some(table) = self.displays.get_mut(id).and_then(LuaValue::as_table_mut) {
+                table.insert("parent".to_string(), LuaValue::String(group_id.to_string()));
+            }
+            moved.push(id.clone());
+        }
+
+        let group = self.displays.get_mut(group_id)?;
+        let mut children: Vec<String> = group
+            .as_table()
+            .and_then(|t| t.get("controlledChildren"))
+            .and_then(LuaValue::as_array)
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|v| match v {
+                        LuaValue::String(child_id) if !moved.contains(child_id) => {
+                            Some(child_id.clone())
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        children.extend(moved.iter().cloned());
+        util::set_controlled_children(group, &children);
+
+        if !moved.is_empty() {
+            self.pending
+                .push(PendingOp::MoveIntoGroup(group_id.to_string(), ids.to_vec()));
+        }
+        Some(moved)
+    }
+
+    /// Rename a stored aura, keeping its place in the file. Its children's
+    /// `parent`, its parent's `controlledChildren` and anchors of other auras
+    /// to it follow the new ID. Fails if `old_id` doesn't exist or `new_id`
+    /// is empty or taken.
+    pub fn rename_aura(&mut self, old_id: &str, new_id: &str) -> Result<()> {
+        if new_id.trim().is_empty() {
+            return Err(WeakAuraError::InvalidValue(
+                "the new ID is empty".to_string(),
+            ));
+        }
+        if self.displays.contains_key(new_id) {
+            return Err(WeakAuraError::InvalidValue(format!(
+                "an aura called '{}' already exists",
+                new_id
+            )));
+        }
+        let Some((index, _, mut data)) = self.displays.shift_remove_full(old_id) else {
+            return Err(WeakAuraError::InvalidValue(format!(
+                "no aura called '{}'",
+                old_id
+            )));
+        };
+        data.set_path("id", LuaValue::String(new_id.to_string()));
+        self.displays.insert(new_id.to_string(), data);
+        self.displays.move_index(self.displays.len() - 1, index);
+
+        let old_anchor = format!("WeakAuras:{}", old_id);
+        for (_, other) in self.displays.iter_mut() {
+            if other.get_path("parent").and_then(LuaValue::as_str) == Some(old_id) {
+                other.set_path("parent", LuaValue::String(new_id.to_string()));
+            }
+            if let Some(LuaValue::Array(children)) = other.get_path_mut("controlledChildren") {
+                for child in children.iter_mut() {
+                    if matches!(child, LuaValue::String(s) if s == old_id) {
+                        *child = LuaValue::String(new_id.to_string());
+                    }
+                }
+            }
+            if other
+                .get_path("anchorFrameFrame")
+                .and_then(LuaValue::as_str)
+                == Some(old_anchor.as_str())
+            {
+                other.set_path(
+                    "anchorFrameFrame",
+                    LuaValue::String(format!("WeakAuras:{}", new_id)),
+                );
+            }
+        }
+
+        self.pending.push(PendingOp::RenameAura(
+            old_id.to_string(),
+            new_id.to_string(),
+        ));
+        Ok(())
+    }
+
+    /// Write edited properties into stored auras, by ID. The properties'
+    /// own `id` is ignored; use [`rename_aura`](Self::rename_aura) to rename.
+    /// Returns the IDs of the auras that were changed.
+    pub fn set_properties(&mut self, updates: &[(String, AuraProperties)]) -> Vec<String> {
+        let mut changed = Vec::new();
+        for (id, properties) in updates {
+            let Some(data) = self.displays.get_mut(id) else {
+                continue;
+            };
+            let properties = AuraProperties {
+                id: id.clone(),
+                ..properties.clone()
+            };
+            let mut updated = data.clone();
+            match properties.write_to(&mut updated) {
+                Ok(()) if updated != *data => {
+                    *data = updated;
+                    changed.push(id.clone());
+                }
+                Ok(()) => {}
+                Err(e) => warn!(id = %id, error = %e, "Could not set aura properties"),
+            }
+        }
+        if !changed.is_empty() {
+            self.pending
+                .push(PendingOp::SetProperties(updates.to_vec()));
+        }
+        changed
+    }
+
+    /// Set fields of stored auras as `(aura ID, dotted path, value)`, in
+    /// order; `Nil` removes the field. Changes to auras that don't exist or
+    /// through values that are not tables are skipped. Returns how many were
+    /// applied.
+    pub fn set_fields(&mut self, changes: &[(String, String, LuaValue)]) -> usize {
+        let mut applied = 0;
+        for (id, path, value) in changes {
+            let Some(data) = self.displays.get_mut(id) else {
+                continue;
+            };
+            let done = match value {
+                LuaValue::Nil => {
+                    data.remove_path(path);
+                    true
+                }
+                value => data.set_path(path, value.clone()),
+            };
+            if done {
+                applied += 1;
+            }
+        }
+        if applied > 0 {
+            self.pending.push(PendingOp::SetFields(changes.to_vec()));
+        }
+        applied
+    }
+
+    /// Collect an aura ID and all its descendant IDs through
+    /// `controlledChildren`, parents before their children.
+    fn collect_descendants(&self, id: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut seen = HashSet::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            // Damaged files may list a child twice or loop back
+            if !seen.insert(current) {
+                continue;
+            }
+            result.push(current.to_string());
+            if let Some(LuaValue::Array(children)) = self
+                .displays
+                .get(current)
+                .and_then(|data| data.as_table())
+                .and_then(|table| table.get("controlledChildren"))
+            {
+                stack.extend(children.iter().rev().filter_map(|child| match child {
+                    LuaValue::String(child_id) => Some(child_id.as_str()),
+                    _ => None,
+                }));
+            }
+        }
+        result
+    }
+
+    /// Collect an aura ID and all its descendant IDs using parent relationships.
+    fn collect_descendants_by_parent(&self, root_id: &str) -> HashSet<String> {
+        let mut children_map: HashMap<String, Vec<String>> = HashMap::new();
+        for (id, data) in &self.displays {
+            if let Some(table) = data.as_table() {
+                if let Some(LuaValue::String(parent_id)) = table.get("parent") {
+                    children_map
+                        .entry(parent_id.clone())
+                        .or_default()
+                        .push(id.clone());
+                }
+            }
+        }
+
+        let mut result: HashSet<String> = HashSet::new();
+        let mut stack: Vec<String> = vec![root_id.to_string()];
+        while let Some(current) = stack.pop() {
+            if result.insert(current.clone()) {
+                if let Some(children) = children_map.get(&current) {
+                    for child_id in children {
+                        stack.push(child_id.clone());
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    fn prune_missing_descendants(&mut self, root_id: &str, incoming_descendants: &HashSet<String>) {
+        let existing_descendants = self.collect_descendants_by_parent(root_id);
+        for existing_id in existing_descendants {
+            if existing_id == root_id {
+                continue;
+            }
+            if !incoming_descendants.contains(&existing_id) {
+                self.displays.shift_remove(&existing_id);
+            }
+        }
+    }
+
+    fn update_parent_field(&mut self, conflict: &ImportConflict) {
+        let Some(existing) = self.displays.get_mut(&conflict.aura_id) else {
+            return;
+        };
+        let Some(incoming_table) = conflict.incoming.as_table() else {
+            return;
+        };
+        let Some(existing_table) = existing.as_table_mut() else {
+            return;
+        };
+
+        if let Some(parent_value) = incoming_table.get("parent") {
+            existing_table.insert("parent".to_string(), parent_value.clone());
+        } else {
+            existing_table.shift_remove("parent");
+        }
+    }
+
+    /// Save the SavedVariables back to file.
+    ///
+    /// If the file was changed on disk since it was loaded (by WoW or another
+    /// tool), it is re-read and the imports and removals made through this
+    /// manager are applied again, so those changes are kept.
+    ///
+    /// Fails with [`WeakAuraError::UnsupportedDbVersion`] if the file's `dbVersion`
+    /// is newer than [`MAX_SUPPORTED_DB_VERSION`], unless
+    /// [`allow_newer_db_version`](Self::allow_newer_db_version) is set.
+    pub fn save(&mut self) -> Result<()> {
+        self.write_file(true)
+    }
+
+    /// [`save`](Self::save) without replacing the backup, so it keeps the
+    /// file from before an earlier save: for the saves after the first of an
+    /// import written in chunks
+    pub fn save_without_backup(&mut self) -> Result<()> {
+        self.write_file(false)
+    }
+
+    fn write_file(&mut self, backup: bool) -> Result<()> {
+        let _lock = lock_file(&self.path, true)?;
+        let _saving = crash::SaveInProgress::start(&self.path);
+        if self.is_stale() {
+            self.reload_and_replay()?;
+        }
+        self.check_db_version()?;
+
+        // Create backup first
+        if backup && self.path.exists() {
+            fs_util::copy(&self.path, &backup_path(&self.path))?;
+        }
+
+        // Generate new content
+        let content = self.generate_lua();
+        fs_util::write(&self.path, content)?;
+        self.disk_stamp = DiskStamp::read(&self.path);
+        self.pending.clear();
+
+        Ok(())
+    }
+
+    /// Replace a SavedVariables file with the backup written by its last
+    /// save. The file it replaces is kept as
+    /// [`damaged_path`](crate::repair::damaged_path); returns that path when
+    /// there was a file to keep.
+    pub fn restore_backup(path: &Path) -> Result<Option<PathBuf>> {
+        let backup = backup_path(path);
+        if !backup.exists() {
+            return Err(WeakAuraError::FileNotFound(
+                backup.to_string_lossy().to_string(),
+            ));
+        }
+        let _lock = lock_file(path, true)?;
+        let kept = if path.exists() {
+            let damaged = crate::repair::damaged_path(path);
+            fs_util::copy(path, &damaged)?;
+            Some(damaged)
+        } else {
+            None
+        };
+        fs_util::copy(&backup, path)?;
+        Ok(kept)
+    }
+
+    /// The aura `id` as loaded now and as stored in the backup written by the
+    /// last save, to see what that save changed
+    pub fn compare_with_backup(&self, id: &str) -> Result<BackupComparison> {
+        let backup = backup_path(&self.path);
+        if !backup.exists() {
+            return Err(WeakAuraError::FileNotFound(
+                backup.to_string_lossy().to_string(),
+            ));
+        }
+        let content = fs::read_to_string(&backup)?;
+        let saved = LuaParser::parse(&content)?;
+        Ok(BackupComparison {
+            aura_id: id.to_string(),
+            current: self.displays.get(id).cloned(),
+            backup: saved.displays.get(id).cloned(),
+            backup_modified: fs::metadata(&backup).and_then(|m| m.modified()).ok(),
+        })
+    }
+
+    /// Whether the file changed on disk since it was loaded (or last saved).
+    /// Always `false` for a manager that never loaded a file.
+    pub fn is_stale(&self) -> bool {
+        self.disk_stamp
+            .is_some_and(|stamp| DiskStamp::read(&self.path) != Some(stamp))
+    }
+
+    /// Save the SavedVariables to a specific file path.
+    #[allow(dead_code)]
+    pub fn save_as(&self, path: &Path) -> Result<()> {
+        self.check_db_version()?;
+        let _lock = lock_file(path, true)?;
+        let _saving = crash::SaveInProgress::start(path);
+        if path.exists() {
+            fs_util::copy(path, &backup_path(path))?;
+        }
+
+        let content = self.generate_lua();
+        fs_util::write(path, content)?;
+
+        Ok(())
+    }
+
+    /// Generate Lua content for SavedVariables
+    pub fn generate_lua(&self) -> String {
+        let mut output = String::new();
+        output.push_str("\nWeakAurasSaved = {\n");
+
+        // Write other fields first (metadata like dbVersion, minimap, registered, etc.)
+        for key in ordered_keys(&self.other_fields, self.sort_keys) {
             let value = &self.other_fields[key];
             let escaped_key = util::escape_lua_string(key);
             output.push_str(&format!(
                 "\t[\"{}\"] = {},\n",
                 escaped_key,
-                LuaParser::serialize(value, 1)
+                self.serialize_value(value, 1)
             ));
         }
 
-        // Write displays
+        // Write displays, serializing only those changed since the last load or save
+        let mut serialized = self.serialized.borrow_mut();
+        serialized.retain(|id, _| self.displays.contains_key(id));
         output.push_str("\t[\"displays\"] = {\n");
-        let mut display_keys: Vec<_> = self.displays.keys().collect();
-        display_keys.sort();
-        for id in display_keys {
+        for id in ordered_keys(&self.displays, self.sort_keys) {
             let data = &self.displays[id];
+            let cached = serialized.get(id).and_then(|entry| {
+                let text = match &entry.text {
+                    SerializedText::Source(span) if !self.sort_keys => {
+                        self.raw_content.as_deref()?.get(span.clone())?
+                    }
+                    SerializedText::Generated { text, sorted } if *sorted == self.sort_keys => {
+                        text.as_str()
+                    }
+                    _ => return None,
+                };
+                // Tables shared with the cached copy compare without a walk
+                (entry.value == *data).then_some(text)
+            });
             // Escape special characters in the ID
             let escaped_id = util::escape_lua_string(id);
-            output.push_str(&format!(
-                "\t\t[\"{}\"] = {},\n",
-                escaped_id,
-                LuaParser::serialize(data, 2)
-            ));
+            output.push_str(&format!("\t\t[\"{}\"] = ", escaped_id));
+            match cached {
+                Some(text) => output.push_str(text),
+                None => {
+                    let text = self.serialize_value(data, 2);
+                    output.push_str(&text);
+                    serialized.insert(
+                        id.clone(),
+                        SerializedDisplay {
+                            value: data.clone(),
+                            text: SerializedText::Generated {
+                                text,
+                                sorted: self.sort_keys,
+                            },
+                        },
+                    );
+                }
+            }
+            output.push_str(",\n");
         }
         output.push_str("\t},\n");
 
         output.push_str("}\n");
         output
     }
+
+    fn serialize_value(&self, value: &LuaValue, indent: usize) -> String {
+        if self.sort_keys {
+            LuaParser::serialize_sorted(value, indent)
+        } else {
+            LuaParser::serialize(value, indent)
+        }
+    }
+}
+
+/// One aura as currently stored and as stored in the file's backup
+#[derive(Debug, Clone)]
+pub struct BackupComparison {
+    pub aura_id: String,
+    /// Current data (`None` if the aura no longer exists)
+    pub current: Option<LuaValue>,
+    /// Data in the backup (`None` if the aura didn't exist before the last save)
+    pub backup: Option<LuaValue>,
+    /// When the backup was written
+    pub backup_modified: Option<SystemTime>,
+}
+
+impl BackupComparison {
+    /// Fields that differ between the backup and the current data, by category
+    pub fn changes(&self) -> Vec<(UpdateCategory, Vec<String>)> {
+        let empty = LuaValue::Table(Default::default());
+        changed_fields_by_category(
+            self.backup.as_ref().unwrap_or(&empty),
+            self.current.as_ref().unwrap_or(&empty),
+        )
+    }
+}
+
+/// Top-level fields that differ between two versions of an aura, grouped by
+/// update category. Internal fields are ignored; categories and fields are
+/// sorted for stable output.
+pub fn changed_fields_by_category(
+    existing: &LuaValue,
+    incoming: &LuaValue,
+) -> Vec<(UpdateCategory, Vec<String>)> {
+    let empty = LuaTable::new();
+    let existing_table = existing.as_table().unwrap_or(&empty);
+    let incoming_table = incoming.as_table().unwrap_or(&empty);
+
+    let mut fields: Vec<&String> = existing_table
+        .keys()
+        .chain(incoming_table.keys())
+        .filter(|f| !CategoryMapper::is_internal_field(f))
+        .collect();
+    fields.sort();
+    fields.dedup();
+
+    let mut changes: Vec<(UpdateCategory, Vec<String>)> = Vec::new();
+    for field in fields {
+        if existing_table.get(field) == incoming_table.get(field) {
+            continue;
+        }
+        let category = CategoryMapper::get_category(field);
+        match changes.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, category_fields)) => category_fields.push(field.clone()),
+            None => changes.push((category, vec![field.clone()])),
+        }
+    }
+
+    let order = UpdateCategory::all();
+    changes.sort_by_key(|(category, _)| order.iter().position(|c| c == category));
+    changes
+}
+
+/// Backup written next to a SavedVariables file before it is overwritten
+pub fn backup_path(path: &Path) -> PathBuf {
+    path.with_extension("lua.backup")
 }
 
 /// Information about a found SavedVariables file
@@ -787,6 +1766,19 @@ pub struct SavedVariablesInfo {
     pub path: PathBuf,
     pub account: String,
     pub flavor: String,
+    /// WoW installation root this file was found under
+    pub install: PathBuf,
+    /// File size in bytes
+    pub size: u64,
+    /// Last modification time
+    pub modified: Option<SystemTime>,
+    /// Number of auras, once the file has been parsed
+    pub aura_count: Option<usize>,
+    /// `dbVersion` of the file, once it has been parsed
+    pub db_version: Option<u32>,
+    /// Whether the WeakAuras addon is installed for this flavor
+    /// (`None` if the file is outside a WoW folder layout)
+    pub addon_installed: Option<bool>,
 }
 
 impl SavedVariablesInfo {
@@ -794,6 +1786,75 @@ impl SavedVariablesInfo {
     pub fn pretty_flavor(&self) -> String {
         format_flavor_name(&self.flavor)
     }
+
+    /// One-line summary of the file for telling accounts apart,
+    /// e.g. "42 auras · 1.3 MB · modified 2 days ago · dbVersion 76"
+    pub fn details(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(count) = self.aura_count {
+            parts.push(format!(
+                "{} aura{}",
+                count,
+                if count == 1 { "" } else { "s" }
+            ));
+        }
+        parts.push(format_size(self.size));
+        if let Some(modified) = self.modified {
+            parts.push(format!("modified {}", format_age(modified)));
+        }
+        if let Some(version) = self.db_version {
+            parts.push(format!("dbVersion {}", version));
+        }
+        if self.addon_installed == Some(false) {
+            parts.push("WeakAuras not installed".to_string());
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Format a byte count, e.g. 1_300_000 → "1.2 MB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Format how long ago a time was, e.g. "just now", "5 minutes ago", "3 days ago"
+pub fn format_age(time: SystemTime) -> String {
+    let Ok(elapsed) = SystemTime::now().duration_since(time) else {
+        return "just now".to_string();
+    };
+    let secs = elapsed.as_secs();
+    let (amount, unit) = match secs {
+        0..=59 => return "just now".to_string(),
+        60..=3_599 => (secs / 60, "minute"),
+        3_600..=86_399 => (secs / 3_600, "hour"),
+        _ => (secs / 86_400, "day"),
+    };
+    format!(
+        "{} {}{} ago",
+        amount,
+        unit,
+        if amount == 1 { "" } else { "s" }
+    )
+}
+
+/// Format a duration for progress messages, e.g. "45s", "3m 05s", "1h 02m"
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3_599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3_600, secs % 3_600 / 60),
+    }
 }
 
 impl std::fmt::Display for SavedVariablesInfo {
@@ -825,13 +1886,22 @@ pub fn format_flavor_name(flavor: &str) -> String {
 }
 
 /// A node in the aura tree (for hierarchical display)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AuraTreeNode {
     /// Aura ID/name
     pub id: String,
+    /// Aura UID, if present in the stored data
+    pub uid: Option<String>,
     /// Whether this is a group
     pub is_group: bool,
-    /// Child auras (if this is a group)
+    /// Region type (icon, aurabar, group, ...), if present in the stored data
+    pub region_type: Option<String>,
+    /// Aura this one is anchored to, if any
+    pub anchored_to: Option<String>,
+    /// Position in the parent group's `controlledChildren` (the order shown in
+    /// game); `None` for top-level auras and children the group doesn't list
+    pub order: Option<usize>,
+    /// Child auras (if this is a group), in game order
     pub children: Vec<AuraTreeNode>,
 }
 
@@ -840,17 +1910,424 @@ impl AuraTreeNode {
     pub fn total_count(&self) -> usize {
         1 + self.children.iter().map(|c| c.total_count()).sum::<usize>()
     }
+
+    /// IDs of the groups among this aura and its descendants
+    pub fn group_ids(&self) -> Vec<&str> {
+        let mut ids = Vec::new();
+        if self.is_group {
+            ids.push(self.id.as_str());
+        }
+        for child in &self.children {
+            ids.extend(child.group_ids());
+        }
+        ids
+    }
+
+    /// IDs of this aura and all its descendants
+    pub fn ids(&self) -> Vec<&str> {
+        let mut ids = vec![self.id.as_str()];
+        for child in &self.children {
+            ids.extend(child.ids());
+        }
+        ids
+    }
+
+    /// Removal checkbox state: checked when the aura itself is selected,
+    /// partial when only some of its descendants are
+    pub fn check_state(&self, selected: &HashSet<String>) -> CheckState {
+        if selected.contains(&self.id) {
+            CheckState::Checked
+        } else if self.ids()[1..].iter().any(|id| selected.contains(*id)) {
+            CheckState::Partial
+        } else {
+            CheckState::Unchecked
+        }
+    }
+}
+
+/// State of a tri-state checkbox in the aura tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Checked,
+    Partial,
+    Unchecked,
+}
+
+/// Selected auras that are not inside another selected aura
+pub fn topmost_selected<'a>(
+    tree: &'a [AuraTreeNode],
+    selected: &HashSet<String>,
+) -> Vec<&'a AuraTreeNode> {
+    let mut found = Vec::new();
+    for node in tree {
+        if selected.contains(&node.id) {
+            found.push(node);
+        } else {
+            found.extend(topmost_selected(&node.children, selected));
+        }
+    }
+    found
+}
+
+/// Check or uncheck an aura of the tree for removal. Checking a group checks
+/// all its descendants; unchecking an aura also unchecks its descendants and
+/// its ancestor groups, which can no longer be removed as a whole.
+pub fn set_tree_checked(
+    tree: &[AuraTreeNode],
+    selected: &mut HashSet<String>,
+    id: &str,
+    checked: bool,
+) {
+    fn find_path<'a>(
+        nodes: &'a [AuraTreeNode],
+        id: &str,
+        path: &mut Vec<&'a AuraTreeNode>,
+    ) -> bool {
+        for node in nodes {
+            path.push(node);
+            if node.id == id || find_path(&node.children, id, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    let mut path = Vec::new();
+    if !find_path(tree, id, &mut path) {
+        return;
+    }
+    let Some((node, ancestors)) = path.split_last() else {
+        return;
+    };
+    if checked {
+        selected.extend(node.ids().into_iter().map(str::to_string));
+    } else {
+        for id in node.ids() {
+            selected.remove(id);
+        }
+        for ancestor in ancestors {
+            selected.remove(&ancestor.id);
+        }
+    }
+}
+
+/// ID of the aura a display is anchored to: WeakAuras stores an anchor to
+/// another aura as `anchorFrameType = "SELECTFRAME"` with `anchorFrameFrame`
+/// set to `"WeakAuras:<id>"`
+pub fn anchor_target(data: &LuaValue) -> Option<&str> {
+    if data.get_path("anchorFrameType")?.as_str()? != "SELECTFRAME" {
+        return None;
+    }
+    data.get_path("anchorFrameFrame")?
+        .as_str()?
+        .strip_prefix("WeakAuras:")
+}
+
+/// Auras of the tree outside `ids` anchored to one of `ids`, as
+/// `(dependent, anchor target)` pairs
+pub fn tree_anchor_dependents(
+    tree: &[AuraTreeNode],
+    ids: &HashSet<String>,
+) -> Vec<(String, String)> {
+    let mut dependents = Vec::new();
+    for node in tree {
+        if let Some(target) = &node.anchored_to {
+            if ids.contains(target) && !ids.contains(&node.id) {
+                dependents.push((node.id.clone(), target.clone()));
+            }
+        }
+        dependents.extend(tree_anchor_dependents(&node.children, ids));
+    }
+    dependents
+}
+
+/// How the existing auras tree is sorted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuraTreeSort {
+    /// Group children as shown in game; top-level groups first, then by name
+    #[default]
+    GameOrder,
+    /// By name at every level
+    Alphabetical,
+    /// Groups with the most auras first
+    Size,
+    /// By region type, then by name
+    RegionType,
+}
+
+impl AuraTreeSort {
+    pub const ALL: [AuraTreeSort; 4] = [
+        AuraTreeSort::GameOrder,
+        AuraTreeSort::Alphabetical,
+        AuraTreeSort::Size,
+        AuraTreeSort::RegionType,
+    ];
+
+    /// Order of two sibling nodes
+    pub fn compare(self, a: &AuraTreeNode, b: &AuraTreeNode) -> Ordering {
+        let by_name = || a.id.to_lowercase().cmp(&b.id.to_lowercase());
+        match self {
+            AuraTreeSort::GameOrder => match (a.order, b.order) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => b.is_group.cmp(&a.is_group).then_with(by_name),
+            },
+            AuraTreeSort::Alphabetical => by_name(),
+            AuraTreeSort::Size => b.total_count().cmp(&a.total_count()).then_with(by_name),
+            AuraTreeSort::RegionType => match (&a.region_type, &b.region_type) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+            .then_with(by_name),
+        }
+    }
+
+    /// Sibling nodes in this order
+    pub fn sorted(self, nodes: &[AuraTreeNode]) -> Vec<&AuraTreeNode> {
+        let mut sorted: Vec<&AuraTreeNode> = nodes.iter().collect();
+        if self != AuraTreeSort::GameOrder {
+            // The tree is already stored in game order
+            sorted.sort_by(|a, b| self.compare(a, b));
+        }
+        sorted
+    }
+}
+
+impl std::fmt::Display for AuraTreeSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuraTreeSort::GameOrder => write!(f, "Game order"),
+            AuraTreeSort::Alphabetical => write!(f, "Alphabetical"),
+            AuraTreeSort::Size => write!(f, "Size"),
+            AuraTreeSort::RegionType => write!(f, "Type"),
+        }
+    }
+}
+
+/// Outcome of importing a single aura
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", content = "categories", rename_all = "snake_case")]
+pub enum ImportOutcome {
+    /// New aura written to SavedVariables
+    Added,
+    /// Existing aura replaced entirely
+    Replaced,
+    /// Existing aura kept untouched
+    Skipped,
+    /// Existing aura updated in the listed categories only
+    Merged(Vec<UpdateCategory>),
+}
+
+impl ImportOutcome {
+    /// Short label for reports
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportOutcome::Added => "added",
+            ImportOutcome::Replaced => "replaced",
+            ImportOutcome::Skipped => "skipped",
+            ImportOutcome::Merged(_) => "merged",
+        }
+    }
+
+    /// Display names of merged categories (empty unless `Merged`)
+    pub fn category_names(&self) -> Vec<&'static str> {
+        match self {
+            ImportOutcome::Merged(categories) => {
+                categories.iter().map(|c| c.display_name()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Per-aura entry in an [`ImportResult`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuraImportOutcome {
+    pub aura_id: String,
+    #[serde(flatten)]
+    pub outcome: ImportOutcome,
 }
 
 /// Result of importing auras
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct ImportResult {
     pub added: Vec<String>,
     pub skipped: Vec<String>,
+    /// Replaced or merged auras
     pub replaced: Vec<String>,
+    /// Every aura touched by the import, in processing order
+    pub outcomes: Vec<AuraImportOutcome>,
+    /// Auras left out because they are malformed, with the reason
+    pub failed: Vec<(String, String)>,
+    /// Auras the batch held more than once, of which only one copy was
+    /// imported (see [`crate::duplicates`])
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<String>,
+    /// The file before and after the import, once it is saved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<FileComparison>,
+}
+
+/// Size of a SavedVariables file's aura data at one point in time
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct FileMetrics {
+    pub aura_count: usize,
+    pub group_count: usize,
+    /// File size in bytes
+    pub file_size: u64,
+}
+
+/// A SavedVariables file before and after a change
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct FileComparison {
+    pub before: FileMetrics,
+    pub after: FileMetrics,
+    /// Groups that did not exist before, sorted
+    pub new_groups: Vec<String>,
+}
+
+impl FileComparison {
+    pub fn aura_delta(&self) -> i64 {
+        self.after.aura_count as i64 - self.before.aura_count as i64
+    }
+
+    pub fn size_delta(&self) -> i64 {
+        self.after.file_size as i64 - self.before.file_size as i64
+    }
+
+    /// e.g. "+12 auras (240 → 252), +48.2 KB (1.2 MB → 1.3 MB), 2 new groups"
+    pub fn summary(&self) -> String {
+        let size_delta = self.size_delta();
+        let mut summary = format!(
+            "{:+} auras ({} → {}), {}{} ({} → {})",
+            self.aura_delta(),
+            self.before.aura_count,
+            self.after.aura_count,
+            if size_delta < 0 { "-" } else { "+" },
+            format_size(size_delta.unsigned_abs()),
+            format_size(self.before.file_size),
+            format_size(self.after.file_size),
+        );
+        match self.new_groups.len() {
+            0 => {}
+            1 => summary.push_str(", 1 new group"),
+            n => summary.push_str(&format!(", {} new groups", n)),
+        }
+        summary
+    }
 }
 
 impl ImportResult {
+    /// Record the outcome for one aura (merged auras also count as replaced)
+    pub fn record(&mut self, aura_id: &str, outcome: ImportOutcome) {
+        match outcome {
+            ImportOutcome::Added => self.added.push(aura_id.to_string()),
+            ImportOutcome::Skipped => self.skipped.push(aura_id.to_string()),
+            ImportOutcome::Replaced | ImportOutcome::Merged(_) => {
+                self.replaced.push(aura_id.to_string())
+            }
+        }
+        self.outcomes.push(AuraImportOutcome {
+            aura_id: aura_id.to_string(),
+            outcome,
+        });
+    }
+
+    /// Add the outcomes of a later import of the same batch, e.g. the next
+    /// chunk of a chunked import
+    pub fn merge(&mut self, other: ImportResult) {
+        self.added.extend(other.added);
+        self.skipped.extend(other.skipped);
+        self.replaced.extend(other.replaced);
+        self.outcomes.extend(other.outcomes);
+        self.failed.extend(other.failed);
+        self.duplicates.extend(other.duplicates);
+        if other.comparison.is_some() {
+            self.comparison = other.comparison;
+        }
+    }
+
+    /// Plain-text report, one aura per line
+    pub fn to_report_text(&self) -> String {
+        let mut out = format!("Import result: {}\n", self.summary());
+        if let Some(comparison) = &self.comparison {
+            out.push_str(&format!("File: {}\n", comparison.summary()));
+        }
+        for entry in &self.outcomes {
+            out.push_str(&format!("{}\t{}", entry.outcome.label(), entry.aura_id));
+            let categories = entry.outcome.category_names();
+            if !categories.is_empty() {
+                out.push_str(&format!(" ({})", categories.join(", ")));
+            }
+            out.push('\n');
+        }
+        for (aura_id, error) in &self.failed {
+            out.push_str(&format!("failed\t{}: {}\n", aura_id, error));
+        }
+        for aura_id in &self.duplicates {
+            out.push_str(&format!("duplicate\t{}\n", aura_id));
+        }
+        out
+    }
+
+    /// CSV report with `aura_id,outcome,categories` columns
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("aura_id,outcome,categories\n");
+        for entry in &self.outcomes {
+            out.push_str(&format!(
+                "{},{},{}\n",
+                csv_field(&entry.aura_id),
+                entry.outcome.label(),
+                csv_field(&entry.outcome.category_names().join(";"))
+            ));
+        }
+        for (aura_id, _) in &self.failed {
+            out.push_str(&format!("{},failed,\n", csv_field(aura_id)));
+        }
+        out
+    }
+
+    /// JSON report with a summary and per-aura outcomes
+    pub fn to_json(&self) -> serde_json::Value {
+        let outcomes: Vec<serde_json::Value> = self
+            .outcomes
+            .iter()
+            .map(|entry| {
+                serde_json::json!({
+                    "aura_id": entry.aura_id,
+                    "outcome": entry.outcome.label(),
+                    "categories": entry.outcome.category_names(),
+                })
+            })
+            .collect();
+        let failed: Vec<serde_json::Value> = self
+            .failed
+            .iter()
+            .map(|(aura_id, error)| serde_json::json!({ "aura_id": aura_id, "error": error }))
+            .collect();
+        serde_json::json!({
+            "summary": {
+                "added": self.added.len(),
+                "replaced": self.replaced.len(),
+                "skipped": self.skipped.len(),
+                "failed": self.failed.len(),
+            },
+            "auras": outcomes,
+            "failed": failed,
+            "duplicates": self.duplicates,
+        })
+    }
+
+    /// Pretty-printed [`ImportResult::to_json`] report
+    pub fn to_json_string(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.to_json())
+            .map_err(|e| WeakAuraError::SerializationError(e.to_string()))
+    }
+
     pub fn summary(&self) -> String {
         let mut parts = Vec::new();
         if !self.added.is_empty() {
@@ -862,6 +2339,12 @@ impl ImportResult {
         if !self.skipped.is_empty() {
             parts.push(format!("{} skipped", self.skipped.len()));
         }
+        if !self.failed.is_empty() {
+            parts.push(format!("{} failed", self.failed.len()));
+        }
+        if !self.duplicates.is_empty() {
+            parts.push(format!("{} duplicates dropped", self.duplicates.len()));
+        }
         if parts.is_empty() {
             "No changes".to_string()
         } else {
@@ -870,19 +2353,120 @@ impl ImportResult {
     }
 }
 
+/// Check that an aura and its children form a complete group hierarchy, so
+/// it can be added without leaving orphaned or half-linked displays
+fn check_hierarchy(aura: &WeakAura) -> std::result::Result<(), String> {
+    if aura.id.is_empty() {
+        return Err("aura has no id".to_string());
+    }
+    if aura.data.as_table().is_none() {
+        return Err("aura data is not a table".to_string());
+    }
+
+    // Child id -> parent id
+    let mut parents: HashMap<&str, &str> = HashMap::new();
+    for (index, child) in aura.child_data.iter().enumerate() {
+        let Some(table) = child.as_table() else {
+            return Err(format!("child #{} is not a table", index + 1));
+        };
+        let child_id = match table.get("id") {
+            Some(LuaValue::String(id)) if !id.is_empty() => id.as_str(),
+            _ => return Err(format!("child #{} has no id", index + 1)),
+        };
+        if child_id == aura.id {
+            return Err(format!("child '{}' has the id of its group", child_id));
+        }
+        let parent = match table.get("parent") {
+            None => aura.id.as_str(),
+            Some(LuaValue::String(parent)) => parent.as_str(),
+            Some(_) => return Err(format!("child '{}' has an invalid parent", child_id)),
+        };
+        if parents.insert(child_id, parent).is_some() {
+            return Err(format!("child '{}' appears more than once", child_id));
+        }
+    }
+
+    // Every chain of parents must end at the root
+    for (&child_id, &parent) in &parents {
+        let mut current = parent;
+        let mut steps = 0;
+        while current != aura.id {
+            current = *parents
+                .get(current)
+                .ok_or_else(|| format!("child '{}' has unknown parent '{}'", child_id, current))?;
+            steps += 1;
+            if steps > parents.len() {
+                return Err(format!("child '{}' is part of a parent cycle", child_id));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Carry the user's `config` values over into replacement data. Options the
+/// incoming aura still defines keep the user's value and new options keep their
+/// defaults; without an incoming `config` table the existing one is kept as-is.
+fn carry_over_user_config(existing: &LuaValue, incoming: &mut LuaValue) {
+    let Some(existing_config) = existing.as_table().and_then(|t| t.get("config")) else {
+        return;
+    };
+    let Some(incoming_table) = incoming.as_table_mut() else {
+        return;
+    };
+    match (
+        incoming_table
+            .get_mut("config")
+            .and_then(LuaValue::as_table_mut),
+        existing_config.as_table(),
+    ) {
+        (Some(incoming_config), Some(existing_values)) => {
+            for (key, value) in incoming_config.iter_mut() {
+                if let Some(user_value) = existing_values.get(key) {
+                    *value = user_value.clone();
+                }
+            }
+        }
+        (Some(_), None) => {}
+        (None, _) => {
+            incoming_table.insert("config".to_string(), existing_config.clone());
+        }
+    }
+}
+
+/// Copy fields from incoming into existing; fields missing from incoming are removed
+fn copy_fields<'a>(
+    existing: &mut LuaTable,
+    incoming: &LuaTable,
+    fields: impl Iterator<Item = &'a str>,
+) {
+    for field in fields {
+        if let Some(value) = incoming.get(field) {
+            existing.insert(field.to_string(), value.clone());
+        } else {
+            existing.shift_remove(field);
+        }
+    }
+}
+
 /// Represents a conflict between an incoming aura and an existing one
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ImportConflict {
     /// ID of the conflicting aura
     pub aura_id: String,
     /// The incoming (new) aura data
     pub incoming: LuaValue,
+    /// The existing aura data at detection time
+    pub existing: LuaValue,
     /// Categories that have differences
     pub changed_categories: HashSet<UpdateCategory>,
     /// Whether this is a group
     pub is_group: bool,
     /// Child count (if group)
     pub child_count: usize,
+    /// Version of the aura as last imported (base for a three-way merge), if known
+    pub base: Option<LuaValue>,
+    /// How the incoming version compares to the existing one
+    pub version_change: VersionChange,
 }
 
 impl ImportConflict {
@@ -895,12 +2479,16 @@ impl ImportConflict {
         child_count: usize,
     ) -> Self {
         let changed_categories = Self::detect_changed_categories(&incoming, &existing);
+        let version_change = compare_versions(&existing, &incoming);
         Self {
             aura_id,
             incoming,
+            existing,
             changed_categories,
             is_group,
             child_count,
+            base: None,
+            version_change,
         }
     }
 
@@ -952,6 +2540,48 @@ impl ImportConflict {
     pub fn has_changes(&self) -> bool {
         !self.changed_categories.is_empty()
     }
+
+    /// Version transition for display, e.g. `"1.2.0 → 1.3.0"` (`None` if neither side has one)
+    pub fn version_label(&self) -> Option<String> {
+        let existing = display_version(&self.existing);
+        let incoming = display_version(&self.incoming);
+        if existing.is_none() && incoming.is_none() {
+            return None;
+        }
+        Some(format!(
+            "{} → {}",
+            existing.as_deref().unwrap_or("?"),
+            incoming.as_deref().unwrap_or("?")
+        ))
+    }
+
+    /// The aura's uid (incoming first, then existing)
+    pub fn uid(&self) -> Option<&str> {
+        [&self.incoming, &self.existing]
+            .into_iter()
+            .find_map(|value| value.get_path("uid").and_then(LuaValue::as_str))
+    }
+
+    /// Top-level fields that differ between existing and incoming data, sorted.
+    /// Internal fields are excluded.
+    pub fn changed_fields(&self) -> Vec<String> {
+        let (Some(incoming_table), Some(existing_table)) =
+            (self.incoming.as_table(), self.existing.as_table())
+        else {
+            return Vec::new();
+        };
+
+        let mut fields: Vec<String> = incoming_table
+            .keys()
+            .chain(existing_table.keys())
+            .filter(|f| !CategoryMapper::is_internal_field(f))
+            .filter(|f| incoming_table.get(*f) != existing_table.get(*f))
+            .cloned()
+            .collect();
+        fields.sort();
+        fields.dedup();
+        fields
+    }
 }
 
 /// Resolution for a single conflict
@@ -963,10 +2593,13 @@ pub struct ConflictResolution {
     pub action: ConflictAction,
     /// Categories to update (only used when action is UpdateSelected)
     pub categories_to_update: HashSet<UpdateCategory>,
+    /// Additional top-level fields to update, e.g. from custom categories or
+    /// field-level selection (only used when action is UpdateSelected)
+    pub extra_fields: BTreeSet<String>,
 }
 
 /// Action for resolving a conflict
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConflictAction {
     /// Skip this aura (keep existing)
     Skip,
@@ -974,6 +2607,19 @@ pub enum ConflictAction {
     ReplaceAll,
     /// Update only selected categories
     UpdateSelected,
+    /// Three-way merge against the last-imported version: take only fields the
+    /// author changed (falls back to `UpdateSelected` when no base is known)
+    Merge,
+}
+
+impl ConflictAction {
+    /// All actions, in display order
+    pub const ALL: [ConflictAction; 4] = [
+        ConflictAction::Skip,
+        ConflictAction::ReplaceAll,
+        ConflictAction::UpdateSelected,
+        ConflictAction::Merge,
+    ];
 }
 
 impl std::fmt::Display for ConflictAction {
@@ -982,6 +2628,7 @@ impl std::fmt::Display for ConflictAction {
             ConflictAction::Skip => write!(f, "Skip"),
             ConflictAction::ReplaceAll => write!(f, "Replace"),
             ConflictAction::UpdateSelected => write!(f, "Update"),
+            ConflictAction::Merge => write!(f, "Merge"),
         }
     }
 }
@@ -992,12 +2639,13 @@ impl Default for ConflictResolution {
             aura_id: String::new(),
             action: ConflictAction::UpdateSelected,
             categories_to_update: UpdateCategory::defaults(),
+            extra_fields: BTreeSet::new(),
         }
     }
 }
 
 /// Result of conflict detection
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct ConflictDetectionResult {
     /// Auras that don't exist (no conflict)
     pub new_auras: Vec<(String, LuaValue)>,
@@ -1007,4 +2655,420 @@ pub struct ConflictDetectionResult {
     pub incoming_children_by_parent: HashMap<String, Vec<String>>,
     /// Incoming root -> descendant IDs mapping
     pub incoming_descendants_by_root: HashMap<String, Vec<String>>,
+    /// IDs of the incoming top-level auras, in import order
+    pub roots: Vec<String>,
+    /// Incoming auras sharing their uid with a different aura
+    pub uid_collisions: Vec<UidCollision>,
+}
+
+/// An incoming aura whose uid is already used by another aura. WeakAuras
+/// matches updates by uid, so the two would be mixed up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UidCollision {
+    pub aura_id: String,
+    pub uid: String,
+    /// The stored (or earlier incoming) aura with that uid
+    pub existing_id: String,
+}
+
+impl ConflictDetectionResult {
+    /// Attach last-imported snapshots as merge bases. Returns how many conflicts got a base.
+    pub fn attach_bases(&mut self, snapshots: &SnapshotStore) -> usize {
+        let mut attached = 0;
+        for conflict in &mut self.conflicts {
+            conflict.base = conflict.uid().and_then(|uid| snapshots.get(uid));
+            if conflict.base.is_some() {
+                attached += 1;
+            }
+        }
+        attached
+    }
+
+    /// Give every aura in [`uid_collisions`](Self::uid_collisions) a fresh
+    /// uid in its incoming data and clear the collisions. Returns the new
+    /// uids by aura ID.
+    pub fn assign_fresh_uids(&mut self) -> HashMap<String, String> {
+        let uids: HashMap<String, String> = self
+            .uid_collisions
+            .drain(..)
+            .map(|collision| (collision.aura_id, util::generate_uid()))
+            .collect();
+        let incoming = self
+            .new_auras
+            .iter_mut()
+            .map(|(id, data)| (&*id, data))
+            .chain(
+                self.conflicts
+                    .iter_mut()
+                    .map(|c| (&c.aura_id, &mut c.incoming)),
+            );
+        for (id, data) in incoming {
+            if let Some(uid) = uids.get(id) {
+                data.set_path("uid", LuaValue::String(uid.clone()));
+            }
+        }
+        uids
+    }
+}
+
+/// Result of a three-way merge
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThreeWayMerge {
+    /// Merged aura data
+    pub merged: LuaValue,
+    /// Field paths (dot-separated) taken from incoming: the author changed them, the user did not
+    pub from_incoming: Vec<String>,
+    /// Field paths changed differently on both sides; the local value is kept
+    pub conflicts: Vec<String>,
+}
+
+impl ThreeWayMerge {
+    /// Categories of the top-level fields taken from incoming, in display order
+    pub fn categories(&self) -> Vec<UpdateCategory> {
+        let touched: HashSet<UpdateCategory> = self
+            .from_incoming
+            .iter()
+            .map(|path| CategoryMapper::get_category(path.split('.').next().unwrap_or(path)))
+            .collect();
+        UpdateCategory::all()
+            .into_iter()
+            .filter(|c| touched.contains(c))
+            .collect()
+    }
+}
+
+/// Three-way merge of aura data. A field is taken from `incoming` only if the
+/// author changed it since `base` and the user did not change it locally.
+/// Nested tables are merged recursively; internal top-level fields are kept as-is.
+pub fn three_way_merge(base: &LuaValue, existing: &LuaValue, incoming: &LuaValue) -> ThreeWayMerge {
+    let mut merge = ThreeWayMerge {
+        merged: existing.clone(),
+        ..Default::default()
+    };
+    let empty = LuaTable::new();
+    let base_table = base.as_table().unwrap_or(&empty);
+    let incoming_table = incoming.as_table().unwrap_or(&empty);
+    if let Some(merged_table) = merge.merged.as_table_mut() {
+        merge_tables(
+            base_table,
+            merged_table,
+            incoming_table,
+            "",
+            &mut merge.from_incoming,
+            &mut merge.conflicts,
+        );
+    }
+    merge
+}
+
+/// Recursive helper for [`three_way_merge`]
+fn merge_tables(
+    base: &LuaTable,
+    existing: &mut LuaTable,
+    incoming: &LuaTable,
+    prefix: &str,
+    from_incoming: &mut Vec<String>,
+    conflicts: &mut Vec<String>,
+) {
+    let mut keys: Vec<&String> = base.keys().chain(incoming.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        if prefix.is_empty() && CategoryMapper::is_internal_field(key) {
+            continue;
+        }
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        let base_value = base.get(key);
+        let incoming_value = incoming.get(key);
+        if incoming_value == base_value {
+            // Author did not change this field
+            continue;
+        }
+        let existing_value = existing.get(key);
+        if existing_value == base_value {
+            // User did not change this field: take the author's version
+            match incoming_value {
+                Some(value) => existing.insert(key.clone(), value.clone()),
+                None => existing.shift_remove(key),
+            };
+            from_incoming.push(path);
+            continue;
+        }
+        if existing_value == incoming_value {
+            continue;
+        }
+        match (base_value, existing.get_mut(key), incoming_value) {
+            (
+                Some(LuaValue::Table(base_child)),
+                Some(LuaValue::Table(existing_child)),
+                Some(LuaValue::Table(incoming_child)),
+            ) => merge_tables(
+                base_child,
+                Arc::make_mut(existing_child),
+                incoming_child,
+                &path,
+                from_incoming,
+                conflicts,
+            ),
+            _ => conflicts.push(path),
+        }
+    }
+}
+
+/// How an incoming aura's version relates to the existing one.
+/// Ordered for sorting: newer first, unknown last.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionChange {
+    /// Incoming is a newer version
+    Newer,
+    /// Both sides have the same version
+    Same,
+    /// Incoming is an older version (downgrade)
+    Older,
+    /// Versions are missing or not comparable
+    #[default]
+    Unknown,
+}
+
+impl VersionChange {
+    /// Short label for UI and reports
+    pub fn label(&self) -> &'static str {
+        match self {
+            VersionChange::Newer => "newer",
+            VersionChange::Same => "same",
+            VersionChange::Older => "older",
+            VersionChange::Unknown => "unknown",
+        }
+    }
+}
+
+/// Compare aura versions using, in order: `semver`, numeric `version`, and the
+/// revision at the end of a wago.io `url` (only when both URLs point to the same aura)
+pub fn compare_versions(existing: &LuaValue, incoming: &LuaValue) -> VersionChange {
+    let field = |value: &LuaValue, key: &str| value.as_table().and_then(|t| t.get(key)).cloned();
+
+    let ordering = match (field(existing, "semver"), field(incoming, "semver")) {
+        (Some(LuaValue::String(a)), Some(LuaValue::String(b))) => compare_semver(&a, &b),
+        _ => None,
+    }
+    .or_else(
+        || match (field(existing, "version"), field(incoming, "version")) {
+            (Some(LuaValue::Number(a)), Some(LuaValue::Number(b))) => a.partial_cmp(&b),
+            _ => None,
+        },
+    )
+    .or_else(|| match (field(existing, "url"), field(incoming, "url")) {
+        (Some(LuaValue::String(a)), Some(LuaValue::String(b))) => compare_url_revisions(&a, &b),
+        _ => None,
+    });
+
+    match ordering {
+        Some(Ordering::Less) => VersionChange::Newer,
+        Some(Ordering::Equal) => VersionChange::Same,
+        Some(Ordering::Greater) => VersionChange::Older,
+        None => VersionChange::Unknown,
+    }
+}
+
+/// Compare two semantic version strings (`"1.2.3"`, `"v2.0-beta"`).
+/// Missing components count as zero and a pre-release sorts before its release.
+/// Returns `None` if either string is not a version.
+pub fn compare_semver(a: &str, b: &str) -> Option<Ordering> {
+    fn parse(version: &str) -> Option<(Vec<u64>, Option<&str>)> {
+        let version = version.trim().trim_start_matches(['v', 'V']);
+        let version = version.split('+').next().unwrap_or(version);
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let parts = core
+            .split('.')
+            .map(|part| part.parse::<u64>().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        Some((parts, pre))
+    }
+
+    let (a_parts, a_pre) = parse(a)?;
+    let (b_parts, b_pre) = parse(b)?;
+    let len = a_parts.len().max(b_parts.len());
+    let component = |parts: &[u64], i: usize| parts.get(i).copied().unwrap_or(0);
+    let core = (0..len)
+        .map(|i| component(&a_parts, i).cmp(&component(&b_parts, i)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal);
+
+    Some(core.then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(a), Some(b)) => a.cmp(b),
+    }))
+}
+
+/// Compare the trailing revision numbers of two URLs to the same aura
+/// (e.g. `https://wago.io/abc/12` vs `https://wago.io/abc/15`)
+fn compare_url_revisions(a: &str, b: &str) -> Option<Ordering> {
+    let split = |url: &str| {
+        let (base, revision) = url.trim_end_matches('/').rsplit_once('/')?;
+        Some((base.to_string(), revision.parse::<u64>().ok()?))
+    };
+    let (a_base, a_rev) = split(a)?;
+    let (b_base, b_rev) = split(b)?;
+    (a_base == b_base).then(|| a_rev.cmp(&b_rev))
+}
+
+/// Human-readable version of aura data (`semver`, else numeric `version`)
+fn display_version(data: &LuaValue) -> Option<String> {
+    let table = data.as_table()?;
+    match (table.get("semver"), table.get("version")) {
+        (Some(LuaValue::String(semver)), _) => Some(semver.clone()),
+        (_, Some(LuaValue::Number(n))) if n.fract() == 0.0 => Some(format!("{}", *n as i64)),
+        (_, Some(LuaValue::Number(n))) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Cached serialization of one display
+#[derive(Debug, Clone)]
+struct SerializedDisplay {
+    /// The display as it was serialized. Holding a clone keeps its tables
+    /// shared, so any later edit copies them and no longer compares equal by pointer.
+    value: LuaValue,
+    text: SerializedText,
+}
+
+#[derive(Debug, Clone)]
+enum SerializedText {
+    /// Byte range of the value in the loaded file content
+    Source(Range<usize>),
+    /// Text produced by the serializer, with or without sorted keys
+    Generated { text: String, sorted: bool },
+}
+
+/// Change made through a [`SavedVariablesManager`] since its file was loaded
+#[derive(Debug, Clone)]
+enum PendingOp {
+    AddAuras(Vec<WeakAura>),
+    ApplyResolutions(ConflictDetectionResult, Vec<ConflictResolution>),
+    RemoveAuras(Vec<String>),
+    DissolveGroup(String),
+    MoveIntoGroup(String, Vec<String>),
+    AnchorToScreen(Vec<String>),
+    RenameAura(String, String),
+    SetProperties(Vec<(String, AuraProperties)>),
+    SetFields(Vec<(String, String, LuaValue)>),
+}
+
+/// Modification time and size of a file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DiskStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl DiskStamp {
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok()?,
+            len: metadata.len(),
+        })
+    }
+}
+
+/// Advisory lock on a SavedVariables file, taken by [`lock_file`]. Dropping
+/// it releases the lock and removes the `<file>.lock` sidecar unless another
+/// instance holds or is waiting for it.
+pub(crate) struct FileLock {
+    file: fs::File,
+    lock_path: PathBuf,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // Only removed while held exclusively; instances that opened the
+        // sidecar before it was removed notice and take a fresh one
+        let _ = FileExt::unlock(&self.file);
+        if FileExt::try_lock_exclusive(&self.file).is_ok() {
+            let _ = fs::remove_file(&self.lock_path);
+            let _ = FileExt::unlock(&self.file);
+        }
+    }
+}
+
+/// Whether `file` is still the file at `path`, i.e. it was not removed by
+/// the previous holder of the lock
+fn is_current(file: &fs::File, path: &Path) -> bool {
+    let (Ok(handle), Ok(current)) = (
+        file.try_clone().map(same_file::Handle::from_file),
+        same_file::Handle::from_path(path),
+    ) else {
+        return false;
+    };
+    handle.is_ok_and(|handle| handle == current)
+}
+
+/// Take an advisory lock on a SavedVariables file, released when the returned
+/// guard is dropped. The lock is held on a `<file>.lock` sidecar so the data
+/// file itself stays readable (Windows locks are mandatory); the sidecar only
+/// exists while the file is in use. Other instances of this tool respect it;
+/// WoW itself does not.
+pub(crate) fn lock_file(path: &Path, exclusive: bool) -> Result<FileLock> {
+    let mut lock_path = path.as_os_str().to_owned();
+    lock_path.push(".lock");
+    let lock_path = PathBuf::from(lock_path);
+
+    let started = Instant::now();
+    let mut retrying = false;
+    loop {
+        let file = match fs_util::open_rw(&lock_path) {
+            Ok(file) => file,
+            // A sidecar being removed on Windows can't be opened until every
+            // handle to it is closed
+            Err(WeakAuraError::PermissionDenied(_))
+                if (exclusive || retrying) && started.elapsed() < LOCK_TIMEOUT =>
+            {
+                thread::sleep(Duration::from_millis(50));
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        loop {
+            let attempt = if exclusive {
+                FileExt::try_lock_exclusive(&file)
+            } else {
+                FileExt::try_lock_shared(&file)
+            };
+            match attempt {
+                Ok(()) => break,
+                Err(e) if e.kind() == fs2::lock_contended_error().kind() => {
+                    if started.elapsed() >= LOCK_TIMEOUT {
+                        return Err(WeakAuraError::FileLocked(
+                            path.to_string_lossy().to_string(),
+                        ));
+                    }
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        if is_current(&file, &lock_path) {
+            return Ok(FileLock { file, lock_path });
+        }
+        // Removed by the previous holder while waiting: start over
+        if started.elapsed() >= LOCK_TIMEOUT {
+            return Err(WeakAuraError::FileLocked(
+                path.to_string_lossy().to_string(),
+            ));
+        }
+        retrying = true;
+    }
 }
@@ -0,0 +1,240 @@
+//! Finding the files to load auras from in a folder.
+//!
+//! [`ScanOptions`] decides which files count: their extensions, paths to
+//! skip, a size limit and whether symlinks are followed. Ignore patterns are
+//! globs over the path relative to the scanned folder, with `/` separators:
+//! - `*` matches within one path component, `**` across components, `?` one
+//!   character
+//! - a pattern without `/` matches a file or folder name at any depth
+//!   (`*.bak`), otherwise it is anchored to the scanned folder
+//!   (`Packs/Old/*.txt`)
+//! - a trailing `/` matches folders only (`Backups/`, `.git/`)
+//!
+//! The walk does not stop at entries it cannot read; see [`scan_folder`].
+
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::error::{Result, WeakAuraError};
+
+/// Extensions read when nothing else is configured
+pub const DEFAULT_EXTENSIONS: &[&str] = &["txt", "md", "lua"];
+
+/// Which files a folder scan picks up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    /// File extensions to read, without the dot; case is ignored
+    pub extensions: Vec<String>,
+    /// Glob patterns of files and folders to skip
+    pub ignore: Vec<String>,
+    /// Files larger than this many bytes are skipped
+    pub max_file_size: Option<u64>,
+    /// Read symlinked files and descend into symlinked folders; a link
+    /// back to one of its own parent folders is reported, not followed
+    pub follow_symlinks: bool,
+    /// Folder levels below the scanned folder to search; `Some(0)` reads
+    /// only the files directly in it
+    pub max_depth: Option<usize>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            extensions: DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            ignore: vec![".git/".to_string()],
+            max_file_size: None,
+            follow_symlinks: true,
+            max_depth: None,
+        }
+    }
+}
+
+/// Files found by [`scan_folder`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FolderScan {
+    /// Files to read, sorted
+    pub files: Vec<PathBuf>,
+    /// Files skipped for exceeding `max_file_size`
+    pub too_large: Vec<PathBuf>,
+    /// Entries that could not be read, with the reason
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+/// A compiled ignore pattern
+#[derive(Debug)]
+struct IgnorePattern {
+    regex: Regex,
+    /// Matches the file or folder name rather than the whole relative path
+    name_only: bool,
+    dirs_only: bool,
+}
+
+impl IgnorePattern {
+    fn new(pattern: &str) -> Result<Self> {
+        let trimmed = pattern.trim().trim_start_matches("./");
+        let dirs_only = trimmed.ends_with('/');
+        let glob = trimmed.trim_end_matches('/');
+        if glob.is_empty() {
+            return Err(WeakAuraError::InvalidPattern(format!(
+                "empty ignore pattern '{}'",
+                pattern
+            )));
+        }
+        let name_only = !glob.contains('/');
+        let regex = Regex::new(&glob_to_regex(glob.trim_start_matches('/')))
+            .map_err(|e| WeakAuraError::InvalidPattern(format!("{}: {}", pattern, e)))?;
+        Ok(Self {
+            regex,
+            name_only,
+            dirs_only,
+        })
+    }
+
+    /// Whether the entry at `relative` (with `/` separators) is skipped
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        if self.dirs_only && !is_dir {
+            return false;
+        }
+        let subject = if self.name_only {
+            relative.rsplit('/').next().unwrap_or(relative)
+        } else {
+            relative
+        };
+        self.regex.is_match(subject)
+    }
+}
+
+/// Anchored regex for a glob
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // `**/` also matches no folder at all
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+impl ScanOptions {
+    /// Whether `path` has one of the configured extensions
+    pub fn has_extension(&self, path: &Path) -> bool {
+        path.extension().is_some_and(|ext| {
+            self.extensions
+                .iter()
+                .any(|e| ext.eq_ignore_ascii_case(e.trim().trim_start_matches('.')))
+        })
+    }
+
+    /// Check the ignore patterns without scanning, e.g. before saving them
+    pub fn validate(&self) -> Result<()> {
+        self.compile_ignore().map(|_| ())
+    }
+
+    fn compile_ignore(&self) -> Result<Vec<IgnorePattern>> {
+        self.ignore
+            .iter()
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| IgnorePattern::new(p))
+            .collect()
+    }
+}
+
+/// Find the files under `folder` that `options` picks up, recursively.
+///
+/// Entries that cannot be read (permission denied, dangling symlinks,
+/// symlink loops) are collected in [`FolderScan::errors`] and the walk goes
+/// on; only an unreadable `folder` itself is an error.
+pub fn scan_folder(folder: &Path, options: &ScanOptions) -> Result<FolderScan> {
+    let ignore = options.compile_ignore()?;
+    let mut scan = FolderScan::default();
+    let mut walker = WalkDir::new(folder).follow_links(options.follow_symlinks);
+    if let Some(depth) = options.max_depth {
+        walker = walker.max_depth(depth.saturating_add(1));
+    }
+    let entries = walker.into_iter().filter_entry(|entry| {
+        if entry.depth() == 0 {
+            return true;
+        }
+        let relative = relative_path(folder, entry.path());
+        let is_dir = entry.file_type().is_dir();
+        !ignore.iter().any(|p| p.matches(&relative, is_dir))
+    });
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) if e.depth() == 0 => return Err(walk_error(e)),
+            Err(e) => {
+                let path = e.path().unwrap_or(folder).to_path_buf();
+                scan.errors.push((path, describe_walk_error(&e)));
+                continue;
+            }
+        };
+        // Unfollowed symlinks are neither files nor folders
+        if !entry.file_type().is_file() || !options.has_extension(entry.path()) {
+            continue;
+        }
+        let size = match entry.metadata() {
+            Ok(metadata) => metadata.len(),
+            Err(e) => {
+                scan.errors
+                    .push((entry.path().to_path_buf(), describe_walk_error(&e)));
+                continue;
+            }
+        };
+        if options.max_file_size.is_some_and(|max| size > max) {
+            scan.too_large.push(entry.into_path());
+        } else {
+            scan.files.push(entry.into_path());
+        }
+    }
+    scan.files.sort();
+    scan.too_large.sort();
+    Ok(scan)
+}
+
+/// `path` relative to `root`, with `/` separators
+fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    parts.join("/")
+}
+
+fn describe_walk_error(error: &walkdir::Error) -> String {
+    if error.loop_ancestor().is_some() {
+        return "symlink loop, not followed".to_string();
+    }
+    match error.io_error() {
+        Some(e) => e.to_string(),
+        None => error.to_string(),
+    }
+}
+
+fn walk_error(error: walkdir::Error) -> WeakAuraError {
+    let message = error.to_string();
+    match error.into_io_error() {
+        Some(e) => WeakAuraError::IoError(e),
+        None => WeakAuraError::InvalidValue(message),
+    }
+}
@@ -0,0 +1,324 @@
+//! Custom batch operations on existing auras, written as Rhai scripts.
+//!
+//! [`run_script`] runs a script against the auras of a
+//! [`SavedVariablesManager`]. Scripts see the auras through a small API:
+//!
+//! - `ids()`: every aura ID, in file order
+//! - `exists(id)`, `children(id)`, `descendants(id)`
+//! - `model(id)`: the typed [`AuraModel`] of an aura, as a map
+//! - `get(id, path)` and `set(id, path, value)` on dotted paths such as
+//!   `actions.start.sound`; setting `()` removes the field
+//! - `print(...)`, collected into the report
+//!
+//! ```text
+//! for id in descendants("Raid Cooldowns") {
+//!     set(id, "actions.start.sound", ());
+//! }
+//! ```
+//!
+//! The engine is sandboxed: scripts cannot read or write files or `import`
+//! modules, `eval` is disabled and runaway loops stop at an operation limit.
+//! Changes collect in the [`ScriptReport`] and reach the manager only when
+//! the run is not a dry run and the whole script succeeded.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use rhai::{Array, Dynamic, Engine, EvalAltResult};
+use serde::Serialize;
+
+use crate::config::AppConfig;
+use crate::decoder::{LuaTable, LuaValue};
+use crate::error::{Result, WeakAuraError};
+use crate::model::AuraModel;
+use crate::saved_variables::SavedVariablesManager;
+
+/// Folder below the config directory holding the user's scripts
+const SCRIPTS_DIR_NAME: &str = "scripts";
+
+/// File extension of scripts
+pub const SCRIPT_EXTENSION: &str = "rhai";
+
+/// Operations a script may run before it is stopped
+const MAX_OPERATIONS: u64 = 5_000_000;
+
+/// One field a script set or removed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScriptChange {
+    pub id: String,
+    /// Dotted path of the field
+    pub path: String,
+    /// Value before the change (`Nil` when the field was missing)
+    pub old: LuaValue,
+    /// Value after the change (`Nil` when the field was removed)
+    pub new: LuaValue,
+}
+
+/// Outcome of a script run
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ScriptReport {
+    /// Lines the script printed
+    pub output: Vec<String>,
+    /// Field changes, in the order the script made them
+    pub changes: Vec<ScriptChange>,
+    /// Whether the changes were applied to the manager
+    pub applied: bool,
+}
+
+impl ScriptReport {
+    /// Auras the script changed, in order of their first change
+    pub fn changed_ids(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.changes
+            .iter()
+            .map(|change| change.id.as_str())
+            .filter(|id| seen.insert(*id))
+            .collect()
+    }
+
+    /// Printed output followed by one line per change, e.g.
+    /// `Icon: actions.start.sound = "bell.ogg" -> nil`
+    pub fn to_text(&self) -> String {
+        let mut lines = self.output.clone();
+        let verb = if self.applied {
+            "Applied"
+        } else {
+            "Would apply"
+        };
+        lines.push(format!(
+            "{} {} change(s) to {} aura(s)",
+            verb,
+            self.changes.len(),
+            self.changed_ids().len()
+        ));
+        for change in &self.changes {
+            lines.push(format!(
+                "{}: {} = {} -> {}",
+                change.id,
+                change.path,
+                value_text(&change.old),
+                value_text(&change.new)
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+fn value_text(value: &LuaValue) -> String {
+    match value {
+        LuaValue::Nil => "nil".to_string(),
+        value => value.to_json().to_string(),
+    }
+}
+
+/// Default folder of the user's scripts
+pub fn scripts_dir() -> Option<PathBuf> {
+    AppConfig::config_dir().map(|dir| dir.join(SCRIPTS_DIR_NAME))
+}
+
+/// Script files of a folder, sorted by name. A missing folder has none.
+pub fn list_scripts(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut scripts: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case(SCRIPT_EXTENSION))
+        })
+        .collect();
+    scripts.sort();
+    scripts
+}
+
+/// Auras as the script sees them, with the changes it made so far
+#[derive(Default)]
+struct ScriptState {
+    displays: LuaTable,
+    changes: Vec<ScriptChange>,
+    output: Vec<String>,
+}
+
+/// Run `source` against the auras of `manager`. With `dry_run`, or when the
+/// script fails, the manager is left as it was; otherwise the changes are
+/// applied (call [`SavedVariablesManager::save`] to persist).
+pub fn run_script(
+    manager: &mut SavedVariablesManager,
+    source: &str,
+    dry_run: bool,
+) -> Result<ScriptReport> {
+    let state = Rc::new(RefCell::new(ScriptState {
+        displays: manager.displays.clone(),
+        ..ScriptState::default()
+    }));
+    let engine = sandboxed_engine(&state);
+    engine
+        .run(source)
+        .map_err(|e| WeakAuraError::ScriptError(e.to_string()))?;
+    drop(engine);
+
+    let state = Rc::try_unwrap(state)
+        .map_err(|_| WeakAuraError::ScriptError("the script state is still in use".to_string()))?
+        .into_inner();
+    let mut report = ScriptReport {
+        output: state.output,
+        changes: state.changes,
+        applied: false,
+    };
+    if !dry_run && !report.changes.is_empty() {
+        let changes: Vec<(String, String, LuaValue)> = report
+            .changes
+            .iter()
+            .map(|change| (change.id.clone(), change.path.clone(), change.new.clone()))
+            .collect();
+        manager.set_fields(&changes);
+        report.applied = true;
+    }
+    Ok(report)
+}
+
+type ScriptResult<T> = std::result::Result<T, Box<EvalAltResult>>;
+
+fn sandboxed_engine(state: &Rc<RefCell<ScriptState>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(64);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(1 << 20);
+    engine.set_max_array_size(1 << 20);
+    engine.set_max_map_size(1 << 16);
+    engine.disable_symbol("eval");
+    // No `import` of script files from disk
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+
+    let output = Rc::clone(state);
+    engine.on_print(move |line| output.borrow_mut().output.push(line.to_string()));
+    let output = Rc::clone(state);
+    engine.on_debug(move |line, _, _| output.borrow_mut().output.push(line.to_string()));
+
+    let s = Rc::clone(state);
+    engine.register_fn("ids", move || -> Array {
+        s.borrow()
+            .displays
+            .keys()
+            .map(|id| Dynamic::from(id.clone()))
+            .collect()
+    });
+    let s = Rc::clone(state);
+    engine.register_fn("exists", move |id: &str| -> bool {
+        s.borrow().displays.contains_key(id)
+    });
+    let s = Rc::clone(state);
+    engine.register_fn("children", move |id: &str| -> ScriptResult<Array> {
+        let state = s.borrow();
+        let data = aura(&state.displays, id)?;
+        Ok(children(data).into_iter().map(Dynamic::from).collect())
+    });
+    let s = Rc::clone(state);
+    engine.register_fn("descendants", move |id: &str| -> ScriptResult<Array> {
+        let state = s.borrow();
+        aura(&state.displays, id)?;
+        Ok(descendants(&state.displays, id)
+            .into_iter()
+            .map(Dynamic::from)
+            .collect())
+    });
+    let s = Rc::clone(state);
+    engine.register_fn("model", move |id: &str| -> ScriptResult<Dynamic> {
+        let state = s.borrow();
+        let model = AuraModel::from_lua(aura(&state.displays, id)?)
+            .map_err(|e| format!("'{}': {}", id, e))?;
+        rhai::serde::to_dynamic(&model)
+    });
+    let s = Rc::clone(state);
+    engine.register_fn(
+        "get",
+        move |id: &str, path: &str| -> ScriptResult<Dynamic> {
+            let state = s.borrow();
+            match aura(&state.displays, id)?.get_path(path) {
+                Some(value) => rhai::serde::to_dynamic(value.to_json()),
+                None => Ok(Dynamic::UNIT),
+            }
+        },
+    );
+    let s = Rc::clone(state);
+    engine.register_fn(
+        "set",
+        move |id: &str, path: &str, value: Dynamic| -> ScriptResult<()> {
+            let new = LuaValue::from_json(rhai::serde::from_dynamic(&value)?);
+            let mut state = s.borrow_mut();
+            let data = state
+                .displays
+                .get_mut(id)
+                .ok_or_else(|| format!("no aura called '{}'", id))?;
+            let old = data.get_path(path).cloned().unwrap_or_default();
+            if old == new {
+                return Ok(());
+            }
+            let done = match &new {
+                LuaValue::Nil => data.remove_path(path).is_some(),
+                new => data.set_path(path, new.clone()),
+            };
+            if !done {
+                return Err(format!("cannot set '{}' of '{}'", path, id).into());
+            }
+            state.changes.push(ScriptChange {
+                id: id.to_string(),
+                path: path.to_string(),
+                old,
+                new,
+            });
+            Ok(())
+        },
+    );
+    engine
+}
+
+fn aura<'a>(displays: &'a LuaTable, id: &str) -> ScriptResult<&'a LuaValue> {
+    displays
+        .get(id)
+        .ok_or_else(|| format!("no aura called '{}'", id).into())
+}
+
+fn children(data: &LuaValue) -> Vec<String> {
+    data.get_path("controlledChildren")
+        .and_then(LuaValue::as_array)
+        .map(|children| {
+            children
+                .iter()
+                .filter_map(LuaValue::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Every aura below `id`, parents before their children. Damaged files may
+/// list a child twice or loop back, so each aura is visited once.
+fn descendants(displays: &LuaTable, id: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut seen = HashSet::from([id.to_string()]);
+    let mut stack: Vec<String> = displays
+        .get(id)
+        .map(children)
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .collect();
+    while let Some(current) = stack.pop() {
+        if !seen.insert(current.clone()) {
+            continue;
+        }
+        if let Some(data) = displays.get(&current) {
+            stack.extend(children(data).into_iter().rev());
+        }
+        result.push(current);
+    }
+    result
+}
@@ -0,0 +1,221 @@
+//! Bulk selection rules for parsed auras.
+//!
+//! A [`SelectionRule`] combines optional predicates (valid only, groups only,
+//! region type, source file, ID regex); all set predicates must match.
+//! [`SelectionMode`] decides how matches update the current selection.
+//! [`RangeSelection`] turns shift/ctrl-clicks into the items they select.
+//! [`trivial_reason`] flags placeholder auras that bulk selection can skip.
+
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::decoder::{LuaValue, ValidationResult, WeakAura};
+use crate::error::{Result, WeakAuraError};
+
+/// Predicates for selecting auras in bulk. Unset predicates match everything.
+#[derive(Debug, Clone, Default)]
+pub struct SelectionRule {
+    /// Only match successfully decoded auras
+    pub valid_only: bool,
+    /// Only match group auras
+    pub groups_only: bool,
+    /// Match this region type (e.g. "icon", "aurabar")
+    pub region_type: Option<String>,
+    /// Match auras loaded from a file with this name
+    pub source_file: Option<String>,
+    /// Match aura IDs against this pattern (see [`SelectionRule::with_id_pattern`])
+    pub id_pattern: Option<Regex>,
+}
+
+impl SelectionRule {
+    /// Set the ID regex (case-insensitive). An empty pattern clears it.
+    pub fn with_id_pattern(mut self, pattern: &str) -> Result<Self> {
+        let pattern = pattern.trim();
+        self.id_pattern = if pattern.is_empty() {
+            None
+        } else {
+            Some(
+                Regex::new(&format!("(?i){}", pattern))
+                    .map_err(|e| WeakAuraError::InvalidPattern(e.to_string()))?,
+            )
+        };
+        Ok(self)
+    }
+
+    /// Whether an aura satisfies every set predicate
+    pub fn matches(
+        &self,
+        validation: &ValidationResult,
+        aura: Option<&WeakAura>,
+        source: Option<&Path>,
+    ) -> bool {
+        if self.valid_only && !validation.is_valid {
+            return false;
+        }
+        if self.groups_only && !validation.is_group {
+            return false;
+        }
+        if let Some(region_type) = &self.region_type {
+            if aura.and_then(|a| a.region_type.as_ref()) != Some(region_type) {
+                return false;
+            }
+        }
+        if let Some(source_file) = &self.source_file {
+            let file_name = source
+                .and_then(Path::file_name)
+                .map(|n| n.to_string_lossy());
+            if file_name.as_deref() != Some(source_file.as_str()) {
+                return false;
+            }
+        }
+        if let Some(re) = &self.id_pattern {
+            match validation.aura_id.as_deref() {
+                Some(id) if re.is_match(id) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+}
+
+/// Fields any aura may carry without showing anything
+const METADATA_KEYS: &[&str] = &[
+    "id",
+    "uid",
+    "desc",
+    "parent",
+    "regionType",
+    "internalVersion",
+    "tocversion",
+    "version",
+    "semver",
+    "url",
+    "wagoID",
+    "source",
+    "preferToUpdate",
+    "information",
+];
+
+/// Why an aura looks like an empty placeholder, or `None` when it does not:
+/// it holds nothing but metadata such as a description, has no triggers, or
+/// has a region of zero width or height. Groups are never trivial.
+pub fn trivial_reason(aura: &WeakAura) -> Option<&'static str> {
+    if aura.is_group {
+        return None;
+    }
+    let table = aura.data.as_table()?;
+    if table
+        .keys()
+        .all(|key| METADATA_KEYS.contains(&key.as_str()))
+    {
+        return Some("only a description");
+    }
+    let has_trigger = aura
+        .data
+        .get_path("triggers")
+        .and_then(LuaValue::as_array)
+        .is_some_and(|triggers| {
+            triggers
+                .iter()
+                .any(|entry| entry.get_path("trigger.type").is_some())
+        });
+    if !has_trigger {
+        return Some("no triggers");
+    }
+    let zero =
+        |key: &str| matches!(aura.data.get_path(key), Some(LuaValue::Number(n)) if *n == 0.0);
+    if zero("width") || zero("height") {
+        return Some("zero-size region");
+    }
+    None
+}
+
+/// How rule matches update the current selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Add matches to the selection
+    Select,
+    /// Remove matches from the selection
+    Deselect,
+    /// Select matches and deselect everything else
+    Only,
+}
+
+impl SelectionMode {
+    /// New selection state for an aura
+    pub fn apply(self, currently_selected: bool, matches: bool) -> bool {
+        match self {
+            SelectionMode::Select => currently_selected || matches,
+            SelectionMode::Deselect => currently_selected && !matches,
+            SelectionMode::Only => matches,
+        }
+    }
+}
+
+/// How a click changes a multi-selection, from the modifier keys held
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickMode {
+    /// Plain click
+    Single,
+    /// Ctrl-click (Cmd on macOS): toggle the item, keeping the rest
+    Toggle,
+    /// Shift-click: every item from the anchor to the clicked one
+    Range,
+}
+
+impl ClickMode {
+    /// Shift wins over ctrl/cmd
+    pub fn from_modifiers(shift: bool, command: bool) -> Self {
+        if shift {
+            ClickMode::Range
+        } else if command {
+            ClickMode::Toggle
+        } else {
+            ClickMode::Single
+        }
+    }
+}
+
+/// Anchor for shift-click range selection in a list of items keyed by `K`
+#[derive(Debug, Clone)]
+pub struct RangeSelection<K> {
+    anchor: Option<K>,
+}
+
+impl<K> Default for RangeSelection<K> {
+    fn default() -> Self {
+        Self { anchor: None }
+    }
+}
+
+impl<K: Clone + PartialEq> RangeSelection<K> {
+    /// Items a click on `clicked` applies to, given the items in display
+    /// `order`. A range runs from the anchor (the last item clicked without
+    /// shift) to `clicked`, in either direction; without an anchor in
+    /// `order` it is just `clicked`. Single and toggle clicks move the anchor.
+    pub fn click(&mut self, order: &[K], clicked: &K, mode: ClickMode) -> Vec<K> {
+        if mode != ClickMode::Range {
+            self.anchor = Some(clicked.clone());
+            return vec![clicked.clone()];
+        }
+        let position = |key: &K| order.iter().position(|k| k == key);
+        match (self.anchor.as_ref().and_then(position), position(clicked)) {
+            (Some(from), Some(to)) => order[from.min(to)..=from.max(to)].to_vec(),
+            _ => {
+                self.anchor = Some(clicked.clone());
+                vec![clicked.clone()]
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn anchor(&self) -> Option<&K> {
+        self.anchor.as_ref()
+    }
+
+    /// Forget the anchor, e.g. when the list changes
+    pub fn clear(&mut self) {
+        self.anchor = None;
+    }
+}
@@ -0,0 +1,76 @@
+//! Auto-saved working session.
+//!
+//! The strings loaded into the GUI are written to `session.json` next to the
+//! app config while the user reviews them, with their selection state and
+//! source file, so a crash or accidental close doesn't lose them. Auras moved
+//! to the staging area are kept in their own list. The next launch offers to
+//! restore the session. An empty session removes the file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, AppConfig};
+use crate::error::{Result, WeakAuraError};
+
+/// Session file name
+const SESSION_FILE_NAME: &str = "session.json";
+
+/// One loaded aura
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionEntry {
+    /// Original import string
+    pub import_string: String,
+    /// Whether the aura was selected for import
+    #[serde(default)]
+    pub selected: bool,
+    /// File the aura was loaded from (`None` for pasted input)
+    #[serde(default)]
+    pub source: Option<PathBuf>,
+}
+
+/// Auras loaded in the GUI
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub entries: Vec<SessionEntry>,
+    /// Auras in the staging area
+    pub staged: Vec<SessionEntry>,
+}
+
+impl Session {
+    /// Default location of the session file
+    pub fn default_path() -> Option<PathBuf> {
+        AppConfig::config_dir().map(|dir| dir.join(SESSION_FILE_NAME))
+    }
+
+    /// Load from the default location, falling back to an empty session on any error
+    pub fn load() -> Self {
+        config::load_json(SESSION_FILE_NAME)
+    }
+
+    /// Save to the default location
+    pub fn save(&self) -> Result<()> {
+        let path = Self::default_path().ok_or_else(|| {
+            WeakAuraError::ConfigError("No config directory available".to_string())
+        })?;
+        self.save_to(&path)
+    }
+
+    /// Save to a specific file, creating parent directories.
+    /// An empty session removes the file instead.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if self.is_empty() {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+            return Ok(());
+        }
+        config::save_json_to(path, self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.staged.is_empty()
+    }
+}
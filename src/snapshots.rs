@@ -0,0 +1,58 @@
+//! Last-imported aura snapshots, stored in a sidecar file.
+//!
+//! Each aura is recorded as it was imported, keyed by `uid`, in
+//! `snapshots.json` next to the app config. The next import of the same aura
+//! uses the snapshot as the base of a three-way merge, so only fields the
+//! author changed overwrite the user's local copy. Values are stored as Lua
+//! table source so mixed tables survive the round trip unchanged.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::config;
+use crate::decoder::LuaValue;
+use crate::error::Result;
+use crate::lua_parser::LuaParser;
+
+/// Sidecar file name
+const SNAPSHOTS_FILE_NAME: &str = "snapshots.json";
+
+/// Snapshot database keyed by aura uid
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotStore {
+    /// uid -> aura data as serialized Lua table
+    pub auras: BTreeMap<String, String>,
+}
+
+impl SnapshotStore {
+    /// Load from the default location, falling back to an empty store on any error
+    pub fn load() -> Self {
+        config::load_json(SNAPSHOTS_FILE_NAME)
+    }
+
+    /// Save to the default location
+    pub fn save(&self) -> Result<()> {
+        config::save_json(SNAPSHOTS_FILE_NAME, self)
+    }
+
+    /// Snapshot for an aura, if one was recorded and still parses
+    pub fn get(&self, uid: &str) -> Option<LuaValue> {
+        let source = self.auras.get(uid)?;
+        LuaParser::parse_value(source)
+            .map_err(|e| warn!(uid, error = %e, "Failed to parse snapshot"))
+            .ok()
+    }
+
+    /// Record aura data under its `uid`. Returns `false` if the data has no uid.
+    pub fn record(&mut self, data: &LuaValue) -> bool {
+        let Some(LuaValue::String(uid)) = data.as_table().and_then(|t| t.get("uid")) else {
+            return false;
+        };
+        self.auras
+            .insert(uid.clone(), LuaParser::serialize(data, 0));
+        true
+    }
+}
@@ -0,0 +1,320 @@
+//! Generating many auras from one parameterized template.
+//!
+//! A template is an ordinary aura or group whose strings hold placeholders.
+//! [`AuraTemplate::expand`] turns it into one concrete aura per row of
+//! parameters, each with fresh uids:
+//! - `{{name}}` anywhere in a string is replaced with the row's `name`
+//! - a string that is only `{{name:number}}` becomes that number, and one
+//!   that is only `{{name:color}}` a color table from `#rrggbb` or `#rrggbbaa`
+//!
+//! Parameters are read from CSV with a header row or from a JSON array of
+//! objects, see [`parse_params`]. Templates are kept in `templates.json` next
+//! to the app config by [`TemplateStore`].
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::{Arc, OnceLock};
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::decoder::{LuaPathSegment, LuaTable, LuaValue, WeakAura, WeakAuraDecoder};
+use crate::error::{Result, WeakAuraError};
+use crate::util::{generate_uid, parse_csv};
+
+/// Sidecar file name
+const TEMPLATES_FILE_NAME: &str = "templates.json";
+
+/// Parameters of one generated aura, by name
+pub type TemplateParams = BTreeMap<String, String>;
+
+fn placeholder_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*(?::\s*(number|color)\s*)?\}\}")
+            .expect("valid placeholder regex")
+    })
+}
+
+/// An aura with placeholders, expanded into concrete auras
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuraTemplate {
+    pub aura: WeakAura,
+}
+
+impl AuraTemplate {
+    pub fn new(aura: WeakAura) -> Self {
+        Self { aura }
+    }
+
+    /// Names of the parameters the template uses, sorted
+    pub fn placeholders(&self) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        for value in std::iter::once(&self.aura.data).chain(&self.aura.child_data) {
+            value.walk(&mut |_, value| {
+                if let LuaValue::String(s) = value {
+                    for captures in placeholder_regex().captures_iter(s) {
+                        names.insert(captures[1].to_string());
+                    }
+                }
+            });
+        }
+        names
+    }
+
+    /// One aura per row of `rows`, in order. Every aura and child gets a
+    /// fresh uid. A placeholder without a parameter in its row, a malformed
+    /// number or color, or an ID generated twice is an error naming the row.
+    pub fn expand(&self, rows: &[TemplateParams]) -> Result<Vec<WeakAura>> {
+        let mut seen_ids = HashSet::new();
+        let mut auras = Vec::with_capacity(rows.len());
+        for (index, params) in rows.iter().enumerate() {
+            let row_error = |message: String| {
+                WeakAuraError::InvalidValue(format!("row {}: {}", index + 1, message))
+            };
+
+            let data = fill(&self.aura.data, params).map_err(row_error)?;
+            let children = self
+                .aura
+                .child_data
+                .iter()
+                .map(|child| fill(child, params))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(row_error)?;
+
+            let mut transmission = LuaTable::new();
+            transmission.insert("d".to_string(), with_fresh_uid(data));
+            transmission.insert(
+                "c".to_string(),
+                LuaValue::Array(children.into_iter().map(with_fresh_uid).collect()),
+            );
+            let aura =
+                WeakAuraDecoder::aura_from_data(LuaValue::Table(Arc::new(transmission)), false)
+                    .map_err(|e| row_error(e.to_string()))?
+                    .aura;
+
+            for id in std::iter::once(aura.id.clone()).chain(aura.children.iter().cloned()) {
+                if !seen_ids.insert(id.clone()) {
+                    return Err(row_error(format!(
+                        "the ID '{}' is generated more than once; add a placeholder to it",
+                        id
+                    )));
+                }
+            }
+            auras.push(aura);
+        }
+        Ok(auras)
+    }
+}
+
+/// `value` with the placeholders of its strings filled from `params`, set
+/// through their paths
+fn fill(value: &LuaValue, params: &TemplateParams) -> std::result::Result<LuaValue, String> {
+    let mut replacements = Vec::new();
+    let mut error = None;
+    value.walk(&mut |path, value| {
+        let LuaValue::String(s) = value else {
+            return;
+        };
+        if error.is_some() || !placeholder_regex().is_match(s) {
+            return;
+        }
+        match substitute(s, params) {
+            Ok(new_value) => replacements.push((dotted_path(path), new_value)),
+            Err(e) => error = Some(e),
+        }
+    });
+    if let Some(e) = error {
+        return Err(e);
+    }
+
+    let mut filled = value.clone();
+    for (path, new_value) in replacements {
+        match path {
+            Some(path) if filled.set_path(&path, new_value) => {}
+            _ => return Err("a placeholder sits under a key containing '.'".to_string()),
+        }
+    }
+    Ok(filled)
+}
+
+/// The value of a string with placeholders
+fn substitute(s: &str, params: &TemplateParams) -> std::result::Result<LuaValue, String> {
+    let param = |name: &str| {
+        params
+            .get(name)
+            .ok_or_else(|| format!("no parameter '{}'", name))
+    };
+
+    // A typed placeholder takes the whole string
+    if let Some(captures) = placeholder_regex().captures(s) {
+        if let Some(kind) = captures.get(2) {
+            if captures[0].len() != s.len() {
+                return Err(format!(
+                    "'{}' must be the whole string to be typed",
+                    &captures[0]
+                ));
+            }
+            let name = &captures[1];
+            let value = param(name)?.trim();
+            return match kind.as_str() {
+                "number" => value
+                    .parse::<f64>()
+                    .map(LuaValue::Number)
+                    .map_err(|_| format!("'{}' of '{}' is not a number", value, name)),
+                _ => parse_color(value)
+                    .ok_or_else(|| format!("'{}' of '{}' is not a #rrggbb color", value, name)),
+            };
+        }
+    }
+
+    let mut missing = None;
+    let text =
+        placeholder_regex().replace_all(s, |captures: &Captures| match param(&captures[1]) {
+            Ok(value) if captures.get(2).is_none() => value.clone(),
+            Ok(_) => {
+                missing.get_or_insert_with(|| {
+                    format!("'{}' must be the whole string to be typed", &captures[0])
+                });
+                String::new()
+            }
+            Err(e) => {
+                missing.get_or_insert(e);
+                String::new()
+            }
+        });
+    match missing {
+        Some(e) => Err(e),
+        None => Ok(LuaValue::String(text.into_owned())),
+    }
+}
+
+/// A WeakAuras color table (`{ r, g, b, a }` from 0 to 1) from `#rrggbb` or
+/// `#rrggbbaa`
+fn parse_color(value: &str) -> Option<LuaValue> {
+    let hex = value.strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let mut channels = Vec::with_capacity(4);
+    for i in (0..hex.len()).step_by(2) {
+        let byte = u8::from_str_radix(&hex[i..i + 2], 16).ok()?;
+        channels.push(LuaValue::Number(f64::from(byte) / 255.0));
+    }
+    if channels.len() == 3 {
+        channels.push(LuaValue::Number(1.0));
+    }
+    Some(LuaValue::Array(channels))
+}
+
+/// Path for [`LuaValue::set_path`], unless a key contains a `.`
+fn dotted_path(path: &[LuaPathSegment]) -> Option<String> {
+    let mut segments = Vec::with_capacity(path.len());
+    for segment in path {
+        match segment {
+            LuaPathSegment::Key(key) if key.contains('.') => return None,
+            LuaPathSegment::Key(key) => segments.push(key.clone()),
+            LuaPathSegment::Index(index) => segments.push(index.to_string()),
+        }
+    }
+    Some(segments.join("."))
+}
+
+fn with_fresh_uid(mut data: LuaValue) -> LuaValue {
+    data.set_path("uid", LuaValue::String(generate_uid()));
+    data
+}
+
+/// Parameter rows from CSV with a header row, or from a JSON array of
+/// objects whose values are strings, numbers or booleans
+pub fn parse_params(text: &str) -> Result<Vec<TemplateParams>> {
+    if text.trim_start().starts_with('[') {
+        return parse_params_json(text);
+    }
+    let mut rows = parse_csv(text)?.into_iter();
+    let Some(header) = rows.next() else {
+        return Ok(Vec::new());
+    };
+    let names: Vec<String> = header.iter().map(|name| name.trim().to_string()).collect();
+    Ok(rows
+        .map(|row| {
+            names
+                .iter()
+                .zip(row.into_iter().chain(std::iter::repeat(String::new())))
+                .filter(|(name, _)| !name.is_empty())
+                .map(|(name, value)| (name.clone(), value))
+                .collect()
+        })
+        .collect())
+}
+
+fn parse_params_json(text: &str) -> Result<Vec<TemplateParams>> {
+    let invalid = |message: String| WeakAuraError::InvalidValue(message);
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| invalid(format!("invalid JSON: {}", e)))?;
+    let serde_json::Value::Array(rows) = value else {
+        return Err(invalid("the parameters must be a JSON array".to_string()));
+    };
+    rows.into_iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let serde_json::Value::Object(object) = row else {
+                return Err(invalid(format!("row {}: not an object", index + 1)));
+            };
+            let mut params = TemplateParams::new();
+            for (name, value) in object {
+                let text = match value {
+                    serde_json::Value::Null => continue,
+                    serde_json::Value::String(s) => s,
+                    serde_json::Value::Number(n) => n.to_string(),
+                    serde_json::Value::Bool(b) => b.to_string(),
+                    _ => {
+                        return Err(invalid(format!(
+                            "row {}: '{}' is not a string, number or boolean",
+                            index + 1,
+                            name
+                        )))
+                    }
+                };
+                params.insert(name, text);
+            }
+            Ok(params)
+        })
+        .collect()
+}
+
+/// Saved templates by name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateStore {
+    pub templates: BTreeMap<String, AuraTemplate>,
+}
+
+impl TemplateStore {
+    /// Load from the default location, falling back to an empty store on any error
+    pub fn load() -> Self {
+        config::load_json(TEMPLATES_FILE_NAME)
+    }
+
+    /// Save to the default location
+    pub fn save(&self) -> Result<()> {
+        config::save_json(TEMPLATES_FILE_NAME, self)
+    }
+
+    /// Store an aura as the template `name`, replacing one of that name.
+    /// Returns whether a template was replaced.
+    pub fn insert(&mut self, name: &str, aura: WeakAura) -> bool {
+        self.templates
+            .insert(name.to_string(), AuraTemplate::new(aura))
+            .is_some()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AuraTemplate> {
+        self.templates.get(name)
+    }
+
+    /// Remove a template. Returns `false` if there was none of that name.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.templates.remove(name).is_some()
+    }
+}
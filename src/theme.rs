@@ -9,7 +9,7 @@
 
 #![allow(dead_code)]
 
-use iced::widget::{button, container, scrollable, text_input};
+use iced::widget::{button, checkbox, container, scrollable, text_input};
 use iced::{Border, Color, Shadow, Theme, Vector};
 
 // ============================================================================
@@ -665,6 +665,42 @@ pub fn text_input_style(_theme: &Theme, status: text_input::Status) -> text_inpu
     }
 }
 
+// ============================================================================
+// CHECKBOX STYLES
+// ============================================================================
+
+/// Unchecked box filled with muted gold - a group with some children selected
+pub fn checkbox_partial(_theme: &Theme, status: checkbox::Status) -> checkbox::Style {
+    let base = checkbox::Style {
+        background: colors::GOLD_MUTED.into(),
+        icon_color: colors::GOLD,
+        border: Border::default()
+            .rounded(design::RADIUS_XS)
+            .color(colors::GOLD_DARK)
+            .width(1.0),
+        text_color: None,
+    };
+
+    match status {
+        checkbox::Status::Hovered { .. } => checkbox::Style {
+            border: Border::default()
+                .rounded(design::RADIUS_XS)
+                .color(colors::GOLD)
+                .width(1.0),
+            ..base
+        },
+        checkbox::Status::Disabled { .. } => checkbox::Style {
+            background: colors::BG_SURFACE.into(),
+            border: Border::default()
+                .rounded(design::RADIUS_XS)
+                .color(colors::BORDER)
+                .width(1.0),
+            ..base
+        },
+        checkbox::Status::Active { .. } => base,
+    }
+}
+
 // ============================================================================
 // SCROLLABLE STYLES
 // ============================================================================
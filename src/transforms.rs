@@ -0,0 +1,799 @@
+//! Transforms applied to decoded auras before import.
+//!
+//! A [`Transform`] changes one [`WeakAura`] in place: its own data and the
+//! data of its children. A [`TransformPipeline`] runs registered transforms in
+//! order, e.g. "strip all sounds", then "rescale by 0.8", then "rename IDs
+//! to `{name} [M+]`". Custom transforms implement the trait; the built-in ones
+//! are described by a [`TransformSpec`] so the GUI can store them in the
+//! config as [`TransformStep`]s.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::decoder::{LuaTable, LuaValue, WeakAura};
+
+/// A change applied to decoded auras before import
+pub trait Transform: Send + Sync {
+    /// Short label, e.g. "Rescale by 0.8"
+    fn name(&self) -> String;
+
+    /// Change one aura in place, including its children
+    fn apply(&self, aura: &mut WeakAura);
+
+    /// Whether the aura is imported at all, checked after [`apply`](Self::apply)
+    fn keeps(&self, _aura: &WeakAura) -> bool {
+        true
+    }
+}
+
+/// Transforms run in registration order
+#[derive(Clone, Default)]
+pub struct TransformPipeline {
+    transforms: Vec<Arc<dyn Transform>>,
+}
+
+impl fmt::Debug for TransformPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.transforms.iter().map(|t| t.name()))
+            .finish()
+    }
+}
+
+impl TransformPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pipeline of the enabled steps, in order
+    pub fn from_steps(steps: &[TransformStep]) -> Self {
+        let mut pipeline = Self::new();
+        for step in steps.iter().filter(|s| s.enabled) {
+            pipeline.register(step.spec.build());
+        }
+        pipeline
+    }
+
+    /// Append a transform
+    pub fn register(&mut self, transform: Arc<dyn Transform>) {
+        self.transforms.push(transform);
+    }
+
+    /// Names of the registered transforms, in order
+    pub fn names(&self) -> Vec<String> {
+        self.transforms.iter().map(|t| t.name()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    /// Run every transform on every aura and drop the auras a transform
+    /// excludes. Returns the number of dropped auras.
+    pub fn apply(&self, auras: &mut Vec<WeakAura>) -> usize {
+        let before = auras.len();
+        auras.retain_mut(|aura| {
+            self.transforms.iter().all(|transform| {
+                transform.apply(aura);
+                transform.keeps(aura)
+            })
+        });
+        before - auras.len()
+    }
+}
+
+/// A built-in transform and its parameters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransformSpec {
+    StripSounds(StripSounds),
+    Rescale(Rescale),
+    Reposition(Reposition),
+    RestrictLoad(RestrictLoad),
+    ExcludeRegionTypes(ExcludeRegionTypes),
+    #[serde(alias = "prefix_ids")]
+    RenameIds(RenameIds),
+}
+
+impl TransformSpec {
+    /// The built-in transforms with default parameters
+    pub fn library() -> Vec<TransformSpec> {
+        vec![
+            TransformSpec::StripSounds(StripSounds::default()),
+            TransformSpec::Rescale(Rescale { factor: 0.8 }),
+            TransformSpec::Reposition(Reposition::default()),
+            TransformSpec::RestrictLoad(RestrictLoad::default()),
+            TransformSpec::ExcludeRegionTypes(ExcludeRegionTypes::default()),
+            TransformSpec::RenameIds(RenameIds {
+                template: "[Guild] {name}".to_string(),
+            }),
+        ]
+    }
+
+    /// What the transform does, for the GUI
+    pub fn description(&self) -> &'static str {
+        match self {
+            TransformSpec::StripSounds(_) => {
+                "Remove sounds, chat announcements and text-to-speech from actions and conditions"
+            }
+            TransformSpec::Rescale(_) => "Scale size, offsets and font sizes",
+            TransformSpec::Reposition(_) => {
+                "Shift auras and groups, or anchor them to another frame"
+            }
+            TransformSpec::RestrictLoad(_) => "Load the auras only for one class or spec",
+            TransformSpec::ExcludeRegionTypes(_) => {
+                "Skip auras of these types, e.g. when their media is missing"
+            }
+            TransformSpec::RenameIds(_) => "Rename every aura ID, {name} being the original ID",
+        }
+    }
+
+    pub fn build(&self) -> Arc<dyn Transform> {
+        match self {
+            TransformSpec::StripSounds(t) => Arc::new(t.clone()),
+            TransformSpec::Rescale(t) => Arc::new(*t),
+            TransformSpec::Reposition(t) => Arc::new(t.clone()),
+            TransformSpec::RestrictLoad(t) => Arc::new(t.clone()),
+            TransformSpec::ExcludeRegionTypes(t) => Arc::new(t.clone()),
+            TransformSpec::RenameIds(t) => Arc::new(t.clone()),
+        }
+    }
+}
+
+impl fmt::Display for TransformSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.build().name())
+    }
+}
+
+/// A built-in transform as stored in the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransformStep {
+    #[serde(flatten)]
+    pub spec: TransformSpec,
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+impl TransformStep {
+    /// Every built-in transform, disabled
+    pub fn defaults() -> Vec<TransformStep> {
+        TransformSpec::library()
+            .into_iter()
+            .map(|spec| TransformStep {
+                spec,
+                enabled: false,
+            })
+            .collect()
+    }
+
+    /// Append the built-in transforms missing from `steps` (e.g. added in a
+    /// newer release than the config), disabled
+    pub fn add_missing_builtins(steps: &mut Vec<TransformStep>) {
+        for step in Self::defaults() {
+            let kind = std::mem::discriminant(&step.spec);
+            if !steps
+                .iter()
+                .any(|s| std::mem::discriminant(&s.spec) == kind)
+            {
+                steps.push(step);
+            }
+        }
+    }
+}
+
+/// Chat channels an action or condition can announce to
+const CHAT_MESSAGE_TYPES: &[&str] = &[
+    "SAY",
+    "YELL",
+    "PARTY",
+    "RAID",
+    "RAID_WARNING",
+    "INSTANCE_CHAT",
+    "SMARTRAID",
+    "GUILD",
+    "OFFICER",
+    "WHISPER",
+    "EMOTE",
+    "CHANNEL",
+];
+
+/// Sound channels kept sounds can be moved to
+pub const SOUND_CHANNELS: &[&str] = &["Master", "SFX", "Music", "Ambience", "Dialog"];
+
+/// Remove sounds, chat announcements and text-to-speech played by actions
+/// and conditions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StripSounds {
+    /// Remove sounds
+    pub sounds: bool,
+    /// Play the sounds that are kept on this channel instead
+    pub sound_channel: Option<String>,
+    /// Remove chat messages, including custom actions calling `SendChatMessage`
+    pub chat: bool,
+    /// Remove text-to-speech messages
+    pub tts: bool,
+}
+
+impl Default for StripSounds {
+    fn default() -> Self {
+        Self {
+            sounds: true,
+            sound_channel: None,
+            chat: true,
+            tts: true,
+        }
+    }
+}
+
+impl StripSounds {
+    /// Whether a message of this type is removed
+    fn strips_message(&self, message_type: &str) -> bool {
+        (self.chat && CHAT_MESSAGE_TYPES.contains(&message_type))
+            || (self.tts && message_type == "TTS")
+    }
+
+    /// Whether a condition change is removed
+    fn strips_change(&self, change: &LuaValue) -> bool {
+        let Some(change) = change.as_table() else {
+            return false;
+        };
+        match change.get("property") {
+            Some(LuaValue::String(p)) if p == "sound" => self.sounds,
+            Some(LuaValue::String(p)) if p == "chat" => matches!(
+                change.get("value").and_then(|v| v.as_table()).and_then(|v| v.get("message_type")),
+                Some(LuaValue::String(t)) if self.strips_message(t)
+            ),
+            _ => false,
+        }
+    }
+
+    fn strip_action(&self, action: &mut LuaTable) {
+        if self.sounds {
+            action.retain(|key, _| !key.starts_with("sound"));
+            if action.contains_key("do_sound") {
+                action.insert("do_sound".to_string(), LuaValue::Bool(false));
+            }
+        } else if let Some(channel) = &self.sound_channel {
+            if action.contains_key("do_sound") {
+                action.insert(
+                    "sound_channel".to_string(),
+                    LuaValue::String(channel.clone()),
+                );
+            }
+        }
+        if matches!(action.get("message_type"), Some(LuaValue::String(t)) if self.strips_message(t))
+        {
+            action.insert("do_message".to_string(), LuaValue::Bool(false));
+        }
+        if self.chat
+            && matches!(action.get("custom"), Some(LuaValue::String(code)) if code.contains("SendChatMessage"))
+        {
+            action.insert("do_custom".to_string(), LuaValue::Bool(false));
+        }
+    }
+}
+
+impl Transform for StripSounds {
+    fn name(&self) -> String {
+        let parts: Vec<&str> = [
+            (self.sounds, "sounds"),
+            (self.chat, "chat"),
+            (self.tts, "TTS"),
+        ]
+        .into_iter()
+        .filter_map(|(on, part)| on.then_some(part))
+        .collect();
+        let mut name = if parts.is_empty() {
+            "Strip nothing".to_string()
+        } else {
+            format!("Strip {}", parts.join(", "))
+        };
+        if let (false, Some(channel)) = (self.sounds, &self.sound_channel) {
+            name.push_str(&format!(" (sounds on {})", channel));
+        }
+        name
+    }
+
+    fn apply(&self, aura: &mut WeakAura) {
+        for_each_table(aura, |table| {
+            if let Some(actions) = table.get_mut("actions").and_then(LuaValue::as_table_mut) {
+                for phase in ["start", "finish"] {
+                    if let Some(action) = actions.get_mut(phase).and_then(LuaValue::as_table_mut) {
+                        self.strip_action(action);
+                    }
+                }
+            }
+            if let Some(conditions) = table.get_mut("conditions") {
+                for condition in array_mut(conditions) {
+                    let Some(changes) = condition.as_table_mut().and_then(|c| c.get_mut("changes"))
+                    else {
+                        continue;
+                    };
+                    retain_array(changes, |change| !self.strips_change(change));
+                    let Some(channel) = self.sound_channel.as_ref().filter(|_| !self.sounds) else {
+                        continue;
+                    };
+                    for change in array_mut(changes) {
+                        let Some(change) = change.as_table_mut() else {
+                            continue;
+                        };
+                        if !matches!(change.get("property"), Some(LuaValue::String(p)) if p == "sound")
+                        {
+                            continue;
+                        }
+                        if let Some(value) =
+                            change.get_mut("value").and_then(LuaValue::as_table_mut)
+                        {
+                            value.insert(
+                                "sound_channel".to_string(),
+                                LuaValue::String(channel.clone()),
+                            );
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Fields scaled on every aura
+const SCALED_FIELDS: &[&str] = &["width", "height", "xOffset", "yOffset", "space", "radius"];
+
+/// Font size fields, scaled and rounded to whole points
+const FONT_SIZE_FIELDS: &[&str] = &["fontSize", "text_fontSize"];
+
+/// Offsets of text sub-regions
+const SCALED_SUB_REGION_FIELDS: &[&str] = &["text_anchorXOffset", "text_anchorYOffset"];
+
+/// Scale sizes, offsets and font sizes by a factor
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rescale {
+    pub factor: f64,
+}
+
+impl Transform for Rescale {
+    fn name(&self) -> String {
+        format!("Rescale by {}", self.factor)
+    }
+
+    fn apply(&self, aura: &mut WeakAura) {
+        let factor = self.factor;
+        for_each_table(aura, |table| {
+            scale_fields(table, SCALED_FIELDS, factor, false);
+            scale_fields(table, FONT_SIZE_FIELDS, factor, true);
+            if let Some(sub_regions) = table.get_mut("subRegions") {
+                for sub_region in array_mut(sub_regions) {
+                    if let Some(sub_table) = sub_region.as_table_mut() {
+                        scale_fields(sub_table, SCALED_SUB_REGION_FIELDS, factor, false);
+                        scale_fields(sub_table, FONT_SIZE_FIELDS, factor, true);
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Shift auras by an offset and optionally anchor them to another frame.
+/// Only the imported aura itself changes: children of a group are
+/// positioned relative to it and move along.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Reposition {
+    pub x_offset: f64,
+    pub y_offset: f64,
+    /// Name of the frame to anchor to, e.g. `ElvUF_Player`
+    pub anchor_frame: Option<String>,
+}
+
+impl Transform for Reposition {
+    fn name(&self) -> String {
+        let mut name = format!("Move by ({}, {})", self.x_offset, self.y_offset);
+        if let Some(frame) = &self.anchor_frame {
+            name.push_str(&format!(" and anchor to {}", frame));
+        }
+        name
+    }
+
+    fn apply(&self, aura: &mut WeakAura) {
+        let Some(table) = aura.data.as_table_mut() else {
+            return;
+        };
+        for (field, delta) in [("xOffset", self.x_offset), ("yOffset", self.y_offset)] {
+            if delta == 0.0 {
+                continue;
+            }
+            let current = match table.get(field) {
+                Some(LuaValue::Number(n)) => *n,
+                _ => 0.0,
+            };
+            table.insert(field.to_string(), LuaValue::Number(current + delta));
+        }
+        if let Some(frame) = &self.anchor_frame {
+            table.insert(
+                "anchorFrameType".to_string(),
+                LuaValue::String("SELECTFRAME".to_string()),
+            );
+            table.insert(
+                "anchorFrameFrame".to_string(),
+                LuaValue::String(frame.clone()),
+            );
+        }
+    }
+}
+
+/// A playable class and its specializations, in game order
+#[derive(Debug, Clone, Copy)]
+pub struct ClassInfo {
+    /// Class file token, e.g. `DEATHKNIGHT`
+    pub token: &'static str,
+    pub name: &'static str,
+    /// Specialization IDs and names
+    pub specs: &'static [(u32, &'static str)],
+}
+
+/// Playable classes, as used by the load options
+pub const CLASSES: &[ClassInfo] = &[
+    ClassInfo {
+        token: "DEATHKNIGHT",
+        name: "Death Knight",
+        specs: &[(250, "Blood"), (251, "Frost"), (252, "Unholy")],
+    },
+    ClassInfo {
+        token: "DEMONHUNTER",
+        name: "Demon Hunter",
+        specs: &[(577, "Havoc"), (581, "Vengeance")],
+    },
+    ClassInfo {
+        token: "DRUID",
+        name: "Druid",
+        specs: &[
+            (102, "Balance"),
+            (103, "Feral"),
+            (104, "Guardian"),
+            (105, "Restoration"),
+        ],
+    },
+    ClassInfo {
+        token: "EVOKER",
+        name: "Evoker",
+        specs: &[
+            (1467, "Devastation"),
+            (1468, "Preservation"),
+            (1473, "Augmentation"),
+        ],
+    },
+    ClassInfo {
+        token: "HUNTER",
+        name: "Hunter",
+        specs: &[
+            (253, "Beast Mastery"),
+            (254, "Marksmanship"),
+            (255, "Survival"),
+        ],
+    },
+    ClassInfo {
+        token: "MAGE",
+        name: "Mage",
+        specs: &[(62, "Arcane"), (63, "Fire"), (64, "Frost")],
+    },
+    ClassInfo {
+        token: "MONK",
+        name: "Monk",
+        specs: &[
+            (268, "Brewmaster"),
+            (270, "Mistweaver"),
+            (269, "Windwalker"),
+        ],
+    },
+    ClassInfo {
+        token: "PALADIN",
+        name: "Paladin",
+        specs: &[(65, "Holy"), (66, "Protection"), (70, "Retribution")],
+    },
+    ClassInfo {
+        token: "PRIEST",
+        name: "Priest",
+        specs: &[(256, "Discipline"), (257, "Holy"), (258, "Shadow")],
+    },
+    ClassInfo {
+        token: "ROGUE",
+        name: "Rogue",
+        specs: &[(259, "Assassination"), (260, "Outlaw"), (261, "Subtlety")],
+    },
+    ClassInfo {
+        token: "SHAMAN",
+        name: "Shaman",
+        specs: &[
+            (262, "Elemental"),
+            (263, "Enhancement"),
+            (264, "Restoration"),
+        ],
+    },
+    ClassInfo {
+        token: "WARLOCK",
+        name: "Warlock",
+        specs: &[
+            (265, "Affliction"),
+            (266, "Demonology"),
+            (267, "Destruction"),
+        ],
+    },
+    ClassInfo {
+        token: "WARRIOR",
+        name: "Warrior",
+        specs: &[(71, "Arms"), (72, "Fury"), (73, "Protection")],
+    },
+];
+
+/// Class with the given token
+pub fn class_info(token: &str) -> Option<&'static ClassInfo> {
+    CLASSES.iter().find(|c| c.token == token)
+}
+
+/// Replace the class and spec load options of every aura (not groups,
+/// which load with their children). Without a class nothing changes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestrictLoad {
+    /// Class token, e.g. `WARRIOR`
+    pub class: Option<String>,
+    /// Specialization ID of that class, e.g. `71` for Arms
+    pub spec: Option<u32>,
+}
+
+impl RestrictLoad {
+    /// Chosen class and spec, ignoring a spec of another class
+    fn resolved(&self) -> Option<(&'static ClassInfo, Option<(u32, &'static str)>)> {
+        let class = class_info(self.class.as_deref()?)?;
+        let spec = self
+            .spec
+            .and_then(|id| class.specs.iter().find(|(s, _)| *s == id).copied());
+        Some((class, spec))
+    }
+}
+
+impl Transform for RestrictLoad {
+    fn name(&self) -> String {
+        match self.resolved() {
+            Some((class, Some((_, spec)))) => format!("Load only as {} {}", spec, class.name),
+            Some((class, None)) => format!("Load only as {}", class.name),
+            None => "Restrict load (no class chosen)".to_string(),
+        }
+    }
+
+    fn apply(&self, aura: &mut WeakAura) {
+        let Some((class, spec)) = self.resolved() else {
+            return;
+        };
+        let single = |value: LuaValue| {
+            LuaValue::Table(Arc::new(
+                std::iter::once(("single".to_string(), value)).collect(),
+            ))
+        };
+        for value in std::iter::once(&mut aura.data).chain(aura.child_data.iter_mut()) {
+            let is_group = matches!(
+                value.get_path("regionType").and_then(LuaValue::as_str),
+                Some("group" | "dynamicgroup")
+            );
+            // `use_*` is true for a single choice, false for multiple and nil when unused
+            if is_group || !value.set_path("load.use_class", LuaValue::Bool(true)) {
+                continue;
+            }
+            value.set_path(
+                "load.class",
+                single(LuaValue::String(class.token.to_string())),
+            );
+            if let Some((id, _)) = spec {
+                value.set_path("load.use_class_and_spec", LuaValue::Bool(true));
+                value.set_path("load.class_and_spec", single(LuaValue::Number(id as f64)));
+            }
+            let Some(load) = value.get_path_mut("load").and_then(LuaValue::as_table_mut) else {
+                continue;
+            };
+            if spec.is_none() {
+                load.shift_remove("use_class_and_spec");
+            }
+            // An older spec option of the pack would still restrict loading
+            load.shift_remove("use_spec");
+        }
+    }
+}
+
+/// Region types of auras that are not groups
+pub const REGION_TYPES: &[&str] = &[
+    "icon",
+    "aurabar",
+    "text",
+    "texture",
+    "progresstexture",
+    "model",
+    "stopmotion",
+    "empty",
+];
+
+/// Skip auras of the given region types. Excluded children are removed
+/// from their group together with their descendants; an excluded top-level
+/// aura is not imported.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExcludeRegionTypes {
+    pub region_types: Vec<String>,
+}
+
+impl ExcludeRegionTypes {
+    fn excludes(&self, value: &LuaValue) -> bool {
+        matches!(
+            value.as_table().and_then(|t| t.get("regionType")),
+            Some(LuaValue::String(t)) if self.region_types.contains(t)
+        )
+    }
+}
+
+impl Transform for ExcludeRegionTypes {
+    fn name(&self) -> String {
+        if self.region_types.is_empty() {
+            "Skip region types (none chosen)".to_string()
+        } else {
+            format!("Skip {} auras", self.region_types.join(", "))
+        }
+    }
+
+    fn apply(&self, aura: &mut WeakAura) {
+        let excluded: HashSet<String> = aura
+            .child_data
+            .iter()
+            .filter(|child| self.excludes(child))
+            .filter_map(|child| match child.as_table().and_then(|t| t.get("id")) {
+                Some(LuaValue::String(id)) => Some(id.clone()),
+                _ => None,
+            })
+            .collect();
+        aura.remove_children(&excluded);
+    }
+
+    fn keeps(&self, aura: &WeakAura) -> bool {
+        !self.excludes(&aura.data)
+    }
+}
+
+/// Placeholder for the original ID in a rename template
+pub const NAME_PLACEHOLDER: &str = "{name}";
+
+/// Rename the aura and all its children with a template such as
+/// `{name} [M+]`, keeping the group structure and anchors between its auras
+/// intact. The first `{name}` is replaced by the original ID; a template
+/// without it is used as a prefix. IDs that already match the template are
+/// kept, so applying it twice renames once.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenameIds {
+    /// Config files from before templates stored a plain prefix
+    #[serde(alias = "prefix")]
+    pub template: String,
+}
+
+impl RenameIds {
+    /// Text before and after the original ID
+    fn affixes(&self) -> (&str, &str) {
+        match self.template.find(NAME_PLACEHOLDER) {
+            Some(i) => (
+                &self.template[..i],
+                &self.template[i + NAME_PLACEHOLDER.len()..],
+            ),
+            None => (&self.template, ""),
+        }
+    }
+
+    /// New ID for `id`
+    pub fn rename(&self, id: &str) -> String {
+        let (prefix, suffix) = self.affixes();
+        let applied = id.len() >= prefix.len() + suffix.len()
+            && id.starts_with(prefix)
+            && id.ends_with(suffix);
+        if applied {
+            id.to_string()
+        } else {
+            format!("{}{}{}", prefix, id, suffix)
+        }
+    }
+}
+
+impl Transform for RenameIds {
+    fn name(&self) -> String {
+        format!("Rename IDs to \"{}\"", self.template)
+    }
+
+    fn apply(&self, aura: &mut WeakAura) {
+        let mut renamed: HashMap<String, String> = HashMap::new();
+        renamed.insert(aura.id.clone(), self.rename(&aura.id));
+        for child in &aura.child_data {
+            if let Some(LuaValue::String(id)) = child.as_table().and_then(|t| t.get("id")) {
+                renamed.insert(id.clone(), self.rename(id));
+            }
+        }
+        let map = |id: &str| renamed.get(id).cloned();
+
+        for_each_table(aura, |table| {
+            for key in ["id", "parent"] {
+                if let Some(LuaValue::String(id)) = table.get_mut(key) {
+                    if let Some(new_id) = map(id) {
+                        *id = new_id;
+                    }
+                }
+            }
+            if let Some(children) = table.get_mut("controlledChildren") {
+                for child in array_mut(children) {
+                    if let LuaValue::String(id) = child {
+                        if let Some(new_id) = map(id) {
+                            *id = new_id;
+                        }
+                    }
+                }
+            }
+            // Auras anchored to another aura name its frame by ID
+            if let Some(LuaValue::String(frame)) = table.get_mut("anchorFrameFrame") {
+                let new_frame = frame
+                    .strip_prefix("WeakAuras:")
+                    .and_then(map)
+                    .map(|new_id| format!("WeakAuras:{}", new_id));
+                if let Some(new_frame) = new_frame {
+                    *frame = new_frame;
+                }
+            }
+            // Dynamic groups key their sort order by child ID
+            if let Some(sort) = table
+                .get_mut("sortHybridTable")
+                .and_then(LuaValue::as_table_mut)
+            {
+                *sort = sort
+                    .drain(..)
+                    .map(|(id, v)| (map(&id).unwrap_or(id), v))
+                    .collect();
+            }
+        });
+
+        aura.id = self.rename(&aura.id);
+        for child in &mut aura.children {
+            if let Some(new_id) = map(child) {
+                *child = new_id;
+            }
+        }
+    }
+}
+
+/// Run `f` on the table of the aura and of each child
+fn for_each_table(aura: &mut WeakAura, mut f: impl FnMut(&mut LuaTable)) {
+    for value in std::iter::once(&mut aura.data).chain(aura.child_data.iter_mut()) {
+        if let Some(table) = value.as_table_mut() {
+            f(table);
+        }
+    }
+}
+
+/// Array part of a value, empty for anything else
+fn array_mut(value: &mut LuaValue) -> &mut [LuaValue] {
+    match value {
+        LuaValue::Array(array) | LuaValue::MixedTable { array, .. } => array,
+        _ => &mut [],
+    }
+}
+
+/// Keep the array elements matching `keep`
+fn retain_array(value: &mut LuaValue, keep: impl FnMut(&LuaValue) -> bool) {
+    if let LuaValue::Array(array) | LuaValue::MixedTable { array, .. } = value {
+        array.retain(keep);
+    }
+}
+
+fn scale_fields(table: &mut LuaTable, fields: &[&str], factor: f64, round: bool) {
+    for field in fields {
+        if let Some(LuaValue::Number(n)) = table.get_mut(*field) {
+            let scaled = *n * factor;
+            *n = if round { scaled.round() } else { scaled };
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Cache of parsed aura trees for discovered SavedVariables files.
+//!
+//! Discovered files are parsed in the background so switching between accounts
+//! is instant. An entry is only used while the file keeps the modification time
+//! it was parsed at.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::error::{Result, WeakAuraError};
+use crate::saved_variables::{AuraTreeNode, SavedVariablesInfo, SavedVariablesManager};
+
+/// Aura tree of one SavedVariables file
+#[derive(Debug, Clone)]
+pub struct ParsedTree {
+    /// Path of the SavedVariables file
+    pub path: PathBuf,
+    /// Modification time of the file when it was parsed
+    pub modified: SystemTime,
+    /// Auras organized as groups with children
+    pub tree: Vec<AuraTreeNode>,
+    /// Total number of auras in the tree
+    pub count: usize,
+    /// `dbVersion` of the file
+    pub db_version: Option<u32>,
+}
+
+impl ParsedTree {
+    /// Parse a SavedVariables file into its aura tree
+    pub fn parse(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(WeakAuraError::FileNotFound(
+                path.to_string_lossy().to_string(),
+            ));
+        }
+        // Taken before reading, so a write during parsing invalidates the entry
+        let modified = fs::metadata(path)?.modified()?;
+        let mut manager = SavedVariablesManager::new(path.to_path_buf());
+        manager.load()?;
+        let tree = manager.get_aura_tree();
+        let count = tree.iter().map(|node| node.total_count()).sum();
+        Ok(Self {
+            path: path.to_path_buf(),
+            modified,
+            tree,
+            count,
+            db_version: manager.db_version(),
+        })
+    }
+
+    /// Whether the file still has the modification time it was parsed at
+    pub fn is_current(&self) -> bool {
+        fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified == self.modified)
+    }
+
+    /// Fill in the parsed details of a discovered file
+    pub fn describe(&self, info: &mut SavedVariablesInfo) {
+        info.aura_count = Some(self.count);
+        info.db_version = self.db_version;
+        info.modified = Some(self.modified);
+        if let Ok(metadata) = fs::metadata(&self.path) {
+            info.size = metadata.len();
+        }
+    }
+}
+
+/// Parsed aura trees by file path
+#[derive(Debug, Default)]
+pub struct AuraTreeCache {
+    entries: HashMap<PathBuf, ParsedTree>,
+}
+
+impl AuraTreeCache {
+    /// Cached tree of a file, unless the file changed since it was parsed
+    pub fn get(&self, path: &Path) -> Option<&ParsedTree> {
+        self.entries.get(path).filter(|parsed| parsed.is_current())
+    }
+
+    /// Store a parsed tree, replacing any older entry for the same file
+    pub fn insert(&mut self, parsed: ParsedTree) {
+        self.entries.insert(parsed.path.clone(), parsed);
+    }
+}
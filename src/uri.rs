@@ -0,0 +1,236 @@
+//! `weakaura://` deep links.
+//!
+//! Links of the form `weakaura://import?string=<percent-encoded string>` (or
+//! `weakaura://import/<string>`) open the app with the string preloaded. The
+//! scheme is registered per user with `--register-uri`. If an instance is
+//! already running, the link is forwarded to it over a loopback socket and the
+//! new process exits (single-instance behaviour).
+//!
+//! The running instance writes a fresh token to the config directory when it
+//! starts listening. A forwarding launch sends one line, the token and the
+//! link separated by a space; the instance only accepts lines with its token
+//! and a valid `weakaura://import` link, so other programs and web pages
+//! cannot feed it anything else.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+
+use tracing::warn;
+
+use crate::config::AppConfig;
+use crate::error::{Result, WeakAuraError};
+use crate::util;
+
+/// URI scheme handled by the app
+pub const SCHEME: &str = "weakaura";
+
+/// Loopback address a running instance listens on for forwarded links
+pub const INSTANCE_ADDR: &str = "127.0.0.1:47823";
+
+/// File in the config directory holding the running instance's token
+const INSTANCE_TOKEN_FILE: &str = "instance.token";
+
+/// Largest accepted handoff line (links of big packs run to a few MB)
+const MAX_HANDOFF_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Read and write timeout of a handoff connection
+const HANDOFF_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Reply of an instance that accepted a link
+const ACCEPTED: &str = "OK";
+
+/// Extract the import string from a `weakaura://import` link
+pub fn parse_import_uri(uri: &str) -> Option<String> {
+    let rest = uri.trim().strip_prefix(SCHEME)?.strip_prefix("://")?;
+    let encoded = if let Some(query) = rest.strip_prefix("import?") {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("string="))?
+    } else {
+        rest.strip_prefix("import/")?
+    };
+    let decoded = percent_decode(encoded)?;
+    let decoded = decoded.trim();
+    (!decoded.is_empty()).then(|| decoded.to_string())
+}
+
+/// Decode `%XX` escapes. `+` is kept as is since legacy strings use it.
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = input.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Listener of a running instance for links forwarded by later launches
+pub struct InstanceListener {
+    listener: TcpListener,
+    token: String,
+}
+
+impl InstanceListener {
+    /// Listen on [`INSTANCE_ADDR`] with a fresh token, written to the config
+    /// directory for later launches. Fails if another instance is listening.
+    pub fn bind() -> Result<Self> {
+        let listener = Self::new(TcpListener::bind(INSTANCE_ADDR)?, util::random_token());
+        AppConfig::write_token(INSTANCE_TOKEN_FILE, &listener.token)?;
+        Ok(listener)
+    }
+
+    /// Accept links sent with `token` on a bound listener
+    pub fn new(listener: TcpListener, token: String) -> Self {
+        Self { listener, token }
+    }
+
+    /// Serve forwarded links until the process exits, calling `on_received`
+    /// with the import string of every accepted link
+    pub fn run(self, mut on_received: impl FnMut(String)) {
+        for stream in self.listener.incoming() {
+            let result = stream.and_then(|stream| {
+                handle_handoff(stream, &self.token).map(|text| text.map(&mut on_received))
+            });
+            if let Err(e) = result {
+                warn!(error = %e, "Forwarded link failed");
+            }
+        }
+    }
+}
+
+/// Read one handoff line and answer it; the import string if it was accepted
+fn handle_handoff(stream: TcpStream, token: &str) -> std::io::Result<Option<String>> {
+    stream.set_read_timeout(Some(HANDOFF_TIMEOUT))?;
+    stream.set_write_timeout(Some(HANDOFF_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut line = String::new();
+    BufReader::new(stream.take(MAX_HANDOFF_BYTES)).read_line(&mut line)?;
+
+    let text = line
+        .trim_end()
+        .split_once(' ')
+        .filter(|(sent, _)| *sent == token)
+        .and_then(|(_, link)| parse_import_uri(link));
+    if text.is_some() {
+        writeln!(writer, "{}", ACCEPTED)?;
+    } else {
+        warn!("Refused a forwarded link without a valid token");
+    }
+    Ok(text)
+}
+
+/// Hand a `weakaura://` link to an already running instance.
+/// Returns `false` if no instance accepted it.
+pub fn forward_to_running_instance(link: &str) -> bool {
+    let Some(token) = AppConfig::read_token(INSTANCE_TOKEN_FILE) else {
+        return false;
+    };
+    INSTANCE_ADDR
+        .parse()
+        .is_ok_and(|addr| send_link(addr, &token, link))
+}
+
+/// Send a link with `token` to the instance listening on `addr`. Returns
+/// `false` if it did not accept it.
+pub fn send_link(addr: SocketAddr, token: &str, link: &str) -> bool {
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, Duration::from_millis(500)) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(HANDOFF_TIMEOUT));
+    if writeln!(stream, "{} {}", token, link.trim()).is_err() {
+        return false;
+    }
+    let mut reply = String::new();
+    let _ = BufReader::new(stream).read_line(&mut reply);
+    reply.trim_end() == ACCEPTED
+}
+
+/// Register this executable as the handler of `weakaura://` links for the current user
+pub fn register() -> Result<()> {
+    let exe = std::env::current_exe()?;
+    register_for(&exe)
+}
+
+#[cfg(target_os = "windows")]
+fn register_for(exe: &std::path::Path) -> Result<()> {
+    let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+    let command = format!("\"{}\" \"%1\"", exe.display());
+    run(
+        "reg",
+        &["add", &key, "/ve", "/d", "URL:WeakAura Import", "/f"],
+    )?;
+    run("reg", &["add", &key, "/v", "URL Protocol", "/d", "", "/f"])?;
+    run(
+        "reg",
+        &[
+            "add",
+            &format!(r"{}\shell\open\command", key),
+            "/ve",
+            "/d",
+            &command,
+            "/f",
+        ],
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn register_for(_exe: &std::path::Path) -> Result<()> {
+    Err(WeakAuraError::ConfigError(format!(
+        "On macOS the {}:// scheme is declared by CFBundleURLTypes in the app bundle's Info.plist",
+        SCHEME
+    )))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn register_for(exe: &std::path::Path) -> Result<()> {
+    const DESKTOP_FILE: &str = "weakauras-mass-import-uri.desktop";
+
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(std::path::PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| std::path::PathBuf::from(home).join(".local/share"))
+        })
+        .ok_or_else(|| WeakAuraError::ConfigError("No data directory available".to_string()))?;
+    let applications = data_home.join("applications");
+    std::fs::create_dir_all(&applications)?;
+    std::fs::write(
+        applications.join(DESKTOP_FILE),
+        format!(
+            "[Desktop Entry]\nType=Application\nName=WeakAuras Mass Importer\n\
+             Exec=\"{}\" %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+            exe.display(),
+            SCHEME
+        ),
+    )?;
+    run(
+        "xdg-mime",
+        &[
+            "default",
+            DESKTOP_FILE,
+            &format!("x-scheme-handler/{}", SCHEME),
+        ],
+    )
+}
+
+#[cfg(not(target_os = "macos"))]
+fn run(program: &str, args: &[&str]) -> Result<()> {
+    let status = std::process::Command::new(program).args(args).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(WeakAuraError::ConfigError(format!(
+            "{} exited with {}",
+            program, status
+        )))
+    }
+}
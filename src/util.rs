@@ -1,7 +1,9 @@
 //! Shared utility functions for the WeakAura importer
 
-use crate::decoder::{LuaValue, WeakAura};
+use crate::decoder::{LuaTable, LuaValue, WeakAura};
+use crate::error::{Result, WeakAuraError};
 use std::collections::HashMap;
+use std::net::{SocketAddr, ToSocketAddrs};
 
 /// Escape special characters in Lua strings
 pub fn escape_lua_string(s: &str) -> String {
@@ -20,7 +22,7 @@ pub struct ChildrenHierarchy {
     /// Map of parent_id -> ordered list of direct child IDs
     pub children_by_parent: HashMap<String, Vec<String>>,
     /// Map of child_id -> prepared child LuaValue (with parent field set)
-    pub prepared_children: HashMap<String, LuaValue>,
+    pub prepared_children: LuaTable,
 }
 
 /// Build a children hierarchy from a WeakAura's flat child_data list.
@@ -30,33 +32,28 @@ pub struct ChildrenHierarchy {
 /// and sets `controlledChildren` on subgroups.
 pub fn build_children_hierarchy(aura: &WeakAura) -> ChildrenHierarchy {
     let mut children_by_parent: HashMap<String, Vec<String>> = HashMap::new();
-    let mut prepared_children: HashMap<String, LuaValue> = HashMap::new();
+    let mut prepared_children = LuaTable::new();
 
     // First pass: extract parent->child relationships and prepare child data
     for child_data in &aura.child_data {
         if let Some(child_table) = child_data.as_table() {
             if let Some(LuaValue::String(child_id)) = child_table.get("id") {
                 // Determine the parent: use existing parent field, fall back to root aura ID
-                let parent_id = child_table
-                    .get("parent")
-                    .and_then(|v| {
-                        if let LuaValue::String(s) = v {
-                            Some(s.clone())
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap_or_else(|| aura.id.clone());
+                let parent_id = child_data
+                    .get_path("parent")
+                    .and_then(LuaValue::as_str)
+                    .map_or_else(|| aura.id.clone(), str::to_string);
 
                 children_by_parent
                     .entry(parent_id)
                     .or_default()
                     .push(child_id.clone());
 
-                // Prepare child data, ensuring parent field is set
+                // Prepare child data, ensuring parent field is set (the clone
+                // shares the table until it is modified)
                 let mut child_value = child_data.clone();
-                if let Some(child_table_mut) = child_value.as_table_mut() {
-                    if !child_table_mut.contains_key("parent") {
+                if !child_table.contains_key("parent") {
+                    if let Some(child_table_mut) = child_value.as_table_mut() {
                         child_table_mut
                             .insert("parent".to_string(), LuaValue::String(aura.id.clone()));
                     }
@@ -95,3 +92,100 @@ pub fn set_controlled_children(data: &mut LuaValue, child_ids: &[String]) {
         table.insert("controlledChildren".to_string(), controlled_children);
     }
 }
+
+/// Characters WeakAuras builds uids from
+const UID_CHARS: &[u8; 64] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789()";
+
+/// A fresh random uid in WeakAuras' format: 11 characters of its base64
+/// alphabet
+pub fn generate_uid() -> String {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hash, Hasher};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    // RandomState is seeded randomly; the counter keeps calls distinct
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let state = RandomState::new();
+    let random = |salt: u64| {
+        let mut hasher = state.build_hasher();
+        (COUNTER.fetch_add(1, Ordering::Relaxed), salt).hash(&mut hasher);
+        std::time::SystemTime::now().hash(&mut hasher);
+        hasher.finish()
+    };
+    let (high, low) = (random(0), random(1));
+    (0..11)
+        .map(|i| {
+            // 66 bits: ten characters from one hash, the last from the other
+            let bits = if i < 10 { high >> (i * 6) } else { low };
+            UID_CHARS[(bits & 63) as usize] as char
+        })
+        .collect()
+}
+
+/// A fresh random access token for local endpoints (33 characters)
+pub fn random_token() -> String {
+    (0..3).map(|_| generate_uid()).collect()
+}
+
+/// Quote a CSV field when it contains separators, quotes, or newlines
+pub fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Rows of a CSV document: comma-separated fields, double quotes around
+/// fields holding commas, quotes or line breaks, `""` for a quote inside
+/// them. Blank lines are skipped.
+pub fn parse_csv(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                let done = std::mem::take(&mut row);
+                if !(done.len() == 1 && done[0].trim().is_empty()) {
+                    rows.push(done);
+                }
+            }
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(crate::error::WeakAuraError::InvalidValue(
+            "CSV ends inside a quoted field".to_string(),
+        ));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Resolve a listen address, refusing any that is not loopback. The local
+/// servers have no authentication of their own against other machines.
+pub fn loopback_addrs(addr: impl ToSocketAddrs) -> Result<Vec<SocketAddr>> {
+    let addrs: Vec<_> = addr.to_socket_addrs()?.collect();
+    if let Some(public) = addrs.iter().find(|addr| !addr.ip().is_loopback()) {
+        return Err(WeakAuraError::ConfigError(format!(
+            "Refusing to listen on {}: only loopback addresses can be served",
+            public
+        )));
+    }
+    Ok(addrs)
+}
@@ -0,0 +1,69 @@
+//! wasm-bindgen wrappers for web preview tools (enabled with the `wasm` feature).
+//!
+//! Build for the browser with
+//! `cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib`
+//! followed by `wasm-bindgen --target web`. Results are returned as JSON strings
+//! so the page can `JSON.parse` them without extra glue.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::decoder::{WeakAura, WeakAuraDecoder};
+use crate::lua_parser::LuaParser;
+
+/// Validation entry for one import string
+#[derive(Serialize)]
+struct Validation {
+    is_valid: bool,
+    aura_id: Option<String>,
+    is_group: bool,
+    child_count: usize,
+    error: Option<String>,
+}
+
+impl From<crate::error::Result<WeakAura>> for Validation {
+    fn from(result: crate::error::Result<WeakAura>) -> Self {
+        match result {
+            Ok(aura) => Self {
+                is_valid: true,
+                aura_id: Some(aura.id),
+                is_group: aura.is_group,
+                child_count: aura.children.len(),
+                error: None,
+            },
+            Err(e) => Self {
+                is_valid: false,
+                aura_id: None,
+                is_group: false,
+                child_count: 0,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+/// Validate every import string in `input` (one per line).
+/// Returns a JSON array of `{is_valid, aura_id, is_group, child_count, error}`.
+#[wasm_bindgen]
+pub fn validate(input: &str) -> String {
+    let entries: Vec<Validation> = WeakAuraDecoder::decode_multiple(input)
+        .into_iter()
+        .map(Validation::from)
+        .collect();
+    serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Decode one import string into the aura as JSON
+#[wasm_bindgen]
+pub fn decode(import_string: &str) -> Result<String, JsError> {
+    let aura = WeakAuraDecoder::decode(import_string)?;
+    Ok(serde_json::to_string(&aura)?)
+}
+
+/// Decode one import string and render its aura data as a Lua table, as it
+/// would appear in `WeakAuras.lua`
+#[wasm_bindgen]
+pub fn to_lua(import_string: &str) -> Result<String, JsError> {
+    let aura = WeakAuraDecoder::decode(import_string)?;
+    Ok(LuaParser::serialize(&aura.data, 0))
+}
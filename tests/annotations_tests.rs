@@ -0,0 +1,82 @@
+//! Tests for the aura tags and notes sidecar store.
+
+use std::path::PathBuf;
+
+use weakauras_mass_import::annotations::AnnotationStore;
+use weakauras_mass_import::config;
+
+#[test]
+fn test_annotations_add_remove_tag() {
+    let mut store = AnnotationStore::default();
+
+    assert!(store.add_tag("uid1", " raid "));
+    assert!(!store.add_tag("uid1", "raid"));
+    assert!(!store.add_tag("uid1", "   "));
+    assert!(store.add_tag("uid1", "needs update"));
+    assert_eq!(store.get("uid1").unwrap().tags.len(), 2);
+
+    assert!(store.remove_tag("uid1", "raid"));
+    assert!(!store.remove_tag("uid1", "raid"));
+    assert!(store.remove_tag("uid1", "needs update"));
+    // Entries without tags or note are pruned
+    assert!(store.get("uid1").is_none());
+}
+
+#[test]
+fn test_annotations_note_and_pruning() {
+    let mut store = AnnotationStore::default();
+    store.set_note("uid1", "Borrowed from guild");
+    assert_eq!(store.get("uid1").unwrap().note, "Borrowed from guild");
+
+    store.set_note("uid1", "  ");
+    assert!(store.get("uid1").is_none());
+}
+
+#[test]
+fn test_annotations_matches_filter() {
+    let mut store = AnnotationStore::default();
+    store.add_tag("uid1", "PvP");
+    store.set_note("uid2", "Tank cooldowns for Mythic+");
+
+    assert!(store.matches(Some("uid1"), ""));
+    assert!(store.matches(None, "  "));
+    assert!(store.matches(Some("uid1"), "pvp"));
+    assert!(store.matches(Some("uid2"), "mythic"));
+    assert!(!store.matches(Some("uid1"), "raid"));
+    assert!(!store.matches(None, "pvp"));
+    assert!(!store.matches(Some("unknown"), "pvp"));
+}
+
+#[test]
+fn test_annotations_all_tags_sorted_unique() {
+    let mut store = AnnotationStore::default();
+    store.add_tag("uid1", "raid");
+    store.add_tag("uid2", "pvp");
+    store.add_tag("uid2", "raid");
+
+    let tags: Vec<String> = store.all_tags().into_iter().collect();
+    assert_eq!(tags, vec!["pvp".to_string(), "raid".to_string()]);
+}
+
+#[test]
+fn test_annotations_round_trip() {
+    let path: PathBuf = std::env::temp_dir()
+        .join("wa_annotations_tests")
+        .join("annotations.json");
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(
+        config::load_json_from::<AnnotationStore>(&path).unwrap(),
+        AnnotationStore::default()
+    );
+
+    let mut store = AnnotationStore::default();
+    store.add_tag("uid1", "raid");
+    store.set_note("uid1", "Check after patch");
+    config::save_json_to(&path, &store).unwrap();
+
+    assert_eq!(
+        config::load_json_from::<AnnotationStore>(&path).unwrap(),
+        store
+    );
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,70 @@
+//! Tests for the public API facade.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use weakauras_mass_import::api::{Importer, LuaValue, ResolutionPolicy, SavedVariablesManager};
+
+use common::aura;
+
+fn temp_sv_path(name: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join("wa_api_tests")
+        .join(name)
+        .join("WeakAuras.lua")
+}
+
+fn load_of(path: &Path, id: &str) -> LuaValue {
+    let mut manager = SavedVariablesManager::new(path.to_path_buf());
+    manager.load().unwrap();
+    manager.displays[id].as_table().unwrap()["load"].clone()
+}
+
+#[test]
+fn test_importer_creates_and_updates_file() {
+    let path = temp_sv_path("create");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let result = Importer::new(&path)
+        .add_auras([aura("First", "old")])
+        .run()
+        .unwrap();
+    assert_eq!(result.added, vec!["First".to_string()]);
+    assert_eq!(load_of(&path, "First"), LuaValue::String("old".into()));
+
+    // Existing aura is kept by the policy
+    let result = Importer::new(&path)
+        .add_auras([aura("First", "new"), aura("Second", "new")])
+        .policy(ResolutionPolicy::SkipAll)
+        .run()
+        .unwrap();
+    assert_eq!(result.skipped, vec!["First".to_string()]);
+    assert_eq!(load_of(&path, "First"), LuaValue::String("old".into()));
+
+    // Dry runs leave the file untouched
+    let result = Importer::new(&path)
+        .add_auras([aura("First", "new")])
+        .policy(ResolutionPolicy::ReplaceAll)
+        .dry_run(true)
+        .run()
+        .unwrap();
+    assert_eq!(result.replaced, vec!["First".to_string()]);
+    assert_eq!(load_of(&path, "First"), LuaValue::String("old".into()));
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn test_importer_rejects_invalid_strings_before_writing() {
+    let path = temp_sv_path("invalid");
+    let _ = std::fs::remove_file(&path);
+
+    let result = Importer::new(&path)
+        .add_auras([aura("First", "old")])
+        .add_strings(["not an import string"])
+        .run();
+    assert!(result.is_err());
+    assert!(!path.exists());
+}
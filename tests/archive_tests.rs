@@ -0,0 +1,147 @@
+//! Tests for reading import strings from ZIP archives.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use weakauras_mass_import::api::{read_archive, ArchiveEntry, ArchiveReader, ScanOptions};
+use weakauras_mass_import::archive_source::is_archive;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Fresh archive with the given entries; names ending in `/` are folders
+fn temp_zip(name: &str, entries: &[(&str, &[u8])]) -> PathBuf {
+    let dir = std::env::temp_dir().join("wa_archive_tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.zip", name));
+    let mut zip = ZipWriter::new(std::fs::File::create(&path).unwrap());
+    for (entry, content) in entries {
+        if entry.ends_with('/') {
+            zip.add_directory(*entry, SimpleFileOptions::default())
+                .unwrap();
+        } else {
+            zip.start_file(*entry, SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content).unwrap();
+        }
+    }
+    zip.finish().unwrap();
+    path
+}
+
+fn texts(entries: &[ArchiveEntry]) -> Vec<(&str, &str)> {
+    entries
+        .iter()
+        .filter_map(|e| match e {
+            ArchiveEntry::Text { name, content } => Some((name.as_str(), content.as_str())),
+            ArchiveEntry::Skipped { .. } => None,
+        })
+        .collect()
+}
+
+fn skipped(entries: &[ArchiveEntry]) -> Vec<(&str, &str)> {
+    entries
+        .iter()
+        .filter_map(|e| match e {
+            ArchiveEntry::Skipped { name, reason } => Some((name.as_str(), reason.as_str())),
+            ArchiveEntry::Text { .. } => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_is_archive() {
+    assert!(is_archive(Path::new("pack.zip")));
+    assert!(is_archive(Path::new("dir/Pack.ZIP")));
+    assert!(!is_archive(Path::new("pack.txt")));
+    assert!(!is_archive(Path::new("zip")));
+}
+
+#[test]
+fn test_read_archive_text_entries() {
+    let path = temp_zip(
+        "texts",
+        &[
+            ("readme.md", b"notes"),
+            ("auras/", b""),
+            ("auras/druid.txt", b"!WA:2!abc"),
+            ("auras/icon.png", b"\x89PNG"),
+            ("auras/macro.LUA", b"-- lua"),
+        ],
+    );
+    let entries = read_archive(&path, &ScanOptions::default()).unwrap();
+    assert_eq!(
+        texts(&entries),
+        vec![
+            ("readme.md", "notes"),
+            ("auras/druid.txt", "!WA:2!abc"),
+            ("auras/macro.LUA", "-- lua"),
+        ]
+    );
+    assert!(skipped(&entries).is_empty());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_read_archive_skips_large_and_binary_entries() {
+    let path = temp_zip(
+        "skipped",
+        &[
+            ("small.txt", b"ok"),
+            ("big.txt", &[b'x'; 100]),
+            ("binary.txt", b"\xff\xfe\x00"),
+        ],
+    );
+    let options = ScanOptions {
+        max_file_size: Some(50),
+        ..ScanOptions::default()
+    };
+    let entries = read_archive(&path, &options).unwrap();
+    assert_eq!(texts(&entries), vec![("small.txt", "ok")]);
+    assert_eq!(
+        skipped(&entries),
+        vec![
+            ("big.txt", "over the size limit"),
+            ("binary.txt", "not UTF-8 text"),
+        ]
+    );
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_archive_reader_progress() {
+    let path = temp_zip(
+        "progress",
+        &[("a.txt", b"12345"), ("b.png", b"123"), ("c.txt", b"12")],
+    );
+    let mut reader = ArchiveReader::open(&path, &ScanOptions::default()).unwrap();
+    assert_eq!(reader.total_bytes(), 10);
+    assert_eq!(reader.done_bytes(), 0);
+
+    assert!(matches!(reader.next(), Some(ArchiveEntry::Text { .. })));
+    assert_eq!(reader.done_bytes(), 5);
+    // The skipped image counts as done along with the next text entry
+    assert!(matches!(reader.next(), Some(ArchiveEntry::Text { .. })));
+    assert_eq!(reader.done_bytes(), 10);
+    assert!(reader.next().is_none());
+    assert_eq!(
+        reader.source_of("c.txt"),
+        path.join("c.txt"),
+        "entries are shown below the archive path"
+    );
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_read_archive_not_a_zip() {
+    let dir = std::env::temp_dir().join("wa_archive_tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("fake.zip");
+    std::fs::write(&path, "!WA:2!not a zip").unwrap();
+    assert!(read_archive(&path, &ScanOptions::default()).is_err());
+    assert!(read_archive(&dir.join("missing.zip"), &ScanOptions::default()).is_err());
+
+    let _ = std::fs::remove_file(&path);
+}
@@ -1,6 +1,10 @@
 //! Tests for update category mapping.
 
-use weakauras_mass_import::categories::{CategoryMapper, UpdateCategory};
+use std::collections::HashSet;
+
+use weakauras_mass_import::categories::{
+    CategoryKey, CategoryMapper, CategorySet, CustomCategory, UpdateCategory,
+};
 
 #[test]
 fn test_category_mapping() {
@@ -36,3 +40,52 @@ fn test_default_enabled() {
     assert!(!UpdateCategory::Anchor.default_enabled());
     assert!(!UpdateCategory::UserConfig.default_enabled());
 }
+
+#[test]
+fn test_custom_category_parse() {
+    let category = CustomCategory::parse(" Positioning = xOffset, yOffset,anchorPoint, ").unwrap();
+    assert_eq!(category.name, "Positioning");
+    assert_eq!(category.fields, vec!["xOffset", "yOffset", "anchorPoint"]);
+
+    // Missing separator, empty name, no fields, internal field, built-in name
+    assert!(CustomCategory::parse("Positioning").is_err());
+    assert!(CustomCategory::parse(" = xOffset").is_err());
+    assert!(CustomCategory::parse("Empty = ,").is_err());
+    assert!(CustomCategory::parse("Ids = uid").is_err());
+    assert!(CustomCategory::parse("anchor = xOffset").is_err());
+}
+
+#[test]
+fn test_category_set_keys_and_resolve() {
+    let set = CategorySet::new([
+        CustomCategory::parse("Positioning = xOffset, yOffset").unwrap(),
+        CustomCategory::parse("Text = customText, displayText").unwrap(),
+        // Duplicate names are dropped
+        CustomCategory::parse("Positioning = width").unwrap(),
+    ]);
+
+    let keys = set.keys();
+    assert_eq!(keys.len(), UpdateCategory::all().len() + 2);
+    assert_eq!(keys[0], CategoryKey::Builtin(UpdateCategory::Name));
+    assert_eq!(
+        keys[keys.len() - 2],
+        CategoryKey::Custom("Positioning".to_string())
+    );
+
+    // Custom categories are off by default
+    let defaults = set.defaults();
+    assert!(defaults.contains(&CategoryKey::Builtin(UpdateCategory::Trigger)));
+    assert!(!defaults.contains(&CategoryKey::Custom("Positioning".to_string())));
+
+    let selection = HashSet::from([
+        CategoryKey::Builtin(UpdateCategory::Load),
+        CategoryKey::Custom("Positioning".to_string()),
+        CategoryKey::Custom("Unknown".to_string()),
+    ]);
+    let (categories, fields) = set.resolve(&selection);
+    assert_eq!(categories, HashSet::from([UpdateCategory::Load]));
+    assert_eq!(
+        fields.into_iter().collect::<Vec<_>>(),
+        vec!["xOffset", "yOffset"]
+    );
+}
@@ -0,0 +1,132 @@
+//! Tests for chunked imports.
+
+use weakauras_mass_import::api::{
+    ImportOptions, Importer, LuaTable, LuaValue, ResolutionPolicy, SavedVariablesManager, WeakAura,
+};
+use weakauras_mass_import::saved_variables::backup_path;
+
+fn aura(id: &str, children: usize) -> WeakAura {
+    let data = |id: &str| {
+        let table: LuaTable = [("id".to_string(), LuaValue::String(id.to_string()))]
+            .into_iter()
+            .collect();
+        LuaValue::Table(table.into())
+    };
+    let child_ids: Vec<String> = (0..children).map(|i| format!("{} {}", id, i)).collect();
+    WeakAura {
+        id: id.to_string(),
+        uid: None,
+        region_type: Some(if children > 0 { "group" } else { "icon" }.to_string()),
+        is_group: children > 0,
+        child_data: child_ids.iter().map(|child| data(child)).collect(),
+        children: child_ids,
+        data: data(id),
+        original_string: String::new(),
+        encoding_version: 2,
+    }
+}
+
+fn ids(chunks: &[&[WeakAura]]) -> Vec<Vec<String>> {
+    chunks
+        .iter()
+        .map(|chunk| chunk.iter().map(|a| a.id.clone()).collect())
+        .collect()
+}
+
+#[test]
+fn test_chunks_keep_groups_whole() {
+    let auras = vec![
+        aura("A", 0),
+        aura("B", 0),
+        aura("Group", 2),
+        aura("C", 0),
+        aura("Big", 5),
+        aura("D", 0),
+    ];
+    assert_eq!(
+        ids(&ImportOptions::default().chunks(&auras)),
+        vec![vec!["A", "B", "Group", "C", "Big", "D"]]
+    );
+    let options = ImportOptions {
+        chunk_size: Some(3),
+    };
+    assert_eq!(
+        ids(&options.chunks(&auras)),
+        vec![
+            vec!["A", "B"],
+            vec!["Group"],
+            vec!["C"],
+            vec!["Big"],
+            vec!["D"]
+        ]
+    );
+    assert_eq!(options.chunks(&[]).len(), 1);
+    assert_eq!(
+        ImportOptions {
+            chunk_size: Some(0)
+        }
+        .chunks(&auras)
+        .len(),
+        1
+    );
+}
+
+#[test]
+fn test_import_chunked_saves_each_chunk() {
+    let dir = std::env::temp_dir().join("wa_chunking_tests");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+
+    let auras: Vec<WeakAura> = (0..5).map(|i| aura(&format!("Aura {}", i), 0)).collect();
+    let mut original = SavedVariablesManager::new(path.clone());
+    original
+        .displays
+        .insert("Original".to_string(), aura("Original", 0).data);
+    original.save().unwrap();
+    let original_content = std::fs::read_to_string(&path).unwrap();
+
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager.load().unwrap();
+    let mut progress = Vec::new();
+    let result = manager
+        .import_chunked(
+            &auras,
+            &ResolutionPolicy::SkipAll,
+            &ImportOptions {
+                chunk_size: Some(2),
+            },
+            |chunk, total| progress.push((chunk, total)),
+        )
+        .unwrap();
+    assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+    assert_eq!(result.added.len(), 5);
+    assert_eq!(result.outcomes.len(), 5);
+
+    // Only the first save wrote the backup: it is still the original file
+    assert_eq!(
+        std::fs::read_to_string(backup_path(&path)).unwrap(),
+        original_content
+    );
+    let mut saved = SavedVariablesManager::new(path.clone());
+    saved.load().unwrap();
+    assert_eq!(saved.displays.len(), 6);
+
+    let result = Importer::new(&path)
+        .add_auras((5..8).map(|i| aura(&format!("Aura {}", i), 0)))
+        .options(ImportOptions {
+            chunk_size: Some(1),
+        })
+        .run()
+        .unwrap();
+    assert_eq!(result.added.len(), 3);
+    let comparison = result.comparison.unwrap();
+    assert_eq!(
+        (comparison.before.aura_count, comparison.after.aura_count),
+        (6, 9)
+    );
+    let mut backup = SavedVariablesManager::new(backup_path(&path));
+    backup.load().unwrap();
+    assert_eq!(backup.displays.len(), 6);
+    let _ = std::fs::remove_dir_all(&dir);
+}
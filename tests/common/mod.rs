@@ -0,0 +1,37 @@
+//! Helpers shared by the integration tests.
+
+// Each test crate uses only some of them
+#![allow(dead_code)]
+
+use weakauras_mass_import::decoder::{LuaTable, LuaValue, WeakAura};
+
+/// Lua table with the given fields
+pub fn table(fields: &[(&str, LuaValue)]) -> LuaValue {
+    LuaValue::Table(
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect::<LuaTable>()
+            .into(),
+    )
+}
+
+/// Lua string
+pub fn s(value: &str) -> LuaValue {
+    LuaValue::String(value.to_string())
+}
+
+/// Icon aura `id` whose `load` field is the string `load`
+pub fn aura(id: &str, load: &str) -> WeakAura {
+    WeakAura {
+        id: id.to_string(),
+        uid: None,
+        region_type: Some("icon".to_string()),
+        is_group: false,
+        children: Vec::new(),
+        data: table(&[("id", s(id)), ("load", s(load))]),
+        child_data: Vec::new(),
+        original_string: String::new(),
+        encoding_version: 2,
+    }
+}
@@ -0,0 +1,130 @@
+//! Tests for the WeakAurasCompanion data addon.
+
+use std::fs;
+
+use weakauras_mass_import::companion_data::{wago_slug, CompanionData, COMPANION_ADDON_NAME};
+use weakauras_mass_import::decoder::{LuaTable, LuaValue, WeakAura};
+use weakauras_mass_import::lua_parser::LuaParser;
+
+fn make_aura(id: &str, url: Option<&str>) -> WeakAura {
+    let mut data = LuaTable::new();
+    data.insert("id".to_string(), LuaValue::String(id.to_string()));
+    if let Some(url) = url {
+        data.insert("url".to_string(), LuaValue::String(url.to_string()));
+        data.insert("semver".to_string(), LuaValue::String("1.2.0".to_string()));
+    }
+    let mut changelog = LuaTable::new();
+    changelog.insert(
+        "text".to_string(),
+        LuaValue::String("Fixed timers".to_string()),
+    );
+    data.insert("changelog".to_string(), LuaValue::Table(changelog.into()));
+    WeakAura {
+        id: id.to_string(),
+        uid: None,
+        region_type: Some("icon".to_string()),
+        is_group: false,
+        children: Vec::new(),
+        data: LuaValue::Table(data.into()),
+        child_data: Vec::new(),
+        original_string: format!("!WA:2!{}", id),
+        encoding_version: 2,
+    }
+}
+
+/// `WeakAuras.slugs` table of a generated `data.lua`
+fn slugs_of(lua: &str) -> LuaTable {
+    let (_, table) = lua.split_once('=').unwrap();
+    let root = LuaParser::parse_value(table).unwrap();
+    let weakauras = root.as_table().unwrap()["WeakAuras"].clone();
+    weakauras.as_table().unwrap()["slugs"]
+        .as_table()
+        .unwrap()
+        .clone()
+}
+
+#[test]
+fn test_wago_slug() {
+    assert_eq!(
+        wago_slug("https://wago.io/AbC123/15"),
+        Some(("AbC123".to_string(), 15))
+    );
+    assert_eq!(wago_slug("https://wago.io/AbC123"), None);
+    assert_eq!(wago_slug("https://example.com/AbC123/15"), None);
+}
+
+#[test]
+fn test_companion_data_from_auras() {
+    // Copied from another SavedVariables file, so without an import string
+    let mut copied = make_aura("Copied", Some("https://wago.io/copied/1"));
+    copied.original_string.clear();
+    let data = CompanionData::from_auras(&[
+        make_aura("Old", Some("https://wago.io/pack/3")),
+        make_aura("New", Some("https://wago.io/pack/5")),
+        make_aura("Local", None),
+        copied,
+    ]);
+    assert_eq!(data.slugs.len(), 1);
+    let entry = &data.slugs["pack"];
+    assert_eq!(entry.name, "New");
+    assert_eq!(entry.wago_version, 5);
+    assert_eq!(entry.wago_semver.as_deref(), Some("1.2.0"));
+    assert_eq!(entry.version_note, "Fixed timers");
+    assert_eq!(entry.encoded, "!WA:2!New");
+}
+
+#[test]
+fn test_companion_data_lua_keeps_existing_slugs() {
+    let existing = r#"-- file generated automatically
+WeakAurasCompanionData = {
+  WeakAuras = {
+    slugs = {
+      ["other"] = { name = "Other", wagoVersion = "2" },
+      ["pack"] = { name = "Outdated", wagoVersion = "1" },
+    },
+    stash = {},
+  },
+  Plater = { slugs = {}, stash = {} },
+}
+"#;
+    let data = CompanionData::from_auras(&[make_aura("Pack", Some("https://wago.io/pack/4"))]);
+    let lua = data.to_lua(Some(existing)).unwrap();
+    assert!(lua.contains("Plater"));
+
+    let slugs = slugs_of(&lua);
+    assert_eq!(slugs.len(), 2);
+    let pack = slugs["pack"].as_table().unwrap();
+    assert_eq!(pack["name"], LuaValue::String("Pack".to_string()));
+    assert_eq!(pack["wagoVersion"], LuaValue::String("4".to_string()));
+    assert_eq!(pack["encoded"], LuaValue::String("!WA:2!Pack".to_string()));
+}
+
+#[test]
+fn test_write_companion_addon() {
+    let flavor_dir = std::env::temp_dir()
+        .join("wa_companion_data_tests")
+        .join("_retail_");
+    let _ = fs::remove_dir_all(&flavor_dir);
+
+    let data = CompanionData::from_auras(&[make_aura("Pack", Some("https://wago.io/pack/4"))]);
+    let addon_dir = data.write_addon(&flavor_dir).unwrap();
+    assert_eq!(
+        addon_dir,
+        flavor_dir
+            .join("Interface/AddOns")
+            .join(COMPANION_ADDON_NAME)
+    );
+    let toc = fs::read_to_string(addon_dir.join("WeakAurasCompanion.toc")).unwrap();
+    assert!(toc.contains("data.lua") && toc.contains("init.lua"));
+    assert!(fs::read_to_string(addon_dir.join("init.lua"))
+        .unwrap()
+        .contains("AddCompanionData"));
+
+    // A second import adds to the data written before
+    let data = CompanionData::from_auras(&[make_aura("Bars", Some("https://wago.io/bars/1"))]);
+    data.write_addon(&flavor_dir).unwrap();
+    let slugs = slugs_of(&fs::read_to_string(addon_dir.join("data.lua")).unwrap());
+    assert!(slugs.contains_key("pack") && slugs.contains_key("bars"));
+
+    let _ = fs::remove_dir_all(&flavor_dir);
+}
@@ -0,0 +1,121 @@
+//! Tests for the browser companion endpoint.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use weakauras_mass_import::companion::CompanionServer;
+
+fn start() -> (SocketAddr, mpsc::Receiver<String>) {
+    let server = CompanionServer::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        server.run(|text| {
+            let _ = tx.send(text);
+        })
+    });
+    (addr, rx)
+}
+
+fn send(addr: SocketAddr, request: &str) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+fn post(addr: SocketAddr, content_type: &str, body: &str) -> String {
+    send(
+        addr,
+        &format!(
+            "POST /import HTTP/1.1\r\nHost: localhost\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n{}",
+            content_type,
+            body.len(),
+            body
+        ),
+    )
+}
+
+#[test]
+fn test_companion_binds_only_loopback_addresses() {
+    let error = CompanionServer::bind("0.0.0.0:0").err().unwrap();
+    assert!(error.to_string().contains("loopback"));
+}
+
+#[test]
+fn test_companion_receives_text_and_json() {
+    let (addr, rx) = start();
+
+    let response = post(addr, "text/plain", "!WA:2!abc\n\n!WA:2!def\n");
+    assert!(response.starts_with("HTTP/1.1 202"));
+    assert!(!response.contains("Access-Control-Allow-Origin"));
+    assert_eq!(rx.recv().unwrap(), "!WA:2!abc\n!WA:2!def");
+
+    let response = post(
+        addr,
+        "application/json",
+        r#"{"string": "!WA:2!one", "strings": ["!WA:2!two"]}"#,
+    );
+    assert!(response.starts_with("HTTP/1.1 202"));
+    assert!(response.ends_with(r#"{"received":2}"#));
+    assert_eq!(rx.recv().unwrap(), "!WA:2!one\n!WA:2!two");
+}
+
+#[test]
+fn test_companion_rejects_empty_and_unknown_requests() {
+    let (addr, rx) = start();
+
+    assert!(post(addr, "application/json", r#"{"strings": []}"#).starts_with("HTTP/1.1 400"));
+    assert!(post(addr, "application/json", "not json").starts_with("HTTP/1.1 400"));
+    assert!(send(addr, "GET /nope HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 404"));
+    assert!(send(addr, "OPTIONS /import HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 204"));
+    assert!(send(addr, "GET /status HTTP/1.1\r\n\r\n").contains("weakauras-mass-import"));
+    assert!(rx.try_recv().is_err());
+}
+
+fn post_from(addr: SocketAddr, origin: &str, body: &str) -> String {
+    send(
+        addr,
+        &format!(
+            "POST /import HTTP/1.1\r\nOrigin: {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+            origin,
+            body.len(),
+            body
+        ),
+    )
+}
+
+#[test]
+fn test_companion_accepts_extensions_and_refuses_web_pages() {
+    let (addr, rx) = start();
+
+    let response = post_from(addr, "chrome-extension://abcdef", "!WA:2!abc");
+    assert!(response.starts_with("HTTP/1.1 202"));
+    assert!(response.contains("Access-Control-Allow-Origin: chrome-extension://abcdef\r\n"));
+    assert_eq!(rx.recv().unwrap(), "!WA:2!abc");
+
+    for origin in ["https://example.com", "null", "chrome-extension://"] {
+        let response = post_from(addr, origin, "!WA:2!abc");
+        assert!(response.starts_with("HTTP/1.1 403"), "{}", origin);
+        assert!(!response.contains("Access-Control-Allow-Origin"));
+    }
+    let preflight = "OPTIONS /import HTTP/1.1\r\nOrigin: https://example.com\r\n\r\n";
+    assert!(send(addr, preflight).starts_with("HTTP/1.1 403"));
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn test_companion_drops_stalled_connections() {
+    let (addr, rx) = start();
+
+    // Sends nothing; the endpoint must time it out rather than wait forever
+    let _stalled = TcpStream::connect(addr).unwrap();
+    let started = Instant::now();
+    let response = post(addr, "text/plain", "!WA:2!abc");
+    assert!(response.starts_with("HTTP/1.1 202"));
+    assert!(started.elapsed() < Duration::from_secs(30));
+    assert_eq!(rx.recv().unwrap(), "!WA:2!abc");
+}
@@ -0,0 +1,197 @@
+//! Tests for persistent user configuration.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use weakauras_mass_import::categories::{CategoryKey, UpdateCategory};
+use weakauras_mass_import::config::{AppConfig, ResolutionPreset};
+use weakauras_mass_import::saved_variables::ConflictAction;
+use weakauras_mass_import::scan::ScanOptions;
+use weakauras_mass_import::transforms::{TransformSpec, TransformStep};
+
+fn temp_config_path(name: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join("wa_config_tests")
+        .join(name)
+        .join("config.json")
+}
+
+#[test]
+fn test_config_round_trip() {
+    let path = temp_config_path("round_trip");
+    let _ = std::fs::remove_file(&path);
+
+    let mut config = AppConfig::default();
+    config.add_wow_path(PathBuf::from("C:/Games/World of Warcraft"));
+    config.add_wow_path(PathBuf::from("D:/Classic/World of Warcraft"));
+    config.save_to(&path).unwrap();
+    // Written under a temporary name and renamed into place
+    assert!(!path.with_extension("json.tmp").exists());
+
+    let loaded = AppConfig::load_from(&path).unwrap();
+    assert_eq!(loaded, config);
+    assert_eq!(loaded.wow_paths.len(), 2);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_config_load_missing_file_is_default() {
+    let path = temp_config_path("missing");
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(AppConfig::load_from(&path).unwrap(), AppConfig::default());
+}
+
+#[test]
+fn test_config_load_ignores_unknown_and_missing_fields() {
+    let path = temp_config_path("partial");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, r#"{ "future_setting": true }"#).unwrap();
+
+    let loaded = AppConfig::load_from(&path).unwrap();
+    assert!(loaded.wow_paths.is_empty());
+
+    std::fs::write(&path, "not json").unwrap();
+    assert!(AppConfig::load_from(&path).is_err());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_config_load_adds_missing_builtin_transforms() {
+    let path = temp_config_path("transforms");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &path,
+        r#"{ "transforms": [{ "type": "rename_ids", "template": "{name} [M+]", "enabled": true }] }"#,
+    )
+    .unwrap();
+
+    let loaded = AppConfig::load_from(&path).unwrap();
+    // The saved step keeps its place and settings, the others are appended disabled
+    assert_eq!(loaded.transforms.len(), TransformStep::defaults().len());
+    assert!(loaded.transforms[0].enabled);
+    assert!(
+        matches!(&loaded.transforms[0].spec, TransformSpec::RenameIds(r) if r.template == "{name} [M+]")
+    );
+    assert!(loaded.transforms[1..].iter().all(|s| !s.enabled));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_config_add_remove_wow_path() {
+    let mut config = AppConfig::default();
+    let path = PathBuf::from("/games/World of Warcraft");
+
+    assert!(config.add_wow_path(path.clone()));
+    assert!(!config.add_wow_path(path.clone()));
+    assert_eq!(config.wow_paths, vec![path.clone()]);
+
+    assert!(config.remove_wow_path(&path));
+    assert!(!config.remove_wow_path(&path));
+    assert!(config.wow_paths.is_empty());
+}
+
+#[test]
+fn test_config_category_set_skips_invalid_definitions() {
+    let config = AppConfig {
+        custom_categories: vec![
+            "Positioning = xOffset, yOffset, anchorPoint".to_string(),
+            "not a definition".to_string(),
+        ],
+        ..Default::default()
+    };
+    let set = config.category_set();
+    assert_eq!(set.keys().len(), UpdateCategory::all().len() + 1);
+}
+
+#[test]
+fn test_config_missing_fields_use_defaults() {
+    let path = temp_config_path("missing_fields");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, r#"{ "wow_paths": [] }"#).unwrap();
+
+    let config = AppConfig::load_from(&path).unwrap();
+    assert!(config.keep_user_config);
+    assert!(!config.preserve_position);
+    assert!(!config.paste_replaces_input);
+    assert!(!config.auto_parse);
+    assert_eq!(config.folder_scan, ScanOptions::default());
+    assert!(!config.skip_trivial_auras);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_resolution_presets() {
+    let mut config = AppConfig {
+        custom_categories: vec!["Positioning = xOffset, yOffset".to_string()],
+        ..Default::default()
+    };
+    let set = config.category_set();
+    let selection: HashSet<CategoryKey> = [
+        CategoryKey::Builtin(UpdateCategory::Trigger),
+        CategoryKey::Builtin(UpdateCategory::Conditions),
+        CategoryKey::Custom("Positioning".to_string()),
+    ]
+    .into_iter()
+    .collect();
+
+    let preset = ResolutionPreset::new(
+        " Triggers ",
+        ConflictAction::UpdateSelected,
+        &selection,
+        &set,
+    );
+    assert_eq!(preset.name, "Triggers");
+    assert_eq!(
+        preset.categories,
+        vec!["Trigger", "Conditions", "Positioning"]
+    );
+    assert_eq!(preset.category_keys(&set), selection);
+
+    // Names unknown to the category set are ignored
+    let without_custom = AppConfig::default().category_set();
+    assert_eq!(preset.category_keys(&without_custom).len(), 2);
+
+    assert!(config.save_preset(preset.clone()));
+    assert!(config.save_preset(ResolutionPreset {
+        action: ConflictAction::Skip,
+        ..preset
+    }));
+    assert_eq!(config.resolution_presets.len(), 1);
+    assert_eq!(
+        config.preset("Triggers").unwrap().action,
+        ConflictAction::Skip
+    );
+    assert!(!config.save_preset(ResolutionPreset::new(
+        "  ",
+        ConflictAction::Skip,
+        &selection,
+        &set
+    )));
+
+    config.auto_preset = Some("Triggers".to_string());
+    assert!(config.remove_preset("Triggers"));
+    assert!(!config.remove_preset("Triggers"));
+    assert_eq!(config.auto_preset, None);
+}
+
+#[test]
+fn test_portable_mode_uses_folder_beside_executable() {
+    let exe_dir = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+
+    AppConfig::set_portable(true);
+    let portable_dir = AppConfig::config_dir();
+    let default_path = AppConfig::default_path();
+    AppConfig::set_portable(false);
+
+    assert_eq!(portable_dir, Some(exe_dir.join("data")));
+    assert_eq!(default_path, Some(exe_dir.join("data").join("config.json")));
+    assert_ne!(AppConfig::config_dir(), portable_dir);
+}
@@ -0,0 +1,68 @@
+//! Tests for crash reports.
+
+use std::path::{Path, PathBuf};
+
+use weakauras_mass_import::crash::{
+    anonymize, format_report, saves_in_progress, take_pending_in, write_report, SaveInProgress,
+};
+use weakauras_mass_import::saved_variables::SavedVariablesManager;
+
+#[test]
+fn test_anonymize_hides_home_and_account_names() {
+    let text = "failed to read /home/alice/Games/WoW/_retail_/WTF/Account/ALICE123/SavedVariables/WeakAuras.lua\n\
+                C:\\Users\\alice\\WoW\\_classic_\\WTF\\Account\\ALICE123#1\\SavedVariables";
+    let anonymized = anonymize(text, Some(Path::new("/home/alice/")));
+    assert!(!anonymized.contains("alice/"));
+    assert!(!anonymized.contains("ALICE123"));
+    assert!(anonymized.contains("~/Games/WoW/_retail_/WTF/Account/<account>/SavedVariables"));
+    assert!(anonymized.contains(r"\WTF\Account\<account>\SavedVariables"));
+}
+
+#[test]
+fn test_crash_report_and_pending_marker() {
+    let report = format_report(
+        "index out of bounds",
+        Some("src/app/mod.rs:10:5"),
+        "0: main",
+        "parsed auras: 3",
+    );
+    assert!(report.contains("Panic: index out of bounds"));
+    assert!(report.contains("Location: src/app/mod.rs:10:5"));
+    assert!(report.contains("parsed auras: 3"));
+    assert!(report.contains(env!("CARGO_PKG_VERSION")));
+
+    let dir = std::env::temp_dir().join("wa_crash_tests");
+    let _ = std::fs::remove_dir_all(&dir);
+    let sv = PathBuf::from("WTF/Account/A/SavedVariables/WeakAuras.lua");
+    let path = write_report(&dir, &report, Some(sv.clone())).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), report);
+
+    let pending = take_pending_in(&dir).unwrap();
+    assert_eq!(pending.report, path);
+    assert_eq!(pending.saved_variables, Some(sv));
+    // Only offered once
+    assert!(take_pending_in(&dir).is_none());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_only_files_being_saved_are_recorded_for_recovery() {
+    let dir = std::env::temp_dir().join("wa_crash_saving_tests");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+
+    let saving = SaveInProgress::start(&path);
+    assert!(saves_in_progress().contains(&path));
+    drop(saving);
+    assert!(!saves_in_progress().contains(&path));
+
+    // Loading and saving leave no marker behind
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager.save().unwrap();
+    manager.load().unwrap();
+    assert!(!saves_in_progress().contains(&path));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -1,10 +1,17 @@
 //! Tests for WeakAura string decoding and LuaValue types.
 
-use std::collections::HashMap;
+mod common;
 
-use weakauras_mass_import::decoder::{LuaValue, WeakAuraDecoder};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use weakauras_mass_import::decoder::{
+    format_lua_path, AuraTextFormat, LuaTable, LuaValue, WeakAura, WeakAuraDecoder,
+};
 use weakauras_mass_import::lua_parser::LuaParser;
 
+use common::{s, table};
+
 #[test]
 fn test_detect_version() {
     assert_eq!(WeakAuraDecoder::detect_version("!WA:2!abc"), 2);
@@ -12,9 +19,29 @@ fn test_detect_version() {
     assert_eq!(WeakAuraDecoder::detect_version("abc"), 0);
 }
 
+#[test]
+fn test_decode_multiple_with_spans() {
+    let input = "Some notes\n  !WA:2!broken  \n\n!not an aura either\r\n!WA:2!";
+    let decoded = WeakAuraDecoder::decode_multiple_with_spans(input);
+    let found: Vec<(&str, usize)> = decoded
+        .iter()
+        .map(|d| (&input[d.span.clone()], d.line))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("!WA:2!broken", 1),
+            ("!not an aura either", 3),
+            ("!WA:2!", 4)
+        ]
+    );
+    assert!(decoded.iter().all(|d| d.result.is_err()));
+    assert_eq!(WeakAuraDecoder::decode_multiple(input).len(), 3);
+}
+
 #[test]
 fn test_triggers_mixed_table_structure() {
-    let mut hash = HashMap::new();
+    let mut hash = LuaTable::new();
     hash.insert(
         "disjunctive".to_string(),
         LuaValue::String("all".to_string()),
@@ -22,34 +49,34 @@ fn test_triggers_mixed_table_structure() {
     hash.insert("activeTriggerMode".to_string(), LuaValue::Number(-10.0));
 
     let trigger1 = {
-        let mut t = HashMap::new();
+        let mut t = LuaTable::new();
         t.insert(
             "trigger".to_string(),
-            LuaValue::Table({
-                let mut inner = HashMap::new();
+            LuaValue::Table(Arc::new({
+                let mut inner = LuaTable::new();
                 inner.insert("type".to_string(), LuaValue::String("aura2".to_string()));
                 inner
-            }),
+            })),
         );
-        LuaValue::Table(t)
+        LuaValue::Table(t.into())
     };
 
     let trigger2 = {
-        let mut t = HashMap::new();
+        let mut t = LuaTable::new();
         t.insert(
             "trigger".to_string(),
-            LuaValue::Table({
-                let mut inner = HashMap::new();
+            LuaValue::Table(Arc::new({
+                let mut inner = LuaTable::new();
                 inner.insert("type".to_string(), LuaValue::String("spell".to_string()));
                 inner
-            }),
+            })),
         );
-        LuaValue::Table(t)
+        LuaValue::Table(t.into())
     };
 
     let mixed = LuaValue::MixedTable {
         array: vec![trigger1, trigger2],
-        hash,
+        hash: hash.into(),
     };
 
     if let LuaValue::MixedTable { array, hash } = &mixed {
@@ -64,7 +91,7 @@ fn test_triggers_mixed_table_structure() {
 
 #[test]
 fn test_triggers_serialization_no_string_numeric_keys() {
-    let mut hash = HashMap::new();
+    let mut hash = LuaTable::new();
     hash.insert(
         "disjunctive".to_string(),
         LuaValue::String("any".to_string()),
@@ -72,37 +99,37 @@ fn test_triggers_serialization_no_string_numeric_keys() {
     hash.insert("activeTriggerMode".to_string(), LuaValue::Number(-10.0));
 
     let trigger1 = {
-        let mut t = HashMap::new();
+        let mut t = LuaTable::new();
         t.insert(
             "trigger".to_string(),
-            LuaValue::Table({
-                let mut inner = HashMap::new();
+            LuaValue::Table(Arc::new({
+                let mut inner = LuaTable::new();
                 inner.insert("type".to_string(), LuaValue::String("aura2".to_string()));
                 inner
-            }),
+            })),
         );
-        t.insert("untrigger".to_string(), LuaValue::Table(HashMap::new()));
-        LuaValue::Table(t)
+        t.insert("untrigger".to_string(), LuaValue::Table(Arc::default()));
+        LuaValue::Table(t.into())
     };
 
     let trigger2 = {
-        let mut t = HashMap::new();
+        let mut t = LuaTable::new();
         t.insert(
             "trigger".to_string(),
-            LuaValue::Table({
-                let mut inner = HashMap::new();
+            LuaValue::Table(Arc::new({
+                let mut inner = LuaTable::new();
                 inner.insert("type".to_string(), LuaValue::String("spell".to_string()));
                 inner.insert("spellName".to_string(), LuaValue::Number(12345.0));
                 inner
-            }),
+            })),
         );
-        t.insert("untrigger".to_string(), LuaValue::Table(HashMap::new()));
-        LuaValue::Table(t)
+        t.insert("untrigger".to_string(), LuaValue::Table(Arc::default()));
+        LuaValue::Table(t.into())
     };
 
     let triggers = LuaValue::MixedTable {
         array: vec![trigger1, trigger2],
-        hash,
+        hash: hash.into(),
     };
 
     let serialized = LuaParser::serialize(&triggers, 0);
@@ -161,27 +188,27 @@ fn test_pure_array_serialization() {
 
 #[test]
 fn test_convert_lua_value_preserves_numeric_keys() {
-    let mut hash = HashMap::new();
+    let mut hash = LuaTable::new();
     hash.insert(
         "disjunctive".to_string(),
         LuaValue::String("any".to_string()),
     );
 
-    let trigger1 = LuaValue::Table({
-        let mut t = HashMap::new();
-        t.insert("trigger".to_string(), LuaValue::Table(HashMap::new()));
+    let trigger1 = LuaValue::Table(Arc::new({
+        let mut t = LuaTable::new();
+        t.insert("trigger".to_string(), LuaValue::Table(Arc::default()));
         t
-    });
+    }));
 
-    let trigger2 = LuaValue::Table({
-        let mut t = HashMap::new();
-        t.insert("trigger".to_string(), LuaValue::Table(HashMap::new()));
+    let trigger2 = LuaValue::Table(Arc::new({
+        let mut t = LuaTable::new();
+        t.insert("trigger".to_string(), LuaValue::Table(Arc::default()));
         t
-    });
+    }));
 
     let mixed = LuaValue::MixedTable {
         array: vec![trigger1, trigger2],
-        hash,
+        hash: hash.into(),
     };
 
     let serialized = LuaParser::serialize(&mixed, 0);
@@ -203,3 +230,458 @@ fn test_convert_lua_value_preserves_numeric_keys() {
         "Should show implicit index 2"
     );
 }
+
+#[test]
+fn test_cloned_tables_are_copied_on_write() {
+    let mut inner = LuaTable::new();
+    inner.insert("type".to_string(), LuaValue::String("aura2".to_string()));
+    let mut outer = LuaTable::new();
+    outer.insert("trigger".to_string(), LuaValue::Table(Arc::new(inner)));
+    let original = LuaValue::Table(Arc::new(outer));
+
+    let mut copy = original.clone();
+    match (&original, &copy) {
+        (LuaValue::Table(a), LuaValue::Table(b)) => assert!(Arc::ptr_eq(a, b)),
+        _ => panic!("Expected tables"),
+    }
+
+    copy.as_table_mut()
+        .unwrap()
+        .insert("id".to_string(), LuaValue::String("Copy".to_string()));
+    assert!(!original.as_table().unwrap().contains_key("id"));
+    assert_ne!(original, copy);
+
+    // Untouched subtables stay shared after the top level was copied
+    match (
+        original.as_table().unwrap().get("trigger"),
+        copy.as_table().unwrap().get("trigger"),
+    ) {
+        (Some(LuaValue::Table(a)), Some(LuaValue::Table(b))) => assert!(Arc::ptr_eq(a, b)),
+        _ => panic!("Expected trigger tables"),
+    }
+}
+
+fn aura_table(id: &str, parent: &str, children: &[&str]) -> LuaValue {
+    let mut table = LuaTable::new();
+    table.insert("id".to_string(), LuaValue::String(id.to_string()));
+    table.insert("parent".to_string(), LuaValue::String(parent.to_string()));
+    if !children.is_empty() {
+        table.insert(
+            "controlledChildren".to_string(),
+            LuaValue::Array(
+                children
+                    .iter()
+                    .map(|c| LuaValue::String(c.to_string()))
+                    .collect(),
+            ),
+        );
+    }
+    LuaValue::Table(table.into())
+}
+
+/// Group "Root" holding the subgroup "Sub" (with "Inner") and the aura "Leaf".
+/// `children` lists every child, as inferred for strings without
+/// `controlledChildren` on the root.
+fn make_nested_group() -> WeakAura {
+    let mut root = LuaTable::new();
+    root.insert("id".to_string(), LuaValue::String("Root".to_string()));
+    root.insert(
+        "controlledChildren".to_string(),
+        LuaValue::Array(vec![
+            LuaValue::String("Sub".to_string()),
+            LuaValue::String("Leaf".to_string()),
+        ]),
+    );
+    WeakAura {
+        id: "Root".to_string(),
+        uid: None,
+        region_type: Some("group".to_string()),
+        is_group: true,
+        children: vec!["Inner".to_string(), "Sub".to_string(), "Leaf".to_string()],
+        data: LuaValue::Table(root.into()),
+        child_data: vec![
+            aura_table("Inner", "Sub", &[]),
+            aura_table("Sub", "Root", &["Inner"]),
+            aura_table("Leaf", "Root", &[]),
+        ],
+        original_string: String::new(),
+        encoding_version: 2,
+    }
+}
+
+#[test]
+fn test_descendants_in_display_order() {
+    assert_eq!(
+        make_nested_group().descendants(),
+        vec![
+            ("Sub".to_string(), 1),
+            ("Inner".to_string(), 2),
+            ("Leaf".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_descendants_of_long_group_chain() {
+    // Far deeper than any real aura, and than the stack allows recursing
+    let levels = 50_000;
+    let id = |i: usize| format!("Group {}", i);
+    let mut aura = make_nested_group();
+    aura.children = (1..=levels).map(id).collect();
+    aura.child_data = (1..=levels)
+        .map(|i| {
+            let parent = if i == 1 {
+                "Root".to_string()
+            } else {
+                id(i - 1)
+            };
+            let next = id(i + 1);
+            let children: &[&str] = if i < levels { &[&next] } else { &[] };
+            aura_table(&id(i), &parent, children)
+        })
+        .collect();
+    let mut root = LuaTable::new();
+    root.insert("id".to_string(), LuaValue::String("Root".to_string()));
+    aura.data = LuaValue::Table(root.into());
+
+    let descendants = aura.descendants();
+    assert_eq!(descendants.len(), levels);
+    assert_eq!(descendants.last(), Some(&(id(levels), levels)));
+}
+
+#[test]
+fn test_remove_children_fixes_hierarchy() {
+    let mut aura = make_nested_group();
+    let removed = aura.remove_children(&HashSet::from(["Sub".to_string()]));
+    // The subgroup goes with its child
+    assert_eq!(removed, 2);
+    assert_eq!(aura.children, vec!["Leaf".to_string()]);
+    assert_eq!(aura.descendants(), vec![("Leaf".to_string(), 1)]);
+    let controlled = aura.data.as_table().unwrap()["controlledChildren"].clone();
+    assert_eq!(
+        controlled,
+        LuaValue::Array(vec![LuaValue::String("Leaf".to_string())])
+    );
+
+    let mut aura = make_nested_group();
+    assert_eq!(
+        aura.remove_children(&HashSet::from(["Inner".to_string()])),
+        1
+    );
+    let sub = aura.child_data[0].as_table().unwrap();
+    assert_eq!(sub["controlledChildren"], LuaValue::Array(Vec::new()));
+    assert_eq!(aura.remove_children(&HashSet::new()), 0);
+}
+
+#[test]
+fn test_unwrap_single_child_group() {
+    assert!(!make_nested_group().is_single_child_wrapper());
+    assert!(make_nested_group().unwrap_single_child().is_none());
+
+    // Without "Leaf", "Root" only wraps the subgroup "Sub"
+    let mut wrapper = make_nested_group();
+    wrapper.remove_children(&HashSet::from(["Leaf".to_string()]));
+    assert!(wrapper.is_single_child_wrapper());
+    let sub = wrapper.unwrap_single_child().unwrap();
+    assert_eq!(sub.id, "Sub");
+    assert!(sub.is_group);
+    assert_eq!(sub.children, vec!["Inner".to_string()]);
+    assert_eq!(sub.descendants(), vec![("Inner".to_string(), 1)]);
+    assert!(!sub.data.as_table().unwrap().contains_key("parent"));
+
+    // Unwrapping the subgroup leaves a plain aura
+    let inner = sub.unwrap_single_child().unwrap();
+    assert_eq!(inner.id, "Inner");
+    assert!(!inner.is_group);
+    assert!(inner.child_data.is_empty());
+    assert!(inner.unwrap_single_child().is_none());
+}
+
+#[test]
+fn test_walk_visits_nested_values_with_paths() {
+    let data = make_nested_group().child_data[1].clone();
+    let mut visited = Vec::new();
+    data.walk(&mut |path, value| {
+        if let LuaValue::String(s) = value {
+            visited.push((format_lua_path(path), s.clone()));
+        }
+    });
+    assert_eq!(
+        visited,
+        vec![
+            ("id".to_string(), "Sub".to_string()),
+            ("parent".to_string(), "Root".to_string()),
+            ("controlledChildren[1]".to_string(), "Inner".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_get_and_set_by_path() {
+    let trigger = aura_table("Trigger", "Root", &[]);
+    let mut data = LuaValue::Table(
+        LuaTable::from([(
+            "triggers".to_string(),
+            LuaValue::MixedTable {
+                array: vec![LuaValue::Table(
+                    LuaTable::from([("trigger".to_string(), trigger)]).into(),
+                )],
+                hash: LuaTable::from([(
+                    "disjunctive".to_string(),
+                    LuaValue::String("any".to_string()),
+                )])
+                .into(),
+            },
+        )])
+        .into(),
+    );
+
+    let parent = data.get_path("triggers.1.trigger.parent");
+    assert_eq!(parent.and_then(LuaValue::as_str), Some("Root"));
+    assert_eq!(
+        data.get_path("triggers.disjunctive")
+            .and_then(LuaValue::as_str),
+        Some("any")
+    );
+    assert!(data.get_path("triggers.2").is_none());
+    assert_eq!(data.get_path(""), Some(&data));
+
+    // Replacing, appending to the array part and creating tables on the way
+    assert!(data.set_path("triggers.1.trigger.spellName", LuaValue::Number(375087.0)));
+    assert!(data.set_path(
+        "triggers.2.trigger.type",
+        LuaValue::String("aura2".to_string())
+    ));
+    assert!(data.set_path("load.use_class", LuaValue::Bool(true)));
+    assert_eq!(
+        data.get_path("triggers.1.trigger.spellName"),
+        Some(&LuaValue::Number(375087.0))
+    );
+    assert_eq!(
+        data.get_path("triggers.2.trigger.type")
+            .and_then(LuaValue::as_str),
+        Some("aura2")
+    );
+    assert_eq!(
+        data.get_path("triggers")
+            .and_then(LuaValue::as_array)
+            .map(Vec::len),
+        Some(2)
+    );
+    assert_eq!(data.get_path("load.use_class"), Some(&LuaValue::Bool(true)));
+
+    // Paths through a non-table value change nothing
+    let before = data.clone();
+    assert!(!data.set_path("triggers.disjunctive.x.y", LuaValue::Nil));
+    assert_eq!(data, before);
+
+    *data.get_path_mut("triggers.disjunctive").unwrap() = LuaValue::String("all".to_string());
+    assert_eq!(
+        data.get_path("triggers.disjunctive")
+            .and_then(LuaValue::as_str),
+        Some("all")
+    );
+}
+
+#[test]
+fn test_edit_data_text_in_lua_and_json() {
+    let mut aura = make_nested_group();
+    aura.data.set_path("triggers", LuaValue::Array(Vec::new()));
+    aura.data.set_path(
+        "triggers.1.trigger.type",
+        LuaValue::String("aura2".to_string()),
+    );
+    aura.data
+        .set_path("triggers.disjunctive", LuaValue::String("any".to_string()));
+    aura.data.set_path("xOffset", LuaValue::Number(-12.5));
+    let original = aura.data.clone();
+
+    for format in [AuraTextFormat::Lua, AuraTextFormat::Json] {
+        let text = aura.data_text(format);
+        aura.set_data_text(&text, format).unwrap();
+        assert_eq!(aura.data, original, "{:?} round trip", format);
+    }
+    let json = aura.data_text(AuraTextFormat::Json);
+    // The trigger list has string keys too, so its entries are keyed "1", ...
+    assert!(json.contains("\"1\": {"));
+    assert!(json.contains("\"disjunctive\": \"any\""));
+
+    // A new ID is re-read and renamed in the direct children
+    let edited = aura
+        .data_text(AuraTextFormat::Lua)
+        .replace("\"Root\"", "\"Renamed\"")
+        .replace("\"Leaf\"", "\"Leaf\", \"Extra\"");
+    aura.set_data_text(&edited, AuraTextFormat::Lua).unwrap();
+    assert_eq!(aura.id, "Renamed");
+    assert_eq!(aura.children, vec!["Sub", "Leaf", "Extra"]);
+    assert_eq!(
+        aura.child_data[1].get_path("parent"),
+        Some(&LuaValue::String("Renamed".to_string()))
+    );
+    assert_eq!(
+        aura.child_data[0].get_path("parent"),
+        Some(&LuaValue::String("Sub".to_string()))
+    );
+
+    let before = aura.data.clone();
+    for (text, format) in [
+        ("{ id = ", AuraTextFormat::Lua),
+        ("{ width = 40 }", AuraTextFormat::Lua),
+        ("[1, 2]", AuraTextFormat::Json),
+        ("{\"id\": 5}", AuraTextFormat::Json),
+    ] {
+        assert!(aura.set_data_text(text, format).is_err(), "{}", text);
+    }
+    assert_eq!(aura.data, before);
+    assert_eq!(aura.id, "Renamed");
+}
+
+#[test]
+fn test_value_to_text_snippets() {
+    let value = LuaValue::Table(
+        LuaTable::from([
+            ("id".to_string(), LuaValue::String("Snippet".to_string())),
+            ("width".to_string(), LuaValue::Number(40.0)),
+            ("alpha".to_string(), LuaValue::Number(0.5)),
+        ])
+        .into(),
+    );
+    let json = value.to_text(AuraTextFormat::Json);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+        serde_json::json!({"id": "Snippet", "width": 40, "alpha": 0.5})
+    );
+    let lua = value.to_text(AuraTextFormat::Lua);
+    assert!(lua.contains("[\"id\"] = \"Snippet\""));
+    assert_eq!(LuaParser::parse_value(&lua).unwrap(), value);
+}
+
+#[test]
+fn test_export_lua_includes_children() {
+    let aura = make_nested_group();
+    let lua = aura.export_lua();
+    assert!(lua.starts_with("-- WeakAuras displays exported"));
+
+    let (_, table) = lua.split_once("WeakAurasExportedDisplays = ").unwrap();
+    let displays = LuaParser::parse_value(table).unwrap();
+    let ids: Vec<&String> = displays.as_table().unwrap().keys().collect();
+    assert_eq!(ids, ["Root", "Inner", "Sub", "Leaf"]);
+    assert_eq!(displays.get_path("Root"), Some(&aura.data));
+    assert_eq!(displays.get_path("Sub"), Some(&aura.child_data[1]));
+}
+
+fn child(id: &str, parent: Option<&str>) -> LuaValue {
+    match parent {
+        Some(parent) => table(&[("id", s(id)), ("parent", s(parent))]),
+        None => table(&[("id", s(id))]),
+    }
+}
+
+fn decode_error(input: &str) -> String {
+    WeakAuraDecoder::decode(input).unwrap_err().to_string()
+}
+
+#[test]
+fn test_decode_rejects_malformed_strings() {
+    assert!(decode_error("   ").contains("empty"));
+    assert!(decode_error("!WA:2").contains("not closed"));
+    assert!(decode_error("!WA:2!").contains("no data"));
+    assert!(decode_error("!WA:2!abc def").contains("invalid character ' ' at position 10"));
+    assert!(decode_error("!ab\u{1F409}c").contains("at position 4"));
+    // Quotes and line breaks are only forgiven by the lossy decoder
+    assert!(decode_error("\"!WA:2!abc\"").contains("invalid character"));
+    assert!(WeakAuraDecoder::decode_lossy(" \"!WA:2!\" ")
+        .unwrap_err()
+        .to_string()
+        .contains("no data"));
+}
+
+#[test]
+fn test_aura_from_data_requires_an_aura() {
+    let error = |data: LuaValue| {
+        WeakAuraDecoder::aura_from_data(data, true)
+            .unwrap_err()
+            .to_string()
+    };
+    assert!(error(s("d")).contains("does not contain aura data"));
+    assert!(error(table(&[("d", LuaValue::Number(1.0))])).contains("does not contain aura data"));
+    assert!(error(table(&[("d", table(&[("id", s(""))]))])).contains("no ID"));
+
+    // A child list with gaps only decodes lossily
+    let sparse = table(&[
+        ("d", table(&[("id", s("Root"))])),
+        (
+            "c",
+            table(&[("1", child("A", None)), ("3", child("B", None))]),
+        ),
+    ]);
+    assert!(WeakAuraDecoder::aura_from_data(sparse.clone(), false)
+        .unwrap_err()
+        .to_string()
+        .contains("child list is not a list"));
+    let salvaged = WeakAuraDecoder::aura_from_data(sparse, true).unwrap();
+    assert_eq!(salvaged.aura.children, vec!["A", "B"]);
+    assert_eq!(salvaged.repairs, vec!["closed gaps in the child list"]);
+
+    // Bare aura tables and empty child lists are fine
+    let bare =
+        WeakAuraDecoder::aura_from_data(table(&[("id", s("Solo")), ("c", table(&[]))]), false)
+            .unwrap();
+    assert_eq!(bare.aura.id, "Solo");
+    assert!(!bare.aura.is_group);
+    assert!(bare.repairs.is_empty());
+}
+
+#[test]
+fn test_aura_from_data_salvages_children() {
+    let data = table(&[
+        ("d", table(&[("id", s("Root")), ("regionType", s("group"))])),
+        (
+            "c",
+            LuaValue::Array(vec![
+                LuaValue::Number(5.0),
+                table(&[("regionType", s("icon"))]),
+                child("A", Some("Root")),
+                child("A", None),
+                child("Root", None),
+                child("B", Some("Missing")),
+                child("C", Some("D")),
+                child("D", Some("C")),
+                child("E", Some("A")),
+            ]),
+        ),
+    ]);
+
+    // Kept as-is without `lossy`; the import then rejects the aura
+    let strict = WeakAuraDecoder::aura_from_data(data.clone(), false).unwrap();
+    assert_eq!(strict.aura.child_data.len(), 9);
+    assert!(strict.repairs.is_empty());
+
+    let salvaged = WeakAuraDecoder::aura_from_data(data, true).unwrap();
+    assert_eq!(
+        salvaged.repairs,
+        vec![
+            "dropped child #1: not a table",
+            "dropped child #2: no ID",
+            "dropped a second child 'A'",
+            "dropped child 'Root': same ID as its group",
+            "moved 'B' to the top of the group: unknown parent",
+            "moved 'C' to the top of the group: its parents form a cycle",
+        ]
+    );
+    let aura = salvaged.aura;
+    let parent_of = |id: &str| {
+        aura.child_data
+            .iter()
+            .find(|c| c.get_path("id").and_then(LuaValue::as_str) == Some(id))
+            .and_then(|c| c.get_path("parent"))
+            .and_then(LuaValue::as_str)
+            .map(str::to_string)
+    };
+    assert_eq!(aura.children, vec!["A", "B", "C", "D", "E"]);
+    assert_eq!(parent_of("A").as_deref(), Some("Root"));
+    assert_eq!(parent_of("B").as_deref(), Some("Root"));
+    assert_eq!(parent_of("C").as_deref(), Some("Root"));
+    assert_eq!(parent_of("D").as_deref(), Some("C"));
+    assert_eq!(parent_of("E").as_deref(), Some("A"));
+}
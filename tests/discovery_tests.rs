@@ -0,0 +1,156 @@
+//! Tests for WoW installation discovery (Battle.net launcher data).
+
+use std::path::PathBuf;
+
+use weakauras_mass_import::discovery;
+
+/// Encode a length-delimited protobuf field
+fn length_delimited(field: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = vec![(field << 3) | 2, payload.len() as u8];
+    out.extend_from_slice(payload);
+    out
+}
+
+#[test]
+fn test_extract_install_paths_nested_message() {
+    let install = length_delimited(1, b"D:/Games/World of Warcraft");
+    let mut product = length_delimited(1, b"wow");
+    product.extend(length_delimited(2, &install));
+    product.extend([0x18, 0x01]); // varint field 3
+    let db = length_delimited(1, &product);
+
+    let paths = discovery::extract_install_paths(&db);
+    assert_eq!(paths, vec![PathBuf::from("D:/Games/World of Warcraft")]);
+}
+
+#[test]
+fn test_extract_install_paths_invalid_input() {
+    assert!(discovery::extract_install_paths(&[]).is_empty());
+    assert!(discovery::extract_install_paths(&[0xff, 0xff, 0xff]).is_empty());
+    assert!(discovery::extract_install_paths(b"not a protobuf message").is_empty());
+}
+
+#[test]
+fn test_parse_launcher_config_default_install_path() {
+    let config = r#"{
+        "Client": { "Install": { "DefaultInstallPath": "E:/Blizzard" } },
+        "Games": { "wow": { "Resumable": "false" } }
+    }"#;
+
+    let paths = discovery::parse_launcher_config(config);
+    assert!(paths.contains(&PathBuf::from("E:/Blizzard/World of Warcraft")));
+    assert!(paths.contains(&PathBuf::from("E:/Blizzard")));
+    assert!(discovery::parse_launcher_config("not json").is_empty());
+}
+
+#[test]
+fn test_parse_reg_query_output() {
+    let output = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\WOW6432Node\\Blizzard Entertainment\\World of Warcraft\r\n    InstallPath    REG_SZ    C:\\Program Files (x86)\\World of Warcraft\\_retail_\\\r\n\r\nEnd of search: 1 match(es) found.\r\n";
+
+    let paths = discovery::parse_reg_query_output(output);
+    assert_eq!(
+        paths,
+        vec![PathBuf::from(
+            "C:\\Program Files (x86)\\World of Warcraft\\_retail_\\"
+        )]
+    );
+}
+
+#[test]
+fn test_normalize_install_root_strips_flavor() {
+    assert_eq!(
+        discovery::normalize_install_root(&PathBuf::from("/games/World of Warcraft/_retail_/")),
+        PathBuf::from("/games/World of Warcraft")
+    );
+    assert_eq!(
+        discovery::normalize_install_root(&PathBuf::from("/games/World of Warcraft")),
+        PathBuf::from("/games/World of Warcraft")
+    );
+}
+
+#[test]
+fn test_is_wow_root() {
+    let dir = std::env::temp_dir().join("wa_discovery_is_wow_root");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    assert!(!discovery::is_wow_root(&dir));
+
+    std::fs::create_dir_all(dir.join("_classic_era_")).unwrap();
+    assert!(discovery::is_wow_root(&dir));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_weakauras_installed_for_saved_variables() {
+    let dir = std::env::temp_dir().join("wa_discovery_addon_installed");
+    let _ = std::fs::remove_dir_all(&dir);
+    let sv_path = dir.join("_retail_/WTF/Account/ACCOUNT1/SavedVariables/WeakAuras.lua");
+    std::fs::create_dir_all(sv_path.parent().unwrap()).unwrap();
+
+    assert_eq!(
+        discovery::flavor_dir_of(&sv_path),
+        Some(dir.join("_retail_"))
+    );
+    assert_eq!(discovery::weakauras_installed(&sv_path), Some(false));
+
+    // WoW under Wine may end up with differently cased folders
+    std::fs::create_dir_all(dir.join("_retail_/Interface/Addons/WeakAuras")).unwrap();
+    assert_eq!(discovery::weakauras_installed(&sv_path), Some(true));
+
+    // Files outside the WoW layout can't be checked
+    assert_eq!(discovery::flavor_dir_of(&dir.join("WeakAuras.lua")), None);
+    assert_eq!(
+        discovery::weakauras_installed(&dir.join("WeakAuras.lua")),
+        None
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_parse_steam_library_folders() {
+    let vdf = r#""libraryfolders"
+{
+	"0"
+	{
+		"path"		"/home/user/.local/share/Steam"
+		"label"		""
+		"apps"
+		{
+			"228980"		"0"
+		}
+	}
+	"1"
+	{
+		"path"		"/mnt/games/SteamLibrary"
+	}
+}"#;
+
+    let paths = discovery::parse_steam_library_folders(vdf);
+    assert_eq!(
+        paths,
+        vec![
+            PathBuf::from("/home/user/.local/share/Steam"),
+            PathBuf::from("/mnt/games/SteamLibrary"),
+        ]
+    );
+    assert!(discovery::parse_steam_library_folders("").is_empty());
+}
+
+#[test]
+fn test_parse_lutris_prefix() {
+    let yml = "game:\n  exe: drive_c/Program Files (x86)/Battle.net/Battle.net Launcher.exe\n  prefix: /home/user/Games/battlenet\nwine:\n  version: lutris-GE\n";
+    assert_eq!(
+        discovery::parse_lutris_prefix(yml),
+        Some(PathBuf::from("/home/user/Games/battlenet"))
+    );
+    assert_eq!(
+        discovery::parse_lutris_prefix("game:\n  prefix: \"/opt/wow prefix\"\n"),
+        Some(PathBuf::from("/opt/wow prefix"))
+    );
+    assert_eq!(
+        discovery::parse_lutris_prefix("game:\n  exe: wow.exe\n"),
+        None
+    );
+}
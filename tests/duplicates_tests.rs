@@ -0,0 +1,127 @@
+//! Tests for batch-level duplicate detection and the duplicate policies.
+
+mod common;
+
+use std::path::PathBuf;
+
+use weakauras_mass_import::api::{Importer, LuaValue, SavedVariablesManager, WeakAura};
+use weakauras_mass_import::duplicates::{dedup_auras, find_duplicates, DuplicatePolicy};
+
+use common::aura;
+
+fn load_of(aura: &WeakAura) -> &LuaValue {
+    &aura.data.as_table().unwrap()["load"]
+}
+
+fn temp_sv_path(name: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join("wa_duplicates_tests")
+        .join(name)
+        .join("WeakAuras.lua")
+}
+
+#[test]
+fn test_find_duplicates() {
+    let batch = [
+        aura("A", "1"),
+        aura("B", "1"),
+        aura("A", "2"),
+        aura("C", "1"),
+        aura("B", "1"),
+        aura("A", "3"),
+    ];
+    let found = find_duplicates(&batch);
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].aura_id, "A");
+    assert_eq!(found[0].positions, vec![0, 2, 5]);
+    assert!(!found[0].identical);
+    assert_eq!(found[1].aura_id, "B");
+    assert_eq!(found[1].positions, vec![1, 4]);
+    assert!(found[1].identical);
+
+    assert!(find_duplicates(&[aura("A", "1"), aura("B", "1")]).is_empty());
+}
+
+#[test]
+fn test_dropped_copies_per_policy() {
+    let found = find_duplicates(&[aura("A", "1"), aura("A", "2"), aura("A", "3")]);
+    let differing = &found[0];
+    assert_eq!(differing.dropped(DuplicatePolicy::KeepFirst), vec![1, 2]);
+    assert_eq!(differing.dropped(DuplicatePolicy::KeepLast), vec![0, 1]);
+    assert!(differing.dropped(DuplicatePolicy::Ask).is_empty());
+
+    // Identical copies are reduced to the first whatever the policy
+    let found = find_duplicates(&[aura("A", "1"), aura("A", "1")]);
+    for policy in DuplicatePolicy::ALL {
+        assert_eq!(found[0].dropped(policy), vec![1], "{}", policy);
+    }
+}
+
+#[test]
+fn test_dedup_auras() {
+    let batch = vec![aura("A", "1"), aura("B", "1"), aura("A", "2")];
+
+    let (kept, found) = dedup_auras(batch.clone(), DuplicatePolicy::KeepFirst).unwrap();
+    assert_eq!(found.len(), 1);
+    let ids: Vec<&str> = kept.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec!["A", "B"]);
+    assert_eq!(load_of(&kept[0]), &LuaValue::String("1".into()));
+
+    let (kept, _) = dedup_auras(batch.clone(), DuplicatePolicy::KeepLast).unwrap();
+    let ids: Vec<&str> = kept.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec!["B", "A"]);
+    assert_eq!(load_of(&kept[1]), &LuaValue::String("2".into()));
+
+    // Nobody to ask about differing copies
+    let err = dedup_auras(batch, DuplicatePolicy::Ask).unwrap_err();
+    assert!(err.to_string().contains("A"), "{}", err);
+
+    // Identical copies need no answer
+    let (kept, _) =
+        dedup_auras(vec![aura("A", "1"), aura("A", "1")], DuplicatePolicy::Ask).unwrap();
+    assert_eq!(kept.len(), 1);
+}
+
+#[test]
+fn test_importer_applies_duplicate_policy() {
+    let path = temp_sv_path("importer");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let result = Importer::new(&path)
+        .add_auras([aura("A", "first"), aura("A", "last")])
+        .duplicates(DuplicatePolicy::KeepLast)
+        .run()
+        .unwrap();
+    assert_eq!(result.added, vec!["A".to_string()]);
+    assert_eq!(result.duplicates, vec!["A".to_string()]);
+    assert!(result.summary().contains("1 duplicates dropped"));
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager.load().unwrap();
+    assert_eq!(
+        manager.displays["A"].as_table().unwrap()["load"],
+        LuaValue::String("last".into())
+    );
+
+    // Differing copies under `Ask` fail before anything is written
+    let _ = std::fs::remove_file(&path);
+    let result = Importer::new(&path)
+        .add_auras([aura("A", "first"), aura("A", "last")])
+        .duplicates(DuplicatePolicy::Ask)
+        .run();
+    assert!(result.is_err());
+    assert!(!path.exists());
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn test_duplicate_policy_serde_names() {
+    assert_eq!(
+        serde_json::to_string(&DuplicatePolicy::KeepFirst).unwrap(),
+        "\"keep_first\""
+    );
+    let policy: DuplicatePolicy = serde_json::from_str("\"keep_last\"").unwrap();
+    assert_eq!(policy, DuplicatePolicy::KeepLast);
+    assert_eq!(DuplicatePolicy::default(), DuplicatePolicy::KeepFirst);
+}
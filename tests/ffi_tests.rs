@@ -0,0 +1,68 @@
+//! Tests for the C ABI (run with `cargo test --features ffi`).
+#![cfg(feature = "ffi")]
+
+use std::ffi::{CStr, CString};
+
+use weakauras_mass_import::ffi::{
+    wa_decode, wa_detect_conflicts, wa_import_with_policy, wa_last_error, wa_string_free,
+};
+
+fn c(s: &str) -> CString {
+    CString::new(s).unwrap()
+}
+
+/// Take ownership of a returned string
+fn take(ptr: *mut std::ffi::c_char) -> String {
+    assert!(!ptr.is_null(), "unexpected error: {}", last_error());
+    let s = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+    unsafe { wa_string_free(ptr) };
+    s
+}
+
+fn last_error() -> String {
+    let ptr = wa_last_error();
+    assert!(!ptr.is_null());
+    unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_ffi_errors_set_last_error() {
+    let ptr = unsafe { wa_decode(c("not an import string").as_ptr()) };
+    assert!(ptr.is_null());
+    assert!(!last_error().is_empty());
+
+    let ptr = unsafe { wa_decode(std::ptr::null()) };
+    assert!(ptr.is_null());
+    assert!(last_error().contains("NULL"));
+
+    let path = c("missing/WeakAuras.lua");
+    let ptr = unsafe {
+        wa_import_with_policy(
+            path.as_ptr(),
+            c("[]").as_ptr(),
+            c(r#""not_a_policy""#).as_ptr(),
+            true,
+        )
+    };
+    assert!(ptr.is_null());
+    assert!(last_error().contains("policy_json"));
+}
+
+#[test]
+fn test_ffi_detect_and_import_json() {
+    let path = std::env::temp_dir()
+        .join("wa_ffi_tests")
+        .join("WeakAuras.lua");
+    let path = c(path.to_str().unwrap());
+
+    let json = take(unsafe { wa_detect_conflicts(path.as_ptr(), c("[]").as_ptr()) });
+    let detection: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(detection["conflicts"], serde_json::json!([]));
+
+    let policy = c(r#"{"update_categories": ["Trigger", "Load"]}"#);
+    let json = take(unsafe {
+        wa_import_with_policy(path.as_ptr(), c("[]").as_ptr(), policy.as_ptr(), true)
+    });
+    let result: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(result["added"], serde_json::json!([]));
+}
@@ -0,0 +1,112 @@
+//! Tests for exporting and re-applying aura fields as CSV.
+
+use std::path::PathBuf;
+
+use weakauras_mass_import::api::{FieldUpdateResult, SavedVariablesManager};
+use weakauras_mass_import::decoder::{LuaTable, LuaValue};
+
+fn manager() -> SavedVariablesManager {
+    let mut displays = LuaTable::new();
+    for (id, data) in [
+        (
+            "Buffs",
+            serde_json::json!({
+                "id": "Buffs", "regionType": "icon", "xOffset": 10, "yOffset": -5.5,
+                "desc": "Tracks buffs, mostly",
+                "load": { "use_class": false, "class": { "multi": { "DRUID": true, "MONK": true } } }
+            }),
+        ),
+        (
+            "Bar",
+            serde_json::json!({
+                "id": "Bar", "regionType": "aurabar",
+                "load": { "use_class_and_spec": true, "class_and_spec": { "single": 105 } }
+            }),
+        ),
+    ] {
+        displays.insert(id.to_string(), LuaValue::from_json(data));
+    }
+    let mut manager = SavedVariablesManager::new(PathBuf::from("test_field_sheet.lua"));
+    manager.displays = displays;
+    manager
+}
+
+#[test]
+fn test_export_fields_csv() {
+    let manager = manager();
+    assert_eq!(
+        manager.export_fields_csv(&[]),
+        "id,xOffset,yOffset,classes,specs,desc\n\
+         Buffs,10,-5.5,DRUID;MONK,,\"Tracks buffs, mostly\"\n\
+         Bar,0,0,,105,\n"
+    );
+    assert_eq!(
+        manager.export_fields_csv(&["Bar".to_string(), "Missing".to_string()]),
+        "id,xOffset,yOffset,classes,specs,desc\nBar,0,0,,105,\n"
+    );
+}
+
+#[test]
+fn test_apply_fields_csv_roundtrip() {
+    let mut manager = manager();
+    let sheet = manager.export_fields_csv(&[]);
+    let result = manager.apply_fields_csv(&sheet).unwrap();
+    assert_eq!(
+        result,
+        FieldUpdateResult {
+            updated: Vec::new(),
+            unchanged: 2,
+            missing: Vec::new(),
+        }
+    );
+
+    let edited = "id,yOffset,classes,desc\nBar,20,warrior; paladin,Health\nGone,1,,\n";
+    let result = manager.apply_fields_csv(edited).unwrap();
+    assert_eq!(result.updated, vec!["Bar"]);
+    assert_eq!(result.missing, vec!["Gone"]);
+    assert_eq!(
+        result.summary(),
+        "1 aura(s) updated, 0 unchanged, 1 not found"
+    );
+
+    let bar = &manager.displays["Bar"];
+    assert_eq!(bar.get_path("yOffset"), Some(&LuaValue::Number(20.0)));
+    assert_eq!(
+        bar.get_path("desc").and_then(LuaValue::as_str),
+        Some("Health")
+    );
+    assert_eq!(
+        bar.get_path("load.class.multi.PALADIN"),
+        Some(&LuaValue::Bool(true))
+    );
+    // Columns left out of the sheet keep their fields
+    assert_eq!(
+        bar.get_path("load.class_and_spec.single"),
+        Some(&LuaValue::Number(105.0))
+    );
+}
+
+#[test]
+fn test_apply_fields_csv_is_all_or_nothing() {
+    let mut manager = manager();
+    let before = manager.displays.clone();
+
+    let bad_number = "id,xOffset\nBuffs,3\nBar,left\n";
+    let error = manager.apply_fields_csv(bad_number).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("row 2: 'left' in xOffset is not a number"),
+        "{}",
+        error
+    );
+    assert!(manager
+        .apply_fields_csv("id,classes\nBuffs,PIRATE\n")
+        .is_err());
+    assert!(manager.apply_fields_csv("id,x\nBuffs,1\n").is_err());
+    assert!(manager.apply_fields_csv("xOffset\n1\n").is_err());
+    assert!(manager
+        .apply_fields_csv("id,xOffset\nBuffs,1\nBuffs,2\n")
+        .is_err());
+    assert_eq!(manager.displays, before);
+}
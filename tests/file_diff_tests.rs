@@ -0,0 +1,221 @@
+//! Tests for comparing two whole SavedVariables files.
+
+use std::path::PathBuf;
+
+use weakauras_mass_import::api::{SavedVariablesManager, UpdateCategory};
+
+const OLD: &str = r#"
+WeakAurasSaved = {
+	["dbVersion"] = 76,
+	["displays"] = {
+		["Kept"] = {
+			["id"] = "Kept",
+			["regionType"] = "icon",
+		},
+		["Edited"] = {
+			["id"] = "Edited",
+			["regionType"] = "text",
+			["displayText"] = "Hi",
+			["xOffset"] = 0,
+			["load"] = {
+				["use_combat"] = true,
+			},
+		},
+		["Moved"] = {
+			["id"] = "Moved",
+			["regionType"] = "icon",
+			["xOffset"] = 0,
+		},
+		["Gone"] = {
+			["id"] = "Gone",
+			["regionType"] = "aurabar",
+		},
+	},
+}
+"#;
+
+const NEW: &str = r#"
+WeakAurasSaved = {
+	["dbVersion"] = 76,
+	["displays"] = {
+		["Kept"] = {
+			["id"] = "Kept",
+			["regionType"] = "icon",
+		},
+		["Edited"] = {
+			["id"] = "Edited",
+			["regionType"] = "text",
+			["displayText"] = "Hello",
+			["xOffset"] = 20,
+			["load"] = {
+				["use_combat"] = false,
+			},
+		},
+		["Moved"] = {
+			["id"] = "Moved",
+			["regionType"] = "icon",
+			["xOffset"] = 50,
+		},
+		["Fresh"] = {
+			["id"] = "Fresh",
+			["regionType"] = "icon",
+		},
+	},
+}
+"#;
+
+fn temp_files(name: &str, old: &str, new: &str) -> (PathBuf, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("wa_file_diff_{}", name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let (old_path, new_path) = (dir.join("old.lua"), dir.join("new.lua"));
+    std::fs::write(&old_path, old).unwrap();
+    std::fs::write(&new_path, new).unwrap();
+    (old_path, new_path)
+}
+
+#[test]
+fn test_diff_files_sorts_auras_into_added_removed_modified() {
+    let (old, new) = temp_files("sets", OLD, NEW);
+    let diff = SavedVariablesManager::diff_files(&old, &new).unwrap();
+
+    assert_eq!(diff.added, vec!["Fresh".to_string()]);
+    assert_eq!(diff.removed, vec!["Gone".to_string()]);
+    let modified: Vec<&str> = diff.modified.iter().map(|aura| aura.id.as_str()).collect();
+    assert_eq!(modified, vec!["Edited", "Moved"]);
+    assert_eq!(diff.unchanged, 1);
+    assert_eq!(
+        diff.summary(),
+        "1 added, 1 removed, 2 modified, 1 unchanged"
+    );
+}
+
+#[test]
+fn test_diff_files_lists_changed_fields_by_category() {
+    let (old, new) = temp_files("fields", OLD, NEW);
+    let diff = SavedVariablesManager::diff_files(&old, &new).unwrap();
+
+    let edited = &diff.modified[0];
+    assert_eq!(
+        edited.changes,
+        vec![
+            (UpdateCategory::Display, vec!["displayText".to_string()]),
+            (UpdateCategory::Load, vec!["load".to_string()]),
+            (UpdateCategory::Anchor, vec!["xOffset".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn test_category_stats_count_auras_most_changed_first() {
+    let (old, new) = temp_files("stats", OLD, NEW);
+    let diff = SavedVariablesManager::diff_files(&old, &new).unwrap();
+
+    let stats = diff.category_stats();
+    assert_eq!(stats[0].category, UpdateCategory::Anchor);
+    assert_eq!((stats[0].auras, stats[0].fields), (2, 2));
+    let rest: Vec<(UpdateCategory, usize)> = stats[1..]
+        .iter()
+        .map(|stat| (stat.category, stat.auras))
+        .collect();
+    assert_eq!(
+        rest,
+        vec![(UpdateCategory::Display, 1), (UpdateCategory::Load, 1)]
+    );
+}
+
+#[test]
+fn test_diff_of_identical_files_is_empty() {
+    let (old, new) = temp_files("identical", OLD, OLD);
+    let diff = SavedVariablesManager::diff_files(&old, &new).unwrap();
+
+    assert!(diff.is_empty());
+    assert_eq!(diff.unchanged, 4);
+    assert!(diff.category_stats().is_empty());
+}
+
+#[test]
+fn test_diff_text_and_json_reports() {
+    let (old, new) = temp_files("reports", OLD, NEW);
+    let diff = SavedVariablesManager::diff_files(&old, &new).unwrap();
+
+    let text = diff.to_text();
+    assert!(text.contains("  + Fresh\n"));
+    assert!(text.contains("  - Gone\n"));
+    assert!(text.contains("  ~ Moved\n      Anchor: xOffset\n"));
+
+    let json: serde_json::Value = serde_json::from_str(&diff.to_json().unwrap()).unwrap();
+    assert_eq!(json["added"][0], "Fresh");
+    assert_eq!(json["unchanged"], 1);
+}
+
+#[test]
+fn test_diff_files_fails_on_missing_file() {
+    let (old, _) = temp_files("missing", OLD, NEW);
+    let missing = old.with_file_name("nope.lua");
+    assert!(SavedVariablesManager::diff_files(&old, &missing).is_err());
+}
+
+const GROUPED: &str = r#"
+WeakAurasSaved = {
+	["displays"] = {
+		["Group"] = {
+			["id"] = "Group",
+			["regionType"] = "dynamicgroup",
+			["controlledChildren"] = { "A", "B" },
+		},
+		["Other"] = {
+			["id"] = "Other",
+			["regionType"] = "group",
+			["controlledChildren"] = { "C" },
+		},
+		["A"] = { ["id"] = "A", ["parent"] = "Group" },
+		["B"] = { ["id"] = "B", ["parent"] = "Group" },
+		["C"] = { ["id"] = "C", ["parent"] = "Other" },
+	},
+}
+"#;
+
+const REGROUPED: &str = r#"
+WeakAurasSaved = {
+	["displays"] = {
+		["Group"] = {
+			["id"] = "Group",
+			["regionType"] = "dynamicgroup",
+			["controlledChildren"] = { "B", "A" },
+		},
+		["Other"] = {
+			["id"] = "Other",
+			["regionType"] = "group",
+			["controlledChildren"] = {},
+		},
+		["A"] = { ["id"] = "A", ["parent"] = "Group" },
+		["B"] = { ["id"] = "B", ["parent"] = "Group" },
+		["C"] = { ["id"] = "C" },
+	},
+}
+"#;
+
+#[test]
+fn test_diff_files_reports_moves_and_reordered_groups() {
+    let (old, new) = temp_files("hierarchy", GROUPED, REGROUPED);
+    let diff = SavedVariablesManager::diff_files(&old, &new).unwrap();
+
+    assert!(diff.modified.is_empty());
+    assert!(!diff.is_empty());
+    assert_eq!(diff.moved.len(), 1);
+    assert_eq!(diff.moved[0].id, "C");
+    assert_eq!(diff.moved[0].from.as_deref(), Some("Other"));
+    assert_eq!(diff.moved[0].to, None);
+    // "Other" lost its child: that shows as the move of "C", not as a reorder
+    assert_eq!(diff.reordered, vec!["Group".to_string()]);
+    assert_eq!(diff.unchanged, 3);
+    assert_eq!(
+        diff.summary(),
+        "0 added, 0 removed, 0 modified, 1 moved, 1 reordered, 3 unchanged"
+    );
+
+    let text = diff.to_text();
+    assert!(text.contains("  > C: Other -> top level\n"));
+    assert!(text.contains("  = Group\n"));
+}
@@ -0,0 +1,82 @@
+
+WeakAurasSaved = {
+	["dbVersion"] = 76,
+	["displays"] = {
+		["Cooldown Bar 2"] = {
+			["barColor"] = {
+				1, -- [1]
+				0.49, -- [2]
+				0.04, -- [3]
+				1, -- [4]
+			},
+			["height"] = 18,
+			["id"] = "Cooldown Bar 2",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+			["parent"] = "Raid Cooldowns",
+			["regionType"] = "aurabar",
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["event"] = "Cooldown Progress (Spell)",
+						["spellName"] = 33206,
+						["type"] = "spell",
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+			},
+			["uid"] = "456789AbCdE",
+			["width"] = 220,
+		},
+		["Raid Cooldowns"] = {
+			["controlledChildren"] = {
+				"Raid Cooldowns - Healers", -- [1]
+				"Cooldown Bar 2", -- [2]
+			},
+			["id"] = "Raid Cooldowns",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+			["regionType"] = "group",
+			["semver"] = "1.0.3",
+			["uid"] = "RaidCds0000",
+			["version"] = 3,
+		},
+		["Raid Cooldowns - Healers"] = {
+			["controlledChildren"] = {
+				"Tranquility", -- [1]
+			},
+			["id"] = "Raid Cooldowns - Healers",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+			["parent"] = "Raid Cooldowns",
+			["regionType"] = "dynamicgroup",
+			["uid"] = "RaidCds0001",
+		},
+		["Tranquility"] = {
+			["height"] = 16,
+			["id"] = "Tranquility",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+			["parent"] = "Raid Cooldowns - Healers",
+			["regionType"] = "aurabar",
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["event"] = "Cooldown Progress (Spell)",
+						["spellName"] = 740,
+						["type"] = "spell",
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+			},
+			["uid"] = "RaidCds0002",
+			["width"] = 180,
+		},
+	},
+}
@@ -0,0 +1,216 @@
+
+WeakAurasSaved = {
+	["dynamicIconCache"] = {
+	},
+	["editor_tab_spaces"] = 4,
+	["login_squelch_time"] = 10,
+	["registered"] = {
+	},
+	["minimap"] = {
+		["minimapPos"] = 201.5,
+		["hide"] = false,
+	},
+	["lastUpgrade"] = 1712345678,
+	["dbVersion"] = 76,
+	["lastArchiveClear"] = 1712300000,
+	["historyCutoff"] = 730,
+	["migrationCutoff"] = 730,
+	["displays"] = {
+		["Player Buffs"] = {
+			["iconSource"] = -1,
+			["xOffset"] = -240,
+			["yOffset"] = 120.5,
+			["anchorPoint"] = "CENTER",
+			["cooldownSwipe"] = true,
+			["cooldownEdge"] = false,
+			["icon"] = true,
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["type"] = "aura2",
+						["auranames"] = {
+							"Power Word: Fortitude", -- [1]
+							"21562", -- [2]
+						},
+						["unit"] = "player",
+						["debuffType"] = "HELPFUL",
+						["matchesShowOn"] = "showAlways",
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+				["activeTriggerMode"] = -10,
+			},
+			["internalVersion"] = 76,
+			["load"] = {
+				["class"] = {
+					["single"] = "PRIEST",
+					["multi"] = {
+					},
+				},
+				["size"] = {
+					["multi"] = {
+						["party"] = true,
+						["raid"] = true,
+					},
+				},
+				["use_class"] = true,
+			},
+			["regionType"] = "icon",
+			["color"] = {
+				1, -- [1]
+				1, -- [2]
+				1, -- [3]
+				1, -- [4]
+			},
+			["uid"] = "aB3dE5fG7hI",
+			["width"] = 40,
+			["height"] = 40,
+			["id"] = "Player Buffs",
+			["frameStrata"] = 1,
+			["conditions"] = {
+			},
+			["information"] = {
+			},
+		},
+		["Cooldown Bars"] = {
+			["grow"] = "DOWN",
+			["controlledChildren"] = {
+				"Cooldown Bar 1", -- [1]
+				"Cooldown Bar 2", -- [2]
+			},
+			["xOffset"] = 0,
+			["yOffset"] = -180,
+			["space"] = 2,
+			["sort"] = "none",
+			["regionType"] = "dynamicgroup",
+			["uid"] = "Jk9LmN0pQrS",
+			["id"] = "Cooldown Bars",
+			["internalVersion"] = 76,
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["type"] = "aura2",
+						["unit"] = "player",
+						["debuffType"] = "HELPFUL",
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+			},
+			["load"] = {
+				["class"] = {
+					["multi"] = {
+					},
+				},
+			},
+		},
+		["Cooldown Bar 1"] = {
+			["parent"] = "Cooldown Bars",
+			["regionType"] = "aurabar",
+			["uid"] = "tUvWxYz0123",
+			["id"] = "Cooldown Bar 1",
+			["width"] = 200,
+			["height"] = 18,
+			["barColor"] = {
+				0.26, -- [1]
+				0.55, -- [2]
+				1, -- [3]
+				1, -- [4]
+			},
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["type"] = "spell",
+						["event"] = "Cooldown Progress (Spell)",
+						["spellName"] = 17,
+						["use_genericShowOn"] = true,
+						["genericShowOn"] = "showAlways",
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+				["disjunctive"] = "any",
+			},
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+		["Cooldown Bar 2"] = {
+			["parent"] = "Raid Cooldowns",
+			["regionType"] = "aurabar",
+			["uid"] = "456789AbCdE",
+			["id"] = "Cooldown Bar 2",
+			["width"] = 200,
+			["height"] = 18,
+			["barColor"] = {
+				1, -- [1]
+				0.49, -- [2]
+				0.04, -- [3]
+				1, -- [4]
+			},
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["type"] = "spell",
+						["event"] = "Cooldown Progress (Spell)",
+						["spellName"] = 33206,
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+			},
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+		["Raid Cooldowns"] = {
+			["controlledChildren"] = {
+				"Raid Cooldowns - Healers", -- [1]
+				"Cooldown Bar 2", -- [2]
+			},
+			["regionType"] = "group",
+			["uid"] = "RaidCds0000",
+			["id"] = "Raid Cooldowns",
+			["version"] = 3,
+			["semver"] = "1.0.3",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+		["Raid Cooldowns - Healers"] = {
+			["controlledChildren"] = {
+				"Tranquility", -- [1]
+			},
+			["parent"] = "Raid Cooldowns",
+			["regionType"] = "dynamicgroup",
+			["uid"] = "RaidCds0001",
+			["id"] = "Raid Cooldowns - Healers",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+		["Tranquility"] = {
+			["parent"] = "Raid Cooldowns - Healers",
+			["regionType"] = "aurabar",
+			["uid"] = "RaidCds0002",
+			["id"] = "Tranquility",
+			["width"] = 180,
+			["height"] = 16,
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["type"] = "spell",
+						["event"] = "Cooldown Progress (Spell)",
+						["spellName"] = 740,
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+			},
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+	},
+}
@@ -0,0 +1,120 @@
+
+WeakAurasSaved = {
+	["dbVersion"] = 76,
+	["features"] = {
+	},
+	["displays"] = {
+		["Anchor: Player"] = {
+			["customText"] = "function()\n    return \"[\" .. UnitName(\"player\") .. \"]\"\nend",
+			["displayText"] = "%p — Spieler ÄÖÜ 🐉",
+			["id"] = "Anchor: Player",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+			["parent"] = "UI Pack - Anchors",
+			["regionType"] = "text",
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["check"] = "update",
+						["custom"] = "function() return true end",
+						["custom_type"] = "status",
+						["type"] = "custom",
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+			},
+			["uid"] = "PackAnch001",
+			["xOffset"] = -312.25,
+			["yOffset"] = -0.000015,
+		},
+		["Anchor: Target"] = {
+			["anchorFrameFrame"] = "WeakAuras:Anchor: Player",
+			["anchorFrameType"] = "SELECTFRAME",
+			["displayText"] = "",
+			["id"] = "Anchor: Target",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+			["parent"] = "UI Pack - Anchors",
+			["regionType"] = "text",
+			["uid"] = "PackAnch002",
+			["xOffset"] = 312.25,
+			["yOffset"] = 0,
+		},
+		["Combo Points"] = {
+			["duration"] = math.huge,
+			["id"] = "Combo Points",
+			["internalVersion"] = 76,
+			["load"] = {
+				["use_never"] = false,
+			},
+			["minValue"] = -math.huge,
+			["parent"] = "UI Pack - Resources",
+			["ratio"] = (0/0),
+			["regionType"] = "icon",
+			["spellIds"] = {
+				31, -- [1]
+				0.5, -- [2]
+			},
+			["stacksFlags"] = {
+				true, -- [1]
+				false, -- [2]
+				nil, -- [3]
+				true, -- [4]
+			},
+			["thresholds"] = {
+				["1"] = 1,
+				["2"] = 3,
+				["5"] = 5,
+			},
+			["uid"] = "PackRes0001",
+		},
+		["UI Pack"] = {
+			["controlledChildren"] = {
+				"UI Pack - Anchors", -- [1]
+				"UI Pack - Resources", -- [2]
+			},
+			["desc"] = "Anonymized fixture.\nSecond line with a tab\tand \"quotes\" and a backslash \\.",
+			["id"] = "UI Pack",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+			["regionType"] = "group",
+			["selfPoint"] = "CENTER",
+			["semver"] = "1.4.2",
+			["uid"] = "PackRoot000",
+			["url"] = "https://wago.io/AbCdEfGhI/12",
+			["version"] = 12,
+		},
+		["UI Pack - Anchors"] = {
+			["controlledChildren"] = {
+				"Anchor: Player", -- [1]
+				"Anchor: Target", -- [2]
+			},
+			["id"] = "UI Pack - Anchors",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+			["parent"] = "UI Pack",
+			["regionType"] = "group",
+			["uid"] = "PackAnch000",
+		},
+		["UI Pack - Resources"] = {
+			["controlledChildren"] = {
+				"Combo Points", -- [1]
+			},
+			["id"] = "UI Pack - Resources",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+			["parent"] = "UI Pack",
+			["regionType"] = "dynamicgroup",
+			["sortHybridTable"] = {
+				["Combo Points"] = false,
+			},
+			["uid"] = "PackRes0000",
+		},
+	},
+}
@@ -0,0 +1,150 @@
+{
+	["Player Buffs"] = {
+		["iconSource"] = -1,
+		["xOffset"] = -240,
+		["yOffset"] = 120.5,
+		["anchorPoint"] = "CENTER",
+		["cooldownSwipe"] = true,
+		["cooldownEdge"] = false,
+		["icon"] = true,
+		["triggers"] = {
+			{
+				["trigger"] = {
+					["type"] = "aura2",
+					["auranames"] = {
+						"Power Word: Fortitude", -- [1]
+						"21562", -- [2]
+					},
+					["unit"] = "player",
+					["debuffType"] = "HELPFUL",
+					["matchesShowOn"] = "showAlways",
+				},
+				["untrigger"] = {
+				},
+			}, -- [1]
+			["activeTriggerMode"] = -10,
+		},
+		["internalVersion"] = 76,
+		["load"] = {
+			["class"] = {
+				["single"] = "PRIEST",
+				["multi"] = {
+				},
+			},
+			["size"] = {
+				["multi"] = {
+					["party"] = true,
+					["raid"] = true,
+				},
+			},
+			["use_class"] = true,
+		},
+		["regionType"] = "icon",
+		["color"] = {
+			1, -- [1]
+			1, -- [2]
+			1, -- [3]
+			1, -- [4]
+		},
+		["uid"] = "aB3dE5fG7hI",
+		["width"] = 40,
+		["height"] = 40,
+		["id"] = "Player Buffs",
+		["frameStrata"] = 1,
+		["conditions"] = {
+		},
+		["information"] = {
+		},
+	},
+	["Cooldown Bars"] = {
+		["grow"] = "DOWN",
+		["controlledChildren"] = {
+			"Cooldown Bar 1", -- [1]
+			"Cooldown Bar 2", -- [2]
+		},
+		["xOffset"] = 0,
+		["yOffset"] = -180,
+		["space"] = 2,
+		["sort"] = "none",
+		["regionType"] = "dynamicgroup",
+		["uid"] = "Jk9LmN0pQrS",
+		["id"] = "Cooldown Bars",
+		["internalVersion"] = 76,
+		["triggers"] = {
+			{
+				["trigger"] = {
+					["type"] = "aura2",
+					["unit"] = "player",
+					["debuffType"] = "HELPFUL",
+				},
+				["untrigger"] = {
+				},
+			}, -- [1]
+		},
+		["load"] = {
+			["class"] = {
+				["multi"] = {
+				},
+			},
+		},
+	},
+	["Cooldown Bar 1"] = {
+		["parent"] = "Cooldown Bars",
+		["regionType"] = "aurabar",
+		["uid"] = "tUvWxYz0123",
+		["id"] = "Cooldown Bar 1",
+		["width"] = 200,
+		["height"] = 18,
+		["barColor"] = {
+			0.26, -- [1]
+			0.55, -- [2]
+			1, -- [3]
+			1, -- [4]
+		},
+		["triggers"] = {
+			{
+				["trigger"] = {
+					["type"] = "spell",
+					["event"] = "Cooldown Progress (Spell)",
+					["spellName"] = 17,
+					["use_genericShowOn"] = true,
+					["genericShowOn"] = "showAlways",
+				},
+				["untrigger"] = {
+				},
+			}, -- [1]
+			["disjunctive"] = "any",
+		},
+		["internalVersion"] = 76,
+		["load"] = {
+		},
+	},
+	["Cooldown Bar 2"] = {
+		["parent"] = "Cooldown Bars",
+		["regionType"] = "aurabar",
+		["uid"] = "456789AbCdE",
+		["id"] = "Cooldown Bar 2",
+		["width"] = 200,
+		["height"] = 18,
+		["barColor"] = {
+			1, -- [1]
+			0.49, -- [2]
+			0.04, -- [3]
+			1, -- [4]
+		},
+		["triggers"] = {
+			{
+				["trigger"] = {
+					["type"] = "spell",
+					["event"] = "Cooldown Progress (Spell)",
+					["spellName"] = 33206,
+				},
+				["untrigger"] = {
+				},
+			}, -- [1]
+		},
+		["internalVersion"] = 76,
+		["load"] = {
+		},
+	},
+}
\ No newline at end of file
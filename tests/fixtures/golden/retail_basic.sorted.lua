@@ -0,0 +1,168 @@
+
+WeakAurasSaved = {
+	["dbVersion"] = 76,
+	["dynamicIconCache"] = {
+	},
+	["editor_tab_spaces"] = 4,
+	["historyCutoff"] = 730,
+	["lastArchiveClear"] = 1712300000,
+	["lastUpgrade"] = 1712345678,
+	["login_squelch_time"] = 10,
+	["migrationCutoff"] = 730,
+	["minimap"] = {
+		["hide"] = false,
+		["minimapPos"] = 201.5,
+	},
+	["registered"] = {
+	},
+	["displays"] = {
+		["Cooldown Bar 1"] = {
+			["barColor"] = {
+				0.26, -- [1]
+				0.55, -- [2]
+				1, -- [3]
+				1, -- [4]
+			},
+			["height"] = 18,
+			["id"] = "Cooldown Bar 1",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+			["parent"] = "Cooldown Bars",
+			["regionType"] = "aurabar",
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["event"] = "Cooldown Progress (Spell)",
+						["genericShowOn"] = "showAlways",
+						["spellName"] = 17,
+						["type"] = "spell",
+						["use_genericShowOn"] = true,
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+				["disjunctive"] = "any",
+			},
+			["uid"] = "tUvWxYz0123",
+			["width"] = 200,
+		},
+		["Cooldown Bar 2"] = {
+			["barColor"] = {
+				1, -- [1]
+				0.49, -- [2]
+				0.04, -- [3]
+				1, -- [4]
+			},
+			["height"] = 18,
+			["id"] = "Cooldown Bar 2",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+			["parent"] = "Cooldown Bars",
+			["regionType"] = "aurabar",
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["event"] = "Cooldown Progress (Spell)",
+						["spellName"] = 33206,
+						["type"] = "spell",
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+			},
+			["uid"] = "456789AbCdE",
+			["width"] = 200,
+		},
+		["Cooldown Bars"] = {
+			["controlledChildren"] = {
+				"Cooldown Bar 1", -- [1]
+				"Cooldown Bar 2", -- [2]
+			},
+			["grow"] = "DOWN",
+			["id"] = "Cooldown Bars",
+			["internalVersion"] = 76,
+			["load"] = {
+				["class"] = {
+					["multi"] = {
+					},
+				},
+			},
+			["regionType"] = "dynamicgroup",
+			["sort"] = "none",
+			["space"] = 2,
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["debuffType"] = "HELPFUL",
+						["type"] = "aura2",
+						["unit"] = "player",
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+			},
+			["uid"] = "Jk9LmN0pQrS",
+			["xOffset"] = 0,
+			["yOffset"] = -180,
+		},
+		["Player Buffs"] = {
+			["anchorPoint"] = "CENTER",
+			["color"] = {
+				1, -- [1]
+				1, -- [2]
+				1, -- [3]
+				1, -- [4]
+			},
+			["conditions"] = {
+			},
+			["cooldownEdge"] = false,
+			["cooldownSwipe"] = true,
+			["frameStrata"] = 1,
+			["height"] = 40,
+			["icon"] = true,
+			["iconSource"] = -1,
+			["id"] = "Player Buffs",
+			["information"] = {
+			},
+			["internalVersion"] = 76,
+			["load"] = {
+				["class"] = {
+					["multi"] = {
+					},
+					["single"] = "PRIEST",
+				},
+				["size"] = {
+					["multi"] = {
+						["party"] = true,
+						["raid"] = true,
+					},
+				},
+				["use_class"] = true,
+			},
+			["regionType"] = "icon",
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["auranames"] = {
+							"Power Word: Fortitude", -- [1]
+							"21562", -- [2]
+						},
+						["debuffType"] = "HELPFUL",
+						["matchesShowOn"] = "showAlways",
+						["type"] = "aura2",
+						["unit"] = "player",
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+				["activeTriggerMode"] = -10,
+			},
+			["uid"] = "aB3dE5fG7hI",
+			["width"] = 40,
+			["xOffset"] = -240,
+			["yOffset"] = 120.5,
+		},
+	},
+}
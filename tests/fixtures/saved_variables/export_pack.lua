@@ -0,0 +1,82 @@
+
+WeakAurasSaved = {
+	["dbVersion"] = 76,
+	["displays"] = {
+		["Raid Cooldowns"] = {
+			["controlledChildren"] = {
+				"Raid Cooldowns - Healers", -- [1]
+				"Cooldown Bar 2", -- [2]
+			},
+			["regionType"] = "group",
+			["uid"] = "RaidCds0000",
+			["id"] = "Raid Cooldowns",
+			["version"] = 3,
+			["semver"] = "1.0.3",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+		["Raid Cooldowns - Healers"] = {
+			["controlledChildren"] = {
+				"Tranquility", -- [1]
+			},
+			["parent"] = "Raid Cooldowns",
+			["regionType"] = "dynamicgroup",
+			["uid"] = "RaidCds0001",
+			["id"] = "Raid Cooldowns - Healers",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+		["Tranquility"] = {
+			["parent"] = "Raid Cooldowns - Healers",
+			["regionType"] = "aurabar",
+			["uid"] = "RaidCds0002",
+			["id"] = "Tranquility",
+			["width"] = 180,
+			["height"] = 16,
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["type"] = "spell",
+						["event"] = "Cooldown Progress (Spell)",
+						["spellName"] = 740,
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+			},
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+		["Cooldown Bar 2"] = {
+			["parent"] = "Raid Cooldowns",
+			["regionType"] = "aurabar",
+			["uid"] = "456789AbCdE",
+			["id"] = "Cooldown Bar 2",
+			["width"] = 220,
+			["height"] = 18,
+			["barColor"] = {
+				1, -- [1]
+				0.49, -- [2]
+				0.04, -- [3]
+				1, -- [4]
+			},
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["type"] = "spell",
+						["event"] = "Cooldown Progress (Spell)",
+						["spellName"] = 33206,
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+			},
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+	},
+}
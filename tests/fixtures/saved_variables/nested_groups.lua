@@ -0,0 +1,122 @@
+
+WeakAurasSaved = {
+	["dbVersion"] = 76,
+	["features"] = {
+	},
+	["displays"] = {
+		["UI Pack"] = {
+			["controlledChildren"] = {
+				"UI Pack - Anchors", -- [1]
+				"UI Pack - Resources", -- [2]
+			},
+			["regionType"] = "group",
+			["uid"] = "PackRoot000",
+			["id"] = "UI Pack",
+			["url"] = "https://wago.io/AbCdEfGhI/12",
+			["version"] = 12,
+			["semver"] = "1.4.2",
+			["desc"] = "Anonymized fixture.\nSecond line with a tab\tand \"quotes\" and a backslash \\.",
+			["internalVersion"] = 76,
+			["selfPoint"] = "CENTER",
+			["load"] = {
+			},
+		},
+		["UI Pack - Anchors"] = {
+			["controlledChildren"] = {
+				"Anchor: Player", -- [1]
+				"Anchor: Target", -- [2]
+			},
+			["parent"] = "UI Pack",
+			["regionType"] = "group",
+			["uid"] = "PackAnch000",
+			["id"] = "UI Pack - Anchors",
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+		["Anchor: Player"] = {
+			["parent"] = "UI Pack - Anchors",
+			["regionType"] = "text",
+			["uid"] = "PackAnch001",
+			["id"] = "Anchor: Player",
+			["displayText"] = "%p — Spieler ÄÖÜ 🐉",
+			["xOffset"] = -312.25,
+			["yOffset"] = -1.5e-05,
+			["customText"] = [[function()
+    return "[" .. UnitName("player") .. "]"
+end]],
+			["triggers"] = {
+				{
+					["trigger"] = {
+						["type"] = "custom",
+						["custom_type"] = "status",
+						["check"] = "update",
+						["custom"] = "function() return true end",
+					},
+					["untrigger"] = {
+					},
+				}, -- [1]
+			},
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+		["Anchor: Target"] = {
+			["parent"] = "UI Pack - Anchors",
+			["regionType"] = "text",
+			["uid"] = "PackAnch002",
+			["id"] = "Anchor: Target",
+			["displayText"] = "",
+			["anchorFrameType"] = "SELECTFRAME",
+			["anchorFrameFrame"] = "WeakAuras:Anchor: Player",
+			["xOffset"] = 312.25,
+			["yOffset"] = 0,
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+		["UI Pack - Resources"] = {
+			["controlledChildren"] = {
+				"Combo Points", -- [1]
+			},
+			["parent"] = "UI Pack",
+			["regionType"] = "dynamicgroup",
+			["uid"] = "PackRes0000",
+			["id"] = "UI Pack - Resources",
+			["sortHybridTable"] = {
+				["Combo Points"] = false,
+			},
+			["internalVersion"] = 76,
+			["load"] = {
+			},
+		},
+		["Combo Points"] = {
+			["parent"] = "UI Pack - Resources",
+			["regionType"] = "icon",
+			["uid"] = "PackRes0001",
+			["id"] = "Combo Points",
+			["duration"] = math.huge,
+			["minValue"] = -math.huge,
+			["ratio"] = (0/0),
+			["stacksFlags"] = {
+				true, -- [1]
+				false, -- [2]
+				nil, -- [3]
+				true, -- [4]
+			},
+			["thresholds"] = {
+				[1] = 1,
+				[2] = 3,
+				[5] = 5,
+			},
+			["spellIds"] = {
+				0x1F, -- [1]
+				.5, -- [2]
+			},
+			["internalVersion"] = 76,
+			["load"] = {
+				["use_never"] = false,
+			},
+		},
+	},
+}
@@ -0,0 +1,175 @@
+//! Golden-output tests over the anonymized SavedVariables corpus in
+//! `tests/fixtures/saved_variables`.
+//!
+//! Run with `UPDATE_GOLDEN=1` to rewrite `tests/fixtures/golden` after an
+//! intended output change, then review the diff.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use weakauras_mass_import::api::{Importer, ResolutionPolicy};
+use weakauras_mass_import::decoder::LuaValue;
+use weakauras_mass_import::lua_parser::LuaParser;
+use weakauras_mass_import::saved_variables::SavedVariablesManager;
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+}
+
+/// Every SavedVariables fixture, by file name
+fn saved_variables_fixtures() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(fixtures_dir().join("saved_variables"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lua"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no fixtures found");
+    paths
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem().unwrap().to_string_lossy().into_owned()
+}
+
+/// Compare `actual` with the golden file `name`, or write it when
+/// `UPDATE_GOLDEN` is set
+fn assert_golden(name: &str, actual: &str) {
+    let path = fixtures_dir().join("golden").join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("{}: {} (run with UPDATE_GOLDEN=1)", path.display(), e))
+        .replace("\r\n", "\n");
+    assert!(
+        expected == actual,
+        "{} differs from the output (run with UPDATE_GOLDEN=1 to update):\n{}",
+        path.display(),
+        actual
+    );
+}
+
+/// Copy a fixture into a fresh temp directory to import into
+fn temp_copy(fixture: &str, test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("wa_fixture_tests").join(test);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+    std::fs::copy(fixtures_dir().join("saved_variables").join(fixture), &path).unwrap();
+    path
+}
+
+#[test]
+fn test_fixtures_parse_and_serialize_round_trip() {
+    for path in saved_variables_fixtures() {
+        let content = std::fs::read_to_string(&path).unwrap();
+        let saved = LuaParser::parse(&content)
+            .unwrap_or_else(|e| panic!("{} does not parse: {}", path.display(), e));
+        assert!(
+            !saved.displays.is_empty(),
+            "{} has no displays",
+            path.display()
+        );
+
+        let displays = LuaValue::Table(Arc::new(saved.displays.clone()));
+        let serialized = LuaParser::serialize(&displays, 0);
+        assert_golden(&format!("{}.displays.lua", stem(&path)), &serialized);
+        // NaN never equals itself, so compare the text of the second pass
+        let reparsed = LuaParser::parse_value(&serialized).unwrap();
+        assert_eq!(LuaParser::serialize(&reparsed, 0), serialized);
+
+        // Each display span holds exactly that display's table
+        for (id, span) in &saved.display_spans {
+            let value = LuaParser::parse_value(&content[span.clone()]).unwrap();
+            assert_eq!(
+                LuaParser::serialize(&value, 0),
+                LuaParser::serialize(&saved.displays[id], 0),
+                "span of '{}' in {}",
+                id,
+                path.display()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_fixtures_generate_stable_files() {
+    for path in saved_variables_fixtures() {
+        let mut manager = SavedVariablesManager::new(path.clone());
+        manager.load().unwrap();
+        manager.sort_keys = true;
+        let generated = manager.generate_lua();
+        assert_golden(&format!("{}.sorted.lua", stem(&path)), &generated);
+
+        // Loading the generated file and writing it again changes nothing
+        let copy = temp_copy(&path.file_name().unwrap().to_string_lossy(), &stem(&path));
+        std::fs::write(&copy, &generated).unwrap();
+        let mut reloaded = SavedVariablesManager::new(copy.clone());
+        reloaded.load().unwrap();
+        reloaded.sort_keys = true;
+        assert_eq!(reloaded.generate_lua(), generated);
+        let tree_ids = |manager: &SavedVariablesManager| -> Vec<String> {
+            let tree = manager.get_aura_tree();
+            tree.iter()
+                .flat_map(|node| node.ids())
+                .map(str::to_string)
+                .collect()
+        };
+        assert_eq!(tree_ids(&reloaded), tree_ids(&manager));
+
+        let _ = std::fs::remove_dir_all(copy.parent().unwrap());
+    }
+}
+
+#[test]
+fn test_fixture_import_simulation() {
+    let mut source =
+        SavedVariablesManager::new(fixtures_dir().join("saved_variables/export_pack.lua"));
+    source.load().unwrap();
+    let auras = source.extract_auras(&["Raid Cooldowns".to_string()]);
+    assert_eq!(auras.len(), 1);
+    assert_eq!(auras[0].child_data.len(), 3);
+
+    // "Cooldown Bar 2" already exists in another group; it only moves into
+    // the pack when the policy takes the incoming aura
+    let policies = [
+        ("skip", ResolutionPolicy::SkipAll, 3),
+        ("replace", ResolutionPolicy::ReplaceAll, 4),
+        ("update", ResolutionPolicy::default(), 4),
+        ("newer", ResolutionPolicy::NewerVersionOnly, 3),
+    ];
+    for (name, policy, pack_size) in policies {
+        let path = temp_copy("retail_basic.lua", &format!("import_{}", name));
+        let result = Importer::new(&path)
+            .add_auras(auras.clone())
+            .policy(policy)
+            .run()
+            .unwrap();
+        assert_golden(
+            &format!("import_{}.report.txt", name),
+            &result.to_report_text(),
+        );
+        if name == "update" {
+            assert_golden(
+                "import_update.lua",
+                &std::fs::read_to_string(&path).unwrap(),
+            );
+        }
+
+        // The imported hierarchy is intact in the saved file
+        let mut manager = SavedVariablesManager::new(path.clone());
+        manager.load().unwrap();
+        let tree = manager.get_aura_tree();
+        let pack = tree
+            .iter()
+            .find(|node| node.id == "Raid Cooldowns")
+            .unwrap();
+        assert_eq!(pack.total_count(), pack_size, "{}", name);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,62 @@
+//! Tests for recognizing other addons' export strings.
+
+use weakauras_mass_import::decoder::WeakAuraDecoder;
+use weakauras_mass_import::error::WeakAuraError;
+use weakauras_mass_import::format_sniffer::{sniff, ForeignFormat};
+
+const ELVUI: &str = "!E1!T3t7YTTUY6)4QfNhMOjpA1C5BwKvp5fLMQzYNMn7rBvEzGi8tMkjP";
+
+#[test]
+fn test_sniff_elvui() {
+    assert_eq!(sniff(ELVUI), Some(ForeignFormat::ElvUi));
+    assert_eq!(sniff("!E12!abc"), Some(ForeignFormat::ElvUi));
+    assert_eq!(
+        sniff("{[\"general\"] = {}}::profile::Default"),
+        Some(ForeignFormat::ElvUi)
+    );
+}
+
+#[test]
+fn test_sniff_plater() {
+    assert_eq!(
+        sniff("!PLATER:2!S3xAVTnos4)kzXxg"),
+        Some(ForeignFormat::Plater)
+    );
+}
+
+#[test]
+fn test_sniff_leaves_weakauras_alone() {
+    assert_eq!(sniff("!WA:2!S3xAVTnos4)kzXxg"), None);
+    assert_eq!(sniff("!S3xAVTnos4)kzXxg"), None);
+    assert_eq!(sniff("!E!abc"), None);
+    assert_eq!(sniff("!Eabc!def"), None);
+}
+
+#[test]
+fn test_decode_reports_foreign_strings() {
+    let error = WeakAuraDecoder::decode(ELVUI).unwrap_err();
+    assert!(matches!(
+        error,
+        WeakAuraError::ForeignString(ForeignFormat::ElvUi)
+    ));
+    assert_eq!(
+        error.to_string(),
+        "This is an ElvUI profile string, not a WeakAura; import it in ElvUI instead"
+    );
+    assert!(matches!(
+        WeakAuraDecoder::decode_lossy("\"!PLATER:2!abc\""),
+        Err(WeakAuraError::ForeignString(ForeignFormat::Plater))
+    ));
+}
+
+#[test]
+fn test_decode_multiple_finds_plain_elvui_exports() {
+    let input = "{\n  [\"general\"] = { [\"fontSize\"] = 12 },\n}::profile::Default\n";
+    let results = WeakAuraDecoder::decode_multiple_with_spans(input);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].line, 2);
+    assert!(matches!(
+        results[0].result,
+        Err(WeakAuraError::ForeignString(ForeignFormat::ElvUi))
+    ));
+}
@@ -0,0 +1,91 @@
+//! Tests for permission-aware file writes.
+
+use std::io;
+use std::path::Path;
+
+use weakauras_mass_import::api::{
+    is_permission_denied, is_protected_location, permission_hint, WeakAuraError,
+};
+use weakauras_mass_import::fs_util;
+
+#[test]
+fn test_is_permission_denied() {
+    assert!(is_permission_denied(&io::Error::from(
+        io::ErrorKind::PermissionDenied
+    )));
+    // EACCES on Unix; on Windows 13 is ERROR_INVALID_DATA
+    #[cfg(unix)]
+    assert!(is_permission_denied(&io::Error::from_raw_os_error(13)));
+    #[cfg(windows)]
+    {
+        assert!(!is_permission_denied(&io::Error::from_raw_os_error(13)));
+        // ERROR_ACCESS_DENIED
+        assert!(is_permission_denied(&io::Error::from_raw_os_error(5)));
+    }
+    assert!(!is_permission_denied(&io::Error::from(
+        io::ErrorKind::NotFound
+    )));
+}
+
+#[test]
+fn test_protected_location_hint() {
+    let protected = Path::new(
+        "C:/Program Files (x86)/World of Warcraft/_retail_/WTF/Account/ME/SavedVariables/WeakAuras.lua",
+    );
+    assert!(is_protected_location(protected));
+    assert!(is_protected_location(Path::new(
+        "C:/PROGRAM FILES/WoW/WeakAuras.lua"
+    )));
+    assert!(!is_protected_location(Path::new(
+        "D:/Games/World of Warcraft/WeakAuras.lua"
+    )));
+    assert!(!is_protected_location(Path::new(
+        "D:/My Program Files Backup/WeakAuras.lua"
+    )));
+
+    assert!(permission_hint(protected).contains("administrator"));
+    let error = WeakAuraError::PermissionDenied(protected.to_path_buf());
+    let message = error.to_string();
+    assert!(message.starts_with("Permission denied writing C:/Program Files (x86)/"));
+    assert!(message.contains("Program Files"), "{}", message);
+}
+
+#[test]
+fn test_check_writable() {
+    let dir = std::env::temp_dir().join("wa_fs_util_check_writable");
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("WeakAuras.lua");
+
+    // A file that does not exist yet only needs a writable folder
+    let _ = std::fs::remove_file(&file);
+    fs_util::check_writable(&file).unwrap();
+    assert!(!file.exists());
+
+    std::fs::write(&file, "WeakAurasSaved = {}").unwrap();
+    fs_util::check_writable(&file).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&file).unwrap(),
+        "WeakAurasSaved = {}"
+    );
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    assert!(fs_util::check_writable(&dir.join("missing").join("WeakAuras.lua")).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_write_errors() {
+    let error = fs_util::map_io_error(
+        Path::new("WeakAuras.lua"),
+        io::Error::from(io::ErrorKind::PermissionDenied),
+    );
+    assert!(
+        matches!(error, WeakAuraError::PermissionDenied(path) if path == Path::new("WeakAuras.lua"))
+    );
+
+    // Other failures stay plain IO errors
+    let dir = std::env::temp_dir().join("wa_fs_util_missing_dir");
+    let _ = std::fs::remove_dir_all(&dir);
+    let error = fs_util::write(&dir.join("WeakAuras.lua"), "x").unwrap_err();
+    assert!(matches!(error, WeakAuraError::IoError(_)), "{}", error);
+}
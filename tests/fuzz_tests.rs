@@ -0,0 +1,224 @@
+//! Property tests for the SavedVariables parser and the import string
+//! decoder: malformed input must come back as an error (never a panic),
+//! serialized tables must parse back to the same value, and salvaged auras
+//! must import. Generated tables stay a few levels deep.
+//!
+//! `fuzz/` runs the same entry points under cargo-fuzz for longer sessions.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use proptest::prelude::*;
+
+use weakauras_mass_import::decoder::{LuaTable, LuaValue, WeakAuraDecoder};
+use weakauras_mass_import::lua_parser::LuaParser;
+use weakauras_mass_import::saved_variables::SavedVariablesManager;
+
+/// Fragments of SavedVariables syntax, including the awkward ones: long
+/// strings and comments, special numbers, sparse and huge indices
+const TOKENS: &[&str] = &[
+    "{",
+    "}",
+    "[",
+    "]",
+    "=",
+    ",",
+    ";",
+    " ",
+    "\n",
+    "\"",
+    "'",
+    "\\",
+    "\"key\"",
+    "'str'",
+    "[[long]]",
+    "[==[",
+    "]==]",
+    "--",
+    "--[[",
+    "]]",
+    "-- [1]\n",
+    "1",
+    "-1.5e3",
+    "0x1F",
+    "0x",
+    "1e999",
+    ".5",
+    "-",
+    "[1]",
+    "[0]",
+    "[2]",
+    "[-1]",
+    "[1.5]",
+    "[1e18]",
+    "[1e300]",
+    "math.huge",
+    "-math.huge",
+    "(0/0)",
+    "(0/",
+    "nil",
+    "true",
+    "false",
+    "trueish",
+    "displays",
+    "WeakAurasSaved",
+    "é",
+    "🐉",
+];
+
+/// Source text built from [`TOKENS`]
+fn lua_soup() -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(TOKENS), 0..64).prop_map(|tokens| tokens.concat())
+}
+
+/// Keys that stay string keys after a round trip (digit-only keys become
+/// array indices, as they do in Lua)
+fn table_key() -> impl Strategy<Value = String> {
+    "[a-zA-Z_][a-zA-Z0-9_ .]{0,8}"
+}
+
+/// Import strings: a prefix (possibly broken) and a payload mostly in the
+/// encoding's alphabet, with the odd quote, space or line break
+fn import_string() -> impl Strategy<Value = String> {
+    (
+        prop::sample::select(&["", "!", "!WA:2!", "!WA:", "!WA:99!", "\"!WA:2!"][..]),
+        "[a-zA-Z0-9() \n\"'`!:]{0,200}",
+    )
+        .prop_map(|(prefix, payload)| format!("{}{}", prefix, payload))
+}
+
+/// Child entries as a damaged string may hold them: non-tables, missing or
+/// repeated IDs, unknown parents and cycles among a few IDs
+fn child_entry() -> impl Strategy<Value = LuaValue> {
+    let id = prop::sample::select(&["Root", "A", "B", "C", "D", ""][..]);
+    let parent = prop_oneof![
+        Just(None),
+        prop::sample::select(&["Root", "A", "B", "C", "D", "Missing"][..])
+            .prop_map(|p| Some(LuaValue::String(p.to_string()))),
+        Just(Some(LuaValue::Number(1.0))),
+    ];
+    prop_oneof![
+        1 => lua_scalar(),
+        6 => (prop::option::of(id), parent).prop_map(|(id, parent)| {
+            let mut table = LuaTable::new();
+            if let Some(id) = id {
+                table.insert("id".to_string(), LuaValue::String(id.to_string()));
+            }
+            if let Some(parent) = parent {
+                table.insert("parent".to_string(), parent);
+            }
+            LuaValue::Table(Arc::new(table))
+        }),
+    ]
+}
+
+fn lua_scalar() -> impl Strategy<Value = LuaValue> {
+    prop_oneof![
+        Just(LuaValue::Nil),
+        any::<bool>().prop_map(LuaValue::Bool),
+        any::<f64>()
+            .prop_filter("NaN never equals itself", |n| !n.is_nan())
+            .prop_map(LuaValue::Number),
+        any::<i32>().prop_map(|n| LuaValue::Number(n.into())),
+        any::<String>().prop_map(LuaValue::String),
+        "[\"'\\\\\n\r\t\\[\\]=,{}-]{0,8}".prop_map(LuaValue::String),
+    ]
+}
+
+/// Values as they come out of the parser: arrays are never empty, mixed
+/// tables have both parts
+fn lua_value() -> impl Strategy<Value = LuaValue> {
+    lua_scalar().prop_recursive(4, 48, 6, |inner| {
+        prop_oneof![
+            prop::collection::vec((table_key(), inner.clone()), 0..6)
+                .prop_map(|entries| LuaValue::Table(Arc::new(entries.into_iter().collect()))),
+            prop::collection::vec(inner.clone(), 1..6).prop_map(LuaValue::Array),
+            (
+                prop::collection::vec(inner.clone(), 1..4),
+                prop::collection::vec((table_key(), inner), 1..4),
+            )
+                .prop_map(|(array, entries)| LuaValue::MixedTable {
+                    array,
+                    hash: Arc::new(entries.into_iter().collect::<LuaTable>()),
+                }),
+        ]
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn parse_never_panics_on_arbitrary_text(input in any::<String>()) {
+        let _ = LuaParser::parse(&input);
+        let _ = LuaParser::parse_value(&input);
+        let _ = LuaParser::parse(&format!("WeakAurasSaved = {{{}", input));
+    }
+
+    #[test]
+    fn parse_never_panics_on_lua_soup(body in lua_soup()) {
+        let _ = LuaParser::parse_value(&body);
+        let _ = LuaParser::parse_value(&format!("{{{}}}", body));
+        let _ = LuaParser::parse(&format!("WeakAurasSaved = {{ [\"displays\"] = {{{}}} }}", body));
+    }
+
+    #[test]
+    fn serialized_values_parse_back(value in lua_value()) {
+        let table = LuaValue::Array(vec![value]);
+        for text in [LuaParser::serialize(&table, 0), LuaParser::serialize_sorted(&table, 0)] {
+            let parsed = LuaParser::parse_value(&text);
+            prop_assert!(parsed.is_ok(), "{:?} in\n{}", parsed.err(), text);
+            prop_assert_eq!(parsed.unwrap(), table.clone(), "serialized as\n{}", text);
+        }
+    }
+
+    #[test]
+    fn serialized_displays_parse_back(
+        displays in prop::collection::vec(
+            (table_key(), prop::collection::vec((table_key(), lua_value()), 0..4)),
+            0..4,
+        )
+    ) {
+        let displays: LuaTable = displays
+            .into_iter()
+            .map(|(id, data)| (id, LuaValue::Table(Arc::new(data.into_iter().collect()))))
+            .collect();
+        let content = format!(
+            "WeakAurasSaved = {{\n\t[\"displays\"] = {},\n}}\n",
+            LuaParser::serialize(&LuaValue::Table(Arc::new(displays.clone())), 1)
+        );
+        let saved = LuaParser::parse(&content);
+        prop_assert!(saved.is_ok(), "{:?}", saved.as_ref().err());
+        let saved = saved.unwrap();
+        prop_assert_eq!(&saved.displays, &displays);
+        // Every display's recorded span holds exactly its own value
+        for (id, span) in &saved.display_spans {
+            let value = LuaParser::parse_value(&content[span.clone()]);
+            prop_assert!(value.is_ok(), "span of '{}' does not hold a table", id);
+            prop_assert_eq!(&value.unwrap(), &displays[id]);
+        }
+    }
+
+    #[test]
+    fn decode_never_panics(input in prop_oneof![any::<String>(), import_string()]) {
+        let _ = WeakAuraDecoder::decode(&input);
+        let _ = WeakAuraDecoder::decode_lossy(&input);
+        let _ = WeakAuraDecoder::decode_multiple(&input);
+    }
+
+    #[test]
+    fn salvaged_auras_import(children in prop::collection::vec(child_entry(), 0..10)) {
+        let mut data = LuaTable::new();
+        let mut root = LuaTable::new();
+        root.insert("id".to_string(), LuaValue::String("Root".to_string()));
+        data.insert("d".to_string(), LuaValue::Table(Arc::new(root)));
+        data.insert("c".to_string(), LuaValue::Array(children));
+
+        let salvaged = WeakAuraDecoder::aura_from_data(LuaValue::Table(Arc::new(data)), true);
+        prop_assert!(salvaged.is_ok(), "{:?}", salvaged.err());
+        let aura = salvaged.unwrap().aura;
+        let mut manager = SavedVariablesManager::new(PathBuf::from("fuzz_salvage.lua"));
+        let result = manager.add_auras(&[aura]).unwrap();
+        prop_assert!(result.failed.is_empty(), "{:?}", result.failed);
+    }
+}
@@ -2,9 +2,7 @@
 //!
 //! Uses real WeakAura import strings to test the complete flow.
 
-use std::collections::HashMap;
-
-use weakauras_mass_import::decoder::{LuaValue, WeakAuraDecoder};
+use weakauras_mass_import::decoder::{LuaTable, LuaValue, WeakAuraDecoder};
 use weakauras_mass_import::lua_parser::LuaParser;
 use weakauras_mass_import::saved_variables::SavedVariablesManager;
 
@@ -429,19 +427,20 @@ fn test_parse_hex_numbers() {
 fn test_generate_lua_deterministic() {
     let temp_path = std::env::temp_dir().join("weakauras_test_deterministic.lua");
     let mut manager = SavedVariablesManager::new(temp_path.clone());
+    manager.sort_keys = true;
 
     // Insert displays in arbitrary order
-    let mut table_a = HashMap::new();
+    let mut table_a = LuaTable::new();
     table_a.insert("id".to_string(), LuaValue::String("aura_z".to_string()));
     manager
         .displays
-        .insert("aura_z".to_string(), LuaValue::Table(table_a));
+        .insert("aura_z".to_string(), LuaValue::Table(table_a.into()));
 
-    let mut table_b = HashMap::new();
+    let mut table_b = LuaTable::new();
     table_b.insert("id".to_string(), LuaValue::String("aura_a".to_string()));
     manager
         .displays
-        .insert("aura_a".to_string(), LuaValue::Table(table_b));
+        .insert("aura_a".to_string(), LuaValue::Table(table_b.into()));
 
     let output1 = manager.generate_lua();
     let output2 = manager.generate_lua();
@@ -1,8 +1,8 @@
 //! Tests for SavedVariables Lua parsing and serialization.
 
-use std::collections::HashMap;
+use std::sync::Arc;
 
-use weakauras_mass_import::decoder::LuaValue;
+use weakauras_mass_import::decoder::{LuaTable, LuaValue, MAX_TABLE_DEPTH};
 use weakauras_mass_import::lua_parser::LuaParser;
 
 #[test]
@@ -13,7 +13,7 @@ fn test_parse_simple_table() {
 
 #[test]
 fn test_serialize_mixed_table() {
-    let mut hash = HashMap::new();
+    let mut hash = LuaTable::new();
     hash.insert(
         "disjunctive".to_string(),
         LuaValue::String("all".to_string()),
@@ -21,12 +21,12 @@ fn test_serialize_mixed_table() {
     hash.insert("activeTriggerMode".to_string(), LuaValue::Number(1.0));
 
     let mixed = LuaValue::MixedTable {
-        array: vec![LuaValue::Table({
-            let mut t = HashMap::new();
-            t.insert("trigger".to_string(), LuaValue::Table(HashMap::new()));
+        array: vec![LuaValue::Table(Arc::new({
+            let mut t = LuaTable::new();
+            t.insert("trigger".to_string(), LuaValue::Table(Arc::default()));
             t
-        })],
-        hash,
+        }))],
+        hash: hash.into(),
     };
 
     let serialized = LuaParser::serialize(&mixed, 0);
@@ -223,11 +223,11 @@ fn test_roundtrip_boolean_array() {
 #[test]
 fn test_roundtrip_special_numbers() {
     // Serialize infinity and NaN, then parse them back
-    let mut table = HashMap::new();
+    let mut table = LuaTable::new();
     table.insert("inf".to_string(), LuaValue::Number(f64::INFINITY));
     table.insert("neg_inf".to_string(), LuaValue::Number(f64::NEG_INFINITY));
     table.insert("nan".to_string(), LuaValue::Number(f64::NAN));
-    let original = LuaValue::Table(table);
+    let original = LuaValue::Table(table.into());
 
     let serialized = LuaParser::serialize(&original, 0);
     assert!(
@@ -301,3 +301,84 @@ fn test_parse_mixed_table_with_booleans() {
         panic!("test should be a Table");
     }
 }
+
+#[test]
+fn test_serialize_keeps_parse_order_unless_sorted() {
+    let input = "{\n\t[\"zeta\"] = 1,\n\t[\"alpha\"] = {\n\t\t[\"y\"] = true,\n\t\t[\"x\"] = false,\n\t},\n}";
+    let value = LuaParser::parse_value(input).unwrap();
+    let keys: Vec<&String> = value.as_table().unwrap().keys().collect();
+    assert_eq!(keys, vec!["zeta", "alpha"]);
+
+    assert_eq!(LuaParser::serialize(&value, 0), input);
+
+    let sorted = LuaParser::serialize_sorted(&value, 0);
+    assert!(sorted.find("[\"alpha\"]").unwrap() < sorted.find("[\"zeta\"]").unwrap());
+    assert!(sorted.find("[\"x\"]").unwrap() < sorted.find("[\"y\"]").unwrap());
+}
+
+#[test]
+fn test_roundtrip_integers_beyond_i64() {
+    let original = LuaValue::Array(vec![
+        LuaValue::Number(1e20),
+        LuaValue::Number(-3.5e270),
+        LuaValue::Number(9007199254740992.0),
+    ]);
+    let serialized = LuaParser::serialize(&original, 0);
+    assert!(!serialized.contains("9223372036854775807"));
+    assert_eq!(LuaParser::parse_value(&serialized).unwrap(), original);
+}
+
+#[test]
+fn test_parse_far_index_in_mixed_table_stays_sparse() {
+    let parsed =
+        LuaParser::parse_value("{ \"a\", [3] = \"c\", [1e18] = \"far\", key = 1 }").unwrap();
+    let LuaValue::MixedTable { array, hash } = parsed else {
+        panic!("expected a mixed table, got {:?}", parsed);
+    };
+    assert_eq!(
+        array,
+        vec![
+            LuaValue::String("a".to_string()),
+            LuaValue::Nil,
+            LuaValue::String("c".to_string()),
+        ]
+    );
+    assert_eq!(
+        hash.get("1000000000000000000"),
+        Some(&LuaValue::String("far".to_string()))
+    );
+    assert_eq!(hash.get("key"), Some(&LuaValue::Number(1.0)));
+}
+
+/// `levels` tables nested inside each other, the innermost holding a value
+fn nested_tables(levels: usize) -> String {
+    format!("{}1{}", "{".repeat(levels), "}".repeat(levels))
+}
+
+#[test]
+fn test_parse_rejects_pathological_nesting() {
+    let err = LuaParser::parse_value(&"{".repeat(100_000)).unwrap_err();
+    assert!(
+        err.to_string().contains("nested deeper than 128 levels"),
+        "{}",
+        err
+    );
+    assert!(LuaParser::parse_value(&nested_tables(MAX_TABLE_DEPTH + 1)).is_err());
+
+    // The limit itself still parses and serializes back
+    let deepest = LuaParser::parse_value(&nested_tables(MAX_TABLE_DEPTH)).unwrap();
+    let serialized = LuaParser::serialize(&deepest, 0);
+    assert_eq!(LuaParser::parse_value(&serialized).unwrap(), deepest);
+
+    // ... also as a display in a file, below `WeakAurasSaved` and `displays`
+    let file = format!(
+        "WeakAurasSaved = {{ [\"displays\"] = {{ [\"Deep\"] = {} }} }}",
+        serialized
+    );
+    assert_eq!(LuaParser::parse(&file).unwrap().displays["Deep"], deepest);
+    let file = format!(
+        "WeakAurasSaved = {{ [\"displays\"] = {{ [\"Deep\"] = {} }} }}",
+        nested_tables(MAX_TABLE_DEPTH + 1)
+    );
+    assert!(LuaParser::parse(&file).is_err());
+}
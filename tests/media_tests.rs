@@ -0,0 +1,88 @@
+//! Tests for media dependency detection.
+
+mod common;
+
+use std::fs;
+
+use weakauras_mass_import::decoder::WeakAura;
+use weakauras_mass_import::media::{missing_media, referenced_media, MediaKind};
+
+use common::{s, table};
+
+fn make_aura() -> WeakAura {
+    let child = table(&[
+        ("id", s("Bar")),
+        (
+            "texture",
+            s("Interface\\AddOns\\SharedMedia_Test\\statusbar\\bar"),
+        ),
+        // Game files are not checked
+        ("icon", s("Interface\\Icons\\spell_nature_bloodlust")),
+    ]);
+    WeakAura {
+        id: "Pack".to_string(),
+        uid: None,
+        region_type: Some("group".to_string()),
+        is_group: true,
+        children: vec!["Bar".to_string()],
+        data: table(&[
+            ("id", s("Pack")),
+            (
+                "actions",
+                table(&[(
+                    "start",
+                    table(&[(
+                        "sound",
+                        s("Interface/AddOns/SharedMedia_Test/Sound/PULL.ogg"),
+                    )]),
+                )]),
+            ),
+            (
+                "font",
+                s("Interface\\AddOns\\SharedMedia_Test\\font\\missing.ttf"),
+            ),
+        ]),
+        child_data: vec![child],
+        original_string: String::new(),
+        encoding_version: 2,
+    }
+}
+
+#[test]
+fn test_referenced_media_lists_addon_paths() {
+    let references = referenced_media(&[make_aura()]);
+    let summary: Vec<(MediaKind, &str)> = references
+        .iter()
+        .map(|r| (r.kind, r.aura_ids[0].as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (MediaKind::Font, "Pack"),
+            (MediaKind::Sound, "Pack"),
+            (MediaKind::Texture, "Bar"),
+        ]
+    );
+    assert_eq!(
+        references[1].path,
+        "Interface\\AddOns\\SharedMedia_Test\\Sound\\PULL.ogg"
+    );
+}
+
+#[test]
+fn test_missing_media_checks_the_flavor_folder() {
+    let flavor_dir = std::env::temp_dir().join("wa_media_tests").join("_retail_");
+    let _ = fs::remove_dir_all(&flavor_dir);
+    let media_dir = flavor_dir.join("Interface/AddOns/SharedMedia_Test");
+    fs::create_dir_all(media_dir.join("sound")).unwrap();
+    fs::create_dir_all(media_dir.join("statusbar")).unwrap();
+    // Names differ in case, the texture path has no extension
+    fs::write(media_dir.join("sound/pull.ogg"), b"").unwrap();
+    fs::write(media_dir.join("statusbar/bar.tga"), b"").unwrap();
+
+    let missing = missing_media(&[make_aura()], &flavor_dir);
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].kind, MediaKind::Font);
+
+    let _ = fs::remove_dir_all(&flavor_dir);
+}
@@ -0,0 +1,175 @@
+//! Tests for the typed aura model.
+
+mod common;
+
+use weakauras_mass_import::decoder::{LuaTable, LuaValue};
+use weakauras_mass_import::model::{AuraModel, TriggerModel};
+
+use common::s;
+
+/// A buff icon in a group, loading for two classes, with custom fields
+fn icon_data() -> LuaValue {
+    let mut data = LuaValue::Table(LuaTable::new().into());
+    data.set_path("id", s("Shield Wall"));
+    data.set_path("uid", s("abcDEF12345"));
+    data.set_path("regionType", s("icon"));
+    data.set_path("parent", s("Warrior Defensives"));
+    data.set_path("load.use_class", LuaValue::Bool(false));
+    data.set_path("load.class.multi.WARRIOR", LuaValue::Bool(true));
+    data.set_path("load.class.multi.PALADIN", LuaValue::Bool(true));
+    data.set_path("load.class.multi.MAGE", LuaValue::Bool(false));
+    data.set_path("load.use_class_and_spec", LuaValue::Bool(true));
+    data.set_path("load.class_and_spec.single", LuaValue::Number(73.0));
+    data.set_path(
+        "triggers",
+        LuaValue::Array(vec![LuaValue::Table(LuaTable::new().into())]),
+    );
+    data.set_path("triggers.1.trigger.type", s("aura2"));
+    data.set_path("triggers.1.trigger.unit", s("player"));
+    data.set_path(
+        "triggers.1.trigger.auranames",
+        LuaValue::Array(vec![s("871"), LuaValue::Number(12975.0)]),
+    );
+    data.set_path("triggers.1.trigger.matchesShowOn", s("showOnActive"));
+    data.set_path("triggers.disjunctive", s("any"));
+    data.set_path("anchorFrameType", s("SCREEN"));
+    data.set_path("selfPoint", s("CENTER"));
+    data.set_path("xOffset", LuaValue::Number(-120.0));
+    data.set_path("width", LuaValue::Number(40.0));
+    data
+}
+
+#[test]
+fn test_model_reads_common_fields() {
+    let model = AuraModel::from_lua(&icon_data()).unwrap();
+    assert_eq!(model.id, "Shield Wall");
+    assert_eq!(model.uid.as_deref(), Some("abcDEF12345"));
+    assert_eq!(model.region_type.as_deref(), Some("icon"));
+    assert_eq!(model.parent.as_deref(), Some("Warrior Defensives"));
+    assert!(model.controlled_children.is_empty());
+    assert!(!model.load.never);
+    assert_eq!(model.load.classes, vec!["WARRIOR", "PALADIN"]);
+    assert_eq!(model.load.specs, vec![73]);
+    assert_eq!(
+        model.triggers,
+        vec![TriggerModel {
+            trigger_type: Some("aura2".to_string()),
+            unit: Some("player".to_string()),
+            aura_names: vec!["871".to_string(), "12975".to_string()],
+            ..Default::default()
+        }]
+    );
+    assert_eq!(model.disjunctive.as_deref(), Some("any"));
+    assert_eq!(model.anchor.frame_type.as_deref(), Some("SCREEN"));
+    assert_eq!(model.anchor.point.as_deref(), Some("CENTER"));
+    assert_eq!(model.anchor.x_offset, -120.0);
+    assert_eq!(model.anchor.y_offset, 0.0);
+
+    assert!(AuraModel::from_lua(&s("not a table")).is_err());
+    assert!(AuraModel::from_lua(&LuaValue::Table(LuaTable::new().into())).is_err());
+}
+
+#[test]
+fn test_model_writes_back_keeping_other_fields() {
+    let mut data = icon_data();
+    let original = data.clone();
+    let model = AuraModel::from_lua(&data).unwrap();
+    model.write_to(&mut data).unwrap();
+    assert_eq!(AuraModel::from_lua(&data).unwrap(), model);
+    assert_eq!(data.get_path("width"), original.get_path("width"));
+    assert_eq!(data.get_path("yOffset"), None);
+
+    let mut changed = model.clone();
+    changed.parent = None;
+    changed.load.classes = vec!["WARRIOR".to_string()];
+    changed.load.specs.clear();
+    changed.triggers[0].unit = Some("target".to_string());
+    changed.triggers.push(TriggerModel {
+        trigger_type: Some("event".to_string()),
+        event: Some("Combat Log".to_string()),
+        ..Default::default()
+    });
+    changed.write_to(&mut data).unwrap();
+
+    assert_eq!(AuraModel::from_lua(&data).unwrap(), changed);
+    assert_eq!(data.get_path("parent"), None);
+    assert_eq!(data.get_path("load.use_class"), Some(&LuaValue::Bool(true)));
+    assert_eq!(data.get_path("load.class.single"), Some(&s("WARRIOR")));
+    assert_eq!(data.get_path("load.use_class_and_spec"), None);
+    assert_eq!(
+        data.get_path("triggers.1.trigger.matchesShowOn"),
+        Some(&s("showOnActive"))
+    );
+    assert_eq!(
+        data.get_path("triggers.2.trigger.event"),
+        Some(&s("Combat Log"))
+    );
+    assert!(AuraModel::default().write_to(&mut s("text")).is_err());
+}
+
+#[test]
+fn test_model_serde_roundtrip() {
+    let model = AuraModel::from_lua(&icon_data()).unwrap();
+    let json = serde_json::to_string(&model).unwrap();
+    assert_eq!(serde_json::from_str::<AuraModel>(&json).unwrap(), model);
+    // Missing fields take their defaults
+    let minimal: AuraModel = serde_json::from_str(r#"{"id":"Solo"}"#).unwrap();
+    assert_eq!(minimal.id, "Solo");
+    assert!(minimal.triggers.is_empty());
+}
+
+#[test]
+fn test_aura_properties_apply_and_validate() {
+    use weakauras_mass_import::decoder::WeakAura;
+    use weakauras_mass_import::model::AuraProperties;
+
+    let mut child = LuaValue::Table(LuaTable::new().into());
+    child.set_path("id", s("Shield Wall Bar"));
+    child.set_path("parent", s("Shield Wall"));
+    let mut aura = WeakAura {
+        id: "Shield Wall".to_string(),
+        uid: None,
+        region_type: Some("group".to_string()),
+        is_group: true,
+        children: vec!["Shield Wall Bar".to_string()],
+        data: icon_data(),
+        child_data: vec![child],
+        original_string: String::new(),
+        encoding_version: 2,
+    };
+
+    let mut properties = AuraProperties::from_aura(&aura).unwrap();
+    assert_eq!(properties.classes, vec!["WARRIOR", "PALADIN"]);
+    assert_eq!(properties.desc, "");
+
+    properties.id = "Big Wall".to_string();
+    properties.y_offset = 35.5;
+    properties.classes = vec!["WARRIOR".to_string()];
+    properties.desc = "Tank cooldown".to_string();
+    properties.apply(&mut aura).unwrap();
+    assert_eq!(aura.id, "Big Wall");
+    assert_eq!(aura.data.get_path("id"), Some(&s("Big Wall")));
+    assert_eq!(aura.data.get_path("yOffset"), Some(&LuaValue::Number(35.5)));
+    assert_eq!(aura.data.get_path("desc"), Some(&s("Tank cooldown")));
+    assert_eq!(aura.child_data[0].get_path("parent"), Some(&s("Big Wall")));
+    assert_eq!(AuraProperties::from_aura(&aura).unwrap(), properties);
+
+    let invalid = [
+        AuraProperties {
+            id: "  ".to_string(),
+            ..properties.clone()
+        },
+        AuraProperties {
+            classes: vec!["PIRATE".to_string()],
+            ..properties.clone()
+        },
+        AuraProperties {
+            x_offset: f64::NAN,
+            ..properties.clone()
+        },
+    ];
+    for bad in invalid {
+        assert!(bad.apply(&mut aura).is_err());
+    }
+    assert_eq!(aura.id, "Big Wall");
+}
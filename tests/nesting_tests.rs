@@ -0,0 +1,117 @@
+//! Tests for the group nesting checks run before importing.
+
+use weakauras_mass_import::decoder::{LuaTable, LuaValue, WeakAura};
+use weakauras_mass_import::nesting::{check_nesting, NestingWarning};
+use weakauras_mass_import::saved_variables::AuraTreeNode;
+
+fn node(id: &str, region_type: &str, children: Vec<AuraTreeNode>) -> AuraTreeNode {
+    AuraTreeNode {
+        id: id.to_string(),
+        uid: None,
+        is_group: !children.is_empty() || region_type.ends_with("group"),
+        region_type: Some(region_type.to_string()),
+        anchored_to: None,
+        order: None,
+        children,
+    }
+}
+
+fn child(id: &str, parent: &str, region_type: &str) -> LuaValue {
+    let mut table = LuaTable::new();
+    table.insert("id".to_string(), LuaValue::String(id.to_string()));
+    table.insert("parent".to_string(), LuaValue::String(parent.to_string()));
+    table.insert(
+        "regionType".to_string(),
+        LuaValue::String(region_type.to_string()),
+    );
+    LuaValue::Table(table.into())
+}
+
+/// Incoming group `id` of the given region type; `children` are
+/// `(id, parent, regionType)`
+fn group(id: &str, region_type: &str, children: &[(&str, &str, &str)]) -> WeakAura {
+    let mut data = LuaTable::new();
+    data.insert("id".to_string(), LuaValue::String(id.to_string()));
+    WeakAura {
+        id: id.to_string(),
+        uid: None,
+        region_type: Some(region_type.to_string()),
+        is_group: true,
+        children: children.iter().map(|(id, _, _)| id.to_string()).collect(),
+        data: LuaValue::Table(data.into()),
+        child_data: children
+            .iter()
+            .map(|(id, parent, region)| child(id, parent, region))
+            .collect(),
+        original_string: String::new(),
+        encoding_version: 2,
+    }
+}
+
+/// Existing "Outer" (group) > "Middle" (group) > "Dyn" (dynamic group)
+fn existing_tree() -> Vec<AuraTreeNode> {
+    vec![node(
+        "Outer",
+        "group",
+        vec![node(
+            "Middle",
+            "group",
+            vec![node("Dyn", "dynamicgroup", Vec::new())],
+        )],
+    )]
+}
+
+#[test]
+fn test_top_level_import_without_warnings() {
+    let auras = vec![group(
+        "Pack",
+        "group",
+        &[("Bars", "Pack", "dynamicgroup"), ("Bar", "Bars", "aurabar")],
+    )];
+    assert!(check_nesting(&existing_tree(), None, &auras).is_empty());
+    // An unknown target is the top level
+    assert!(check_nesting(&existing_tree(), Some("Missing"), &auras).is_empty());
+}
+
+#[test]
+fn test_import_into_deep_group_is_too_deep() {
+    let auras = vec![group(
+        "Pack",
+        "group",
+        &[("Bars", "Pack", "group"), ("Bar", "Bars", "aurabar")],
+    )];
+    assert!(check_nesting(&existing_tree(), Some("Outer"), &auras).is_empty());
+    assert_eq!(
+        check_nesting(&existing_tree(), Some("Middle"), &auras),
+        vec![NestingWarning::TooDeep {
+            aura_id: "Bar".to_string(),
+            depth: 4,
+        }]
+    );
+}
+
+#[test]
+fn test_dynamic_group_inside_dynamic_group() {
+    let auras = vec![group(
+        "Icons",
+        "dynamicgroup",
+        &[("Nested", "Icons", "dynamicgroup")],
+    )];
+    assert_eq!(
+        check_nesting(&[], None, &auras),
+        vec![NestingWarning::DynamicInDynamic {
+            group_id: "Nested".to_string(),
+            parent_id: "Icons".to_string(),
+        }]
+    );
+
+    let warnings = check_nesting(&existing_tree(), Some("Dyn"), &auras[..1]);
+    assert!(warnings.contains(&NestingWarning::DynamicInDynamic {
+        group_id: "Icons".to_string(),
+        parent_id: "Dyn".to_string(),
+    }));
+    assert!(warnings.contains(&NestingWarning::TooDeep {
+        aura_id: "Nested".to_string(),
+        depth: 4,
+    }));
+}
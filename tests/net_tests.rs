@@ -0,0 +1,197 @@
+//! Tests for the shared HTTP layer, over a scripted transport.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use weakauras_mass_import::error::WeakAuraError;
+use weakauras_mass_import::net::{
+    download_url, links_in, set_offline, HttpClient, Response, Transport,
+};
+
+/// Serializes the tests, as the offline switch is global
+static GLOBAL: Mutex<()> = Mutex::new(());
+
+fn lock() -> MutexGuard<'static, ()> {
+    GLOBAL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Answers requests from a script and records the headers it was sent
+#[derive(Default)]
+struct ScriptedTransport {
+    responses: Mutex<VecDeque<Result<Response, String>>>,
+    requests: Mutex<Vec<Vec<(String, String)>>>,
+}
+
+impl ScriptedTransport {
+    fn new(responses: Vec<Result<Response, String>>) -> Arc<Self> {
+        Arc::new(Self {
+            responses: Mutex::new(responses.into()),
+            requests: Mutex::default(),
+        })
+    }
+
+    fn requests(&self) -> Vec<Vec<(String, String)>> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for ScriptedTransport {
+    fn get(&self, _url: &str, headers: &[(&str, String)]) -> Result<Response, String> {
+        self.requests.lock().unwrap().push(
+            headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        );
+        self.responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| Err("no scripted response".to_string()))
+    }
+}
+
+fn ok(body: &str, etag: Option<&str>) -> Result<Response, String> {
+    Ok(Response {
+        status: 200,
+        body: body.as_bytes().to_vec(),
+        etag: etag.map(str::to_string),
+        ..Response::default()
+    })
+}
+
+fn status(status: u16) -> Result<Response, String> {
+    Ok(Response {
+        status,
+        ..Response::default()
+    })
+}
+
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("wa_net_tests").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn make_client(transport: &Arc<ScriptedTransport>, cache: &str) -> HttpClient {
+    HttpClient::with_transport(transport.clone())
+        .cache_dir(cache_dir(cache))
+        .min_interval(Duration::ZERO)
+        .retries(2, Duration::ZERO)
+}
+
+const URL: &str = "https://data.wago.io/api/check";
+
+#[test]
+fn test_cache_revalidates_with_etag() {
+    let _lock = lock();
+    let transport = ScriptedTransport::new(vec![ok("v1", Some("\"abc\"")), status(304)]);
+    let client = make_client(&transport, "etag");
+
+    assert_eq!(client.get_text(URL).unwrap(), "v1");
+    assert_eq!(client.get_text(URL).unwrap(), "v1");
+    let requests = transport.requests();
+    assert!(requests[0].is_empty());
+    assert_eq!(
+        requests[1],
+        vec![("If-None-Match".to_string(), "\"abc\"".to_string())]
+    );
+}
+
+#[test]
+fn test_retries_server_errors() {
+    let _lock = lock();
+    let transport = ScriptedTransport::new(vec![
+        status(503),
+        Err("connection reset".to_string()),
+        ok("done", None),
+    ]);
+    let client = make_client(&transport, "retry");
+    assert_eq!(client.get_text(URL).unwrap(), "done");
+    assert_eq!(transport.requests().len(), 3);
+
+    // Client errors are not retried
+    let transport = ScriptedTransport::new(vec![status(404), ok("unused", None)]);
+    let client = make_client(&transport, "not_found");
+    assert!(matches!(client.get(URL), Err(WeakAuraError::HttpError(_))));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn test_long_retry_after_is_not_waited_for() {
+    let _lock = lock();
+    let throttled = Ok(Response {
+        status: 429,
+        retry_after: Some(Duration::from_secs(3600)),
+        ..Response::default()
+    });
+    let transport = ScriptedTransport::new(vec![throttled, ok("unused", None)]);
+    let client = make_client(&transport, "retry_after");
+
+    let start = Instant::now();
+    assert!(matches!(client.get(URL), Err(WeakAuraError::HttpError(_))));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn test_failed_request_falls_back_to_cache() {
+    let _lock = lock();
+    let transport = ScriptedTransport::new(vec![ok("cached", None), status(500), status(502)]);
+    let client = make_client(&transport, "stale").retries(1, Duration::ZERO);
+    assert_eq!(client.get_text(URL).unwrap(), "cached");
+    assert_eq!(client.get_text(URL).unwrap(), "cached");
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[test]
+fn test_offline_serves_only_cached_responses() {
+    let _lock = lock();
+    let transport = ScriptedTransport::new(vec![ok("cached", None)]);
+    let client = make_client(&transport, "offline");
+    client.get(URL).unwrap();
+
+    set_offline(true);
+    let cached = client.get_text(URL);
+    let uncached = client.get("https://data.wago.io/other");
+    set_offline(false);
+
+    assert_eq!(cached.unwrap(), "cached");
+    assert!(matches!(uncached, Err(WeakAuraError::Offline(_))));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn test_requests_to_a_host_are_spaced() {
+    let _lock = lock();
+    let transport = ScriptedTransport::new(vec![ok("a", None), ok("b", None)]);
+    let client =
+        HttpClient::with_transport(transport.clone()).min_interval(Duration::from_millis(50));
+
+    let start = Instant::now();
+    client.get("https://wago.io/a").unwrap();
+    client.get("https://wago.io/b").unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn test_links_in_input_and_their_download_urls() {
+    let input = "!WA:2!abc\n  https://wago.io/AbC-12/4  \nsee https://example.com\nhttp://pastebin.com/raw/xyz\n";
+    let links = links_in(input);
+    assert_eq!(
+        links,
+        vec!["https://wago.io/AbC-12/4", "http://pastebin.com/raw/xyz"]
+    );
+
+    assert_eq!(
+        download_url(&links[0]),
+        "https://data.wago.io/api/raw/encoded?id=AbC-12"
+    );
+    assert_eq!(download_url(&links[1]), links[1]);
+    assert_eq!(download_url("https://wago.io/"), "https://wago.io/");
+}
@@ -0,0 +1,140 @@
+//! Tests for aura packs with bundled media.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use weakauras_mass_import::pack::{MediaInstallResult, MediaPack};
+
+/// Fresh temp folder for a test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("wa_pack_tests").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn make_pack(root: &Path, manifest: Option<&str>) {
+    fs::write(root.join("auras.txt"), "!WA:2!abc").unwrap();
+    fs::create_dir_all(root.join("media/sound")).unwrap();
+    fs::write(root.join("media/sound/pull.ogg"), b"pull").unwrap();
+    fs::write(root.join("media/bar.tga"), b"bar").unwrap();
+    if let Some(manifest) = manifest {
+        fs::write(root.join("pack.json"), manifest).unwrap();
+    }
+}
+
+#[test]
+fn test_open_pack_without_media() {
+    let root = temp_dir("no_media");
+    fs::write(root.join("auras.txt"), "!WA:2!abc").unwrap();
+    assert_eq!(MediaPack::open(&root).unwrap(), None);
+
+    // An empty media folder is no pack media either
+    fs::create_dir_all(root.join("media")).unwrap();
+    assert_eq!(MediaPack::open(&root).unwrap(), None);
+}
+
+#[test]
+fn test_open_pack_reads_manifest_and_files() {
+    let root = temp_dir("MyPack");
+    make_pack(
+        &root,
+        Some(r#"{ "name": "My Pack", "media_addon": "SharedMedia_MyPack" }"#),
+    );
+
+    let pack = MediaPack::open(&root).unwrap().unwrap();
+    assert_eq!(pack.name(), "My Pack");
+    assert_eq!(
+        pack.files,
+        vec![PathBuf::from("bar.tga"), PathBuf::from("sound/pull.ogg")]
+    );
+    assert_eq!(
+        pack.game_paths("Fallback"),
+        vec![
+            "Interface\\AddOns\\SharedMedia_MyPack\\bar.tga",
+            "Interface\\AddOns\\SharedMedia_MyPack\\sound\\pull.ogg",
+        ]
+    );
+}
+
+#[test]
+fn test_media_addon_falls_back_and_is_validated() {
+    let root = temp_dir("Unnamed");
+    make_pack(&root, None);
+    let pack = MediaPack::open(&root).unwrap().unwrap();
+    assert_eq!(pack.name(), "Unnamed");
+    assert_eq!(pack.media_addon("MyMedia").unwrap(), "MyMedia");
+    assert!(pack.media_addon("").is_err());
+    assert!(pack.media_addon("..").is_err());
+    assert!(pack.media_addon("a/b").is_err());
+    assert!(pack.game_paths("..\\Evil").is_empty());
+
+    fs::write(root.join("pack.json"), "{ not json").unwrap();
+    assert!(MediaPack::open(&root).is_err());
+}
+
+#[test]
+fn test_install_copies_media_into_addon_folder() {
+    let root = temp_dir("InstallPack");
+    make_pack(&root, Some(r#"{ "media_addon": "PackMedia" }"#));
+    let flavor_dir = temp_dir("_retail_");
+    let target = flavor_dir.join("Interface/AddOns/PackMedia");
+    fs::create_dir_all(target.join("sound")).unwrap();
+    fs::write(target.join("sound/pull.ogg"), b"old").unwrap();
+
+    let pack = MediaPack::open(&root).unwrap().unwrap();
+    // A differing installed file is kept unless replacing was asked for
+    let result = pack.install(&flavor_dir, "Unused", false).unwrap();
+    assert_eq!(
+        result,
+        MediaInstallResult {
+            copied: 1,
+            replaced: 0,
+            kept: 1,
+            unchanged: 0,
+        }
+    );
+    assert_eq!(fs::read(target.join("bar.tga")).unwrap(), b"bar");
+    assert_eq!(fs::read(target.join("sound/pull.ogg")).unwrap(), b"old");
+
+    let result = pack.install(&flavor_dir, "Unused", true).unwrap();
+    assert_eq!((result.replaced, result.unchanged), (1, 1));
+    assert_eq!(fs::read(target.join("sound/pull.ogg")).unwrap(), b"pull");
+
+    // Installing again changes nothing
+    let result = pack.install(&flavor_dir, "Unused", true).unwrap();
+    assert_eq!(result.unchanged, 2);
+    assert_eq!(result.copied + result.replaced + result.kept, 0);
+}
+
+#[test]
+fn test_install_refuses_existing_addon_folder() {
+    let root = temp_dir("AddonPack");
+    make_pack(&root, Some(r#"{ "media_addon": "WeakAuras" }"#));
+    let flavor_dir = temp_dir("_classic_");
+    let addon = flavor_dir.join("Interface/AddOns/WeakAuras");
+    fs::create_dir_all(&addon).unwrap();
+    fs::write(addon.join("WeakAuras.toc"), "## Title: WeakAuras").unwrap();
+
+    let pack = MediaPack::open(&root).unwrap().unwrap();
+    assert!(pack.install(&flavor_dir, "Unused", true).is_err());
+    assert!(!addon.join("bar.tga").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_open_pack_skips_symlinks() {
+    let root = temp_dir("LinkPack");
+    make_pack(&root, None);
+    let outside = temp_dir("outside");
+    fs::write(outside.join("secret.txt"), "secret").unwrap();
+    std::os::unix::fs::symlink(outside.join("secret.txt"), root.join("media/secret.txt")).unwrap();
+    // A link back to the media folder would recurse forever if followed
+    std::os::unix::fs::symlink(root.join("media"), root.join("media/sound/loop")).unwrap();
+
+    let pack = MediaPack::open(&root).unwrap().unwrap();
+    assert_eq!(
+        pack.files,
+        vec![PathBuf::from("bar.tga"), PathBuf::from("sound/pull.ogg")]
+    );
+}
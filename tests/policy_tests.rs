@@ -0,0 +1,129 @@
+//! Tests for non-interactive conflict resolution policies.
+
+mod common;
+
+use std::path::PathBuf;
+
+use weakauras_mass_import::categories::UpdateCategory;
+use weakauras_mass_import::decoder::{LuaValue, WeakAura};
+use weakauras_mass_import::policy::ResolutionPolicy;
+use weakauras_mass_import::saved_variables::{ImportOutcome, SavedVariablesManager};
+
+use common::{s, table};
+
+fn aura_data(id: &str, version: f64, load: &str, x: f64) -> LuaValue {
+    table(&[
+        ("id", s(id)),
+        ("version", LuaValue::Number(version)),
+        ("load", s(load)),
+        ("xOffset", LuaValue::Number(x)),
+    ])
+}
+
+fn incoming(id: &str, version: f64) -> WeakAura {
+    WeakAura {
+        id: id.to_string(),
+        uid: None,
+        region_type: Some("icon".to_string()),
+        is_group: false,
+        children: Vec::new(),
+        data: aura_data(id, version, "new", 20.0),
+        child_data: Vec::new(),
+        original_string: String::new(),
+        encoding_version: 2,
+    }
+}
+
+/// Manager with "Old" (v1) and "Current" (v5) installed
+fn manager() -> SavedVariablesManager {
+    let mut mgr = SavedVariablesManager::new(PathBuf::from("policy_test.lua"));
+    for (id, version) in [("Old", 1.0), ("Current", 5.0)] {
+        mgr.displays
+            .insert(id.to_string(), aura_data(id, version, "old", 10.0));
+    }
+    mgr
+}
+
+fn batch() -> Vec<WeakAura> {
+    vec![
+        incoming("Old", 2.0),
+        incoming("Current", 5.0),
+        incoming("Fresh", 1.0),
+    ]
+}
+
+fn field<'a>(mgr: &'a SavedVariablesManager, id: &str, key: &str) -> &'a LuaValue {
+    mgr.displays[id].as_table().unwrap().get(key).unwrap()
+}
+
+#[test]
+fn test_skip_and_replace_all() {
+    let mut mgr = manager();
+    let result = mgr.import_with_policy(&batch(), &ResolutionPolicy::SkipAll);
+    assert_eq!(result.added, vec!["Fresh".to_string()]);
+    assert_eq!(result.skipped.len(), 2);
+    assert_eq!(field(&mgr, "Old", "load"), &s("old"));
+
+    let mut mgr = manager();
+    let result = mgr.import_with_policy(&batch(), &ResolutionPolicy::ReplaceAll);
+    assert_eq!(result.replaced.len(), 2);
+    assert_eq!(field(&mgr, "Current", "xOffset"), &LuaValue::Number(20.0));
+}
+
+#[test]
+fn test_update_categories_policy() {
+    let mut mgr = manager();
+    let policy = ResolutionPolicy::UpdateCategories([UpdateCategory::Load].into_iter().collect());
+    mgr.import_with_policy(&batch(), &policy);
+
+    assert_eq!(field(&mgr, "Old", "load"), &s("new"));
+    // Anchor was not selected
+    assert_eq!(field(&mgr, "Old", "xOffset"), &LuaValue::Number(10.0));
+}
+
+#[test]
+fn test_newer_version_only_policy() {
+    let mut mgr = manager();
+    let result = mgr.import_with_policy(&batch(), &ResolutionPolicy::NewerVersionOnly);
+
+    let outcome_of = |id: &str| {
+        result
+            .outcomes
+            .iter()
+            .find(|o| o.aura_id == id)
+            .map(|o| o.outcome.clone())
+            .unwrap()
+    };
+    assert!(matches!(outcome_of("Old"), ImportOutcome::Merged(_)));
+    assert_eq!(outcome_of("Current"), ImportOutcome::Skipped);
+    assert_eq!(outcome_of("Fresh"), ImportOutcome::Added);
+    assert_eq!(field(&mgr, "Old", "load"), &s("new"));
+    assert_eq!(field(&mgr, "Current", "load"), &s("old"));
+}
+
+#[test]
+fn test_policy_from_name() {
+    let none: [&str; 0] = [];
+    assert_eq!(
+        ResolutionPolicy::from_name("skip", &none).unwrap(),
+        ResolutionPolicy::SkipAll
+    );
+    assert_eq!(
+        ResolutionPolicy::from_name("Newer_Version_Only", &none).unwrap(),
+        ResolutionPolicy::NewerVersionOnly
+    );
+    assert_eq!(
+        ResolutionPolicy::from_name("update", &none).unwrap(),
+        ResolutionPolicy::default()
+    );
+    assert_eq!(
+        ResolutionPolicy::from_name("update", &["trigger", "Author Options"]).unwrap(),
+        ResolutionPolicy::UpdateCategories(
+            [UpdateCategory::Trigger, UpdateCategory::AuthorOptions]
+                .into_iter()
+                .collect()
+        )
+    );
+    assert!(ResolutionPolicy::from_name("update", &["Nope"]).is_err());
+    assert!(ResolutionPolicy::from_name("merge_everything", &none).is_err());
+}
@@ -0,0 +1,76 @@
+//! Tests for the find-references search over stored aura data.
+
+mod common;
+
+use weakauras_mass_import::decoder::{LuaTable, LuaValue};
+use weakauras_mass_import::references::{find_references, Reference};
+
+use common::{s, table};
+
+fn displays() -> LuaTable {
+    let trigger = table(&[
+        ("spellName", LuaValue::Number(375087.0)),
+        (
+            "auranames",
+            LuaValue::Array(vec![s("Dragonrage"), s("375087")]),
+        ),
+    ]);
+    let mut displays = LuaTable::new();
+    displays.insert(
+        "Rage Bar".to_string(),
+        table(&[
+            ("id", s("Rage Bar")),
+            (
+                "triggers",
+                LuaValue::MixedTable {
+                    array: vec![table(&[("trigger", trigger)])],
+                    hash: LuaTable::from([("disjunctive".to_string(), s("any"))]).into(),
+                },
+            ),
+        ]),
+    );
+    displays.insert(
+        "Cooldowns".to_string(),
+        table(&[
+            ("id", s("Cooldowns")),
+            (
+                "spellOptions",
+                table(&[("375087", table(&[("glow", LuaValue::Bool(true))]))]),
+            ),
+        ]),
+    );
+    displays
+}
+
+fn reference(aura_id: &str, path: &str, value: &str) -> Reference {
+    Reference {
+        aura_id: aura_id.to_string(),
+        path: path.to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[test]
+fn test_find_spell_id_in_values_and_keys() {
+    assert_eq!(
+        find_references(&displays(), " 375087 "),
+        vec![
+            reference("Rage Bar", "triggers[1].trigger.spellName", "375087"),
+            reference("Rage Bar", "triggers[1].trigger.auranames[2]", "375087"),
+            reference("Cooldowns", "spellOptions.375087", "{...}"),
+        ]
+    );
+}
+
+#[test]
+fn test_find_text_ignores_case() {
+    assert_eq!(
+        find_references(&displays(), "dragon"),
+        vec![reference(
+            "Rage Bar",
+            "triggers[1].trigger.auranames[1]",
+            "Dragonrage"
+        )]
+    );
+    assert!(find_references(&displays(), "  ").is_empty());
+}
@@ -0,0 +1,166 @@
+//! Tests for salvaging truncated or damaged SavedVariables files.
+
+use std::path::PathBuf;
+
+use weakauras_mass_import::api::{
+    damaged_path, find_fallbacks, game_backup_path, FallbackSource, SavedVariablesManager,
+};
+use weakauras_mass_import::decoder::LuaValue;
+use weakauras_mass_import::error::WeakAuraError;
+use weakauras_mass_import::lua_parser::LuaParser;
+use weakauras_mass_import::saved_variables::{backup_path, MAX_SUPPORTED_DB_VERSION};
+
+const INTACT: &str = r#"
+WeakAurasSaved = {
+	["dbVersion"] = 76,
+	["displays"] = {
+		["First"] = {
+			["id"] = "First",
+			["regionType"] = "icon",
+		},
+		["Second"] = {
+			["id"] = "Second",
+			["regionType"] = "text",
+			["displayText"] = "Hi",
+		},
+		["Third"] = {
+			["id"] = "Third",
+			["regionType"] = "aurabar",
+		},
+	},
+}
+"#;
+
+fn temp_file(name: &str, content: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wa_repair_{}", name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_parse_salvaging_truncated() {
+    let (saved, damage) = LuaParser::parse_salvaging(INTACT).unwrap();
+    assert!(damage.is_none());
+    assert_eq!(saved.displays.len(), 3);
+
+    let cut = INTACT.find("\"Hi\"").unwrap();
+    assert!(LuaParser::parse(&INTACT[..cut]).is_err());
+    let (saved, damage) = LuaParser::parse_salvaging(&INTACT[..cut]).unwrap();
+    let damage = damage.unwrap();
+    assert_eq!(saved.displays.keys().collect::<Vec<_>>(), vec!["First"]);
+    assert_eq!(saved.other.get("dbVersion"), Some(&LuaValue::Number(76.0)));
+    assert_eq!(damage.display.as_deref(), Some("Second"));
+    assert!(damage.in_displays);
+    assert_eq!(&INTACT[damage.offset..damage.offset + 10], "[\"Second\"]");
+}
+
+#[test]
+fn test_repair_reports_and_writes_salvaged_file() {
+    // Garbage in the middle of the file: everything from there on is lost
+    let damaged = INTACT.replace("\"Hi\"", "\"Hi\" @@@");
+    let path = temp_file("garbage", &damaged);
+    let mut manager = SavedVariablesManager::new(path.clone());
+    assert!(manager.load().is_err());
+
+    let report = manager.load_salvaging().unwrap().unwrap();
+    assert_eq!(report.recovered, 1);
+    assert_eq!(report.lost, vec!["Second", "Third"]);
+    assert_eq!(report.dropped_bytes, damaged.len() - report.damaged_at);
+    assert_eq!(
+        report.summary(),
+        format!(
+            "Recovered 1 aura(s); 2 lost after byte {}",
+            report.damaged_at
+        )
+    );
+
+    let kept = manager.write_repaired().unwrap();
+    assert_eq!(kept, damaged_path(&path));
+    assert_eq!(std::fs::read_to_string(&kept).unwrap(), damaged);
+
+    let mut reloaded = SavedVariablesManager::new(path.clone());
+    reloaded.load().unwrap();
+    assert_eq!(reloaded.displays.keys().collect::<Vec<_>>(), vec!["First"]);
+    assert!(reloaded.load_salvaging().unwrap().is_none());
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_repair_lists_lost_top_level_settings() {
+    let with_settings = INTACT.replace(
+        "\t},\n}\n",
+        "\t},\n\t[\"registered\"] = {\n\t},\n\t[\"editor_theme\"] = \"Monokai\",\n}\n",
+    );
+    let damaged = with_settings.replace("\"Hi\"", "\"Hi\" @@@");
+    let path = temp_file("settings", &damaged);
+    let mut manager = SavedVariablesManager::new(path.clone());
+
+    let report = manager.load_salvaging().unwrap().unwrap();
+    assert_eq!(report.lost, vec!["Second", "Third"]);
+    assert_eq!(report.lost_keys, vec!["registered", "editor_theme"]);
+    assert!(report.summary().ends_with("; 2 top-level setting(s) lost"));
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_repair_refuses_newer_db_version() {
+    let newer = INTACT.replace(
+        "[\"dbVersion\"] = 76",
+        &format!("[\"dbVersion\"] = {}", MAX_SUPPORTED_DB_VERSION + 1),
+    );
+    let damaged = newer.replace("\"Hi\"", "\"Hi\" @@@");
+    let path = temp_file("newer", &damaged);
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager.load_salvaging().unwrap().unwrap();
+
+    assert!(matches!(
+        manager.write_repaired(),
+        Err(WeakAuraError::UnsupportedDbVersion(_))
+    ));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), damaged);
+    assert!(!damaged_path(&path).exists());
+
+    manager.allow_newer_db_version = true;
+    manager.write_repaired().unwrap();
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_fallbacks_newest_parsable_first() {
+    let truncated = &INTACT[..INTACT.find("\"Hi\"").unwrap()];
+    let path = temp_file("fallbacks", truncated);
+    assert!(find_fallbacks(&path).is_empty());
+
+    // WoW's copy is damaged too: not offered
+    std::fs::write(game_backup_path(&path), truncated).unwrap();
+    std::fs::write(backup_path(&path), INTACT).unwrap();
+    let fallbacks = find_fallbacks(&path);
+    assert_eq!(fallbacks.len(), 1);
+    assert_eq!(fallbacks[0].source, FallbackSource::Importer);
+
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    let older_two = INTACT.replace("\t\t[\"Third\"] = {\n\t\t\t[\"id\"] = \"Third\",\n\t\t\t[\"regionType\"] = \"aurabar\",\n\t\t},\n", "");
+    std::fs::write(game_backup_path(&path), &older_two).unwrap();
+    let fallbacks = find_fallbacks(&path);
+    assert_eq!(
+        fallbacks.iter().map(|c| c.source).collect::<Vec<_>>(),
+        vec![FallbackSource::Game, FallbackSource::Importer]
+    );
+    assert_eq!(fallbacks[0].aura_count, 2);
+    assert!(fallbacks[0]
+        .describe()
+        .starts_with("WeakAuras.lua.bak (WoW's backup, just now, 2 aura(s))"));
+
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager.restore_fallback(&fallbacks[0]).unwrap();
+    assert_eq!(manager.displays.len(), 2);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), older_two);
+    assert_eq!(
+        std::fs::read_to_string(damaged_path(&path)).unwrap(),
+        truncated
+    );
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,109 @@
+//! Tests for machine-readable import reports.
+
+mod common;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use weakauras_mass_import::categories::UpdateCategory;
+use weakauras_mass_import::decoder::{LuaValue, WeakAura};
+use weakauras_mass_import::report::{self, ImportReport};
+use weakauras_mass_import::saved_variables::{
+    ConflictAction, ConflictResolution, SavedVariablesManager,
+};
+
+use common::{s, table};
+
+#[test]
+fn test_diff_by_category_groups_fields() {
+    let existing = table(&[
+        ("id", s("Aura")),
+        ("uid", s("old-uid")),
+        ("xOffset", LuaValue::Number(10.0)),
+        ("load", s("old")),
+        ("desc", s("removed")),
+    ]);
+    let incoming = table(&[
+        ("id", s("Aura")),
+        ("uid", s("new-uid")),
+        ("xOffset", LuaValue::Number(20.0)),
+        ("load", s("new")),
+        ("width", LuaValue::Number(64.0)),
+    ]);
+
+    let diffs = report::diff_by_category(&existing, &incoming);
+    let categories: Vec<UpdateCategory> = diffs.iter().map(|d| d.category).collect();
+    // uid is internal and ignored; output follows UpdateCategory::all() order
+    assert_eq!(
+        categories,
+        vec![
+            UpdateCategory::Load,
+            UpdateCategory::Anchor,
+            UpdateCategory::Metadata
+        ]
+    );
+
+    let anchor = &diffs[1];
+    let fields: Vec<&str> = anchor.fields.iter().map(|f| f.field.as_str()).collect();
+    assert_eq!(fields, vec!["width", "xOffset"]);
+    assert_eq!(anchor.fields[0].existing, None);
+    assert_eq!(anchor.fields[0].incoming, Some(LuaValue::Number(64.0)));
+
+    let metadata = &diffs[2];
+    assert_eq!(metadata.fields[0].incoming, None);
+}
+
+#[test]
+fn test_import_report_json() {
+    let mut mgr = SavedVariablesManager::new(PathBuf::from("report_test.lua"));
+    mgr.displays.insert(
+        "Existing".to_string(),
+        table(&[("id", s("Existing")), ("load", s("old"))]),
+    );
+
+    let make = |id: &str, load: &str| WeakAura {
+        id: id.to_string(),
+        uid: None,
+        region_type: Some("icon".to_string()),
+        is_group: false,
+        children: Vec::new(),
+        data: table(&[("id", s(id)), ("load", s(load))]),
+        child_data: Vec::new(),
+        original_string: String::new(),
+        encoding_version: 2,
+    };
+    let incoming = vec![make("Existing", "new"), make("Fresh", "new")];
+
+    let detection = mgr.detect_conflicts(&incoming);
+    let resolutions = vec![ConflictResolution {
+        aura_id: "Existing".to_string(),
+        action: ConflictAction::UpdateSelected,
+        categories_to_update: HashSet::from([UpdateCategory::Load]),
+        ..Default::default()
+    }];
+    let result = mgr.apply_resolutions(&detection, &resolutions);
+
+    let report = ImportReport::new(Path::new("WeakAuras.lua"), &result)
+        .with_conflicts(&detection, &resolutions);
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+
+    assert_eq!(json["new_auras"], serde_json::json!(["Fresh"]));
+    let conflict = &json["conflicts"][0];
+    assert_eq!(conflict["aura_id"], "Existing");
+    assert_eq!(conflict["action"], "UpdateSelected");
+    assert_eq!(
+        conflict["categories_to_update"],
+        serde_json::json!(["Load"])
+    );
+    assert_eq!(conflict["diffs"][0]["category"], "Load");
+    assert_eq!(conflict["diffs"][0]["fields"][0]["existing"], "old");
+    assert_eq!(conflict["diffs"][0]["fields"][0]["incoming"], "new");
+
+    let outcomes = json["result"]["outcomes"].as_array().unwrap();
+    assert!(outcomes
+        .iter()
+        .any(|o| o["aura_id"] == "Fresh" && o["outcome"] == "added"));
+    assert!(outcomes
+        .iter()
+        .any(|o| o["aura_id"] == "Existing" && o["outcome"] == "merged"));
+}
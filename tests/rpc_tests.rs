@@ -0,0 +1,190 @@
+//! Tests for the JSON-RPC server.
+
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+use weakauras_mass_import::decoder::{LuaTable, LuaValue};
+use weakauras_mass_import::rpc::{Reply, RpcServer, Session};
+use weakauras_mass_import::saved_variables::SavedVariablesManager;
+
+fn line(method: &str, params: Value) -> String {
+    json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params }).to_string()
+}
+
+fn parse(reply: &Reply) -> Value {
+    serde_json::from_str(&reply.response).unwrap()
+}
+
+/// Session that has already authenticated
+fn authenticated(server: &RpcServer) -> Session<'_> {
+    let mut session = server.session();
+    let reply = session.handle_request(&line("authenticate", json!({ "token": server.token() })));
+    assert_eq!(parse(&reply)["result"], true);
+    assert!(!reply.close);
+    session
+}
+
+fn request(session: &mut Session, method: &str, params: Value) -> Value {
+    let reply = session.handle_request(&line(method, params));
+    assert!(!reply.close);
+    parse(&reply)
+}
+
+fn aura(id: &str) -> LuaValue {
+    LuaValue::Table(
+        [("id".to_string(), LuaValue::String(id.to_string()))]
+            .into_iter()
+            .collect::<LuaTable>()
+            .into(),
+    )
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wa_rpc_{}", name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_rpc_protocol_errors() {
+    let server = RpcServer::with_roots(Vec::new());
+    let mut session = authenticated(&server);
+
+    let response = request(&mut session, "format_disk", json!({}));
+    assert_eq!(response["id"], 7);
+    assert_eq!(response["error"]["code"], -32601);
+
+    let response = request(&mut session, "decode", json!({ "wrong": 1 }));
+    assert_eq!(response["error"]["code"], -32602);
+
+    let response = request(&mut session, "decode", json!({ "string": "garbage" }));
+    assert_eq!(response["error"]["code"], -32000);
+
+    let response = request(
+        &mut session,
+        "import",
+        json!({ "saved_variables": "x.lua", "strings": [], "policy": "sometimes" }),
+    );
+    assert_eq!(response["error"]["code"], -32002);
+}
+
+#[test]
+fn test_rpc_closes_on_lines_that_are_not_json_rpc() {
+    let server = RpcServer::with_roots(Vec::new());
+
+    for garbage in [
+        "{not json",
+        "POST / HTTP/1.1",
+        r#"{"id": 1, "method": "decode"}"#,
+        r#"{"jsonrpc": "1.0", "id": 1, "method": "decode"}"#,
+    ] {
+        let mut session = authenticated(&server);
+        let reply = session.handle_request(garbage);
+        assert!(reply.close, "{}", garbage);
+        assert!(parse(&reply)["error"]["code"].as_i64().unwrap() <= -32600);
+    }
+}
+
+#[test]
+fn test_rpc_requires_the_token_first() {
+    let server = RpcServer::with_roots(Vec::new());
+
+    let mut session = server.session();
+    let reply = session.handle_request(&line("decode", json!({ "string": "!WA:2!x" })));
+    assert!(reply.close);
+    assert_eq!(parse(&reply)["error"]["code"], -32001);
+
+    let mut session = server.session();
+    let reply = session.handle_request(&line("authenticate", json!({ "token": "guess" })));
+    assert!(reply.close);
+    assert_eq!(parse(&reply)["error"]["code"], -32001);
+
+    // Tokens differ between servers
+    assert_ne!(server.token(), RpcServer::with_roots(Vec::new()).token());
+}
+
+#[test]
+fn test_rpc_only_touches_files_in_allowed_folders() {
+    let allowed = temp_dir("allowed");
+    let outside = temp_dir("outside");
+    let server = RpcServer::with_roots(vec![allowed.clone()]);
+    let mut session = authenticated(&server);
+
+    for path in [
+        outside.join("WeakAuras.lua"),
+        allowed
+            .join("..")
+            .join("wa_rpc_outside")
+            .join("WeakAuras.lua"),
+        allowed.join("notes.txt"),
+        allowed.join("missing").join("WeakAuras.lua"),
+    ] {
+        let response = request(
+            &mut session,
+            "remove",
+            json!({ "saved_variables": path, "ids": ["Alpha"] }),
+        );
+        assert_eq!(response["error"]["code"], -32002, "{}", path.display());
+    }
+    assert!(!outside.join("WeakAuras.lua").exists());
+
+    let _ = std::fs::remove_dir_all(&allowed);
+    let _ = std::fs::remove_dir_all(&outside);
+}
+
+#[test]
+fn test_rpc_serves_only_loopback_addresses() {
+    let error = RpcServer::with_roots(Vec::new())
+        .serve("0.0.0.0:0")
+        .unwrap_err();
+    assert!(error.to_string().contains("loopback"));
+}
+
+#[test]
+fn test_rpc_list_import_and_remove() {
+    let dir = temp_dir("tests");
+    let path = dir.join("WeakAuras.lua");
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager.displays.insert("Alpha".to_string(), aura("Alpha"));
+    manager.displays.insert("Beta".to_string(), aura("Beta"));
+    manager.save().unwrap();
+
+    let server = RpcServer::with_roots(vec![dir.clone()]);
+    let mut session = authenticated(&server);
+
+    let response = request(
+        &mut session,
+        "list_auras",
+        json!({ "saved_variables": path }),
+    );
+    let ids: Vec<&str> = response["result"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["Alpha", "Beta"]);
+
+    let response = request(
+        &mut session,
+        "import",
+        json!({ "saved_variables": path, "strings": [], "policy": "skip", "dry_run": true }),
+    );
+    assert_eq!(response["result"]["added"], json!([]));
+
+    let response = request(
+        &mut session,
+        "remove",
+        json!({ "saved_variables": path, "ids": ["Alpha"] }),
+    );
+    assert_eq!(response["result"], json!(["Alpha"]));
+    let response = request(
+        &mut session,
+        "list_auras",
+        json!({ "saved_variables": path }),
+    );
+    assert_eq!(response["result"].as_array().unwrap().len(), 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -1,12 +1,24 @@
 //! Tests for SavedVariables management and hierarchy preservation.
 
-use std::collections::{HashMap, HashSet};
+mod common;
+
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use weakauras_mass_import::categories::UpdateCategory;
-use weakauras_mass_import::decoder::{LuaValue, WeakAura, WeakAuraDecoder};
-use weakauras_mass_import::saved_variables::SavedVariablesManager;
+use weakauras_mass_import::decoder::{LuaTable, LuaValue, WeakAura, WeakAuraDecoder};
+use weakauras_mass_import::error::WeakAuraError;
+use weakauras_mass_import::lua_parser::LuaParser;
+use weakauras_mass_import::saved_variables::{
+    compare_semver, compare_versions, set_tree_checked, three_way_merge, topmost_selected,
+    tree_anchor_dependents, AuraTreeNode, AuraTreeSort, CheckState, ImportOutcome, ImportResult,
+    SavedVariablesManager, VersionChange, MAX_SUPPORTED_DB_VERSION,
+};
 use weakauras_mass_import::saved_variables::{ConflictAction, ConflictResolution};
+use weakauras_mass_import::snapshots::SnapshotStore;
+
+use common::table;
 
 /// Helper: decode the Hunter import string and run it through add_auras,
 /// then verify the parent-child hierarchy is correctly preserved.
@@ -127,7 +139,7 @@ fn test_add_auras_preserves_hierarchy() {
 
 /// Helper: create a simple non-group aura table.
 fn make_aura(id: &str, parent: Option<&str>) -> LuaValue {
-    let mut table = HashMap::new();
+    let mut table = LuaTable::new();
     table.insert("id".to_string(), LuaValue::String(id.to_string()));
     table.insert(
         "regionType".to_string(),
@@ -136,12 +148,12 @@ fn make_aura(id: &str, parent: Option<&str>) -> LuaValue {
     if let Some(p) = parent {
         table.insert("parent".to_string(), LuaValue::String(p.to_string()));
     }
-    LuaValue::Table(table)
+    LuaValue::Table(table.into())
 }
 
 /// Helper: create a group aura table with controlledChildren.
 fn make_group(id: &str, parent: Option<&str>, children: &[&str]) -> LuaValue {
-    let mut table = HashMap::new();
+    let mut table = LuaTable::new();
     table.insert("id".to_string(), LuaValue::String(id.to_string()));
     table.insert(
         "regionType".to_string(),
@@ -158,11 +170,11 @@ fn make_group(id: &str, parent: Option<&str>, children: &[&str]) -> LuaValue {
         "controlledChildren".to_string(),
         LuaValue::Array(controlled),
     );
-    LuaValue::Table(table)
+    LuaValue::Table(table.into())
 }
 
 /// Helper: build a manager with pre-populated displays (no file needed).
-fn manager_with_displays(displays: HashMap<String, LuaValue>) -> SavedVariablesManager {
+fn manager_with_displays(displays: LuaTable) -> SavedVariablesManager {
     let mut mgr = SavedVariablesManager::new(PathBuf::from("test_remove.lua"));
     mgr.displays = displays;
     mgr
@@ -174,7 +186,7 @@ fn make_group_with_fields(
     children: &[&str],
     custom: &str,
 ) -> LuaValue {
-    let mut table = HashMap::new();
+    let mut table = LuaTable::new();
     table.insert("id".to_string(), LuaValue::String(id.to_string()));
     table.insert(
         "regionType".to_string(),
@@ -192,11 +204,11 @@ fn make_group_with_fields(
         "controlledChildren".to_string(),
         LuaValue::Array(controlled),
     );
-    LuaValue::Table(table)
+    LuaValue::Table(table.into())
 }
 
 fn make_aura_with_custom(id: &str, parent: Option<&str>, custom: &str) -> LuaValue {
-    let mut table = HashMap::new();
+    let mut table = LuaTable::new();
     table.insert("id".to_string(), LuaValue::String(id.to_string()));
     table.insert(
         "regionType".to_string(),
@@ -206,12 +218,12 @@ fn make_aura_with_custom(id: &str, parent: Option<&str>, custom: &str) -> LuaVal
     if let Some(p) = parent {
         table.insert("parent".to_string(), LuaValue::String(p.to_string()));
     }
-    LuaValue::Table(table)
+    LuaValue::Table(table.into())
 }
 
 #[test]
 fn test_remove_standalone_aura() {
-    let mut displays = HashMap::new();
+    let mut displays = LuaTable::new();
     displays.insert("Aura1".to_string(), make_aura("Aura1", None));
     displays.insert("Aura2".to_string(), make_aura("Aura2", None));
     displays.insert("Aura3".to_string(), make_aura("Aura3", None));
@@ -231,7 +243,7 @@ fn test_remove_standalone_aura() {
 #[test]
 fn test_remove_group_removes_all_descendants() {
     // Group hierarchy: RootGroup -> [ChildA, SubGroup -> [GrandchildX]]
-    let mut displays = HashMap::new();
+    let mut displays = LuaTable::new();
     displays.insert(
         "RootGroup".to_string(),
         make_group("RootGroup", None, &["ChildA", "SubGroup"]),
@@ -270,7 +282,7 @@ fn test_remove_group_removes_all_descendants() {
 #[test]
 fn test_remove_child_updates_parent_controlled_children() {
     // Group with two children; remove one, verify parent's controlledChildren updated
-    let mut displays = HashMap::new();
+    let mut displays = LuaTable::new();
     displays.insert(
         "MyGroup".to_string(),
         make_group("MyGroup", None, &["Child1", "Child2"]),
@@ -295,7 +307,7 @@ fn test_remove_child_updates_parent_controlled_children() {
 
 #[test]
 fn test_remove_nonexistent_returns_empty() {
-    let mut displays = HashMap::new();
+    let mut displays = LuaTable::new();
     displays.insert("Aura1".to_string(), make_aura("Aura1", None));
 
     let mut mgr = manager_with_displays(displays);
@@ -305,9 +317,47 @@ fn test_remove_nonexistent_returns_empty() {
     assert_eq!(mgr.displays.len(), 1);
 }
 
+#[test]
+fn test_extract_auras_copies_between_files() {
+    let mut displays = LuaTable::new();
+    displays.insert(
+        "Root".to_string(),
+        make_group("Root", None, &["Sub", "Leaf"]),
+    );
+    displays.insert(
+        "Sub".to_string(),
+        make_group("Sub", Some("Root"), &["Inner"]),
+    );
+    displays.insert("Inner".to_string(), make_aura("Inner", Some("Sub")));
+    displays.insert("Leaf".to_string(), make_aura("Leaf", Some("Root")));
+    displays.insert("Other".to_string(), make_aura("Other", None));
+    let source = manager_with_displays(displays);
+
+    let auras = source.extract_auras(&["Root".to_string(), "Missing".to_string()]);
+    assert_eq!(auras.len(), 1);
+    let root = &auras[0];
+    assert!(root.is_group);
+    assert_eq!(root.children, vec!["Sub".to_string(), "Leaf".to_string()]);
+    assert_eq!(root.child_data.len(), 3);
+
+    let mut target = manager_with_displays(LuaTable::new());
+    let result = target.add_auras(&auras).unwrap();
+    assert_eq!(result.added.len(), 4);
+    assert!(!target.displays.contains_key("Other"));
+    for id in ["Root", "Sub", "Inner", "Leaf"] {
+        assert_eq!(target.displays[id], source.displays[id], "{}", id);
+    }
+
+    // A subgroup copied on its own becomes a top-level group
+    let auras = source.extract_auras(&["Sub".to_string()]);
+    assert_eq!(auras[0].child_data.len(), 1);
+    let table = auras[0].data.as_table().unwrap();
+    assert!(!table.contains_key("parent"));
+}
+
 #[test]
 fn test_replace_group_removes_stale_children_and_updates_descendants() {
-    let mut displays = HashMap::new();
+    let mut displays = LuaTable::new();
     displays.insert(
         "Root".to_string(),
         make_group_with_fields("Root", None, &["ChildA", "ChildB", "StaleChild"], "old"),
@@ -348,6 +398,7 @@ fn test_replace_group_removes_stale_children_and_updates_descendants() {
         aura_id: "Root".to_string(),
         action: ConflictAction::ReplaceAll,
         categories_to_update: Default::default(),
+        ..Default::default()
     }];
 
     let _result = mgr.apply_resolutions(&conflicts, &resolutions);
@@ -387,7 +438,7 @@ fn test_replace_group_removes_stale_children_and_updates_descendants() {
 
 #[test]
 fn test_update_selected_arrangement_prunes_missing_children() {
-    let mut displays = HashMap::new();
+    let mut displays = LuaTable::new();
     displays.insert(
         "Root".to_string(),
         make_group_with_fields("Root", None, &["ChildA", "StaleChild"], "old"),
@@ -425,6 +476,7 @@ fn test_update_selected_arrangement_prunes_missing_children() {
         aura_id: "Root".to_string(),
         action: ConflictAction::UpdateSelected,
         categories_to_update: categories,
+        ..Default::default()
     }];
 
     let _result = mgr.apply_resolutions(&conflicts, &resolutions);
@@ -433,3 +485,1340 @@ fn test_update_selected_arrangement_prunes_missing_children() {
     assert!(mgr.displays.contains_key("ChildA"));
     assert!(!mgr.displays.contains_key("StaleChild"));
 }
+
+#[test]
+fn test_update_selected_merges_extra_fields() {
+    let mut existing = make_aura_with_custom("Aura", None, "old");
+    if let Some(t) = existing.as_table_mut() {
+        t.insert("xOffset".to_string(), LuaValue::Number(10.0));
+        t.insert("yOffset".to_string(), LuaValue::Number(20.0));
+        t.insert("width".to_string(), LuaValue::Number(32.0));
+    }
+    let mut displays = LuaTable::new();
+    displays.insert("Aura".to_string(), existing);
+    let mut mgr = manager_with_displays(displays);
+
+    let mut incoming_data = make_aura_with_custom("Aura", None, "new");
+    if let Some(t) = incoming_data.as_table_mut() {
+        t.insert("xOffset".to_string(), LuaValue::Number(-5.0));
+        t.insert("width".to_string(), LuaValue::Number(64.0));
+    }
+    let incoming = WeakAura {
+        id: "Aura".to_string(),
+        uid: None,
+        region_type: Some("icon".to_string()),
+        is_group: false,
+        children: Vec::new(),
+        data: incoming_data,
+        child_data: Vec::new(),
+        original_string: String::new(),
+        encoding_version: 2,
+    };
+
+    let conflicts = mgr.detect_conflicts(&[incoming]);
+    let resolutions = vec![ConflictResolution {
+        aura_id: "Aura".to_string(),
+        action: ConflictAction::UpdateSelected,
+        categories_to_update: HashSet::new(),
+        extra_fields: ["xOffset", "yOffset"]
+            .iter()
+            .map(|f| f.to_string())
+            .collect(),
+    }];
+    mgr.apply_resolutions(&conflicts, &resolutions);
+
+    let table = mgr.displays["Aura"].as_table().unwrap();
+    assert_eq!(table.get("xOffset"), Some(&LuaValue::Number(-5.0)));
+    // Missing from incoming, so removed like category fields
+    assert_eq!(table.get("yOffset"), None);
+    // Not selected: kept as-is
+    assert_eq!(table.get("width"), Some(&LuaValue::Number(32.0)));
+    assert_eq!(
+        table.get("custom"),
+        Some(&LuaValue::String("old".to_string()))
+    );
+}
+
+#[test]
+fn test_selective_merge_fields_copies_only_chosen_fields() {
+    let mut existing = make_aura_with_custom("Aura", None, "mine");
+    if let Some(t) = existing.as_table_mut() {
+        t.insert("triggers".to_string(), LuaValue::String("old".to_string()));
+    }
+    let mut displays = LuaTable::new();
+    displays.insert("Aura".to_string(), existing);
+    let mut mgr = manager_with_displays(displays);
+
+    let mut incoming_data = make_aura_with_custom("Aura", None, "theirs");
+    if let Some(t) = incoming_data.as_table_mut() {
+        t.insert("triggers".to_string(), LuaValue::String("new".to_string()));
+        t.insert("uid".to_string(), LuaValue::String("newUid".to_string()));
+    }
+    let incoming = WeakAura {
+        id: "Aura".to_string(),
+        uid: None,
+        region_type: Some("icon".to_string()),
+        is_group: false,
+        children: Vec::new(),
+        data: incoming_data,
+        child_data: Vec::new(),
+        original_string: String::new(),
+        encoding_version: 2,
+    };
+
+    let detection = mgr.detect_conflicts(&[incoming]);
+    let conflict = &detection.conflicts[0];
+    // Internal fields are never offered
+    assert_eq!(conflict.changed_fields(), vec!["custom", "triggers"]);
+
+    let fields = ["triggers", "uid"].iter().map(|f| f.to_string()).collect();
+    mgr.selective_merge_fields(conflict, &fields);
+
+    let table = mgr.displays["Aura"].as_table().unwrap();
+    assert_eq!(
+        table.get("triggers"),
+        Some(&LuaValue::String("new".to_string()))
+    );
+    assert_eq!(
+        table.get("custom"),
+        Some(&LuaValue::String("mine".to_string()))
+    );
+    assert_eq!(table.get("uid"), None);
+
+    // Outcome reports the categories touched by the chosen fields
+    let resolutions = vec![ConflictResolution {
+        aura_id: "Aura".to_string(),
+        action: ConflictAction::UpdateSelected,
+        categories_to_update: HashSet::new(),
+        extra_fields: fields,
+    }];
+    let result = mgr.apply_resolutions(&detection, &resolutions);
+    assert_eq!(
+        result.outcomes[0].outcome,
+        ImportOutcome::Merged(vec![UpdateCategory::Trigger])
+    );
+}
+
+#[test]
+fn test_three_way_merge_keeps_local_customizations() {
+    let s = |v: &str| LuaValue::String(v.to_string());
+    let base = table(&[
+        ("triggers", s("v1")),
+        ("xOffset", LuaValue::Number(0.0)),
+        ("display", table(&[("text", s("v1")), ("color", s("red"))])),
+        ("sound", s("ding")),
+    ]);
+    let existing = table(&[
+        ("triggers", s("v1")),
+        ("xOffset", LuaValue::Number(150.0)),
+        (
+            "display",
+            table(&[("text", s("mine")), ("color", s("red"))]),
+        ),
+        ("sound", s("mine")),
+        ("localOnly", s("kept")),
+    ]);
+    let incoming = table(&[
+        ("triggers", s("v2")),
+        ("xOffset", LuaValue::Number(0.0)),
+        ("display", table(&[("text", s("v1")), ("color", s("blue"))])),
+        ("sound", s("dong")),
+        ("newField", s("added")),
+    ]);
+
+    let merge = three_way_merge(&base, &existing, &incoming);
+    let merged = merge.merged.as_table().unwrap();
+
+    // Author changes the user did not touch are taken
+    assert_eq!(merged.get("triggers"), Some(&s("v2")));
+    assert_eq!(merged.get("newField"), Some(&s("added")));
+    // Local customizations the author did not touch are kept
+    assert_eq!(merged.get("xOffset"), Some(&LuaValue::Number(150.0)));
+    assert_eq!(merged.get("localOnly"), Some(&s("kept")));
+    // Nested tables merge field by field
+    let display = merged.get("display").unwrap().as_table().unwrap();
+    assert_eq!(display.get("text"), Some(&s("mine")));
+    assert_eq!(display.get("color"), Some(&s("blue")));
+    // Both sides changed: local wins and the field is reported
+    assert_eq!(merged.get("sound"), Some(&s("mine")));
+    assert_eq!(merge.conflicts, vec!["sound"]);
+    assert_eq!(
+        merge.from_incoming,
+        vec!["display.color", "newField", "triggers"]
+    );
+    assert_eq!(
+        merge.categories(),
+        vec![UpdateCategory::Display, UpdateCategory::Trigger]
+    );
+}
+
+#[test]
+fn test_merge_resolution_uses_snapshot_base() {
+    let s = |v: &str| LuaValue::String(v.to_string());
+    let base = table(&[
+        ("id", s("Aura")),
+        ("uid", s("uid-1")),
+        ("triggers", s("v1")),
+        ("xOffset", LuaValue::Number(0.0)),
+    ]);
+    let existing = table(&[
+        ("id", s("Aura")),
+        ("uid", s("uid-1")),
+        ("triggers", s("v1")),
+        ("xOffset", LuaValue::Number(99.0)),
+    ]);
+    let incoming_data = table(&[
+        ("id", s("Aura")),
+        ("uid", s("uid-1")),
+        ("triggers", s("v2")),
+        ("xOffset", LuaValue::Number(0.0)),
+    ]);
+
+    let mut snapshots = SnapshotStore::default();
+    snapshots.record(&base);
+
+    let mut displays = LuaTable::new();
+    displays.insert("Aura".to_string(), existing);
+    let mut mgr = manager_with_displays(displays);
+    let incoming = WeakAura {
+        id: "Aura".to_string(),
+        uid: Some("uid-1".to_string()),
+        region_type: None,
+        is_group: false,
+        children: Vec::new(),
+        data: incoming_data,
+        child_data: Vec::new(),
+        original_string: String::new(),
+        encoding_version: 2,
+    };
+
+    let mut detection = mgr.detect_conflicts(&[incoming]);
+    assert_eq!(detection.attach_bases(&snapshots), 1);
+
+    let resolutions = vec![ConflictResolution {
+        aura_id: "Aura".to_string(),
+        action: ConflictAction::Merge,
+        ..Default::default()
+    }];
+    let result = mgr.apply_resolutions(&detection, &resolutions);
+
+    let merged = mgr.displays["Aura"].as_table().unwrap();
+    assert_eq!(merged.get("triggers"), Some(&s("v2")));
+    assert_eq!(merged.get("xOffset"), Some(&LuaValue::Number(99.0)));
+    assert_eq!(
+        result.outcomes[0].outcome,
+        ImportOutcome::Merged(vec![UpdateCategory::Trigger])
+    );
+}
+
+#[test]
+fn test_preserve_position_on_replace_and_update() {
+    let positioned = |id: &str, x: f64, width: Option<f64>, custom: &str| {
+        let mut aura = make_aura_with_custom(id, None, custom);
+        if let Some(t) = aura.as_table_mut() {
+            t.insert("xOffset".to_string(), LuaValue::Number(x));
+            if let Some(w) = width {
+                t.insert("width".to_string(), LuaValue::Number(w));
+            }
+        }
+        aura
+    };
+
+    let mut displays = LuaTable::new();
+    displays.insert(
+        "Replaced".to_string(),
+        positioned("Replaced", 100.0, None, "old"),
+    );
+    displays.insert(
+        "Updated".to_string(),
+        positioned("Updated", 100.0, Some(40.0), "old"),
+    );
+    let mut mgr = manager_with_displays(displays);
+    mgr.preserve_position = true;
+
+    let incoming: Vec<WeakAura> = ["Replaced", "Updated"]
+        .iter()
+        .map(|id| WeakAura {
+            id: id.to_string(),
+            uid: None,
+            region_type: Some("icon".to_string()),
+            is_group: false,
+            children: Vec::new(),
+            data: positioned(id, 0.0, Some(64.0), "new"),
+            child_data: Vec::new(),
+            original_string: String::new(),
+            encoding_version: 2,
+        })
+        .collect();
+
+    let conflicts = mgr.detect_conflicts(&incoming);
+    let resolutions = vec![
+        ConflictResolution {
+            aura_id: "Replaced".to_string(),
+            action: ConflictAction::ReplaceAll,
+            ..Default::default()
+        },
+        ConflictResolution {
+            aura_id: "Updated".to_string(),
+            action: ConflictAction::UpdateSelected,
+            categories_to_update: UpdateCategory::all().into_iter().collect(),
+            ..Default::default()
+        },
+    ];
+    mgr.apply_resolutions(&conflicts, &resolutions);
+
+    let replaced = mgr.displays["Replaced"].as_table().unwrap();
+    assert_eq!(
+        replaced.get("custom"),
+        Some(&LuaValue::String("new".to_string()))
+    );
+    assert_eq!(replaced.get("xOffset"), Some(&LuaValue::Number(100.0)));
+    // Absent before the import, so it stays absent
+    assert_eq!(replaced.get("width"), None);
+
+    let updated = mgr.displays["Updated"].as_table().unwrap();
+    assert_eq!(
+        updated.get("custom"),
+        Some(&LuaValue::String("new".to_string()))
+    );
+    assert_eq!(updated.get("xOffset"), Some(&LuaValue::Number(100.0)));
+    assert_eq!(updated.get("width"), Some(&LuaValue::Number(40.0)));
+}
+
+#[test]
+fn test_replace_keeps_user_config_values() {
+    let with_config = |id: &str, values: &[(&str, f64)]| {
+        let mut aura = make_aura_with_custom(id, None, "x");
+        if let Some(t) = aura.as_table_mut() {
+            let config = values
+                .iter()
+                .map(|(k, v)| (k.to_string(), LuaValue::Number(*v)))
+                .collect();
+            t.insert("config".to_string(), LuaValue::Table(Arc::new(config)));
+        }
+        aura
+    };
+    let config_of = |mgr: &SavedVariablesManager| {
+        mgr.displays["Aura"].as_table().unwrap()["config"]
+            .as_table()
+            .unwrap()
+            .clone()
+    };
+
+    for keep in [true, false] {
+        let mut displays = LuaTable::new();
+        displays.insert(
+            "Aura".to_string(),
+            with_config("Aura", &[("size", 50.0), ("removed", 1.0)]),
+        );
+        let mut mgr = manager_with_displays(displays);
+        mgr.keep_user_config = keep;
+
+        let incoming = WeakAura {
+            id: "Aura".to_string(),
+            uid: None,
+            region_type: Some("icon".to_string()),
+            is_group: false,
+            children: Vec::new(),
+            data: with_config("Aura", &[("size", 30.0), ("added", 2.0)]),
+            child_data: Vec::new(),
+            original_string: String::new(),
+            encoding_version: 2,
+        };
+        let conflicts = mgr.detect_conflicts(&[incoming]);
+        let resolutions = vec![ConflictResolution {
+            aura_id: "Aura".to_string(),
+            action: ConflictAction::ReplaceAll,
+            ..Default::default()
+        }];
+        mgr.apply_resolutions(&conflicts, &resolutions);
+
+        let config = config_of(&mgr);
+        let expected_size = if keep { 50.0 } else { 30.0 };
+        assert_eq!(config.get("size"), Some(&LuaValue::Number(expected_size)));
+        // New options keep their defaults, options the author removed are dropped
+        assert_eq!(config.get("added"), Some(&LuaValue::Number(2.0)));
+        assert_eq!(config.get("removed"), None);
+    }
+}
+
+#[test]
+fn test_compare_semver() {
+    use std::cmp::Ordering;
+
+    assert_eq!(compare_semver("1.2.3", "1.2.10"), Some(Ordering::Less));
+    assert_eq!(compare_semver("v2.0", "2.0.0"), Some(Ordering::Equal));
+    assert_eq!(compare_semver("2.0.0-beta", "2.0.0"), Some(Ordering::Less));
+    assert_eq!(
+        compare_semver("2.0.0-beta", "2.0.0-alpha"),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(
+        compare_semver("3.1", "3.0.9+build5"),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(compare_semver("latest", "1.0"), None);
+}
+
+#[test]
+fn test_compare_versions_between_auras() {
+    let s = |v: &str| LuaValue::String(v.to_string());
+
+    // semver takes precedence over the numeric version
+    let existing = table(&[("semver", s("1.4.0")), ("version", LuaValue::Number(9.0))]);
+    let incoming = table(&[("semver", s("1.5.0")), ("version", LuaValue::Number(3.0))]);
+    assert_eq!(compare_versions(&existing, &incoming), VersionChange::Newer);
+
+    let existing = table(&[("version", LuaValue::Number(12.0))]);
+    let incoming = table(&[("version", LuaValue::Number(12.0))]);
+    assert_eq!(compare_versions(&existing, &incoming), VersionChange::Same);
+
+    // wago.io revision, only for the same aura
+    let existing = table(&[("url", s("https://wago.io/abcDEF/15"))]);
+    let incoming = table(&[("url", s("https://wago.io/abcDEF/12"))]);
+    assert_eq!(compare_versions(&existing, &incoming), VersionChange::Older);
+    let other = table(&[("url", s("https://wago.io/xyz/20"))]);
+    assert_eq!(compare_versions(&existing, &other), VersionChange::Unknown);
+
+    assert_eq!(
+        compare_versions(&table(&[]), &table(&[])),
+        VersionChange::Unknown
+    );
+}
+
+#[test]
+fn test_get_aura_tree_exposes_uid() {
+    let mut with_uid = make_aura("WithUid", None);
+    if let Some(t) = with_uid.as_table_mut() {
+        t.insert(
+            "uid".to_string(),
+            LuaValue::String("abcDEF12345".to_string()),
+        );
+    }
+    let mut displays = LuaTable::new();
+    displays.insert("WithUid".to_string(), with_uid);
+    displays.insert("NoUid".to_string(), make_aura("NoUid", None));
+
+    let mgr = manager_with_displays(displays);
+    let tree = mgr.get_aura_tree();
+
+    let uid_of = |id: &str| tree.iter().find(|n| n.id == id).unwrap().uid.clone();
+    assert_eq!(uid_of("WithUid").as_deref(), Some("abcDEF12345"));
+    assert_eq!(uid_of("NoUid"), None);
+}
+
+#[test]
+fn test_apply_resolutions_records_per_aura_outcomes() {
+    let mut displays = LuaTable::new();
+    displays.insert(
+        "Kept".to_string(),
+        make_aura_with_custom("Kept", None, "old"),
+    );
+    displays.insert(
+        "Merged".to_string(),
+        make_aura_with_custom("Merged", None, "old"),
+    );
+    let mut mgr = manager_with_displays(displays);
+
+    let incoming: Vec<WeakAura> = ["Kept", "Merged", "Fresh"]
+        .iter()
+        .map(|id| WeakAura {
+            id: id.to_string(),
+            uid: None,
+            region_type: Some("icon".to_string()),
+            is_group: false,
+            children: Vec::new(),
+            data: make_aura_with_custom(id, None, "new"),
+            child_data: Vec::new(),
+            original_string: String::new(),
+            encoding_version: 2,
+        })
+        .collect();
+
+    let conflicts = mgr.detect_conflicts(&incoming);
+    let resolutions = vec![
+        ConflictResolution {
+            aura_id: "Kept".to_string(),
+            action: ConflictAction::Skip,
+            categories_to_update: HashSet::new(),
+            ..Default::default()
+        },
+        ConflictResolution {
+            aura_id: "Merged".to_string(),
+            action: ConflictAction::UpdateSelected,
+            categories_to_update: UpdateCategory::all().into_iter().collect(),
+            ..Default::default()
+        },
+    ];
+
+    let result = mgr.apply_resolutions(&conflicts, &resolutions);
+
+    let outcome_of = |id: &str| {
+        result
+            .outcomes
+            .iter()
+            .find(|o| o.aura_id == id)
+            .map(|o| o.outcome.clone())
+            .unwrap()
+    };
+    assert_eq!(outcome_of("Fresh"), ImportOutcome::Added);
+    assert_eq!(outcome_of("Kept"), ImportOutcome::Skipped);
+    assert!(matches!(outcome_of("Merged"), ImportOutcome::Merged(_)));
+    // Merged auras still count as replaced in the summary
+    assert!(result.replaced.contains(&"Merged".to_string()));
+    assert_eq!(result.outcomes.len(), 3);
+}
+
+#[test]
+fn test_import_result_report_formats() {
+    let mut result = ImportResult::default();
+    result.record("Plain", ImportOutcome::Added);
+    result.record("Comma, \"Quoted\"", ImportOutcome::Skipped);
+    result.record(
+        "Partial",
+        ImportOutcome::Merged(vec![UpdateCategory::Trigger, UpdateCategory::Load]),
+    );
+
+    let csv = result.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "aura_id,outcome,categories");
+    assert_eq!(lines[1], "Plain,added,");
+    assert_eq!(lines[2], "\"Comma, \"\"Quoted\"\"\",skipped,");
+    assert_eq!(lines[3], "Partial,merged,Trigger;Load");
+
+    let json = result.to_json();
+    assert_eq!(json["summary"]["added"], 1);
+    assert_eq!(json["summary"]["replaced"], 1);
+    assert_eq!(json["auras"][2]["categories"][1], "Load");
+
+    assert!(result
+        .to_report_text()
+        .contains("merged\tPartial (Trigger, Load)"));
+}
+
+#[test]
+fn test_save_reapplies_changes_when_file_changed_on_disk() {
+    let dir = std::env::temp_dir().join("wa_sv_concurrent_tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+
+    let mut writer = SavedVariablesManager::new(path.clone());
+    writer
+        .displays
+        .insert("Alpha".to_string(), make_aura("Alpha", None));
+    writer
+        .displays
+        .insert("Old".to_string(), make_aura("Old", None));
+    writer.save().unwrap();
+
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager.load().unwrap();
+    assert!(!manager.is_stale());
+    manager
+        .add_auras(&[WeakAura {
+            id: "Gamma".to_string(),
+            uid: None,
+            region_type: Some("icon".to_string()),
+            is_group: false,
+            children: Vec::new(),
+            data: make_aura("Gamma", None),
+            child_data: Vec::new(),
+            original_string: String::new(),
+            encoding_version: 2,
+        }])
+        .unwrap();
+    manager.remove_auras(&["Old".to_string()]);
+
+    // Another instance saves in between
+    writer
+        .displays
+        .insert("Beta".to_string(), make_aura("Beta", None));
+    writer.save().unwrap();
+    assert!(manager.is_stale());
+
+    // Both the other write and our own changes survive
+    manager.save().unwrap();
+    assert!(!manager.is_stale());
+    let mut ids: Vec<&String> = manager.displays.keys().collect();
+    ids.sort();
+    assert_eq!(ids, vec!["Alpha", "Beta", "Gamma"]);
+
+    let mut reloaded = SavedVariablesManager::new(path.clone());
+    reloaded.load().unwrap();
+    assert_eq!(reloaded.displays.len(), 3);
+    assert!(reloaded.displays.contains_key("Beta"));
+    assert!(!reloaded.displays.contains_key("Old"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_concurrent_loads_and_saves_remove_the_lock_file() {
+    let dir = std::env::temp_dir().join("wa_sv_lock_tests");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+    let mut writer = SavedVariablesManager::new(path.clone());
+    for id in ["Alpha", "Beta"] {
+        writer.displays.insert(id.to_string(), make_aura(id, None));
+    }
+    writer.save().unwrap();
+
+    // Readers and writers contend for the lock, and take over sidecars the
+    // previous holder is removing
+    let threads: Vec<_> = (0..6)
+        .map(|i| {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                let mut manager = SavedVariablesManager::new(path);
+                for _ in 0..10 {
+                    manager.load().unwrap();
+                    if i % 2 == 0 {
+                        manager.save_without_backup().unwrap();
+                    }
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let mut reloaded = SavedVariablesManager::new(path.clone());
+    reloaded.load().unwrap();
+    assert_eq!(reloaded.displays.len(), 2);
+    // The lock sidecar only exists while the file is in use
+    assert!(!dir.join("WeakAuras.lua.lock").exists());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_generate_lua_only_reserializes_changed_displays() {
+    let dir = std::env::temp_dir().join("wa_sv_incremental_tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+    std::fs::write(
+        &path,
+        "WeakAurasSaved = {\n\t[\"displays\"] = {\n\t\t[\"A\"] = {[\"id\"]=\"A\",[\"x\"]=1},\n\t\t[\"B\"] = {[\"id\"]=\"B\"},\n\t},\n}\n",
+    )
+    .unwrap();
+
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager.load().unwrap();
+    let output = manager.generate_lua();
+    assert!(output.contains("[\"A\"] = {[\"id\"]=\"A\",[\"x\"]=1},"));
+    assert!(output.contains("[\"B\"] = {[\"id\"]=\"B\"},"));
+
+    // Only the edited display is written by the serializer
+    manager
+        .displays
+        .get_mut("B")
+        .and_then(LuaValue::as_table_mut)
+        .unwrap()
+        .insert("x".to_string(), LuaValue::Number(2.0));
+    let output = manager.generate_lua();
+    assert!(output.contains("[\"A\"] = {[\"id\"]=\"A\",[\"x\"]=1},"));
+    assert!(!output.contains("[\"B\"] = {[\"id\"]=\"B\"},"));
+
+    let reparsed = LuaParser::parse(&output).unwrap();
+    assert_eq!(reparsed.displays, manager.displays);
+
+    // Sorted output never reuses text in another key order
+    manager.sort_keys = true;
+    assert!(!manager.generate_lua().contains("{[\"id\"]=\"A\""));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_save_refuses_newer_db_version_unless_allowed() {
+    let dir = std::env::temp_dir().join("wa_sv_db_version_tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+    let newer = MAX_SUPPORTED_DB_VERSION + 1;
+    let content = format!(
+        "WeakAurasSaved = {{\n\t[\"dbVersion\"] = {},\n\t[\"displays\"] = {{\n\t}},\n}}\n",
+        newer
+    );
+    std::fs::write(&path, &content).unwrap();
+
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager.load().unwrap();
+    assert_eq!(manager.db_version(), Some(newer));
+    assert!(manager.has_unsupported_db_version());
+    manager
+        .displays
+        .insert("Alpha".to_string(), make_aura("Alpha", None));
+    assert!(matches!(
+        manager.save(),
+        Err(WeakAuraError::UnsupportedDbVersion(v)) if v == newer
+    ));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+
+    manager.allow_newer_db_version = true;
+    manager.save().unwrap();
+    assert!(std::fs::read_to_string(&path).unwrap().contains("Alpha"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_restore_backup_brings_back_previous_file() {
+    let dir = std::env::temp_dir().join("wa_sv_restore_backup_tests");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+    assert!(SavedVariablesManager::restore_backup(&path).is_err());
+
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager
+        .displays
+        .insert("Alpha".to_string(), make_aura("Alpha", None));
+    manager.save().unwrap();
+    let original = std::fs::read_to_string(&path).unwrap();
+    manager
+        .displays
+        .insert("Beta".to_string(), make_aura("Beta", None));
+    manager.save().unwrap();
+    let replaced = std::fs::read_to_string(&path).unwrap();
+    assert_ne!(replaced, original);
+
+    let kept = SavedVariablesManager::restore_backup(&path)
+        .unwrap()
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    // The file the backup replaced is kept, not lost
+    assert_eq!(kept, dir.join("WeakAuras.lua.damaged"));
+    assert_eq!(std::fs::read_to_string(&kept).unwrap(), replaced);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_add_auras_skips_malformed_auras_without_partial_inserts() {
+    let group = |id: &str, child_data: Vec<LuaValue>| WeakAura {
+        id: id.to_string(),
+        uid: None,
+        region_type: Some("group".to_string()),
+        is_group: true,
+        children: Vec::new(),
+        data: make_group(id, None, &[]),
+        child_data,
+        original_string: String::new(),
+        encoding_version: 2,
+    };
+    let auras = vec![
+        group("Good", vec![make_aura("Good Child", None)]),
+        // Second child points at a parent that is not part of the group
+        group(
+            "Orphaned",
+            vec![
+                make_aura("Orphaned Child", None),
+                make_aura("Lost Child", Some("Missing")),
+            ],
+        ),
+        group(
+            "Cyclic",
+            vec![
+                make_group("Sub A", Some("Sub B"), &[]),
+                make_group("Sub B", Some("Sub A"), &[]),
+            ],
+        ),
+        group("Not A Table", vec![LuaValue::Number(1.0)]),
+    ];
+
+    let mut manager = SavedVariablesManager::new(PathBuf::from("unused.lua"));
+    let result = manager.add_auras(&auras).unwrap();
+
+    assert_eq!(result.added, vec!["Good Child", "Good"]);
+    let failed: Vec<&str> = result.failed.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(failed, vec!["Orphaned", "Cyclic", "Not A Table"]);
+    assert!(result.failed[0].1.contains("Missing"));
+    assert!(result.failed[1].1.contains("cycle"));
+    assert!(result.summary().contains("3 failed"));
+
+    // Nothing of the failed groups was inserted
+    let mut ids: Vec<&String> = manager.displays.keys().collect();
+    ids.sort();
+    assert_eq!(ids, vec!["Good", "Good Child"]);
+}
+
+#[test]
+fn test_group_children_keep_game_order() {
+    let aura = WeakAura {
+        id: "Root".to_string(),
+        uid: None,
+        region_type: Some("dynamicgroup".to_string()),
+        is_group: true,
+        children: Vec::new(),
+        data: make_group("Root", None, &[]),
+        child_data: vec![
+            make_aura("Zeta", None),
+            make_group("Sub", None, &[]),
+            make_aura("Sub Zulu", Some("Sub")),
+            make_aura("Sub Alpha", Some("Sub")),
+            make_aura("Alpha", None),
+        ],
+        original_string: String::new(),
+        encoding_version: 2,
+    };
+    let mut manager = SavedVariablesManager::new(PathBuf::from("unused.lua"));
+    manager.add_auras(&[aura]).unwrap();
+
+    let controlled = |id: &str| -> Vec<String> {
+        manager.displays[id].as_table().unwrap()["controlledChildren"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| match v {
+                LuaValue::String(s) => s.clone(),
+                other => panic!("unexpected child entry {:?}", other),
+            })
+            .collect()
+    };
+    assert_eq!(controlled("Root"), vec!["Zeta", "Sub", "Alpha"]);
+    assert_eq!(controlled("Sub"), vec!["Sub Zulu", "Sub Alpha"]);
+
+    // The tree follows controlledChildren instead of sorting by name
+    let tree = manager.get_aura_tree();
+    let root = tree.iter().find(|n| n.id == "Root").unwrap();
+    let ids: Vec<&str> = root.children.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, vec!["Zeta", "Sub", "Alpha"]);
+    let orders: Vec<Option<usize>> = root.children.iter().map(|c| c.order).collect();
+    assert_eq!(orders, vec![Some(0), Some(1), Some(2)]);
+    let sub_ids: Vec<&str> = root.children[1]
+        .children
+        .iter()
+        .map(|c| c.id.as_str())
+        .collect();
+    assert_eq!(sub_ids, vec!["Sub Zulu", "Sub Alpha"]);
+    assert_eq!(root.order, None);
+}
+
+#[test]
+fn test_aura_tree_sort_orders() {
+    let mut displays = LuaTable::new();
+    displays.insert(
+        "Big".to_string(),
+        make_group("Big", None, &["Zed", "Bar", "Mid"]),
+    );
+    displays.insert("Zed".to_string(), make_aura("Zed", Some("Big")));
+    displays.insert("Bar".to_string(), make_group("Bar", Some("Big"), &[]));
+    displays.insert("Mid".to_string(), make_aura("Mid", Some("Big")));
+    displays.insert("Small".to_string(), make_group("Small", None, &["One"]));
+    displays.insert("One".to_string(), make_aura("One", Some("Small")));
+    displays.insert("alpha".to_string(), make_aura("alpha", None));
+    let tree = manager_with_displays(displays).get_aura_tree();
+
+    let ids =
+        |nodes: Vec<&AuraTreeNode>| -> Vec<String> { nodes.iter().map(|n| n.id.clone()).collect() };
+    // Game order: groups first at the top, controlledChildren order below
+    assert_eq!(
+        ids(AuraTreeSort::GameOrder.sorted(&tree)),
+        vec!["Big", "Small", "alpha"]
+    );
+    assert_eq!(
+        ids(AuraTreeSort::GameOrder.sorted(&tree[0].children)),
+        vec!["Zed", "Bar", "Mid"]
+    );
+    assert_eq!(
+        ids(AuraTreeSort::Alphabetical.sorted(&tree)),
+        vec!["alpha", "Big", "Small"]
+    );
+    assert_eq!(
+        ids(AuraTreeSort::Alphabetical.sorted(&tree[0].children)),
+        vec!["Bar", "Mid", "Zed"]
+    );
+    assert_eq!(
+        ids(AuraTreeSort::Size.sorted(&tree)),
+        vec!["Big", "Small", "alpha"]
+    );
+    assert_eq!(
+        ids(AuraTreeSort::RegionType.sorted(&tree[0].children)),
+        vec!["Bar", "Mid", "Zed"]
+    );
+    assert_eq!(tree[0].children[1].region_type.as_deref(), Some("group"));
+}
+
+#[test]
+fn test_tree_removal_selection_is_tri_state() {
+    let mut displays = LuaTable::new();
+    displays.insert(
+        "Root".to_string(),
+        make_group("Root", None, &["Sub", "Leaf"]),
+    );
+    displays.insert(
+        "Sub".to_string(),
+        make_group("Sub", Some("Root"), &["Inner"]),
+    );
+    displays.insert("Inner".to_string(), make_aura("Inner", Some("Sub")));
+    displays.insert("Leaf".to_string(), make_aura("Leaf", Some("Root")));
+    let tree = manager_with_displays(displays).get_aura_tree();
+    let root = &tree[0];
+    let mut selected = HashSet::new();
+
+    // Checking a group checks everything below it
+    set_tree_checked(&tree, &mut selected, "Sub", true);
+    assert_eq!(root.check_state(&selected), CheckState::Partial);
+    assert_eq!(root.children[0].check_state(&selected), CheckState::Checked);
+    assert!(selected.contains("Inner"));
+
+    // All children checked still leaves the parent itself partial
+    set_tree_checked(&tree, &mut selected, "Leaf", true);
+    assert_eq!(root.check_state(&selected), CheckState::Partial);
+
+    set_tree_checked(&tree, &mut selected, "Root", true);
+    assert_eq!(root.check_state(&selected), CheckState::Checked);
+
+    // Unchecking a child unchecks its ancestors but keeps its siblings
+    set_tree_checked(&tree, &mut selected, "Inner", false);
+    assert_eq!(root.check_state(&selected), CheckState::Partial);
+    assert_eq!(
+        root.children[0].check_state(&selected),
+        CheckState::Unchecked
+    );
+    let mut remaining: Vec<&String> = selected.iter().collect();
+    remaining.sort();
+    assert_eq!(remaining, vec!["Leaf"]);
+
+    set_tree_checked(&tree, &mut selected, "Leaf", false);
+    assert_eq!(root.check_state(&selected), CheckState::Unchecked);
+}
+
+#[test]
+fn test_dissolve_group_promotes_children() {
+    let mut displays = LuaTable::new();
+    displays.insert("Outer".to_string(), make_group("Outer", None, &["Inner"]));
+    displays.insert(
+        "Inner".to_string(),
+        make_group("Inner", Some("Outer"), &["Sub", "Leaf"]),
+    );
+    displays.insert(
+        "Sub".to_string(),
+        make_group("Sub", Some("Inner"), &["Deep"]),
+    );
+    displays.insert("Deep".to_string(), make_aura("Deep", Some("Sub")));
+    displays.insert("Leaf".to_string(), make_aura("Leaf", Some("Inner")));
+    let mut manager = manager_with_displays(displays);
+
+    assert_eq!(manager.dissolve_group("Leaf"), None);
+    assert_eq!(manager.dissolve_group("Missing"), None);
+    assert_eq!(
+        manager.dissolve_group("Inner"),
+        Some(vec!["Sub".to_string(), "Leaf".to_string()])
+    );
+
+    assert!(!manager.displays.contains_key("Inner"));
+    let table = |id: &str| manager.displays[id].as_table().unwrap().clone();
+    assert!(!table("Sub").contains_key("parent"));
+    assert!(!table("Leaf").contains_key("parent"));
+    // Grandchildren stay in their own subgroup
+    assert_eq!(
+        table("Deep").get("parent"),
+        Some(&LuaValue::String("Sub".to_string()))
+    );
+    assert_eq!(
+        table("Outer").get("controlledChildren"),
+        Some(&LuaValue::Array(Vec::new()))
+    );
+
+    let tree = manager.get_aura_tree();
+    let top: Vec<&str> = tree.iter().map(|n| n.id.as_str()).collect();
+    assert_eq!(top, vec!["Outer", "Sub", "Leaf"]);
+}
+
+#[test]
+fn test_move_into_group_extends_controlled_children() {
+    let mut displays = LuaTable::new();
+    displays.insert("Target".to_string(), make_group("Target", None, &["Old"]));
+    displays.insert("Old".to_string(), make_aura("Old", Some("Target")));
+    displays.insert("Other".to_string(), make_group("Other", None, &["Moved"]));
+    displays.insert("Moved".to_string(), make_aura("Moved", Some("Other")));
+    displays.insert("Loose".to_string(), make_aura("Loose", None));
+    let mut manager = manager_with_displays(displays);
+
+    assert_eq!(manager.move_into_group("Loose", &["Old".to_string()]), None);
+    let ids: Vec<String> = ["Loose", "Moved", "Target", "Missing"]
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+    assert_eq!(
+        manager.move_into_group("Target", &ids),
+        Some(vec!["Loose".to_string(), "Moved".to_string()])
+    );
+
+    let table = |id: &str| manager.displays[id].as_table().unwrap().clone();
+    let strings = |ids: &[&str]| {
+        LuaValue::Array(
+            ids.iter()
+                .map(|id| LuaValue::String(id.to_string()))
+                .collect(),
+        )
+    };
+    assert_eq!(
+        table("Target").get("controlledChildren"),
+        Some(&strings(&["Old", "Loose", "Moved"]))
+    );
+    assert_eq!(
+        table("Other").get("controlledChildren"),
+        Some(&strings(&[]))
+    );
+    assert_eq!(
+        table("Moved").get("parent"),
+        Some(&LuaValue::String("Target".to_string()))
+    );
+    assert!(!table("Target").contains_key("parent"));
+
+    let tree = manager.get_aura_tree();
+    let groups: Vec<&str> = tree.iter().flat_map(|node| node.group_ids()).collect();
+    assert_eq!(groups, vec!["Other", "Target"]);
+}
+
+/// Icon anchored to another aura, as WeakAuras stores it
+fn make_anchored_aura(id: &str, parent: Option<&str>, anchor: &str) -> LuaValue {
+    let mut aura = make_aura(id, parent);
+    let table = aura.as_table_mut().unwrap();
+    table.insert(
+        "anchorFrameType".to_string(),
+        LuaValue::String("SELECTFRAME".to_string()),
+    );
+    table.insert(
+        "anchorFrameFrame".to_string(),
+        LuaValue::String(format!("WeakAuras:{}", anchor)),
+    );
+    aura
+}
+
+#[test]
+fn test_anchor_dependents_of_removed_auras() {
+    let mut displays = LuaTable::new();
+    displays.insert(
+        "Group".to_string(),
+        make_group("Group", None, &["Bar", "Sibling"]),
+    );
+    displays.insert("Bar".to_string(), make_aura("Bar", Some("Group")));
+    displays.insert(
+        "Follower".to_string(),
+        make_anchored_aura("Follower", None, "Bar"),
+    );
+    displays.insert(
+        "Sibling".to_string(),
+        make_anchored_aura("Sibling", Some("Group"), "Bar"),
+    );
+    let mut manager = manager_with_displays(displays);
+
+    // Anchors inside the removed group don't count
+    let tree = manager.get_aura_tree();
+    let selected: HashSet<String> = ["Group", "Bar", "Sibling"]
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+    let expected = vec![("Follower".to_string(), "Bar".to_string())];
+    assert_eq!(tree_anchor_dependents(&tree, &selected), expected);
+
+    let removed = manager.remove_auras(&["Group".to_string()]);
+    assert_eq!(manager.anchor_dependents(&removed), expected);
+
+    assert_eq!(manager.anchor_to_screen(&["Follower".to_string()]), 1);
+    assert!(manager.anchor_dependents(&removed).is_empty());
+    let follower = manager.displays["Follower"].as_table().unwrap();
+    assert_eq!(
+        follower.get("anchorFrameType"),
+        Some(&LuaValue::String("SCREEN".to_string()))
+    );
+    assert!(!follower.contains_key("anchorFrameFrame"));
+}
+
+#[test]
+fn test_topmost_selected_skips_nested_selections() {
+    let mut displays = LuaTable::new();
+    displays.insert("Root".to_string(), make_group("Root", None, &["Sub"]));
+    displays.insert(
+        "Sub".to_string(),
+        make_group("Sub", Some("Root"), &["Leaf"]),
+    );
+    displays.insert("Leaf".to_string(), make_aura("Leaf", Some("Sub")));
+    displays.insert("Solo".to_string(), make_aura("Solo", None));
+    let tree = manager_with_displays(displays).get_aura_tree();
+
+    let selected: HashSet<String> = ["Sub", "Leaf", "Solo"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let ids: Vec<&str> = topmost_selected(&tree, &selected)
+        .iter()
+        .map(|n| n.id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["Sub", "Solo"]);
+}
+
+#[test]
+fn test_export_displays_lua_includes_descendants() {
+    let mut displays = LuaTable::new();
+    displays.insert("Group".to_string(), make_group("Group", None, &["Child"]));
+    displays.insert("Child".to_string(), make_aura("Child", Some("Group")));
+    displays.insert("Other".to_string(), make_aura("Other", None));
+    let manager = manager_with_displays(displays);
+
+    let lua = manager.export_displays_lua(&["Group".to_string(), "Child".to_string()]);
+    assert!(lua.starts_with("-- WeakAuras displays exported before removal."));
+    assert_eq!(lua.matches("[\"Child\"] = {").count(), 1);
+    assert!(lua.contains("[\"Group\"] = {"));
+    assert!(!lua.contains("[\"Other\"]"));
+
+    // The dump parses back into the same auras
+    let parsed = LuaParser::parse_value(&lua[lua.find('{').unwrap()..]).unwrap();
+    let table = parsed.as_table().unwrap();
+    assert_eq!(table.get("Child"), manager.displays.get("Child"));
+}
+
+#[test]
+fn test_compare_with_backup_lists_changed_fields() {
+    let dir = std::env::temp_dir().join("wa_sv_compare_backup_tests");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager
+        .displays
+        .insert("Alpha".to_string(), make_aura("Alpha", None));
+    manager.save().unwrap();
+    assert!(manager.compare_with_backup("Alpha").is_err());
+
+    let mut changed = make_aura("Alpha", None);
+    let table = changed.as_table_mut().unwrap();
+    table.insert("load".to_string(), LuaValue::Table(LuaTable::new().into()));
+    table.insert("width".to_string(), LuaValue::Number(64.0));
+    manager.displays.insert("Alpha".to_string(), changed);
+    manager
+        .displays
+        .insert("Beta".to_string(), make_aura("Beta", None));
+    manager.save().unwrap();
+
+    let comparison = manager.compare_with_backup("Alpha").unwrap();
+    assert!(comparison.backup.is_some() && comparison.current.is_some());
+    let changes = comparison.changes();
+    let categories: Vec<UpdateCategory> = changes.iter().map(|(c, _)| *c).collect();
+    assert!(categories.contains(&UpdateCategory::Load));
+    let fields: Vec<&String> = changes.iter().flat_map(|(_, f)| f).collect();
+    assert_eq!(fields.len(), 2);
+
+    // Added by the last save
+    let added = manager.compare_with_backup("Beta").unwrap();
+    assert!(added.backup.is_none());
+    assert_eq!(
+        added.changes().iter().map(|(_, f)| f.len()).sum::<usize>(),
+        2
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_uid_collisions_get_fresh_uids() {
+    let s = |v: &str| LuaValue::String(v.to_string());
+    let mut displays = LuaTable::new();
+    displays.insert(
+        "Stored".to_string(),
+        table(&[("id", s("Stored")), ("uid", s("sharedUid01"))]),
+    );
+    displays.insert(
+        "Same".to_string(),
+        table(&[("id", s("Same")), ("uid", s("sameUid0001"))]),
+    );
+    let mut manager = manager_with_displays(displays);
+
+    let aura = |id: &str, uid: &str| WeakAura {
+        id: id.to_string(),
+        uid: Some(uid.to_string()),
+        region_type: Some("icon".to_string()),
+        is_group: false,
+        children: Vec::new(),
+        data: table(&[
+            ("id", s(id)),
+            ("uid", s(uid)),
+            ("width", LuaValue::Number(40.0)),
+        ]),
+        child_data: Vec::new(),
+        original_string: String::new(),
+        encoding_version: 2,
+    };
+    // "Copy" reuses a stored aura's uid, "Twin" an incoming one; "Same"
+    // updates the aura that owns its uid
+    let incoming = vec![
+        aura("Copy", "sharedUid01"),
+        aura("Same", "sameUid0001"),
+        aura("Fresh", "freshUid001"),
+        aura("Twin", "freshUid001"),
+    ];
+
+    let mut detection = manager.detect_conflicts(&incoming);
+    let collided: Vec<(&str, &str)> = detection
+        .uid_collisions
+        .iter()
+        .map(|c| (c.aura_id.as_str(), c.existing_id.as_str()))
+        .collect();
+    assert_eq!(collided, vec![("Copy", "Stored"), ("Twin", "Fresh")]);
+
+    let uids = detection.assign_fresh_uids();
+    assert!(detection.uid_collisions.is_empty());
+    assert_eq!(uids.len(), 2);
+    for uid in uids.values() {
+        assert_eq!(uid.len(), 11);
+        assert!(uid
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '(' || c == ')'));
+    }
+    assert_ne!(uids["Copy"], uids["Twin"]);
+    let mut copy = incoming[0].clone();
+    assert_eq!(copy.replace_uids(&uids), 1);
+    assert_eq!(copy.uid.as_deref(), Some(uids["Copy"].as_str()));
+
+    manager.apply_resolutions(&detection, &[]);
+    let uid_of = |id: &str| manager.displays[id].get_path("uid").cloned();
+    assert_eq!(uid_of("Copy"), Some(s(&uids["Copy"])));
+    assert_eq!(uid_of("Twin"), Some(s(&uids["Twin"])));
+    assert_eq!(uid_of("Stored"), Some(s("sharedUid01")));
+    assert_eq!(uid_of("Fresh"), Some(s("freshUid001")));
+}
+
+#[test]
+fn test_rename_aura_updates_references() {
+    let mut displays = LuaTable::new();
+    displays.insert(
+        "Group".to_string(),
+        make_group("Group", None, &["Bar", "Other"]),
+    );
+    displays.insert(
+        "Bar".to_string(),
+        make_group("Bar", Some("Group"), &["Inner"]),
+    );
+    displays.insert("Inner".to_string(), make_aura("Inner", Some("Bar")));
+    displays.insert("Other".to_string(), make_aura("Other", Some("Group")));
+    displays.insert(
+        "Follower".to_string(),
+        make_anchored_aura("Follower", None, "Bar"),
+    );
+    let mut manager = manager_with_displays(displays);
+
+    assert!(manager.rename_aura("Missing", "New").is_err());
+    assert!(manager.rename_aura("Bar", "Other").is_err());
+    assert!(manager.rename_aura("Bar", " ").is_err());
+
+    manager.rename_aura("Bar", "Bars").unwrap();
+    let ids: Vec<&str> = manager.displays.keys().map(String::as_str).collect();
+    assert_eq!(ids, vec!["Group", "Bars", "Inner", "Other", "Follower"]);
+    let s = |v: &str| LuaValue::String(v.to_string());
+    assert_eq!(manager.displays["Bars"].get_path("id"), Some(&s("Bars")));
+    assert_eq!(
+        manager.displays["Inner"].get_path("parent"),
+        Some(&s("Bars"))
+    );
+    assert_eq!(
+        manager.displays["Group"].get_path("controlledChildren.1"),
+        Some(&s("Bars"))
+    );
+    assert_eq!(
+        manager.displays["Follower"].get_path("anchorFrameFrame"),
+        Some(&s("WeakAuras:Bars"))
+    );
+}
+
+#[test]
+fn test_comparison_reports_import_deltas() {
+    let dir = std::env::temp_dir().join("wa_sv_comparison_tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+    let _ = std::fs::remove_file(&path);
+
+    // A file that did not exist compares against nothing
+    let mut manager = SavedVariablesManager::new(path.clone());
+    assert!(manager.load().is_err());
+    manager
+        .displays
+        .insert("Alpha".to_string(), make_aura("Alpha", None));
+    manager.save().unwrap();
+    let created = manager.comparison();
+    assert_eq!(created.before.aura_count, 0);
+    assert_eq!(created.before.file_size, 0);
+    assert_eq!(created.after.aura_count, 1);
+    assert!(created.after.file_size > 0);
+    assert!(created.new_groups.is_empty());
+
+    let mut manager = SavedVariablesManager::new(path.clone());
+    manager.load().unwrap();
+    manager
+        .displays
+        .insert("Pack".to_string(), make_group("Pack", None, &["Child"]));
+    manager
+        .displays
+        .insert("Child".to_string(), make_aura("Child", Some("Pack")));
+    manager.save().unwrap();
+
+    let comparison = manager.comparison();
+    assert_eq!(comparison.before.aura_count, 1);
+    assert_eq!(comparison.after.aura_count, 3);
+    assert_eq!(comparison.after.group_count, 1);
+    assert_eq!(comparison.aura_delta(), 2);
+    assert!(comparison.size_delta() > 0);
+    assert_eq!(comparison.new_groups, vec!["Pack".to_string()]);
+    let summary = comparison.summary();
+    assert!(summary.starts_with("+2 auras (1 → 3), +"), "{}", summary);
+    assert!(summary.ends_with(", 1 new group"), "{}", summary);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_remove_auras_survives_cyclic_children() {
+    // Damaged file: two groups listing each other as children
+    let mut displays = LuaTable::new();
+    displays.insert("A".to_string(), make_group("A", None, &["B", "B"]));
+    displays.insert("B".to_string(), make_group("B", Some("A"), &["A"]));
+    displays.insert("Other".to_string(), make_aura("Other", None));
+
+    let mut mgr = manager_with_displays(displays);
+    let mut removed = mgr.remove_auras(&["A".to_string()]);
+    removed.sort();
+    assert_eq!(removed, vec!["A".to_string(), "B".to_string()]);
+    assert_eq!(mgr.displays.len(), 1);
+}
+
+#[test]
+fn test_aura_tree_flattens_deep_group_chains() {
+    // 500 groups each holding the next, the last one holding an aura
+    let mut displays = LuaTable::new();
+    let group_id = |i: usize| format!("Group {}", i);
+    for i in 0..500 {
+        let parent = (i > 0).then(|| group_id(i - 1));
+        let child = if i == 499 {
+            "Leaf".to_string()
+        } else {
+            group_id(i + 1)
+        };
+        displays.insert(
+            group_id(i),
+            make_group(&group_id(i), parent.as_deref(), &[&child]),
+        );
+    }
+    displays.insert("Leaf".to_string(), make_aura("Leaf", Some("Group 499")));
+
+    let mgr = manager_with_displays(displays);
+    let tree = mgr.get_aura_tree();
+    assert_eq!(tree.len(), 1);
+    assert_eq!(tree[0].total_count(), 501);
+
+    let mut depth = 1;
+    let mut node = &tree[0];
+    while let Some(child) = node.children.first() {
+        depth += 1;
+        node = child;
+    }
+    assert!(depth < 100, "tree is {} levels deep", depth);
+    // Everything below the cut-off keeps its order
+    let ids = tree[0].ids();
+    assert_eq!(ids[ids.len() - 2], "Group 499");
+    assert_eq!(ids[ids.len() - 1], "Leaf");
+}
@@ -0,0 +1,188 @@
+//! Tests for the folder scan and its options.
+
+use std::path::{Path, PathBuf};
+
+use weakauras_mass_import::api::{scan_folder, ScanOptions};
+
+/// Fresh folder with the given files, each holding `size` bytes
+fn temp_tree(name: &str, files: &[(&str, usize)]) -> PathBuf {
+    let dir = std::env::temp_dir().join("wa_scan_tests").join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    for (file, size) in files {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "x".repeat(*size)).unwrap();
+    }
+    dir
+}
+
+fn relative(root: &Path, paths: &[PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|p| {
+            p.strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect()
+}
+
+#[test]
+fn test_scan_default_options() {
+    let root = temp_tree(
+        "defaults",
+        &[
+            ("a.txt", 1),
+            ("b.LUA", 1),
+            ("notes.md", 1),
+            ("image.png", 1),
+            ("pack/c.txt", 1),
+            (".git/d.txt", 1),
+        ],
+    );
+    let scan = scan_folder(&root, &ScanOptions::default()).unwrap();
+    assert_eq!(
+        relative(&root, &scan.files),
+        vec!["a.txt", "b.LUA", "notes.md", "pack/c.txt"]
+    );
+    assert!(scan.too_large.is_empty());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_scan_extensions_and_ignore_patterns() {
+    let root = temp_tree(
+        "ignore",
+        &[
+            ("a.txt", 1),
+            ("a.bak.txt", 1),
+            ("strings.json", 1),
+            ("Backups/old.txt", 1),
+            ("pack/Backups/old.txt", 1),
+            ("pack/keep.txt", 1),
+            ("pack/old/skip.txt", 1),
+            ("deep/x/y/skip.json", 1),
+        ],
+    );
+    let options = ScanOptions {
+        extensions: vec!["txt".to_string(), ".json".to_string()],
+        ignore: vec![
+            "Backups/".to_string(),
+            "*.bak.txt".to_string(),
+            "pack/old/*".to_string(),
+            "deep/**/skip.json".to_string(),
+        ],
+        ..ScanOptions::default()
+    };
+    let scan = scan_folder(&root, &options).unwrap();
+    assert_eq!(
+        relative(&root, &scan.files),
+        vec!["a.txt", "pack/keep.txt", "strings.json"]
+    );
+
+    // A trailing slash only matches folders
+    let options = ScanOptions {
+        ignore: vec!["a.txt/".to_string()],
+        ..ScanOptions::default()
+    };
+    let scan = scan_folder(&root, &options).unwrap();
+    assert!(relative(&root, &scan.files).contains(&"a.txt".to_string()));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_scan_max_file_size() {
+    let root = temp_tree("size", &[("small.txt", 10), ("big.txt", 100)]);
+    let options = ScanOptions {
+        max_file_size: Some(50),
+        ..ScanOptions::default()
+    };
+    let scan = scan_folder(&root, &options).unwrap();
+    assert_eq!(relative(&root, &scan.files), vec!["small.txt"]);
+    assert_eq!(relative(&root, &scan.too_large), vec!["big.txt"]);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_scan_invalid_pattern() {
+    let options = ScanOptions {
+        ignore: vec!["/".to_string()],
+        ..ScanOptions::default()
+    };
+    assert!(options.validate().is_err());
+    assert!(scan_folder(&std::env::temp_dir(), &options).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_scan_symlinks() {
+    let root = temp_tree("symlinks", &[("a.txt", 1)]);
+    let outside = temp_tree("symlinks_target", &[("linked.txt", 1)]);
+    std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+    let scan = scan_folder(&root, &ScanOptions::default()).unwrap();
+    assert_eq!(
+        relative(&root, &scan.files),
+        vec!["a.txt", "link/linked.txt"]
+    );
+
+    let options = ScanOptions {
+        follow_symlinks: false,
+        ..ScanOptions::default()
+    };
+    let scan = scan_folder(&root, &options).unwrap();
+    assert_eq!(relative(&root, &scan.files), vec!["a.txt"]);
+
+    let _ = std::fs::remove_dir_all(&root);
+    let _ = std::fs::remove_dir_all(&outside);
+}
+
+#[test]
+fn test_scan_max_depth() {
+    let root = temp_tree(
+        "depth",
+        &[("a.txt", 1), ("one/b.txt", 1), ("one/two/c.txt", 1)],
+    );
+    let depth = |max_depth| {
+        let options = ScanOptions {
+            max_depth,
+            ..ScanOptions::default()
+        };
+        relative(&root, &scan_folder(&root, &options).unwrap().files)
+    };
+    assert_eq!(depth(Some(0)), vec!["a.txt"]);
+    assert_eq!(depth(Some(1)), vec!["a.txt", "one/b.txt"]);
+    assert_eq!(depth(None), vec!["a.txt", "one/b.txt", "one/two/c.txt"]);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_scan_missing_folder_is_error() {
+    let missing = std::env::temp_dir().join("wa_scan_tests").join("missing");
+    let _ = std::fs::remove_dir_all(&missing);
+    assert!(scan_folder(&missing, &ScanOptions::default()).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_scan_collects_unreadable_entries_and_goes_on() {
+    let root = temp_tree("unreadable", &[("a.txt", 1), ("pack/b.txt", 1)]);
+    // A link back to its own parent and a dangling link
+    std::os::unix::fs::symlink(root.join("pack"), root.join("pack/loop")).unwrap();
+    std::os::unix::fs::symlink(root.join("gone.txt"), root.join("dangling.txt")).unwrap();
+
+    let scan = scan_folder(&root, &ScanOptions::default()).unwrap();
+    assert_eq!(relative(&root, &scan.files), vec!["a.txt", "pack/b.txt"]);
+    assert_eq!(scan.errors.len(), 2, "{:?}", scan.errors);
+    assert!(scan
+        .errors
+        .iter()
+        .any(|(_, error)| error.contains("symlink loop")));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
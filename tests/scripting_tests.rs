@@ -0,0 +1,127 @@
+//! Tests for running batch scripts against existing auras.
+
+use std::path::PathBuf;
+
+use weakauras_mass_import::api::{run_script, SavedVariablesManager};
+use weakauras_mass_import::decoder::{LuaTable, LuaValue};
+use weakauras_mass_import::error::WeakAuraError;
+
+fn manager() -> SavedVariablesManager {
+    let mut displays = LuaTable::new();
+    for data in [
+        serde_json::json!({
+            "id": "Raid", "regionType": "dynamicgroup", "controlledChildren": ["Icon", "Bars"]
+        }),
+        serde_json::json!({
+            "id": "Icon", "parent": "Raid", "regionType": "icon",
+            "actions": { "start": { "do_sound": true, "sound": "bell.ogg" } }
+        }),
+        serde_json::json!({
+            "id": "Bars", "parent": "Raid", "regionType": "dynamicgroup",
+            "controlledChildren": ["Bar"]
+        }),
+        serde_json::json!({
+            "id": "Bar", "parent": "Bars", "regionType": "aurabar",
+            "actions": { "start": { "sound": "horn.ogg" } }
+        }),
+        serde_json::json!({
+            "id": "Other", "regionType": "icon",
+            "actions": { "start": { "sound": "gong.ogg" } }
+        }),
+    ] {
+        let id = data["id"].as_str().unwrap().to_string();
+        displays.insert(id, LuaValue::from_json(data));
+    }
+    let mut manager = SavedVariablesManager::new(PathBuf::from("test_scripting.lua"));
+    manager.displays = displays;
+    manager
+}
+
+const MUTE_RAID: &str = r#"
+    for id in descendants("Raid") {
+        if model(id).region_type != "dynamicgroup" {
+            set(id, "actions.start.sound", ());
+            print(`muted ${id}`);
+        }
+    }
+"#;
+
+#[test]
+fn test_dry_run_leaves_manager_alone() {
+    let mut manager = manager();
+    let before = manager.displays.clone();
+    let report = run_script(&mut manager, MUTE_RAID, true).unwrap();
+
+    assert!(!report.applied);
+    assert_eq!(report.output, vec!["muted Icon", "muted Bar"]);
+    assert_eq!(report.changed_ids(), vec!["Icon", "Bar"]);
+    assert_eq!(report.changes[0].old, LuaValue::String("bell.ogg".into()));
+    assert_eq!(report.changes[0].new, LuaValue::Nil);
+    assert!(report
+        .to_text()
+        .contains("Icon: actions.start.sound = \"bell.ogg\" -> nil"));
+    assert_eq!(manager.displays, before);
+}
+
+#[test]
+fn test_run_applies_changes() {
+    let mut manager = manager();
+    let report = run_script(&mut manager, MUTE_RAID, false).unwrap();
+    assert!(report.applied);
+    assert_eq!(
+        manager.displays["Icon"].get_path("actions.start.sound"),
+        None
+    );
+    assert_eq!(
+        manager.displays["Icon"].get_path("actions.start.do_sound"),
+        Some(&LuaValue::Bool(true))
+    );
+    assert_eq!(
+        manager.displays["Bar"].get_path("actions.start.sound"),
+        None
+    );
+    assert!(manager.displays["Other"]
+        .get_path("actions.start.sound")
+        .is_some());
+
+    let script =
+        r#"for id in ids() { if get(id, "regionType") == "icon" { set(id, "width", 48); } }"#;
+    let report = run_script(&mut manager, script, false).unwrap();
+    assert_eq!(report.changed_ids(), vec!["Icon", "Other"]);
+    assert_eq!(
+        manager.displays["Other"].get_path("width"),
+        Some(&LuaValue::Number(48.0))
+    );
+}
+
+#[test]
+fn test_failing_script_changes_nothing() {
+    let mut manager = manager();
+    let before = manager.displays.clone();
+    let error = run_script(
+        &mut manager,
+        r#"set("Icon", "width", 1); set("Missing", "width", 1);"#,
+        false,
+    )
+    .unwrap_err();
+    assert!(matches!(error, WeakAuraError::ScriptError(_)));
+    assert!(error.to_string().contains("no aura called 'Missing'"));
+    assert_eq!(manager.displays, before);
+}
+
+#[test]
+fn test_script_is_sandboxed() {
+    let mut manager = manager();
+    assert!(run_script(&mut manager, "loop {}", true).is_err());
+    assert!(run_script(&mut manager, r#"eval("1 + 1")"#, true).is_err());
+
+    let dir = std::env::temp_dir().join("wa_scripting_import_tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("helper.rhai"), "fn answer() { 42 }").unwrap();
+    let script = format!(
+        r#"import "{}" as helper; print(helper::answer());"#,
+        dir.join("helper").display().to_string().replace('\\', "/")
+    );
+    assert!(run_script(&mut manager, &script, true).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
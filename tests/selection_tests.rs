@@ -0,0 +1,183 @@
+//! Tests for bulk selection rules.
+
+use std::path::Path;
+
+use weakauras_mass_import::decoder::{LuaValue, ValidationResult, WeakAura};
+use weakauras_mass_import::selection::{
+    trivial_reason, ClickMode, RangeSelection, SelectionMode, SelectionRule,
+};
+
+fn make_entry(id: &str, region_type: &str, is_group: bool) -> (ValidationResult, WeakAura) {
+    let validation = ValidationResult {
+        is_valid: true,
+        aura_id: Some(id.to_string()),
+        is_group,
+        child_count: 0,
+        error: None,
+    };
+    let aura = WeakAura {
+        id: id.to_string(),
+        uid: None,
+        region_type: Some(region_type.to_string()),
+        is_group,
+        children: Vec::new(),
+        data: LuaValue::Nil,
+        child_data: Vec::new(),
+        original_string: String::new(),
+        encoding_version: 2,
+    };
+    (validation, aura)
+}
+
+#[test]
+fn test_selection_rule_default_matches_everything() {
+    let (validation, aura) = make_entry("Aura", "icon", false);
+    let rule = SelectionRule::default();
+    assert!(rule.matches(&validation, Some(&aura), None));
+}
+
+#[test]
+fn test_selection_rule_groups_and_region_type() {
+    let (icon_v, icon) = make_entry("Icon", "icon", false);
+    let (group_v, group) = make_entry("Group", "dynamicgroup", true);
+
+    let groups_only = SelectionRule {
+        groups_only: true,
+        ..SelectionRule::default()
+    };
+    assert!(!groups_only.matches(&icon_v, Some(&icon), None));
+    assert!(groups_only.matches(&group_v, Some(&group), None));
+
+    let icons = SelectionRule {
+        region_type: Some("icon".to_string()),
+        ..SelectionRule::default()
+    };
+    assert!(icons.matches(&icon_v, Some(&icon), None));
+    assert!(!icons.matches(&group_v, Some(&group), None));
+}
+
+#[test]
+fn test_selection_rule_valid_only() {
+    let invalid = ValidationResult {
+        is_valid: false,
+        aura_id: None,
+        is_group: false,
+        child_count: 0,
+        error: Some("bad string".to_string()),
+    };
+    let rule = SelectionRule {
+        valid_only: true,
+        ..SelectionRule::default()
+    };
+    assert!(!rule.matches(&invalid, None, None));
+}
+
+#[test]
+fn test_selection_rule_source_file() {
+    let (validation, aura) = make_entry("Aura", "icon", false);
+    let rule = SelectionRule {
+        source_file: Some("raid.txt".to_string()),
+        ..SelectionRule::default()
+    };
+    assert!(rule.matches(
+        &validation,
+        Some(&aura),
+        Some(Path::new("/imports/raid.txt"))
+    ));
+    assert!(!rule.matches(
+        &validation,
+        Some(&aura),
+        Some(Path::new("/imports/pvp.txt"))
+    ));
+    assert!(!rule.matches(&validation, Some(&aura), None));
+}
+
+#[test]
+fn test_selection_rule_id_pattern() {
+    let (boss_v, boss) = make_entry("Boss Timer", "aurabar", false);
+    let (trash_v, trash) = make_entry("Trash Warning", "text", false);
+
+    let rule = SelectionRule::default()
+        .with_id_pattern("^boss.*timer$")
+        .unwrap();
+    assert!(rule.matches(&boss_v, Some(&boss), None));
+    assert!(!rule.matches(&trash_v, Some(&trash), None));
+
+    let cleared = rule.with_id_pattern("  ").unwrap();
+    assert!(cleared.matches(&trash_v, Some(&trash), None));
+
+    assert!(SelectionRule::default()
+        .with_id_pattern("(unclosed")
+        .is_err());
+}
+
+#[test]
+fn test_selection_mode_apply() {
+    assert!(SelectionMode::Select.apply(false, true));
+    assert!(SelectionMode::Select.apply(true, false));
+    assert!(!SelectionMode::Deselect.apply(true, true));
+    assert!(SelectionMode::Deselect.apply(true, false));
+    assert!(!SelectionMode::Only.apply(true, false));
+    assert!(SelectionMode::Only.apply(false, true));
+}
+
+#[test]
+fn test_range_selection_clicks() {
+    assert_eq!(ClickMode::from_modifiers(true, true), ClickMode::Range);
+    assert_eq!(ClickMode::from_modifiers(false, true), ClickMode::Toggle);
+    assert_eq!(ClickMode::from_modifiers(false, false), ClickMode::Single);
+
+    let order = vec![4, 0, 2, 7, 5];
+    let mut range = RangeSelection::default();
+    // Without an anchor a range is just the clicked item, which becomes the anchor
+    assert_eq!(range.click(&order, &2, ClickMode::Range), vec![2]);
+    assert_eq!(range.anchor(), Some(&2));
+
+    assert_eq!(range.click(&order, &5, ClickMode::Range), vec![2, 7, 5]);
+    assert_eq!(range.click(&order, &4, ClickMode::Range), vec![4, 0, 2]);
+    assert_eq!(range.anchor(), Some(&2));
+
+    assert_eq!(range.click(&order, &7, ClickMode::Toggle), vec![7]);
+    assert_eq!(range.click(&order, &0, ClickMode::Range), vec![0, 2, 7]);
+
+    // An anchor no longer shown starts over
+    assert_eq!(range.click(&[1, 3], &3, ClickMode::Range), vec![3]);
+    range.clear();
+    assert_eq!(range.anchor(), None);
+}
+
+fn aura_with_data(data: serde_json::Value) -> WeakAura {
+    let (_, mut aura) = make_entry("Aura", "icon", false);
+    aura.data = LuaValue::from_json(data);
+    aura
+}
+
+#[test]
+fn test_trivial_reason() {
+    let trigger = serde_json::json!({ "1": { "trigger": { "type": "aura2" } } });
+
+    let working = aura_with_data(serde_json::json!({
+        "id": "Aura", "triggers": trigger, "width": 40, "height": 40
+    }));
+    assert_eq!(trivial_reason(&working), None);
+
+    let description = aura_with_data(serde_json::json!({
+        "id": "Aura", "desc": "Put your own triggers here", "regionType": "text"
+    }));
+    assert_eq!(trivial_reason(&description), Some("only a description"));
+
+    let untriggered = aura_with_data(serde_json::json!({
+        "id": "Aura", "width": 40, "triggers": { "1": { "untrigger": {} }, "disjunctive": "any" }
+    }));
+    assert_eq!(trivial_reason(&untriggered), Some("no triggers"));
+
+    let zero_size = aura_with_data(serde_json::json!({
+        "id": "Aura", "triggers": trigger, "width": 0, "height": 40
+    }));
+    assert_eq!(trivial_reason(&zero_size), Some("zero-size region"));
+
+    // Groups have no triggers of their own
+    let (_, mut group) = make_entry("Group", "group", true);
+    group.data = LuaValue::from_json(serde_json::json!({ "id": "Group" }));
+    assert_eq!(trivial_reason(&group), None);
+}
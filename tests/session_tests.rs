@@ -0,0 +1,53 @@
+//! Tests for the auto-saved working session.
+
+use std::path::PathBuf;
+
+use weakauras_mass_import::config;
+use weakauras_mass_import::session::{Session, SessionEntry};
+
+#[test]
+fn test_session_round_trip() {
+    let path = std::env::temp_dir()
+        .join("wa_session_tests")
+        .join("session.json");
+    let _ = std::fs::remove_file(&path);
+
+    let session = Session {
+        entries: vec![
+            SessionEntry {
+                import_string: "!WA:2!abc".to_string(),
+                selected: true,
+                source: Some(PathBuf::from("auras.txt")),
+            },
+            SessionEntry {
+                import_string: "!WA:2!def".to_string(),
+                selected: false,
+                source: None,
+            },
+        ],
+        staged: vec![SessionEntry {
+            import_string: "!WA:2!ghi".to_string(),
+            selected: true,
+            source: None,
+        }],
+    };
+    session.save_to(&path).unwrap();
+    assert_eq!(config::load_json_from::<Session>(&path).unwrap(), session);
+
+    // Staged auras alone are kept too
+    let staged_only = Session {
+        entries: Vec::new(),
+        ..session
+    };
+    assert!(!staged_only.is_empty());
+    staged_only.save_to(&path).unwrap();
+    assert_eq!(
+        config::load_json_from::<Session>(&path).unwrap(),
+        staged_only
+    );
+
+    // An empty session removes the file
+    Session::default().save_to(&path).unwrap();
+    assert!(!path.exists());
+    assert!(config::load_json_from::<Session>(&path).unwrap().is_empty());
+}
@@ -0,0 +1,62 @@
+//! Tests for the last-imported aura snapshot store.
+
+use weakauras_mass_import::config;
+use weakauras_mass_import::decoder::{LuaTable, LuaValue};
+use weakauras_mass_import::snapshots::SnapshotStore;
+
+fn aura_with_uid(uid: Option<&str>) -> LuaValue {
+    let mut triggers = LuaTable::new();
+    triggers.insert(
+        "disjunctive".to_string(),
+        LuaValue::String("all".to_string()),
+    );
+    let mut table = LuaTable::new();
+    table.insert("id".to_string(), LuaValue::String("Aura".to_string()));
+    table.insert("xOffset".to_string(), LuaValue::Number(-12.5));
+    table.insert(
+        "triggers".to_string(),
+        LuaValue::MixedTable {
+            array: vec![LuaValue::String("first".to_string())],
+            hash: triggers.into(),
+        },
+    );
+    if let Some(uid) = uid {
+        table.insert("uid".to_string(), LuaValue::String(uid.to_string()));
+    }
+    LuaValue::Table(table.into())
+}
+
+#[test]
+fn test_snapshots_record_and_get() {
+    let mut store = SnapshotStore::default();
+    let aura = aura_with_uid(Some("abc123"));
+
+    assert!(store.record(&aura));
+    assert!(!store.record(&aura_with_uid(None)));
+    assert_eq!(store.auras.len(), 1);
+
+    // Mixed tables survive the Lua round trip
+    assert_eq!(store.get("abc123"), Some(aura));
+    assert_eq!(store.get("missing"), None);
+}
+
+#[test]
+fn test_snapshots_round_trip() {
+    let path = std::env::temp_dir()
+        .join("wa_snapshots_tests")
+        .join("snapshots.json");
+    let _ = std::fs::remove_file(&path);
+
+    let mut store = SnapshotStore::default();
+    store.record(&aura_with_uid(Some("abc123")));
+    config::save_json_to(&path, &store).unwrap();
+
+    let loaded = config::load_json_from::<SnapshotStore>(&path).unwrap();
+    assert_eq!(loaded, store);
+
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(
+        config::load_json_from::<SnapshotStore>(&path).unwrap(),
+        SnapshotStore::default()
+    );
+}
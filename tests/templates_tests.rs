@@ -0,0 +1,191 @@
+//! Tests for generating auras from templates.
+
+use weakauras_mass_import::api::{parse_params, AuraTemplate, TemplateParams, TemplateStore};
+use weakauras_mass_import::config;
+use weakauras_mass_import::decoder::{LuaValue, WeakAuraDecoder};
+
+fn template(data: serde_json::Value, children: serde_json::Value) -> AuraTemplate {
+    let data = LuaValue::from_json(serde_json::json!({ "d": data, "c": children }));
+    AuraTemplate::new(WeakAuraDecoder::aura_from_data(data, false).unwrap().aura)
+}
+
+fn icon_template() -> AuraTemplate {
+    template(
+        serde_json::json!({
+            "id": "Buff: {{name}}",
+            "uid": "templateUid",
+            "regionType": "icon",
+            "desc": "Tracks {{name}} ({{spell}})",
+            "triggers": { "1": { "trigger": {
+                "type": "aura2",
+                "auranames": { "1": "{{spell}}" },
+                "spellName": "{{spell:number}}"
+            } } },
+            "color": "{{color:color}}"
+        }),
+        serde_json::json!([]),
+    )
+}
+
+fn row(pairs: &[(&str, &str)]) -> TemplateParams {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_template_placeholders() {
+    let names: Vec<String> = icon_template().placeholders().into_iter().collect();
+    assert_eq!(names, vec!["color", "name", "spell"]);
+}
+
+#[test]
+fn test_expand_fills_placeholders() {
+    let rows = vec![
+        row(&[
+            ("name", "Rejuvenation"),
+            ("spell", "774"),
+            ("color", "#ff0000"),
+        ]),
+        row(&[
+            ("name", "Lifebloom"),
+            ("spell", "33763"),
+            ("color", "#00ff0080"),
+        ]),
+    ];
+    let auras = icon_template().expand(&rows).unwrap();
+    assert_eq!(auras.len(), 2);
+
+    let first = &auras[0];
+    assert_eq!(first.id, "Buff: Rejuvenation");
+    assert_eq!(
+        first.data.get_path("desc").and_then(LuaValue::as_str),
+        Some("Tracks Rejuvenation (774)")
+    );
+    assert_eq!(
+        first
+            .data
+            .get_path("triggers.1.trigger.auranames.1")
+            .and_then(LuaValue::as_str),
+        Some("774")
+    );
+    assert_eq!(
+        first.data.get_path("triggers.1.trigger.spellName"),
+        Some(&LuaValue::Number(774.0))
+    );
+    assert_eq!(
+        first.data.get_path("color"),
+        Some(&LuaValue::Array(vec![
+            LuaValue::Number(1.0),
+            LuaValue::Number(0.0),
+            LuaValue::Number(0.0),
+            LuaValue::Number(1.0),
+        ]))
+    );
+    assert_eq!(
+        auras[1].data.get_path("color.4"),
+        Some(&LuaValue::Number(128.0 / 255.0))
+    );
+
+    // Fresh uids, different from the template's and from each other
+    let uids: Vec<_> = auras.iter().map(|a| a.uid.clone().unwrap()).collect();
+    assert_eq!(uids[0].len(), 11);
+    assert_ne!(uids[0], uids[1]);
+    assert!(!uids.contains(&"templateUid".to_string()));
+}
+
+#[test]
+fn test_expand_group_template() {
+    let group = template(
+        serde_json::json!({
+            "id": "{{class}} Cooldowns",
+            "regionType": "dynamicgroup",
+            "controlledChildren": ["{{class}} Icon"]
+        }),
+        serde_json::json!([
+            { "id": "{{class}} Icon", "parent": "{{class}} Cooldowns", "regionType": "icon" }
+        ]),
+    );
+    let auras = group
+        .expand(&[row(&[("class", "Druid")]), row(&[("class", "Monk")])])
+        .unwrap();
+    assert_eq!(auras[1].id, "Monk Cooldowns");
+    assert_eq!(auras[1].children, vec!["Monk Icon"]);
+    assert_eq!(
+        auras[1].child_data[0]
+            .get_path("parent")
+            .and_then(LuaValue::as_str),
+        Some("Monk Cooldowns")
+    );
+}
+
+#[test]
+fn test_expand_errors_name_the_row() {
+    let template = icon_template();
+    let missing = template
+        .expand(&[
+            row(&[("name", "A"), ("spell", "1"), ("color", "#000000")]),
+            row(&[("name", "B")]),
+        ])
+        .unwrap_err();
+    assert!(
+        missing.to_string().contains("row 2: no parameter"),
+        "{}",
+        missing
+    );
+
+    let not_number = template
+        .expand(&[row(&[("name", "A"), ("spell", "x"), ("color", "#000000")])])
+        .unwrap_err();
+    assert!(not_number.to_string().contains("is not a number"));
+
+    let same_id = template
+        .expand(&[
+            row(&[("name", "A"), ("spell", "1"), ("color", "#000000")]),
+            row(&[("name", "A"), ("spell", "2"), ("color", "#000000")]),
+        ])
+        .unwrap_err();
+    assert!(same_id.to_string().contains("row 2: the ID 'Buff: A'"));
+}
+
+#[test]
+fn test_parse_params_csv_and_json() {
+    let csv =
+        "name,spell,note\nRejuvenation,774,\"heals, over time\"\n\n\"Life \"\"bloom\"\"\",33763\n";
+    let rows = parse_params(csv).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["note"], "heals, over time");
+    assert_eq!(rows[1]["name"], "Life \"bloom\"");
+    assert_eq!(rows[1]["note"], "");
+
+    let json = r#"[{ "name": "Rejuvenation", "spell": 774, "glow": true, "skip": null }]"#;
+    let rows = parse_params(json).unwrap();
+    assert_eq!(rows[0]["spell"], "774");
+    assert_eq!(rows[0]["glow"], "true");
+    assert!(!rows[0].contains_key("skip"));
+
+    assert!(parse_params("name\n\"unterminated").is_err());
+    assert!(parse_params(r#"[{ "name": ["nested"] }]"#).is_err());
+}
+
+#[test]
+fn test_template_store_roundtrip() {
+    let path = std::env::temp_dir()
+        .join("wa_templates_tests")
+        .join("templates.json");
+    let _ = std::fs::remove_file(&path);
+
+    let mut store = TemplateStore::default();
+    assert!(!store.insert("Buff icons", icon_template().aura));
+    assert!(store.insert("Buff icons", icon_template().aura));
+    config::save_json_to(&path, &store).unwrap();
+
+    let mut loaded = config::load_json_from::<TemplateStore>(&path).unwrap();
+    let template = loaded.get("Buff icons").unwrap();
+    assert_eq!(template.placeholders().len(), 3);
+    assert!(loaded.remove("Buff icons"));
+    assert!(!loaded.remove("Buff icons"));
+
+    let _ = std::fs::remove_file(&path);
+}
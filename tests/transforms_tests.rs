@@ -0,0 +1,489 @@
+//! Tests for the transforms run on auras before import.
+
+mod common;
+
+use weakauras_mass_import::decoder::{LuaValue, WeakAura};
+use weakauras_mass_import::transforms::{
+    ExcludeRegionTypes, RenameIds, Reposition, Rescale, RestrictLoad, StripSounds, Transform,
+    TransformPipeline, TransformSpec, TransformStep,
+};
+
+use common::{s, table};
+
+fn get<'a>(value: &'a LuaValue, key: &str) -> Option<&'a LuaValue> {
+    value.as_table().and_then(|t| t.get(key))
+}
+
+/// Group "Raid" with a subgroup "Boss" holding the icon "Timer"
+fn make_group() -> WeakAura {
+    let sound_action = table(&[
+        ("do_sound", LuaValue::Bool(true)),
+        ("sound", s("Interface\\Sounds\\alert.ogg")),
+        ("do_message", LuaValue::Bool(true)),
+    ]);
+    let sound_condition = table(&[(
+        "changes",
+        LuaValue::Array(vec![
+            table(&[("property", s("sound")), ("value", table(&[]))]),
+            table(&[("property", s("alpha")), ("value", LuaValue::Number(0.5))]),
+        ]),
+    )]);
+    let timer = table(&[
+        ("id", s("Timer")),
+        ("parent", s("Boss")),
+        ("width", LuaValue::Number(64.0)),
+        ("xOffset", LuaValue::Number(-10.0)),
+        ("actions", table(&[("start", sound_action)])),
+        ("conditions", LuaValue::Array(vec![sound_condition])),
+        (
+            "subRegions",
+            LuaValue::Array(vec![table(&[("text_fontSize", LuaValue::Number(13.0))])]),
+        ),
+    ]);
+    let boss = table(&[
+        ("id", s("Boss")),
+        ("regionType", s("group")),
+        ("parent", s("Raid")),
+        ("controlledChildren", LuaValue::Array(vec![s("Timer")])),
+    ]);
+    WeakAura {
+        id: "Raid".to_string(),
+        uid: None,
+        region_type: Some("group".to_string()),
+        is_group: true,
+        children: vec!["Boss".to_string(), "Timer".to_string()],
+        data: table(&[
+            ("id", s("Raid")),
+            ("regionType", s("group")),
+            ("controlledChildren", LuaValue::Array(vec![s("Boss")])),
+        ]),
+        child_data: vec![boss, timer],
+        original_string: String::new(),
+        encoding_version: 2,
+    }
+}
+
+#[test]
+fn test_builtin_transforms_run_in_order() {
+    let steps = vec![
+        TransformStep {
+            spec: TransformSpec::StripSounds(StripSounds::default()),
+            enabled: true,
+        },
+        TransformStep {
+            spec: TransformSpec::Rescale(Rescale { factor: 0.5 }),
+            enabled: true,
+        },
+        TransformStep {
+            spec: TransformSpec::RenameIds(RenameIds {
+                template: "[Guild] {name}".to_string(),
+            }),
+            enabled: true,
+        },
+        // Disabled steps are skipped
+        TransformStep {
+            spec: TransformSpec::RenameIds(RenameIds {
+                template: "X".to_string(),
+            }),
+            enabled: false,
+        },
+    ];
+    let pipeline = TransformPipeline::from_steps(&steps);
+    assert_eq!(pipeline.names().len(), 3);
+
+    let mut auras = vec![make_group()];
+    pipeline.apply(&mut auras);
+    let aura = &auras[0];
+
+    // IDs are prefixed and the hierarchy follows
+    assert_eq!(aura.id, "[Guild] Raid");
+    assert_eq!(aura.children, vec!["[Guild] Boss", "[Guild] Timer"]);
+    assert!(matches!(get(&aura.data, "id"), Some(LuaValue::String(id)) if id == "[Guild] Raid"));
+    assert!(matches!(
+        get(&aura.data, "controlledChildren").and_then(LuaValue::as_array).map(|a| &a[..]),
+        Some([LuaValue::String(id)]) if id == "[Guild] Boss"
+    ));
+    let boss = &aura.child_data[0];
+    assert!(matches!(get(boss, "parent"), Some(LuaValue::String(p)) if p == "[Guild] Raid"));
+    assert!(matches!(
+        get(boss, "controlledChildren").and_then(LuaValue::as_array).map(|a| &a[..]),
+        Some([LuaValue::String(id)]) if id == "[Guild] Timer"
+    ));
+    let timer = &aura.child_data[1];
+    assert!(matches!(get(timer, "parent"), Some(LuaValue::String(p)) if p == "[Guild] Boss"));
+
+    // Sizes, offsets and font sizes are scaled
+    assert!(matches!(get(timer, "width"), Some(LuaValue::Number(n)) if *n == 32.0));
+    assert!(matches!(get(timer, "xOffset"), Some(LuaValue::Number(n)) if *n == -5.0));
+    let sub_region = &get(timer, "subRegions")
+        .and_then(LuaValue::as_array)
+        .unwrap()[0];
+    assert!(matches!(get(sub_region, "text_fontSize"), Some(LuaValue::Number(n)) if *n == 7.0));
+
+    // Sounds are gone, other actions and condition changes are kept
+    let start = get(timer, "actions").and_then(|a| get(a, "start")).unwrap();
+    assert!(get(start, "sound").is_none());
+    assert!(matches!(
+        get(start, "do_sound"),
+        Some(LuaValue::Bool(false))
+    ));
+    assert!(matches!(
+        get(start, "do_message"),
+        Some(LuaValue::Bool(true))
+    ));
+    let condition = &get(timer, "conditions")
+        .and_then(LuaValue::as_array)
+        .unwrap()[0];
+    let changes = get(condition, "changes")
+        .and_then(LuaValue::as_array)
+        .unwrap();
+    assert_eq!(changes.len(), 1);
+    assert!(matches!(get(&changes[0], "property"), Some(LuaValue::String(p)) if p == "alpha"));
+
+    // Renaming twice keeps a single prefix
+    pipeline.apply(&mut auras);
+    assert_eq!(auras[0].id, "[Guild] Raid");
+}
+
+#[test]
+fn test_transform_steps_round_trip_through_json() {
+    let steps = TransformStep::defaults();
+    assert_eq!(steps.len(), TransformSpec::library().len());
+    assert!(steps.iter().all(|s| !s.enabled));
+
+    let json = serde_json::to_string(&steps).unwrap();
+    let parsed: Vec<TransformStep> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, steps);
+}
+
+/// Aura announcing with a sound, a raid warning, text-to-speech and custom code
+fn make_noisy_aura() -> WeakAura {
+    let actions = table(&[
+        (
+            "start",
+            table(&[
+                ("do_sound", LuaValue::Bool(true)),
+                ("sound", s("alert.ogg")),
+                ("do_message", LuaValue::Bool(true)),
+                ("message_type", s("RAID_WARNING")),
+                ("do_custom", LuaValue::Bool(true)),
+                ("custom", s("SendChatMessage('pull', 'SAY')")),
+            ]),
+        ),
+        (
+            "finish",
+            table(&[
+                ("do_message", LuaValue::Bool(true)),
+                ("message_type", s("TTS")),
+            ]),
+        ),
+    ]);
+    let changes = LuaValue::Array(vec![
+        table(&[
+            ("property", s("sound")),
+            ("value", table(&[("sound", s("alert.ogg"))])),
+        ]),
+        table(&[
+            ("property", s("chat")),
+            ("value", table(&[("message_type", s("TTS"))])),
+        ]),
+        table(&[
+            ("property", s("chat")),
+            ("value", table(&[("message_type", s("PRINT"))])),
+        ]),
+    ]);
+    WeakAura {
+        id: "Pull".to_string(),
+        uid: None,
+        region_type: Some("icon".to_string()),
+        is_group: false,
+        children: Vec::new(),
+        data: table(&[
+            ("id", s("Pull")),
+            ("actions", actions),
+            (
+                "conditions",
+                LuaValue::Array(vec![table(&[("changes", changes)])]),
+            ),
+        ]),
+        child_data: Vec::new(),
+        original_string: String::new(),
+        encoding_version: 2,
+    }
+}
+
+#[test]
+fn test_strip_sounds_options() {
+    let action = |aura: &WeakAura, phase: &str, key: &str| {
+        get(&aura.data, "actions")
+            .and_then(|a| get(a, phase))
+            .and_then(|a| get(a, key))
+            .cloned()
+    };
+    let change_count = |aura: &WeakAura| {
+        let condition = &get(&aura.data, "conditions")
+            .and_then(LuaValue::as_array)
+            .unwrap()[0];
+        get(condition, "changes")
+            .and_then(LuaValue::as_array)
+            .unwrap()
+            .len()
+    };
+
+    // Everything off: chat, TTS and custom announcements are silenced
+    let mut aura = make_noisy_aura();
+    StripSounds::default().apply(&mut aura);
+    assert!(matches!(
+        action(&aura, "start", "do_sound"),
+        Some(LuaValue::Bool(false))
+    ));
+    assert!(matches!(
+        action(&aura, "start", "do_message"),
+        Some(LuaValue::Bool(false))
+    ));
+    assert!(matches!(
+        action(&aura, "start", "do_custom"),
+        Some(LuaValue::Bool(false))
+    ));
+    assert!(matches!(
+        action(&aura, "finish", "do_message"),
+        Some(LuaValue::Bool(false))
+    ));
+    // Only the chat frame print survives
+    assert_eq!(change_count(&aura), 1);
+
+    // Keep sounds on another channel, strip only TTS
+    let mut aura = make_noisy_aura();
+    let strip = StripSounds {
+        sounds: false,
+        sound_channel: Some("SFX".to_string()),
+        chat: false,
+        tts: true,
+    };
+    assert_eq!(strip.name(), "Strip TTS (sounds on SFX)");
+    strip.apply(&mut aura);
+    assert!(
+        matches!(action(&aura, "start", "sound_channel"), Some(LuaValue::String(c)) if c == "SFX")
+    );
+    assert!(matches!(
+        action(&aura, "start", "do_message"),
+        Some(LuaValue::Bool(true))
+    ));
+    assert!(matches!(
+        action(&aura, "start", "do_custom"),
+        Some(LuaValue::Bool(true))
+    ));
+    assert!(matches!(
+        action(&aura, "finish", "do_message"),
+        Some(LuaValue::Bool(false))
+    ));
+    assert_eq!(change_count(&aura), 2);
+}
+
+#[test]
+fn test_strip_sounds_step_without_options_uses_defaults() {
+    let step: TransformStep =
+        serde_json::from_str(r#"{"type": "strip_sounds", "enabled": true}"#).unwrap();
+    assert_eq!(
+        step.spec,
+        TransformSpec::StripSounds(StripSounds::default())
+    );
+}
+
+#[test]
+fn test_reposition_moves_only_the_imported_aura() {
+    let mut aura = make_group();
+    let reposition = Reposition {
+        x_offset: 20.0,
+        y_offset: -15.5,
+        anchor_frame: Some("ElvUF_Player".to_string()),
+    };
+    reposition.apply(&mut aura);
+
+    assert!(matches!(get(&aura.data, "xOffset"), Some(LuaValue::Number(n)) if *n == 20.0));
+    assert!(matches!(get(&aura.data, "yOffset"), Some(LuaValue::Number(n)) if *n == -15.5));
+    assert!(
+        matches!(get(&aura.data, "anchorFrameType"), Some(LuaValue::String(t)) if t == "SELECTFRAME")
+    );
+    assert!(
+        matches!(get(&aura.data, "anchorFrameFrame"), Some(LuaValue::String(f)) if f == "ElvUF_Player")
+    );
+    // Children are positioned relative to the group
+    let timer = &aura.child_data[1];
+    assert!(matches!(get(timer, "xOffset"), Some(LuaValue::Number(n)) if *n == -10.0));
+    assert!(get(timer, "anchorFrameFrame").is_none());
+
+    // A zero offset leaves the position alone
+    let mut aura = make_group();
+    Reposition::default().apply(&mut aura);
+    assert!(get(&aura.data, "xOffset").is_none());
+}
+
+#[test]
+fn test_rename_template() {
+    let rename = RenameIds {
+        template: "{name} [M+]".to_string(),
+    };
+    assert_eq!(rename.rename("Timer"), "Timer [M+]");
+    assert_eq!(rename.rename("Timer [M+]"), "Timer [M+]");
+
+    // Parent references follow the renamed IDs
+    let mut aura = make_group();
+    rename.apply(&mut aura);
+    assert_eq!(aura.id, "Raid [M+]");
+    let timer = &aura.child_data[1];
+    assert!(matches!(get(timer, "id"), Some(LuaValue::String(id)) if id == "Timer [M+]"));
+    assert!(matches!(get(timer, "parent"), Some(LuaValue::String(p)) if p == "Boss [M+]"));
+
+    // Without a placeholder the template is a prefix, as in older configs
+    let step: TransformStep =
+        serde_json::from_str(r#"{"type": "prefix_ids", "prefix": "[Guild] "}"#).unwrap();
+    let TransformSpec::RenameIds(prefix) = step.spec else {
+        panic!("expected a rename step");
+    };
+    assert_eq!(prefix.rename("Timer"), "[Guild] Timer");
+    assert_eq!(
+        RenameIds {
+            template: String::new()
+        }
+        .rename("Timer"),
+        "Timer"
+    );
+}
+
+#[test]
+fn test_rename_follows_anchors_to_siblings() {
+    let mut aura = make_group();
+    aura.children.push("Bar".to_string());
+    aura.child_data.push(table(&[
+        ("id", s("Bar")),
+        ("parent", s("Boss")),
+        ("anchorFrameType", s("SELECTFRAME")),
+        ("anchorFrameFrame", s("WeakAuras:Timer")),
+    ]));
+    // Anchors outside the pack are left alone
+    if let Some(timer) = aura.child_data[1].as_table_mut() {
+        timer.insert("anchorFrameFrame".to_string(), s("WeakAuras:Other"));
+    }
+
+    RenameIds {
+        template: "[Guild] {name}".to_string(),
+    }
+    .apply(&mut aura);
+
+    let bar = &aura.child_data[2];
+    assert!(matches!(get(bar, "id"), Some(LuaValue::String(id)) if id == "[Guild] Bar"));
+    assert!(matches!(
+        get(bar, "anchorFrameFrame"),
+        Some(LuaValue::String(frame)) if frame == "WeakAuras:[Guild] Timer"
+    ));
+    assert!(matches!(
+        get(&aura.child_data[1], "anchorFrameFrame"),
+        Some(LuaValue::String(frame)) if frame == "WeakAuras:Other"
+    ));
+}
+
+#[test]
+fn test_restrict_load_to_class_and_spec() {
+    let mut aura = make_group();
+    // The pack loaded for another spec before
+    if let Some(timer) = aura.child_data[1].as_table_mut() {
+        timer.insert(
+            "load".to_string(),
+            table(&[
+                ("use_class_and_spec", LuaValue::Bool(false)),
+                ("use_spec", LuaValue::Bool(true)),
+                ("use_combat", LuaValue::Bool(true)),
+            ]),
+        );
+    }
+    let restrict = RestrictLoad {
+        class: Some("WARRIOR".to_string()),
+        spec: Some(71),
+    };
+    assert_eq!(restrict.name(), "Load only as Arms Warrior");
+    restrict.apply(&mut aura);
+
+    let load = get(&aura.child_data[1], "load").unwrap();
+    assert!(matches!(get(load, "use_class"), Some(LuaValue::Bool(true))));
+    let class = get(load, "class").unwrap();
+    assert!(matches!(get(class, "single"), Some(LuaValue::String(c)) if c == "WARRIOR"));
+    assert!(matches!(
+        get(load, "use_class_and_spec"),
+        Some(LuaValue::Bool(true))
+    ));
+    let spec = get(load, "class_and_spec").unwrap();
+    assert!(matches!(get(spec, "single"), Some(LuaValue::Number(n)) if *n == 71.0));
+    assert!(get(load, "use_spec").is_none());
+    // Other load options are kept
+    assert!(matches!(
+        get(load, "use_combat"),
+        Some(LuaValue::Bool(true))
+    ));
+    // Groups load with their children
+    assert!(get(&aura.data, "load").is_none());
+    assert!(get(&aura.child_data[0], "load").is_none());
+
+    // A spec of another class is ignored
+    let mut aura = make_group();
+    RestrictLoad {
+        class: Some("MAGE".to_string()),
+        spec: Some(71),
+    }
+    .apply(&mut aura);
+    let load = get(&aura.child_data[1], "load").unwrap();
+    assert!(get(load, "use_class_and_spec").is_none());
+}
+
+#[test]
+fn test_exclude_region_types_fixes_hierarchy() {
+    let exclude = |types: &[&str]| TransformStep {
+        spec: TransformSpec::ExcludeRegionTypes(ExcludeRegionTypes {
+            region_types: types.iter().map(|t| t.to_string()).collect(),
+        }),
+        enabled: true,
+    };
+
+    // Excluding the subgroup drops its children and the reference to it
+    let mut group = make_group();
+    if let Some(timer) = group.child_data[1].as_table_mut() {
+        timer.insert("regionType".to_string(), s("icon"));
+    }
+    let mut standalone = make_group();
+    standalone.id = "Model".to_string();
+    standalone.is_group = false;
+    standalone.children.clear();
+    standalone.child_data.clear();
+    standalone.data = table(&[("id", s("Model")), ("regionType", s("model"))]);
+
+    let mut auras = vec![group.clone(), standalone];
+    let skipped = TransformPipeline::from_steps(&[exclude(&["model"])]).apply(&mut auras);
+    // The top-level model aura is not imported
+    assert_eq!(skipped, 1);
+    assert_eq!(auras.len(), 1);
+    assert_eq!(auras[0].child_data.len(), 2);
+
+    let mut auras = vec![group.clone()];
+    if let Some(boss) = auras[0].child_data[0].as_table_mut() {
+        boss.insert("regionType".to_string(), s("dynamicgroup"));
+    }
+    TransformPipeline::from_steps(&[exclude(&["dynamicgroup"])]).apply(&mut auras);
+    let aura = &auras[0];
+    assert!(aura.child_data.is_empty());
+    assert!(aura.children.is_empty());
+    assert!(matches!(
+        get(&aura.data, "controlledChildren").and_then(LuaValue::as_array),
+        Some(children) if children.is_empty()
+    ));
+
+    // Excluding a leaf keeps its group
+    let mut auras = vec![group];
+    TransformPipeline::from_steps(&[exclude(&["icon"])]).apply(&mut auras);
+    let aura = &auras[0];
+    assert_eq!(aura.children, vec!["Boss"]);
+    let boss = &aura.child_data[0];
+    assert!(matches!(
+        get(boss, "controlledChildren").and_then(LuaValue::as_array),
+        Some(children) if children.is_empty()
+    ));
+}
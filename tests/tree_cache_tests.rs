@@ -0,0 +1,96 @@
+//! Tests for the parsed aura tree cache.
+
+use std::time::{Duration, SystemTime};
+
+use weakauras_mass_import::error::WeakAuraError;
+use weakauras_mass_import::saved_variables::{
+    format_age, format_duration, format_size, SavedVariablesManager,
+};
+use weakauras_mass_import::tree_cache::{AuraTreeCache, ParsedTree};
+
+fn write_displays(path: &std::path::Path, ids: &[&str]) {
+    let entries: String = ids
+        .iter()
+        .map(|id| format!("\t\t[\"{0}\"] = {{[\"id\"] = \"{0}\"}},\n", id))
+        .collect();
+    std::fs::write(
+        path,
+        format!(
+            "WeakAurasSaved = {{\n\t[\"displays\"] = {{\n{}\t}},\n}}\n",
+            entries
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_tree_cache_invalidated_when_file_changes() {
+    let dir = std::env::temp_dir().join("wa_tree_cache_tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("WeakAuras.lua");
+    write_displays(&path, &["Alpha", "Beta"]);
+
+    let mut cache = AuraTreeCache::default();
+    assert!(cache.get(&path).is_none());
+
+    let parsed = ParsedTree::parse(&path).unwrap();
+    assert_eq!(parsed.count, 2);
+    cache.insert(parsed);
+    assert_eq!(cache.get(&path).unwrap().count, 2);
+
+    std::thread::sleep(Duration::from_millis(20));
+    write_displays(&path, &["Alpha"]);
+    assert!(cache.get(&path).is_none());
+
+    cache.insert(ParsedTree::parse(&path).unwrap());
+    assert_eq!(cache.get(&path).unwrap().count, 1);
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(cache.get(&path).is_none());
+    assert!(matches!(
+        ParsedTree::parse(&path),
+        Err(WeakAuraError::FileNotFound(_))
+    ));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_discovered_file_details() {
+    let root = std::env::temp_dir().join("wa_tree_cache_details");
+    let sv_dir = root.join("_retail_/WTF/Account/ACCOUNT1/SavedVariables");
+    std::fs::create_dir_all(&sv_dir).unwrap();
+    std::fs::create_dir_all(root.join("_retail_/Interface/AddOns/WeakAuras")).unwrap();
+    let path = sv_dir.join("WeakAuras.lua");
+    std::fs::write(
+        &path,
+        "WeakAurasSaved = {\n\t[\"dbVersion\"] = 76,\n\t[\"displays\"] = {\n\t\t[\"Alpha\"] = {[\"id\"] = \"Alpha\"},\n\t},\n}\n",
+    )
+    .unwrap();
+
+    let mut found = SavedVariablesManager::find_saved_variables(&root);
+    assert_eq!(found.len(), 1);
+    let info = &mut found[0];
+    assert_eq!(info.size, std::fs::metadata(&path).unwrap().len());
+    assert!(info.modified.is_some());
+    assert_eq!(info.aura_count, None);
+
+    ParsedTree::parse(&path).unwrap().describe(info);
+    assert_eq!(info.aura_count, Some(1));
+    assert_eq!(info.db_version, Some(76));
+    assert!(info.details().starts_with("1 aura · "));
+    assert!(info.details().ends_with("dbVersion 76"));
+
+    assert_eq!(format_size(512), "512 B");
+    assert_eq!(format_size(1536), "1.5 KB");
+    assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    let now = SystemTime::now();
+    assert_eq!(format_age(now), "just now");
+    assert_eq!(format_age(now - Duration::from_secs(120)), "2 minutes ago");
+    assert_eq!(format_age(now - Duration::from_secs(86_400)), "1 day ago");
+    assert_eq!(format_duration(Duration::from_millis(45_900)), "45s");
+    assert_eq!(format_duration(Duration::from_secs(185)), "3m 05s");
+    assert_eq!(format_duration(Duration::from_secs(3_720)), "1h 02m");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,79 @@
+//! Tests for `weakaura://` deep links and forwarding them to a running instance.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use weakauras_mass_import::uri::{parse_import_uri, send_link, InstanceListener};
+
+#[test]
+fn test_parse_import_uri_forms() {
+    assert_eq!(
+        parse_import_uri("weakaura://import?string=!WA:2!abc").as_deref(),
+        Some("!WA:2!abc")
+    );
+    assert_eq!(
+        parse_import_uri("weakaura://import?source=wago&string=!WA%3A2!a%2Fb").as_deref(),
+        Some("!WA:2!a/b")
+    );
+    assert_eq!(
+        parse_import_uri("weakaura://import/!WA:2!abc").as_deref(),
+        Some("!WA:2!abc")
+    );
+    // Legacy strings use '+', which must survive untouched
+    assert_eq!(
+        parse_import_uri(" weakaura://import/d0+Tx ").as_deref(),
+        Some("d0+Tx")
+    );
+}
+
+#[test]
+fn test_parse_import_uri_rejects_invalid_links() {
+    assert_eq!(parse_import_uri("https://wago.io/abc"), None);
+    assert_eq!(parse_import_uri("weakaura://export?string=abc"), None);
+    assert_eq!(parse_import_uri("weakaura://import?other=abc"), None);
+    assert_eq!(parse_import_uri("weakaura://import?string="), None);
+    assert_eq!(parse_import_uri("weakaura://import/%2"), None);
+    assert_eq!(parse_import_uri("weakaura://import/%zz"), None);
+}
+
+fn start_listener(token: &str) -> (SocketAddr, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let instance = InstanceListener::new(listener, token.to_string());
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        instance.run(|text| {
+            let _ = tx.send(text);
+        })
+    });
+    (addr, rx)
+}
+
+#[test]
+fn test_instance_accepts_links_with_its_token() {
+    let (addr, rx) = start_listener("secret");
+
+    assert!(send_link(addr, "secret", "weakaura://import/!WA:2!abc"));
+    assert_eq!(rx.recv().unwrap(), "!WA:2!abc");
+}
+
+#[test]
+fn test_instance_refuses_wrong_tokens_and_other_payloads() {
+    let (addr, rx) = start_listener("secret");
+
+    assert!(!send_link(addr, "guess", "weakaura://import/!WA:2!abc"));
+    assert!(!send_link(addr, "secret", "!WA:2!abc"));
+
+    // What the companion endpoint used to accept: an HTTP POST
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .write_all(b"POST /import HTTP/1.1\r\nContent-Length: 9\r\n\r\n!WA:2!abc")
+        .unwrap();
+    let mut reply = String::new();
+    let _ = stream.read_to_string(&mut reply);
+    assert_eq!(reply, "");
+
+    assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+}
@@ -0,0 +1,19 @@
+//! Tests for the browser bindings (run with `cargo test --features wasm`).
+#![cfg(feature = "wasm")]
+
+use weakauras_mass_import::wasm::validate;
+
+#[test]
+fn test_validate_reports_each_string() {
+    let long_garbage = format!("!WA:2!{}", "x".repeat(60));
+    let json = validate(&format!("{}\n\n!not-valid\n", long_garbage));
+    let entries: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let entries = entries.as_array().unwrap();
+
+    assert_eq!(entries.len(), 2);
+    for entry in entries {
+        assert_eq!(entry["is_valid"], false);
+        assert!(entry["error"].is_string());
+    }
+    assert_eq!(validate(""), "[]");
+}